@@ -11,7 +11,7 @@ use scsir::{
 
 fn main() -> scsir::Result<()> {
     let mut scsi = scsir::Scsi::new("/dev/sdX")?;
-    scsi.set_timeout(Duration::from_secs(3));
+    scsi.set_default_timeout(Duration::from_secs(3));
 
     println!("Single Page:");
     print_power_condition(&scsi)?;
@@ -4,7 +4,7 @@ use scsir::{self, Scsi};
 
 fn main() -> scsir::Result<()> {
     let mut scsi = scsir::Scsi::new("/dev/sdX")?;
-    scsi.set_timeout(Duration::from_secs(3));
+    scsi.set_default_timeout(Duration::from_secs(3));
 
     println!("{:x?}", read_bytes(&scsi, 0, 512)?);
 
@@ -53,7 +53,8 @@ fn read_bytes(interface: &Scsi, byte_offset: u64, byte_count: u64) -> scsir::Res
             .logical_block_address(lba_offset)
             .logical_block_size(cap.logical_block_length_in_bytes)
             .transfer_length(lba_count as u32)
-            .issue_16()?;
+            .issue_16()?
+            .data;
 
         let mut data = &data[..];
 
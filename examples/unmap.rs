@@ -4,14 +4,14 @@ use scsir::{self, Scsi};
 
 fn main() -> scsir::Result<()> {
     let mut scsi = scsir::Scsi::new("/dev/sdX")?;
-    scsi.set_timeout(Duration::from_secs(3));
+    scsi.set_default_timeout(Duration::from_secs(3));
 
     #[allow(unused_variables)]
     let cap = scsi.read_capacity().issue_16()?;
 
     let block_limit = scsir::shortcut::inquiry::block_limits(&mut scsi.inquiry())?;
 
-    if block_limit.maximum_unmap_lba_count == 0 {
+    if block_limit.maximum_unmap_lba_count.unwrap_or(0) == 0 {
         return Err(scsir::Error::Other("Unmap Unsupported.".to_owned()));
     }
 
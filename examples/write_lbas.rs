@@ -4,7 +4,7 @@ use scsir::{self, Scsi};
 
 fn main() -> scsir::Result<()> {
     let mut scsi = scsir::Scsi::new("/dev/sdX")?;
-    scsi.set_timeout(Duration::from_secs(3));
+    scsi.set_default_timeout(Duration::from_secs(3));
 
     // dangerous!!!
     // write_lbas(&scsi, 0, &[0x0F; 512])?;
@@ -0,0 +1,353 @@
+use std::{
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{mpsc, Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread::{self, JoinHandle},
+};
+
+use crate::{Command, Scsi};
+
+/// A [`Scsi`] handle owned by a dedicated worker thread.
+///
+/// Commands are queued to the worker and issued one at a time, so the device handle never
+/// moves between threads once the wrapper is created. The returned [`Task`] is a plain
+/// [`Future`] and works with any executor, the `async` feature doesn't pull in tokio.
+///
+/// Every builder of [`Scsi`] has a method of the same name here that starts the builder on
+/// the worker and issues it with a closure:
+///
+/// ```no_run
+/// # async fn capacity(scsi: &scsir::AsyncScsi) -> scsir::Result<()> {
+/// let capacity = scsi.read_capacity(|command| command.issue_16()).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Dropping a [`Task`] does not cancel the command. An ioctl can't be interrupted halfway,
+/// so the worker always runs the command to completion and discards the result.
+#[derive(Debug)]
+pub struct AsyncScsi {
+    worker: Worker<Scsi>,
+}
+
+/// The pending result of a command queued on an [`AsyncScsi`].
+///
+/// A command that panics on the worker thread completes with [`crate::Error::Other`], the
+/// worker goes on with the next one.
+#[derive(Debug)]
+pub struct Task<T> {
+    state: Arc<Mutex<TaskState<T>>>,
+}
+
+impl AsyncScsi {
+    pub fn new(scsi: Scsi) -> Self {
+        Self {
+            worker: Worker::new(scsi),
+        }
+    }
+
+    /// Issues `command` on the worker thread, like [`Scsi::issue`].
+    ///
+    /// The command builders borrow the handle, issue them with the method of the same name on
+    /// [`AsyncScsi`] instead.
+    pub fn issue_async<C, T>(&self, command: C) -> Task<T>
+    where
+        C: Command<ReturnType = crate::Result<T>> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.worker.run(move |scsi| scsi.issue(&command))
+    }
+
+    /// Runs `f` with the wrapped [`Scsi`] on the worker thread.
+    ///
+    /// For the shortcuts or several commands in a row, e.g.
+    /// `async_scsi.run(|scsi| scsi.device_info())`.
+    pub fn run<F, T>(&self, f: F) -> Task<T>
+    where
+        F: FnOnce(&Scsi) -> crate::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.worker.run(f)
+    }
+}
+
+impl<T> Future for Task<T> {
+    type Output = crate::Result<T>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(result) = state.result.take() {
+            return Poll::Ready(result);
+        }
+
+        if state.abandoned {
+            return Poll::Ready(Err(crate::Error::Other(
+                "the SCSI worker panicked before the command completed".to_owned(),
+            )));
+        }
+
+        state.waker = Some(context.waker().clone());
+        Poll::Pending
+    }
+}
+
+type Job<T> = Box<dyn FnOnce(&T) + Send>;
+
+#[derive(Debug)]
+struct Worker<T> {
+    sender: Option<mpsc::Sender<Job<T>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Debug)]
+struct TaskState<T> {
+    result: Option<crate::Result<T>>,
+    abandoned: bool,
+    waker: Option<Waker>,
+}
+
+// wakes the task with `abandoned` set if the job is dropped without a result
+struct Completer<T> {
+    state: Arc<Mutex<TaskState<T>>>,
+}
+
+impl<T: Send + 'static> Worker<T> {
+    fn new(value: T) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job<T>>();
+
+        let thread = thread::spawn(move || {
+            for job in receiver {
+                // a panicking job only abandons its own task
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| job(&value)));
+            }
+        });
+
+        Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        }
+    }
+
+    fn run<F, R>(&self, f: F) -> Task<R>
+    where
+        F: FnOnce(&T) -> crate::Result<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let state = Arc::new(Mutex::new(TaskState {
+            result: None,
+            abandoned: false,
+            waker: None,
+        }));
+
+        let completer = Completer {
+            state: state.clone(),
+        };
+
+        let job: Job<T> = Box::new(move |value| completer.complete(f(value)));
+
+        // the completer inside a rejected job is dropped here, which abandons the task
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(job);
+        }
+
+        Task { state }
+    }
+}
+
+impl<T> Drop for Worker<T> {
+    fn drop(&mut self) {
+        // closing the channel lets the worker finish the queued jobs and exit
+        drop(self.sender.take());
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl<T> Completer<T> {
+    fn complete(self, result: crate::Result<T>) {
+        let mut state = self.state.lock().unwrap();
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if state.result.is_none() {
+            state.abandoned = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            mpsc::Receiver,
+        },
+        task::Wake,
+        time::Duration,
+    };
+
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+
+        loop {
+            match future.as_mut().poll(&mut context) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn send_sync_test() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<AsyncScsi>();
+        assert_send_sync::<Task<crate::Result<()>>>();
+    }
+
+    #[test]
+    fn worker_test() {
+        let worker = Worker::new(Arc::new(AtomicUsize::new(0)));
+
+        let first = worker.run(|counter| Ok(counter.fetch_add(1, Ordering::SeqCst)));
+        let second = worker.run(|counter| Ok(counter.fetch_add(1, Ordering::SeqCst)));
+
+        // jobs run in the order they were queued
+        assert_eq!(block_on(second).unwrap(), 1, "second job");
+        assert_eq!(block_on(first).unwrap(), 0, "first job");
+    }
+
+    #[test]
+    fn dropped_task_test() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver): (_, Receiver<()>) = mpsc::channel();
+        let worker = Worker::new(counter.clone());
+
+        let blocked = worker.run(move |_| Ok(receiver.recv_timeout(Duration::from_secs(10))));
+        let dropped = worker.run(|counter| Ok(counter.fetch_add(1, Ordering::SeqCst)));
+        drop(dropped);
+
+        sender.send(()).unwrap();
+        assert!(block_on(blocked).unwrap().is_ok(), "blocked job");
+
+        // dropping the worker waits for the queued jobs
+        drop(worker);
+        assert_eq!(counter.load(Ordering::SeqCst), 1, "dropped job still ran");
+    }
+
+    #[test]
+    fn panicked_job_test() {
+        let worker = Worker::new(());
+        let task = worker.run(|_| -> crate::Result<u8> { panic!("command failed") });
+        let next = worker.run(|_| Ok(1));
+
+        assert!(
+            matches!(block_on(task), Err(crate::Error::Other(_))),
+            "panicked job"
+        );
+        assert_eq!(block_on(next).unwrap(), 1, "the worker survived");
+    }
+
+    #[test]
+    fn mock_test() {
+        // TEST UNIT READY, written against the Command trait so it can move to the worker
+        struct TestUnitReady;
+
+        impl Command for TestUnitReady {
+            type CommandBuffer = [u8; 6];
+
+            type DataBuffer = ();
+
+            type DataBufferWrapper = ();
+
+            type ReturnType = crate::Result<()>;
+
+            fn direction(&self) -> crate::DataDirection {
+                crate::DataDirection::None
+            }
+
+            fn command(&self) -> Self::CommandBuffer {
+                [0; 6]
+            }
+
+            fn data(&self) -> Self::DataBufferWrapper {}
+
+            fn data_size(&self) -> u32 {
+                0
+            }
+
+            fn process_result(
+                &self,
+                result: crate::ResultData<Self::DataBufferWrapper>,
+            ) -> Self::ReturnType {
+                result.check_ioctl_error()?;
+                result.check_common_error()
+            }
+        }
+
+        let mock = crate::MockScsi::new();
+        mock.expect(&[0x00, 0, 0, 0, 0, 0], crate::MockResponse::good())
+            // NOT READY, MEDIUM NOT PRESENT
+            .expect(
+                &[0x00, 0, 0, 0, 0, 0],
+                crate::MockResponse::check_condition(&[
+                    0x70, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x3A,
+                    0x00, 0x00, 0x00, 0x00, 0x00,
+                ]),
+            )
+            // INQUIRY
+            .expect(
+                &[0x12, 0x00, 0x00, 0x00, 0x04, 0x00],
+                crate::MockResponse::good().data(&[0x00, 0x00, 0x06, 0x02]),
+            );
+
+        let scsi = AsyncScsi::new(Scsi::mock(mock.clone()));
+        let ready = scsi.issue_async(TestUnitReady);
+        let not_ready = scsi.run(|scsi| scsi.test_unit_ready().issue());
+        let inquiry = scsi.inquiry(|command| command.allocation_length(4).issue());
+
+        assert!(block_on(ready).is_ok(), "ready");
+        assert!(
+            matches!(block_on(not_ready), Err(crate::Error::NotReady(_))),
+            "not ready"
+        );
+        assert_eq!(
+            block_on(inquiry).unwrap(),
+            [0x00, 0x00, 0x06, 0x02],
+            "inquiry data"
+        );
+
+        // the worker has issued everything once the handle is dropped
+        drop(scsi);
+        mock.verify();
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    alloc::{self, Layout},
+    borrow::{Borrow, BorrowMut},
+    fmt::{self, Debug, Formatter},
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+    slice,
+    sync::Mutex,
+};
+
+use crate::data_wrapper::AnyType;
+
+/// Reusable data buffers for commands issued in a loop, see [`crate::Scsi::set_buffer_pool`].
+///
+/// A buffer goes back to the pool when its [`PooledBuffer`] is dropped. Buffers only grow, so
+/// after the first few commands the pool holds buffers of the largest transfer seen and
+/// stops allocating. Every buffer is aligned to [`BufferPool::alignment`], which direct I/O
+/// needs from user buffers.
+pub struct BufferPool {
+    alignment: usize,
+    free: Mutex<Vec<AlignedBuffer>>,
+}
+
+/// A buffer borrowed from a [`BufferPool`], dereferences to the transferred bytes.
+pub struct PooledBuffer<'a> {
+    pool: &'a BufferPool,
+    buffer: AlignedBuffer,
+    length: usize,
+}
+
+struct AlignedBuffer {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+// the buffer is owned memory, like a Vec<u8>
+unsafe impl Send for AlignedBuffer {}
+unsafe impl Sync for AlignedBuffer {}
+
+impl BufferPool {
+    /// the page size of most platforms, which satisfies the DMA alignment of common HBAs
+    pub const DEFAULT_ALIGNMENT: usize = 4096;
+
+    pub fn new() -> Self {
+        Self::with_alignment(Self::DEFAULT_ALIGNMENT)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `alignment` is not a power of two.
+    pub fn with_alignment(alignment: usize) -> Self {
+        assert!(
+            alignment.is_power_of_two(),
+            "alignment must be a power of two, but {} was provided",
+            alignment
+        );
+
+        Self {
+            alignment,
+            free: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn alignment(&self) -> usize {
+        self.alignment
+    }
+
+    /// A zeroed buffer of `length` bytes, reusing a free buffer if there is one.
+    pub fn get(&self, length: usize) -> PooledBuffer<'_> {
+        let buffer = self.free.lock().unwrap().pop();
+
+        let mut buffer = match buffer {
+            Some(buffer) if buffer.capacity() >= length => buffer,
+            // grown to the new high-water mark, the old buffer is freed
+            _ => AlignedBuffer::new(length, self.alignment),
+        };
+        buffer.as_mut_slice()[..length].fill(0);
+
+        PooledBuffer {
+            pool: self,
+            buffer,
+            length,
+        }
+    }
+
+    fn put(&self, buffer: AlignedBuffer) {
+        if buffer.capacity() != 0 {
+            self.free.lock().unwrap().push(buffer);
+        }
+    }
+}
+
+impl Debug for BufferPool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferPool")
+            .field("alignment", &self.alignment)
+            .field("free", &self.free.lock().map(|free| free.len()).ok())
+            .finish()
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> PooledBuffer<'a> {
+    #[cfg(feature = "block")]
+    pub(crate) fn empty(pool: &'a BufferPool) -> Self {
+        Self {
+            pool,
+            buffer: AlignedBuffer::new(0, pool.alignment),
+            length: 0,
+        }
+    }
+
+    #[cfg(feature = "block")]
+    pub(crate) fn truncate(&mut self, length: usize) {
+        self.length = usize::min(self.length, length);
+    }
+}
+
+impl Deref for PooledBuffer<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer.as_slice()[..self.length]
+    }
+}
+
+impl DerefMut for PooledBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buffer.as_mut_slice()[..self.length]
+    }
+}
+
+impl Borrow<AnyType> for PooledBuffer<'_> {
+    fn borrow(&self) -> &AnyType {
+        unsafe { &*self.buffer.ptr.as_ptr().cast() }
+    }
+}
+
+impl BorrowMut<AnyType> for PooledBuffer<'_> {
+    fn borrow_mut(&mut self) -> &mut AnyType {
+        unsafe { &mut *self.buffer.ptr.as_ptr().cast() }
+    }
+}
+
+impl Debug for PooledBuffer<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl Drop for PooledBuffer<'_> {
+    fn drop(&mut self) {
+        let buffer =
+            std::mem::replace(&mut self.buffer, AlignedBuffer::new(0, self.pool.alignment));
+        self.pool.put(buffer);
+    }
+}
+
+impl AlignedBuffer {
+    // zero sized buffers are dangling and never allocated
+    fn new(capacity: usize, alignment: usize) -> Self {
+        let layout = Layout::from_size_align(capacity, alignment).unwrap();
+        let ptr = match capacity {
+            0 => NonNull::new(alignment as *mut u8).unwrap(),
+            _ => match NonNull::new(unsafe { alloc::alloc(layout) }) {
+                Some(ptr) => ptr,
+                None => alloc::handle_alloc_error(layout),
+            },
+        };
+
+        Self { ptr, layout }
+    }
+
+    fn capacity(&self) -> usize {
+        self.layout.size()
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.capacity()) }
+    }
+
+    // the bytes are uninitialized until filled, PooledBuffer zeroes them before use
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.capacity()) }
+    }
+}
+
+impl Drop for AlignedBuffer {
+    fn drop(&mut self) {
+        if self.capacity() != 0 {
+            unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::counting_allocator::allocations;
+
+    #[test]
+    fn reuse_test() {
+        let pool = BufferPool::with_alignment(512);
+
+        let address = {
+            let mut buffer = pool.get(4096);
+            assert_eq!(buffer.len(), 4096, "length");
+            assert_eq!(buffer.as_ptr() as usize % 512, 0, "aligned");
+            buffer[0] = 0xFF;
+            buffer.as_ptr() as usize
+        };
+
+        let before = allocations();
+        let buffer = pool.get(1024);
+        assert_eq!(allocations(), before, "smaller buffer reused");
+        assert_eq!(buffer.as_ptr() as usize, address, "same buffer");
+        assert_eq!(buffer.len(), 1024, "length");
+        assert_eq!(buffer[0], 0x00, "zeroed");
+        drop(buffer);
+
+        let buffer = pool.get(8192);
+        assert_eq!(buffer.len(), 8192, "grown buffer");
+        assert_eq!(buffer.as_ptr() as usize % 512, 0, "grown buffer aligned");
+        drop(buffer);
+
+        let before = allocations();
+        for _ in 0..100 {
+            drop(pool.get(8192));
+        }
+        assert_eq!(allocations(), before, "high-water mark reached");
+
+        assert!(pool.get(0).is_empty(), "empty buffer");
+    }
+}
@@ -0,0 +1,684 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{
+        bitfield_bound_check, cdb_bytes,
+        sense::{Descriptor, SenseData},
+        timeout_setter, Control,
+    },
+    data_wrapper::{AnyType, VecBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct AtaPassThroughCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    multiple_count: u8,
+    protocol: Protocol,
+    extend: bool,
+    off_line: u8,
+    check_condition: bool,
+    transfer_type: bool,
+    transfer_direction: bool,
+    byte_block: bool,
+    transfer_length: u8,
+    features: u16,
+    sector_count: u16,
+    logical_block_address: u64,
+    device: u8,
+    command: u8,
+    control: u8,
+    allocation_length: u32,
+    data_buffer: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Protocol {
+    HardwareReset,
+    SoftwareReset,
+    NonData,
+    PioDataIn,
+    PioDataOut,
+    Dma,
+    ExecuteDeviceDiagnostic,
+    DeviceReset,
+    UdmaDataIn,
+    UdmaDataOut,
+    Fpdma,
+    ReturnResponseInformation,
+    Other(u8),
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandResult {
+    pub data: Vec<u8>,
+    // only returned when check_condition is set
+    pub registers: Option<AtaRegisters>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtaRegisters {
+    pub extend: bool,
+    pub error: u8,
+    pub count: u16,
+    pub logical_block_address: u64,
+    pub device: u8,
+    pub status: u8,
+}
+
+impl<'a> AtaPassThroughCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            multiple_count: 0,
+            protocol: Protocol::NonData,
+            extend: false,
+            off_line: 0,
+            check_condition: false,
+            transfer_type: false,
+            transfer_direction: false,
+            byte_block: false,
+            transfer_length: 0,
+            features: 0,
+            sector_count: 0,
+            logical_block_address: 0,
+            device: 0,
+            command: 0,
+            control: 0,
+            allocation_length: 0,
+            data_buffer: vec![],
+        }
+    }
+
+    // multiple_count must be less than 0x08
+    pub fn multiple_count(&mut self, value: u8) -> &mut Self {
+        self.multiple_count = value;
+        self
+    }
+
+    pub fn protocol(&mut self, value: Protocol) -> &mut Self {
+        self.protocol = value;
+        self
+    }
+
+    // use the 48 bits registers, only valid for ATA PASS-THROUGH (16)
+    pub fn extend(&mut self, value: bool) -> &mut Self {
+        self.extend = value;
+        self
+    }
+
+    // off_line must be less than 0x04
+    pub fn off_line(&mut self, value: u8) -> &mut Self {
+        self.off_line = value;
+        self
+    }
+
+    // return the ATA output registers in the sense data
+    pub fn check_condition(&mut self, value: bool) -> &mut Self {
+        self.check_condition = value;
+        self
+    }
+
+    // count blocks in logical blocks instead of 512 bytes
+    pub fn transfer_type(&mut self, value: bool) -> &mut Self {
+        self.transfer_type = value;
+        self
+    }
+
+    // true transfers from the device
+    pub fn transfer_direction(&mut self, value: bool) -> &mut Self {
+        self.transfer_direction = value;
+        self
+    }
+
+    // the transfer length is counted in blocks instead of bytes
+    pub fn byte_block(&mut self, value: bool) -> &mut Self {
+        self.byte_block = value;
+        self
+    }
+
+    // transfer_length must be less than 0x04
+    // 0 means no data, 1 the features field, 2 the sector count field and 3 the TPSIU
+    pub fn transfer_length(&mut self, value: u8) -> &mut Self {
+        self.transfer_length = value;
+        self
+    }
+
+    pub fn features(&mut self, value: u16) -> &mut Self {
+        self.features = value;
+        self
+    }
+
+    pub fn sector_count(&mut self, value: u16) -> &mut Self {
+        self.sector_count = value;
+        self
+    }
+
+    // logical_block_address must be less than 0x1_0000_0000_0000 with extend, 0x1000_0000 otherwise
+    pub fn logical_block_address(&mut self, value: u64) -> &mut Self {
+        self.logical_block_address = value;
+        self
+    }
+
+    pub fn device(&mut self, value: u8) -> &mut Self {
+        self.device = value;
+        self
+    }
+
+    pub fn command(&mut self, value: u8) -> &mut Self {
+        self.command = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
+        self
+    }
+
+    // size of the data transfered from the device
+    pub fn allocation_length(&mut self, value: u32) -> &mut Self {
+        self.allocation_length = value;
+        self
+    }
+
+    // data transfered to the device
+    pub fn parameter(&mut self, value: &[u8]) -> &mut Self {
+        self.data_buffer = value.to_owned();
+        self
+    }
+
+    fn error_check(&self) -> crate::Result<()> {
+        bitfield_bound_check!(self.multiple_count, 3, "multiple count")?;
+        bitfield_bound_check!(u8::from(self.protocol), 4, "protocol")?;
+        bitfield_bound_check!(self.off_line, 2, "off line")?;
+        bitfield_bound_check!(self.transfer_length, 2, "transfer length")?;
+
+        if self.transfer_direction && !self.data_buffer.is_empty() {
+            return Err(crate::Error::BadArgument(
+                "Cannot send parameter data when transfering from the device".to_owned(),
+            ));
+        }
+
+        if !self.transfer_direction && self.allocation_length != 0 {
+            return Err(crate::Error::BadArgument(
+                "Cannot set allocation length when transfering to the device".to_owned(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn this_command<C>(&self, command_buffer: C) -> ThisCommand<C> {
+        let direction = direction(self.protocol, self.transfer_length, self.transfer_direction);
+
+        let data_buffer = match direction {
+            DataDirection::FromDevice => vec![0; self.allocation_length as usize],
+            DataDirection::ToDevice => self.data_buffer.clone(),
+            _ => vec![],
+        };
+
+        ThisCommand {
+            command_buffer,
+            direction,
+            check_condition: self.check_condition,
+            data_buffer,
+            timeout: self.timeout,
+        }
+    }
+
+    timeout_setter!();
+
+    // 0xA1 is also BLANK on MMC devices, use issue_16 when talking to optical drives
+    pub fn issue_12(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_12()?;
+
+        self.interface.issue(&self.this_command(command_buffer))
+    }
+
+    cdb_bytes!(cdb_bytes_12: issue_12 => command_buffer_12);
+
+    fn command_buffer_12(&self) -> crate::Result<CommandBuffer12> {
+        self.error_check()?;
+        if self.extend {
+            return Err(crate::Error::BadArgument(
+                "ATA PASS-THROUGH (12) cannot send 48 bits registers".to_owned(),
+            ));
+        }
+
+        let registers = pack_registers(
+            false,
+            self.features,
+            self.sector_count,
+            self.logical_block_address,
+            self.device,
+        )?;
+
+        Ok(CommandBuffer12::new()
+            .with_operation_code(OPERATION_CODE_12)
+            .with_multiple_count(self.multiple_count)
+            .with_protocol(self.protocol.into())
+            .with_off_line(self.off_line)
+            .with_check_condition(self.check_condition.into())
+            .with_transfer_type(self.transfer_type.into())
+            .with_transfer_direction(self.transfer_direction.into())
+            .with_byte_block(self.byte_block.into())
+            .with_transfer_length(self.transfer_length)
+            .with_features(registers.features as u8)
+            .with_sector_count(registers.sector_count as u8)
+            .with_lba_low(registers.logical_block_address as u8)
+            .with_lba_mid((registers.logical_block_address >> 8) as u8)
+            .with_lba_high((registers.logical_block_address >> 16) as u8)
+            .with_device(registers.device)
+            .with_command(self.command)
+            .with_control(self.control))
+    }
+
+    pub fn issue_16(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_16()?;
+
+        self.interface.issue(&self.this_command(command_buffer))
+    }
+
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
+
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
+        self.error_check()?;
+        let registers = pack_registers(
+            self.extend,
+            self.features,
+            self.sector_count,
+            self.logical_block_address,
+            self.device,
+        )?;
+
+        Ok(CommandBuffer16::new()
+            .with_operation_code(OPERATION_CODE_16)
+            .with_multiple_count(self.multiple_count)
+            .with_protocol(self.protocol.into())
+            .with_extend(self.extend.into())
+            .with_off_line(self.off_line)
+            .with_check_condition(self.check_condition.into())
+            .with_transfer_type(self.transfer_type.into())
+            .with_transfer_direction(self.transfer_direction.into())
+            .with_byte_block(self.byte_block.into())
+            .with_transfer_length(self.transfer_length)
+            .with_features(registers.features)
+            .with_sector_count(registers.sector_count)
+            .with_lba_low_ext((registers.logical_block_address >> 24) as u8)
+            .with_lba_low(registers.logical_block_address as u8)
+            .with_lba_mid_ext((registers.logical_block_address >> 32) as u8)
+            .with_lba_mid((registers.logical_block_address >> 8) as u8)
+            .with_lba_high_ext((registers.logical_block_address >> 40) as u8)
+            .with_lba_high((registers.logical_block_address >> 16) as u8)
+            .with_device(registers.device)
+            .with_command(self.command)
+            .with_control(self.control))
+    }
+}
+
+impl Scsi {
+    pub fn ata_pass_through(&self) -> AtaPassThroughCommand<'_> {
+        AtaPassThroughCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(ata_pass_through: AtaPassThroughCommand);
+
+impl From<Protocol> for u8 {
+    fn from(value: Protocol) -> Self {
+        match value {
+            Protocol::HardwareReset => 0x0,
+            Protocol::SoftwareReset => 0x1,
+            Protocol::NonData => 0x3,
+            Protocol::PioDataIn => 0x4,
+            Protocol::PioDataOut => 0x5,
+            Protocol::Dma => 0x6,
+            Protocol::ExecuteDeviceDiagnostic => 0x8,
+            Protocol::DeviceReset => 0x9,
+            Protocol::UdmaDataIn => 0xA,
+            Protocol::UdmaDataOut => 0xB,
+            Protocol::Fpdma => 0xC,
+            Protocol::ReturnResponseInformation => 0xF,
+            Protocol::Other(x) => x,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct InputRegisters {
+    features: u16,
+    sector_count: u16,
+    logical_block_address: u64,
+    device: u8,
+}
+
+fn pack_registers(
+    extend: bool,
+    features: u16,
+    sector_count: u16,
+    logical_block_address: u64,
+    device: u8,
+) -> crate::Result<InputRegisters> {
+    if extend {
+        bitfield_bound_check!(logical_block_address, 48, "logical block address")?;
+
+        return Ok(InputRegisters {
+            features,
+            sector_count,
+            logical_block_address,
+            device,
+        });
+    }
+
+    bitfield_bound_check!(features, 8, "features")?;
+    bitfield_bound_check!(sector_count, 8, "sector count")?;
+    bitfield_bound_check!(logical_block_address, 28, "logical block address")?;
+
+    // 28 bits commands carry the highest 4 bits of the address in the device field
+    Ok(InputRegisters {
+        features,
+        sector_count,
+        logical_block_address: logical_block_address & 0xFF_FFFF,
+        device: (device & 0xF0) | (logical_block_address >> 24) as u8,
+    })
+}
+
+fn direction(protocol: Protocol, transfer_length: u8, transfer_direction: bool) -> DataDirection {
+    match protocol {
+        Protocol::HardwareReset
+        | Protocol::SoftwareReset
+        | Protocol::NonData
+        | Protocol::ExecuteDeviceDiagnostic
+        | Protocol::DeviceReset
+        | Protocol::ReturnResponseInformation => DataDirection::None,
+        _ if transfer_length == 0 => DataDirection::None,
+        _ if transfer_direction => DataDirection::FromDevice,
+        _ => DataDirection::ToDevice,
+    }
+}
+
+fn parse_registers(sense: &SenseData) -> Option<AtaRegisters> {
+    match sense {
+        SenseData::Descriptor(sense) => sense.descriptors.iter().find_map(|x| match x {
+            Descriptor::AtaReturn(raw) if raw.len() >= ATA_STATUS_RETURN_DESCRIPTOR_LENGTH => {
+                Some(AtaRegisters {
+                    extend: raw[2] & 0b0000_0001 != 0,
+                    error: raw[3],
+                    count: u16::from_be_bytes([raw[4], raw[5]]),
+                    logical_block_address: u64::from_be_bytes([
+                        0, 0, raw[10], raw[8], raw[6], raw[11], raw[9], raw[7],
+                    ]),
+                    device: raw[12],
+                    status: raw[13],
+                })
+            }
+            _ => None,
+        }),
+        // ATA PASS THROUGH INFORMATION AVAILABLE, only the 28 bits registers are returned
+        SenseData::Fixed(sense) if *sense.additional_sense_code == 0x001D => Some(AtaRegisters {
+            extend: sense.command_specific_information[0] & 0b1000_0000 != 0,
+            error: sense.information[0],
+            count: sense.information[3] as u16,
+            logical_block_address: u32::from_be_bytes([
+                0,
+                sense.command_specific_information[3],
+                sense.command_specific_information[2],
+                sense.command_specific_information[1],
+            ]) as u64,
+            device: sense.information[2],
+            status: sense.information[1],
+        }),
+        _ => None,
+    }
+}
+
+const OPERATION_CODE_12: u8 = 0xA1;
+const OPERATION_CODE_16: u8 = 0x85;
+const ATA_STATUS_RETURN_DESCRIPTOR_LENGTH: usize = 14;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer12 {
+    operation_code: B8,
+    multiple_count: B3,
+    protocol: B4,
+    reserved_0: B1,
+    off_line: B2,
+    check_condition: B1,
+    transfer_type: B1,
+    transfer_direction: B1,
+    byte_block: B1,
+    transfer_length: B2,
+    features: B8,
+    sector_count: B8,
+    lba_low: B8,
+    lba_mid: B8,
+    lba_high: B8,
+    device: B8,
+    command: B8,
+    reserved_1: B8,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer16 {
+    operation_code: B8,
+    multiple_count: B3,
+    protocol: B4,
+    extend: B1,
+    off_line: B2,
+    check_condition: B1,
+    transfer_type: B1,
+    transfer_direction: B1,
+    byte_block: B1,
+    transfer_length: B2,
+    features: B16,
+    sector_count: B16,
+    lba_low_ext: B8,
+    lba_low: B8,
+    lba_mid_ext: B8,
+    lba_mid: B8,
+    lba_high_ext: B8,
+    lba_high: B8,
+    device: B8,
+    command: B8,
+    control: B8,
+}
+
+struct ThisCommand<C> {
+    command_buffer: C,
+    direction: DataDirection,
+    check_condition: bool,
+    data_buffer: Vec<u8>,
+    timeout: Option<Duration>,
+}
+
+impl<C: Copy> Command for ThisCommand<C> {
+    type CommandBuffer = C;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = VecBufferWrapper;
+
+    type ReturnType = crate::Result<CommandResult>;
+
+    fn direction(&self) -> DataDirection {
+        self.direction
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        self.data_buffer.clone().into()
+    }
+
+    fn data_size(&self) -> u32 {
+        self.data_buffer.len() as u32
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+
+        // the device terminates with CHECK CONDITION to return the registers,
+        // the ATA status and error registers tell whether the command succeeded
+        let registers = if self.check_condition {
+            parse_registers(result.sense_buffer)
+        } else {
+            None
+        };
+        if registers.is_none() {
+            result.check_common_error()?;
+        }
+
+        let length = result.data_in_length(result.data.len());
+        let mut data: Vec<u8> = std::mem::take(result.data);
+        data.truncate(length);
+
+        Ok(CommandResult { data, registers })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::sense::MAX_SENSE_BUFFER_LENGTH;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH_12: usize = 12;
+    const COMMAND_LENGTH_16: usize = 16;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer12>(),
+            COMMAND_LENGTH_12,
+            concat!("Size of: ", stringify!(CommandBuffer12))
+        );
+
+        assert_eq!(
+            size_of::<CommandBuffer16>(),
+            COMMAND_LENGTH_16,
+            concat!("Size of: ", stringify!(CommandBuffer16))
+        );
+    }
+
+    #[test]
+    fn identify_device_test() {
+        // IDENTIFY DEVICE, one 512 bytes block of PIO data in
+        let command_buffer = CommandBuffer16::new()
+            .with_operation_code(OPERATION_CODE_16)
+            .with_protocol(Protocol::PioDataIn.into())
+            .with_transfer_direction(1)
+            .with_byte_block(1)
+            .with_transfer_length(2)
+            .with_sector_count(1)
+            .with_command(0xEC);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [
+                0x85, 0x08, 0x0E, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0xEC, 0x00
+            ],
+            "identify device command"
+        );
+
+        assert!(
+            matches!(
+                direction(Protocol::PioDataIn, 2, true),
+                DataDirection::FromDevice
+            ),
+            "pio data in"
+        );
+        assert!(
+            matches!(direction(Protocol::NonData, 2, true), DataDirection::None),
+            "non data"
+        );
+        assert!(
+            matches!(direction(Protocol::Dma, 2, false), DataDirection::ToDevice),
+            "dma data out"
+        );
+    }
+
+    #[test]
+    fn command_buffer_12_test() {
+        // CHECK POWER MODE
+        let command_buffer = CommandBuffer12::new()
+            .with_operation_code(OPERATION_CODE_12)
+            .with_protocol(Protocol::NonData.into())
+            .with_check_condition(1)
+            .with_command(0xE5);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0xA1, 0x06, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xE5, 0x00, 0x00],
+            "check power mode command"
+        );
+    }
+
+    #[test]
+    fn pack_registers_test() {
+        assert_eq!(
+            pack_registers(false, 0xD0, 0x01, 0x0ABC_DEF0, 0xE0).unwrap(),
+            InputRegisters {
+                features: 0xD0,
+                sector_count: 0x01,
+                logical_block_address: 0xBC_DEF0,
+                device: 0xEA,
+            },
+            "28 bits registers"
+        );
+
+        assert!(
+            pack_registers(false, 0x100, 0, 0, 0).is_err(),
+            "features out of bounds"
+        );
+
+        assert_eq!(
+            pack_registers(true, 0x1234, 0x0100, 0x1234_5678_9ABC, 0x40)
+                .unwrap()
+                .logical_block_address,
+            0x1234_5678_9ABC,
+            "48 bits registers"
+        );
+    }
+
+    #[test]
+    fn parse_registers_test() {
+        let mut raw = [0u8; MAX_SENSE_BUFFER_LENGTH];
+        // RECOVERED ERROR, ATA PASS THROUGH INFORMATION AVAILABLE
+        raw[..22].copy_from_slice(&[
+            0x72, 0x01, 0x00, 0x1D, 0x00, 0x00, 0x00, 0x0E, 0x09, 0x0C, 0x01, 0x00, 0x00, 0x01,
+            0x12, 0x78, 0x34, 0x9A, 0x56, 0xBC, 0xE0, 0x50,
+        ]);
+
+        assert_eq!(
+            parse_registers(&SenseData::parse(&raw, 22)),
+            Some(AtaRegisters {
+                extend: true,
+                error: 0,
+                count: 1,
+                logical_block_address: 0x5634_12BC_9A78,
+                device: 0xE0,
+                status: 0x50,
+            }),
+            "ata status return descriptor"
+        );
+    }
+}
@@ -1,12 +1,19 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
-use crate::{command::bitfield_bound_check, result_data::ResultData, Command, DataDirection, Scsi};
+use crate::{
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
 
 #[derive(Clone, Debug)]
 pub struct BackgroundControlCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     background_operation_control: u8,
     command_buffer: CommandBuffer,
 }
@@ -15,6 +22,7 @@ impl<'a> BackgroundControlCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             background_operation_control: 0,
             command_buffer: CommandBuffer::new()
                 .with_operation_code(OPERATION_CODE)
@@ -38,33 +46,52 @@ impl<'a> BackgroundControlCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
-    pub fn issue(&mut self) -> crate::Result<()> {
-        bitfield_bound_check!(
-            self.background_operation_control,
-            2,
-            "background operation control"
-        )?;
+    timeout_setter!();
 
+    pub fn issue(&mut self) -> crate::Result<()> {
         let temp = ThisCommand {
-            command_buffer: self
-                .command_buffer
-                .with_background_operation_control(self.background_operation_control),
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
         };
         self.interface.issue(&temp)
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        background_operation_control_check(self.background_operation_control)?;
+
+        Ok(self
+            .command_buffer
+            .with_background_operation_control(self.background_operation_control))
+    }
 }
 
 impl Scsi {
-    pub fn background_control(&self) -> BackgroundControlCommand {
+    pub fn background_control(&self) -> BackgroundControlCommand<'_> {
         BackgroundControlCommand::new(self)
     }
 }
 
+crate::command::async_issue!(background_control: BackgroundControlCommand);
+
+fn background_operation_control_check(value: u8) -> crate::Result<()> {
+    bitfield_bound_check!(value, 2, "background operation control")?;
+
+    if value == 0x3 {
+        return Err(crate::Error::BadArgument(
+            "background operation control 0x3 is reserved".to_owned(),
+        ));
+    }
+
+    Ok(())
+}
+
 const OPERATION_CODE: u8 = 0x9E;
 const SERVICE_ACTION: u8 = 0x15;
 
@@ -83,6 +110,7 @@ struct CommandBuffer {
 
 struct ThisCommand {
     command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -104,6 +132,10 @@ impl Command for ThisCommand {
 
     fn data(&self) -> Self::DataBufferWrapper {}
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -127,4 +159,36 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn command_buffer_test() {
+        // start for 10 seconds
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_service_action(SERVICE_ACTION)
+            .with_background_operation_control(0x1)
+            .with_background_operation_time(100);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [
+                0x9E, 0x15, 0x40, 0x64, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00
+            ],
+            "background control command"
+        );
+
+        assert!(
+            background_operation_control_check(0x2).is_ok(),
+            "stop background operations"
+        );
+        assert!(
+            background_operation_control_check(0x3).is_err(),
+            "reserved background operation control"
+        );
+        assert!(
+            background_operation_control_check(0x4).is_err(),
+            "background operation control out of bounds"
+        );
+    }
 }
@@ -0,0 +1,347 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{
+        bitfield_bound_check, cdb_bytes, logical_block_size_check,
+        sense::{Descriptor, SenseData, SenseKey},
+        timeout_setter,
+        write::transfer_length_check,
+        Control,
+    },
+    data_wrapper::{AnyType, SliceBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct CompareAndWriteCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    write_protect: u8,
+    disable_page_out: bool,
+    force_unit_access: bool,
+    logical_block_address: u64,
+    number_of_logical_blocks: Option<u8>,
+    group_number: u8,
+    control: u8,
+    logical_block_size: u32,
+    compare_buffer: Vec<u8>,
+    write_buffer: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CommandResult {
+    Written,
+    // nothing was written, offset is the first byte of the compare data that did not match
+    Miscompare { offset: Option<u64> },
+}
+
+impl<'a> CompareAndWriteCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            write_protect: 0,
+            disable_page_out: false,
+            force_unit_access: false,
+            logical_block_address: 0,
+            number_of_logical_blocks: None,
+            group_number: 0,
+            control: 0,
+            logical_block_size: 0,
+            compare_buffer: vec![],
+            write_buffer: vec![],
+        }
+    }
+
+    // write_protect must be less than 0x08
+    pub fn write_protect(&mut self, value: u8) -> &mut Self {
+        self.write_protect = value;
+        self
+    }
+
+    pub fn disable_page_out(&mut self, value: bool) -> &mut Self {
+        self.disable_page_out = value;
+        self
+    }
+
+    pub fn force_unit_access(&mut self, value: bool) -> &mut Self {
+        self.force_unit_access = value;
+        self
+    }
+
+    pub fn logical_block_address(&mut self, value: u64) -> &mut Self {
+        self.logical_block_address = value;
+        self
+    }
+
+    // number of logical blocks is derived from the compare data length if not set
+    pub fn number_of_logical_blocks(&mut self, value: u8) -> &mut Self {
+        self.number_of_logical_blocks = Some(value);
+        self
+    }
+
+    // group_number must be less than 0x20
+    pub fn group_number(&mut self, value: u8) -> &mut Self {
+        self.group_number = value;
+        self
+    }
+
+    // required, the logical block length of READ CAPACITY, see Scsi::capacity
+    pub fn logical_block_size(&mut self, value: u32) -> &mut Self {
+        self.logical_block_size = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
+        self
+    }
+
+    // the data expected on the medium
+    pub fn compare_data(&mut self, value: &[u8]) -> &mut Self {
+        self.compare_buffer = value.to_owned();
+        self
+    }
+
+    // the data written when the compare succeeds, must be as long as the compare data
+    pub fn write_data(&mut self, value: &[u8]) -> &mut Self {
+        self.write_buffer = value.to_owned();
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<CommandResult> {
+        let (command_buffer, data_buffer) = self.command()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            data_buffer: SliceBufferWrapper(&data_buffer),
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        self.command().map(|(command_buffer, _)| command_buffer)
+    }
+
+    // the number of logical blocks comes from the data out, so both are built together
+    fn command(&self) -> crate::Result<(CommandBuffer, Vec<u8>)> {
+        logical_block_size_check(self.logical_block_size)?;
+        bitfield_bound_check!(self.write_protect, 3, "write protect")?;
+        bitfield_bound_check!(self.group_number, 5, "group number")?;
+
+        let (number_of_logical_blocks, data_buffer) = data_out(
+            &self.compare_buffer,
+            &self.write_buffer,
+            self.logical_block_size,
+            self.number_of_logical_blocks,
+        )?;
+
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_write_protect(self.write_protect)
+            .with_disable_page_out(self.disable_page_out.into())
+            .with_force_unit_access(self.force_unit_access.into())
+            .with_logical_block_address(self.logical_block_address)
+            .with_number_of_logical_blocks(number_of_logical_blocks)
+            .with_group_number(self.group_number)
+            .with_control(self.control);
+
+        Ok((command_buffer, data_buffer))
+    }
+}
+
+impl Scsi {
+    pub fn compare_and_write(&self) -> CompareAndWriteCommand<'_> {
+        CompareAndWriteCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(compare_and_write: CompareAndWriteCommand);
+
+// the compare blocks are followed by the write blocks
+fn data_out(
+    compare_buffer: &[u8],
+    write_buffer: &[u8],
+    logical_block_size: u32,
+    number_of_logical_blocks: Option<u8>,
+) -> crate::Result<(u8, Vec<u8>)> {
+    let block_count = transfer_length_check(
+        compare_buffer.len(),
+        logical_block_size,
+        number_of_logical_blocks.map(u32::from),
+        8,
+    )?;
+
+    if write_buffer.len() != compare_buffer.len() {
+        return Err(crate::Error::BadArgument(format!(
+            "write data length should match compare data length. Expected {} bytes, but {} was provided.",
+            compare_buffer.len(),
+            write_buffer.len()
+        )));
+    }
+
+    let mut data_buffer = Vec::with_capacity(compare_buffer.len() * 2);
+    data_buffer.extend_from_slice(compare_buffer);
+    data_buffer.extend_from_slice(write_buffer);
+
+    Ok((block_count as u8, data_buffer))
+}
+
+fn miscompare(sense: &SenseData) -> Option<CommandResult> {
+    match sense {
+        SenseData::Fixed(sense) if matches!(sense.sense_key, SenseKey::Miscompare) => {
+            Some(CommandResult::Miscompare {
+                offset: sense
+                    .is_valid
+                    .then(|| u32::from_be_bytes(sense.information) as u64),
+            })
+        }
+        SenseData::Descriptor(sense) if matches!(sense.sense_key, SenseKey::Miscompare) => {
+            Some(CommandResult::Miscompare {
+                offset: sense.descriptors.iter().find_map(|x| match x {
+                    Descriptor::Information {
+                        is_valid: true,
+                        information,
+                    } => Some(u64::from_be_bytes(*information)),
+                    _ => None,
+                }),
+            })
+        }
+        _ => None,
+    }
+}
+
+const OPERATION_CODE: u8 = 0x89;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    write_protect: B3,
+    disable_page_out: B1,
+    force_unit_access: B1,
+    reserved_0: B1,
+    obsolete: B1,
+    reserved_1: B1,
+    logical_block_address: B64,
+    reserved_2: B24,
+    number_of_logical_blocks: B8,
+    reserved_3: B3,
+    group_number: B5,
+    control: B8,
+}
+
+struct ThisCommand<'a> {
+    command_buffer: CommandBuffer,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
+}
+
+impl<'a> Command for ThisCommand<'a> {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
+
+    type ReturnType = crate::Result<CommandResult>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::ToDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        self.data_buffer
+    }
+
+    fn data_size(&self) -> u32 {
+        self.data_buffer.len() as u32
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+
+        if let Some(miscompare) = miscompare(result.sense_buffer) {
+            return Ok(miscompare);
+        }
+
+        result.check_common_error()?;
+
+        Ok(CommandResult::Written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::sense::MAX_SENSE_BUFFER_LENGTH;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+    }
+
+    #[test]
+    fn data_out_test() {
+        let (number_of_logical_blocks, data_buffer) =
+            data_out(&[0xAA; 1024], &[0x55; 1024], 512, None).unwrap();
+
+        assert_eq!(number_of_logical_blocks, 2, "number of logical blocks");
+        assert_eq!(data_buffer.len(), 2 * 2 * 512, "data out length");
+        assert_eq!(data_buffer[1023], 0xAA, "compare data");
+        assert_eq!(data_buffer[1024], 0x55, "write data");
+
+        assert!(
+            data_out(&[0xAA; 512], &[0x55; 1024], 512, None).is_err(),
+            "write data longer than compare data"
+        );
+        assert!(
+            data_out(&[0xAA; 512], &[0x55; 512], 512, Some(2)).is_err(),
+            "number of logical blocks mismatch"
+        );
+    }
+
+    #[test]
+    fn miscompare_test() {
+        let mut raw = [0u8; MAX_SENSE_BUFFER_LENGTH];
+        // MISCOMPARE DURING VERIFY OPERATION at byte 0x10
+        raw[..18].copy_from_slice(&[
+            0xF0, 0x00, 0x0E, 0x00, 0x00, 0x00, 0x10, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x1D, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+
+        assert_eq!(
+            miscompare(&SenseData::parse(&raw, 18)),
+            Some(CommandResult::Miscompare { offset: Some(0x10) }),
+            "miscompare"
+        );
+
+        assert_eq!(miscompare(&SenseData::None), None, "no sense data");
+    }
+}
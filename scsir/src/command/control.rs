@@ -0,0 +1,90 @@
+/// The CONTROL byte ending every CDB.
+///
+/// Bits 7 and 6 are vendor specific, bit 2 is NACA and the others are reserved or obsolete.
+/// The `control` setters of the commands take a raw `u8` as well, for bits this doesn't name.
+///
+/// The setters and `issue` leave the reserved and obsolete bits alone. The builders of this
+/// type never set them, so they can only come from a raw `u8`, which is taken as deliberate. A
+/// device that rejects them answers with ILLEGAL REQUEST, INVALID FIELD IN CDB.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Control(u8);
+
+impl Control {
+    const NACA: u8 = 0b0000_0100;
+    const VENDOR_SPECIFIC: u8 = 0b1100_0000;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Normal ACA, a CHECK CONDITION of this command establishes an ACA condition instead of
+    /// clearing the task set. Some HBAs reject commands with it set.
+    pub fn naca(self, value: bool) -> Self {
+        match value {
+            true => Self(self.0 | Self::NACA),
+            false => Self(self.0 & !Self::NACA),
+        }
+    }
+
+    /// The two vendor specific bits, only the low 2 bits of `value` are used.
+    pub fn vendor_specific(self, value: u8) -> Self {
+        Self((self.0 & !Self::VENDOR_SPECIFIC) | ((value << 6) & Self::VENDOR_SPECIFIC))
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<u8> for Control {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Control> for u8 {
+    fn from(value: Control) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naca_test() {
+        assert_eq!(
+            Control::new().naca(true).bits(),
+            0b0000_0100,
+            "NACA is bit 2"
+        );
+        assert_eq!(
+            Control::from(0xFF).naca(false).bits(),
+            0b1111_1011,
+            "NACA cleared"
+        );
+        assert_eq!(
+            Control::new().vendor_specific(0b11).naca(true).bits(),
+            0b1100_0100,
+            "vendor specific bits"
+        );
+    }
+
+    #[test]
+    fn setter_test() {
+        use crate::{MockResponse, MockScsi, Scsi};
+
+        let mock = MockScsi::new();
+        mock.expect(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x04], MockResponse::good())
+            .expect(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x80], MockResponse::good());
+
+        let scsi = Scsi::mock(mock.clone());
+        scsi.test_unit_ready()
+            .control(Control::new().naca(true))
+            .issue()
+            .unwrap();
+        scsi.test_unit_ready().control(0x80).issue().unwrap();
+        mock.verify();
+    }
+}
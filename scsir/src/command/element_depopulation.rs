@@ -0,0 +1,221 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct RemoveElementAndTruncateCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    command_buffer: RemoveElementCommandBuffer,
+}
+
+#[derive(Clone, Debug)]
+pub struct RestoreElementsAndRebuildCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    command_buffer: RestoreElementsCommandBuffer,
+}
+
+impl<'a> RemoveElementAndTruncateCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            command_buffer: RemoveElementCommandBuffer::new()
+                .with_operation_code(OPERATION_CODE)
+                .with_service_action(SERVICE_ACTION_REMOVE_ELEMENT),
+        }
+    }
+
+    // the capacity in logical blocks after truncation, 0 lets the device decide
+    pub fn requested_capacity(&mut self, value: u64) -> &mut Self {
+        self.command_buffer.set_requested_capacity(value);
+        self
+    }
+
+    // as reported by GET PHYSICAL ELEMENT STATUS
+    pub fn element_identifier(&mut self, value: u32) -> &mut Self {
+        self.command_buffer.set_element_identifier(value);
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<()> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<RemoveElementCommandBuffer> {
+        Ok(self.command_buffer)
+    }
+}
+
+impl<'a> RestoreElementsAndRebuildCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            command_buffer: RestoreElementsCommandBuffer::new()
+                .with_operation_code(OPERATION_CODE)
+                .with_service_action(SERVICE_ACTION_RESTORE_ELEMENTS),
+        }
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<()> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<RestoreElementsCommandBuffer> {
+        Ok(self.command_buffer)
+    }
+}
+
+impl Scsi {
+    pub fn remove_element_and_truncate(&self) -> RemoveElementAndTruncateCommand<'_> {
+        RemoveElementAndTruncateCommand::new(self)
+    }
+
+    pub fn restore_elements_and_rebuild(&self) -> RestoreElementsAndRebuildCommand<'_> {
+        RestoreElementsAndRebuildCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(remove_element_and_truncate: RemoveElementAndTruncateCommand);
+
+crate::command::async_issue!(restore_elements_and_rebuild: RestoreElementsAndRebuildCommand);
+
+const OPERATION_CODE: u8 = 0x9E;
+const SERVICE_ACTION_REMOVE_ELEMENT: u8 = 0x18;
+const SERVICE_ACTION_RESTORE_ELEMENTS: u8 = 0x19;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct RemoveElementCommandBuffer {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    requested_capacity: B64,
+    element_identifier: B32,
+    reserved_1: B8,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct RestoreElementsCommandBuffer {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    reserved_1: B104,
+    control: B8,
+}
+
+struct ThisCommand<C> {
+    command_buffer: C,
+    timeout: Option<Duration>,
+}
+
+impl<C: Copy> Command for ThisCommand<C> {
+    type CommandBuffer = C;
+
+    type DataBuffer = ();
+
+    type DataBufferWrapper = ();
+
+    type ReturnType = crate::Result<()>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::None
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {}
+
+    fn data_size(&self) -> u32 {
+        0
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<RemoveElementCommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(RemoveElementCommandBuffer))
+        );
+
+        assert_eq!(
+            size_of::<RestoreElementsCommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(RestoreElementsCommandBuffer))
+        );
+    }
+
+    #[test]
+    fn remove_element_test() {
+        let command_buffer = RemoveElementCommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_service_action(SERVICE_ACTION_REMOVE_ELEMENT)
+            .with_element_identifier(0x02);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [
+                0x9E, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+                0x00, 0x00
+            ],
+            "remove element and truncate command"
+        );
+    }
+}
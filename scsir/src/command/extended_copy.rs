@@ -0,0 +1,415 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    data_wrapper::{AnyType, SliceBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct ExtendedCopyCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    control: u8,
+    data_buffer: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct ParameterBuilder<'a> {
+    parent: &'a mut ExtendedCopyCommand<'a>,
+    list_identifier: u8,
+    sequential_striped: bool,
+    list_id_usage: u8,
+    priority: u8,
+    target_descriptors: Vec<u8>,
+    segment_descriptors: Vec<u8>,
+    inline_data: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlockToBlockSegment {
+    // the number of blocks is counted in destination blocks instead of source blocks
+    pub destination_count: bool,
+    pub source_target_descriptor_index: u16,
+    pub destination_target_descriptor_index: u16,
+    pub number_of_blocks: u16,
+    pub source_logical_block_address: u64,
+    pub destination_logical_block_address: u64,
+}
+
+impl<'a> ExtendedCopyCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            control: 0,
+            data_buffer: vec![],
+        }
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
+        self
+    }
+
+    // a complete parameter list, target descriptors, segment descriptors and inline data included
+    pub fn parameter(&mut self, value: &[u8]) -> &mut Self {
+        self.data_buffer = value.to_owned();
+        self
+    }
+
+    pub fn parameter_builder(&'a mut self) -> ParameterBuilder<'a> {
+        ParameterBuilder::new(self)
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<()> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(self.data_buffer.len(), 32, "parameter list length")?;
+
+        Ok(CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_service_action(SERVICE_ACTION_LID1)
+            .with_parameter_list_length(self.data_buffer.len() as u32)
+            .with_control(self.control))
+    }
+}
+
+impl<'a> ParameterBuilder<'a> {
+    fn new(parent: &'a mut ExtendedCopyCommand<'a>) -> Self {
+        Self {
+            parent,
+            list_identifier: 0,
+            sequential_striped: false,
+            list_id_usage: 0,
+            priority: 0,
+            target_descriptors: vec![],
+            segment_descriptors: vec![],
+            inline_data: vec![],
+        }
+    }
+
+    pub fn list_identifier(&mut self, value: u8) -> &mut Self {
+        self.list_identifier = value;
+        self
+    }
+
+    pub fn sequential_striped(&mut self, value: bool) -> &mut Self {
+        self.sequential_striped = value;
+        self
+    }
+
+    // list_id_usage must be less than 0x04
+    // 0 keeps the copy results available through RECEIVE COPY RESULTS, 3 means list_identifier is ignored
+    pub fn list_id_usage(&mut self, value: u8) -> &mut Self {
+        self.list_id_usage = value;
+        self
+    }
+
+    // priority must be less than 0x08
+    pub fn priority(&mut self, value: u8) -> &mut Self {
+        self.priority = value;
+        self
+    }
+
+    // target descriptors are 32 bytes long
+    pub fn add_target_descriptor(&mut self, value: &[u8]) -> &mut Self {
+        self.target_descriptors.extend_from_slice(value);
+        self
+    }
+
+    pub fn add_segment_descriptor(&mut self, value: &[u8]) -> &mut Self {
+        self.segment_descriptors.extend_from_slice(value);
+        self
+    }
+
+    pub fn add_block_to_block_segment(&mut self, value: BlockToBlockSegment) -> &mut Self {
+        self.add_segment_descriptor(&block_to_block_segment_descriptor(value))
+    }
+
+    pub fn inline_data(&mut self, value: &[u8]) -> &mut Self {
+        self.inline_data = value.to_owned();
+        self
+    }
+
+    pub fn done(&mut self) -> crate::Result<&'a mut ExtendedCopyCommand<'_>> {
+        bitfield_bound_check!(self.list_id_usage, 2, "list id usage")?;
+        bitfield_bound_check!(self.priority, 3, "priority")?;
+
+        let header = ParameterListHeader::new()
+            .with_list_identifier(self.list_identifier)
+            .with_sequential_striped(self.sequential_striped.into())
+            .with_list_id_usage(self.list_id_usage)
+            .with_priority(self.priority);
+
+        self.parent.data_buffer = parameter_list(
+            header,
+            &self.target_descriptors,
+            &self.segment_descriptors,
+            &self.inline_data,
+        )?;
+
+        Ok(self.parent)
+    }
+}
+
+impl Scsi {
+    pub fn extended_copy(&self) -> ExtendedCopyCommand<'_> {
+        ExtendedCopyCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(extended_copy: ExtendedCopyCommand);
+
+fn block_to_block_segment_descriptor(value: BlockToBlockSegment) -> [u8; 28] {
+    BlockToBlockSegmentDescriptor::new()
+        .with_descriptor_type_code(BLOCK_TO_BLOCK_TYPE_CODE)
+        .with_destination_count(value.destination_count.into())
+        .with_descriptor_length(BLOCK_TO_BLOCK_DESCRIPTOR_LENGTH)
+        .with_source_target_descriptor_index(value.source_target_descriptor_index)
+        .with_destination_target_descriptor_index(value.destination_target_descriptor_index)
+        .with_number_of_blocks(value.number_of_blocks)
+        .with_source_logical_block_address(value.source_logical_block_address)
+        .with_destination_logical_block_address(value.destination_logical_block_address)
+        .into_bytes()
+}
+
+fn parameter_list(
+    header: ParameterListHeader,
+    target_descriptors: &[u8],
+    segment_descriptors: &[u8],
+    inline_data: &[u8],
+) -> crate::Result<Vec<u8>> {
+    if target_descriptors.len() % TARGET_DESCRIPTOR_LENGTH != 0 {
+        return Err(crate::Error::BadArgument(format!(
+            "Target descriptor list length must be a multiple of {}, but {} was provided.",
+            TARGET_DESCRIPTOR_LENGTH,
+            target_descriptors.len()
+        )));
+    }
+
+    bitfield_bound_check!(
+        target_descriptors.len(),
+        16,
+        "target descriptor list length"
+    )?;
+    bitfield_bound_check!(
+        segment_descriptors.len(),
+        32,
+        "segment descriptor list length"
+    )?;
+    bitfield_bound_check!(inline_data.len(), 32, "inline data length")?;
+
+    let header = header
+        .with_target_descriptor_list_length(target_descriptors.len() as u16)
+        .with_segment_descriptor_list_length(segment_descriptors.len() as u32)
+        .with_inline_data_length(inline_data.len() as u32);
+
+    let mut bytes = header.into_bytes().to_vec();
+    bytes.extend_from_slice(target_descriptors);
+    bytes.extend_from_slice(segment_descriptors);
+    bytes.extend_from_slice(inline_data);
+
+    Ok(bytes)
+}
+
+const OPERATION_CODE: u8 = 0x83;
+const SERVICE_ACTION_LID1: u8 = 0x00;
+const TARGET_DESCRIPTOR_LENGTH: usize = 32;
+const BLOCK_TO_BLOCK_TYPE_CODE: u8 = 0x02;
+const BLOCK_TO_BLOCK_DESCRIPTOR_LENGTH: u16 = 0x14;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    reserved_1: B64,
+    parameter_list_length: B32,
+    reserved_2: B8,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct ParameterListHeader {
+    list_identifier: B8,
+    reserved_0: B2,
+    sequential_striped: B1,
+    list_id_usage: B2,
+    priority: B3,
+    target_descriptor_list_length: B16,
+    reserved_1: B32,
+    segment_descriptor_list_length: B32,
+    inline_data_length: B32,
+}
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct BlockToBlockSegmentDescriptor {
+    descriptor_type_code: B8,
+    reserved_0: B6,
+    destination_count: B1,
+    cat: B1,
+    descriptor_length: B16,
+    source_target_descriptor_index: B16,
+    destination_target_descriptor_index: B16,
+    reserved_1: B16,
+    number_of_blocks: B16,
+    source_logical_block_address: B64,
+    destination_logical_block_address: B64,
+}
+
+struct ThisCommand<'a> {
+    command_buffer: CommandBuffer,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
+}
+
+impl<'a> Command for ThisCommand<'a> {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
+
+    type ReturnType = crate::Result<()>;
+
+    fn direction(&self) -> DataDirection {
+        if self.data_buffer.is_empty() {
+            DataDirection::None
+        } else {
+            DataDirection::ToDevice
+        }
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        self.data_buffer
+    }
+
+    fn data_size(&self) -> u32 {
+        self.data_buffer.len() as u32
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+    const PARAMETER_HEADER_LENGTH: usize = 16;
+    const BLOCK_TO_BLOCK_LENGTH: usize = 28;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+
+        assert_eq!(
+            size_of::<ParameterListHeader>(),
+            PARAMETER_HEADER_LENGTH,
+            concat!("Size of: ", stringify!(ParameterListHeader))
+        );
+
+        assert_eq!(
+            size_of::<BlockToBlockSegmentDescriptor>(),
+            BLOCK_TO_BLOCK_LENGTH,
+            concat!("Size of: ", stringify!(BlockToBlockSegmentDescriptor))
+        );
+    }
+
+    #[test]
+    fn command_buffer_test() {
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_service_action(SERVICE_ACTION_LID1)
+            .with_parameter_list_length(0x0001_0020);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [
+                0x83, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x20,
+                0x00, 0x00
+            ],
+            "extended copy command"
+        );
+    }
+
+    #[test]
+    fn block_to_block_test() {
+        let segment = block_to_block_segment_descriptor(BlockToBlockSegment {
+            destination_count: false,
+            source_target_descriptor_index: 0,
+            destination_target_descriptor_index: 1,
+            number_of_blocks: 0x0800,
+            source_logical_block_address: 0x1000,
+            destination_logical_block_address: 0x2000,
+        });
+
+        let targets = [0u8; 2 * TARGET_DESCRIPTOR_LENGTH];
+        let bytes = parameter_list(
+            ParameterListHeader::new().with_list_identifier(0x05),
+            &targets,
+            &segment,
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            bytes[..PARAMETER_HEADER_LENGTH],
+            [
+                0x05, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1C, 0x00, 0x00,
+                0x00, 0x00
+            ],
+            "parameter list header"
+        );
+        assert_eq!(
+            bytes[PARAMETER_HEADER_LENGTH + targets.len()..],
+            [
+                0x02, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x00
+            ],
+            "block to block segment descriptor"
+        );
+
+        assert!(
+            parameter_list(ParameterListHeader::new(), &[0; 16], &segment, &[]).is_err(),
+            "partial target descriptor"
+        );
+    }
+}
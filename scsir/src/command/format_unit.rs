@@ -1,12 +1,12 @@
 #![allow(dead_code)]
 
-use std::mem::size_of_val;
+use std::{mem::size_of_val, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{bitfield_bound_check, cdb_bytes, logical_block_size_check, timeout_setter, Control},
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -14,9 +14,11 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct FormatUnitCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     format_protection_information: u8,
     defect_list_format: u8,
     fast_format: u8,
+    logical_block_size: u32,
     command_buffer: CommandBuffer,
     header_buffer: LongParameterListHeader,
     initialization_pattern_descriptor_header: InitializationPatternDescriptorHeader,
@@ -58,9 +60,11 @@ impl<'a> FormatUnitCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             format_protection_information: 0,
             defect_list_format: 0,
             fast_format: 0,
+            logical_block_size: 0,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
             header_buffer: LongParameterListHeader::new(),
             initialization_pattern_descriptor_header: InitializationPatternDescriptorHeader::new(),
@@ -102,16 +106,61 @@ impl<'a> FormatUnitCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    // required with an initialization pattern, the logical block length after the format
+    pub fn logical_block_size(&mut self, value: u32) -> &mut Self {
+        self.logical_block_size = value;
         self
     }
 
-    pub fn parameter(&'a mut self) -> ParameterBuilder {
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    pub fn parameter(&'a mut self) -> ParameterBuilder<'a> {
         ParameterBuilder::new(self)
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer()?;
+
+        if command_buffer.format_data() == 0 {
+            let temp = ThisCommand {
+                command_buffer,
+                data_buffer: SliceBufferWrapper(&[]),
+                timeout: self.timeout,
+            };
+            return self.interface.issue(&temp);
+        }
+
+        if self.header_buffer.initialization_pattern() == 1 {
+            initialization_pattern_check(
+                self.initialization_pattern.len(),
+                self.logical_block_size,
+            )?;
+        }
+
+        let data_buffer = parameter_list(
+            command_buffer.longlist() != 0,
+            self.header_buffer,
+            self.initialization_pattern_descriptor_header,
+            &self.initialization_pattern,
+            &self.defect_list,
+        )?;
+
+        let temp = ThisCommand {
+            command_buffer,
+            data_buffer: SliceBufferWrapper(&data_buffer),
+            timeout: self.timeout,
+        };
+        self.interface.issue(&temp)
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
         bitfield_bound_check!(
             self.format_protection_information,
             2,
@@ -119,78 +168,104 @@ impl<'a> FormatUnitCommand<'a> {
         )?;
         bitfield_bound_check!(self.defect_list_format, 3, "defect list format")?;
         bitfield_bound_check!(self.fast_format, 2, "fast format")?;
-        self.command_buffer
-            .set_format_protection_information(self.format_protection_information);
-        self.command_buffer
-            .set_defect_list_format(self.defect_list_format);
-        self.command_buffer.set_fast_format(self.fast_format);
 
-        if self.command_buffer.format_data() == 0 {
-            let temp = ThisCommand {
-                command_buffer: self.command_buffer,
-                data_buffer: vec![],
-            };
-            return self.interface.issue(&temp);
-        }
+        Ok(self
+            .command_buffer
+            .with_format_protection_information(self.format_protection_information)
+            .with_defect_list_format(self.defect_list_format)
+            .with_fast_format(self.fast_format))
+    }
+}
 
-        let mut data_buffer: Vec<u8> = vec![];
+// the device repeats the pattern over every logical block, so it may fill at most one
+fn initialization_pattern_check(
+    initialization_pattern_length: usize,
+    logical_block_size: u32,
+) -> crate::Result<()> {
+    if initialization_pattern_length == 0 {
+        return Ok(());
+    }
 
-        if self.command_buffer.longlist() == 0 {
-            let long_list = self.header_buffer;
+    logical_block_size_check(logical_block_size)?;
+    if initialization_pattern_length > logical_block_size as usize {
+        return Err(crate::Error::ArgumentOutOfBounds(format!(
+            "initialization pattern is longer than a logical block. The maximum possible value is {}, but {} was provided.",
+            logical_block_size, initialization_pattern_length
+        )));
+    }
 
-            bitfield_bound_check!(long_list.defect_list_length(), 16, "defect list length")?;
+    Ok(())
+}
 
-            let header = ShortParameterListHeader::new()
-                .with_protection_fields_usage(long_list.protection_fields_usage())
-                .with_format_options_valid(long_list.format_options_valid())
-                .with_disable_primary(long_list.disable_primary())
-                .with_disable_certification(long_list.disable_certification())
-                .with_stop_format(long_list.stop_format())
-                .with_initialization_pattern(long_list.initialization_pattern())
-                .with_immediate(long_list.immediate())
-                .with_vendor_specific(long_list.vendor_specific())
-                .with_defect_list_length(long_list.defect_list_length() as u16);
+fn parameter_list(
+    longlist: bool,
+    header: LongParameterListHeader,
+    initialization_pattern_descriptor_header: InitializationPatternDescriptorHeader,
+    initialization_pattern: &[u8],
+    defect_list: &[DefectListItem],
+) -> crate::Result<Vec<u8>> {
+    let mut data_buffer: Vec<u8> = vec![];
+
+    if !longlist {
+        let long_list = header;
+
+        bitfield_bound_check!(long_list.defect_list_length(), 16, "defect list length")?;
+
+        let header = ShortParameterListHeader::new()
+            .with_protection_fields_usage(long_list.protection_fields_usage())
+            .with_format_options_valid(long_list.format_options_valid())
+            .with_disable_primary(long_list.disable_primary())
+            .with_disable_certification(long_list.disable_certification())
+            .with_stop_format(long_list.stop_format())
+            .with_initialization_pattern(long_list.initialization_pattern())
+            .with_immediate(long_list.immediate())
+            .with_vendor_specific(long_list.vendor_specific())
+            .with_defect_list_length(long_list.defect_list_length() as u16);
+
+        data_buffer.extend_from_slice(&header.bytes);
+    } else {
+        data_buffer.extend_from_slice(&header.bytes);
+    }
 
-            data_buffer.extend_from_slice(&header.bytes);
-        } else {
-            data_buffer.extend_from_slice(&self.header_buffer.bytes);
-        }
+    if header.initialization_pattern() == 1 {
+        data_buffer.extend_from_slice(&initialization_pattern_descriptor_header.bytes);
+        data_buffer.extend_from_slice(initialization_pattern);
+    }
 
-        if self.header_buffer.initialization_pattern() == 1 {
-            data_buffer.extend_from_slice(&self.initialization_pattern_descriptor_header.bytes);
-            data_buffer.extend_from_slice(&self.initialization_pattern);
-        }
+    let defect_list_offset = data_buffer.len();
 
-        for item in &self.defect_list {
-            match item {
-                DefectListItem::ShortBlockFormatAddressDescriptor(x) => {
-                    data_buffer.extend_from_slice(&x.bytes)
-                }
-                DefectListItem::ExtendedBytesFromIndexAddressDescriptor(x) => {
-                    data_buffer.extend_from_slice(&x.bytes)
-                }
-                DefectListItem::ExtendedPhysicalSectorAddressDescriptor(x) => {
-                    data_buffer.extend_from_slice(&x.bytes)
-                }
-                DefectListItem::LongBlockFormatAddressDescriptor(x) => {
-                    data_buffer.extend_from_slice(&x.bytes)
-                }
-                DefectListItem::BytesFromIndexFormatAddressDescriptor(x) => {
-                    data_buffer.extend_from_slice(&x.bytes)
-                }
-                DefectListItem::PhysicalSectorFormatAddressDescriptor(x) => {
-                    data_buffer.extend_from_slice(&x.bytes)
-                }
-                DefectListItem::CustomDescriptor(x) => data_buffer.extend_from_slice(x),
+    for item in defect_list {
+        match item {
+            DefectListItem::ShortBlockFormatAddressDescriptor(x) => {
+                data_buffer.extend_from_slice(&x.bytes)
+            }
+            DefectListItem::ExtendedBytesFromIndexAddressDescriptor(x) => {
+                data_buffer.extend_from_slice(&x.bytes)
             }
+            DefectListItem::ExtendedPhysicalSectorAddressDescriptor(x) => {
+                data_buffer.extend_from_slice(&x.bytes)
+            }
+            DefectListItem::LongBlockFormatAddressDescriptor(x) => {
+                data_buffer.extend_from_slice(&x.bytes)
+            }
+            DefectListItem::BytesFromIndexFormatAddressDescriptor(x) => {
+                data_buffer.extend_from_slice(&x.bytes)
+            }
+            DefectListItem::PhysicalSectorFormatAddressDescriptor(x) => {
+                data_buffer.extend_from_slice(&x.bytes)
+            }
+            DefectListItem::CustomDescriptor(x) => data_buffer.extend_from_slice(x),
         }
+    }
 
-        let temp = ThisCommand {
-            command_buffer: self.command_buffer,
-            data_buffer,
-        };
-        self.interface.issue(&temp)
+    // the device would interpret trailing bytes as defects, or miss some
+    if header.defect_list_length() as usize != data_buffer.len() - defect_list_offset {
+        return Err(crate::Error::BadArgument(
+            "Defect list length does not match the defect list".to_owned(),
+        ));
     }
+
+    Ok(data_buffer)
 }
 
 impl<'a> ParameterBuilder<'a> {
@@ -223,7 +298,7 @@ impl<'a> ParameterBuilder<'a> {
         DefectListBuilder::new(self)
     }
 
-    pub fn done(&'a mut self) -> crate::Result<&'a mut FormatUnitCommand> {
+    pub fn done(&'a mut self) -> crate::Result<&'a mut FormatUnitCommand<'a>> {
         self.parent
             .command_buffer
             .set_longlist(self.longlist.into());
@@ -284,7 +359,7 @@ impl<'a> ShortParameterListHeaderBuilder<'a> {
         self
     }
 
-    pub fn done(&'a mut self) -> crate::Result<&'a mut ParameterBuilder> {
+    pub fn done(&'a mut self) -> crate::Result<&'a mut ParameterBuilder<'a>> {
         let parent = self.delegate.done()?;
         parent.longlist = false;
         Ok(parent)
@@ -332,6 +407,8 @@ impl<'a> LongParameterListHeaderBuilder<'a> {
         self
     }
 
+    // return once the parameter list is validated and format in the background,
+    // progress is reported by REQUEST SENSE
     pub fn immediate(&mut self, value: bool) -> &mut Self {
         self.header_buffer.set_immediate(value as u8);
         self
@@ -348,7 +425,7 @@ impl<'a> LongParameterListHeaderBuilder<'a> {
         self
     }
 
-    pub fn done(&'a mut self) -> crate::Result<&'a mut ParameterBuilder> {
+    pub fn done(&'a mut self) -> crate::Result<&'a mut ParameterBuilder<'a>> {
         bitfield_bound_check!(self.protection_fields_usage, 3, "protection fields usage")?;
         bitfield_bound_check!(
             self.protection_interval_exponent,
@@ -361,6 +438,9 @@ impl<'a> LongParameterListHeaderBuilder<'a> {
         self.header_buffer
             .set_protection_interval_exponent(self.protection_interval_exponent);
 
+        // keep the length of a defect list that was already added
+        self.header_buffer
+            .set_defect_list_length(self.parent.header_buffer.defect_list_length());
         self.parent.header_buffer = self.header_buffer;
         self.parent.longlist = true;
 
@@ -395,7 +475,7 @@ impl<'a> InitializationPatternDescriptorBuilder<'a> {
         self
     }
 
-    pub fn done(&'a mut self) -> crate::Result<&'a mut ParameterBuilder> {
+    pub fn done(&'a mut self) -> crate::Result<&'a mut ParameterBuilder<'a>> {
         bitfield_bound_check!(
             self.initialization_pattern.len(),
             16,
@@ -537,7 +617,7 @@ impl<'a> DefectListBuilder<'a> {
         self
     }
 
-    pub fn done(&'a mut self) -> crate::Result<&'a mut ParameterBuilder> {
+    pub fn done(&'a mut self) -> crate::Result<&'a mut ParameterBuilder<'a>> {
         for w in self.defect_list.windows(2) {
             if std::mem::discriminant(&w[0]) != std::mem::discriminant(&w[1]) {
                 return Err(crate::Error::BadArgument(
@@ -587,11 +667,13 @@ impl<'a> DefectListBuilder<'a> {
 }
 
 impl Scsi {
-    pub fn format_unit(&self) -> FormatUnitCommand {
+    pub fn format_unit(&self) -> FormatUnitCommand<'_> {
         FormatUnitCommand::new(self)
     }
 }
 
+crate::command::async_issue!(format_unit: FormatUnitCommand);
+
 const OPERATION_CODE: u8 = 0x04;
 
 #[bitfield]
@@ -715,22 +797,27 @@ pub(super) struct PhysicalSectorFormatAddressDescriptor {
     pub(super) sector_number: B32,
 }
 
-struct ThisCommand {
+struct ThisCommand<'a> {
     command_buffer: CommandBuffer,
-    data_buffer: Vec<u8>,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl Command for ThisCommand {
+impl<'a> Command for ThisCommand<'a> {
     type CommandBuffer = CommandBuffer;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
     fn direction(&self) -> DataDirection {
-        DataDirection::ToDevice
+        if self.data_buffer.is_empty() {
+            DataDirection::None
+        } else {
+            DataDirection::ToDevice
+        }
     }
 
     fn command(&self) -> Self::CommandBuffer {
@@ -738,13 +825,17 @@ impl Command for ThisCommand {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        VecBufferWrapper(self.data_buffer.clone())
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -846,4 +937,132 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn command_buffer_test() {
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_format_protection_information(0b01)
+            .with_longlist(1)
+            .with_format_data(1)
+            .with_complete_list(1)
+            .with_defect_list_format(0b011);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x04, 0x7B, 0x00, 0x00, 0x00, 0x00],
+            "format unit command"
+        );
+    }
+
+    #[test]
+    fn no_data_test() {
+        let command = ThisCommand {
+            command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
+            data_buffer: SliceBufferWrapper(&[]),
+            timeout: None,
+        };
+
+        assert!(
+            matches!(command.direction(), DataDirection::None),
+            "format without parameter list"
+        );
+    }
+
+    #[test]
+    fn parameter_list_test() {
+        let defect_list = [
+            DefectListItem::ShortBlockFormatAddressDescriptor(
+                ShortBlockFormatAddressDescriptor::new().with_short_block_address(0x1234),
+            ),
+            DefectListItem::ShortBlockFormatAddressDescriptor(
+                ShortBlockFormatAddressDescriptor::new().with_short_block_address(0x5678),
+            ),
+        ];
+        let header = LongParameterListHeader::new()
+            .with_format_options_valid(1)
+            .with_initialization_pattern(1)
+            .with_immediate(1)
+            .with_defect_list_length(8);
+        let initialization_pattern_descriptor_header = InitializationPatternDescriptorHeader::new()
+            .with_initialization_pattern_type(0x01)
+            .with_initialization_pattern_length(2);
+
+        let data_buffer = parameter_list(
+            false,
+            header,
+            initialization_pattern_descriptor_header,
+            &[0xDE, 0xAD],
+            &defect_list,
+        )
+        .unwrap();
+        assert_eq!(
+            data_buffer,
+            [
+                0x00, 0x8A, 0x00, 0x08, 0x00, 0x01, 0x00, 0x02, 0xDE, 0xAD, 0x00, 0x00, 0x12, 0x34,
+                0x00, 0x00, 0x56, 0x78
+            ],
+            "short parameter list"
+        );
+
+        let command = ThisCommand {
+            command_buffer: CommandBuffer::new().with_format_data(1),
+            data_buffer: SliceBufferWrapper(&data_buffer),
+            timeout: None,
+        };
+        assert!(
+            matches!(command.direction(), DataDirection::ToDevice),
+            "format with parameter list"
+        );
+
+        let data_buffer = parameter_list(
+            true,
+            header.with_initialization_pattern(0),
+            initialization_pattern_descriptor_header,
+            &[],
+            &defect_list,
+        )
+        .unwrap();
+        assert_eq!(data_buffer.len(), 16, "long parameter list");
+
+        assert!(
+            parameter_list(
+                false,
+                header
+                    .with_initialization_pattern(0)
+                    .with_defect_list_length(4),
+                initialization_pattern_descriptor_header,
+                &[],
+                &defect_list,
+            )
+            .is_err(),
+            "defect list length mismatch"
+        );
+    }
+
+    #[test]
+    fn initialization_pattern_check_test() {
+        assert!(
+            initialization_pattern_check(0, 0).is_ok(),
+            "default pattern without logical block size"
+        );
+        assert!(
+            matches!(
+                initialization_pattern_check(4, 0),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "pattern without logical block size"
+        );
+        assert!(
+            initialization_pattern_check(512, 512).is_ok(),
+            "one block pattern"
+        );
+        assert!(
+            matches!(
+                initialization_pattern_check(513, 512),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "pattern longer than a logical block"
+        );
+    }
 }
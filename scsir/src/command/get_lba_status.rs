@@ -1,11 +1,15 @@
 #![allow(dead_code)]
 
-use std::mem::size_of;
+use std::{
+    mem::{self, size_of},
+    time::Duration,
+};
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    data_wrapper::{AnyType, FlexibleStruct},
+    command::{cdb_bytes, checked_allocation_length, issue_full_length, timeout_setter, Control},
+    data_wrapper::{impl_pod, AnyType, FlexibleStruct},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -13,17 +17,26 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct GetLbaStatusCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     command_buffer: CommandBuffer,
     descriptor_length: u32,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommandResult {
     pub total_descripter_length: usize,
     pub lba_status_descriptors: Vec<LbaStatusDescriptor>,
 }
 
+/// The undecoded response, see [`LbaStatus::descriptors`].
 #[derive(Debug)]
+pub struct LbaStatus {
+    data: FlexibleStruct<ParameterHeader, Descriptor>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LbaStatusDescriptor {
     pub logical_block_address: u64,
     pub number_of_logical_blocks: u32,
@@ -31,6 +44,8 @@ pub struct LbaStatusDescriptor {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ProvisioningStatus {
     MappedOrUnknown,
     Deallocated,
@@ -38,10 +53,23 @@ pub enum ProvisioningStatus {
     Other(u8),
 }
 
+impl ProvisioningStatus {
+    // a stable name for logs and serialized output, reserved values are all "other"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MappedOrUnknown => "mapped_or_unknown",
+            Self::Deallocated => "deallocated",
+            Self::Anchored => "anchored",
+            Self::Other(_) => "other",
+        }
+    }
+}
+
 impl<'a> GetLbaStatusCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             descriptor_length: 0,
             command_buffer: CommandBuffer::new()
                 .with_operation_code(OPERATION_CODE)
@@ -55,8 +83,8 @@ impl<'a> GetLbaStatusCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
@@ -66,36 +94,87 @@ impl<'a> GetLbaStatusCommand<'a> {
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<CommandResult> {
-        const MAX_DESCRIPTOR_LENGTH: usize =
-            (u32::MAX as usize - size_of::<ParameterHeader>()) / size_of::<Descriptor>();
-        if self.descriptor_length > MAX_DESCRIPTOR_LENGTH as u32 {
-            return Err(
-                crate::Error::ArgumentOutOfBounds(
-                    format!(
-                        "descriptor length is out of bounds. The maximum possible value is {}, but {} was provided.",
-                        MAX_DESCRIPTOR_LENGTH,
-                        self.descriptor_length)));
-        }
+        let status = self.issue_lba_status()?;
 
+        Ok(parse(
+            status.data.body_as_ref(),
+            status.data.elements_as_slice(),
+        ))
+    }
+
+    // the descriptors are decoded while iterating, without collecting them into a Vec
+    pub fn issue_lba_status(&mut self) -> crate::Result<LbaStatus> {
         let temp = ThisCommand {
-            command_buffer: self.command_buffer.with_allocation_length(
-                size_of::<ParameterHeader>() as u32
-                    + self.descriptor_length * size_of::<Descriptor>() as u32,
-            ),
+            command_buffer: self.command_buffer()?,
             max_descriptor_length: self.descriptor_length,
+            timeout: self.timeout,
         };
 
         self.interface.issue(&temp)
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        let allocation_length = checked_allocation_length(
+            size_of::<ParameterHeader>(),
+            size_of::<Descriptor>(),
+            self.descriptor_length,
+        )?;
+
+        Ok(self
+            .command_buffer
+            .with_allocation_length(allocation_length))
+    }
+
+    // issues again with the descriptor length reported by the parameter data length if the
+    // first response was truncated, the descriptor length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<CommandResult> {
+        let descriptor_length = self.descriptor_length as usize;
+        let status = issue_full_length(
+            self,
+            descriptor_length,
+            (u32::MAX as usize - size_of::<ParameterHeader>()) / size_of::<Descriptor>(),
+            |command, length| {
+                command.descriptor_length(length as u32);
+            },
+            Self::issue_lba_status,
+            LbaStatus::required_descriptor_length,
+        )?;
+
+        Ok(parse(
+            status.data.body_as_ref(),
+            status.data.elements_as_slice(),
+        ))
+    }
+}
+
+impl LbaStatus {
+    pub fn total_descripter_length(&self) -> usize {
+        descriptor_count(self.data.body_as_ref(), self.data.length())
+    }
+
+    /// The descriptor length needed for every descriptor, as the header reports it
+    pub fn required_descriptor_length(&self) -> usize {
+        descriptor_count(self.data.body_as_ref(), usize::MAX)
+    }
+
+    pub fn descriptors(&self) -> impl Iterator<Item = LbaStatusDescriptor> + '_ {
+        descriptors(self.data.body_as_ref(), self.data.elements_as_slice())
+    }
 }
 
 impl Scsi {
-    pub fn get_lba_status(&self) -> GetLbaStatusCommand {
+    pub fn get_lba_status(&self) -> GetLbaStatusCommand<'_> {
         GetLbaStatusCommand::new(self)
     }
 }
 
+crate::command::async_issue!(get_lba_status: GetLbaStatusCommand);
+
 const OPERATION_CODE: u8 = 0x9E;
 const SERVICE_ACTION: u8 = 0x12;
 
@@ -112,24 +191,28 @@ struct CommandBuffer {
 }
 
 #[bitfield]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 struct ParameterHeader {
     parameter_data_length: B32,
     reserved: B32,
 }
 
 #[bitfield]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 struct Descriptor {
     lba_status_logical_block_address: B64,
     number_oflogical_blocks: B32,
-    provisioning_status: B8,
-    reserved: B24,
+    reserved_0: B4,
+    provisioning_status: B4,
+    reserved_1: B24,
 }
 
+impl_pod!(ParameterHeader, Descriptor);
+
 struct ThisCommand {
     command_buffer: CommandBuffer,
     max_descriptor_length: u32,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -139,7 +222,7 @@ impl Command for ThisCommand {
 
     type DataBufferWrapper = FlexibleStruct<ParameterHeader, Descriptor>;
 
-    type ReturnType = crate::Result<CommandResult>;
+    type ReturnType = crate::Result<LbaStatus>;
 
     fn direction(&self) -> DataDirection {
         DataDirection::FromDevice
@@ -150,7 +233,7 @@ impl Command for ThisCommand {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        unsafe { FlexibleStruct::with_length(self.max_descriptor_length as usize) }
+        FlexibleStruct::zeroed(self.max_descriptor_length as usize)
     }
 
     fn data_size(&self) -> u32 {
@@ -158,17 +241,43 @@ impl Command for ThisCommand {
             + size_of::<ParameterHeader>() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        let data = result.data;
-        let length = unsafe { data.body_as_ref() }.parameter_data_length();
-        let length = (length as usize - size_of::<u32>()) / size_of::<Descriptor>();
+        // the buffer is moved out instead of copying the descriptors
+        Ok(LbaStatus {
+            data: mem::replace(result.data, FlexibleStruct::zeroed(0)),
+        })
+    }
+}
+
+fn parse(header: &ParameterHeader, descriptors: &[Descriptor]) -> CommandResult {
+    CommandResult {
+        total_descripter_length: descriptor_count(header, descriptors.len()),
+        lba_status_descriptors: self::descriptors(header, descriptors).collect(),
+    }
+}
 
-        let mut lba_status_descriptors = vec![];
+fn descriptor_count(header: &ParameterHeader, available: usize) -> usize {
+    // the parameter data length does not include itself
+    let length = (header.parameter_data_length() as usize).saturating_sub(size_of::<u32>())
+        / size_of::<Descriptor>();
+
+    usize::min(length, available)
+}
 
-        for item in unsafe { &data.elements_as_slice()[..usize::min(length, data.length())] } {
+fn descriptors<'a>(
+    header: &ParameterHeader,
+    descriptors: &'a [Descriptor],
+) -> impl Iterator<Item = LbaStatusDescriptor> + 'a {
+    descriptors[..descriptor_count(header, descriptors.len())]
+        .iter()
+        .map(|item| {
             let provisioning_status = match item.provisioning_status() {
                 0 => ProvisioningStatus::MappedOrUnknown,
                 1 => ProvisioningStatus::Deallocated,
@@ -176,23 +285,18 @@ impl Command for ThisCommand {
                 other => ProvisioningStatus::Other(other),
             };
 
-            lba_status_descriptors.push(LbaStatusDescriptor {
+            LbaStatusDescriptor {
                 logical_block_address: item.lba_status_logical_block_address(),
                 number_of_logical_blocks: item.number_oflogical_blocks(),
                 provisioning_status,
-            });
-        }
-
-        Ok(CommandResult {
-            total_descripter_length: length,
-            lba_status_descriptors,
+            }
         })
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::counting_allocator::allocations;
     use std::mem::size_of;
 
     const COMMAND_LENGTH: usize = 16;
@@ -219,4 +323,121 @@ mod tests {
             concat!("Size of: ", stringify!(Descriptor))
         );
     }
+
+    #[test]
+    fn parse_test() {
+        let header = ParameterHeader::new().with_parameter_data_length(0x24);
+        let descriptors = [
+            Descriptor::new()
+                .with_lba_status_logical_block_address(0)
+                .with_number_oflogical_blocks(0x800),
+            Descriptor::new()
+                .with_lba_status_logical_block_address(0x800)
+                .with_number_oflogical_blocks(0x1000)
+                .with_provisioning_status(1),
+            Descriptor::new(),
+        ];
+
+        let result = parse(&header, &descriptors);
+        assert_eq!(result.total_descripter_length, 2, "descriptor length");
+        assert_eq!(result.lba_status_descriptors.len(), 2, "descriptors");
+
+        let descriptor = &result.lba_status_descriptors[0];
+        assert_eq!(descriptor.logical_block_address, 0, "logical block address");
+        assert_eq!(descriptor.number_of_logical_blocks, 0x800, "blocks");
+        assert!(
+            matches!(
+                descriptor.provisioning_status,
+                ProvisioningStatus::MappedOrUnknown
+            ),
+            "mapped"
+        );
+
+        let descriptor = &result.lba_status_descriptors[1];
+        assert_eq!(
+            descriptor.logical_block_address, 0x800,
+            "logical block address"
+        );
+        assert_eq!(descriptor.number_of_logical_blocks, 0x1000, "blocks");
+        assert!(
+            matches!(
+                descriptor.provisioning_status,
+                ProvisioningStatus::Deallocated
+            ),
+            "deallocated"
+        );
+        assert_eq!(
+            descriptor.provisioning_status.name(),
+            "deallocated",
+            "provisioning status name"
+        );
+
+        let result = parse(&ParameterHeader::new(), &descriptors);
+        assert!(result.lba_status_descriptors.is_empty(), "empty response");
+    }
+
+    #[test]
+    fn descriptors_test() {
+        // 3 of the 4 descriptors are reported by the parameter data length
+        let mut data = FlexibleStruct::<ParameterHeader, Descriptor>::with_body_capacity(
+            ParameterHeader::new().with_parameter_data_length(4 + 3 * 16),
+            4,
+        );
+        for i in 0..4u8 {
+            data.push(
+                Descriptor::new()
+                    .with_lba_status_logical_block_address(i as u64 * 0x100)
+                    .with_number_oflogical_blocks(0x100)
+                    .with_provisioning_status(i % 2),
+            );
+        }
+        let status = LbaStatus { data };
+
+        let before = allocations();
+        let mut blocks = 0;
+        let mut deallocated = 0;
+        for descriptor in status.descriptors() {
+            blocks += descriptor.number_of_logical_blocks;
+            if let ProvisioningStatus::Deallocated = descriptor.provisioning_status {
+                deallocated += 1;
+            }
+        }
+
+        assert_eq!(allocations(), before, "no allocation while iterating");
+        assert_eq!(status.total_descripter_length(), 3, "descriptor length");
+        assert_eq!(blocks, 0x300, "blocks");
+        assert_eq!(deallocated, 1, "deallocated descriptors");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let header = ParameterHeader::new().with_parameter_data_length(0x14);
+        let descriptors = [Descriptor::new()
+            .with_lba_status_logical_block_address(0x800)
+            .with_number_oflogical_blocks(0x1000)
+            .with_provisioning_status(1)];
+        let result = parse(&header, &descriptors);
+
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(
+            json.contains(r#""provisioning_status":"deallocated""#),
+            "provisioning status name, {}",
+            json
+        );
+
+        let result: CommandResult = serde_json::from_str(&json).unwrap();
+        let descriptor = &result.lba_status_descriptors[0];
+        assert_eq!(
+            descriptor.logical_block_address, 0x800,
+            "logical block address"
+        );
+        assert!(
+            matches!(
+                descriptor.provisioning_status,
+                ProvisioningStatus::Deallocated
+            ),
+            "deallocated"
+        );
+    }
 }
@@ -0,0 +1,338 @@
+#![allow(dead_code)]
+
+use std::{mem::size_of, time::Duration};
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{
+        bitfield_bound_check, cdb_bytes, get_array, issue_full_length, timeout_setter, Control,
+    },
+    data_wrapper::{AnyType, VecBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct GetPhysicalElementStatusCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    filter: u8,
+    report_type: u8,
+    command_buffer: CommandBuffer,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandResult {
+    pub number_of_descriptors: u32,
+    pub number_of_descriptors_returned: u32,
+    pub identifier_of_element_beyond_last_returned: u32,
+    pub descriptors: Vec<PhysicalElementDescriptor>,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhysicalElementDescriptor {
+    pub element_identifier: u32,
+    // 0x01 means storage element
+    pub physical_element_type: u8,
+    // 0x00 not reported, 0x01..=0x63 within limits, 0x64 at the limit, 0x65..=0xCF beyond limits,
+    // 0xFB depopulation error, 0xFC restoration in progress, 0xFD depopulation in progress,
+    // 0xFE depopulation not completed, 0xFF depopulated
+    pub physical_element_health: u8,
+    pub associated_capacity: u64,
+    pub restoration_allowed: bool,
+}
+
+impl<'a> GetPhysicalElementStatusCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            filter: 0,
+            report_type: 0,
+            command_buffer: CommandBuffer::new()
+                .with_operation_code(OPERATION_CODE)
+                .with_service_action(SERVICE_ACTION),
+        }
+    }
+
+    // report elements with an identifier greater than or equal to this
+    pub fn starting_element(&mut self, value: u32) -> &mut Self {
+        self.command_buffer.set_starting_element(value);
+        self
+    }
+
+    pub fn allocation_length(&mut self, value: u32) -> &mut Self {
+        self.command_buffer.set_allocation_length(value);
+        self
+    }
+
+    // filter must be less than 0x04
+    // 0 reports all elements, 1 only the ones outside the health limits or depopulated
+    pub fn filter(&mut self, value: u8) -> &mut Self {
+        self.filter = value;
+        self
+    }
+
+    // report_type must be less than 0x10
+    // 0 reports physical elements, 1 storage elements
+    pub fn report_type(&mut self, value: u8) -> &mut Self {
+        self.report_type = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<CommandResult> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(self.filter, 2, "filter")?;
+        bitfield_bound_check!(self.report_type, 4, "report type")?;
+
+        Ok(self
+            .command_buffer
+            .with_filter(self.filter)
+            .with_report_type(self.report_type))
+    }
+
+    // issues again with the allocation length needed for the number of descriptors reported
+    // by the device if the first response was truncated, the allocation length is left at the
+    // larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<CommandResult> {
+        let allocation_length = self.command_buffer.allocation_length() as usize;
+        issue_full_length(
+            self,
+            allocation_length,
+            u32::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u32);
+            },
+            Self::issue,
+            CommandResult::required_length,
+        )
+    }
+}
+
+impl CommandResult {
+    /// The allocation length needed for every descriptor, as the header reports it
+    pub fn required_length(&self) -> usize {
+        size_of::<ParameterHeader>()
+            + self.number_of_descriptors as usize * size_of::<DescriptorData>()
+    }
+}
+
+impl Scsi {
+    pub fn get_physical_element_status(&self) -> GetPhysicalElementStatusCommand<'_> {
+        GetPhysicalElementStatusCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(get_physical_element_status: GetPhysicalElementStatusCommand);
+
+fn parse(bytes: &[u8]) -> CommandResult {
+    let (array, left) = get_array(bytes);
+    let header = ParameterHeader::from_bytes(array);
+
+    let length = usize::min(
+        header.number_of_descriptors_returned() as usize * size_of::<DescriptorData>(),
+        left.len(),
+    );
+    let descriptors = left[..length]
+        .chunks_exact(size_of::<DescriptorData>())
+        .map(|x| {
+            let (array, _) = get_array(x);
+            let data = DescriptorData::from_bytes(array);
+
+            PhysicalElementDescriptor {
+                element_identifier: data.element_identifier(),
+                physical_element_type: data.physical_element_type(),
+                physical_element_health: data.physical_element_health(),
+                associated_capacity: data.associated_capacity(),
+                restoration_allowed: data.restoration_allowed() != 0,
+            }
+        })
+        .collect();
+
+    CommandResult {
+        number_of_descriptors: header.number_of_descriptors(),
+        number_of_descriptors_returned: header.number_of_descriptors_returned(),
+        identifier_of_element_beyond_last_returned: header
+            .identifier_of_element_beyond_last_returned(),
+        descriptors,
+    }
+}
+
+const OPERATION_CODE: u8 = 0x9E;
+const SERVICE_ACTION: u8 = 0x17;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    reserved_1: B32,
+    starting_element: B32,
+    allocation_length: B32,
+    filter: B2,
+    reserved_2: B2,
+    report_type: B4,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct ParameterHeader {
+    number_of_descriptors: B32,
+    number_of_descriptors_returned: B32,
+    identifier_of_element_beyond_last_returned: B32,
+    reserved_0: B128,
+    reserved_1: B32,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct DescriptorData {
+    reserved_0: B32,
+    element_identifier: B32,
+    reserved_1: B48,
+    physical_element_type: B8,
+    physical_element_health: B8,
+    associated_capacity: B64,
+    reserved_2: B56,
+    reserved_3: B7,
+    restoration_allowed: B1,
+}
+
+struct ThisCommand {
+    command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
+}
+
+impl Command for ThisCommand {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = VecBufferWrapper;
+
+    type ReturnType = crate::Result<CommandResult>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::FromDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        unsafe { VecBufferWrapper::with_len(self.command_buffer.allocation_length() as usize) }
+    }
+
+    fn data_size(&self) -> u32 {
+        self.command_buffer.allocation_length()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        let length = result.data_in_length(result.data().len());
+
+        Ok(parse(&result.data()[..length]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+    const PARAMETER_HEADER_LENGTH: usize = 32;
+    const DESCRIPTOR_LENGTH: usize = 32;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+
+        assert_eq!(
+            size_of::<ParameterHeader>(),
+            PARAMETER_HEADER_LENGTH,
+            concat!("Size of: ", stringify!(ParameterHeader))
+        );
+
+        assert_eq!(
+            size_of::<DescriptorData>(),
+            DESCRIPTOR_LENGTH,
+            concat!("Size of: ", stringify!(DescriptorData))
+        );
+    }
+
+    #[test]
+    fn two_elements_test() {
+        let mut bytes = vec![0u8; PARAMETER_HEADER_LENGTH + 2 * DESCRIPTOR_LENGTH];
+        bytes[..4].copy_from_slice(&2u32.to_be_bytes());
+        bytes[4..8].copy_from_slice(&2u32.to_be_bytes());
+        bytes[8..12].copy_from_slice(&3u32.to_be_bytes());
+
+        let element = &mut bytes[PARAMETER_HEADER_LENGTH..];
+        element[4..8].copy_from_slice(&1u32.to_be_bytes());
+        element[14] = 0x01;
+        element[15] = 0x32;
+        element[16..24].copy_from_slice(&0x1000_0000u64.to_be_bytes());
+
+        let element = &mut bytes[PARAMETER_HEADER_LENGTH + DESCRIPTOR_LENGTH..];
+        element[4..8].copy_from_slice(&2u32.to_be_bytes());
+        element[14] = 0x01;
+        element[15] = 0xFF;
+        element[16..24].copy_from_slice(&0x1000_0000u64.to_be_bytes());
+        element[31] = 0x01;
+
+        let result = parse(&bytes);
+        assert_eq!(result.number_of_descriptors, 2, "number of descriptors");
+        assert_eq!(
+            result.identifier_of_element_beyond_last_returned, 3,
+            "identifier of element beyond last returned"
+        );
+        assert_eq!(result.descriptors.len(), 2, "descriptors");
+
+        let element = &result.descriptors[0];
+        assert_eq!(element.element_identifier, 1, "element identifier");
+        assert_eq!(element.physical_element_type, 0x01, "element type");
+        assert_eq!(element.physical_element_health, 0x32, "element health");
+        assert_eq!(
+            element.associated_capacity, 0x1000_0000,
+            "associated capacity"
+        );
+        assert!(!element.restoration_allowed, "restoration allowed");
+
+        let element = &result.descriptors[1];
+        assert_eq!(element.element_identifier, 2, "element identifier");
+        assert_eq!(element.physical_element_health, 0xFF, "depopulated");
+        assert!(element.restoration_allowed, "restoration allowed");
+    }
+}
@@ -1,86 +1,153 @@
 #![allow(dead_code)]
 
-use std::mem::size_of;
+use std::{
+    mem::{self, size_of},
+    time::Duration,
+};
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    data_wrapper::{AnyType, FlexibleStruct},
+    command::{cdb_bytes, checked_allocation_length, define_command, issue_full_length, truncated},
+    data_wrapper::{impl_pod, AnyType, FlexibleStruct},
     result_data::ResultData,
-    Command, DataDirection, Scsi,
 };
 
-#[derive(Clone, Debug)]
-pub struct GetStreamStatusCommand<'a> {
-    interface: &'a Scsi,
-    command_buffer: CommandBuffer,
-    descriptor_length: u32,
+define_command! {
+    pub struct GetStreamStatusCommand<'a>(get_stream_status) {
+        command_buffer: CommandBuffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_service_action(SERVICE_ACTION),
+        setters: {
+            starting_stream_identifier: u16 => set_starting_stream_identifier,
+        },
+        fields: {
+            // descriptor length must be less than 268435455(0xFFF_FFFF), which is (0xFFFF_FFFF - 8) / 16
+            descriptor_length: u32 = 0,
+        },
+    }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommandResult {
+    /// the descriptor length needed for every descriptor, see `truncated`
     pub total_descripter_length: usize,
     pub number_of_open_streams: u16,
     pub stream_identifiers: Vec<u16>,
+    /// true if the device has more descriptors than the descriptor length had room for
+    pub truncated: bool,
 }
 
-impl<'a> GetStreamStatusCommand<'a> {
-    fn new(interface: &'a Scsi) -> Self {
-        Self {
-            interface,
-            descriptor_length: 0,
-            command_buffer: CommandBuffer::new()
-                .with_operation_code(OPERATION_CODE)
-                .with_service_action(SERVICE_ACTION),
-        }
-    }
+/// The undecoded response, see [`StreamStatus::stream_identifiers`].
+#[derive(Debug)]
+pub struct StreamStatus {
+    data: FlexibleStruct<ParameterHeader, Descriptor>,
+    max_descriptor_length: usize,
+}
+
+impl GetStreamStatusCommand<'_> {
+    pub fn issue(&mut self) -> crate::Result<CommandResult> {
+        let status = self.issue_stream_status()?;
 
-    pub fn starting_stream_identifier(&mut self, value: u16) -> &mut Self {
-        self.command_buffer.set_starting_stream_identifier(value);
-        self
+        Ok(CommandResult {
+            total_descripter_length: status.total_descripter_length(),
+            number_of_open_streams: status.number_of_open_streams(),
+            stream_identifiers: status.stream_identifiers().collect(),
+            truncated: status.truncated(),
+        })
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
-        self
+    // issues again with the descriptor length reported by the device if the first response
+    // was truncated, the descriptor length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<CommandResult> {
+        let descriptor_length = self.descriptor_length as usize;
+        // the descriptor length is 32 bits, more descriptors don't fit in one transfer anyway
+        issue_full_length(
+            self,
+            descriptor_length,
+            u32::MAX as usize,
+            |command, length| {
+                command.descriptor_length(length as u32);
+            },
+            Self::issue,
+            |result| result.total_descripter_length,
+        )
     }
 
-    // descriptor length must be less than 268435455(0xFFF_FFFF), which is (0xFFFF_FFFF - 8) / 16
-    pub fn descriptor_length(&mut self, value: u32) -> &mut Self {
-        self.descriptor_length = value;
-        self
+    // the stream identifiers are decoded while iterating, without collecting them into a Vec
+    pub fn issue_stream_status(&mut self) -> crate::Result<StreamStatus> {
+        let temp = self.this_command()?;
+        self.interface.issue(&temp)
     }
 
-    pub fn issue(&mut self) -> crate::Result<CommandResult> {
-        const MAX_DESCRIPTOR_LENGTH: usize =
-            (u32::MAX as usize - size_of::<ParameterHeader>()) / size_of::<Descriptor>();
-        if self.descriptor_length > MAX_DESCRIPTOR_LENGTH as u32 {
-            return Err(
-                crate::Error::ArgumentOutOfBounds(
-                    format!(
-                        "descriptor length is out of bounds. The maximum possible value is {}, but {} was provided.",
-                        MAX_DESCRIPTOR_LENGTH,
-                        self.descriptor_length)));
-        }
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
 
-        let temp = ThisCommand {
-            command_buffer: self.command_buffer.with_allocation_length(
-                size_of::<ParameterHeader>() as u32
-                    + self.descriptor_length * size_of::<Descriptor>() as u32,
-            ),
+    fn this_command(&self) -> crate::Result<ThisCommand> {
+        Ok(ThisCommand {
+            command_buffer: self.command_buffer()?,
             max_descriptor_length: self.descriptor_length,
-        };
+            timeout: self.timeout,
+        })
+    }
 
-        self.interface.issue(&temp)
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        let allocation_length = checked_allocation_length(
+            size_of::<ParameterHeader>(),
+            size_of::<Descriptor>(),
+            self.descriptor_length,
+        )?;
+
+        Ok(self
+            .command_buffer
+            .with_allocation_length(allocation_length))
     }
 }
 
-impl Scsi {
-    pub fn get_stream_status(&self) -> GetStreamStatusCommand {
-        GetStreamStatusCommand::new(self)
+impl StreamStatus {
+    // keeps the descriptors that were both transferred and reported by the header
+    fn new(
+        mut data: FlexibleStruct<ParameterHeader, Descriptor>,
+        max_descriptor_length: usize,
+        transfered_data_length: usize,
+    ) -> Self {
+        data.truncate_to_transfered(transfered_data_length);
+        data.truncate(total_descripter_length(data.body_as_ref()));
+
+        Self {
+            data,
+            max_descriptor_length,
+        }
+    }
+
+    // the header reports more descriptors than were allocated, see
+    // GetStreamStatusCommand::issue_full_length
+    pub fn truncated(&self) -> bool {
+        truncated(self.total_descripter_length(), self.max_descriptor_length)
+    }
+
+    pub fn total_descripter_length(&self) -> usize {
+        total_descripter_length(self.data.body_as_ref())
+    }
+
+    pub fn number_of_open_streams(&self) -> u16 {
+        self.data.body_as_ref().number_of_open_streams()
+    }
+
+    pub fn stream_identifiers(&self) -> impl Iterator<Item = u16> + '_ {
+        self.data
+            .elements_as_slice()
+            .iter()
+            .map(|item| item.stream_identifier())
     }
 }
 
+fn total_descripter_length(header: &ParameterHeader) -> usize {
+    // a length too short for the header itself means no descriptors
+    (header.parameter_data_length() as usize).saturating_sub(size_of::<ParameterHeader>())
+        / size_of::<Descriptor>()
+}
+
 const OPERATION_CODE: u8 = 0x9E;
 const SERVICE_ACTION: u8 = 0x16;
 
@@ -99,7 +166,7 @@ struct CommandBuffer {
 }
 
 #[bitfield]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 struct ParameterHeader {
     parameter_data_length: B32,
     reserved: B16,
@@ -107,62 +174,48 @@ struct ParameterHeader {
 }
 
 #[bitfield]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 struct Descriptor {
     reserved_0: B16,
     stream_identifier: B16,
     reserved_1: B32,
 }
 
+impl_pod!(ParameterHeader, Descriptor);
+
 struct ThisCommand {
     command_buffer: CommandBuffer,
     max_descriptor_length: u32,
+    timeout: Option<Duration>,
 }
 
-impl Command for ThisCommand {
-    type CommandBuffer = CommandBuffer;
-
-    type DataBuffer = AnyType;
+define_command! {
+    impl Command for ThisCommand(CommandBuffer, FromDevice) {
+        type DataBuffer = AnyType;
 
-    type DataBufferWrapper = FlexibleStruct<ParameterHeader, Descriptor>;
+        type DataBufferWrapper = FlexibleStruct<ParameterHeader, Descriptor>;
 
-    type ReturnType = crate::Result<CommandResult>;
+        type ReturnType = crate::Result<StreamStatus>;
 
-    fn direction(&self) -> DataDirection {
-        DataDirection::FromDevice
-    }
-
-    fn command(&self) -> Self::CommandBuffer {
-        self.command_buffer
-    }
-
-    fn data(&self) -> Self::DataBufferWrapper {
-        unsafe { FlexibleStruct::with_length(self.max_descriptor_length as usize) }
-    }
-
-    fn data_size(&self) -> u32 {
-        self.max_descriptor_length * size_of::<Descriptor>() as u32
-            + size_of::<ParameterHeader>() as u32
-    }
+        fn data(&self) -> Self::DataBufferWrapper {
+            FlexibleStruct::zeroed(self.max_descriptor_length as usize)
+        }
 
-    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
-        result.check_ioctl_error()?;
-        result.check_common_error()?;
+        fn data_size(&self) -> u32 {
+            self.max_descriptor_length * size_of::<Descriptor>() as u32
+                + size_of::<ParameterHeader>() as u32
+        }
 
-        let data = result.data;
-        let length = unsafe { data.body_as_ref() }.parameter_data_length();
-        let length = (length as usize - size_of::<u64>()) / size_of::<Descriptor>();
+        fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+            result.check_ioctl_error()?;
+            result.check_common_error()?;
 
-        let mut stream_identifiers = vec![];
-        for item in unsafe { &data.elements_as_slice()[..usize::min(length, data.length())] } {
-            stream_identifiers.push(item.stream_identifier());
+            Ok(StreamStatus::new(
+                mem::replace(result.data, FlexibleStruct::zeroed(0)),
+                self.max_descriptor_length as usize,
+                result.data_in_length(self.data_size() as usize),
+            ))
         }
-
-        Ok(CommandResult {
-            total_descripter_length: length,
-            number_of_open_streams: unsafe { data.body_as_ref() }.number_of_open_streams(),
-            stream_identifiers,
-        })
     }
 }
 
@@ -195,4 +248,219 @@ mod tests {
             concat!("Size of: ", stringify!(Descriptor))
         );
     }
+
+    #[test]
+    fn stream_identifiers_test() {
+        let mut data = FlexibleStruct::<ParameterHeader, Descriptor>::with_body_capacity(
+            ParameterHeader::new()
+                .with_parameter_data_length(8 + 2 * 8)
+                .with_number_of_open_streams(2),
+            3,
+        );
+        for stream_identifier in [0x0001, 0x0004, 0x0000] {
+            data.push(Descriptor::new().with_stream_identifier(stream_identifier));
+        }
+        let transfered_data_length = data.total_size();
+        let status = StreamStatus::new(data, 3, transfered_data_length);
+
+        assert_eq!(status.total_descripter_length(), 2, "descriptor length");
+        assert!(!status.truncated(), "not truncated");
+        assert_eq!(status.number_of_open_streams(), 2, "open streams");
+        assert!(
+            status.stream_identifiers().eq([0x0001, 0x0004]),
+            "stream identifiers"
+        );
+    }
+
+    #[test]
+    fn short_parameter_data_length_test() {
+        for parameter_data_length in [0, 4, 7] {
+            let mut data = FlexibleStruct::<ParameterHeader, Descriptor>::with_body_capacity(
+                ParameterHeader::new().with_parameter_data_length(parameter_data_length),
+                1,
+            );
+            data.push(Descriptor::new().with_stream_identifier(0x0001));
+            let transfered_data_length = data.total_size();
+            let status = StreamStatus::new(data, 1, transfered_data_length);
+
+            assert_eq!(
+                status.total_descripter_length(),
+                0,
+                "descriptor length of parameter data length {}",
+                parameter_data_length
+            );
+            assert_eq!(
+                status.stream_identifiers().count(),
+                0,
+                "stream identifiers of parameter data length {}",
+                parameter_data_length
+            );
+        }
+    }
+
+    #[test]
+    fn cdb_bytes_test() {
+        use crate::{command::Control, Scsi};
+
+        let scsi = Scsi::mock(crate::MockScsi::new());
+        assert_eq!(
+            scsi.get_stream_status()
+                .starting_stream_identifier(0x1234)
+                .descriptor_length(4)
+                .control(Control::new().naca(true))
+                .cdb_bytes()
+                .unwrap(),
+            [
+                0x9E, 0x16, 0x00, 0x00, 0x12, 0x34, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x28,
+                0x00, 0x04,
+            ],
+            "GET STREAM STATUS"
+        );
+    }
+
+    #[test]
+    fn mock_test() {
+        use crate::{MockResponse, MockScsi, Scsi};
+
+        let mock = MockScsi::new();
+        mock.expect(
+            &[
+                0x9E, 0x16, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18,
+                0x00, 0x00,
+            ],
+            MockResponse::good().data(&[
+                0x00, 0x00, 0x00, 0x18, 0x00, 0x00, 0x00, 0x02, // header
+                0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, // stream 1
+                0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, // stream 4
+            ]),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let result = scsi
+            .get_stream_status()
+            .starting_stream_identifier(1)
+            .descriptor_length(2)
+            .issue()
+            .unwrap();
+
+        mock.verify();
+        assert_eq!(result.number_of_open_streams, 2, "open streams");
+        assert_eq!(
+            result.stream_identifiers,
+            [0x0001, 0x0004],
+            "stream identifiers"
+        );
+        assert_eq!(
+            mock.issued()[0].direction,
+            crate::DataDirection::FromDevice,
+            "data direction"
+        );
+    }
+
+    #[test]
+    fn short_transfer_test() {
+        use crate::{MockResponse, MockScsi, Scsi};
+
+        // room for four descriptors, the device returns one and reports three
+        let mock = MockScsi::new();
+        mock.expect(
+            &[
+                0x9E, 0x16, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x28,
+                0x00, 0x00,
+            ],
+            MockResponse::good().data(&[
+                0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x03, // header
+                0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00, 0x00, // stream 7
+                0x00, 0x00, 0x00, // part of a descriptor
+            ]),
+        );
+
+        let scsi = Scsi::mock(mock);
+        let status = scsi
+            .get_stream_status()
+            .descriptor_length(4)
+            .issue_stream_status()
+            .unwrap();
+
+        assert_eq!(status.total_descripter_length(), 3, "reported descriptors");
+        assert!(
+            status.stream_identifiers().eq([0x0007]),
+            "transferred descriptors only"
+        );
+        assert!(!status.truncated(), "room for every reported descriptor");
+    }
+
+    #[test]
+    fn truncated_test() {
+        use crate::{MockResponse, MockScsi, Scsi};
+
+        // two open streams, a header and three descriptors in all
+        let response = [
+            0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x02, // header
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, // stream 1
+            0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, // stream 2
+            0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, // stream 3
+        ];
+
+        let one_descriptor = [
+            0x9E, 0x16, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+            0x00, 0x00,
+        ];
+        let three_descriptors = [
+            0x9E, 0x16, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+            0x00, 0x00,
+        ];
+
+        let mock = MockScsi::new();
+        mock.expect(&one_descriptor, MockResponse::good().data(&response))
+            .expect(&one_descriptor, MockResponse::good().data(&response))
+            .expect(&three_descriptors, MockResponse::good().data(&response));
+
+        let scsi = Scsi::mock(mock.clone());
+        let mut command = scsi.get_stream_status();
+        let result = command.descriptor_length(1).issue().unwrap();
+        assert!(result.truncated, "undersized allocation");
+        assert_eq!(
+            result.total_descripter_length, 3,
+            "needed descriptor length"
+        );
+        assert_eq!(result.stream_identifiers, [0x0001], "descriptors that fit");
+
+        let result = command.descriptor_length(1).issue_full_length().unwrap();
+        assert!(!result.truncated, "full length");
+        assert_eq!(
+            result.stream_identifiers,
+            [0x0001, 0x0002, 0x0003],
+            "every descriptor"
+        );
+        mock.verify();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let result = CommandResult {
+            total_descripter_length: 2,
+            number_of_open_streams: 2,
+            stream_identifiers: vec![0x0001, 0x0004],
+            truncated: false,
+        };
+
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(
+            json,
+            r#"{"total_descripter_length":2,"number_of_open_streams":2,"stream_identifiers":[1,4],"truncated":false}"#,
+            "serialized"
+        );
+
+        let result: CommandResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(result.total_descripter_length, 2, "descriptor length");
+        assert_eq!(result.number_of_open_streams, 2, "open streams");
+        assert_eq!(
+            result.stream_identifiers,
+            [0x0001, 0x0004],
+            "stream identifiers"
+        );
+        assert!(!result.truncated, "not truncated");
+    }
 }
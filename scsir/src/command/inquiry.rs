@@ -3,11 +3,13 @@
 use std::{
     marker::PhantomData,
     mem::{size_of, MaybeUninit},
+    time::Duration,
 };
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
+    command::{cdb_bytes, issue_full_length, page_required_length, timeout_setter},
     data_wrapper::{AnyType, FlexibleStruct},
     result_data::ResultData,
     Command, DataDirection, Scsi,
@@ -16,6 +18,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct InquiryCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     command_buffer: CommandBuffer,
 }
 
@@ -23,6 +26,7 @@ impl<'a> InquiryCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
         }
     }
@@ -39,11 +43,37 @@ impl<'a> InquiryCommand<'a> {
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<Vec<u8>> {
         let result: FlexibleStruct<(), u8> =
             self.issue_flex(self.command_buffer.allocation_length().into())?;
 
-        unsafe { Ok(result.elements_as_slice().to_vec()) }
+        Ok(result.elements_as_slice().to_vec())
+    }
+
+    // issues again with the allocation length reported by the additional length of the standard
+    // data, or the page length of a VPD page, if the first response was truncated, the
+    // allocation length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<Vec<u8>> {
+        let allocation_length = self.command_buffer.allocation_length() as usize;
+        let vital_product_data = self.command_buffer.enable_vital_product_data() != 0;
+        issue_full_length(
+            self,
+            allocation_length,
+            u16::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u16);
+            },
+            Self::issue,
+            |data| {
+                if vital_product_data {
+                    page_required_length(data)
+                } else {
+                    data.get(4).map_or(0, |&length| length as usize + 5)
+                }
+            },
+        )
     }
 
     pub fn issue_generic<Body: Copy, Element: Copy>(
@@ -62,6 +92,14 @@ impl<'a> InquiryCommand<'a> {
         &self,
         element_length: usize,
     ) -> crate::Result<FlexibleStruct<B, E>> {
+        Ok(self.issue_flex_transfered(element_length)?.0)
+    }
+
+    // also returns how many bytes the device transferred, the rest of the data is zeroed
+    pub(crate) fn issue_flex_transfered<B: Copy, E: Copy>(
+        &self,
+        element_length: usize,
+    ) -> crate::Result<(FlexibleStruct<B, E>, usize)> {
         let max_element = (u16::MAX as usize - size_of::<B>()) / usize::max(size_of::<E>(), 1);
         if element_length > max_element {
             return Err(
@@ -73,21 +111,30 @@ impl<'a> InquiryCommand<'a> {
         }
 
         let this_command: ThisCommand<B, E> = ThisCommand {
-            command_buffer: self.command_buffer,
+            command_buffer: self.command_buffer()?,
             element_length,
             phantom_data: PhantomData,
+            timeout: self.timeout,
         };
 
         self.interface.issue(&this_command)
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        Ok(self.command_buffer)
+    }
 }
 
 impl Scsi {
-    pub fn inquiry(&self) -> InquiryCommand {
+    pub fn inquiry(&self) -> InquiryCommand<'_> {
         InquiryCommand::new(self)
     }
 }
 
+crate::command::async_issue!(inquiry: InquiryCommand);
+
 const OPERATION_CODE: u8 = 0x12;
 
 #[bitfield]
@@ -107,6 +154,7 @@ struct ThisCommand<Body, Element> {
     element_length: usize,
 
     phantom_data: PhantomData<(Body, Element)>,
+    timeout: Option<Duration>,
 }
 
 impl<Body: Copy, Element: Copy> Command for ThisCommand<Body, Element> {
@@ -116,7 +164,7 @@ impl<Body: Copy, Element: Copy> Command for ThisCommand<Body, Element> {
 
     type DataBufferWrapper = FlexibleStruct<Body, Element>;
 
-    type ReturnType = crate::Result<FlexibleStruct<Body, Element>>;
+    type ReturnType = crate::Result<(FlexibleStruct<Body, Element>, usize)>;
 
     fn direction(&self) -> DataDirection {
         DataDirection::FromDevice
@@ -135,11 +183,16 @@ impl<Body: Copy, Element: Copy> Command for ThisCommand<Body, Element> {
         (size_of::<Body>() + self.element_length * size_of::<Element>()) as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        Ok(result.data.clone())
+        let transfered = (self.data_size() as usize).saturating_sub(result.residual);
+        Ok((result.data.clone(), transfered))
     }
 }
 
@@ -158,4 +211,49 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn full_length_test() {
+        use crate::{MockResponse, MockScsi};
+
+        // the unit serial number page, 8 bytes of serial number after the header
+        let page = [
+            0x00, 0x80, 0x00, 0x08, b'S', b'N', b'0', b'0', b'0', b'0', b'0', b'1',
+        ];
+        // the standard data, with an additional length of 31
+        let mut standard = vec![0x00; 36];
+        standard[4] = 0x1F;
+
+        let mock = MockScsi::new();
+        mock.expect(
+            &[0x12, 0x01, 0x80, 0x00, 0x04, 0x00],
+            MockResponse::good().data(&page[..4]),
+        )
+        .expect(
+            &[0x12, 0x01, 0x80, 0x00, 0x0C, 0x00],
+            MockResponse::good().data(&page),
+        )
+        .expect(
+            &[0x12, 0x00, 0x00, 0x00, 0x24, 0x00],
+            MockResponse::good().data(&standard),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let data = scsi
+            .inquiry()
+            .page_code(Some(0x80))
+            .allocation_length(4)
+            .issue_full_length()
+            .unwrap();
+        assert_eq!(data, page, "whole page");
+
+        // the standard data fits, only one INQUIRY is issued
+        let data = scsi
+            .inquiry()
+            .allocation_length(36)
+            .issue_full_length()
+            .unwrap();
+        assert_eq!(data.len(), 36, "standard data");
+        mock.verify();
+    }
 }
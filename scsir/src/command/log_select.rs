@@ -1,12 +1,12 @@
 #![allow(dead_code)]
 
-use std::{mem::size_of, slice};
+use std::{mem::size_of, slice, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -14,6 +14,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct LogSelectCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     page_control: u8,
     page_code: u8,
     command_buffer: CommandBuffer,
@@ -24,6 +25,7 @@ impl<'a> LogSelectCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
             page_control: 0,
             page_code: 0,
@@ -58,8 +60,8 @@ impl<'a> LogSelectCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
@@ -70,18 +72,13 @@ impl<'a> LogSelectCommand<'a> {
         self
     }
 
-    pub fn issue(&mut self) -> crate::Result<()> {
-        bitfield_bound_check!(self.page_control, 2, "page control")?;
-        bitfield_bound_check!(self.page_code, 6, "page code")?;
-        bitfield_bound_check!(self.data_buffer.len(), 16, "parameter list length")?;
+    timeout_setter!();
 
+    pub fn issue(&mut self) -> crate::Result<()> {
         let temp = ThisCommand {
-            command_buffer: self
-                .command_buffer
-                .with_page_control(self.page_control)
-                .with_page_code(self.page_code)
-                .with_parameter_list_length(self.data_buffer.len() as u16),
-            parameter: self.data_buffer.clone().into(),
+            command_buffer: self.command_buffer()?,
+            parameter: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         };
 
         self.interface.issue(&temp)?;
@@ -89,6 +86,26 @@ impl<'a> LogSelectCommand<'a> {
         Ok(())
     }
 
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(self.page_control, 2, "page control")?;
+        bitfield_bound_check!(self.page_code, 6, "page code")?;
+        bitfield_bound_check!(self.data_buffer.len(), 16, "parameter list length")?;
+
+        if self.command_buffer.parameter_code_reset() != 0 && !self.data_buffer.is_empty() {
+            return Err(crate::Error::BadArgument(
+                "parameter list is not allowed with parameter code reset".to_owned(),
+            ));
+        }
+
+        Ok(self
+            .command_buffer
+            .with_page_control(self.page_control)
+            .with_page_code(self.page_code)
+            .with_parameter_list_length(self.data_buffer.len() as u16))
+    }
+
     pub fn issue_generic<T: Copy>(&mut self, parameter: T) -> crate::Result<()> {
         let u8_slice: &[u8] =
             unsafe { slice::from_raw_parts(&parameter as *const _ as *const _, size_of::<T>()) };
@@ -99,11 +116,13 @@ impl<'a> LogSelectCommand<'a> {
 }
 
 impl Scsi {
-    pub fn log_select(&self) -> LogSelectCommand {
+    pub fn log_select(&self) -> LogSelectCommand<'_> {
         LogSelectCommand::new(self)
     }
 }
 
+crate::command::async_issue!(log_select: LogSelectCommand);
+
 const OPERATION_CODE: u8 = 0x4C;
 
 #[bitfield]
@@ -121,22 +140,27 @@ struct CommandBuffer {
     control: B8,
 }
 
-struct ThisCommand {
+struct ThisCommand<'a> {
     command_buffer: CommandBuffer,
-    parameter: VecBufferWrapper,
+    parameter: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl Command for ThisCommand {
+impl<'a> Command for ThisCommand<'a> {
     type CommandBuffer = CommandBuffer;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
     fn direction(&self) -> DataDirection {
-        DataDirection::ToDevice
+        if self.parameter.is_empty() {
+            DataDirection::None
+        } else {
+            DataDirection::ToDevice
+        }
     }
 
     fn command(&self) -> Self::CommandBuffer {
@@ -144,13 +168,17 @@ impl Command for ThisCommand {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.parameter.clone()
+        self.parameter
     }
 
     fn data_size(&self) -> u32 {
         self.parameter.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -174,4 +202,45 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn direction_test() {
+        let command = ThisCommand {
+            command_buffer: CommandBuffer::new().with_parameter_code_reset(1),
+            parameter: SliceBufferWrapper(&[]),
+            timeout: None,
+        };
+        assert!(
+            matches!(command.direction(), DataDirection::None),
+            "reset without parameter list"
+        );
+        assert_eq!(command.data_size(), 0, "data size");
+
+        let command = ThisCommand {
+            command_buffer: CommandBuffer::new().with_parameter_list_length(4),
+            parameter: SliceBufferWrapper(&[0x0D, 0x00, 0x00, 0x00]),
+            timeout: None,
+        };
+        assert!(
+            matches!(command.direction(), DataDirection::ToDevice),
+            "parameter list"
+        );
+        assert_eq!(command.data_size(), 4, "data size");
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.log_select();
+        command
+            .page_control(0b01)
+            .page_code(0x0D)
+            .parameter(&[0x0D, 0x00, 0x00, 0x00]);
+
+        let before = allocations();
+        command.issue().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
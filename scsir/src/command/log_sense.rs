@@ -3,12 +3,16 @@
 use std::{
     marker::PhantomData,
     mem::{size_of, MaybeUninit},
+    time::Duration,
 };
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
+    command::{
+        bitfield_bound_check, cdb_bytes, issue_full_length, page_required_length, timeout_setter,
+        Control,
+    },
     data_wrapper::{AnyType, FlexibleStruct},
     result_data::ResultData,
     Command, DataDirection, Scsi,
@@ -17,6 +21,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct LogSenseCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     page_control: u8,
     page_code: u8,
     command_buffer: CommandBuffer,
@@ -26,6 +31,7 @@ impl<'a> LogSenseCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
             page_control: 0,
             page_code: 0,
@@ -64,16 +70,34 @@ impl<'a> LogSenseCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<Vec<u8>> {
         let result: FlexibleStruct<(), u8> =
             self.issue_flex(self.command_buffer.allocation_length() as usize)?;
 
-        unsafe { Ok(result.elements_as_slice().to_vec()) }
+        Ok(result.elements_as_slice().to_vec())
+    }
+
+    // issues again with the allocation length reported by the page length if the first
+    // response was truncated, the allocation length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<Vec<u8>> {
+        let allocation_length = self.command_buffer.allocation_length() as usize;
+        issue_full_length(
+            self,
+            allocation_length,
+            u16::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u16);
+            },
+            Self::issue,
+            |data| page_required_length(data),
+        )
     }
 
     pub fn issue_generic<Body: Copy, Element: Copy>(
@@ -102,28 +126,37 @@ impl<'a> LogSenseCommand<'a> {
                         element_length)));
         }
 
-        bitfield_bound_check!(self.page_control, 2, "page control")?;
-        bitfield_bound_check!(self.page_code, 6, "page code")?;
-
         let temp = ThisCommand {
-            command_buffer: self
-                .command_buffer
-                .with_page_control(self.page_control)
-                .with_page_code(self.page_code),
+            command_buffer: self.command_buffer()?,
             element_length,
             phantom_data: PhantomData,
+            timeout: self.timeout,
         };
 
         self.interface.issue(&temp)
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(self.page_control, 2, "page control")?;
+        bitfield_bound_check!(self.page_code, 6, "page code")?;
+
+        Ok(self
+            .command_buffer
+            .with_page_control(self.page_control)
+            .with_page_code(self.page_code))
+    }
 }
 
 impl Scsi {
-    pub fn log_sense(&self) -> LogSenseCommand {
+    pub fn log_sense(&self) -> LogSenseCommand<'_> {
         LogSenseCommand::new(self)
     }
 }
 
+crate::command::async_issue!(log_sense: LogSenseCommand);
+
 const OPERATION_CODE: u8 = 0x4D;
 
 #[bitfield]
@@ -147,6 +180,7 @@ struct ThisCommand<Body, Element> {
     element_length: usize,
 
     phantom_data: PhantomData<(Body, Element)>,
+    timeout: Option<Duration>,
 }
 
 impl<Body: Copy, Element: Copy> Command for ThisCommand<Body, Element> {
@@ -175,6 +209,10 @@ impl<Body: Copy, Element: Copy> Command for ThisCommand<Body, Element> {
         (size_of::<Body>() + self.element_length * size_of::<Element>()) as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
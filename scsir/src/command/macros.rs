@@ -0,0 +1,220 @@
+/// Generates the scaffolding every command module repeats.
+///
+/// The first form declares the builder: a struct holding the [`crate::Scsi`] handle, the
+/// timeout override and the command buffer, `new` filling in the operation code and service
+/// action, one setter per command buffer field, one field with a setter per extra argument,
+/// the `control` and `timeout` setters and the method on [`crate::Scsi`] that starts the
+/// builder. The issue methods go in an `impl` block of their own.
+///
+/// ```ignore
+/// define_command! {
+///     pub struct GetStreamStatusCommand<'a>(get_stream_status) {
+///         command_buffer: CommandBuffer = CommandBuffer::new()
+///             .with_operation_code(OPERATION_CODE)
+///             .with_service_action(SERVICE_ACTION),
+///         setters: {
+///             starting_stream_identifier: u16 => set_starting_stream_identifier,
+///         },
+///         fields: {
+///             descriptor_length: u32 = 0,
+///         },
+///     }
+/// }
+/// ```
+///
+/// The second form implements [`crate::Command`] for a `ThisCommand` holding a
+/// `command_buffer` and the `timeout` of the builder, filling in `CommandBuffer`, `direction`,
+/// `command` and `timeout`. The data buffer types, `data`, `data_size` and `process_result`
+/// stay hand-written.
+///
+/// ```ignore
+/// define_command! {
+///     impl Command for ThisCommand(CommandBuffer, FromDevice) {
+///         type DataBuffer = AnyType;
+///         ...
+///     }
+/// }
+/// ```
+// only the stream commands use it so far, which may be compiled out
+#[allow(unused_macros)]
+macro_rules! define_command {
+    (
+        $( #[$meta:meta] )*
+        pub struct $name:ident<'a>($scsi_method:ident) {
+            command_buffer: $command_buffer:ty = $new_command_buffer:expr,
+            setters: {
+                $( $( #[$setter_meta:meta] )* $setter:ident: $setter_type:ty => $set:ident ),* $(,)?
+            },
+            fields: {
+                $( $( #[$field_meta:meta] )* $field:ident: $field_type:ty = $default:expr ),* $(,)?
+            } $(,)?
+        }
+    ) => {
+        $( #[$meta] )*
+        #[derive(Clone, Debug)]
+        pub struct $name<'a> {
+            interface: &'a crate::Scsi,
+            timeout: Option<std::time::Duration>,
+            command_buffer: $command_buffer,
+            $( $field: $field_type, )*
+        }
+
+        impl<'a> $name<'a> {
+            fn new(interface: &'a crate::Scsi) -> Self {
+                Self {
+                    interface,
+                    timeout: None,
+                    command_buffer: $new_command_buffer,
+                    $( $field: $default, )*
+                }
+            }
+
+            $(
+                $( #[$setter_meta] )*
+                pub fn $setter(&mut self, value: $setter_type) -> &mut Self {
+                    self.command_buffer.$set(value);
+                    self
+                }
+            )*
+
+            $(
+                $( #[$field_meta] )*
+                pub fn $field(&mut self, value: $field_type) -> &mut Self {
+                    self.$field = value;
+                    self
+                }
+            )*
+
+            pub fn control(&mut self, value: impl Into<crate::command::Control>) -> &mut Self {
+                self.command_buffer.set_control(value.into().bits());
+                self
+            }
+
+            crate::command::timeout_setter!();
+        }
+
+        impl crate::Scsi {
+            pub fn $scsi_method(&self) -> $name<'_> {
+                $name::new(self)
+            }
+        }
+
+        crate::command::async_issue!($scsi_method: $name);
+    };
+
+    (
+        impl Command for $this:ident($command_buffer:ty, $direction:ident) {
+            $( $item:item )*
+        }
+    ) => {
+        impl crate::Command for $this {
+            type CommandBuffer = $command_buffer;
+
+            fn direction(&self) -> crate::DataDirection {
+                crate::DataDirection::$direction
+            }
+
+            fn command(&self) -> Self::CommandBuffer {
+                self.command_buffer
+            }
+
+            fn timeout(&self) -> Option<std::time::Duration> {
+                self.timeout
+            }
+
+            $( $item )*
+        }
+    };
+}
+
+pub(crate) use define_command;
+
+/// The `timeout` setter of a builder, for an `impl` block of a struct with a
+/// `timeout: Option<Duration>` field that its [`crate::Command`] returns from
+/// [`crate::Command::timeout`].
+macro_rules! timeout_setter {
+    () => {
+        /// Overrides the timeout of the [`crate::Scsi`] handle for this command, see
+        /// [`crate::Scsi::set_default_timeout`].
+        pub fn timeout(&mut self, value: std::time::Duration) -> &mut Self {
+            self.timeout = Some(value);
+            self
+        }
+    };
+}
+
+pub(crate) use timeout_setter;
+
+/// A `cdb_bytes` method of a builder, for an `impl` block with a method building the command
+/// buffer an issue method sends, e.g. `cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16)`.
+/// The command buffer method checks the fields of the CDB the way the issue method does.
+macro_rules! cdb_bytes {
+    ($name:ident: $issue:ident => $command_buffer:ident) => {
+        #[doc = concat!("The CDB [`Self::", stringify!($issue), "`] sends, byte for byte, see")]
+        /// [`crate::CdbBytes`].
+        pub fn $name(&self) -> crate::Result<Vec<u8>> {
+            Ok(crate::command::cdb_bytes(&self.$command_buffer()?))
+        }
+    };
+}
+
+pub(crate) use cdb_bytes;
+
+/// One method on [`crate::AsyncScsi`] per method on [`crate::Scsi`] that starts a builder, with
+/// the `async` feature. It starts the builder on the worker thread and hands it to a closure
+/// that sets it up and issues it, e.g. `async_issue!(read_capacity: ReadCapacityCommand)` for
+/// `async_scsi.read_capacity(|command| command.issue_16())`.
+macro_rules! async_issue {
+    ($($scsi_method:ident),+ : $name:ident) => {
+        #[cfg(feature = "async")]
+        impl crate::AsyncScsi {
+            $(
+                #[doc = concat!("Starts [`crate::Scsi::", stringify!($scsi_method), "`] on the worker thread and issues it with `issue`, see [`crate::AsyncScsi::run`].")]
+                pub fn $scsi_method<F, T>(&self, issue: F) -> crate::Task<T>
+                where
+                    F: FnOnce(&mut $name<'_>) -> crate::Result<T> + Send + 'static,
+                    T: Send + 'static,
+                {
+                    self.run(move |scsi| issue(&mut scsi.$scsi_method()))
+                }
+            )+
+        }
+    };
+}
+
+pub(crate) use async_issue;
+
+/// Serializes bitfields as their bytes, the layout of the page or descriptor on the wire, with
+/// the `serde` feature. Expands to nothing without it.
+macro_rules! impl_bitfield_serde {
+    ($($name:ty),+ $(,)?) => {
+        $(
+            #[cfg(feature = "serde")]
+            impl serde::Serialize for $name {
+                fn serialize<S: serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_bytes(&self.into_bytes())
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            impl<'de> serde::Deserialize<'de> for $name {
+                fn deserialize<D: serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Self, D::Error> {
+                    let bytes = <Vec<u8> as serde::Deserialize>::deserialize(deserializer)?;
+                    let length = bytes.len();
+                    let bytes = bytes.try_into().map_err(|_| {
+                        <D::Error as serde::de::Error>::invalid_length(length, &stringify!($name))
+                    })?;
+
+                    Ok(Self::from_bytes(bytes))
+                }
+            }
+        )+
+    };
+}
+
+pub(crate) use impl_bitfield_serde;
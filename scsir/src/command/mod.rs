@@ -1,52 +1,119 @@
+pub mod ata_pass_through;
+#[cfg(feature = "block")]
 pub mod background_control;
+#[cfg(feature = "block")]
+pub mod compare_and_write;
+mod control;
+#[cfg(feature = "block")]
+pub mod element_depopulation;
+#[cfg(feature = "copy-offload")]
+pub mod extended_copy;
+#[cfg(feature = "block")]
 pub mod format_unit;
+#[cfg(feature = "block")]
 pub mod get_lba_status;
+#[cfg(feature = "block")]
+pub mod get_physical_element_status;
+#[cfg(feature = "stream")]
 pub mod get_stream_status;
 pub mod inquiry;
 pub mod log_select;
 pub mod log_sense;
+mod macros;
 pub mod mode_select;
 pub mod mode_sense;
+pub mod passthrough;
 pub mod persistent_reserve_in;
 pub mod persistent_reserve_out;
+#[cfg(feature = "copy-offload")]
+pub mod populate_token;
+#[cfg(feature = "block")]
+pub mod pre_fetch;
+pub mod prevent_allow_medium_removal;
+#[cfg(feature = "block")]
+pub mod protection_information;
+#[cfg(feature = "block")]
 pub mod read;
+pub mod read_attribute;
 pub mod read_buffer;
+#[cfg(feature = "block")]
 pub mod read_capacity;
+#[cfg(feature = "block")]
 pub mod read_defect_data;
+#[cfg(feature = "block")]
+pub mod read_long;
+#[cfg(feature = "block")]
 pub mod reassign_blocks;
+#[cfg(feature = "copy-offload")]
+pub mod receive_copy_results;
+#[cfg(feature = "diagnostics")]
 pub mod receive_diagnostic_results;
 pub mod report_identifying_information;
 pub mod report_luns;
+#[cfg(feature = "block")]
+pub mod report_referrals;
 pub mod report_supported_operation_codes;
 pub mod report_supported_task_management_functions;
 pub mod report_timestamp;
+#[cfg(feature = "zbc")]
+pub mod report_zones;
 pub mod request_sense;
+pub mod reserve_release_6;
+#[cfg(feature = "block")]
 pub mod sanitize;
+#[cfg(feature = "security")]
 pub mod security_protocol_in;
+#[cfg(feature = "security")]
 pub mod security_protocol_out;
+#[cfg(feature = "diagnostics")]
 pub mod send_diagnostic;
 pub mod sense;
 pub mod set_identifying_information;
 pub mod set_timestamp;
 pub mod shortcut;
+#[cfg(feature = "block")]
 pub mod start_stop_unit;
+#[cfg(feature = "stream")]
 pub mod stream_control;
+#[cfg(feature = "block")]
 pub mod synchronize_cache;
 pub mod test_unit_ready;
+#[cfg(feature = "block")]
 pub mod unmap;
+#[cfg(feature = "block")]
 pub mod verify;
+#[cfg(feature = "block")]
 pub mod write;
+#[cfg(feature = "block")]
 pub mod write_and_verify;
+#[cfg(feature = "block")]
 pub mod write_atomic;
+pub mod write_attribute;
 pub mod write_buffer;
+#[cfg(feature = "block")]
 pub mod write_long;
+#[cfg(feature = "block")]
 pub mod write_same;
+#[cfg(feature = "stream")]
 pub mod write_stream;
+#[cfg(feature = "copy-offload")]
+pub mod write_using_token;
+#[cfg(feature = "block")]
+pub mod xdwriteread;
+#[cfg(feature = "zbc")]
+pub mod zone_management;
 
-use std::{borrow::BorrowMut, mem::size_of};
+use std::{
+    borrow::BorrowMut,
+    mem::{size_of, size_of_val},
+    slice,
+    time::Duration,
+};
 
 use crate::{result_data::ResultData, DataDirection};
 
+pub use control::Control;
+
 pub trait Command {
     type CommandBuffer;
     type DataBuffer;
@@ -63,9 +130,220 @@ pub trait Command {
         size_of::<Self::DataBuffer>() as u32
     }
 
+    /// The length of the data in buffer of a [`DataDirection::Bidirectional`] command.
+    ///
+    /// The data buffer holds `data_size() - data_in_size()` bytes sent to the device,
+    /// followed by the `data_in_size()` bytes read from it. The transfered data length and
+    /// residual of the result describe the data in part.
+    fn data_in_size(&self) -> u32 {
+        0
+    }
+
+    /// overrides the timeout of the [`crate::Scsi`] handle for this command
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType;
 }
 
+/// The CDB a command sends, byte for byte, e.g. for logging or test assertions.
+pub trait CdbBytes {
+    fn cdb_bytes(&self) -> Vec<u8>;
+}
+
+impl<T: Command> CdbBytes for T {
+    fn cdb_bytes(&self) -> Vec<u8> {
+        cdb_bytes(&self.command())
+    }
+}
+
+// the CDB of a command buffer, as sent, see the cdb_bytes methods of the builders
+pub(crate) fn cdb_bytes<B>(command_buffer: &B) -> Vec<u8> {
+    let mut bytes = command_bytes(command_buffer).to_vec();
+    fill_additional_cdb_length(&mut bytes);
+    bytes
+}
+
+// command buffers are plain bitfields or byte arrays, so their bytes are the CDB
+pub(crate) fn command_bytes<B>(command_buffer: &B) -> &[u8] {
+    unsafe {
+        slice::from_raw_parts(
+            command_buffer as *const B as *const u8,
+            size_of_val(command_buffer),
+        )
+    }
+}
+
+/// The operation code of the variable length CDBs of SPC-5, like READ (32) or WRITE SAME (32).
+///
+/// Such a CDB is declared as a `#[bitfield]` like any other command buffer, starting with the
+/// common header: OPERATION CODE, CONTROL, five bytes whose last one usually holds the GROUP
+/// NUMBER, ADDITIONAL CDB LENGTH and a 16 bit SERVICE ACTION, followed by the fields of the
+/// service action. The ADDITIONAL CDB LENGTH is left at 0, [`crate::Scsi::issue`] sets it from
+/// the size of the command buffer through [`fill_additional_cdb_length`].
+pub(crate) const VARIABLE_LENGTH_OPERATION_CODE: u8 = 0x7F;
+
+pub(crate) fn fill_additional_cdb_length(cdb: &mut [u8]) {
+    const HEADER_LENGTH: usize = 8;
+
+    if cdb.len() > HEADER_LENGTH && cdb[0] == VARIABLE_LENGTH_OPERATION_CODE {
+        cdb[7] = u8::try_from(cdb.len() - HEADER_LENGTH).unwrap_or(u8::MAX);
+    }
+}
+
+/// The allocation length of a response with a header followed by `count` elements, or
+/// [`crate::Error::ArgumentOutOfBounds`] if it doesn't fit in 32 bits.
+#[cfg(any(feature = "block", feature = "stream"))]
+pub(crate) fn checked_allocation_length(
+    header_size: usize,
+    element_size: usize,
+    count: u32,
+) -> crate::Result<u32> {
+    element_size
+        .checked_mul(count as usize)
+        .and_then(|length| length.checked_add(header_size))
+        .and_then(|length| u32::try_from(length).ok())
+        .ok_or_else(|| {
+            crate::Error::ArgumentOutOfBounds(format!(
+                "descriptor length is out of bounds. The maximum possible value is {}, but {} was provided.",
+                u32::MAX.saturating_sub(header_size as u32) as usize / element_size.max(1),
+                count
+            ))
+        })
+}
+
+/// True if a response whose header reports `required_length` didn't fit in the
+/// `allocation_length` it was read with.
+pub(crate) fn truncated(required_length: usize, allocation_length: usize) -> bool {
+    required_length > allocation_length
+}
+
+/// The length of a page with the common 4 byte header of VPD, log and diagnostic pages, whose
+/// PAGE LENGTH in bytes 2 and 3 counts the bytes after the header.
+pub(crate) fn page_required_length(page: &[u8]) -> usize {
+    page.get(2..)
+        .and_then(crate::util::be_u16)
+        .map_or(0, |length| length as usize + 4)
+}
+
+/// Issues a command with an allocation length, and once more if the response was truncated,
+/// with the allocation length `required_length` reads from its header.
+///
+/// The second allocation length is capped at `maximum`, the largest one the CDB holds, so a
+/// response longer than that is returned truncated. The command keeps the larger allocation
+/// length.
+pub(crate) fn issue_full_length<C, R>(
+    command: &mut C,
+    allocation_length: usize,
+    maximum: usize,
+    set_allocation_length: impl FnOnce(&mut C, usize),
+    mut issue: impl FnMut(&mut C) -> crate::Result<R>,
+    required_length: impl FnOnce(&R) -> usize,
+) -> crate::Result<R> {
+    let result = issue(command)?;
+    let required_length = required_length(&result);
+    if !truncated(required_length, allocation_length) || allocation_length >= maximum {
+        return Ok(result);
+    }
+
+    set_allocation_length(command, required_length.min(maximum));
+    issue(command)
+}
+
+/// Whether a READ or WRITE of `block_count` blocks needs the 16 byte CDB instead of the 10 byte
+/// one, either for its range or because the cached supported commands lack the 10 byte one.
+///
+/// [`crate::Error::ArgumentOutOfBounds`] if the range goes past the last possible LBA.
+#[cfg(feature = "block")]
+pub(crate) fn needs_16_byte_cdb(
+    interface: &crate::Scsi,
+    operation_code_10: u8,
+    logical_block_address: u64,
+    block_count: u32,
+) -> crate::Result<bool> {
+    if logical_block_address
+        .checked_add(block_count as u64)
+        .is_none()
+    {
+        return Err(crate::Error::ArgumentOutOfBounds(format!(
+            "logical block range is out of bounds. {} blocks at {} go past the last possible logical block address.",
+            block_count, logical_block_address
+        )));
+    }
+
+    let fits_10 = u32::try_from(logical_block_address).is_ok() && block_count <= u16::MAX as u32;
+    let supports_10 = interface
+        .cached_supported_commands()
+        .is_none_or(|commands| commands.supports(operation_code_10, None));
+
+    Ok(!(fits_10 && supports_10))
+}
+
+/// The DPO, FUA and GROUP NUMBER fields of the READ, WRITE and VERIFY like commands, set on
+/// their builders directly or all at once with e.g. [`read::ReadCommand::transfer_flags`].
+#[cfg(feature = "block")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransferFlags {
+    pub(crate) disable_page_out: bool,
+    pub(crate) force_unit_access: bool,
+    pub(crate) group_number: u8,
+}
+
+#[cfg(feature = "block")]
+impl TransferFlags {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn disable_page_out(&mut self, value: bool) -> &mut Self {
+        self.disable_page_out = value;
+        self
+    }
+
+    pub fn force_unit_access(&mut self, value: bool) -> &mut Self {
+        self.force_unit_access = value;
+        self
+    }
+
+    // group_number must be less than 0x40, or less than 0x20 for the 10 and 12 byte CDBs
+    pub fn group_number(&mut self, value: u8) -> &mut Self {
+        self.group_number = value;
+        self
+    }
+
+    // VERIFY and WRITE AND VERIFY have no FUA bit
+    pub(crate) fn check(
+        &self,
+        group_number_bits: u32,
+        allow_force_unit_access: bool,
+    ) -> crate::Result<()> {
+        bitfield_bound_check!(self.group_number, group_number_bits, "group number")?;
+
+        if !allow_force_unit_access && self.force_unit_access {
+            return Err(crate::Error::BadArgument(
+                "FUA is not allowed here".to_owned(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// [`crate::Error::ArgumentOutOfBounds`] if no logical block size was set on a block command.
+///
+/// There is no default, a 512 byte guess reads the wrong range of a 4Kn device. The logical
+/// block length of READ CAPACITY, e.g. through [`crate::Scsi::capacity`], is the right value.
+#[cfg(any(feature = "block", feature = "stream"))]
+pub(crate) fn logical_block_size_check(logical_block_size: u32) -> crate::Result<()> {
+    match logical_block_size {
+        0 => Err(crate::Error::ArgumentOutOfBounds(
+            "logical block size must be set and not be 0, see READ CAPACITY.".to_owned(),
+        )),
+        _ => Ok(()),
+    }
+}
+
 pub(crate) fn get_array<const N: usize>(bytes: &[u8]) -> ([u8; N], &[u8]) {
     let mut array: [u8; N] = [0; N];
     let min_len = usize::min(array.len(), bytes.len());
@@ -92,3 +370,48 @@ macro_rules! bitfield_bound_check {
 }
 
 pub(crate) use bitfield_bound_check;
+#[allow(unused_imports)]
+pub(crate) use macros::{
+    async_issue, cdb_bytes, define_command, impl_bitfield_serde, timeout_setter,
+};
+
+#[cfg(all(test, any(feature = "block", feature = "stream")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_allocation_length_test() {
+        // an 8 byte header followed by 16 byte descriptors, like GET LBA STATUS
+        const MAX_COUNT: u32 = (u32::MAX - 8) / 16;
+
+        assert_eq!(
+            checked_allocation_length(8, 16, 0).unwrap(),
+            8,
+            "header only"
+        );
+        assert_eq!(
+            checked_allocation_length(8, 16, MAX_COUNT).unwrap(),
+            8 + MAX_COUNT * 16,
+            "largest count"
+        );
+        assert!(
+            matches!(
+                checked_allocation_length(8, 16, MAX_COUNT + 1),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "one descriptor too many"
+        );
+        assert!(
+            matches!(
+                checked_allocation_length(8, 16, u32::MAX),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "largest requested count"
+        );
+        assert_eq!(
+            checked_allocation_length(0, 1, u32::MAX).unwrap(),
+            u32::MAX,
+            "exactly 32 bits"
+        );
+    }
+}
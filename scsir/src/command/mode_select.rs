@@ -1,10 +1,16 @@
 #![allow(dead_code)]
 
+use std::{borrow::Cow, mem::size_of, time::Duration};
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{
+        bitfield_bound_check, cdb_bytes,
+        shortcut::mode::{ModePage, PageWrapper},
+        timeout_setter, Control,
+    },
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,9 +18,11 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct ModeSelectCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     page_format: bool,
     revert_to_defaults: bool,
     saved_pages: bool,
+    clear_mode_data_length: bool,
     control: u8,
     data_buffer: Vec<u8>,
 }
@@ -23,9 +31,11 @@ impl<'a> ModeSelectCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             page_format: false,
             revert_to_defaults: false,
             saved_pages: false,
+            clear_mode_data_length: false,
             control: 0,
             data_buffer: vec![],
         }
@@ -46,8 +56,14 @@ impl<'a> ModeSelectCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    // the mode data length field is reserved for MODE SELECT, but MODE SENSE data has it set
+    pub fn clear_mode_data_length(&mut self, value: bool) -> &mut Self {
+        self.clear_mode_data_length = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
@@ -57,6 +73,15 @@ impl<'a> ModeSelectCommand<'a> {
         self
     }
 
+    /// Writes back a page read with MODE SENSE, see [`PageWrapper::to_select_bytes`].
+    ///
+    /// The parameter list has a long header, so issue it with [`Self::issue_10`].
+    pub fn mode_page<Page: ModePage>(&mut self, value: &PageWrapper<Page>) -> &mut Self {
+        self.data_buffer = value.to_select_bytes();
+        self.page_format = true;
+        self
+    }
+
     fn error_check(
         &self,
         parameter_length_bits: u32,
@@ -77,46 +102,85 @@ impl<'a> ModeSelectCommand<'a> {
         Ok(())
     }
 
-    pub fn issue_6(&mut self) -> crate::Result<()> {
-        self.error_check(8, true)?;
+    // copied only if the mode data length has to be cleared
+    fn data_buffer(&self, mode_data_length_size: usize) -> Cow<'_, [u8]> {
+        if !self.clear_mode_data_length {
+            return Cow::Borrowed(&self.data_buffer);
+        }
+
+        let mut data_buffer = self.data_buffer.clone();
+        clear_mode_data_length(&mut data_buffer, mode_data_length_size);
 
+        Cow::Owned(data_buffer)
+    }
+
+    timeout_setter!();
+
+    pub fn issue_6(&mut self) -> crate::Result<()> {
+        let command = self.command_buffer_6()?;
+        let data_buffer = self.data_buffer(size_of::<u8>());
         let temp = ThisCommand {
-            command: CommandBuffer6::new()
-                .with_operation_code(OPERATION_CODE_6)
-                .with_page_format(self.page_format.into())
-                .with_revert_to_defaults(self.revert_to_defaults.into())
-                .with_saved_pages(self.saved_pages.into())
-                .with_parameter_list_length(self.data_buffer.len() as u8)
-                .with_control(self.control),
-            data_buffer: self.data_buffer.clone().into(),
+            command,
+            data_buffer: SliceBufferWrapper(&data_buffer),
+            timeout: self.timeout,
         };
 
         self.interface.issue(&temp)
     }
 
-    pub fn issue_10(&mut self) -> crate::Result<()> {
-        self.error_check(16, false)?;
+    cdb_bytes!(cdb_bytes_6: issue_6 => command_buffer_6);
 
+    fn command_buffer_6(&self) -> crate::Result<CommandBuffer6> {
+        self.error_check(8, true)?;
+
+        Ok(CommandBuffer6::new()
+            .with_operation_code(OPERATION_CODE_6)
+            .with_page_format(self.page_format.into())
+            .with_revert_to_defaults(self.revert_to_defaults.into())
+            .with_saved_pages(self.saved_pages.into())
+            .with_parameter_list_length(self.data_buffer.len() as u8)
+            .with_control(self.control))
+    }
+
+    pub fn issue_10(&mut self) -> crate::Result<()> {
+        let command = self.command_buffer_10()?;
+        let data_buffer = self.data_buffer(size_of::<u16>());
         let temp = ThisCommand {
-            command: CommandBuffer10::new()
-                .with_operation_code(OPERATION_CODE_10)
-                .with_page_format(self.page_format.into())
-                .with_saved_pages(self.saved_pages.into())
-                .with_parameter_list_length(self.data_buffer.len() as u16)
-                .with_control(self.control),
-            data_buffer: self.data_buffer.clone().into(),
+            command,
+            data_buffer: SliceBufferWrapper(&data_buffer),
+            timeout: self.timeout,
         };
 
         self.interface.issue(&temp)
     }
+
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
+        self.error_check(16, false)?;
+
+        Ok(CommandBuffer10::new()
+            .with_operation_code(OPERATION_CODE_10)
+            .with_page_format(self.page_format.into())
+            .with_saved_pages(self.saved_pages.into())
+            .with_parameter_list_length(self.data_buffer.len() as u16)
+            .with_control(self.control))
+    }
 }
 
 impl Scsi {
-    pub fn mode_select(&self) -> ModeSelectCommand {
+    pub fn mode_select(&self) -> ModeSelectCommand<'_> {
         ModeSelectCommand::new(self)
     }
 }
 
+crate::command::async_issue!(mode_select: ModeSelectCommand);
+
+fn clear_mode_data_length(bytes: &mut [u8], mode_data_length_size: usize) {
+    let length = usize::min(mode_data_length_size, bytes.len());
+    bytes[..length].fill(0);
+}
+
 const OPERATION_CODE_6: u8 = 0x15;
 const OPERATION_CODE_10: u8 = 0x55;
 
@@ -147,17 +211,18 @@ struct CommandBuffer10 {
     control: B8,
 }
 
-struct ThisCommand<C: Copy> {
+struct ThisCommand<'a, C: Copy> {
     command: C,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl<C: Copy> Command for ThisCommand<C> {
+impl<'a, C: Copy> Command for ThisCommand<'a, C> {
     type CommandBuffer = C;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
@@ -170,13 +235,17 @@ impl<C: Copy> Command for ThisCommand<C> {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -207,4 +276,77 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer10))
         );
     }
+
+    #[test]
+    fn clear_mode_data_length_test() {
+        let mut bytes = [0x00, 0x1E, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        clear_mode_data_length(&mut bytes, size_of::<u16>());
+        assert_eq!(bytes, [0; 8], "long header");
+
+        let mut bytes = [0x17, 0x00, 0x80, 0x08];
+        clear_mode_data_length(&mut bytes, size_of::<u8>());
+        assert_eq!(bytes, [0x00, 0x00, 0x80, 0x08], "short header");
+
+        let mut bytes = [0x17];
+        clear_mode_data_length(&mut bytes, size_of::<u16>());
+        assert_eq!(bytes, [0x00], "short parameter list");
+    }
+
+    #[test]
+    fn mode_page_test() {
+        use crate::command::shortcut::mode::{CachingPage, DescriptorType, HeaderType};
+        use crate::{MockResponse, MockScsi};
+
+        let mut bytes = vec![0x13, 0x00, 0x00, 0x00];
+        bytes.extend_from_slice(&[
+            0x88, 0x12, 0x04, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x14,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ]);
+        let page = PageWrapper::<CachingPage>::from_bytes(
+            HeaderType::Short,
+            DescriptorType::Short,
+            &bytes,
+        );
+
+        let mock = MockScsi::new();
+        mock.expect(
+            &[0x55, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1C, 0x00],
+            MockResponse::good(),
+        );
+        Scsi::mock(mock.clone())
+            .mode_select()
+            .mode_page(&page)
+            .issue_10()
+            .unwrap();
+
+        let issued = mock.issued();
+        assert_eq!(issued[0].data[..8], [0; 8], "long header");
+        assert_eq!(issued[0].data[8], 0x08, "page code without PS");
+        assert_eq!(issued[0].data[9..], bytes[5..], "page");
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.mode_select();
+        command.page_format(true).parameter(&[
+            0x00, 0x00, 0x00, 0x00, 0x08, 0x0A, 0x04, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0xFF, 0xFF,
+        ]);
+
+        // the parameter is borrowed unless the mode data length has to be cleared
+        let before = allocations();
+        command.issue_6().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+
+        command.clear_mode_data_length(true);
+        let before = allocations();
+        command.issue_6().unwrap();
+        assert_eq!(
+            allocations(),
+            before + 1,
+            "allocations with a cleared length"
+        );
+    }
 }
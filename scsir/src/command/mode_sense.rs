@@ -1,17 +1,21 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
+    command::{bitfield_bound_check, cdb_bytes, issue_full_length, timeout_setter, Control},
     data_wrapper::{AnyType, VecBufferWrapper},
     result_data::ResultData,
+    util::be_u16,
     Command, DataDirection, Scsi,
 };
 
 #[derive(Clone, Debug)]
 pub struct ModeSenseCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     long_lba_accepted: bool,
     disable_block_descriptors: bool,
     page_control: u8,
@@ -32,6 +36,7 @@ impl<'a> ModeSenseCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             long_lba_accepted: false,
             disable_block_descriptors: false,
             page_control: 0,
@@ -78,8 +83,8 @@ impl<'a> ModeSenseCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, control: u8) -> &mut Self {
-        self.control = control;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
@@ -105,30 +110,53 @@ impl<'a> ModeSenseCommand<'a> {
         Ok(())
     }
 
+    timeout_setter!();
+
     pub fn issue_6(&mut self) -> crate::Result<Vec<u8>> {
+        let command_buffer = self.command_buffer_6()?;
+
+        let temp = ThisCommand {
+            command_buffer,
+            allocation_length: self.allocation_length.into(),
+            timeout: self.timeout,
+        };
+
+        self.interface.issue(&temp)
+    }
+
+    cdb_bytes!(cdb_bytes_6: issue_6 => command_buffer_6);
+
+    fn command_buffer_6(&self) -> crate::Result<CommandBuffer6> {
         self.error_check(8, false)?;
 
-        let command_buffer = CommandBuffer6::new()
+        Ok(CommandBuffer6::new()
             .with_operation_code(OPERATION_CODE_6)
             .with_disable_block_descriptors(self.disable_block_descriptors.into())
             .with_page_control(self.page_control)
             .with_page_code(self.page_code)
             .with_subpage_code(self.subpage_code)
             .with_allocation_length(self.allocation_length as u8)
-            .with_control(self.control);
+            .with_control(self.control))
+    }
+
+    pub fn issue_10(&mut self) -> crate::Result<Vec<u8>> {
+        let command_buffer = self.command_buffer_10()?;
 
         let temp = ThisCommand {
             command_buffer,
             allocation_length: self.allocation_length.into(),
+            timeout: self.timeout,
         };
 
         self.interface.issue(&temp)
     }
 
-    pub fn issue_10(&mut self) -> crate::Result<Vec<u8>> {
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
         self.error_check(16, true)?;
 
-        let command_buffer = CommandBuffer10::new()
+        Ok(CommandBuffer10::new()
             .with_operation_code(OPERATION_CODE_10)
             .with_long_lba_accepted(self.long_lba_accepted.into())
             .with_disable_block_descriptors(self.disable_block_descriptors.into())
@@ -136,23 +164,48 @@ impl<'a> ModeSenseCommand<'a> {
             .with_page_code(self.page_code)
             .with_subpage_code(self.subpage_code)
             .with_allocation_length(self.allocation_length)
-            .with_control(self.control);
+            .with_control(self.control))
+    }
 
-        let temp = ThisCommand {
-            command_buffer,
-            allocation_length: self.allocation_length.into(),
-        };
+    // issue_6 and issue_10 again with the allocation length reported by the mode data length
+    // if the first response was truncated, the allocation length is left at the larger value
+    pub fn issue_6_full_length(&mut self) -> crate::Result<Vec<u8>> {
+        let allocation_length = self.allocation_length as usize;
+        issue_full_length(
+            self,
+            allocation_length,
+            u8::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u16);
+            },
+            Self::issue_6,
+            |data| data.first().map_or(0, |&length| length as usize + 1),
+        )
+    }
 
-        self.interface.issue(&temp)
+    pub fn issue_10_full_length(&mut self) -> crate::Result<Vec<u8>> {
+        let allocation_length = self.allocation_length as usize;
+        issue_full_length(
+            self,
+            allocation_length,
+            u16::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u16);
+            },
+            Self::issue_10,
+            |data| be_u16(data).map_or(0, |length| length as usize + 2),
+        )
     }
 }
 
 impl Scsi {
-    pub fn mode_sense(&self) -> ModeSenseCommand {
+    pub fn mode_sense(&self) -> ModeSenseCommand<'_> {
         ModeSenseCommand::new(self)
     }
 }
 
+crate::command::async_issue!(mode_sense: ModeSenseCommand);
+
 const OPERATION_CODE_6: u8 = 0x1A;
 const OPERATION_CODE_10: u8 = 0x5A;
 
@@ -189,6 +242,7 @@ struct CommandBuffer10 {
 struct ThisCommand<C> {
     command_buffer: C,
     allocation_length: usize,
+    timeout: Option<Duration>,
 }
 
 impl<C: Copy> Command for ThisCommand<C> {
@@ -216,6 +270,10 @@ impl<C: Copy> Command for ThisCommand<C> {
         self.allocation_length as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
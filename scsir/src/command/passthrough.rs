@@ -0,0 +1,385 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use crate::{
+    command::{fill_additional_cdb_length, sense::SenseData, timeout_setter},
+    data_wrapper::{AnyType, VecBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, HasSense, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct PassthroughCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    command_buffer: Vec<u8>,
+    direction: DataDirection,
+    data_buffer: Vec<u8>,
+    allocation_length: u32,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandResult {
+    /// bytes read from the device, empty unless the direction is FromDevice, ToFromDevice or
+    /// Bidirectional
+    pub data: Vec<u8>,
+    pub residual: usize,
+    /// set if the device completed the command with a RECOVERED ERROR
+    pub sense: Option<SenseData>,
+}
+
+impl HasSense for CommandResult {
+    fn check_sense(&self) -> Option<&SenseData> {
+        self.sense.as_ref()
+    }
+}
+
+impl<'a> PassthroughCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            command_buffer: vec![],
+            direction: DataDirection::None,
+            data_buffer: vec![],
+            allocation_length: 0,
+        }
+    }
+
+    // the length of the CDB must be 6, 10, 12, 16 or 32
+    pub fn command_buffer(&mut self, value: &[u8]) -> &mut Self {
+        self.command_buffer = value.to_owned();
+        self
+    }
+
+    pub fn direction(&mut self, value: DataDirection) -> &mut Self {
+        self.direction = value;
+        self
+    }
+
+    // sent to the device when the direction is ToDevice, ToFromDevice or Bidirectional
+    pub fn parameter(&mut self, value: &[u8]) -> &mut Self {
+        self.data_buffer = value.to_owned();
+        self
+    }
+
+    // the number of bytes to read when the direction is FromDevice, ToFromDevice or
+    // Bidirectional
+    pub fn allocation_length(&mut self, value: u32) -> &mut Self {
+        self.allocation_length = value;
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<CommandResult> {
+        command_length_check(self.command_buffer.len())?;
+        let data_buffer = data_buffer(self.direction, &self.data_buffer, self.allocation_length)?;
+
+        match self.command_buffer.len() {
+            6 => self.issue_n::<6>(data_buffer),
+            10 => self.issue_n::<10>(data_buffer),
+            12 => self.issue_n::<12>(data_buffer),
+            16 => self.issue_n::<16>(data_buffer),
+            _ => self.issue_n::<32>(data_buffer),
+        }
+    }
+
+    /// The CDB [`Self::issue`] sends, byte for byte, see [`crate::CdbBytes`]. The additional CDB
+    /// length of a variable length CDB is filled in, like every other command.
+    pub fn cdb_bytes(&self) -> crate::Result<Vec<u8>> {
+        command_length_check(self.command_buffer.len())?;
+
+        let mut bytes = self.command_buffer.clone();
+        fill_additional_cdb_length(&mut bytes);
+        Ok(bytes)
+    }
+
+    fn issue_n<const N: usize>(&self, data_buffer: Vec<u8>) -> crate::Result<CommandResult> {
+        self.interface.issue(&ThisCommand::<N> {
+            command_buffer: self.command_buffer.as_slice().try_into().unwrap(),
+            direction: self.direction,
+            data_buffer: data_buffer.into(),
+            data_in_length: match self.direction {
+                DataDirection::Bidirectional => self.allocation_length,
+                _ => 0,
+            },
+            timeout: self.timeout,
+        })
+    }
+}
+
+impl Scsi {
+    pub fn passthrough(&self) -> PassthroughCommand<'_> {
+        PassthroughCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(passthrough: PassthroughCommand);
+
+fn command_length_check(length: usize) -> crate::Result<()> {
+    match length {
+        6 | 10 | 12 | 16 | 32 => Ok(()),
+        length => Err(crate::Error::BadArgument(format!(
+            "CDB length must be 6, 10, 12, 16 or 32, but {} was provided.",
+            length
+        ))),
+    }
+}
+
+fn data_buffer(
+    direction: DataDirection,
+    parameter: &[u8],
+    allocation_length: u32,
+) -> crate::Result<Vec<u8>> {
+    let allocation_length = allocation_length as usize;
+
+    let data_buffer = match direction {
+        DataDirection::None => vec![],
+        DataDirection::ToDevice => parameter.to_owned(),
+        DataDirection::FromDevice => vec![0; allocation_length],
+        DataDirection::ToFromDevice => {
+            // the device overwrites the data out buffer with the data in
+            let mut data_buffer = parameter.to_owned();
+            data_buffer.resize(usize::max(parameter.len(), allocation_length), 0);
+            data_buffer
+        }
+        // a separate data in buffer right after the data out
+        DataDirection::Bidirectional => {
+            let mut data_buffer = parameter.to_owned();
+            data_buffer.resize(parameter.len() + allocation_length, 0);
+            data_buffer
+        }
+        DataDirection::Unknown => {
+            return Err(crate::Error::BadArgument(String::from(
+                "Data direction must be known for a passthrough command.",
+            )))
+        }
+    };
+
+    if u32::try_from(data_buffer.len()).is_err() {
+        return Err(crate::Error::ArgumentOutOfBounds(format!(
+            "Data length is out of bounds. The maximum possible value is {}, but {} was provided.",
+            u32::MAX,
+            data_buffer.len()
+        )));
+    }
+
+    Ok(data_buffer)
+}
+
+struct ThisCommand<const N: usize> {
+    command_buffer: [u8; N],
+    direction: DataDirection,
+    data_buffer: VecBufferWrapper,
+    data_in_length: u32,
+    timeout: Option<Duration>,
+}
+
+impl<const N: usize> Command for ThisCommand<N> {
+    type CommandBuffer = [u8; N];
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = VecBufferWrapper;
+
+    type ReturnType = crate::Result<CommandResult>;
+
+    fn direction(&self) -> DataDirection {
+        match self.data_buffer.len() {
+            0 => DataDirection::None,
+            _ => self.direction,
+        }
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        self.data_buffer.clone()
+    }
+
+    fn data_size(&self) -> u32 {
+        self.data_buffer.len() as u32
+    }
+
+    fn data_in_size(&self) -> u32 {
+        self.data_in_length
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        let data = match self.direction {
+            DataDirection::FromDevice | DataDirection::ToFromDevice => {
+                let length = result.data_in_length(result.data().len());
+                Vec::from(&result.data()[..length])
+            }
+            DataDirection::Bidirectional => {
+                let data_in = &result.data()[result.data().len() - self.data_in_length as usize..];
+                let length = result.data_in_length(data_in.len());
+                Vec::from(&data_in[..length])
+            }
+            _ => vec![],
+        };
+
+        Ok(CommandResult {
+            data,
+            residual: result.residual,
+            sense: result.check_sense().cloned(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockResponse, MockScsi};
+
+    #[test]
+    fn test_unit_ready_test() {
+        let mock = MockScsi::new();
+        mock.expect(&[0x00; 6], MockResponse::good())
+            // NOT READY, MEDIUM NOT PRESENT
+            .expect(
+                &[0x00; 6],
+                MockResponse::check_condition(&[
+                    0x70, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x3A,
+                    0x00, 0x00, 0x00, 0x00, 0x00,
+                ]),
+            );
+
+        let scsi = Scsi::mock(mock.clone());
+        let result = scsi
+            .passthrough()
+            .command_buffer(&[0x00; 6])
+            .direction(DataDirection::None)
+            .issue()
+            .unwrap();
+        assert!(result.data.is_empty(), "no data in");
+        assert!(result.sense.is_none(), "no sense");
+
+        let result = scsi
+            .passthrough()
+            .command_buffer(&[0x00; 6])
+            .direction(DataDirection::None)
+            .issue();
+        assert!(
+            matches!(
+                result.check_sense().and_then(SenseData::sense_key),
+                Some(crate::command::sense::SenseKey::NotReady)
+            ),
+            "not ready sense"
+        );
+
+        let issued = mock.issued();
+        assert_eq!(issued[0].command, [0x00; 6], "test unit ready command");
+        assert_eq!(issued[0].direction, DataDirection::None, "direction");
+        mock.verify();
+    }
+
+    #[test]
+    fn data_buffer_test() {
+        assert_eq!(
+            data_buffer(DataDirection::FromDevice, &[0xFF], 4).unwrap(),
+            [0x00; 4],
+            "data in"
+        );
+        assert_eq!(
+            data_buffer(DataDirection::ToDevice, &[0x01, 0x02], 4).unwrap(),
+            [0x01, 0x02],
+            "data out"
+        );
+        assert_eq!(
+            data_buffer(DataDirection::ToFromDevice, &[0x01, 0x02], 4).unwrap(),
+            [0x01, 0x02, 0x00, 0x00],
+            "to from device"
+        );
+        assert_eq!(
+            data_buffer(DataDirection::Bidirectional, &[0x01, 0x02], 4).unwrap(),
+            [0x01, 0x02, 0x00, 0x00, 0x00, 0x00],
+            "bidirectional"
+        );
+        assert!(
+            data_buffer(DataDirection::Unknown, &[], 0).is_err(),
+            "unknown direction"
+        );
+    }
+
+    #[test]
+    fn cdb_bytes_test() {
+        let scsi = Scsi::mock(MockScsi::new());
+
+        let mut cdb = [0x00; 32];
+        cdb[0] = 0x7F;
+        cdb[9] = 0x09;
+        let bytes = scsi.passthrough().command_buffer(&cdb).cdb_bytes().unwrap();
+        assert_eq!(bytes[7], 0x18, "additional cdb length filled in");
+        assert_eq!(bytes[9], 0x09, "service action");
+
+        assert!(
+            matches!(
+                scsi.passthrough().command_buffer(&[0x00; 7]).cdb_bytes(),
+                Err(crate::Error::BadArgument(_))
+            ),
+            "7 bytes CDB"
+        );
+    }
+
+    #[test]
+    fn recovered_error_test() {
+        // RECOVERED ERROR, RECOVERED DATA WITH RETRIES
+        const SENSE: [u8; 18] = [
+            0x70, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x17, 0x01,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let mock = MockScsi::new();
+        mock.expect(&[0x00; 6], MockResponse::check_condition(&SENSE));
+        let scsi = Scsi::mock(mock.clone());
+        let result = scsi
+            .passthrough()
+            .command_buffer(&[0x00; 6])
+            .direction(DataDirection::None)
+            .issue();
+
+        assert!(result.is_ok(), "recovered error completes the command");
+        assert!(
+            matches!(
+                result.check_sense().and_then(SenseData::sense_key),
+                Some(crate::command::sense::SenseKey::RecoveredError)
+            ),
+            "recovered error sense"
+        );
+
+        // MEDIUM ERROR, UNRECOVERED READ ERROR
+        let mut sense = SENSE;
+        sense[2] = 0x03;
+        sense[12] = 0x11;
+        sense[13] = 0x00;
+        mock.expect(&[0x00; 6], MockResponse::check_condition(&sense));
+        let result = scsi
+            .passthrough()
+            .command_buffer(&[0x00; 6])
+            .direction(DataDirection::None)
+            .issue();
+
+        assert!(result.is_err(), "medium error fails the command");
+        assert!(
+            matches!(
+                result.check_sense().and_then(SenseData::sense_key),
+                Some(crate::command::sense::SenseKey::MediumError)
+            ),
+            "medium error sense"
+        );
+        mock.verify();
+    }
+}
@@ -1,19 +1,23 @@
 #![allow(dead_code)]
 
-use std::mem::size_of;
+use std::{mem::size_of, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::{bitfield_bound_check, get_array},
+    command::{
+        bitfield_bound_check, cdb_bytes, get_array, issue_full_length, timeout_setter, Control,
+    },
     data_wrapper::{AnyType, VecBufferWrapper},
     result_data::ResultData,
+    util::{be_u32, be_u64},
     Command, DataDirection, Scsi,
 };
 
 #[derive(Clone, Debug)]
 pub struct PersistentReserveInCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     service_action: ServiceAction,
     command_buffer: CommandBuffer,
 }
@@ -27,6 +31,8 @@ pub enum ServiceAction {
     Other(u8),
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum CommandResult {
     ReadKeys(ReadKeysData),
     ReadReservation(ReadReservationData),
@@ -35,19 +41,28 @@ pub enum CommandResult {
     Raw(Vec<u8>),
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadKeysData {
     pub persistent_reservations_generation: u32,
     pub required_length: u32,
     pub reservation_keys: Vec<u64>,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadReservationData {
     pub persistent_reservations_generation: u32,
+    pub required_length: u32,
+    pub reservation: Option<Reservation>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reservation {
     pub reservation_key: u64,
     pub reservation_scope: u8,
     pub reservation_type: u8,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReportCapabilitiesData {
     pub replace_lost_reservation_capable: bool,
     pub compatible_reservation_handling: bool,
@@ -65,12 +80,14 @@ pub struct ReportCapabilitiesData {
     pub exclusive_access_all_registrants: bool,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadFullStatusData {
     pub persistent_reservations_generation: u32,
     pub required_length: u32,
     pub descriptors: Vec<ReadFullStatusDescriptor>,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadFullStatusDescriptor {
     pub reservation_key: u64,
     pub all_target_ports: bool,
@@ -85,6 +102,7 @@ impl<'a> PersistentReserveInCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             service_action: ServiceAction::ReadKeys,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
         }
@@ -101,44 +119,83 @@ impl<'a> PersistentReserveInCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
-    pub fn issue(&mut self) -> crate::Result<CommandResult> {
-        bitfield_bound_check!(u8::from(self.service_action), 5, "service action")?;
-        self.command_buffer
-            .set_service_action(self.service_action.into());
+    timeout_setter!();
 
+    pub fn issue(&mut self) -> crate::Result<CommandResult> {
         let temp = ThisCommand {
-            command_buffer: self.command_buffer,
+            command_buffer: self.command_buffer()?,
             service_action: self.service_action,
+            timeout: self.timeout,
         };
         self.interface.issue(&temp)
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(u8::from(self.service_action), 5, "service action")?;
+
+        Ok(self
+            .command_buffer
+            .with_service_action(self.service_action.into()))
+    }
+
+    // issues again with the allocation length reported by the device if the first response
+    // was truncated, the allocation length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<CommandResult> {
+        let allocation_length = self.command_buffer.allocation_length() as usize;
+        issue_full_length(
+            self,
+            allocation_length,
+            u16::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u16);
+            },
+            Self::issue,
+            CommandResult::required_length,
+        )
+    }
+}
+
+impl CommandResult {
+    /// The allocation length needed for the whole response, as its header reports it
+    pub fn required_length(&self) -> usize {
+        match self {
+            Self::ReadKeys(data) => data.required_length as usize,
+            Self::ReadReservation(data) => data.required_length as usize,
+            Self::ReportCapabilities(_) => size_of::<ReportCapabilitiesBitfield>(),
+            Self::ReadFullStatus(data) => data.required_length as usize,
+            // the format of other service actions is unknown
+            Self::Raw(bytes) => bytes.len(),
+        }
+    }
 }
 
 impl Scsi {
-    pub fn persistent_reserve_in(&self) -> PersistentReserveInCommand {
+    pub fn persistent_reserve_in(&self) -> PersistentReserveInCommand<'_> {
         PersistentReserveInCommand::new(self)
     }
 }
 
+crate::command::async_issue!(persistent_reserve_in: PersistentReserveInCommand);
+
 impl ReadKeysData {
     fn from_bytes(bytes: &[u8]) -> Self {
-        let (array, bytes) = get_array(bytes);
-        let persistent_reservations_generation = u32::from_be_bytes(array);
-
-        let (array, bytes) = get_array(bytes);
-        let additional_length = u32::from_be_bytes(array);
+        let persistent_reservations_generation = be_u32(bytes).unwrap_or(0);
+        let additional_length = bytes.get(4..).and_then(be_u32).unwrap_or(0);
         let required_length = additional_length.saturating_add(8);
 
-        let mut reservation_keys = vec![];
-
-        for chunk in bytes.chunks(size_of::<u64>()) {
-            reservation_keys.push(u64::from_be_bytes(get_array(chunk).0))
-        }
+        let bytes = bytes.get(8..).unwrap_or_default();
+        let length = usize::min(additional_length as usize, bytes.len());
+        let reservation_keys = bytes[..length]
+            .chunks_exact(size_of::<u64>())
+            .filter_map(be_u64)
+            .collect();
 
         Self {
             persistent_reservations_generation,
@@ -153,11 +210,23 @@ impl ReadReservationData {
         let (array, _) = get_array(bytes);
         let data = ReadReservationBitfield::from_bytes(array);
 
+        // the additional length is zero when there is no persistent reservation
+        let reservation = if data.additional_length() as usize >= RESERVATION_DESCRIPTOR_LENGTH
+            && bytes.len() >= size_of::<ReadReservationBitfield>()
+        {
+            Some(Reservation {
+                reservation_key: data.reservation_key(),
+                reservation_scope: data.reservation_scope(),
+                reservation_type: data.reservation_type(),
+            })
+        } else {
+            None
+        };
+
         Self {
             persistent_reservations_generation: data.persistent_reservations_generation(),
-            reservation_key: data.reservation_key(),
-            reservation_scope: data.reservation_scope(),
-            reservation_type: data.reservation_type(),
+            required_length: data.additional_length().saturating_add(8),
+            reservation,
         }
     }
 }
@@ -301,6 +370,7 @@ struct ReadFullsstatusDescriptorHeaderBitfield {
 }
 
 const OPERATION_CODE: u8 = 0x5E;
+const RESERVATION_DESCRIPTOR_LENGTH: usize = 16;
 
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
@@ -316,6 +386,7 @@ struct CommandBuffer {
 struct ThisCommand {
     command_buffer: CommandBuffer,
     service_action: ServiceAction,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -343,11 +414,16 @@ impl Command for ThisCommand {
         self.command_buffer.allocation_length() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        let bytes = &result.data()[..];
+        let length = result.data_in_length(result.data().len());
+        let bytes = &result.data()[..length];
 
         Ok(match self.service_action {
             ServiceAction::ReadKeys => CommandResult::ReadKeys(ReadKeysData::from_bytes(bytes)),
@@ -411,4 +487,47 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn read_keys_test() {
+        let bytes = [
+            0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x10, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB,
+            0xCD, 0xEF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2A, 0xFF, 0xFF,
+        ];
+
+        let data = ReadKeysData::from_bytes(&bytes);
+        assert_eq!(data.persistent_reservations_generation, 5, "generation");
+        assert_eq!(data.required_length, 24, "required length");
+        assert_eq!(
+            data.reservation_keys,
+            [0x0123_4567_89AB_CDEF, 0x2A],
+            "reservation keys"
+        );
+
+        let data = ReadKeysData::from_bytes(&bytes[..20]);
+        assert_eq!(
+            data.reservation_keys,
+            [0x0123_4567_89AB_CDEF],
+            "truncated reservation keys"
+        );
+    }
+
+    #[test]
+    fn read_reservation_test() {
+        let bytes = [0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00, 0x00];
+
+        let data = ReadReservationData::from_bytes(&bytes);
+        assert_eq!(data.persistent_reservations_generation, 7, "generation");
+        assert!(data.reservation.is_none(), "no reservation");
+
+        let mut bytes = [0u8; READ_RESERVATION_BITFIELD_LENGTH];
+        bytes[7] = 0x10;
+        bytes[15] = 0x2A;
+        bytes[21] = 0x05;
+
+        let reservation = ReadReservationData::from_bytes(&bytes).reservation.unwrap();
+        assert_eq!(reservation.reservation_key, 0x2A, "reservation key");
+        assert_eq!(reservation.reservation_scope, 0, "reservation scope");
+        assert_eq!(reservation.reservation_type, 5, "reservation type");
+    }
 }
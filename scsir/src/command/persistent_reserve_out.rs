@@ -1,10 +1,12 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,6 +14,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct PersistentReserveOutCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     service_action: ServiceAction,
     reservation_scope: u8,
     reservation_type: u8,
@@ -54,6 +57,7 @@ impl<'a> PersistentReserveOutCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             service_action: ServiceAction::Register,
             reservation_scope: 0,
             reservation_type: 0,
@@ -77,8 +81,8 @@ impl<'a> PersistentReserveOutCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
@@ -86,23 +90,64 @@ impl<'a> PersistentReserveOutCommand<'a> {
         ParameterBuilder::new(self)
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<()> {
+        let temp = ThisCommand {
+            command_buffer: self.command_buffer()?,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
+        };
+
+        self.interface.issue(&temp)
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
         bitfield_bound_check!(u8::from(self.service_action), 5, "service action")?;
         bitfield_bound_check!(self.reservation_scope, 4, "reservation scope")?;
         bitfield_bound_check!(self.reservation_type, 4, "reservation type")?;
         bitfield_bound_check!(self.data_buffer.len(), 32, "parameter list length")?;
+        service_action_check(
+            self.service_action,
+            self.reservation_type,
+            &self.data_buffer,
+        )?;
+
+        Ok(self
+            .command_buffer
+            .with_service_action(self.service_action.into())
+            .with_reservation_scope(self.reservation_scope)
+            .with_reservation_type(self.reservation_type)
+            .with_parameter_list_length(self.data_buffer.len() as u32))
+    }
+}
 
-        let temp = ThisCommand {
-            command_buffer: self
-                .command_buffer
-                .with_service_action(self.service_action.into())
-                .with_reservation_scope(self.reservation_scope)
-                .with_reservation_type(self.reservation_type)
-                .with_parameter_list_length(self.data_buffer.len() as u32),
-            data_buffer: self.data_buffer.clone().into(),
-        };
+// WRITE EXCLUSIVE - ALL REGISTRANTS and EXCLUSIVE ACCESS - ALL REGISTRANTS
+const ALL_REGISTRANTS_TYPES: [u8; 2] = [0x07, 0x08];
 
-        self.interface.issue(&temp)
+fn service_action_check(
+    service_action: ServiceAction,
+    reservation_type: u8,
+    data_buffer: &[u8],
+) -> crate::Result<()> {
+    match service_action {
+        ServiceAction::Reserve if reservation_type == 0 => Err(crate::Error::BadArgument(
+            "Reserve requires a reservation type".to_owned(),
+        )),
+        // preempting an all registrants reservation with a key of 0 removes every other
+        // registration
+        ServiceAction::Preempt | ServiceAction::PreemptAndAbort
+            if !data_buffer.get(8..16).is_some_and(|x| {
+                x.iter().any(|&b| b != 0) || ALL_REGISTRANTS_TYPES.contains(&reservation_type)
+            }) =>
+        {
+            Err(crate::Error::BadArgument(
+                "Preempt requires a service action reservation key".to_owned(),
+            ))
+        }
+        _ => Ok(()),
     }
 }
 
@@ -122,7 +167,7 @@ impl<'a> ParameterBuilder<'a> {
         RegisterAndMoveParameterData::new(self)
     }
 
-    pub fn done(&'a mut self) -> &'a mut PersistentReserveOutCommand {
+    pub fn done(&'a mut self) -> &'a mut PersistentReserveOutCommand<'a> {
         self.parent.data_buffer = std::mem::take(&mut self.data_buffer);
         self.parent
     }
@@ -169,7 +214,7 @@ impl<'a> BasicParameterData<'a> {
         self
     }
 
-    pub fn done(&'a mut self) -> &'a mut ParameterBuilder {
+    pub fn done(&'a mut self) -> &'a mut ParameterBuilder<'a> {
         self.parent.data_buffer.clear();
         self.parent
             .data_buffer
@@ -226,7 +271,7 @@ impl<'a> RegisterAndMoveParameterData<'a> {
         self
     }
 
-    pub fn done(&'a mut self) -> &'a mut ParameterBuilder {
+    pub fn done(&'a mut self) -> &'a mut ParameterBuilder<'a> {
         self.parent.data_buffer.clear();
         self.header
             .set_transportid_parameter_data_length(self.transport_id.len() as u32);
@@ -240,11 +285,13 @@ impl<'a> RegisterAndMoveParameterData<'a> {
 }
 
 impl Scsi {
-    pub fn persistent_reserve_out(&self) -> PersistentReserveOutCommand {
+    pub fn persistent_reserve_out(&self) -> PersistentReserveOutCommand<'_> {
         PersistentReserveOutCommand::new(self)
     }
 }
 
+crate::command::async_issue!(persistent_reserve_out: PersistentReserveOutCommand);
+
 impl From<ServiceAction> for u8 {
     fn from(value: ServiceAction) -> Self {
         match value {
@@ -305,22 +352,23 @@ struct CommandBuffer {
     control: B8,
 }
 
-struct ThisCommand {
+struct ThisCommand<'a> {
     command_buffer: CommandBuffer,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl Command for ThisCommand {
+impl<'a> Command for ThisCommand<'a> {
     type CommandBuffer = CommandBuffer;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
     fn direction(&self) -> DataDirection {
-        DataDirection::FromDevice
+        DataDirection::ToDevice
     }
 
     fn command(&self) -> Self::CommandBuffer {
@@ -328,7 +376,15 @@ impl Command for ThisCommand {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
+    }
+
+    fn data_size(&self) -> u32 {
+        self.data_buffer.len() as u32
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
     }
 
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
@@ -368,4 +424,81 @@ mod tests {
             concat!("Size of: ", stringify!(RegisterAndMoveParameterHeader))
         );
     }
+
+    #[test]
+    fn register_parameter_test() {
+        let header = BasicParameterHeader::new()
+            .with_service_action_reservation_key(0x0123_4567_89AB_CDEF)
+            .with_activate_persist_through_power_loss(1);
+
+        assert_eq!(
+            header.into_bytes(),
+            [
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB,
+                0xCD, 0xEF, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00
+            ],
+            "register parameter list"
+        );
+    }
+
+    #[test]
+    fn service_action_check_test() {
+        let header = BasicParameterHeader::new().with_reservation_key(0x2A);
+
+        assert!(
+            service_action_check(ServiceAction::Reserve, 0, &header.bytes).is_err(),
+            "reserve without type"
+        );
+        assert!(
+            service_action_check(ServiceAction::Reserve, 1, &header.bytes).is_ok(),
+            "reserve with type"
+        );
+        assert!(
+            service_action_check(ServiceAction::Preempt, 1, &header.bytes).is_err(),
+            "preempt without service action key"
+        );
+        assert!(
+            service_action_check(ServiceAction::Preempt, 1, &[]).is_err(),
+            "preempt without parameter list"
+        );
+        assert!(
+            service_action_check(
+                ServiceAction::Preempt,
+                1,
+                &header.with_service_action_reservation_key(0x2B).bytes
+            )
+            .is_ok(),
+            "preempt with service action key"
+        );
+        assert!(
+            service_action_check(ServiceAction::Preempt, 7, &header.bytes).is_ok(),
+            "preempt all registrants without service action key"
+        );
+        assert!(
+            service_action_check(ServiceAction::PreemptAndAbort, 8, &header.bytes).is_ok(),
+            "preempt and abort all registrants without service action key"
+        );
+        assert!(
+            service_action_check(ServiceAction::Preempt, 7, &[]).is_err(),
+            "preempt all registrants without parameter list"
+        );
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.persistent_reserve_out();
+        command.service_action(ServiceAction::Register);
+        let mut parameter = command.parameter();
+        let mut data = parameter.basic_parameter();
+        data.service_action_reservation_key(0x0123_4567_89AB_CDEF);
+        let command = data.done().done();
+
+        // the parameter list is built by the builder, issuing hands it out as is
+        let before = allocations();
+        command.issue().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
@@ -0,0 +1,322 @@
+#![allow(dead_code)]
+
+use std::{mem::size_of, time::Duration};
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    data_wrapper::{AnyType, SliceBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct PopulateTokenCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    list_identifier: u32,
+    group_number: u8,
+    control: u8,
+    immediate: bool,
+    inactivity_timeout: u32,
+    rod_type: Option<u32>,
+    block_ranges: Vec<(u64, u32)>,
+}
+
+impl<'a> PopulateTokenCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            list_identifier: 0,
+            group_number: 0,
+            control: 0,
+            immediate: false,
+            inactivity_timeout: 0,
+            rod_type: None,
+            block_ranges: vec![],
+        }
+    }
+
+    // the token is retrieved later by RECEIVE ROD TOKEN INFORMATION with the same list identifier
+    pub fn list_identifier(&mut self, value: u32) -> &mut Self {
+        self.list_identifier = value;
+        self
+    }
+
+    // group_number must be less than 0x20
+    pub fn group_number(&mut self, value: u8) -> &mut Self {
+        self.group_number = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
+        self
+    }
+
+    pub fn immediate(&mut self, value: bool) -> &mut Self {
+        self.immediate = value;
+        self
+    }
+
+    // seconds the token stays valid without being used, 0 means the device default
+    pub fn inactivity_timeout(&mut self, value: u32) -> &mut Self {
+        self.inactivity_timeout = value;
+        self
+    }
+
+    // the device picks the ROD type if not set
+    pub fn rod_type(&mut self, value: u32) -> &mut Self {
+        self.rod_type = Some(value);
+        self
+    }
+
+    pub fn add_block_range(
+        &mut self,
+        logical_block_address: u64,
+        number_of_logical_blocks: u32,
+    ) -> &mut Self {
+        self.block_ranges
+            .push((logical_block_address, number_of_logical_blocks));
+        self
+    }
+
+    // each range is a logical block address and a number of logical blocks
+    pub fn add_block_ranges(&mut self, ranges: &[(u64, u32)]) -> &mut Self {
+        self.block_ranges.extend_from_slice(ranges);
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<()> {
+        let (command_buffer, data_buffer) = self.command()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            data_buffer: SliceBufferWrapper(&data_buffer),
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        self.command().map(|(command_buffer, _)| command_buffer)
+    }
+
+    // the parameter list length comes from the parameter list, so both are built together
+    fn command(&self) -> crate::Result<(CommandBuffer, Vec<u8>)> {
+        bitfield_bound_check!(self.group_number, 5, "group number")?;
+
+        let data_buffer = parameter_list(
+            self.immediate,
+            self.inactivity_timeout,
+            self.rod_type,
+            &self.block_ranges,
+        )?;
+
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_service_action(SERVICE_ACTION)
+            .with_list_identifier(self.list_identifier)
+            .with_parameter_list_length(data_buffer.len() as u32)
+            .with_group_number(self.group_number)
+            .with_control(self.control);
+
+        Ok((command_buffer, data_buffer))
+    }
+}
+
+impl Scsi {
+    pub fn populate_token(&self) -> PopulateTokenCommand<'_> {
+        PopulateTokenCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(populate_token: PopulateTokenCommand);
+
+fn parameter_list(
+    immediate: bool,
+    inactivity_timeout: u32,
+    rod_type: Option<u32>,
+    block_ranges: &[(u64, u32)],
+) -> crate::Result<Vec<u8>> {
+    let descriptors = block_device_range_descriptors(block_ranges)?;
+    let total_size = size_of::<ParameterListHeader>() + descriptors.len();
+    bitfield_bound_check!(total_size, 16, "parameter list length")?;
+
+    let header = ParameterListHeader::new()
+        .with_populate_token_data_length((total_size - size_of::<u16>()) as u16)
+        .with_rod_type_valid(rod_type.is_some().into())
+        .with_immediate(immediate.into())
+        .with_inactivity_timeout(inactivity_timeout)
+        .with_rod_type(rod_type.unwrap_or_default())
+        .with_block_device_range_descriptor_length(descriptors.len() as u16);
+
+    let mut bytes = header.into_bytes().to_vec();
+    bytes.extend_from_slice(&descriptors);
+
+    Ok(bytes)
+}
+
+pub(super) fn block_device_range_descriptors(
+    block_ranges: &[(u64, u32)],
+) -> crate::Result<Vec<u8>> {
+    if block_ranges.is_empty() {
+        return Err(crate::Error::BadArgument(
+            "at least one block device range descriptor is required".to_owned(),
+        ));
+    }
+
+    let mut bytes =
+        Vec::with_capacity(block_ranges.len() * size_of::<BlockDeviceRangeDescriptor>());
+    for &(logical_block_address, number_of_logical_blocks) in block_ranges {
+        bytes.extend_from_slice(
+            &BlockDeviceRangeDescriptor::new()
+                .with_logical_block_address(logical_block_address)
+                .with_number_of_logical_blocks(number_of_logical_blocks)
+                .into_bytes(),
+        );
+    }
+
+    Ok(bytes)
+}
+
+const OPERATION_CODE: u8 = 0x83;
+const SERVICE_ACTION: u8 = 0x10;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    reserved_1: B32,
+    list_identifier: B32,
+    parameter_list_length: B32,
+    reserved_2: B3,
+    group_number: B5,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct ParameterListHeader {
+    populate_token_data_length: B16,
+    reserved_0: B6,
+    rod_type_valid: B1,
+    immediate: B1,
+    reserved_1: B8,
+    inactivity_timeout: B32,
+    rod_type: B32,
+    reserved_2: B16,
+    block_device_range_descriptor_length: B16,
+}
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct BlockDeviceRangeDescriptor {
+    logical_block_address: B64,
+    number_of_logical_blocks: B32,
+    reserved: B32,
+}
+
+struct ThisCommand<'a> {
+    command_buffer: CommandBuffer,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
+}
+
+impl<'a> Command for ThisCommand<'a> {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
+
+    type ReturnType = crate::Result<()>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::ToDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        self.data_buffer
+    }
+
+    fn data_size(&self) -> u32 {
+        self.data_buffer.len() as u32
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+    const PARAMETER_HEADER_LENGTH: usize = 16;
+    const BLOCK_DEVICE_RANGE_DESCRIPTOR_LENGTH: usize = 16;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+
+        assert_eq!(
+            size_of::<ParameterListHeader>(),
+            PARAMETER_HEADER_LENGTH,
+            concat!("Size of: ", stringify!(ParameterListHeader))
+        );
+
+        assert_eq!(
+            size_of::<BlockDeviceRangeDescriptor>(),
+            BLOCK_DEVICE_RANGE_DESCRIPTOR_LENGTH,
+            concat!("Size of: ", stringify!(BlockDeviceRangeDescriptor))
+        );
+    }
+
+    #[test]
+    fn two_ranges_test() {
+        let bytes = parameter_list(true, 0, None, &[(0x1000, 0x800), (0x20_0000, 0x10)]).unwrap();
+
+        assert_eq!(
+            bytes,
+            [
+                0x00, 0x2E, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x20, // header
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
+                0x00, 0x00, // first range
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00,
+                0x00, 0x00, // second range
+            ],
+            "populate token parameter list"
+        );
+
+        assert!(
+            parameter_list(false, 0, None, &[]).is_err(),
+            "no block device range descriptor"
+        );
+    }
+}
@@ -0,0 +1,261 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    result_data::{ResultData, Status},
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct PreFetchCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    immediate: bool,
+    group_number: u8,
+    logical_block_address: u64,
+    number_of_blocks: u32,
+    control: u8,
+}
+
+impl<'a> PreFetchCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            immediate: false,
+            group_number: 0,
+            logical_block_address: 0,
+            number_of_blocks: 0,
+            control: 0,
+        }
+    }
+
+    pub fn immediate(&mut self, value: bool) -> &mut Self {
+        self.immediate = value;
+        self
+    }
+
+    // group_number must be less than 0x20
+    pub fn group_number(&mut self, value: u8) -> &mut Self {
+        self.group_number = value;
+        self
+    }
+
+    pub fn logical_block_address(&mut self, value: u64) -> &mut Self {
+        self.logical_block_address = value;
+        self
+    }
+
+    // 0 means all logical blocks from logical_block_address to the end of the medium
+    pub fn number_of_blocks(&mut self, value: u32) -> &mut Self {
+        self.number_of_blocks = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
+        self
+    }
+
+    fn error_check(
+        &self,
+        logical_block_address_bits: u32,
+        number_of_blocks_bits: u32,
+    ) -> crate::Result<()> {
+        bitfield_bound_check!(self.group_number, 5, "group number")?;
+        bitfield_bound_check!(
+            self.logical_block_address,
+            logical_block_address_bits,
+            "logical block address"
+        )?;
+        bitfield_bound_check!(
+            self.number_of_blocks,
+            number_of_blocks_bits,
+            "number of blocks"
+        )?;
+
+        Ok(())
+    }
+
+    timeout_setter!();
+
+    // returns true if the whole range fits in the cache
+    pub fn issue_10(&mut self) -> crate::Result<bool> {
+        let command_buffer = self.command_buffer_10()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
+        self.error_check(32, 16)?;
+
+        Ok(CommandBuffer10::new()
+            .with_operation_code(OPERATION_CODE_10)
+            .with_immediate(self.immediate.into())
+            .with_logical_block_address(self.logical_block_address as u32)
+            .with_group_number(self.group_number)
+            .with_prefetch_length(self.number_of_blocks as u16)
+            .with_control(self.control))
+    }
+
+    // returns true if the whole range fits in the cache
+    pub fn issue_16(&mut self) -> crate::Result<bool> {
+        let command_buffer = self.command_buffer_16()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
+
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
+        self.error_check(64, 32)?;
+
+        Ok(CommandBuffer16::new()
+            .with_operation_code(OPERATION_CODE_16)
+            .with_immediate(self.immediate.into())
+            .with_logical_block_address(self.logical_block_address)
+            .with_prefetch_length(self.number_of_blocks)
+            .with_group_number(self.group_number)
+            .with_control(self.control))
+    }
+}
+
+impl Scsi {
+    pub fn pre_fetch(&self) -> PreFetchCommand<'_> {
+        PreFetchCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(pre_fetch: PreFetchCommand);
+
+const OPERATION_CODE_10: u8 = 0x34;
+const OPERATION_CODE_16: u8 = 0x90;
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct CommandBuffer10 {
+    operation_code: B8,
+    reserved_0: B6,
+    immediate: B1,
+    obsolete: B1,
+    logical_block_address: B32,
+    reserved_1: B3,
+    group_number: B5,
+    prefetch_length: B16,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct CommandBuffer16 {
+    operation_code: B8,
+    reserved_0: B6,
+    immediate: B1,
+    reserved_1: B1,
+    logical_block_address: B64,
+    prefetch_length: B32,
+    reserved_2: B3,
+    group_number: B5,
+    control: B8,
+}
+
+struct ThisCommand<C> {
+    command_buffer: C,
+    timeout: Option<Duration>,
+}
+
+impl<C: Copy> Command for ThisCommand<C> {
+    type CommandBuffer = C;
+
+    type DataBuffer = ();
+
+    type DataBufferWrapper = ();
+
+    type ReturnType = crate::Result<bool>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::None
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {}
+
+    fn data_size(&self) -> u32 {
+        0
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+
+        // CONDITION MET means the device had enough cache for the whole range
+        if matches!(result.status, Status::ConditionMet) && result.transfered_sense_length == 0 {
+            return Ok(true);
+        }
+
+        result.check_common_error()?;
+
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH_10: usize = 10;
+    const COMMAND_LENGTH_16: usize = 16;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer10>(),
+            COMMAND_LENGTH_10,
+            concat!("Size of: ", stringify!(CommandBuffer10))
+        );
+
+        assert_eq!(
+            size_of::<CommandBuffer16>(),
+            COMMAND_LENGTH_16,
+            concat!("Size of: ", stringify!(CommandBuffer16))
+        );
+    }
+
+    #[test]
+    fn command_buffer_16_test() {
+        let command_buffer = CommandBuffer16::new()
+            .with_operation_code(OPERATION_CODE_16)
+            .with_immediate(1)
+            .with_logical_block_address(0x1_0000_0000)
+            .with_prefetch_length(0x800)
+            .with_group_number(0x01);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [
+                0x90, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00,
+                0x01, 0x00
+            ],
+            "pre-fetch (16) command"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct PreventAllowMediumRemovalCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    prevent: u8,
+    command_buffer: CommandBuffer,
+}
+
+impl<'a> PreventAllowMediumRemovalCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            prevent: 0,
+            command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
+        }
+    }
+
+    // prevent must be less than 0x04, 0x00 allows and 0x01 prevents medium removal
+    pub fn prevent(&mut self, value: u8) -> &mut Self {
+        self.prevent = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<()> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(self.prevent, 2, "prevent")?;
+
+        Ok(self.command_buffer.with_prevent(self.prevent))
+    }
+}
+
+impl Scsi {
+    pub fn prevent_allow_medium_removal(&self) -> PreventAllowMediumRemovalCommand<'_> {
+        PreventAllowMediumRemovalCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(prevent_allow_medium_removal: PreventAllowMediumRemovalCommand);
+
+const OPERATION_CODE: u8 = 0x1E;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    reserved_0: B24,
+    reserved_1: B6,
+    prevent: B2,
+    control: B8,
+}
+
+struct ThisCommand {
+    command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
+}
+
+impl Command for ThisCommand {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = ();
+
+    type DataBufferWrapper = ();
+
+    type ReturnType = crate::Result<()>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::None
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {}
+
+    fn data_size(&self) -> u32 {
+        0
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 6;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+    }
+}
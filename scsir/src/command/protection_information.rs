@@ -0,0 +1,228 @@
+//! T10 protection information (DIF), the 8 bytes that follow every logical block of a READ or
+//! WRITE with a non-zero RDPROTECT or WRPROTECT, see
+//! [`crate::command::read::ReadCommand::protection`].
+
+/// The bytes of protection information per logical block
+pub const PROTECTION_INFORMATION_LENGTH: usize = 8;
+
+/// The protection type of a formatted medium, see the P_TYPE of READ CAPACITY (16)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtectionType {
+    /// the reference tag is the lower 32 bits of the LBA
+    Type1,
+    /// the reference tag starts at the EXPECTED INITIAL LOGICAL BLOCK REFERENCE TAG, which only
+    /// the 32 byte CDBs carry
+    Type2,
+    /// the reference tag is owned by the application and not checked
+    Type3,
+}
+
+impl ProtectionType {
+    // P_TYPE of READ CAPACITY (16) is the protection type minus 1
+    pub fn from_p_type(p_type: u8) -> Option<Self> {
+        match p_type {
+            0 => Some(Self::Type1),
+            1 => Some(Self::Type2),
+            2 => Some(Self::Type3),
+            _ => None,
+        }
+    }
+
+    /// The reference tag the device checks for the `index`th block of a transfer, None for
+    /// type 3.
+    pub fn reference_tag(
+        &self,
+        logical_block_address: u64,
+        expected_initial_reference_tag: u32,
+        index: u32,
+    ) -> Option<u32> {
+        match self {
+            Self::Type1 => Some(logical_block_address.wrapping_add(index as u64) as u32),
+            Self::Type2 => Some(expected_initial_reference_tag.wrapping_add(index)),
+            Self::Type3 => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProtectionInformation {
+    /// CRC of the logical block, see [`guard`]
+    pub guard: u16,
+    pub application_tag: u16,
+    pub reference_tag: u32,
+}
+
+impl ProtectionInformation {
+    pub fn from_bytes(bytes: [u8; PROTECTION_INFORMATION_LENGTH]) -> Self {
+        Self {
+            guard: u16::from_be_bytes([bytes[0], bytes[1]]),
+            application_tag: u16::from_be_bytes([bytes[2], bytes[3]]),
+            reference_tag: u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; PROTECTION_INFORMATION_LENGTH] {
+        let mut bytes = [0; PROTECTION_INFORMATION_LENGTH];
+        bytes[..2].copy_from_slice(&self.guard.to_be_bytes());
+        bytes[2..4].copy_from_slice(&self.application_tag.to_be_bytes());
+        bytes[4..].copy_from_slice(&self.reference_tag.to_be_bytes());
+        bytes
+    }
+}
+
+/// The CRC of the GUARD field, CRC-16 with the T10-DIF polynomial 0x8BB7
+pub fn guard(block: &[u8]) -> u16 {
+    const POLYNOMIAL: u16 = 0x8BB7;
+
+    block.iter().fold(0, |crc, &byte| {
+        (0..8).fold(crc ^ (byte as u16) << 8, |crc, _| match crc & 0x8000 {
+            0 => crc << 1,
+            _ => crc << 1 ^ POLYNOMIAL,
+        })
+    })
+}
+
+/// Splits the data of a protected READ into its logical blocks and their protection information.
+///
+/// A trailing partial block is left out.
+pub fn blocks(
+    data: &[u8],
+    logical_block_size: u32,
+) -> impl Iterator<Item = (&[u8], ProtectionInformation)> {
+    data.chunks_exact(logical_block_size as usize + PROTECTION_INFORMATION_LENGTH)
+        .map(move |chunk| {
+            let (block, protection) = chunk.split_at(logical_block_size as usize);
+            (
+                block,
+                ProtectionInformation::from_bytes(protection.try_into().unwrap()),
+            )
+        })
+}
+
+/// Appends the protection information to every logical block of `data`, for a protected WRITE.
+///
+/// `data` must be a whole number of blocks, with one entry of `protection` per block.
+pub fn interleave(
+    data: &[u8],
+    logical_block_size: u32,
+    protection: &[ProtectionInformation],
+) -> crate::Result<Vec<u8>> {
+    let logical_block_size = logical_block_size as usize;
+    if logical_block_size == 0 || data.len() != logical_block_size * protection.len() {
+        return Err(crate::Error::BadArgument(format!(
+            "{} bytes of data are not {} blocks of {} bytes.",
+            data.len(),
+            protection.len(),
+            logical_block_size
+        )));
+    }
+
+    let mut result =
+        Vec::with_capacity(data.len() + protection.len() * PROTECTION_INFORMATION_LENGTH);
+    for (block, protection) in data.chunks_exact(logical_block_size).zip(protection) {
+        result.extend_from_slice(block);
+        result.extend_from_slice(&protection.to_bytes());
+    }
+
+    Ok(result)
+}
+
+// the bytes per logical block of the data buffer
+pub(crate) fn transferred_block_size(
+    logical_block_size: u32,
+    protection: Option<ProtectionType>,
+) -> u32 {
+    match protection {
+        Some(_) => logical_block_size.saturating_add(PROTECTION_INFORMATION_LENGTH as u32),
+        None => logical_block_size,
+    }
+}
+
+// protect is RDPROTECT or WRPROTECT, allow_type_2 is true for the 32 byte CDBs. A non-zero
+// protect field transfers 8 more bytes per block, so it needs the protection type to size the
+// buffer with
+pub(crate) fn protection_check(
+    protection: Option<ProtectionType>,
+    protect: u8,
+    allow_type_2: bool,
+) -> crate::Result<()> {
+    match protection {
+        None if protect != 0 => Err(crate::Error::BadArgument(
+            "a non-zero protect field transfers protection information, set the protection type"
+                .to_owned(),
+        )),
+        Some(_) if protect == 0 => Err(crate::Error::BadArgument(
+            "protection information is only transferred with a non-zero protect field".to_owned(),
+        )),
+        Some(ProtectionType::Type2) if !allow_type_2 => Err(crate::Error::BadArgument(
+            "type 2 protection needs the 32 byte CDB".to_owned(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guard_test() {
+        assert_eq!(guard(b"123456789"), 0xD0DB, "check value");
+        assert_eq!(guard(&[0; 512]), 0x0000, "zeroed block");
+    }
+
+    #[test]
+    fn reference_tag_test() {
+        const LBA: u64 = 0x1_0000_0010;
+
+        assert_eq!(
+            ProtectionType::Type1.reference_tag(LBA, 0, 2),
+            Some(0x12),
+            "type 1 is the lower 32 bits of the LBA"
+        );
+        assert_eq!(
+            ProtectionType::Type2.reference_tag(LBA, 0x100, 2),
+            Some(0x102),
+            "type 2 counts from the expected initial reference tag"
+        );
+        assert_eq!(
+            ProtectionType::Type3.reference_tag(LBA, 0x100, 2),
+            None,
+            "type 3 has no reference tag"
+        );
+        assert_eq!(ProtectionType::from_p_type(1), Some(ProtectionType::Type2));
+        assert_eq!(ProtectionType::from_p_type(3), None, "reserved");
+    }
+
+    #[test]
+    fn interleave_test() {
+        let data: Vec<u8> = (0..8).collect();
+        let protection = [
+            ProtectionInformation {
+                guard: guard(&data[..4]),
+                application_tag: 0,
+                reference_tag: 7,
+            },
+            ProtectionInformation {
+                guard: guard(&data[4..]),
+                application_tag: 0xFFFF,
+                reference_tag: 8,
+            },
+        ];
+
+        let bytes = interleave(&data, 4, &protection).unwrap();
+        assert_eq!(bytes.len(), 24, "blocks and protection information");
+        assert_eq!(bytes[4..6], protection[0].guard.to_be_bytes(), "guard");
+        assert_eq!(bytes[8..12], [0, 0, 0, 7], "reference tag");
+
+        let blocks: Vec<_> = blocks(&bytes, 4).collect();
+        assert_eq!(blocks.len(), 2, "blocks");
+        assert_eq!(blocks[1].0, &data[4..], "block data");
+        assert_eq!(blocks[1].1, protection[1], "protection information");
+
+        assert!(
+            interleave(&data, 4, &protection[..1]).is_err(),
+            "a protection information per block"
+        );
+    }
+}
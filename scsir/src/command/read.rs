@@ -1,22 +1,28 @@
 #![allow(dead_code)]
 
+use std::{ops::ControlFlow, time::Duration};
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{
+        bitfield_bound_check, cdb_bytes, logical_block_size_check, needs_16_byte_cdb,
+        protection_information::{protection_check, transferred_block_size, ProtectionType},
+        sense::SenseData,
+        timeout_setter, Control, TransferFlags, VARIABLE_LENGTH_OPERATION_CODE,
+    },
+    data_wrapper::{AnyType, MutSliceBufferWrapper, VecBufferWrapper},
     result_data::ResultData,
-    Command, DataDirection, Scsi,
+    BufferPool, Command, DataDirection, HasSense, PooledBuffer, Scsi,
 };
 
 #[derive(Clone, Debug)]
 pub struct ReadCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     control: u8,
-    group_number: u8,
+    transfer_flags: TransferFlags,
     read_protect: u8,
-    disable_page_out: bool,
-    force_unit_access: bool,
     rebuild_assist_recovery_control: bool,
     logical_block_address: u64,
     expected_initial_logical_block_reference_tag: u32,
@@ -27,17 +33,47 @@ pub struct ReadCommand<'a> {
     dld_1: bool,
     dld_2: bool,
     logical_block_size: u32,
+    protection: Option<ProtectionType>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandResult {
+    pub data: Vec<u8>,
+    /// bytes requested but not transferred by a short read
+    pub residual: usize,
+    /// set if the device completed the read with a RECOVERED ERROR
+    pub sense: Option<SenseData>,
+}
+
+impl HasSense for CommandResult {
+    fn check_sense(&self) -> Option<&SenseData> {
+        self.sense.as_ref()
+    }
+}
+
+#[derive(Debug)]
+pub struct PooledCommandResult<'a> {
+    /// the transferred bytes, in a buffer borrowed from the pool of the Scsi handle
+    pub data: PooledBuffer<'a>,
+    /// bytes requested but not transferred by a short read
+    pub residual: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct IntoCommandResult {
+    /// bytes at the end of the caller's buffer not transferred by a short read
+    pub residual: usize,
 }
 
 impl<'a> ReadCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             control: 0,
-            group_number: 0,
+            transfer_flags: TransferFlags::new(),
             read_protect: 0,
-            disable_page_out: false,
-            force_unit_access: false,
             rebuild_assist_recovery_control: false,
             logical_block_address: 0,
             expected_initial_logical_block_reference_tag: 0,
@@ -47,34 +83,41 @@ impl<'a> ReadCommand<'a> {
             dld_0: false,
             dld_1: false,
             dld_2: false,
-            logical_block_size: 512,
+            logical_block_size: 0,
+            protection: None,
         }
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
     // group_number must be less than 0x40 for read(16) or less than 0x20 for others
     pub fn group_number(&mut self, value: u8) -> &mut Self {
-        self.group_number = value;
+        self.transfer_flags.group_number(value);
+        self
+    }
+
+    // replaces DPO, FUA and the group number at once
+    pub fn transfer_flags(&mut self, value: TransferFlags) -> &mut Self {
+        self.transfer_flags = value;
         self
     }
 
-    // read_protect must be less than 0x08
+    // read_protect must be less than 0x08, and 0 without a protection type
     pub fn read_protect(&mut self, value: u8) -> &mut Self {
         self.read_protect = value;
         self
     }
 
     pub fn disable_page_out(&mut self, value: bool) -> &mut Self {
-        self.disable_page_out = value;
+        self.transfer_flags.disable_page_out(value);
         self
     }
 
     pub fn force_unit_access(&mut self, value: bool) -> &mut Self {
-        self.force_unit_access = value;
+        self.transfer_flags.force_unit_access(value);
         self
     }
 
@@ -123,11 +166,29 @@ impl<'a> ReadCommand<'a> {
         self
     }
 
+    // required, the logical block length of READ CAPACITY, see Scsi::capacity
     pub fn logical_block_size(&mut self, value: u32) -> &mut Self {
         self.logical_block_size = value;
         self
     }
 
+    // with a protection type, every block is followed by 8 bytes of protection information,
+    // see protection_information::blocks. read_protect must not be 0
+    pub fn protection(&mut self, value: Option<ProtectionType>) -> &mut Self {
+        self.protection = value;
+        self
+    }
+
+    // the bytes per block in the data buffer
+    fn transferred_block_size(&self) -> u32 {
+        transferred_block_size(self.logical_block_size, self.protection)
+    }
+
+    fn allocation_length(&self) -> u32 {
+        self.transferred_block_size()
+            .saturating_mul(self.transfer_length)
+    }
+
     fn common_check(
         &self,
         group_number_bits: u32,
@@ -136,8 +197,9 @@ impl<'a> ReadCommand<'a> {
         allow_dld: bool,
         expect_tag: bool,
     ) -> crate::Result<()> {
+        logical_block_size_check(self.logical_block_size)?;
         bitfield_bound_check!(self.read_protect, 3, "read protect")?;
-        bitfield_bound_check!(self.group_number, group_number_bits, "group number")?;
+        self.transfer_flags.check(group_number_bits, true)?;
         bitfield_bound_check!(
             self.logical_block_address,
             logical_block_address_bits,
@@ -148,11 +210,8 @@ impl<'a> ReadCommand<'a> {
             transfer_length_bits,
             "transfer length"
         )?;
-        bitfield_bound_check!(
-            (self.transfer_length as u64).saturating_mul(self.logical_block_size as u64),
-            32,
-            "total transfer bytes"
-        )?;
+        total_transfer_check(self.transfer_length, self.transferred_block_size())?;
+        protection_check(self.protection, self.read_protect, expect_tag)?;
 
         if !allow_dld && (self.dld_0 || self.dld_1 || self.dld_2) {
             return Err(crate::Error::BadArgument(
@@ -173,87 +232,216 @@ impl<'a> ReadCommand<'a> {
         Ok(())
     }
 
-    pub fn issue_10(&mut self) -> crate::Result<Vec<u8>> {
-        self.common_check(5, 32, 16, false, false)?;
+    timeout_setter!();
 
-        let command_buffer = CommandBuffer10::new()
-            .with_operation_code(OPERATION_CODE_10)
-            .with_read_protect(self.read_protect)
-            .with_disable_page_out(self.disable_page_out.into())
-            .with_force_unit_access(self.force_unit_access.into())
-            .with_rebuild_assist_recovery_control(self.rebuild_assist_recovery_control.into())
+    pub fn issue_6(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_6()?;
+        let (_, block_count) = transfer_length_6(self.transfer_length)?;
+        let allocation_length = self.transferred_block_size().saturating_mul(block_count);
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            allocation_length,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_6: issue_6 => command_buffer_6);
+
+    fn command_buffer_6(&self) -> crate::Result<CommandBuffer6> {
+        self.common_check(0, 21, 32, false, false)?;
+
+        if self.read_protect != 0
+            || self.transfer_flags.disable_page_out
+            || self.transfer_flags.force_unit_access
+            || self.rebuild_assist_recovery_control
+        {
+            return Err(crate::Error::BadArgument(
+                "read protect, DPO, FUA and RARC are not allowed here".to_owned(),
+            ));
+        }
+
+        let (transfer_length, block_count) = transfer_length_6(self.transfer_length)?;
+        total_transfer_check(block_count, self.transferred_block_size())?;
+
+        Ok(CommandBuffer6::new()
+            .with_operation_code(OPERATION_CODE_6)
             .with_logical_block_address(self.logical_block_address as u32)
-            .with_group_number(self.group_number)
-            .with_transfer_length(self.transfer_length as u16)
-            .with_control(self.control);
+            .with_transfer_length(transfer_length)
+            .with_control(self.control))
+    }
 
-        let allocation_length = self.logical_block_size.saturating_mul(self.transfer_length);
+    pub fn issue_10(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_10()?;
+        let allocation_length = self.allocation_length();
 
         self.interface.issue(&ThisCommand {
             command_buffer,
             allocation_length,
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_12(&mut self) -> crate::Result<Vec<u8>> {
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    pub fn issue_12(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_12()?;
+
+        let allocation_length = self.allocation_length();
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            allocation_length,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_12: issue_12 => command_buffer_12);
+
+    fn command_buffer_12(&self) -> crate::Result<CommandBuffer12> {
         self.common_check(5, 32, 32, false, false)?;
 
-        let command_buffer = CommandBuffer12::new()
+        Ok(CommandBuffer12::new()
             .with_operation_code(OPERATION_CODE_12)
             .with_read_protect(self.read_protect)
-            .with_disable_page_out(self.disable_page_out.into())
-            .with_force_unit_access(self.force_unit_access.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
+            .with_force_unit_access(self.transfer_flags.force_unit_access.into())
             .with_rebuild_assist_recovery_control(self.rebuild_assist_recovery_control.into())
             .with_logical_block_address(self.logical_block_address as u32)
-            .with_group_number(self.group_number)
+            .with_group_number(self.transfer_flags.group_number)
             .with_transfer_length(self.transfer_length)
-            .with_control(self.control);
+            .with_control(self.control))
+    }
 
-        let allocation_length = self.logical_block_size.saturating_mul(self.transfer_length);
+    pub fn issue_16(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_16()?;
+        let allocation_length = self.allocation_length();
 
         self.interface.issue(&ThisCommand {
             command_buffer,
             allocation_length,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
+
+    // reads into a buffer of the pool set with Scsi::set_buffer_pool, which goes back to the
+    // pool when the result is dropped
+    pub fn issue_16_pooled(&mut self) -> crate::Result<PooledCommandResult<'a>> {
+        let interface = self.interface;
+        let pool = interface.buffer_pool().ok_or_else(|| {
+            crate::Error::BadArgument("No buffer pool is set on the Scsi handle.".to_owned())
+        })?;
+
+        let command_buffer = self.command_buffer_16()?;
+        let allocation_length = self.allocation_length();
+
+        interface.issue(&PooledCommand {
+            command_buffer,
+            allocation_length,
+            pool,
+            timeout: self.timeout,
+        })
+    }
+
+    // reads into the caller's buffer instead of a fresh one, the buffer must be exactly
+    // transfer length times the logical block size long. With ScsiBuilder::direct it must
+    // also be aligned for DMA, a buffer of a BufferPool always is
+    pub fn issue_10_into(&mut self, buffer: &mut [u8]) -> crate::Result<IntoCommandResult> {
+        let command_buffer = self.command_buffer_10()?;
+        let buffer = self.caller_buffer(buffer)?;
+
+        self.interface.issue(&IntoCommand {
+            command_buffer,
+            buffer,
+            timeout: self.timeout,
+        })
+    }
+
+    // see issue_10_into
+    pub fn issue_16_into(&mut self, buffer: &mut [u8]) -> crate::Result<IntoCommandResult> {
+        let command_buffer = self.command_buffer_16()?;
+        let buffer = self.caller_buffer(buffer)?;
+
+        self.interface.issue(&IntoCommand {
+            command_buffer,
+            buffer,
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_16(&mut self) -> crate::Result<Vec<u8>> {
+    fn caller_buffer<'b>(&self, buffer: &'b mut [u8]) -> crate::Result<MutSliceBufferWrapper<'b>> {
+        let allocation_length = self.allocation_length();
+        if buffer.len() != allocation_length as usize {
+            return Err(crate::Error::BadArgument(format!(
+                "buffer length does not match transfer length. Expected {} bytes, but {} was provided.",
+                allocation_length,
+                buffer.len()
+            )));
+        }
+
+        Ok(MutSliceBufferWrapper::new(buffer))
+    }
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
+        self.common_check(5, 32, 16, false, false)?;
+
+        Ok(CommandBuffer10::new()
+            .with_operation_code(OPERATION_CODE_10)
+            .with_read_protect(self.read_protect)
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
+            .with_force_unit_access(self.transfer_flags.force_unit_access.into())
+            .with_rebuild_assist_recovery_control(self.rebuild_assist_recovery_control.into())
+            .with_logical_block_address(self.logical_block_address as u32)
+            .with_group_number(self.transfer_flags.group_number)
+            .with_transfer_length(self.transfer_length as u16)
+            .with_control(self.control))
+    }
+
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
         self.common_check(6, 64, 32, true, false)?;
 
-        let command_buffer = CommandBuffer16::new()
+        Ok(CommandBuffer16::new()
             .with_operation_code(OPERATION_CODE_16)
             .with_read_protect(self.read_protect)
-            .with_disable_page_out(self.disable_page_out.into())
-            .with_force_unit_access(self.force_unit_access.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
+            .with_force_unit_access(self.transfer_flags.force_unit_access.into())
             .with_rebuild_assist_recovery_control(self.rebuild_assist_recovery_control.into())
             .with_logical_block_address(self.logical_block_address)
-            .with_group_number(self.group_number)
+            .with_group_number(self.transfer_flags.group_number)
             .with_transfer_length(self.transfer_length)
             .with_dld_0(self.dld_0.into())
             .with_dld_1(self.dld_1.into())
             .with_dld_2(self.dld_2.into())
-            .with_control(self.control);
+            .with_control(self.control))
+    }
 
-        let allocation_length = self.logical_block_size.saturating_mul(self.transfer_length);
+    pub fn issue_32(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_32()?;
+
+        let allocation_length = self.allocation_length();
 
         self.interface.issue(&ThisCommand {
             command_buffer,
             allocation_length,
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_32(&mut self) -> crate::Result<Vec<u8>> {
+    cdb_bytes!(cdb_bytes_32: issue_32 => command_buffer_32);
+
+    fn command_buffer_32(&self) -> crate::Result<CommandBuffer32> {
         self.common_check(5, 64, 32, false, true)?;
 
-        let command_buffer = CommandBuffer32::new()
+        Ok(CommandBuffer32::new()
             .with_operation_code(OPERATION_CODE_32)
             .with_control(self.control)
-            .with_group_number(self.group_number)
-            .with_additional_cdb_length(0x18)
+            .with_group_number(self.transfer_flags.group_number)
             .with_service_action(SERVICE_ACTION_32)
             .with_read_protect(self.read_protect)
-            .with_disable_page_out(self.disable_page_out.into())
-            .with_force_unit_access(self.force_unit_access.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
+            .with_force_unit_access(self.transfer_flags.force_unit_access.into())
             .with_rebuild_assist_recovery_control(self.rebuild_assist_recovery_control.into())
             .with_logical_block_address(self.logical_block_address)
             .with_expected_initial_logical_block_reference_tag(
@@ -263,29 +451,160 @@ impl<'a> ReadCommand<'a> {
                 self.expected_logical_block_application_tag,
             )
             .with_logical_block_application_tag_mask(self.logical_block_application_tag_mask)
-            .with_transfer_length(self.transfer_length);
-
-        let allocation_length = self.logical_block_size.saturating_mul(self.transfer_length);
+            .with_transfer_length(self.transfer_length))
+    }
+}
 
-        self.interface.issue(&ThisCommand {
-            command_buffer,
-            allocation_length,
-        })
+// a transfer length of 0 means 256 logical blocks for read (6), so both 0 and 256 are accepted
+fn transfer_length_6(transfer_length: u32) -> crate::Result<(u8, u32)> {
+    match transfer_length {
+        0 | 256 => Ok((0, 256)),
+        1..=255 => Ok((transfer_length as u8, transfer_length)),
+        _ => Err(crate::Error::ArgumentOutOfBounds(format!(
+            "transfer length is out of bounds. The maximum possible value is 256, but {} was provided.",
+            transfer_length
+        ))),
     }
 }
 
+// both sg and SPTD take the data length as 32 bits
+fn total_transfer_check(transfer_length: u32, logical_block_size: u32) -> crate::Result<()> {
+    bitfield_bound_check!(
+        (transfer_length as u64).saturating_mul(logical_block_size as u64),
+        32,
+        "total transfer bytes"
+    )
+}
+
 impl Scsi {
-    pub fn read(&self) -> ReadCommand {
+    pub fn read(&self) -> ReadCommand<'_> {
         ReadCommand::new(self)
     }
+
+    /// Reads `block_count` blocks with READ (10), or READ (16) if the range or the device
+    /// needs it, see [`Scsi::supported_commands`].
+    pub fn read_blocks(
+        &self,
+        logical_block_address: u64,
+        block_count: u32,
+        logical_block_size: u32,
+    ) -> crate::Result<CommandResult> {
+        let mut command = self.read();
+        command
+            .logical_block_address(logical_block_address)
+            .transfer_length(block_count)
+            .logical_block_size(logical_block_size);
+
+        match needs_16_byte_cdb(self, OPERATION_CODE_10, logical_block_address, block_count)? {
+            true => command.issue_16(),
+            false => command.issue_10(),
+        }
+    }
+
+    /// Reads `block_count` blocks into `buffer` like [`Scsi::read_blocks`], without allocating
+    /// a buffer of its own.
+    ///
+    /// `buffer` must hold exactly `block_count` blocks. After a short read the residual counts
+    /// the bytes at its end that were left as they were.
+    pub fn read_into(
+        &self,
+        logical_block_address: u64,
+        block_count: u32,
+        logical_block_size: u32,
+        buffer: &mut [u8],
+    ) -> crate::Result<IntoCommandResult> {
+        let mut command = self.read();
+        command
+            .logical_block_address(logical_block_address)
+            .transfer_length(block_count)
+            .logical_block_size(logical_block_size);
+
+        match needs_16_byte_cdb(self, OPERATION_CODE_10, logical_block_address, block_count)? {
+            true => command.issue_16_into(buffer),
+            false => command.issue_10_into(buffer),
+        }
+    }
+
+    /// Reads `total_blocks` blocks with one READ (16) per `chunk_blocks`, handing every chunk
+    /// to `callback` with its byte offset from `logical_block_address`.
+    ///
+    /// Only one chunk is held at a time, which keeps imaging a whole device from allocating its
+    /// size. The last chunk is shorter if `chunk_blocks` doesn't divide `total_blocks`. Reading
+    /// stops after `callback` returns [`ControlFlow::Break`], or at the first failed or short
+    /// read.
+    pub fn read_stream<F>(
+        &self,
+        logical_block_address: u64,
+        total_blocks: u64,
+        logical_block_size: u32,
+        chunk_blocks: u32,
+        mut callback: F,
+    ) -> crate::Result<()>
+    where
+        F: FnMut(u64, &[u8]) -> ControlFlow<()>,
+    {
+        if chunk_blocks == 0 {
+            return Err(crate::Error::BadArgument(
+                "chunk blocks should not be 0.".to_owned(),
+            ));
+        }
+
+        if logical_block_address.checked_add(total_blocks).is_none() {
+            return Err(crate::Error::ArgumentOutOfBounds(format!(
+                "logical block range is out of bounds. {} blocks at {} go past the last possible logical block address.",
+                total_blocks, logical_block_address
+            )));
+        }
+
+        let mut done = 0;
+        while done < total_blocks {
+            let blocks = u64::min(total_blocks - done, chunk_blocks as u64) as u32;
+            let result = self
+                .read()
+                .logical_block_address(logical_block_address + done)
+                .transfer_length(blocks)
+                .logical_block_size(logical_block_size)
+                .issue_16()?;
+
+            if result.residual != 0 {
+                return Err(crate::Error::Other(format!(
+                    "short read of {} blocks at logical block address {}, {} bytes are missing.",
+                    blocks,
+                    logical_block_address + done,
+                    result.residual
+                )));
+            }
+
+            if callback(done * logical_block_size as u64, &result.data).is_break() {
+                break;
+            }
+
+            done += blocks as u64;
+        }
+
+        Ok(())
+    }
 }
 
+crate::command::async_issue!(read: ReadCommand);
+
+const OPERATION_CODE_6: u8 = 0x08;
 const OPERATION_CODE_10: u8 = 0x28;
 const OPERATION_CODE_12: u8 = 0xA8;
 const OPERATION_CODE_16: u8 = 0x88;
-const OPERATION_CODE_32: u8 = 0x7F;
+const OPERATION_CODE_32: u8 = VARIABLE_LENGTH_OPERATION_CODE;
 const SERVICE_ACTION_32: u16 = 0x0009;
 
+#[bitfield]
+#[derive(Clone, Copy)]
+struct CommandBuffer6 {
+    operation_code: B8,
+    reserved: B3,
+    logical_block_address: B21,
+    transfer_length: B8,
+    control: B8,
+}
+
 #[bitfield]
 #[derive(Clone, Copy)]
 struct CommandBuffer10 {
@@ -363,6 +682,7 @@ struct CommandBuffer32 {
 struct ThisCommand<C> {
     command_buffer: C,
     allocation_length: u32,
+    timeout: Option<Duration>,
 }
 
 impl<C: Copy> Command for ThisCommand<C> {
@@ -372,7 +692,7 @@ impl<C: Copy> Command for ThisCommand<C> {
 
     type DataBufferWrapper = VecBufferWrapper;
 
-    type ReturnType = crate::Result<Vec<u8>>;
+    type ReturnType = crate::Result<CommandResult>;
 
     fn direction(&self) -> DataDirection {
         DataDirection::FromDevice
@@ -390,19 +710,136 @@ impl<C: Copy> Command for ThisCommand<C> {
         self.allocation_length
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        let residual = result.data_in_residual(result.data.len());
+        let sense = result.check_sense().cloned();
+        Ok(parse(std::mem::take(result.data).0, residual, sense))
+    }
+}
+
+struct PooledCommand<'a, C> {
+    command_buffer: C,
+    allocation_length: u32,
+    pool: &'a BufferPool,
+    timeout: Option<Duration>,
+}
+
+impl<'a, C: Copy> Command for PooledCommand<'a, C> {
+    type CommandBuffer = C;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = PooledBuffer<'a>;
+
+    type ReturnType = crate::Result<PooledCommandResult<'a>>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::FromDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        self.pool.get(self.allocation_length as usize)
+    }
+
+    fn data_size(&self) -> u32 {
+        self.allocation_length
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        let mut data = std::mem::replace(result.data, PooledBuffer::empty(self.pool));
+        let residual = result.data_in_residual(data.len());
+        data.truncate(data.len() - residual);
+
+        Ok(PooledCommandResult { data, residual })
+    }
+}
+
+struct IntoCommand<'a, C> {
+    command_buffer: C,
+    buffer: MutSliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
+}
+
+impl<'a, C: Copy> Command for IntoCommand<'a, C> {
+    type CommandBuffer = C;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = MutSliceBufferWrapper<'a>;
+
+    type ReturnType = crate::Result<IntoCommandResult>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::FromDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        self.buffer
+    }
+
+    fn data_size(&self) -> u32 {
+        self.buffer.len() as u32
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        Ok(std::mem::take(result.data).0)
+        Ok(IntoCommandResult {
+            residual: result.data_in_residual(self.buffer.len()),
+        })
+    }
+}
+
+// residual is at most the length of data
+fn parse(mut data: Vec<u8>, residual: usize, sense: Option<SenseData>) -> CommandResult {
+    data.truncate(data.len() - residual);
+
+    CommandResult {
+        data,
+        residual,
+        sense,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::mem::size_of;
+    use crate::{
+        command::fill_additional_cdb_length,
+        counting_allocator::allocations,
+        os::{Backend, Request, Response},
+        MockResponse, MockScsi,
+    };
+    use std::{mem::size_of, path::Path};
 
+    const COMMAND_LENGTH_6: usize = 6;
     const COMMAND_LENGTH_10: usize = 10;
     const COMMAND_LENGTH_12: usize = 12;
     const COMMAND_LENGTH_16: usize = 16;
@@ -410,6 +847,12 @@ mod tests {
 
     #[test]
     fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer6>(),
+            COMMAND_LENGTH_6,
+            concat!("Size of: ", stringify!(CommandBuffer6))
+        );
+
         assert_eq!(
             size_of::<CommandBuffer10>(),
             COMMAND_LENGTH_10,
@@ -434,4 +877,646 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer32))
         );
     }
+
+    #[test]
+    fn short_read_test() {
+        let result = parse(vec![0xAA; 1024], 512, None);
+        assert_eq!(result.data, [0xAA; 512], "transferred data");
+        assert_eq!(result.residual, 512, "residual");
+
+        let result = parse(vec![0xAA; 1024], 0, None);
+        assert_eq!(result.data.len(), 1024, "complete read");
+        assert_eq!(result.residual, 0, "no residual");
+    }
+
+    #[test]
+    fn residual_test() {
+        let mock = MockScsi::new();
+        // the device returned one of the two blocks
+        mock.expect(
+            &[0x28, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x02, 0x00],
+            MockResponse::good().data(&[0xA5; 512]),
+        )
+        // the adapter reports a residual although the whole buffer was written
+        .expect(
+            &[0x28, 0x00, 0x00, 0x00, 0x00, 0x12, 0x00, 0x00, 0x02, 0x00],
+            MockResponse::good().data(&[0xA5; 1024]).residual(512),
+        )
+        .expect(
+            &[0x28, 0x00, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x02, 0x00],
+            MockResponse::good().data(&[0xA5; 1024]),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let read = |logical_block_address| {
+            scsi.read()
+                .logical_block_address(logical_block_address)
+                .transfer_length(2)
+                .logical_block_size(512)
+                .issue_10()
+                .unwrap()
+        };
+
+        let result = read(0x10);
+        assert_eq!(result.data, [0xA5; 512], "short read");
+        assert_eq!(result.residual, 512, "short read residual");
+
+        let result = read(0x12);
+        assert_eq!(result.data.len(), 512, "residual of the adapter");
+        assert_eq!(result.residual, 512, "adapter residual");
+
+        let result = read(0x14);
+        assert_eq!(result.data.len(), 1024, "complete read");
+        assert_eq!(result.residual, 0, "no residual");
+        mock.verify();
+    }
+
+    #[test]
+    fn recovered_error_test() {
+        use crate::command::sense::SenseKey;
+
+        const READ_10: [u8; 10] = [0x28, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x01, 0x00];
+        // RECOVERED ERROR, RECOVERED DATA WITH RETRIES
+        const SENSE: [u8; 18] = [
+            0x70, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x17, 0x01,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        // MEDIUM ERROR, UNRECOVERED READ ERROR
+        let mut medium_error = SENSE;
+        medium_error[2] = 0x03;
+        medium_error[12] = 0x11;
+        medium_error[13] = 0x00;
+
+        let recovered = MockResponse::check_condition(&SENSE).data(&[0xA5; 512]);
+        // SG_IO sets DRIVER_SENSE whenever it returns sense data
+        #[cfg(target_os = "linux")]
+        let recovered = recovered.driver_status(0x08);
+
+        let mock = MockScsi::new();
+        mock.expect(&READ_10, recovered)
+            .expect(&READ_10, MockResponse::good().data(&[0x5A; 512]))
+            .expect(&READ_10, MockResponse::check_condition(&medium_error));
+
+        let scsi = Scsi::mock(mock.clone());
+        let read = || {
+            scsi.read()
+                .logical_block_address(0x10)
+                .transfer_length(1)
+                .logical_block_size(512)
+                .issue_10()
+        };
+
+        let result = read().unwrap();
+        assert_eq!(result.data, [0xA5; 512], "recovered data");
+        assert!(
+            matches!(
+                result.check_sense().and_then(SenseData::sense_key),
+                Some(SenseKey::RecoveredError)
+            ),
+            "recovered error sense"
+        );
+
+        let result = read().unwrap();
+        assert_eq!(result.data, [0x5A; 512], "data");
+        assert!(result.sense.is_none(), "no sense");
+
+        assert!(
+            matches!(read(), Err(crate::Error::MediumError(_))),
+            "medium error"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    fn total_transfer_test() {
+        assert!(
+            total_transfer_check(0x007F_FFFF, 512).is_ok(),
+            "largest 512 bytes transfer"
+        );
+        assert!(
+            matches!(
+                total_transfer_check(0x0080_0000, 512),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "4 GiB transfer"
+        );
+        assert!(
+            matches!(
+                total_transfer_check(u32::MAX, 4096),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "maximum read (16) transfer length"
+        );
+    }
+
+    #[test]
+    fn command_buffer_12_test() {
+        let command_buffer = CommandBuffer12::new()
+            .with_operation_code(OPERATION_CODE_12)
+            .with_read_protect(0b001)
+            .with_disable_page_out(1)
+            .with_force_unit_access(1)
+            .with_logical_block_address(0x1234_5678)
+            .with_transfer_length(0x0001_0000)
+            .with_group_number(0x1F);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0xA8, 0x38, 0x12, 0x34, 0x56, 0x78, 0x00, 0x01, 0x00, 0x00, 0x1F, 0x00],
+            "read (12) command"
+        );
+    }
+
+    #[test]
+    fn transfer_length_6_test() {
+        assert_eq!(
+            transfer_length_6(0).unwrap(),
+            (0, 256),
+            "0 means 256 blocks"
+        );
+        assert_eq!(transfer_length_6(256).unwrap(), (0, 256), "256 blocks");
+        assert_eq!(transfer_length_6(1).unwrap(), (1, 1), "single block");
+        assert_eq!(transfer_length_6(255).unwrap(), (255, 255), "255 blocks");
+        assert!(
+            matches!(
+                transfer_length_6(257),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "too many blocks"
+        );
+
+        let command_buffer = CommandBuffer6::new()
+            .with_operation_code(OPERATION_CODE_6)
+            .with_logical_block_address(0x1F_FFFF)
+            .with_transfer_length(transfer_length_6(256).unwrap().0);
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x08, 0x1F, 0xFF, 0xFF, 0x00, 0x00],
+            "read (6) command"
+        );
+    }
+
+    #[test]
+    fn command_buffer_32_test() {
+        let mut bytes = CommandBuffer32::new()
+            .with_operation_code(OPERATION_CODE_32)
+            .with_service_action(SERVICE_ACTION_32)
+            .with_transfer_length(1)
+            .into_bytes();
+        assert_eq!(bytes.len(), COMMAND_LENGTH_32, "read (32) length");
+        assert_eq!(bytes[7], 0x00, "additional cdb length before issue");
+
+        fill_additional_cdb_length(&mut bytes);
+        assert_eq!(
+            bytes[..10],
+            [0x7F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00, 0x09],
+            "read (32) header"
+        );
+        assert_eq!(bytes[28..], [0x00, 0x00, 0x00, 0x01], "transfer length");
+
+        // fixed length CDBs are left alone
+        let mut bytes = [0x28, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00];
+        fill_additional_cdb_length(&mut bytes);
+        assert_eq!(bytes[7], 0x00, "read (10)");
+    }
+
+    #[test]
+    fn cdb_bytes_test() {
+        const READ_16: [u8; 16] = [
+            0x88, 0x08, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08,
+            0x00, 0x00,
+        ];
+
+        let mock = MockScsi::new();
+        mock.expect(&READ_16, MockResponse::good().data(&[0; 8 * 512]));
+        let scsi = Scsi::mock(mock.clone());
+
+        let mut command = scsi.read();
+        command
+            .logical_block_address(0x1_0000_0000)
+            .transfer_length(8)
+            .logical_block_size(512)
+            .force_unit_access(true);
+        assert_eq!(command.cdb_bytes_16().unwrap(), READ_16, "read (16)");
+        assert!(
+            matches!(
+                command.cdb_bytes_10(),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "logical block address beyond 32 bits"
+        );
+        let cdb = command.cdb_bytes_32().unwrap();
+        assert_eq!(
+            cdb[..10],
+            [0x7F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00, 0x09],
+            "read (32) header with the additional cdb length"
+        );
+
+        // nothing was sent until now, and the CDB is the one issued
+        assert!(mock.issued().is_empty(), "nothing issued");
+        command.issue_16().unwrap();
+        assert_eq!(mock.issued()[0].command, READ_16, "issued read (16)");
+        mock.verify();
+    }
+
+    fn read_10(logical_block_address: u32, transfer_length: u16) -> Vec<u8> {
+        let mut command = vec![OPERATION_CODE_10, 0x00];
+        command.extend_from_slice(&logical_block_address.to_be_bytes());
+        command.push(0x00);
+        command.extend_from_slice(&transfer_length.to_be_bytes());
+        command.push(0x00);
+        command
+    }
+
+    fn read_16(logical_block_address: u64, transfer_length: u32) -> Vec<u8> {
+        let mut command = vec![OPERATION_CODE_16, 0x00];
+        command.extend_from_slice(&logical_block_address.to_be_bytes());
+        command.extend_from_slice(&transfer_length.to_be_bytes());
+        command.extend_from_slice(&[0x00, 0x00]);
+        command
+    }
+
+    // fills every read with 0xA5. The mock allocates to record every command, so the tests
+    // that count allocations read from this instead
+    #[derive(Debug)]
+    struct PatternBackend;
+
+    impl Backend for PatternBackend {
+        fn issue(&self, request: Request) -> Response {
+            unsafe { request.data.write_bytes(0xA5, request.data_length as usize) };
+
+            Response {
+                ioctl_result: 0,
+                os_error: 0,
+                transfered_data_length: request.data_length as usize,
+                residual: 0,
+                sense_length: 0,
+                status: 0x00,
+                duration: None,
+                #[cfg(target_os = "linux")]
+                host_status: 0,
+                #[cfg(target_os = "linux")]
+                driver_status: crate::os::linux::DriverStatus::OK,
+            }
+        }
+    }
+
+    #[test]
+    fn pooled_test() {
+        const ROUNDS: usize = 100;
+
+        let mut scsi = Scsi::with_backend(Path::new("/dev/null"), Box::new(PatternBackend));
+        assert!(
+            scsi.read()
+                .transfer_length(8)
+                .logical_block_size(512)
+                .issue_16_pooled()
+                .is_err(),
+            "no buffer pool"
+        );
+        scsi.set_buffer_pool(BufferPool::with_alignment(512));
+
+        let before = allocations();
+        for _ in 0..ROUNDS {
+            scsi.read()
+                .transfer_length(8)
+                .logical_block_size(512)
+                .issue_16()
+                .unwrap();
+        }
+        let unpooled = allocations() - before;
+
+        // the first read grows the pool to the transfer size
+        drop(
+            scsi.read()
+                .transfer_length(8)
+                .logical_block_size(512)
+                .issue_16_pooled()
+                .unwrap(),
+        );
+
+        let before = allocations();
+        for _ in 0..ROUNDS {
+            let result = scsi
+                .read()
+                .transfer_length(8)
+                .logical_block_size(512)
+                .issue_16_pooled()
+                .unwrap();
+            assert_eq!(result.data.len(), 4096, "data length");
+            assert_eq!(result.data.as_ptr() as usize % 512, 0, "aligned");
+            assert!(result.data.iter().all(|&b| b == 0xA5), "data");
+        }
+        let pooled = allocations() - before;
+
+        assert!(unpooled >= ROUNDS, "one buffer per unpooled read");
+        assert_eq!(pooled, 0, "no allocation with a warm pool");
+    }
+
+    #[test]
+    fn read_blocks_test() {
+        // the first LBA past 2 TiB of 512 byte blocks
+        const LBA_2TIB: u64 = 1 << 32;
+
+        // the smallest sufficient CDB
+        let mock = MockScsi::new();
+        mock.expect(&read_10(0, 2), MockResponse::good().data(&[0xA5; 1024]))
+            .expect(
+                &read_10((LBA_2TIB - 1) as u32, 1),
+                MockResponse::good().data(&[0xA5; 512]),
+            )
+            .expect(
+                &read_16(LBA_2TIB, 1),
+                MockResponse::good().data(&[0xA5; 512]),
+            )
+            // more blocks than READ (10) can carry
+            .expect(&read_16(0, 0x10000), MockResponse::good().residual(0));
+
+        let scsi = Scsi::mock(mock.clone());
+        let result = scsi.read_blocks(0, 2, 512).unwrap();
+        assert_eq!(result.data, [0xA5; 1024], "data");
+        assert_eq!(result.residual, 0, "residual");
+        scsi.read_blocks(LBA_2TIB - 1, 1, 512).unwrap();
+        scsi.read_blocks(LBA_2TIB, 1, 512).unwrap();
+        scsi.read_blocks(0, 0x10000, 512).unwrap();
+        assert!(
+            scsi.read_blocks(u64::MAX, 2, 512).is_err(),
+            "past the last LBA"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    fn transfer_flags_test() {
+        let scsi = Scsi::mock(MockScsi::new());
+        let mut flags = TransferFlags::new();
+        flags
+            .disable_page_out(true)
+            .force_unit_access(true)
+            .group_number(0x15);
+
+        let mut command = scsi.read();
+        command.logical_block_size(512).transfer_flags(flags);
+
+        let bytes = command.command_buffer_10().unwrap().into_bytes();
+        assert_eq!(bytes[1], 0b0001_1000, "read (10) DPO and FUA");
+        assert_eq!(bytes[6], 0x15, "read (10) group number");
+
+        let bytes = command.command_buffer_16().unwrap().into_bytes();
+        assert_eq!(bytes[1], 0b0001_1000, "read (16) DPO and FUA");
+        assert_eq!(bytes[14], 0x15, "read (16) group number");
+
+        command.group_number(0x3F);
+        assert!(
+            command.command_buffer_10().is_err(),
+            "read (10) group number is 5 bits"
+        );
+        assert_eq!(
+            command.command_buffer_16().unwrap().into_bytes()[14],
+            0x3F,
+            "read (16) group number is 6 bits"
+        );
+        command.group_number(0x40);
+        assert!(
+            command.command_buffer_16().is_err(),
+            "group number too large"
+        );
+
+        let mut command = scsi.verify();
+        command.logical_block_size(512).transfer_flags(flags);
+        assert!(
+            matches!(command.issue_16(), Err(crate::Error::BadArgument(_))),
+            "verify has no FUA"
+        );
+    }
+
+    #[test]
+    fn protection_test() {
+        let mock = MockScsi::new();
+        let mut read_16 = read_16(0, 8);
+        // RDPROTECT 1
+        read_16[1] = 0x20;
+        mock.expect(&read_16, MockResponse::good().data(&[0xA5; 8 * 520]))
+            .expect(
+                &[
+                    0x7F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00, 0x09, 0x20, 0x00, 0x00,
+                    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                    0x00, 0x00, 0x00, 0x00, 0x00, 0x08,
+                ],
+                MockResponse::good().data(&[0xA5; 8 * 520]),
+            );
+
+        let scsi = Scsi::mock(mock.clone());
+        let mut command = scsi.read();
+        command.transfer_length(8).logical_block_size(512);
+        assert_eq!(command.allocation_length(), 4096, "without protection");
+
+        command.read_protect(1);
+        assert!(
+            matches!(command.issue_16(), Err(crate::Error::BadArgument(_))),
+            "read protect needs a protection type"
+        );
+        command.read_protect(0);
+
+        command.protection(Some(ProtectionType::Type1));
+        assert_eq!(command.allocation_length(), 8 * 520, "with protection");
+        assert!(
+            command.command_buffer_16().is_err(),
+            "protection needs read protect"
+        );
+
+        command.read_protect(1);
+        assert_eq!(
+            command.command_buffer_16().unwrap().into_bytes()[1] >> 5,
+            1,
+            "read protect"
+        );
+        assert_eq!(
+            command.issue_16().unwrap().data.len(),
+            8 * 520,
+            "data and protection information"
+        );
+
+        command.protection(Some(ProtectionType::Type2));
+        assert!(
+            command.command_buffer_16().is_err(),
+            "type 2 needs read (32)"
+        );
+        assert!(command.issue_32().is_ok(), "type 2 with read (32)");
+        mock.verify();
+    }
+
+    #[test]
+    fn logical_block_size_test() {
+        let mock = MockScsi::new();
+        mock.expect(&read_16(0, 8), MockResponse::good().data(&[0xA5; 8 * 4096]));
+        let scsi = Scsi::mock(mock.clone());
+
+        assert!(
+            matches!(
+                scsi.read().transfer_length(8).issue_16(),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "read without a logical block size"
+        );
+        assert!(
+            matches!(
+                scsi.write().parameter(&[0; 4096]).issue_16(),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "write without a logical block size"
+        );
+        assert!(
+            matches!(
+                scsi.verify().parameter(&[0; 4096]).byte_check(1).issue_16(),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "verify without a logical block size"
+        );
+
+        let result = scsi
+            .read()
+            .transfer_length(8)
+            .logical_block_size(4096)
+            .issue_16()
+            .unwrap();
+        assert_eq!(result.data.len(), 8 * 4096, "4Kn blocks");
+        mock.verify();
+    }
+
+    #[test]
+    fn read_stream_test() {
+        // every byte of a block is the low byte of its LBA
+        fn blocks(logical_block_address: u64, count: u64) -> Vec<u8> {
+            (logical_block_address..logical_block_address + count)
+                .flat_map(|lba| [lba as u8; 512])
+                .collect()
+        }
+
+        let mock = MockScsi::new();
+        for (lba, count) in [(10, 4), (14, 4), (18, 2), (0, 4), (90, 4), (94, 4)] {
+            mock.expect(
+                &read_16(lba, count as u32),
+                MockResponse::good().data(&blocks(lba, count)),
+            );
+        }
+        // LBA 100 and later can't be read, MEDIUM ERROR, UNRECOVERED READ ERROR
+        mock.expect(
+            &read_16(98, 4),
+            MockResponse::check_condition(&[
+                0x70, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x11, 0x00,
+                0x00, 0x00, 0x00, 0x00,
+            ]),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+
+        let mut chunks = vec![];
+        scsi.read_stream(10, 10, 512, 4, |offset, data| {
+            assert!(
+                data.chunks(512).enumerate().all(|(index, block)| block
+                    .iter()
+                    .all(|&byte| byte as u64 == 10 + offset / 512 + index as u64)),
+                "data at offset {}",
+                offset
+            );
+            chunks.push((offset, data.len()));
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(
+            chunks,
+            [(0, 4 * 512), (4 * 512, 4 * 512), (8 * 512, 2 * 512)],
+            "three chunks, the last one short"
+        );
+
+        let mut calls = 0;
+        scsi.read_stream(0, 10, 512, 4, |_, _| {
+            calls += 1;
+            ControlFlow::Break(())
+        })
+        .unwrap();
+        assert_eq!(calls, 1, "stopped early");
+
+        let mut calls = 0;
+        let result = scsi.read_stream(90, 20, 512, 4, |_, _| {
+            calls += 1;
+            ControlFlow::Continue(())
+        });
+        assert!(
+            matches!(result, Err(crate::Error::MediumError(_))),
+            "error mid-stream"
+        );
+        assert_eq!(calls, 2, "chunks before the error");
+
+        assert!(
+            scsi.read_stream(0, 10, 512, 0, |_, _| ControlFlow::Continue(()))
+                .is_err(),
+            "empty chunks"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    fn issue_16_into_allocation_test() {
+        let scsi = Scsi::with_backend(Path::new("/dev/null"), Box::new(PatternBackend));
+        let mut buffer = [0; 8 * 512];
+
+        // the CDB, the data wrapper and the sense buffer all live on the stack
+        let before = allocations();
+        for lba in 0..16 {
+            let result = scsi
+                .read()
+                .logical_block_address(lba * 8)
+                .transfer_length(8)
+                .logical_block_size(512)
+                .issue_16_into(&mut buffer)
+                .unwrap();
+            assert_eq!(result.residual, 0, "complete read");
+        }
+        assert_eq!(allocations() - before, 0, "allocations of READ (16)");
+        assert!(buffer.iter().all(|&b| b == 0xA5), "buffer filled");
+    }
+
+    #[test]
+    fn read_into_test() {
+        let scsi = Scsi::with_backend(Path::new("/dev/null"), Box::new(PatternBackend));
+        let mut buffer = vec![0; 4 * 512];
+
+        let before = allocations();
+        let result = scsi.read_into(0, 4, 512, &mut buffer).unwrap();
+        assert_eq!(allocations(), before, "no allocation");
+        assert_eq!(result.residual, 0, "complete read");
+        assert!(buffer.iter().all(|&b| b == 0xA5), "buffer filled");
+
+        assert!(
+            scsi.read_into(0, 3, 512, &mut buffer).is_err(),
+            "buffer longer than the transfer"
+        );
+        assert!(
+            scsi.read_into(0, 5, 512, &mut buffer).is_err(),
+            "buffer shorter than the transfer"
+        );
+
+        // the device stops after the first half
+        let mock = MockScsi::new();
+        mock.expect(&read_16(0, 4), MockResponse::good().data(&[0x5A; 2 * 512]));
+        let scsi = Scsi::mock(mock.clone());
+        let result = scsi
+            .read()
+            .transfer_length(4)
+            .logical_block_size(512)
+            .issue_16_into(&mut buffer)
+            .unwrap();
+        assert_eq!(result.residual, 2 * 512, "short read");
+        assert!(
+            buffer[..2 * 512].iter().all(|&b| b == 0x5A),
+            "transferred part"
+        );
+        assert!(
+            buffer[2 * 512..].iter().all(|&b| b == 0xA5),
+            "rest left as it was"
+        );
+        mock.verify();
+    }
 }
@@ -0,0 +1,393 @@
+#![allow(dead_code)]
+
+use std::{mem::size_of, time::Duration};
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{
+        bitfield_bound_check, cdb_bytes, get_array, issue_full_length, timeout_setter, Control,
+    },
+    data_wrapper::{AnyType, VecBufferWrapper},
+    result_data::ResultData,
+    util::be_u32,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct ReadAttributeCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    service_action: ServiceAction,
+    element_type: u8,
+    command_buffer: CommandBuffer,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ServiceAction {
+    AttributeValues,
+    AttributeList,
+    LogicalVolumeList,
+    PartitionList,
+    SupportedAttributes,
+    Other(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AttributeFormat {
+    Binary,
+    Ascii,
+    Text,
+    Reserved,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CommandResult {
+    AttributeValues(AttributeValuesData),
+    Raw(Vec<u8>),
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttributeValuesData {
+    pub required_length: u32,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attribute {
+    pub attribute_identifier: u16,
+    pub read_only: bool,
+    pub format: AttributeFormat,
+    pub value: Vec<u8>,
+}
+
+impl<'a> ReadAttributeCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            service_action: ServiceAction::AttributeValues,
+            element_type: 0,
+            command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
+        }
+    }
+
+    // service_action must be less than 0x20
+    pub fn service_action(&mut self, value: ServiceAction) -> &mut Self {
+        self.service_action = value;
+        self
+    }
+
+    // only used by media changers
+    pub fn element_address(&mut self, value: u16) -> &mut Self {
+        self.command_buffer.set_element_address(value);
+        self
+    }
+
+    // element_type must be less than 0x10, only used by media changers
+    pub fn element_type(&mut self, value: u8) -> &mut Self {
+        self.element_type = value;
+        self
+    }
+
+    pub fn logical_volume_number(&mut self, value: u8) -> &mut Self {
+        self.command_buffer.set_logical_volume_number(value);
+        self
+    }
+
+    pub fn partition_number(&mut self, value: u8) -> &mut Self {
+        self.command_buffer.set_partition_number(value);
+        self
+    }
+
+    pub fn first_attribute_identifier(&mut self, value: u16) -> &mut Self {
+        self.command_buffer.set_first_attribute_identifier(value);
+        self
+    }
+
+    pub fn allocation_length(&mut self, value: u32) -> &mut Self {
+        self.command_buffer.set_allocation_length(value);
+        self
+    }
+
+    pub fn cache(&mut self, value: bool) -> &mut Self {
+        self.command_buffer.set_cache(value.into());
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<CommandResult> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            service_action: self.service_action,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(u8::from(self.service_action), 5, "service action")?;
+        bitfield_bound_check!(self.element_type, 4, "element type")?;
+
+        Ok(self
+            .command_buffer
+            .with_service_action(self.service_action.into())
+            .with_element_type(self.element_type))
+    }
+
+    // issues again with the allocation length reported by the available data if the first
+    // response was truncated, the allocation length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<CommandResult> {
+        let allocation_length = self.command_buffer.allocation_length() as usize;
+        issue_full_length(
+            self,
+            allocation_length,
+            u32::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u32);
+            },
+            Self::issue,
+            CommandResult::required_length,
+        )
+    }
+}
+
+impl CommandResult {
+    /// The allocation length needed for the whole response, as its header reports it
+    pub fn required_length(&self) -> usize {
+        match self {
+            Self::AttributeValues(data) => data.required_length as usize,
+            // the available data of the other service actions differs in width
+            Self::Raw(bytes) => bytes.len(),
+        }
+    }
+}
+
+impl Scsi {
+    pub fn read_attribute(&self) -> ReadAttributeCommand<'_> {
+        ReadAttributeCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(read_attribute: ReadAttributeCommand);
+
+impl From<ServiceAction> for u8 {
+    fn from(value: ServiceAction) -> Self {
+        match value {
+            ServiceAction::AttributeValues => 0x00,
+            ServiceAction::AttributeList => 0x01,
+            ServiceAction::LogicalVolumeList => 0x02,
+            ServiceAction::PartitionList => 0x03,
+            ServiceAction::SupportedAttributes => 0x05,
+            ServiceAction::Other(x) => x,
+        }
+    }
+}
+
+impl From<u8> for AttributeFormat {
+    fn from(value: u8) -> Self {
+        match value {
+            0b00 => AttributeFormat::Binary,
+            0b01 => AttributeFormat::Ascii,
+            0b10 => AttributeFormat::Text,
+            _ => AttributeFormat::Reserved,
+        }
+    }
+}
+
+impl From<AttributeFormat> for u8 {
+    fn from(value: AttributeFormat) -> Self {
+        match value {
+            AttributeFormat::Binary => 0b00,
+            AttributeFormat::Ascii => 0b01,
+            AttributeFormat::Text => 0b10,
+            AttributeFormat::Reserved => 0b11,
+        }
+    }
+}
+
+impl AttributeValuesData {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let available_data = be_u32(bytes).unwrap_or(0);
+        let bytes = bytes.get(4..).unwrap_or_default();
+
+        let mut bytes = &bytes[..usize::min(available_data as usize, bytes.len())];
+        let mut attributes = vec![];
+
+        while bytes.len() >= size_of::<AttributeHeader>() {
+            let (array, left) = get_array(bytes);
+            let header = AttributeHeader::from_bytes(array);
+            let length = usize::min(header.attribute_length() as usize, left.len());
+
+            attributes.push(Attribute {
+                attribute_identifier: header.attribute_identifier(),
+                read_only: header.read_only() != 0,
+                format: header.format().into(),
+                value: Vec::from(&left[..length]),
+            });
+
+            bytes = &left[length..];
+        }
+
+        Self {
+            required_length: available_data.saturating_add(4),
+            attributes,
+        }
+    }
+}
+
+const OPERATION_CODE: u8 = 0x8C;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    element_address: B16,
+    reserved_1: B4,
+    element_type: B4,
+    logical_volume_number: B8,
+    reserved_2: B8,
+    partition_number: B8,
+    first_attribute_identifier: B16,
+    allocation_length: B32,
+    reserved_3: B7,
+    cache: B1,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+pub(super) struct AttributeHeader {
+    pub(super) attribute_identifier: B16,
+    pub(super) read_only: B1,
+    reserved: B5,
+    pub(super) format: B2,
+    pub(super) attribute_length: B16,
+}
+
+struct ThisCommand {
+    command_buffer: CommandBuffer,
+    service_action: ServiceAction,
+    timeout: Option<Duration>,
+}
+
+impl Command for ThisCommand {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = VecBufferWrapper;
+
+    type ReturnType = crate::Result<CommandResult>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::FromDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        unsafe { VecBufferWrapper::with_len(self.command_buffer.allocation_length() as usize) }
+    }
+
+    fn data_size(&self) -> u32 {
+        self.command_buffer.allocation_length()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        let length = result.data_in_length(result.data().len());
+        let bytes = &result.data()[..length];
+
+        Ok(match self.service_action {
+            ServiceAction::AttributeValues => {
+                CommandResult::AttributeValues(AttributeValuesData::from_bytes(bytes))
+            }
+            _ => CommandResult::Raw(Vec::from(bytes)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+    const ATTRIBUTE_HEADER_LENGTH: usize = 5;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+
+        assert_eq!(
+            size_of::<AttributeHeader>(),
+            ATTRIBUTE_HEADER_LENGTH,
+            concat!("Size of: ", stringify!(AttributeHeader))
+        );
+    }
+
+    #[test]
+    fn attribute_values_test() {
+        let bytes = [
+            0x00, 0x00, 0x00, 0x14, // available data
+            0x00, 0x00, 0x80, 0x00, 0x08, // remaining capacity in partition
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x08, 0x00, 0x01, 0x00,
+            0x02, // application vendor
+            b'A', b'B',
+        ];
+
+        let data = AttributeValuesData::from_bytes(&bytes);
+        assert_eq!(data.required_length, 24, "required length");
+        assert_eq!(data.attributes.len(), 2, "attributes");
+
+        let attribute = &data.attributes[0];
+        assert_eq!(
+            attribute.attribute_identifier, 0x0000,
+            "attribute identifier"
+        );
+        assert!(attribute.read_only, "read only");
+        assert_eq!(attribute.format, AttributeFormat::Binary, "format");
+        assert_eq!(
+            attribute.value,
+            [0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00],
+            "value"
+        );
+
+        let attribute = &data.attributes[1];
+        assert_eq!(
+            attribute.attribute_identifier, 0x0800,
+            "attribute identifier"
+        );
+        assert!(!attribute.read_only, "writable");
+        assert_eq!(attribute.format, AttributeFormat::Ascii, "format");
+        assert_eq!(attribute.value, b"AB", "value");
+    }
+}
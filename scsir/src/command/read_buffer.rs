@@ -1,17 +1,21 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
     data_wrapper::{AnyType, VecBufferWrapper},
     result_data::ResultData,
+    util::be_u24,
     Command, DataDirection, Scsi,
 };
 
 #[derive(Clone, Debug)]
 pub struct ReadBufferCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     mode_specific: u8,
     mode: u8,
     buffer_offset: u64,
@@ -20,10 +24,18 @@ pub struct ReadBufferCommand<'a> {
     control: u8,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BufferDescriptor {
+    // buffer offsets must be a multiple of 2^offset_boundary, 0xFF means only offset 0 is allowed
+    pub offset_boundary: u8,
+    pub buffer_capacity: u32,
+}
+
 impl<'a> ReadBufferCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             mode_specific: 0,
             mode: 0,
             buffer_offset: 0,
@@ -51,7 +63,8 @@ impl<'a> ReadBufferCommand<'a> {
         self
     }
 
-    // allocation_length must be less than 0xFF_FFFF for issue_10
+    // allocation_length must be less than 0xFF_FFFF for issue_10, the buffer has no header
+    // reporting its length, see issue_descriptor for its capacity
     pub fn allocation_length(&mut self, value: u32) -> &mut Self {
         self.allocation_length = value;
         self
@@ -62,8 +75,8 @@ impl<'a> ReadBufferCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
@@ -84,51 +97,101 @@ impl<'a> ReadBufferCommand<'a> {
         Ok(())
     }
 
+    timeout_setter!();
+
     pub fn issue_10(&mut self) -> crate::Result<Vec<u8>> {
+        let command_buffer = self.command_buffer_10()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            allocation_length: self.allocation_length,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
         self.error_check(24, 24)?;
 
-        let command_buffer = CommandBuffer10::new()
+        Ok(CommandBuffer10::new()
             .with_operation_code(OPERATION_CODE_10)
             .with_mode_specific(self.mode_specific)
             .with_mode(self.mode)
             .with_buffer_id(self.buffer_id)
             .with_buffer_offset(self.buffer_offset as u32)
             .with_allocation_length(self.allocation_length)
-            .with_control(self.control);
+            .with_control(self.control))
+    }
+
+    // reads the capacity and offset boundary of buffer_id with the descriptor mode
+    pub fn issue_descriptor(&mut self) -> crate::Result<BufferDescriptor> {
+        let command_buffer = self.command_buffer_descriptor()?;
+
+        self.interface.issue(&DescriptorCommand {
+            command_buffer,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_descriptor: issue_descriptor => command_buffer_descriptor);
+
+    fn command_buffer_descriptor(&self) -> crate::Result<CommandBuffer10> {
+        Ok(CommandBuffer10::new()
+            .with_operation_code(OPERATION_CODE_10)
+            .with_mode(MODE_DESCRIPTOR)
+            .with_buffer_id(self.buffer_id)
+            .with_allocation_length(DESCRIPTOR_LENGTH)
+            .with_control(self.control))
+    }
+
+    pub fn issue_16(&mut self) -> crate::Result<Vec<u8>> {
+        let command_buffer = self.command_buffer_16()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
             allocation_length: self.allocation_length,
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_16(&mut self) -> crate::Result<Vec<u8>> {
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
+
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
         self.error_check(64, 32)?;
 
-        let command_buffer = CommandBuffer16::new()
+        Ok(CommandBuffer16::new()
             .with_operation_code(OPERATION_CODE_16)
             .with_mode_specific(self.mode_specific)
             .with_mode(self.mode)
             .with_buffer_offset(self.buffer_offset)
             .with_allocation_length(self.allocation_length)
             .with_buffer_id(self.buffer_id)
-            .with_control(self.control);
-
-        self.interface.issue(&ThisCommand {
-            command_buffer,
-            allocation_length: self.allocation_length,
-        })
+            .with_control(self.control))
     }
 }
 
 impl Scsi {
-    pub fn read_buffer(&self) -> ReadBufferCommand {
+    pub fn read_buffer(&self) -> ReadBufferCommand<'_> {
         ReadBufferCommand::new(self)
     }
 }
 
+crate::command::async_issue!(read_buffer: ReadBufferCommand);
+
+fn parse_descriptor(bytes: &[u8]) -> BufferDescriptor {
+    BufferDescriptor {
+        offset_boundary: bytes.first().copied().unwrap_or(0),
+        buffer_capacity: bytes.get(1..).and_then(be_u24).unwrap_or(0),
+    }
+}
+
 const OPERATION_CODE_10: u8 = 0x3C;
 const OPERATION_CODE_16: u8 = 0x9B;
+const MODE_DESCRIPTOR: u8 = 0x03;
+const DESCRIPTOR_LENGTH: u32 = 4;
+
+type DescriptorData = [u8; DESCRIPTOR_LENGTH as usize];
 
 #[bitfield]
 #[derive(Clone, Copy)]
@@ -157,6 +220,7 @@ struct CommandBuffer16 {
 struct ThisCommand<C> {
     command_buffer: C,
     allocation_length: u32,
+    timeout: Option<Duration>,
 }
 
 impl<C: Copy> Command for ThisCommand<C> {
@@ -184,11 +248,64 @@ impl<C: Copy> Command for ThisCommand<C> {
         self.allocation_length
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        let length = result.data_in_length(result.data.len());
+        let mut data: Vec<u8> = std::mem::take(result.data).0;
+        data.truncate(length);
+
+        Ok(data)
+    }
+}
+
+// the descriptor has a fixed length, so it is read into an inline buffer
+struct DescriptorCommand {
+    command_buffer: CommandBuffer10,
+    timeout: Option<Duration>,
+}
+
+impl Command for DescriptorCommand {
+    type CommandBuffer = CommandBuffer10;
+
+    type DataBuffer = DescriptorData;
+
+    type DataBufferWrapper = DescriptorData;
+
+    type ReturnType = crate::Result<BufferDescriptor>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::FromDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        [0; DESCRIPTOR_LENGTH as usize]
+    }
+
+    fn data_size(&self) -> u32 {
+        DESCRIPTOR_LENGTH
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        Ok(std::mem::take(result.data).0)
+        let length = result.data_in_length(result.data.len());
+
+        Ok(parse_descriptor(&result.data[..length]))
     }
 }
 
@@ -214,4 +331,53 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer16))
         );
     }
+
+    #[test]
+    fn descriptor_test() {
+        let command_buffer = CommandBuffer10::new()
+            .with_operation_code(OPERATION_CODE_10)
+            .with_mode(MODE_DESCRIPTOR)
+            .with_allocation_length(DESCRIPTOR_LENGTH);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x3C, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00],
+            "read buffer descriptor command"
+        );
+
+        assert_eq!(
+            parse_descriptor(&[0x09, 0x01, 0x00, 0x00]),
+            BufferDescriptor {
+                offset_boundary: 0x09,
+                buffer_capacity: 0x1_0000,
+            },
+            "buffer descriptor"
+        );
+
+        assert_eq!(
+            parse_descriptor(&[0xFF]).buffer_capacity,
+            0,
+            "short buffer descriptor"
+        );
+    }
+
+    #[test]
+    fn issue_descriptor_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.read_buffer();
+        command.buffer_id(1);
+
+        let before = allocations();
+        assert_eq!(
+            command.issue_descriptor().unwrap(),
+            BufferDescriptor {
+                offset_boundary: 0,
+                buffer_capacity: 0,
+            },
+            "descriptor"
+        );
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
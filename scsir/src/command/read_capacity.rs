@@ -1,14 +1,19 @@
 #![allow(dead_code)]
 
-use std::{marker::PhantomData, mem::size_of};
+use std::{marker::PhantomData, mem::size_of, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
-use crate::{result_data::ResultData, Command, DataDirection, Scsi};
+use crate::{
+    command::{cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
 
 #[derive(Clone, Debug)]
 pub struct ReadCapacityCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     control: u8,
 }
 
@@ -32,27 +37,65 @@ pub struct ReadCapacity16Result {
     pub lowest_aligned_logical_block_address: u16,
 }
 
+/// The size of the medium, see [`Scsi::capacity`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Capacity {
+    pub logical_block_count: u64,
+    pub logical_block_size: u32,
+}
+
+impl Capacity {
+    pub fn capacity_bytes(&self) -> u128 {
+        self.logical_block_count as u128 * self.logical_block_size as u128
+    }
+}
+
+impl ReadCapacity10Result {
+    /// The device has more logical blocks than can be reported, READ CAPACITY (16) is required
+    pub fn exceeds_capacity(&self) -> bool {
+        self.returned_logical_block_address == u32::MAX
+    }
+
+    pub fn capacity_bytes(&self) -> u64 {
+        (self.returned_logical_block_address as u64 + 1) * self.block_length_in_bytes as u64
+    }
+}
+
+impl ReadCapacity16Result {
+    pub fn capacity_bytes(&self) -> u128 {
+        (self.returned_logical_block_address as u128 + 1)
+            * self.logical_block_length_in_bytes as u128
+    }
+
+    pub fn physical_block_length_in_bytes(&self) -> u64 {
+        (self.logical_block_length_in_bytes as u64)
+            << self.logical_blocks_per_physical_block_exponent
+    }
+}
+
 impl<'a> ReadCapacityCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             control: 0,
         }
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
+    timeout_setter!();
+
     pub fn issue_10(&mut self) -> crate::Result<ReadCapacity10Result> {
-        let command_buffer = CommandBuffer10::new()
-            .with_operation_code(OPERATION_CODE_10)
-            .with_control(self.control);
+        let command_buffer = self.command_buffer_10()?;
 
         let result = self.interface.issue(&ThisCommand {
             command_buffer,
             marker: PhantomData::<DataBuffer10>,
+            timeout: self.timeout,
         })?;
 
         Ok(ReadCapacity10Result {
@@ -61,43 +104,91 @@ impl<'a> ReadCapacityCommand<'a> {
         })
     }
 
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
+        Ok(CommandBuffer10::new()
+            .with_operation_code(OPERATION_CODE_10)
+            .with_control(self.control))
+    }
+
     pub fn issue_16(&mut self) -> crate::Result<ReadCapacity16Result> {
-        let command_buffer = CommandBuffer16::new()
-            .with_operation_code(OPERATION_CODE_16)
-            .with_service_action(SERVICE_ACTION_16)
-            .with_allocation_length(size_of::<DataBuffer16>() as u32)
-            .with_control(self.control);
+        let command_buffer = self.command_buffer_16()?;
 
         let result = self.interface.issue(&ThisCommand {
             command_buffer,
             marker: PhantomData::<DataBuffer16>,
+            timeout: self.timeout,
         })?;
 
-        Ok(ReadCapacity16Result {
-            returned_logical_block_address: result.returned_logical_block_address(),
-            logical_block_length_in_bytes: result.logical_block_length_in_bytes(),
-            read_capacity_basis: result.read_capacity_basis(),
-            protection_type: result.protection_type(),
-            protection_enabled: result.protection_enabled() != 0,
-            p_i_exponent: result.p_i_exponent(),
-            logical_blocks_per_physical_block_exponent: result
-                .logical_blocks_per_physical_block_exponent(),
-            logical_block_provisioning_management_enabled: result
-                .logical_block_provisioning_management_enabled()
-                != 0,
-            logical_block_provisioning_read_zeros: result.logical_block_provisioning_read_zeros()
-                != 0,
-            lowest_aligned_logical_block_address: result.lowest_aligned_logical_block_address(),
-        })
+        Ok(parse_16(result))
+    }
+
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
+
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
+        Ok(CommandBuffer16::new()
+            .with_operation_code(OPERATION_CODE_16)
+            .with_service_action(SERVICE_ACTION_16)
+            .with_allocation_length(size_of::<DataBuffer16>() as u32)
+            .with_control(self.control))
+    }
+}
+
+fn parse_16(result: DataBuffer16) -> ReadCapacity16Result {
+    ReadCapacity16Result {
+        returned_logical_block_address: result.returned_logical_block_address(),
+        logical_block_length_in_bytes: result.logical_block_length_in_bytes(),
+        read_capacity_basis: result.read_capacity_basis(),
+        protection_type: result.protection_type(),
+        protection_enabled: result.protection_enabled() != 0,
+        p_i_exponent: result.p_i_exponent(),
+        logical_blocks_per_physical_block_exponent: result
+            .logical_blocks_per_physical_block_exponent(),
+        logical_block_provisioning_management_enabled: result
+            .logical_block_provisioning_management_enabled()
+            != 0,
+        logical_block_provisioning_read_zeros: result.logical_block_provisioning_read_zeros() != 0,
+        lowest_aligned_logical_block_address: result.lowest_aligned_logical_block_address(),
     }
 }
 
 impl Scsi {
-    pub fn read_capacity(&self) -> ReadCapacityCommand {
+    pub fn read_capacity(&self) -> ReadCapacityCommand<'_> {
         ReadCapacityCommand::new(self)
     }
+
+    /// Issues READ CAPACITY (16), or READ CAPACITY (10) if the device rejects it with ILLEGAL
+    /// REQUEST, as older devices without the 16 byte form do.
+    ///
+    /// The capacity isn't cached, issue it again after e.g. a FORMAT UNIT.
+    pub fn capacity(&self) -> crate::Result<Capacity> {
+        let result = match self.read_capacity().issue_16() {
+            Ok(result) => {
+                return Ok(Capacity {
+                    logical_block_count: result.returned_logical_block_address + 1,
+                    logical_block_size: result.logical_block_length_in_bytes,
+                })
+            }
+            Err(crate::Error::IllegalRequest(_)) => self.read_capacity().issue_10()?,
+            Err(error) => return Err(error),
+        };
+
+        if result.exceeds_capacity() {
+            return Err(crate::Error::Other(String::from(
+                "the capacity exceeds READ CAPACITY (10), but READ CAPACITY (16) is not supported",
+            )));
+        }
+
+        Ok(Capacity {
+            logical_block_count: result.returned_logical_block_address as u64 + 1,
+            logical_block_size: result.block_length_in_bytes,
+        })
+    }
 }
 
+crate::command::async_issue!(read_capacity: ReadCapacityCommand);
+
 const OPERATION_CODE_10: u8 = 0x25;
 const OPERATION_CODE_16: u8 = 0x9E;
 const SERVICE_ACTION_16: u8 = 0x10;
@@ -156,6 +247,7 @@ struct ThisCommand<C, D> {
     command_buffer: C,
 
     marker: PhantomData<D>,
+    timeout: Option<Duration>,
 }
 
 impl<C: Copy, D: Copy + Default> Command for ThisCommand<C, D> {
@@ -179,6 +271,10 @@ impl<C: Copy, D: Copy + Default> Command for ThisCommand<C, D> {
         D::default()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -223,4 +319,104 @@ mod tests {
             concat!("Size of: ", stringify!(DataBuffer16))
         );
     }
+
+    #[test]
+    fn capacity_10_test() {
+        let result = ReadCapacity10Result {
+            returned_logical_block_address: 124_999_999,
+            block_length_in_bytes: 512,
+        };
+        assert_eq!(result.capacity_bytes(), 64_000_000_000, "capacity");
+        assert!(!result.exceeds_capacity(), "capacity fits");
+
+        let result = ReadCapacity10Result {
+            returned_logical_block_address: u32::MAX,
+            block_length_in_bytes: 512,
+        };
+        assert!(result.exceeds_capacity(), "capacity exceeds 2 TiB");
+    }
+
+    #[test]
+    fn advanced_format_16_test() {
+        let mut bytes = [0u8; DATA_LENGTH_16];
+        bytes[..8].copy_from_slice(&0x0000_0001_D1C0_BEAFu64.to_be_bytes());
+        bytes[8..12].copy_from_slice(&512u32.to_be_bytes());
+        bytes[12] = 0b0000_0011; // type 2 protection, enabled
+        bytes[13] = 0x03; // 8 logical blocks per physical block
+        bytes[14] = 0b1100_0000;
+
+        let result = parse_16(DataBuffer16::from_bytes(bytes));
+        assert_eq!(
+            result.returned_logical_block_address, 0x0000_0001_D1C0_BEAF,
+            "returned logical block address"
+        );
+        assert_eq!(result.logical_block_length_in_bytes, 512, "block length");
+        assert_eq!(result.protection_type, 1, "protection type");
+        assert!(result.protection_enabled, "protection enabled");
+        assert_eq!(
+            result.logical_blocks_per_physical_block_exponent, 3,
+            "physical block exponent"
+        );
+        assert_eq!(
+            result.physical_block_length_in_bytes(),
+            4096,
+            "physical block length"
+        );
+        assert!(
+            result.logical_block_provisioning_management_enabled,
+            "logical block provisioning management enabled"
+        );
+        assert!(
+            result.logical_block_provisioning_read_zeros,
+            "logical block provisioning read zeros"
+        );
+        assert_eq!(
+            result.lowest_aligned_logical_block_address, 0,
+            "lowest aligned"
+        );
+    }
+
+    #[test]
+    fn capacity_fallback_test() {
+        use crate::{MockResponse, MockScsi};
+
+        const READ_CAPACITY_16: [u8; 16] = [
+            0x9E, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+            0x00, 0x00,
+        ];
+        // ILLEGAL REQUEST, INVALID COMMAND OPERATION CODE
+        let invalid_command = MockResponse::check_condition(&[
+            0x70, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x20, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+
+        let mock = MockScsi::new();
+        mock.expect(&READ_CAPACITY_16, invalid_command.clone())
+            .expect(&READ_CAPACITY_16, invalid_command)
+            .expect(
+                &[0x25, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                MockResponse::good().data(&[0x07, 0x73, 0x59, 0x3F, 0x00, 0x00, 0x10, 0x00]),
+            );
+
+        let scsi = Scsi::mock(mock.clone());
+        assert!(
+            matches!(
+                scsi.read_capacity().issue_16(),
+                Err(crate::Error::IllegalRequest(_))
+            ),
+            "READ CAPACITY (16) rejected"
+        );
+
+        let capacity = scsi.capacity().unwrap();
+        assert_eq!(
+            capacity,
+            Capacity {
+                logical_block_count: 125_000_000,
+                logical_block_size: 4096,
+            },
+            "READ CAPACITY (10)"
+        );
+        assert_eq!(capacity.capacity_bytes(), 512_000_000_000, "capacity");
+        mock.verify();
+    }
 }
@@ -1,11 +1,13 @@
 #![allow(dead_code)]
 
-use std::{marker::PhantomData, mem::size_of};
+use std::{marker::PhantomData, mem::size_of, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::{bitfield_bound_check, get_array},
+    command::{
+        bitfield_bound_check, cdb_bytes, get_array, issue_full_length, timeout_setter, Control,
+    },
     data_wrapper::{AnyType, FlexibleStruct},
     result_data::ResultData,
     Command, DataDirection, Scsi,
@@ -14,6 +16,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct ReadDefectDataCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     request_primary_defect_list: bool,
     request_grown_defect_list: bool,
     defect_list_format: u8,
@@ -23,6 +26,7 @@ pub struct ReadDefectDataCommand<'a> {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommandResult {
     pub primary_defect_list_valid: bool,
     pub grown_defect_list_valid: bool,
@@ -31,6 +35,8 @@ pub struct CommandResult {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum DefectList {
     ShortBlockFormat(Vec<ShortBlockFormatAddressDescriptor>),
     ExtendedBytesFromIndex(Vec<ExtendedBytesFromIndexAddressDescriptor>),
@@ -42,11 +48,13 @@ pub enum DefectList {
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShortBlockFormatAddressDescriptor {
     pub short_block_address: u32,
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedBytesFromIndexAddressDescriptor {
     pub cylinder_number: u32,
     pub head_number: u8,
@@ -55,6 +63,7 @@ pub struct ExtendedBytesFromIndexAddressDescriptor {
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtendedPhysicalSectorAddressDescriptor {
     pub cylinder_number: u32,
     pub head_number: u8,
@@ -63,11 +72,13 @@ pub struct ExtendedPhysicalSectorAddressDescriptor {
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LongBlockFormatAddressDescriptor {
     pub long_block_address: u64,
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BytesFromIndexFormatAddressDescriptor {
     pub cylinder_number: u32,
     pub head_number: u8,
@@ -75,6 +86,7 @@ pub struct BytesFromIndexFormatAddressDescriptor {
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PhysicalSectorFormatAddressDescriptor {
     pub cylinder_number: u32,
     pub head_number: u8,
@@ -85,6 +97,7 @@ impl<'a> ReadDefectDataCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             request_primary_defect_list: false,
             request_grown_defect_list: false,
             defect_list_format: 0,
@@ -115,8 +128,8 @@ impl<'a> ReadDefectDataCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
@@ -154,87 +167,267 @@ impl<'a> ReadDefectDataCommand<'a> {
     }
 
     fn get_defect_list_item_size(&self) -> usize {
-        match self.defect_list_format {
-            0b0000 => size_of::<super::format_unit::ShortBlockFormatAddressDescriptor>(),
-            0b0001 => size_of::<super::format_unit::ExtendedBytesFromIndexAddressDescriptor>(),
-            0b0010 => size_of::<super::format_unit::ExtendedPhysicalSectorAddressDescriptor>(),
-            0b0011 => size_of::<super::format_unit::LongBlockFormatAddressDescriptor>(),
-            0b0100 => size_of::<super::format_unit::BytesFromIndexFormatAddressDescriptor>(),
-            0b0101 => size_of::<super::format_unit::PhysicalSectorFormatAddressDescriptor>(),
-            _ => size_of::<u8>(),
-        }
+        defect_list_item_size(self.defect_list_format)
     }
 
+    timeout_setter!();
+
     pub fn issue_10(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_10()?;
+        let extra_allocation_length =
+            self.descriptor_length as usize * self.get_defect_list_item_size();
+
+        let (body, defect_list) = self.interface.issue(&ThisCommand {
+            command_buffer,
+            extra_allocation_length,
+            marker: PhantomData::<DataBufferHeader10>,
+            timeout: self.timeout,
+        })?;
+
+        Ok(command_result(
+            body.primary_defect_list_valid() != 0,
+            body.grown_defect_list_valid() != 0,
+            body.defect_list_format(),
+            body.defect_list_length() as usize,
+            &defect_list,
+        ))
+    }
+
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
         let extra_allocation_length =
             self.descriptor_length as usize * self.get_defect_list_item_size();
         let allocation_length = size_of::<DataBufferHeader10>() + extra_allocation_length;
 
         self.error_check(size_of::<DataBufferHeader10>(), u16::MAX.into(), false)?;
 
-        let command_buffer = CommandBuffer10::new()
+        Ok(CommandBuffer10::new()
             .with_operation_code(OPERATION_CODE_10)
             .with_request_primary_defect_list(self.request_primary_defect_list.into())
             .with_request_grown_defect_list(self.request_grown_defect_list.into())
             .with_defect_list_format(self.defect_list_format)
             .with_allocation_length(allocation_length as u16)
-            .with_control(self.control);
+            .with_control(self.control))
+    }
+
+    pub fn issue_12(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_12()?;
+        let extra_allocation_length =
+            self.descriptor_length as usize * self.get_defect_list_item_size();
 
         let (body, defect_list) = self.interface.issue(&ThisCommand {
             command_buffer,
             extra_allocation_length,
-            defect_list_format: self.defect_list_format,
-            marker: PhantomData::<DataBufferHeader10>,
+            marker: PhantomData::<DataBufferHeader12>,
+            timeout: self.timeout,
         })?;
 
-        Ok(CommandResult {
-            primary_defect_list_valid: body.primary_defect_list_valid() != 0,
-            grown_defect_list_valid: body.grown_defect_list_valid() != 0,
-            total_descriptor_length: (body.defect_list_length() as usize
-                / self.get_defect_list_item_size()) as u32,
-            descriptors: defect_list,
-        })
+        Ok(command_result(
+            body.primary_defect_list_valid() != 0,
+            body.grown_defect_list_valid() != 0,
+            body.defect_list_format(),
+            body.defect_list_length() as usize,
+            &defect_list,
+        ))
     }
 
-    pub fn issue_12(&mut self) -> crate::Result<CommandResult> {
+    cdb_bytes!(cdb_bytes_12: issue_12 => command_buffer_12);
+
+    fn command_buffer_12(&self) -> crate::Result<CommandBuffer12> {
         let extra_allocation_length =
             self.descriptor_length as usize * self.get_defect_list_item_size();
         let allocation_length = size_of::<DataBufferHeader12>() + extra_allocation_length;
 
         self.error_check(size_of::<DataBufferHeader12>(), u32::MAX as usize, true)?;
 
-        let command_buffer = CommandBuffer12::new()
+        Ok(CommandBuffer12::new()
             .with_operation_code(OPERATION_CODE_12)
             .with_request_primary_defect_list(self.request_primary_defect_list.into())
             .with_request_grown_defect_list(self.request_grown_defect_list.into())
             .with_defect_list_format(self.defect_list_format)
             .with_address_descriptor_index(self.address_descriptor_index)
             .with_allocation_length(allocation_length as u32)
-            .with_control(self.control);
+            .with_control(self.control))
+    }
 
-        let (body, defect_list) = self.interface.issue(&ThisCommand {
-            command_buffer,
-            extra_allocation_length,
-            defect_list_format: self.defect_list_format,
-            marker: PhantomData::<DataBufferHeader12>,
-        })?;
+    // issue_10 and issue_12 again with the descriptor length reported by the defect list
+    // length if the first response was truncated, the descriptor length is left at the larger
+    // value
+    pub fn issue_10_full_length(&mut self) -> crate::Result<CommandResult> {
+        let descriptor_length = self.descriptor_length as usize;
+        let maximum = (u16::MAX as usize - size_of::<DataBufferHeader10>())
+            / self.get_defect_list_item_size();
+        issue_full_length(
+            self,
+            descriptor_length,
+            maximum,
+            |command, length| {
+                command.descriptor_length(length as u32);
+            },
+            Self::issue_10,
+            |result| result.total_descriptor_length as usize,
+        )
+    }
+
+    pub fn issue_12_full_length(&mut self) -> crate::Result<CommandResult> {
+        let descriptor_length = self.descriptor_length as usize;
+        let maximum = (u32::MAX as usize - size_of::<DataBufferHeader12>())
+            / self.get_defect_list_item_size();
+        issue_full_length(
+            self,
+            descriptor_length,
+            maximum,
+            |command, length| {
+                command.descriptor_length(length as u32);
+            },
+            Self::issue_12,
+            |result| result.total_descriptor_length as usize,
+        )
+    }
+}
+
+// the header of the (10) and (12) data only differ in the width of the defect list length
+fn command_result(
+    primary_defect_list_valid: bool,
+    grown_defect_list_valid: bool,
+    defect_list_format: u8,
+    defect_list_length: usize,
+    defect_list: &[u8],
+) -> CommandResult {
+    // the device may return another format than the requested one, the list is decoded in
+    // the format of the header
+    let length = usize::min(defect_list_length, defect_list.len());
+
+    CommandResult {
+        primary_defect_list_valid,
+        grown_defect_list_valid,
+        total_descriptor_length: (defect_list_length / defect_list_item_size(defect_list_format))
+            as u32,
+        descriptors: parse_defect_list(defect_list_format, &defect_list[..length]),
+    }
+}
+
+fn defect_list_item_size(defect_list_format: u8) -> usize {
+    match defect_list_format {
+        0b0000 => size_of::<super::format_unit::ShortBlockFormatAddressDescriptor>(),
+        0b0001 => size_of::<super::format_unit::ExtendedBytesFromIndexAddressDescriptor>(),
+        0b0010 => size_of::<super::format_unit::ExtendedPhysicalSectorAddressDescriptor>(),
+        0b0011 => size_of::<super::format_unit::LongBlockFormatAddressDescriptor>(),
+        0b0100 => size_of::<super::format_unit::BytesFromIndexFormatAddressDescriptor>(),
+        0b0101 => size_of::<super::format_unit::PhysicalSectorFormatAddressDescriptor>(),
+        _ => size_of::<u8>(),
+    }
+}
 
-        Ok(CommandResult {
-            primary_defect_list_valid: body.primary_defect_list_valid() != 0,
-            grown_defect_list_valid: body.grown_defect_list_valid() != 0,
-            total_descriptor_length: (body.defect_list_length() as usize
-                / self.get_defect_list_item_size()) as u32,
-            descriptors: defect_list,
-        })
+fn parse_defect_list(defect_list_format: u8, bytes: &[u8]) -> DefectList {
+    let mut defect_list = match defect_list_format {
+        0b0000 => DefectList::ShortBlockFormat(vec![]),
+        0b0001 => DefectList::ExtendedBytesFromIndex(vec![]),
+        0b0010 => DefectList::ExtendedPhysicalSector(vec![]),
+        0b0011 => DefectList::LongBlockFormat(vec![]),
+        0b0100 => DefectList::BytesFromIndexFormat(vec![]),
+        0b0101 => DefectList::PhysicalSectorFormat(vec![]),
+        _ => DefectList::Custom(vec![]),
+    };
+
+    match &mut defect_list {
+        DefectList::ShortBlockFormat(v) => {
+            for chunk in bytes.chunks_exact(size_of::<
+                super::format_unit::ShortBlockFormatAddressDescriptor,
+            >()) {
+                let (bytes, _) = get_array(chunk);
+                let raw = super::format_unit::ShortBlockFormatAddressDescriptor::from_bytes(bytes);
+                v.push(ShortBlockFormatAddressDescriptor {
+                    short_block_address: raw.short_block_address(),
+                });
+            }
+        }
+        DefectList::ExtendedBytesFromIndex(v) => {
+            for chunk in bytes.chunks_exact(size_of::<
+                super::format_unit::ExtendedBytesFromIndexAddressDescriptor,
+            >()) {
+                let (bytes, _) = get_array(chunk);
+                let raw =
+                    super::format_unit::ExtendedBytesFromIndexAddressDescriptor::from_bytes(bytes);
+                v.push(ExtendedBytesFromIndexAddressDescriptor {
+                    cylinder_number: raw.cylinder_number(),
+                    head_number: raw.head_number(),
+                    multi_address_descriptor_start: raw.multi_address_descriptor_start() != 0,
+                    bytes_from_index: raw.bytes_from_index(),
+                });
+            }
+        }
+        DefectList::ExtendedPhysicalSector(v) => {
+            for chunk in bytes.chunks_exact(size_of::<
+                super::format_unit::ExtendedPhysicalSectorAddressDescriptor,
+            >()) {
+                let (bytes, _) = get_array(chunk);
+                let raw =
+                    super::format_unit::ExtendedPhysicalSectorAddressDescriptor::from_bytes(bytes);
+                v.push(ExtendedPhysicalSectorAddressDescriptor {
+                    cylinder_number: raw.cylinder_number(),
+                    head_number: raw.head_number(),
+                    multi_address_descriptor_start: raw.multi_address_descriptor_start() != 0,
+                    sector_number: raw.sector_number(),
+                });
+            }
+        }
+        DefectList::LongBlockFormat(v) => {
+            for chunk in bytes.chunks_exact(size_of::<
+                super::format_unit::LongBlockFormatAddressDescriptor,
+            >()) {
+                let (bytes, _) = get_array(chunk);
+                let raw = super::format_unit::LongBlockFormatAddressDescriptor::from_bytes(bytes);
+                v.push(LongBlockFormatAddressDescriptor {
+                    long_block_address: raw.long_block_address(),
+                });
+            }
+        }
+        DefectList::BytesFromIndexFormat(v) => {
+            for chunk in bytes.chunks_exact(size_of::<
+                super::format_unit::BytesFromIndexFormatAddressDescriptor,
+            >()) {
+                let (bytes, _) = get_array(chunk);
+                let raw =
+                    super::format_unit::BytesFromIndexFormatAddressDescriptor::from_bytes(bytes);
+                v.push(BytesFromIndexFormatAddressDescriptor {
+                    cylinder_number: raw.cylinder_number(),
+                    head_number: raw.head_number(),
+                    bytes_from_index: raw.bytes_from_index(),
+                });
+            }
+        }
+        DefectList::PhysicalSectorFormat(v) => {
+            for chunk in bytes.chunks_exact(size_of::<
+                super::format_unit::PhysicalSectorFormatAddressDescriptor,
+            >()) {
+                let (bytes, _) = get_array(chunk);
+                let raw =
+                    super::format_unit::PhysicalSectorFormatAddressDescriptor::from_bytes(bytes);
+                v.push(PhysicalSectorFormatAddressDescriptor {
+                    cylinder_number: raw.cylinder_number(),
+                    head_number: raw.head_number(),
+                    sector_number: raw.sector_number(),
+                });
+            }
+        }
+        DefectList::Custom(v) => {
+            v.extend_from_slice(bytes);
+        }
     }
+
+    defect_list
 }
 
 impl Scsi {
-    pub fn read_defect_data(&self) -> ReadDefectDataCommand {
+    pub fn read_defect_data(&self) -> ReadDefectDataCommand<'_> {
         ReadDefectDataCommand::new(self)
     }
 }
 
+crate::command::async_issue!(read_defect_data: ReadDefectDataCommand);
+
 const OPERATION_CODE_10: u8 = 0x37;
 const OPERATION_CODE_12: u8 = 0xB7;
 
@@ -292,9 +485,9 @@ struct DataBufferHeader12 {
 struct ThisCommand<C, Body> {
     command_buffer: C,
     extra_allocation_length: usize,
-    defect_list_format: u8,
 
     marker: PhantomData<Body>,
+    timeout: Option<Duration>,
 }
 
 impl<C: Copy, Body: Copy> Command for ThisCommand<C, Body> {
@@ -304,7 +497,7 @@ impl<C: Copy, Body: Copy> Command for ThisCommand<C, Body> {
 
     type DataBufferWrapper = FlexibleStruct<Body, u8>;
 
-    type ReturnType = crate::Result<(Body, DefectList)>;
+    type ReturnType = crate::Result<(Body, Vec<u8>)>;
 
     fn direction(&self) -> DataDirection {
         DataDirection::FromDevice
@@ -322,117 +515,22 @@ impl<C: Copy, Body: Copy> Command for ThisCommand<C, Body> {
         (self.extra_allocation_length + size_of::<Body>()) as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        let mut defect_list = match self.defect_list_format {
-            0b0000 => DefectList::ShortBlockFormat(vec![]),
-            0b0001 => DefectList::ExtendedBytesFromIndex(vec![]),
-            0b0010 => DefectList::ExtendedPhysicalSector(vec![]),
-            0b0011 => DefectList::LongBlockFormat(vec![]),
-            0b0100 => DefectList::BytesFromIndexFormat(vec![]),
-            0b0101 => DefectList::PhysicalSectorFormat(vec![]),
-            _ => DefectList::Custom(vec![]),
-        };
-
-        match &mut defect_list {
-            DefectList::ShortBlockFormat(v) => {
-                for chunk in unsafe { result.data.elements_as_slice() }.chunks(size_of::<
-                    super::format_unit::ShortBlockFormatAddressDescriptor,
-                >()) {
-                    let (bytes, _) = get_array(chunk);
-                    let raw =
-                        super::format_unit::ShortBlockFormatAddressDescriptor::from_bytes(bytes);
-                    v.push(ShortBlockFormatAddressDescriptor {
-                        short_block_address: raw.short_block_address(),
-                    });
-                }
-            }
-            DefectList::ExtendedBytesFromIndex(v) => {
-                for chunk in unsafe { result.data.elements_as_slice() }.chunks(size_of::<
-                    super::format_unit::ExtendedBytesFromIndexAddressDescriptor,
-                >()) {
-                    let (bytes, _) = get_array(chunk);
-                    let raw =
-                        super::format_unit::ExtendedBytesFromIndexAddressDescriptor::from_bytes(
-                            bytes,
-                        );
-                    v.push(ExtendedBytesFromIndexAddressDescriptor {
-                        cylinder_number: raw.cylinder_number(),
-                        head_number: raw.head_number(),
-                        multi_address_descriptor_start: raw.multi_address_descriptor_start() != 0,
-                        bytes_from_index: raw.bytes_from_index(),
-                    });
-                }
-            }
-            DefectList::ExtendedPhysicalSector(v) => {
-                for chunk in unsafe { result.data.elements_as_slice() }.chunks(size_of::<
-                    super::format_unit::ExtendedPhysicalSectorAddressDescriptor,
-                >()) {
-                    let (bytes, _) = get_array(chunk);
-                    let raw =
-                        super::format_unit::ExtendedPhysicalSectorAddressDescriptor::from_bytes(
-                            bytes,
-                        );
-                    v.push(ExtendedPhysicalSectorAddressDescriptor {
-                        cylinder_number: raw.cylinder_number(),
-                        head_number: raw.head_number(),
-                        multi_address_descriptor_start: raw.multi_address_descriptor_start() != 0,
-                        sector_number: raw.sector_number(),
-                    });
-                }
-            }
-            DefectList::LongBlockFormat(v) => {
-                for chunk in unsafe { result.data.elements_as_slice() }.chunks(size_of::<
-                    super::format_unit::LongBlockFormatAddressDescriptor,
-                >()) {
-                    let (bytes, _) = get_array(chunk);
-                    let raw =
-                        super::format_unit::LongBlockFormatAddressDescriptor::from_bytes(bytes);
-                    v.push(LongBlockFormatAddressDescriptor {
-                        long_block_address: raw.long_block_address(),
-                    });
-                }
-            }
-            DefectList::BytesFromIndexFormat(v) => {
-                for chunk in unsafe { result.data.elements_as_slice() }.chunks(size_of::<
-                    super::format_unit::BytesFromIndexFormatAddressDescriptor,
-                >()) {
-                    let (bytes, _) = get_array(chunk);
-                    let raw = super::format_unit::BytesFromIndexFormatAddressDescriptor::from_bytes(
-                        bytes,
-                    );
-                    v.push(BytesFromIndexFormatAddressDescriptor {
-                        cylinder_number: raw.cylinder_number(),
-                        head_number: raw.head_number(),
-                        bytes_from_index: raw.bytes_from_index(),
-                    });
-                }
-            }
-            DefectList::PhysicalSectorFormat(v) => {
-                for chunk in unsafe { result.data.elements_as_slice() }.chunks(size_of::<
-                    super::format_unit::PhysicalSectorFormatAddressDescriptor,
-                >()) {
-                    let (bytes, _) = get_array(chunk);
-                    let raw = super::format_unit::PhysicalSectorFormatAddressDescriptor::from_bytes(
-                        bytes,
-                    );
-                    v.push(PhysicalSectorFormatAddressDescriptor {
-                        cylinder_number: raw.cylinder_number(),
-                        head_number: raw.head_number(),
-                        sector_number: raw.sector_number(),
-                    });
-                }
-            }
-            DefectList::Custom(v) => {
-                v.extend_from_slice(unsafe { result.data.elements_as_slice() });
-            }
-        }
+        let elements = result.data.elements_as_slice();
+        let length = result
+            .data_in_length(self.data_size() as usize)
+            .saturating_sub(size_of::<Body>());
 
         Ok((
             unsafe { result.data.get_body_maybe_uninit().assume_init() },
-            defect_list,
+            Vec::from(&elements[..length]),
         ))
     }
 }
@@ -473,4 +571,139 @@ mod tests {
             concat!("Size of: ", stringify!(DataBufferHeader12))
         );
     }
+
+    #[test]
+    fn block_format_test() {
+        let bytes = [0x00, 0x00, 0x12, 0x34, 0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF];
+
+        let DefectList::ShortBlockFormat(v) = parse_defect_list(0b000, &bytes) else {
+            panic!("short block format")
+        };
+        assert_eq!(v.len(), 2, "complete descriptors only");
+        assert_eq!(v[0].short_block_address, 0x1234, "short block address");
+        assert_eq!(v[1].short_block_address, 0x0001_0000, "short block address");
+
+        let DefectList::LongBlockFormat(v) = parse_defect_list(0b011, &bytes[..8]) else {
+            panic!("long block format")
+        };
+        assert_eq!(
+            v[0].long_block_address, 0x0000_1234_0001_0000,
+            "long block address"
+        );
+    }
+
+    #[test]
+    fn physical_sector_format_test() {
+        let bytes = [
+            0x00, 0x01, 0x02, 0x03, 0x00, 0x00, 0x00, 0x2A, 0x00, 0x01, 0x02, 0x04, 0xFF, 0xFF,
+            0xFF, 0xFF,
+        ];
+
+        let DefectList::PhysicalSectorFormat(v) = parse_defect_list(0b101, &bytes) else {
+            panic!("physical sector format")
+        };
+        assert_eq!(v.len(), 2, "descriptors");
+        assert_eq!(v[0].cylinder_number, 0x0102, "cylinder number");
+        assert_eq!(v[0].head_number, 0x03, "head number");
+        assert_eq!(v[0].sector_number, 0x2A, "sector number");
+        assert_eq!(v[1].head_number, 0x04, "head number");
+        assert_eq!(v[1].sector_number, 0xFFFF_FFFF, "whole track");
+    }
+
+    #[test]
+    fn extended_format_test() {
+        let bytes = [
+            0x00, 0x01, 0x02, 0x03, 0x80, 0x00, 0x00, 0x2A, // start of a range
+            0x00, 0x01, 0x02, 0x03, 0x0F, 0xFF, 0xFF, 0xFF, // end of the range
+        ];
+
+        let DefectList::ExtendedPhysicalSector(v) = parse_defect_list(0b010, &bytes) else {
+            panic!("extended physical sector format")
+        };
+        assert_eq!(v.len(), 2, "descriptors");
+        assert_eq!(v[0].cylinder_number, 0x0102, "cylinder number");
+        assert_eq!(v[0].head_number, 0x03, "head number");
+        assert!(v[0].multi_address_descriptor_start, "MADS");
+        assert_eq!(v[0].sector_number, 0x2A, "sector number");
+        assert!(!v[1].multi_address_descriptor_start, "MADS");
+        assert_eq!(v[1].sector_number, 0x0FFF_FFFF, "28 bit sector number");
+
+        let DefectList::ExtendedBytesFromIndex(v) = parse_defect_list(0b001, &bytes) else {
+            panic!("extended bytes from index format")
+        };
+        assert!(v[0].multi_address_descriptor_start, "MADS");
+        assert_eq!(v[0].bytes_from_index, 0x2A, "bytes from index");
+        assert_eq!(
+            v[1].bytes_from_index, 0x0FFF_FFFF,
+            "28 bit bytes from index"
+        );
+
+        let DefectList::BytesFromIndexFormat(v) = parse_defect_list(0b100, &bytes) else {
+            panic!("bytes from index format")
+        };
+        assert_eq!(v[0].cylinder_number, 0x0102, "cylinder number");
+        assert_eq!(
+            v[0].bytes_from_index, 0x8000_002A,
+            "32 bit bytes from index"
+        );
+
+        let DefectList::Custom(v) = parse_defect_list(0b110, &bytes) else {
+            panic!("vendor specific format")
+        };
+        assert_eq!(v, bytes, "raw bytes");
+    }
+
+    #[test]
+    fn returned_format_test() {
+        use crate::{MockResponse, MockScsi};
+
+        // a grown list of two short block descriptors instead of the bytes from index format
+        let mock = MockScsi::new();
+        mock.expect(
+            &[
+                0xB7, 0x0B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x28, 0x00, 0x00,
+            ],
+            MockResponse::good().data(&[
+                0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, // header
+                0x00, 0x00, 0x12, 0x34, 0x00, 0x00, 0x56, 0x78, // descriptors
+            ]),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let result = scsi
+            .read_defect_data()
+            .request_grown_defect_list(true)
+            .defect_list_format(0b011)
+            .descriptor_length(4)
+            .issue_12()
+            .unwrap();
+
+        assert!(result.grown_defect_list_valid, "GLISTV");
+        assert!(!result.primary_defect_list_valid, "PLISTV");
+        assert_eq!(
+            result.total_descriptor_length, 2,
+            "descriptors in the header format"
+        );
+        let DefectList::ShortBlockFormat(v) = result.descriptors else {
+            panic!("decoded in the returned format instead of the requested one")
+        };
+        let addresses: Vec<_> = v.iter().map(|d| d.short_block_address).collect();
+        assert_eq!(addresses, [0x1234, 0x5678], "short block addresses");
+        mock.verify();
+    }
+
+    #[test]
+    fn command_buffer_10_test() {
+        let command_buffer = CommandBuffer10::new()
+            .with_operation_code(OPERATION_CODE_10)
+            .with_request_grown_defect_list(1)
+            .with_defect_list_format(0b101)
+            .with_allocation_length(0x0204);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x37, 0x00, 0x0D, 0x00, 0x00, 0x00, 0x00, 0x02, 0x04, 0x00],
+            "read defect data (10) command"
+        );
+    }
 }
@@ -0,0 +1,320 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{
+        bitfield_bound_check, cdb_bytes,
+        sense::{Descriptor, SenseData, SenseKey},
+        timeout_setter, Control,
+    },
+    data_wrapper::{AnyType, VecBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct ReadLongCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    physical_block: bool,
+    correct: bool,
+    logical_block_address: u64,
+    byte_transfer_length: u16,
+    control: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CommandResult {
+    Data(Vec<u8>),
+    // nothing was transfered, retry with the byte transfer length the device asked for
+    IncorrectLength { byte_transfer_length: u16 },
+}
+
+impl<'a> ReadLongCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            physical_block: false,
+            correct: false,
+            logical_block_address: 0,
+            byte_transfer_length: 0,
+            control: 0,
+        }
+    }
+
+    // transfer the whole physical block containing the logical block
+    pub fn physical_block(&mut self, value: bool) -> &mut Self {
+        self.physical_block = value;
+        self
+    }
+
+    // apply the ECC correction before transfering the data
+    pub fn correct(&mut self, value: bool) -> &mut Self {
+        self.correct = value;
+        self
+    }
+
+    pub fn logical_block_address(&mut self, value: u64) -> &mut Self {
+        self.logical_block_address = value;
+        self
+    }
+
+    // the long block size, the data plus the ECC bytes
+    pub fn byte_transfer_length(&mut self, value: u16) -> &mut Self {
+        self.byte_transfer_length = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue_10(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_10()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            byte_transfer_length: self.byte_transfer_length,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
+        bitfield_bound_check!(self.logical_block_address, 32, "logical block address")?;
+
+        Ok(CommandBuffer10::new()
+            .with_operation_code(OPERATION_CODE_10)
+            .with_physical_block(self.physical_block.into())
+            .with_correct(self.correct.into())
+            .with_logical_block_address(self.logical_block_address as u32)
+            .with_byte_transfer_length(self.byte_transfer_length)
+            .with_control(self.control))
+    }
+
+    pub fn issue_16(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_16()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            byte_transfer_length: self.byte_transfer_length,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
+
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
+        Ok(CommandBuffer16::new()
+            .with_operation_code(OPERATION_CODE_16)
+            .with_service_action(SERVICE_ACTION_16)
+            .with_logical_block_address(self.logical_block_address)
+            .with_byte_transfer_length(self.byte_transfer_length)
+            .with_physical_block(self.physical_block.into())
+            .with_correct(self.correct.into())
+            .with_control(self.control))
+    }
+}
+
+impl Scsi {
+    pub fn read_long(&self) -> ReadLongCommand<'_> {
+        ReadLongCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(read_long: ReadLongCommand);
+
+// a wrong byte transfer length is rejected with ILLEGAL REQUEST and the incorrect length
+// indicator set, the information field holds the requested minus the actual length
+pub(super) fn long_block_length(sense: &SenseData, byte_transfer_length: u16) -> Option<u16> {
+    let difference = match sense {
+        SenseData::Fixed(sense)
+            if matches!(sense.sense_key, SenseKey::IllegalRequest)
+                && sense.is_valid
+                && sense.is_incorrect_length_indicator =>
+        {
+            i32::from_be_bytes(sense.information) as i64
+        }
+        SenseData::Descriptor(sense) if matches!(sense.sense_key, SenseKey::IllegalRequest) => {
+            let incorrect_length = sense.descriptors.iter().any(|x| {
+                matches!(x, Descriptor::BlockCommands(raw) if raw.get(3).is_some_and(|&b| b & 0b0010_0000 != 0))
+            });
+            if !incorrect_length {
+                return None;
+            }
+
+            sense.descriptors.iter().find_map(|x| match x {
+                Descriptor::Information {
+                    is_valid: true,
+                    information,
+                } => Some(i64::from_be_bytes(*information)),
+                _ => None,
+            })?
+        }
+        _ => return None,
+    };
+
+    u16::try_from(byte_transfer_length as i64 - difference).ok()
+}
+
+const OPERATION_CODE_10: u8 = 0x3E;
+const OPERATION_CODE_16: u8 = 0x9E;
+const SERVICE_ACTION_16: u8 = 0x11;
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct CommandBuffer10 {
+    operation_code: B8,
+    reserved_0: B5,
+    physical_block: B1,
+    correct: B1,
+    obsolete: B1,
+    logical_block_address: B32,
+    reserved_1: B8,
+    byte_transfer_length: B16,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct CommandBuffer16 {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    logical_block_address: B64,
+    reserved_1: B16,
+    byte_transfer_length: B16,
+    reserved_2: B6,
+    physical_block: B1,
+    correct: B1,
+    control: B8,
+}
+
+struct ThisCommand<C> {
+    command_buffer: C,
+    byte_transfer_length: u16,
+    timeout: Option<Duration>,
+}
+
+impl<C: Copy> Command for ThisCommand<C> {
+    type CommandBuffer = C;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = VecBufferWrapper;
+
+    type ReturnType = crate::Result<CommandResult>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::FromDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        unsafe { VecBufferWrapper::with_len(self.byte_transfer_length as usize) }
+    }
+
+    fn data_size(&self) -> u32 {
+        self.byte_transfer_length as u32
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+
+        if let Some(byte_transfer_length) =
+            long_block_length(result.sense_buffer, self.byte_transfer_length)
+        {
+            return Ok(CommandResult::IncorrectLength {
+                byte_transfer_length,
+            });
+        }
+
+        result.check_common_error()?;
+
+        let length = result.data_in_length(result.data.len());
+        let mut data: Vec<u8> = std::mem::take(result.data).0;
+        data.truncate(length);
+
+        Ok(CommandResult::Data(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::sense::MAX_SENSE_BUFFER_LENGTH;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH_10: usize = 10;
+    const COMMAND_LENGTH_16: usize = 16;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer10>(),
+            COMMAND_LENGTH_10,
+            concat!("Size of: ", stringify!(CommandBuffer10))
+        );
+
+        assert_eq!(
+            size_of::<CommandBuffer16>(),
+            COMMAND_LENGTH_16,
+            concat!("Size of: ", stringify!(CommandBuffer16))
+        );
+    }
+
+    #[test]
+    fn long_block_length_test() {
+        let mut raw = [0u8; MAX_SENSE_BUFFER_LENGTH];
+        // asked for 512 bytes, the long block is 520 bytes, so the information is -8
+        raw[..18].copy_from_slice(&[
+            0xF0, 0x00, 0x25, 0xFF, 0xFF, 0xFF, 0xF8, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x24, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+
+        assert_eq!(
+            long_block_length(&SenseData::parse(&raw, 18), 512),
+            Some(520),
+            "fixed sense data"
+        );
+
+        let mut raw = [0u8; MAX_SENSE_BUFFER_LENGTH];
+        raw[..28].copy_from_slice(&[
+            0x72, 0x05, 0x24, 0x00, 0x00, 0x00, 0x00, 0x14, // header
+            0x00, 0x0A, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x08, // information
+            0x05, 0x02, 0x00, 0x20, // block commands, ili
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+
+        assert_eq!(
+            long_block_length(&SenseData::parse(&raw, 28), 528),
+            Some(520),
+            "descriptor sense data"
+        );
+
+        raw[23] = 0x00;
+        assert_eq!(
+            long_block_length(&SenseData::parse(&raw, 28), 528),
+            None,
+            "no incorrect length indicator"
+        );
+    }
+}
@@ -1,12 +1,12 @@
 #![allow(dead_code)]
 
-use std::mem::size_of;
+use std::{mem::size_of, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -14,28 +14,30 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct ReassignBlocksCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     command_buffer: CommandBuffer,
     data_buffer: Vec<u8>,
 }
 
 pub struct ParameterBuilder<'a> {
     parent: &'a mut ReassignBlocksCommand<'a>,
-    data_buffer: Vec<u8>,
-    data_length: usize,
-    long_lba_list: bool,
+    long_lba: bool,
+    long_list: bool,
+    lba_list: Vec<u64>,
 }
 
 impl<'a> ReassignBlocksCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
             data_buffer: vec![],
         }
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
@@ -43,72 +45,113 @@ impl<'a> ReassignBlocksCommand<'a> {
         ParameterBuilder::new(self)
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<()> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self.command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            command_buffer: self.command_buffer()?,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        Ok(self.command_buffer)
+    }
 }
 
 impl<'a> ParameterBuilder<'a> {
     fn new(parent: &'a mut ReassignBlocksCommand<'a>) -> Self {
         Self {
             parent,
-            data_buffer: vec![],
-            data_length: 0,
-            long_lba_list: false,
+            long_lba: false,
+            long_list: false,
+            lba_list: vec![],
         }
     }
 
-    pub fn short_lba_list(&mut self, value: &[u32]) -> &mut Self {
-        self.long_lba_list = false;
-        self.data_buffer.clear();
-        self.data_length = value.len() * size_of::<u32>();
-        self.data_buffer
-            .extend_from_slice(&(self.data_length as u32).to_be_bytes());
-        for n in value {
-            self.data_buffer.extend_from_slice(&n.to_be_bytes());
-        }
-
+    pub fn long_lba(&mut self, value: bool) -> &mut Self {
+        self.long_lba = value;
         self
     }
 
-    pub fn long_lba_list(&mut self, value: &[u64]) -> &mut Self {
-        self.long_lba_list = true;
-        self.data_buffer.clear();
-        self.data_length = value.len() * size_of::<u64>();
-        self.data_buffer
-            .extend_from_slice(&(self.data_length as u32).to_be_bytes());
-        for n in value {
-            self.data_buffer.extend_from_slice(&n.to_be_bytes());
-        }
+    pub fn long_list(&mut self, value: bool) -> &mut Self {
+        self.long_list = value;
+        self
+    }
 
+    // every logical block address must be less or equal than 0xFFFF_FFFF without long_lba
+    pub fn lba_list(&mut self, value: &[u64]) -> &mut Self {
+        self.lba_list = value.to_vec();
         self
     }
 
-    pub fn done(&'a mut self) -> crate::Result<&'a mut ReassignBlocksCommand> {
-        let data_length_bits = if self.long_lba_list { 32 } else { 16 };
+    pub fn short_lba_list(&mut self, value: &[u32]) -> &mut Self {
+        self.long_lba = false;
+        self.long_list = false;
+        self.lba_list = value.iter().map(|&n| n as u64).collect();
+        self
+    }
 
-        bitfield_bound_check!(self.data_length, data_length_bits, "parameter length")?;
+    pub fn long_lba_list(&mut self, value: &[u64]) -> &mut Self {
+        self.long_lba = true;
+        self.long_list = true;
+        self.lba_list = value.to_vec();
+        self
+    }
 
+    pub fn done(&'a mut self) -> crate::Result<&'a mut ReassignBlocksCommand<'a>> {
+        self.parent.data_buffer = parameter_list(self.long_lba, self.long_list, &self.lba_list)?;
         self.parent
             .command_buffer
-            .set_long_lba(self.long_lba_list.into());
+            .set_long_lba(self.long_lba.into());
         self.parent
             .command_buffer
-            .set_long_list(self.long_lba_list.into());
-        self.parent.data_buffer = std::mem::take(&mut self.data_buffer);
+            .set_long_list(self.long_list.into());
         Ok(self.parent)
     }
 }
 
+fn parameter_list(long_lba: bool, long_list: bool, lba_list: &[u64]) -> crate::Result<Vec<u8>> {
+    let lba_size = if long_lba {
+        size_of::<u64>()
+    } else {
+        size_of::<u32>()
+    };
+    let data_length = lba_list.len() * lba_size;
+
+    let mut data_buffer = vec![];
+    if long_list {
+        bitfield_bound_check!(data_length, 32, "defect list length")?;
+        data_buffer.extend_from_slice(&(data_length as u32).to_be_bytes());
+    } else {
+        bitfield_bound_check!(data_length, 16, "defect list length")?;
+        data_buffer.extend_from_slice(&[0, 0]);
+        data_buffer.extend_from_slice(&(data_length as u16).to_be_bytes());
+    }
+
+    for &n in lba_list {
+        if long_lba {
+            data_buffer.extend_from_slice(&n.to_be_bytes());
+        } else {
+            bitfield_bound_check!(n, 32, "logical block address")?;
+            data_buffer.extend_from_slice(&(n as u32).to_be_bytes());
+        }
+    }
+
+    Ok(data_buffer)
+}
+
 impl Scsi {
-    pub fn reassign_blocks(&self) -> ReassignBlocksCommand {
+    pub fn reassign_blocks(&self) -> ReassignBlocksCommand<'_> {
         ReassignBlocksCommand::new(self)
     }
 }
 
+crate::command::async_issue!(reassign_blocks: ReassignBlocksCommand);
+
 const OPERATION_CODE: u8 = 0x07;
 
 #[bitfield]
@@ -122,17 +165,18 @@ struct CommandBuffer {
     control: B8,
 }
 
-struct ThisCommand {
+struct ThisCommand<'a> {
     command_buffer: CommandBuffer,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl Command for ThisCommand {
+impl<'a> Command for ThisCommand<'a> {
     type CommandBuffer = CommandBuffer;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
@@ -145,13 +189,17 @@ impl Command for ThisCommand {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -175,4 +223,32 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn short_lba_test() {
+        assert_eq!(
+            parameter_list(false, false, &[0x1234, 0xFFFF_FFFF]).unwrap(),
+            [0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x12, 0x34, 0xFF, 0xFF, 0xFF, 0xFF],
+            "short lba list"
+        );
+
+        assert!(
+            parameter_list(false, true, &[0x1_0000_0000]).is_err(),
+            "logical block address out of bounds"
+        );
+    }
+
+    #[test]
+    fn long_lba_test() {
+        assert_eq!(
+            parameter_list(true, true, &[0x0001_0000_0000_1234]).unwrap(),
+            [0x00, 0x00, 0x00, 0x08, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x12, 0x34],
+            "long lba list"
+        );
+
+        assert!(
+            parameter_list(true, false, &vec![0; 0x2000]).is_err(),
+            "defect list length out of bounds"
+        );
+    }
 }
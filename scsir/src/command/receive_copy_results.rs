@@ -0,0 +1,407 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{
+        bitfield_bound_check, cdb_bytes, get_array, issue_full_length, timeout_setter, Control,
+    },
+    data_wrapper::{AnyType, VecBufferWrapper},
+    result_data::ResultData,
+    util::be_u32,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct ReceiveCopyResultsCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    service_action: ServiceAction,
+    command_buffer: CommandBuffer,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ServiceAction {
+    CopyStatus,
+    ReceiveData,
+    OperatingParameters,
+    FailedSegmentDetails,
+    Other(u8),
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CommandResult {
+    CopyStatus(CopyStatus),
+    OperatingParameters(OperatingParameters),
+    Raw(Vec<u8>),
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CopyStatus {
+    pub held_data_discarded: bool,
+    pub copy_manager_status: u8,
+    pub segments_processed: u16,
+    pub transfer_count_units: u8,
+    pub transfer_count: u32,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OperatingParameters {
+    pub supports_no_list_identifier: bool,
+    pub maximum_target_descriptor_count: u16,
+    pub maximum_segment_descriptor_count: u16,
+    pub maximum_descriptor_list_length: u32,
+    pub maximum_segment_length: u32,
+    pub maximum_inline_data_length: u32,
+    pub held_data_limit: u32,
+    pub maximum_stream_device_transfer_size: u32,
+    pub total_concurrent_copies: u16,
+    pub maximum_concurrent_copies: u8,
+    // the granularities are log2 of the value in bytes
+    pub data_segment_granularity: u8,
+    pub inline_data_granularity: u8,
+    pub held_data_granularity: u8,
+    pub implemented_descriptor_type_codes: Vec<u8>,
+}
+
+impl<'a> ReceiveCopyResultsCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            service_action: ServiceAction::CopyStatus,
+            command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
+        }
+    }
+
+    // service_action must be less than 0x20
+    pub fn service_action(&mut self, value: ServiceAction) -> &mut Self {
+        self.service_action = value;
+        self
+    }
+
+    // the list identifier given to EXTENDED COPY, ignored by OPERATING PARAMETERS
+    pub fn list_identifier(&mut self, value: u8) -> &mut Self {
+        self.command_buffer.set_list_identifier(value);
+        self
+    }
+
+    pub fn allocation_length(&mut self, value: u32) -> &mut Self {
+        self.command_buffer.set_allocation_length(value);
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<CommandResult> {
+        Ok(self.issue_required_length()?.0)
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    // issues again with the allocation length reported by the available data if the first
+    // response was truncated, the allocation length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<CommandResult> {
+        let allocation_length = self.command_buffer.allocation_length() as usize;
+        let (result, _) = issue_full_length(
+            self,
+            allocation_length,
+            u32::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u32);
+            },
+            Self::issue_required_length,
+            |&(_, required_length)| required_length,
+        )?;
+
+        Ok(result)
+    }
+
+    fn issue_required_length(&mut self) -> crate::Result<(CommandResult, usize)> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            service_action: self.service_action,
+            timeout: self.timeout,
+        })
+    }
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(u8::from(self.service_action), 5, "service action")?;
+
+        Ok(self
+            .command_buffer
+            .with_service_action(self.service_action.into()))
+    }
+}
+
+impl Scsi {
+    pub fn receive_copy_results(&self) -> ReceiveCopyResultsCommand<'_> {
+        ReceiveCopyResultsCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(receive_copy_results: ReceiveCopyResultsCommand);
+
+impl From<ServiceAction> for u8 {
+    fn from(value: ServiceAction) -> Self {
+        match value {
+            ServiceAction::CopyStatus => 0x00,
+            ServiceAction::ReceiveData => 0x01,
+            ServiceAction::OperatingParameters => 0x03,
+            ServiceAction::FailedSegmentDetails => 0x04,
+            ServiceAction::Other(x) => x,
+        }
+    }
+}
+
+impl CopyStatus {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let (array, _) = get_array(bytes);
+        let data = CopyStatusData::from_bytes(array);
+
+        Self {
+            held_data_discarded: data.held_data_discarded() != 0,
+            copy_manager_status: data.copy_manager_status(),
+            segments_processed: data.segments_processed(),
+            transfer_count_units: data.transfer_count_units(),
+            transfer_count: data.transfer_count(),
+        }
+    }
+}
+
+impl OperatingParameters {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let (array, left) = get_array(bytes);
+        let data = OperatingParametersData::from_bytes(array);
+        let length = usize::min(
+            data.implemented_descriptor_list_length() as usize,
+            left.len(),
+        );
+
+        Self {
+            supports_no_list_identifier: data.supports_no_list_identifier() != 0,
+            maximum_target_descriptor_count: data.maximum_target_descriptor_count(),
+            maximum_segment_descriptor_count: data.maximum_segment_descriptor_count(),
+            maximum_descriptor_list_length: data.maximum_descriptor_list_length(),
+            maximum_segment_length: data.maximum_segment_length(),
+            maximum_inline_data_length: data.maximum_inline_data_length(),
+            held_data_limit: data.held_data_limit(),
+            maximum_stream_device_transfer_size: data.maximum_stream_device_transfer_size(),
+            total_concurrent_copies: data.total_concurrent_copies(),
+            maximum_concurrent_copies: data.maximum_concurrent_copies(),
+            data_segment_granularity: data.data_segment_granularity(),
+            inline_data_granularity: data.inline_data_granularity(),
+            held_data_granularity: data.held_data_granularity(),
+            implemented_descriptor_type_codes: Vec::from(&left[..length]),
+        }
+    }
+}
+
+const OPERATION_CODE: u8 = 0x84;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    list_identifier: B8,
+    reserved_1: B56,
+    allocation_length: B32,
+    reserved_2: B8,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct CopyStatusData {
+    available_data: B32,
+    held_data_discarded: B1,
+    copy_manager_status: B7,
+    segments_processed: B16,
+    transfer_count_units: B8,
+    transfer_count: B32,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct OperatingParametersData {
+    available_data: B32,
+    reserved_0: B7,
+    supports_no_list_identifier: B1,
+    reserved_1: B24,
+    maximum_target_descriptor_count: B16,
+    maximum_segment_descriptor_count: B16,
+    maximum_descriptor_list_length: B32,
+    maximum_segment_length: B32,
+    maximum_inline_data_length: B32,
+    held_data_limit: B32,
+    maximum_stream_device_transfer_size: B32,
+    reserved_2: B16,
+    total_concurrent_copies: B16,
+    maximum_concurrent_copies: B8,
+    data_segment_granularity: B8,
+    inline_data_granularity: B8,
+    held_data_granularity: B8,
+    reserved_3: B24,
+    implemented_descriptor_list_length: B8,
+}
+
+struct ThisCommand {
+    command_buffer: CommandBuffer,
+    service_action: ServiceAction,
+    timeout: Option<Duration>,
+}
+
+impl Command for ThisCommand {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = VecBufferWrapper;
+
+    type ReturnType = crate::Result<(CommandResult, usize)>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::FromDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        unsafe { VecBufferWrapper::with_len(self.command_buffer.allocation_length() as usize) }
+    }
+
+    fn data_size(&self) -> u32 {
+        self.command_buffer.allocation_length()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        let length = result.data_in_length(result.data().len());
+        let bytes = &result.data()[..length];
+
+        // every format starts with the available data
+        let required_length = be_u32(bytes).map_or(0, |length| length as usize + 4);
+        let result = match self.service_action {
+            ServiceAction::CopyStatus => CommandResult::CopyStatus(CopyStatus::from_bytes(bytes)),
+            ServiceAction::OperatingParameters => {
+                CommandResult::OperatingParameters(OperatingParameters::from_bytes(bytes))
+            }
+            _ => CommandResult::Raw(Vec::from(bytes)),
+        };
+
+        Ok((result, required_length))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+    const COPY_STATUS_LENGTH: usize = 12;
+    const OPERATING_PARAMETERS_LENGTH: usize = 44;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+
+        assert_eq!(
+            size_of::<CopyStatusData>(),
+            COPY_STATUS_LENGTH,
+            concat!("Size of: ", stringify!(CopyStatusData))
+        );
+
+        assert_eq!(
+            size_of::<OperatingParametersData>(),
+            OPERATING_PARAMETERS_LENGTH,
+            concat!("Size of: ", stringify!(OperatingParametersData))
+        );
+    }
+
+    #[test]
+    fn operating_parameters_test() {
+        let bytes = [
+            0x00, 0x00, 0x00, 0x2A, // available data
+            0x01, 0x00, 0x00, 0x00, // snlid
+            0x00, 0x02, 0x00, 0x01, // descriptor counts
+            0x00, 0x00, 0x04, 0x00, // maximum descriptor list length
+            0x00, 0x10, 0x00, 0x00, // maximum segment length
+            0x00, 0x00, 0x00, 0x00, // maximum inline data length
+            0x00, 0x00, 0x00, 0x00, // held data limit
+            0x00, 0x00, 0x00, 0x00, // maximum stream device transfer size
+            0x00, 0x00, 0x00, 0x08, // total concurrent copies
+            0x04, 0x09, 0x00, 0x00, // concurrent copies and granularities
+            0x00, 0x00, 0x00, 0x02, // implemented descriptor list length
+            0x02, 0xE4,
+        ];
+
+        let data = OperatingParameters::from_bytes(&bytes);
+        assert!(data.supports_no_list_identifier, "snlid");
+        assert_eq!(
+            data.maximum_target_descriptor_count, 2,
+            "maximum target descriptor count"
+        );
+        assert_eq!(
+            data.maximum_segment_descriptor_count, 1,
+            "maximum segment descriptor count"
+        );
+        assert_eq!(
+            data.maximum_descriptor_list_length, 0x400,
+            "maximum descriptor list length"
+        );
+        assert_eq!(
+            data.maximum_segment_length, 0x10_0000,
+            "maximum segment length"
+        );
+        assert_eq!(data.total_concurrent_copies, 8, "total concurrent copies");
+        assert_eq!(
+            data.maximum_concurrent_copies, 4,
+            "maximum concurrent copies"
+        );
+        assert_eq!(data.data_segment_granularity, 9, "data segment granularity");
+        assert_eq!(
+            data.implemented_descriptor_type_codes,
+            [0x02, 0xE4],
+            "implemented descriptor type codes"
+        );
+    }
+
+    #[test]
+    fn copy_status_test() {
+        let data = CopyStatus::from_bytes(&[
+            0x00, 0x00, 0x00, 0x08, 0x80, 0x00, 0x01, 0x05, 0x00, 0x00, 0x08, 0x00,
+        ]);
+
+        assert!(data.held_data_discarded, "held data discarded");
+        assert_eq!(data.copy_manager_status, 0, "copy manager status");
+        assert_eq!(data.segments_processed, 1, "segments processed");
+        assert_eq!(data.transfer_count_units, 5, "transfer count units");
+        assert_eq!(data.transfer_count, 0x800, "transfer count");
+    }
+}
@@ -1,8 +1,11 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
+    command::{cdb_bytes, issue_full_length, page_required_length, timeout_setter, Control},
     data_wrapper::{AnyType, VecBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
@@ -11,6 +14,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct ReceiveDiagnosticResultsCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     command_buffer: CommandBuffer,
 }
 
@@ -18,6 +22,7 @@ impl<'a> ReceiveDiagnosticResultsCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
         }
     }
@@ -34,24 +39,51 @@ impl<'a> ReceiveDiagnosticResultsCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<Vec<u8>> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self.command_buffer,
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        Ok(self.command_buffer)
+    }
+
+    // issues again with the allocation length reported by the page length if the first
+    // response was truncated, the allocation length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<Vec<u8>> {
+        let allocation_length = self.command_buffer.allocation_length() as usize;
+        issue_full_length(
+            self,
+            allocation_length,
+            u16::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u16);
+            },
+            Self::issue,
+            |data| page_required_length(data),
+        )
+    }
 }
 
 impl Scsi {
-    pub fn receive_diagnostic_results(&self) -> ReceiveDiagnosticResultsCommand {
+    pub fn receive_diagnostic_results(&self) -> ReceiveDiagnosticResultsCommand<'_> {
         ReceiveDiagnosticResultsCommand::new(self)
     }
 }
 
+crate::command::async_issue!(receive_diagnostic_results: ReceiveDiagnosticResultsCommand);
+
 const OPERATION_CODE: u8 = 0x1C;
 
 #[bitfield]
@@ -67,6 +99,7 @@ struct CommandBuffer {
 
 struct ThisCommand {
     command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -94,14 +127,25 @@ impl Command for ThisCommand {
         self.command_buffer.allocation_length() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        Ok(std::mem::take(result.data))
+        let length = result.data_in_length(result.data.len());
+        Ok(parse(std::mem::take(result.data), length))
     }
 }
 
+// diagnostic pages vary in size, only return what the device transfered
+fn parse(mut data: Vec<u8>, length: usize) -> Vec<u8> {
+    data.truncate(length);
+    data
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,4 +161,28 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn command_buffer_test() {
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_page_code_valid(1)
+            .with_page_code(0x02)
+            .with_allocation_length(0x1000);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x1C, 0x01, 0x02, 0x10, 0x00, 0x00],
+            "receive diagnostic results command"
+        );
+    }
+
+    #[test]
+    fn short_page_test() {
+        let data = parse(vec![0x02, 0x00, 0x00, 0x02, 0xAA, 0xBB, 0x00, 0x00], 6);
+        assert_eq!(data, [0x02, 0x00, 0x00, 0x02, 0xAA, 0xBB], "short page");
+
+        let data = parse(vec![0x00; 4], 8);
+        assert_eq!(data.len(), 4, "clamped page");
+    }
 }
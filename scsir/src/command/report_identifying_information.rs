@@ -1,9 +1,13 @@
 #![allow(dead_code)]
 
+use std::{mem::size_of, time::Duration};
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
+    command::{
+        bitfield_bound_check, cdb_bytes, get_array, issue_full_length, timeout_setter, Control,
+    },
     data_wrapper::{AnyType, VecBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
@@ -12,6 +16,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct ReportIdentifyingInformationCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     information_type: u8,
     command_buffer: CommandBuffer,
 }
@@ -20,6 +25,7 @@ impl<'a> ReportIdentifyingInformationCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             information_type: 0,
             command_buffer: CommandBuffer::new()
                 .with_operation_code(OPERATION_CODE)
@@ -33,33 +39,79 @@ impl<'a> ReportIdentifyingInformationCommand<'a> {
     }
 
     // information_type must be less than 0x80
+    // 0x00 is the peripheral device identifying information, up to 512 bytes usually set to
+    // an ASCII label, 0x7F reports the supported information types
     pub fn information_type(&mut self, value: u8) -> &mut Self {
         self.information_type = value;
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<Vec<u8>> {
-        bitfield_bound_check!(self.information_type, 7, "information type")?;
+        Ok(self.issue_required_length()?.0)
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
 
+    // issues again with the allocation length reported by the identifying information length
+    // if the first response was truncated, the allocation length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<Vec<u8>> {
+        let allocation_length = self.command_buffer.allocation_length() as usize;
+        let (information, _) = issue_full_length(
+            self,
+            allocation_length,
+            u32::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u32);
+            },
+            Self::issue_required_length,
+            |&(_, required_length)| required_length,
+        )?;
+
+        Ok(information)
+    }
+
+    fn issue_required_length(&mut self) -> crate::Result<(Vec<u8>, usize)> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self
-                .command_buffer
-                .with_information_type(self.information_type),
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
         })
     }
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(self.information_type, 7, "information type")?;
+
+        Ok(self
+            .command_buffer
+            .with_information_type(self.information_type))
+    }
 }
 
 impl Scsi {
-    pub fn report_identifying_information(&self) -> ReportIdentifyingInformationCommand {
+    pub fn report_identifying_information(&self) -> ReportIdentifyingInformationCommand<'_> {
         ReportIdentifyingInformationCommand::new(self)
     }
 }
 
+crate::command::async_issue!(report_identifying_information: ReportIdentifyingInformationCommand);
+
+// also returns the allocation length the whole response needs
+fn parse(bytes: &[u8]) -> (Vec<u8>, usize) {
+    let (array, left) = get_array(bytes);
+    let header = ParameterHeader::from_bytes(array);
+    let length = usize::min(header.identifying_information_length() as usize, left.len());
+    let required_length =
+        size_of::<ParameterHeader>() + header.identifying_information_length() as usize;
+
+    (Vec::from(&left[..length]), required_length)
+}
+
 const OPERATION_CODE: u8 = 0xA3;
 const SERVICE_ACTION: u8 = 0x05;
 
@@ -76,8 +128,16 @@ struct CommandBuffer {
     control: B8,
 }
 
+#[bitfield]
+#[derive(Clone, Copy)]
+struct ParameterHeader {
+    reserved: B16,
+    identifying_information_length: B16,
+}
+
 struct ThisCommand {
     command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -87,7 +147,7 @@ impl Command for ThisCommand {
 
     type DataBufferWrapper = VecBufferWrapper;
 
-    type ReturnType = crate::Result<Vec<u8>>;
+    type ReturnType = crate::Result<(Vec<u8>, usize)>;
 
     fn direction(&self) -> DataDirection {
         DataDirection::FromDevice
@@ -105,11 +165,17 @@ impl Command for ThisCommand {
         self.command_buffer.allocation_length()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        Ok(std::mem::take(result.data))
+        let length = result.data_in_length(result.data().len());
+
+        Ok(parse(&result.data()[..length]))
     }
 }
 
@@ -119,6 +185,7 @@ mod tests {
     use std::mem::size_of;
 
     const COMMAND_LENGTH: usize = 12;
+    const PARAMETER_HEADER_LENGTH: usize = 4;
 
     #[test]
     fn layout_test() {
@@ -127,5 +194,71 @@ mod tests {
             COMMAND_LENGTH,
             concat!("Size of: ", stringify!(CommandBuffer))
         );
+
+        assert_eq!(
+            size_of::<ParameterHeader>(),
+            PARAMETER_HEADER_LENGTH,
+            concat!("Size of: ", stringify!(ParameterHeader))
+        );
+    }
+
+    #[test]
+    fn parse_test() {
+        // the label written by SET IDENTIFYING INFORMATION, padded to a multiple of 4 bytes
+        let label = b"backup-01\0\0\0";
+        let mut bytes = vec![0x00, 0x00, 0x00, label.len() as u8];
+        bytes.extend_from_slice(label);
+        bytes.extend_from_slice(&[0; 4]);
+
+        assert_eq!(
+            parse(&bytes),
+            (label.to_vec(), 16),
+            "identifying information"
+        );
+        assert_eq!(
+            parse(&bytes[..8]),
+            (b"back".to_vec(), 16),
+            "truncated identifying information"
+        );
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let label = b"backup-01\0\0\0";
+        let mut report = vec![0x00, 0x00, 0x00, label.len() as u8];
+        report.extend_from_slice(label);
+
+        let mock = crate::MockScsi::new();
+        // SET IDENTIFYING INFORMATION of the peripheral device identifying information
+        mock.expect(
+            &[
+                0xA4, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00,
+            ],
+            crate::MockResponse::good(),
+        )
+        // REPORT IDENTIFYING INFORMATION, answered with what was set
+        .expect(
+            &[
+                0xA3, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x04, 0x00, 0x00,
+            ],
+            crate::MockResponse::good().data(&report),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        scsi.set_identifying_information()
+            .information_type(0x00)
+            .parameter(label)
+            .issue()
+            .unwrap();
+        assert_eq!(mock.issued()[0].data, label, "label sent to the device");
+
+        let information = scsi
+            .report_identifying_information()
+            .information_type(0x00)
+            .allocation_length(4 + 512)
+            .issue()
+            .unwrap();
+        assert_eq!(information, label, "label read back");
+        mock.verify();
     }
 }
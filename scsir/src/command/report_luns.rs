@@ -1,33 +1,211 @@
 #![allow(dead_code)]
 
-use std::mem::size_of;
+use std::{mem::size_of, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::get_array,
+    command::{cdb_bytes, issue_full_length, timeout_setter, Control},
     data_wrapper::{AnyType, VecBufferWrapper},
     result_data::ResultData,
+    util::{be_u32, be_u64},
     Command, DataDirection, Scsi,
 };
 
 #[derive(Clone, Debug)]
 pub struct ReportLunsCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     descriptor_length: u32,
     command_buffer: CommandBuffer,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommandResult {
     pub total_descriptor_length: u32,
-    pub descriptors: Vec<u64>,
+    pub descriptors: Vec<Lun>,
+}
+
+/// The first level of a LUN structure of REPORT LUNS, decoded by its address method.
+///
+/// The structure is 8 bytes, hierarchical addressing puts further 2 byte levels after the
+/// first one, those are left to the caller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Lun {
+    Peripheral {
+        bus_identifier: u8,
+        target_or_lun: u8,
+    },
+    Flat {
+        lun: u16,
+    },
+    LogicalUnit {
+        target: u8,
+        bus_number: u8,
+        lun: u8,
+    },
+    WellKnown(WellKnownLun),
+    ExtendedFlat {
+        lun: u32,
+    },
+    LongExtendedFlat {
+        lun: u64,
+    },
+    NotSpecified,
+    // any other extended address method
+    Extended {
+        length: u8,
+        extended_address_method: u8,
+        raw: u64,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum WellKnownLun {
+    ReportLuns,
+    AccessControls,
+    TargetLogPages,
+    SecurityProtocol,
+    ManagementProtocol,
+    TargetCommands,
+    Other(u8),
+}
+
+impl Lun {
+    pub fn is_well_known(&self) -> bool {
+        matches!(self, Self::WellKnown(_))
+    }
+
+    /// The LUN number of a single level peripheral or flat space LUN, as used by most tools.
+    pub fn number(&self) -> Option<u64> {
+        match *self {
+            Self::Peripheral {
+                bus_identifier: 0,
+                target_or_lun,
+            } => Some(target_or_lun as u64),
+            Self::Flat { lun } => Some(lun as u64),
+            _ => None,
+        }
+    }
+
+    /// The inverse of [`Lun::number`], peripheral below 256 and flat space up to 0x3FFF.
+    pub fn from_number(value: u64) -> Option<Self> {
+        match value {
+            0..=0xFF => Some(Self::Peripheral {
+                bus_identifier: 0,
+                target_or_lun: value as u8,
+            }),
+            0x100..=0x3FFF => Some(Self::Flat { lun: value as u16 }),
+            _ => None,
+        }
+    }
+}
+
+impl From<u8> for WellKnownLun {
+    fn from(value: u8) -> Self {
+        match value {
+            0x01 => Self::ReportLuns,
+            0x02 => Self::AccessControls,
+            0x03 => Self::TargetLogPages,
+            0x04 => Self::SecurityProtocol,
+            0x05 => Self::ManagementProtocol,
+            0x06 => Self::TargetCommands,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl From<WellKnownLun> for u8 {
+    fn from(value: WellKnownLun) -> Self {
+        match value {
+            WellKnownLun::ReportLuns => 0x01,
+            WellKnownLun::AccessControls => 0x02,
+            WellKnownLun::TargetLogPages => 0x03,
+            WellKnownLun::SecurityProtocol => 0x04,
+            WellKnownLun::ManagementProtocol => 0x05,
+            WellKnownLun::TargetCommands => 0x06,
+            WellKnownLun::Other(other) => other,
+        }
+    }
+}
+
+impl From<u64> for Lun {
+    fn from(value: u64) -> Self {
+        let bytes = value.to_be_bytes();
+        match bytes[0] >> 6 {
+            0b00 => Self::Peripheral {
+                bus_identifier: bytes[0] & 0b0011_1111,
+                target_or_lun: bytes[1],
+            },
+            0b01 => Self::Flat {
+                lun: u16::from_be_bytes([bytes[0], bytes[1]]) & 0x3FFF,
+            },
+            0b10 => Self::LogicalUnit {
+                target: bytes[0] & 0b0011_1111,
+                bus_number: bytes[1] >> 5,
+                lun: bytes[1] & 0b0001_1111,
+            },
+            _ => {
+                let length = (bytes[0] >> 4) & 0b11;
+                let extended_address_method = bytes[0] & 0b1111;
+                match (length, extended_address_method) {
+                    (0b00, 0x1) => Self::WellKnown(bytes[1].into()),
+                    (0b01, 0x2) => Self::ExtendedFlat {
+                        lun: u32::from_be_bytes([0, bytes[1], bytes[2], bytes[3]]),
+                    },
+                    (0b10, 0x2) => Self::LongExtendedFlat {
+                        lun: (value >> 16) & 0xFF_FFFF_FFFF,
+                    },
+                    (0b11, 0xF) => Self::NotSpecified,
+                    _ => Self::Extended {
+                        length,
+                        extended_address_method,
+                        raw: value,
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Encodes the first level back into a LUN structure, [`Lun::Extended`] keeps its raw value.
+impl From<Lun> for u64 {
+    fn from(value: Lun) -> Self {
+        let first = match value {
+            Lun::Peripheral {
+                bus_identifier,
+                target_or_lun,
+            } => u16::from_be_bytes([bus_identifier & 0b0011_1111, target_or_lun]) as u64,
+            Lun::Flat { lun } => (0x4000 | (lun & 0x3FFF)) as u64,
+            Lun::LogicalUnit {
+                target,
+                bus_number,
+                lun,
+            } => u16::from_be_bytes([
+                0x80 | (target & 0b0011_1111),
+                (bus_number << 5) | (lun & 0b0001_1111),
+            ]) as u64,
+            Lun::WellKnown(lun) => u16::from_be_bytes([0xC1, lun.into()]) as u64,
+            Lun::ExtendedFlat { lun } => return (0xD2 << 24 | (lun as u64 & 0xFF_FFFF)) << 32,
+            Lun::LongExtendedFlat { lun } => return (0xE2 << 40 | (lun & 0xFF_FFFF_FFFF)) << 16,
+            Lun::NotSpecified => return u64::MAX,
+            Lun::Extended { raw, .. } => return raw,
+        };
+
+        first << 48
+    }
 }
 
 impl<'a> ReportLunsCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
             descriptor_length: 0,
         }
@@ -38,8 +216,8 @@ impl<'a> ReportLunsCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
@@ -49,7 +227,18 @@ impl<'a> ReportLunsCommand<'a> {
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<CommandResult> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
         let max_descriptor_length = (u32::MAX - 8) / 8;
         if self.descriptor_length > max_descriptor_length {
             return Err(
@@ -61,20 +250,36 @@ impl<'a> ReportLunsCommand<'a> {
             )));
         }
 
-        self.interface.issue(&ThisCommand {
-            command_buffer: self
-                .command_buffer
-                .with_allocation_length(self.descriptor_length * 8 + 8),
-        })
+        Ok(self
+            .command_buffer
+            .with_allocation_length(self.descriptor_length * 8 + 8))
+    }
+
+    // issues again with the descriptor length reported by the LUN list length if the first
+    // response was truncated, the descriptor length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<CommandResult> {
+        let descriptor_length = self.descriptor_length as usize;
+        issue_full_length(
+            self,
+            descriptor_length,
+            (u32::MAX as usize - 8) / 8,
+            |command, length| {
+                command.descriptor_length(length as u32);
+            },
+            Self::issue,
+            |result| result.total_descriptor_length as usize / size_of::<u64>(),
+        )
     }
 }
 
 impl Scsi {
-    pub fn report_luns(&self) -> ReportLunsCommand {
+    pub fn report_luns(&self) -> ReportLunsCommand<'_> {
         ReportLunsCommand::new(self)
     }
 }
 
+crate::command::async_issue!(report_luns: ReportLunsCommand);
+
 const OPERATION_CODE: u8 = 0xA0;
 
 #[bitfield]
@@ -91,6 +296,7 @@ struct CommandBuffer {
 
 struct ThisCommand {
     command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -118,21 +324,34 @@ impl Command for ThisCommand {
         self.command_buffer.allocation_length()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        let (length, left) = get_array(result.data);
-        let (skip, left) = get_array(left);
-        _ = u32::from_be_bytes(skip);
+        let length = result.data_in_length(result.data.len());
 
-        Ok(CommandResult {
-            total_descriptor_length: u32::from_be_bytes(length),
-            descriptors: left
-                .chunks(size_of::<u64>())
-                .map(|c| u64::from_be_bytes(get_array(c).0))
-                .collect(),
-        })
+        Ok(parse(&result.data[..length]))
+    }
+}
+
+fn parse(bytes: &[u8]) -> CommandResult {
+    // LUN LIST LENGTH, then 4 reserved bytes
+    let total_descriptor_length = be_u32(bytes).unwrap_or(0);
+    let left = bytes.get(8..).unwrap_or_default();
+    // the list may be truncated if the allocation length was too small
+    let length = usize::min(total_descriptor_length as usize, left.len());
+
+    CommandResult {
+        total_descriptor_length,
+        descriptors: left[..length]
+            .chunks_exact(size_of::<u64>())
+            .filter_map(be_u64)
+            .map(Lun::from)
+            .collect(),
     }
 }
 
@@ -151,4 +370,174 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn parse_test() {
+        let mut bytes = vec![0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00];
+        bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        bytes.extend_from_slice(&[0x41, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        bytes.extend_from_slice(&[0x00; 16]);
+
+        let result = parse(&bytes);
+        assert_eq!(result.total_descriptor_length, 16, "lun list length");
+        assert_eq!(
+            result.descriptors,
+            [
+                Lun::Peripheral {
+                    bus_identifier: 0,
+                    target_or_lun: 0
+                },
+                Lun::Flat { lun: 0x105 }
+            ],
+            "luns"
+        );
+
+        let result = parse(&bytes[..20]);
+        assert_eq!(
+            result.total_descriptor_length, 16,
+            "truncated lun list length"
+        );
+        assert_eq!(result.descriptors.len(), 1, "truncated luns");
+    }
+
+    #[test]
+    fn full_length_test() {
+        use crate::{MockResponse, MockScsi};
+
+        let mut response = vec![0x00, 0x00, 0x00, 0x18, 0x00, 0x00, 0x00, 0x00];
+        for lun in 0..3 {
+            response.extend_from_slice(&[0x00, lun, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        }
+
+        let mock = MockScsi::new();
+        // room for one LUN, then for the three the device reported
+        mock.expect(
+            &[
+                0xA0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00,
+            ],
+            MockResponse::good().data(&response[..16]),
+        )
+        .expect(
+            &[
+                0xA0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00,
+            ],
+            MockResponse::good().data(&response),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let result = scsi
+            .report_luns()
+            .descriptor_length(1)
+            .issue_full_length()
+            .unwrap();
+        assert_eq!(
+            result
+                .descriptors
+                .iter()
+                .map(Lun::number)
+                .collect::<Vec<_>>(),
+            [Some(0), Some(1), Some(2)],
+            "every lun"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    fn address_method_test() {
+        let cases = [
+            (
+                0x0000_0000_0000_0000,
+                Lun::Peripheral {
+                    bus_identifier: 0,
+                    target_or_lun: 0,
+                },
+            ),
+            (
+                0x0107_0000_0000_0000,
+                Lun::Peripheral {
+                    bus_identifier: 1,
+                    target_or_lun: 7,
+                },
+            ),
+            (0x7FFF_0000_0000_0000, Lun::Flat { lun: 0x3FFF }),
+            (
+                0x8345_0000_0000_0000,
+                Lun::LogicalUnit {
+                    target: 3,
+                    bus_number: 2,
+                    lun: 5,
+                },
+            ),
+            (
+                0xC101_0000_0000_0000,
+                Lun::WellKnown(WellKnownLun::ReportLuns),
+            ),
+            (
+                0xC1F0_0000_0000_0000,
+                Lun::WellKnown(WellKnownLun::Other(0xF0)),
+            ),
+            (0xD212_3456_0000_0000, Lun::ExtendedFlat { lun: 0x12_3456 }),
+            (
+                0xE212_3456_789A_0000,
+                Lun::LongExtendedFlat {
+                    lun: 0x12_3456_789A,
+                },
+            ),
+            (0xFFFF_FFFF_FFFF_FFFF, Lun::NotSpecified),
+            (
+                0xF300_0000_0000_0001,
+                Lun::Extended {
+                    length: 0b11,
+                    extended_address_method: 0x3,
+                    raw: 0xF300_0000_0000_0001,
+                },
+            ),
+        ];
+
+        for (raw, lun) in cases {
+            assert_eq!(Lun::from(raw), lun, "decode {:016X}", raw);
+            assert_eq!(u64::from(lun), raw, "encode {:016X}", raw);
+        }
+        assert!(
+            Lun::from(0xC103_0000_0000_0000).is_well_known(),
+            "target log pages"
+        );
+        assert!(!Lun::from(0).is_well_known(), "lun 0");
+    }
+
+    #[test]
+    fn number_test() {
+        assert_eq!(
+            Lun::from(0x0005_0000_0000_0000).number(),
+            Some(5),
+            "peripheral"
+        );
+        assert_eq!(
+            Lun::from(0x4105_0000_0000_0000).number(),
+            Some(0x105),
+            "flat"
+        );
+        assert_eq!(
+            Lun::from(0x0105_0000_0000_0000).number(),
+            None,
+            "peripheral on another bus"
+        );
+        assert_eq!(
+            Lun::from(0xC101_0000_0000_0000).number(),
+            None,
+            "well known"
+        );
+
+        assert_eq!(
+            Lun::from_number(5).map(u64::from),
+            Some(0x0005_0000_0000_0000),
+            "peripheral"
+        );
+        assert_eq!(
+            Lun::from_number(0x105).map(u64::from),
+            Some(0x4105_0000_0000_0000),
+            "flat"
+        );
+        assert_eq!(Lun::from_number(0x4000), None, "out of flat space");
+    }
 }
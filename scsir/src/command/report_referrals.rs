@@ -0,0 +1,329 @@
+#![allow(dead_code)]
+
+use std::{mem::size_of, time::Duration};
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{cdb_bytes, get_array, issue_full_length, timeout_setter, Control},
+    data_wrapper::{AnyType, VecBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct ReportReferralsCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    command_buffer: CommandBuffer,
+}
+
+#[derive(Clone, Debug)]
+pub struct UserDataSegmentReferral {
+    pub first_user_data_segment_logical_block_address: u64,
+    pub last_user_data_segment_logical_block_address: u64,
+    pub target_port_groups: Vec<TargetPortGroup>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TargetPortGroup {
+    pub preferred: bool,
+    pub asymmetric_access_state: u8,
+    pub target_port_group: u16,
+}
+
+impl<'a> ReportReferralsCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            command_buffer: CommandBuffer::new()
+                .with_operation_code(OPERATION_CODE)
+                .with_service_action(SERVICE_ACTION),
+        }
+    }
+
+    pub fn logical_block_address(&mut self, value: u64) -> &mut Self {
+        self.command_buffer.set_logical_block_address(value);
+        self
+    }
+
+    pub fn allocation_length(&mut self, value: u32) -> &mut Self {
+        self.command_buffer.set_allocation_length(value);
+        self
+    }
+
+    // only report the segment containing logical_block_address
+    pub fn one_segment(&mut self, value: bool) -> &mut Self {
+        self.command_buffer.set_one_segment(value.into());
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<Vec<UserDataSegmentReferral>> {
+        Ok(self.issue_required_length()?.0)
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    // issues again with the allocation length reported by the descriptor length if the first
+    // response was truncated, the allocation length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<Vec<UserDataSegmentReferral>> {
+        let allocation_length = self.command_buffer.allocation_length() as usize;
+        let (referrals, _) = issue_full_length(
+            self,
+            allocation_length,
+            u32::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u32);
+            },
+            Self::issue_required_length,
+            |&(_, required_length)| required_length,
+        )?;
+
+        Ok(referrals)
+    }
+
+    fn issue_required_length(&mut self) -> crate::Result<(Vec<UserDataSegmentReferral>, usize)> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
+        })
+    }
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        Ok(self.command_buffer)
+    }
+}
+
+impl Scsi {
+    pub fn report_referrals(&self) -> ReportReferralsCommand<'_> {
+        ReportReferralsCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(report_referrals: ReportReferralsCommand);
+
+fn parse(bytes: &[u8]) -> Vec<UserDataSegmentReferral> {
+    let (array, left) = get_array(bytes);
+    let header = ParameterHeader::from_bytes(array);
+
+    let mut bytes = &left[..usize::min(header.descriptor_length() as usize, left.len())];
+    let mut referrals = vec![];
+
+    while bytes.len() >= size_of::<ReferralDescriptorHeader>() {
+        let (array, left) = get_array(bytes);
+        let descriptor = ReferralDescriptorHeader::from_bytes(array);
+
+        let length = usize::min(
+            descriptor.number_of_target_port_group_descriptors() as usize
+                * size_of::<TargetPortGroupDescriptor>(),
+            left.len(),
+        );
+        let target_port_groups = left[..length]
+            .chunks_exact(size_of::<TargetPortGroupDescriptor>())
+            .map(|x| {
+                let (array, _) = get_array(x);
+                let group = TargetPortGroupDescriptor::from_bytes(array);
+
+                TargetPortGroup {
+                    preferred: group.preferred() != 0,
+                    asymmetric_access_state: group.asymmetric_access_state(),
+                    target_port_group: group.target_port_group(),
+                }
+            })
+            .collect();
+
+        referrals.push(UserDataSegmentReferral {
+            first_user_data_segment_logical_block_address: descriptor
+                .first_user_data_segment_logical_block_address(),
+            last_user_data_segment_logical_block_address: descriptor
+                .last_user_data_segment_logical_block_address(),
+            target_port_groups,
+        });
+
+        bytes = &left[length..];
+    }
+
+    referrals
+}
+
+const OPERATION_CODE: u8 = 0x9E;
+const SERVICE_ACTION: u8 = 0x13;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    logical_block_address: B64,
+    allocation_length: B32,
+    reserved_1: B7,
+    one_segment: B1,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct ParameterHeader {
+    reserved: B16,
+    descriptor_length: B16,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct ReferralDescriptorHeader {
+    reserved: B24,
+    number_of_target_port_group_descriptors: B8,
+    first_user_data_segment_logical_block_address: B64,
+    last_user_data_segment_logical_block_address: B64,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct TargetPortGroupDescriptor {
+    preferred: B1,
+    reserved_0: B3,
+    asymmetric_access_state: B4,
+    reserved_1: B8,
+    target_port_group: B16,
+}
+
+struct ThisCommand {
+    command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
+}
+
+impl Command for ThisCommand {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = VecBufferWrapper;
+
+    type ReturnType = crate::Result<(Vec<UserDataSegmentReferral>, usize)>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::FromDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        unsafe { VecBufferWrapper::with_len(self.command_buffer.allocation_length() as usize) }
+    }
+
+    fn data_size(&self) -> u32 {
+        self.command_buffer.allocation_length()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        let length = result.data_in_length(result.data().len());
+        let bytes = &result.data()[..length];
+        let (array, _) = get_array(bytes);
+        let required_length = size_of::<ParameterHeader>()
+            + ParameterHeader::from_bytes(array).descriptor_length() as usize;
+
+        Ok((parse(bytes), required_length))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+    const PARAMETER_HEADER_LENGTH: usize = 4;
+    const REFERRAL_DESCRIPTOR_HEADER_LENGTH: usize = 20;
+    const TARGET_PORT_GROUP_DESCRIPTOR_LENGTH: usize = 4;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+
+        assert_eq!(
+            size_of::<ParameterHeader>(),
+            PARAMETER_HEADER_LENGTH,
+            concat!("Size of: ", stringify!(ParameterHeader))
+        );
+
+        assert_eq!(
+            size_of::<ReferralDescriptorHeader>(),
+            REFERRAL_DESCRIPTOR_HEADER_LENGTH,
+            concat!("Size of: ", stringify!(ReferralDescriptorHeader))
+        );
+
+        assert_eq!(
+            size_of::<TargetPortGroupDescriptor>(),
+            TARGET_PORT_GROUP_DESCRIPTOR_LENGTH,
+            concat!("Size of: ", stringify!(TargetPortGroupDescriptor))
+        );
+    }
+
+    #[test]
+    fn parse_test() {
+        let bytes = [
+            0x00, 0x00, 0x00, 0x34, // header
+            0x00, 0x00, 0x00, 0x02, // two target port groups
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // first logical block address
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x0F, 0xFF, 0xFF, // last logical block address
+            0x80, 0x00, 0x00, 0x01, // preferred, active/optimized
+            0x01, 0x00, 0x00, 0x02, // active/non-optimized
+            0x00, 0x00, 0x00, 0x01, // one target port group
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, // first logical block address
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x1F, 0xFF, 0xFF, // last logical block address
+            0x80, 0x00, 0x00, 0x02,
+        ];
+
+        let referrals = parse(&bytes);
+        assert_eq!(referrals.len(), 2, "referrals");
+
+        let referral = &referrals[0];
+        assert_eq!(
+            referral.first_user_data_segment_logical_block_address, 0,
+            "first logical block address"
+        );
+        assert_eq!(
+            referral.last_user_data_segment_logical_block_address, 0xF_FFFF,
+            "last logical block address"
+        );
+        assert_eq!(referral.target_port_groups.len(), 2, "target port groups");
+        assert!(referral.target_port_groups[0].preferred, "preferred");
+        assert_eq!(
+            referral.target_port_groups[1].asymmetric_access_state, 1,
+            "asymmetric access state"
+        );
+        assert_eq!(
+            referral.target_port_groups[1].target_port_group, 2,
+            "target port group"
+        );
+
+        let referral = &referrals[1];
+        assert_eq!(
+            referral.first_user_data_segment_logical_block_address, 0x10_0000,
+            "first logical block address"
+        );
+        assert_eq!(referral.target_port_groups.len(), 1, "target port groups");
+    }
+}
@@ -1,11 +1,14 @@
 #![allow(dead_code)]
 
-use std::mem::size_of;
+use std::{mem::size_of, sync::Arc, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::{bitfield_bound_check, get_array},
+    command::{
+        bitfield_bound_check, cdb_bytes, get_array, issue_full_length, timeout_setter, truncated,
+        Control,
+    },
     data_wrapper::{AnyType, VecBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
@@ -14,11 +17,14 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct ReportSupportedOperationCodesCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     reporting_options: u8,
     command_buffer: CommandBuffer,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum CommandResult {
     AllCommands(AllCommands),
     OneCommand(OneCommand),
@@ -26,27 +32,40 @@ pub enum CommandResult {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AllCommands {
     pub required_allocation_length: u32,
     pub descriptors: Vec<CommandDescriptor>,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommandDescriptor {
     pub operation_code: u8,
     pub service_action: Option<u16>,
     pub cdb_length: u16,
+    // byte 5 of the descriptor, including the CTDP and SERVACTV bits
+    pub support_flags: u8,
     pub timeout_descriptor: Option<TimeoutsDescriptor>,
 }
 
+/// The cached "all commands" report, see [`Scsi::supported_commands`]
 #[derive(Clone, Debug)]
+pub struct SupportedCommands {
+    descriptors: Vec<CommandDescriptor>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OneCommand {
+    pub required_allocation_length: u32,
     pub support: u8,
     pub cdb_usage_data: Vec<u8>,
     pub timeout_descriptor: Option<TimeoutsDescriptor>,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeoutsDescriptor {
     pub command_specific: u8,
     pub nominal_command_processing_timeout: u32,
@@ -57,6 +76,7 @@ impl<'a> ReportSupportedOperationCodesCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             reporting_options: 0,
             command_buffer: CommandBuffer::new()
                 .with_operation_code(OPERATION_CODE)
@@ -71,6 +91,7 @@ impl<'a> ReportSupportedOperationCodesCommand<'a> {
     }
 
     // reporting_options must be less than 0x08
+    // 0b000 reports all commands, 0b001 one command, 0b010 one command with service action
     pub fn reporting_options(&mut self, value: u8) -> &mut Self {
         self.reporting_options = value;
         self
@@ -91,26 +112,132 @@ impl<'a> ReportSupportedOperationCodesCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<CommandResult> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
         bitfield_bound_check!(self.reporting_options, 3, "reporting options")?;
 
-        self.interface.issue(&ThisCommand {
-            command_buffer: self
-                .command_buffer
-                .with_reporting_options(self.reporting_options),
+        Ok(self
+            .command_buffer
+            .with_reporting_options(self.reporting_options))
+    }
+
+    // issues again with the allocation length reported by the device if the first response
+    // was truncated, the allocation length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<CommandResult> {
+        let allocation_length = self.command_buffer.allocation_length() as usize;
+        issue_full_length(
+            self,
+            allocation_length,
+            u32::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u32);
+            },
+            Self::issue,
+            CommandResult::required_length,
+        )
+    }
+}
+
+impl CommandResult {
+    /// The allocation length needed for the whole response, as its header reports it
+    pub fn required_length(&self) -> usize {
+        match self {
+            Self::AllCommands(commands) => commands.required_allocation_length as usize,
+            Self::OneCommand(command) => command.required_allocation_length as usize,
+            // the format of other reporting options is unknown
+            Self::Other(bytes) => bytes.len(),
+        }
+    }
+}
+
+impl SupportedCommands {
+    // service_action must be None for commands without one, e.g. READ (16)
+    pub fn supports(&self, operation_code: u8, service_action: Option<u16>) -> bool {
+        self.descriptors.iter().any(|descriptor| {
+            descriptor.operation_code == operation_code
+                && descriptor.service_action == service_action
         })
     }
+
+    pub fn descriptors(&self) -> &[CommandDescriptor] {
+        &self.descriptors
+    }
 }
 
 impl Scsi {
-    pub fn report_supported_operation_codes(&self) -> ReportSupportedOperationCodesCommand {
+    pub fn report_supported_operation_codes(&self) -> ReportSupportedOperationCodesCommand<'_> {
         ReportSupportedOperationCodesCommand::new(self)
     }
+
+    /// Every command the device supports, reported once and then cached on the handle.
+    ///
+    /// A firmware download can change the command set, call
+    /// [`Scsi::invalidate_supported_commands`] afterwards to report it again.
+    pub fn supported_commands(&self) -> crate::Result<Arc<SupportedCommands>> {
+        // held while reporting, so concurrent callers don't all issue the command
+        let mut cache = self.supported_commands_cache().lock().unwrap();
+        if let Some(commands) = cache.as_ref() {
+            return Ok(commands.clone());
+        }
+
+        let commands = Arc::new(report_all_commands(self)?);
+        *cache = Some(commands.clone());
+        Ok(commands)
+    }
+
+    // the cached report, without issuing one
+    pub(crate) fn cached_supported_commands(&self) -> Option<Arc<SupportedCommands>> {
+        self.supported_commands_cache().lock().unwrap().clone()
+    }
+
+    pub fn invalidate_supported_commands(&self) {
+        *self.supported_commands_cache().lock().unwrap() = None;
+    }
+}
+
+crate::command::async_issue!(report_supported_operation_codes: ReportSupportedOperationCodesCommand);
+
+// enough for about 500 commands without timeouts descriptors, which covers most devices
+const INITIAL_ALLOCATION_LENGTH: u32 = 4096;
+
+fn report_all_commands(interface: &Scsi) -> crate::Result<SupportedCommands> {
+    let mut command = interface.report_supported_operation_codes();
+    let result = command
+        .allocation_length(INITIAL_ALLOCATION_LENGTH)
+        .issue_full_length()?;
+
+    let CommandResult::AllCommands(commands) = result else {
+        unreachable!("reporting options 0 always report all commands")
+    };
+
+    // the second report, with the length the first one asked for, must have fit
+    if truncated(
+        commands.required_allocation_length as usize,
+        command.command_buffer.allocation_length() as usize,
+    ) {
+        return Err(crate::Error::Other(String::from(
+            "the supported commands grew between two reports",
+        )));
+    }
+
+    Ok(SupportedCommands {
+        descriptors: commands.descriptors,
+    })
 }
 
 const OPERATION_CODE: u8 = 0xA3;
@@ -173,6 +300,7 @@ struct CommandTimeoutsDescriptor {
 
 struct ThisCommand {
     command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -200,56 +328,21 @@ impl Command for ThisCommand {
         self.command_buffer.allocation_length()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        if self.command_buffer.reporting_options() == 0 {
-            let (bytes, left) = get_array(result.data);
-            let header = AllCommandsParameterDataHeader::from_bytes(bytes);
-            let mut descriptors = vec![];
-            let mut left = &left[..usize::min(header.command_data_length() as usize, left.len())];
-            while !left.is_empty() {
-                let (bytes, l) = get_array(left);
-                left = l;
-                let command_descriptor = CommandDescriptorHeader::from_bytes(bytes);
-                let service_action = if command_descriptor.service_action_valid() != 0 {
-                    Some(command_descriptor.service_action())
-                } else {
-                    None
-                };
-
-                let timeout_descriptor = if command_descriptor.command_timeouts_descriptor_present()
-                    != 0
-                {
-                    let (bytes, l) = get_array(left);
-                    left = l;
-                    let timeout_descriptor = CommandTimeoutsDescriptor::from_bytes(bytes);
-                    Some(TimeoutsDescriptor {
-                        command_specific: timeout_descriptor.command_specific(),
-                        nominal_command_processing_timeout: timeout_descriptor
-                            .nominal_command_processing_timeout(),
-                        recommend_command_timeout: timeout_descriptor.recommend_command_timeout(),
-                    })
-                } else {
-                    None
-                };
-
-                descriptors.push(CommandDescriptor {
-                    operation_code: command_descriptor.operation_code(),
-                    service_action,
-                    cdb_length: command_descriptor.cdb_length(),
-                    timeout_descriptor,
-                })
-            }
+        let length = result.data_in_length(result.data.len());
+        let data = &result.data[..length];
 
-            Ok(CommandResult::AllCommands(AllCommands {
-                required_allocation_length: header.command_data_length()
-                    + size_of::<AllCommandsParameterDataHeader>() as u32,
-                descriptors,
-            }))
+        if self.command_buffer.reporting_options() == 0 {
+            Ok(CommandResult::AllCommands(parse_all_commands(data)))
         } else if self.command_buffer.reporting_options() < 0b100 {
-            let (bytes, left) = get_array(result.data);
+            let (bytes, left) = get_array(data);
             let header = OneCommandParameterDataHeader::from_bytes(bytes);
             let (cdb_data, left) =
                 left[..].split_at(usize::min(header.cdb_size() as usize, left.len()));
@@ -267,17 +360,72 @@ impl Command for ThisCommand {
                 None
             };
 
+            let timeout_descriptor_length = if timeout_descriptor.is_some() {
+                size_of::<CommandTimeoutsDescriptor>()
+            } else {
+                0
+            };
+
             Ok(CommandResult::OneCommand(OneCommand {
+                required_allocation_length: (size_of::<OneCommandParameterDataHeader>()
+                    + header.cdb_size() as usize
+                    + timeout_descriptor_length) as u32,
                 support: header.support(),
                 cdb_usage_data: cdb_data,
                 timeout_descriptor,
             }))
         } else {
-            Ok(CommandResult::Other(std::mem::take(result.data)))
+            Ok(CommandResult::Other(Vec::from(data)))
         }
     }
 }
 
+fn parse_all_commands(bytes: &[u8]) -> AllCommands {
+    let (bytes, left) = get_array(bytes);
+    let header = AllCommandsParameterDataHeader::from_bytes(bytes);
+    let mut descriptors = vec![];
+    let mut left = &left[..usize::min(header.command_data_length() as usize, left.len())];
+    while left.len() >= size_of::<CommandDescriptorHeader>() {
+        let (bytes, l) = get_array(left);
+        left = l;
+        let support_flags = bytes[5];
+        let command_descriptor = CommandDescriptorHeader::from_bytes(bytes);
+        let service_action = if command_descriptor.service_action_valid() != 0 {
+            Some(command_descriptor.service_action())
+        } else {
+            None
+        };
+
+        let timeout_descriptor = if command_descriptor.command_timeouts_descriptor_present() != 0 {
+            let (bytes, l) = get_array(left);
+            left = l;
+            let timeout_descriptor = CommandTimeoutsDescriptor::from_bytes(bytes);
+            Some(TimeoutsDescriptor {
+                command_specific: timeout_descriptor.command_specific(),
+                nominal_command_processing_timeout: timeout_descriptor
+                    .nominal_command_processing_timeout(),
+                recommend_command_timeout: timeout_descriptor.recommend_command_timeout(),
+            })
+        } else {
+            None
+        };
+
+        descriptors.push(CommandDescriptor {
+            operation_code: command_descriptor.operation_code(),
+            service_action,
+            cdb_length: command_descriptor.cdb_length(),
+            support_flags,
+            timeout_descriptor,
+        })
+    }
+
+    AllCommands {
+        required_allocation_length: header.command_data_length()
+            + size_of::<AllCommandsParameterDataHeader>() as u32,
+        descriptors,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -321,4 +469,81 @@ mod tests {
             concat!("Size of: ", stringify!(CommandTimeoutsDescriptor))
         );
     }
+
+    #[test]
+    fn all_commands_test() {
+        let bytes = [
+            0x00, 0x00, 0x00, 0x24, // command data length
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, // TEST UNIT READY
+            0x9E, 0x00, 0x00, 0x10, 0x00, 0x03, 0x00, 0x10, // READ CAPACITY (16)
+            0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1E, 0x00, 0x00, 0x00,
+            0x3C, // timeouts
+            0x28, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // truncated READ (10)
+        ];
+
+        let result = parse_all_commands(&bytes);
+        assert_eq!(result.required_allocation_length, 40, "required length");
+        assert_eq!(result.descriptors.len(), 2, "complete descriptors only");
+
+        let descriptor = &result.descriptors[0];
+        assert_eq!(descriptor.operation_code, 0x00, "operation code");
+        assert_eq!(descriptor.service_action, None, "no service action");
+        assert_eq!(descriptor.cdb_length, 6, "cdb length");
+        assert!(descriptor.timeout_descriptor.is_none(), "no timeouts");
+
+        let descriptor = &result.descriptors[1];
+        assert_eq!(descriptor.operation_code, 0x9E, "operation code");
+        assert_eq!(descriptor.service_action, Some(0x10), "service action");
+        assert_eq!(descriptor.cdb_length, 16, "cdb length");
+        assert_eq!(descriptor.support_flags, 0x03, "support flags");
+        let timeouts = descriptor.timeout_descriptor.as_ref().unwrap();
+        assert_eq!(
+            timeouts.nominal_command_processing_timeout, 30,
+            "nominal timeout"
+        );
+        assert_eq!(
+            timeouts.recommend_command_timeout, 60,
+            "recommended timeout"
+        );
+    }
+
+    #[test]
+    fn supported_commands_test() {
+        use crate::{MockResponse, MockScsi};
+
+        // TEST UNIT READY and READ CAPACITY (16)
+        const REPORT: [u8; 20] = [
+            0x00, 0x00, 0x00, 0x10, // command data length
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, // TEST UNIT READY
+            0x9E, 0x00, 0x00, 0x10, 0x00, 0x01, 0x00, 0x10, // READ CAPACITY (16)
+        ];
+        // every command, with an allocation length of 4 KiB
+        const REPORT_ALL: [u8; 12] = [
+            0xA3, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00,
+        ];
+
+        // reported once, and again after the cache is invalidated
+        let mock = MockScsi::new();
+        mock.expect(&REPORT_ALL, MockResponse::good().data(&REPORT))
+            .expect(&REPORT_ALL, MockResponse::good().data(&REPORT));
+
+        let scsi = Scsi::mock(mock.clone());
+        let commands = scsi.supported_commands().unwrap();
+        assert!(commands.supports(0x00, None), "TEST UNIT READY");
+        assert!(commands.supports(0x9E, Some(0x10)), "READ CAPACITY (16)");
+        assert!(
+            !commands.supports(0x9E, Some(0x11)),
+            "GET LBA STATUS isn't reported"
+        );
+        assert!(!commands.supports(0x88, None), "READ (16) isn't reported");
+        assert_eq!(commands.descriptors().len(), 2, "descriptors");
+
+        scsi.supported_commands().unwrap();
+        assert_eq!(mock.issued().len(), 1, "cached");
+
+        scsi.invalidate_supported_commands();
+        assert!(scsi.supported_commands().unwrap().supports(0x00, None));
+        assert_eq!(mock.issued().len(), 2, "reported again");
+        mock.verify();
+    }
 }
@@ -1,18 +1,24 @@
 #![allow(dead_code)]
 
-use std::mem::size_of;
+use std::{mem::size_of, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
-use crate::{result_data::ResultData, Command, DataDirection, Scsi};
+use crate::{
+    command::{cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
 
 #[derive(Clone, Debug)]
 pub struct ReportSupportedTaskManagementFunctionsCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     command_buffer: CommandBuffer,
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommandResult {
     pub abort_task_supported: bool,
     pub abort_task_set_supported: bool,
@@ -20,6 +26,10 @@ pub struct CommandResult {
     pub clear_task_set_supported: bool,
     pub logical_unit_reset_supported: bool,
     pub query_task_supported: bool,
+    // obsolete since SPC-4
+    pub target_reset_supported: bool,
+    // obsolete since SPC-4
+    pub wakeup_supported: bool,
     pub query_asynchronous_event_supported: bool,
     pub query_task_set_supported: bool,
     pub i_t_nexus_reset_supported: bool,
@@ -41,6 +51,7 @@ impl<'a> ReportSupportedTaskManagementFunctionsCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new()
                 .with_operation_code(OPERATION_CODE)
                 .with_service_action(SERVICE_ACTION)
@@ -56,26 +67,39 @@ impl<'a> ReportSupportedTaskManagementFunctionsCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<CommandResult> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self.command_buffer,
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        Ok(self.command_buffer)
+    }
 }
 
 impl Scsi {
     pub fn report_supported_task_management_functions(
         &self,
-    ) -> ReportSupportedTaskManagementFunctionsCommand {
+    ) -> ReportSupportedTaskManagementFunctionsCommand<'_> {
         ReportSupportedTaskManagementFunctionsCommand::new(self)
     }
 }
 
+crate::command::async_issue!(
+    report_supported_task_management_functions: ReportSupportedTaskManagementFunctionsCommand
+);
+
 const OPERATION_CODE: u8 = 0xA3;
 const SERVICE_ACTION: u8 = 0x0D;
 
@@ -101,7 +125,8 @@ struct ReportSupportedTaskManagementFunctionsExtendedParameterData {
     clear_task_set_supported: B1,
     logical_unit_reset_supported: B1,
     query_task_supported: B1,
-    obsolete: B2,
+    target_reset_supported: B1,
+    wakeup_supported: B1,
     reserved_0: B5,
     query_asynchronous_event_supported: B1,
     query_task_set_supported: B1,
@@ -128,6 +153,7 @@ struct ReportSupportedTaskManagementFunctionsExtendedParameterData {
 
 struct ThisCommand {
     command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -155,37 +181,45 @@ impl Command for ThisCommand {
         self.command_buffer.allocation_length()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        let data = result.data;
-        Ok(CommandResult {
-            abort_task_supported: data.abort_task_supported() != 0,
-            abort_task_set_supported: data.abort_task_set_supported() != 0,
-            clear_aca_supported: data.clear_aca_supported() != 0,
-            clear_task_set_supported: data.clear_task_set_supported() != 0,
-            logical_unit_reset_supported: data.logical_unit_reset_supported() != 0,
-            query_task_supported: data.query_task_supported() != 0,
-            query_asynchronous_event_supported: data.query_asynchronous_event_supported() != 0,
-            query_task_set_supported: data.query_task_set_supported() != 0,
-            i_t_nexus_reset_supported: data.i_t_nexus_reset_supported() != 0,
-            task_management_function_timeouts_valid: data.task_management_function_timeouts_valid()
-                != 0,
-            abort_task_timeout_selector: data.abort_task_timeout_selector() != 0,
-            abort_task_set_timeout_selector: data.abort_task_set_timeout_selector() != 0,
-            clear_aca_timeout_selector: data.clear_aca_timeout_selector() != 0,
-            clear_task_set_timeout_selector: data.clear_task_set_timeout_selector() != 0,
-            logical_unit_reset_timeout_selector: data.logical_unit_reset_timeout_selector() != 0,
-            query_task_timeout_selector: data.query_task_timeout_selector() != 0,
-            query_asynchronous_event_timeout_selector: data
-                .query_asynchronous_event_timeout_selector()
-                != 0,
-            query_task_set_timeout_selector: data.query_task_set_timeout_selector() != 0,
-            i_t_nexus_reset_timeout_selector: data.i_t_nexus_reset_timeout_selector() != 0,
-            task_management_functions_long_timeout: data.task_management_functions_long_timeout(),
-            task_management_functions_short_timeout: data.task_management_functions_short_timeout(),
-        })
+        Ok(parse(result.data))
+    }
+}
+
+fn parse(data: &ReportSupportedTaskManagementFunctionsExtendedParameterData) -> CommandResult {
+    CommandResult {
+        abort_task_supported: data.abort_task_supported() != 0,
+        abort_task_set_supported: data.abort_task_set_supported() != 0,
+        clear_aca_supported: data.clear_aca_supported() != 0,
+        clear_task_set_supported: data.clear_task_set_supported() != 0,
+        logical_unit_reset_supported: data.logical_unit_reset_supported() != 0,
+        query_task_supported: data.query_task_supported() != 0,
+        target_reset_supported: data.target_reset_supported() != 0,
+        wakeup_supported: data.wakeup_supported() != 0,
+        query_asynchronous_event_supported: data.query_asynchronous_event_supported() != 0,
+        query_task_set_supported: data.query_task_set_supported() != 0,
+        i_t_nexus_reset_supported: data.i_t_nexus_reset_supported() != 0,
+        task_management_function_timeouts_valid: data.task_management_function_timeouts_valid()
+            != 0,
+        abort_task_timeout_selector: data.abort_task_timeout_selector() != 0,
+        abort_task_set_timeout_selector: data.abort_task_set_timeout_selector() != 0,
+        clear_aca_timeout_selector: data.clear_aca_timeout_selector() != 0,
+        clear_task_set_timeout_selector: data.clear_task_set_timeout_selector() != 0,
+        logical_unit_reset_timeout_selector: data.logical_unit_reset_timeout_selector() != 0,
+        query_task_timeout_selector: data.query_task_timeout_selector() != 0,
+        query_asynchronous_event_timeout_selector: data.query_asynchronous_event_timeout_selector()
+            != 0,
+        query_task_set_timeout_selector: data.query_task_set_timeout_selector() != 0,
+        i_t_nexus_reset_timeout_selector: data.i_t_nexus_reset_timeout_selector() != 0,
+        task_management_functions_long_timeout: data.task_management_functions_long_timeout(),
+        task_management_functions_short_timeout: data.task_management_functions_short_timeout(),
     }
 }
 
@@ -214,4 +248,33 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn parse_test() {
+        let mut bytes = [0u8; PARAMETER_LENGTH];
+        bytes[0] = 0b1000_1101;
+        bytes[1] = 0b0000_0011;
+        bytes[3] = 0x0C;
+        bytes[4] = 0b0000_0001;
+        bytes[8..12].copy_from_slice(&30_000u32.to_be_bytes());
+
+        let result =
+            parse(&ReportSupportedTaskManagementFunctionsExtendedParameterData::from_bytes(bytes));
+        assert!(result.abort_task_supported, "abort task");
+        assert!(!result.abort_task_set_supported, "abort task set");
+        assert!(result.logical_unit_reset_supported, "logical unit reset");
+        assert!(result.query_task_supported, "query task");
+        assert!(!result.target_reset_supported, "target reset");
+        assert!(result.wakeup_supported, "wakeup");
+        assert!(result.query_task_set_supported, "query task set");
+        assert!(result.i_t_nexus_reset_supported, "i_t nexus reset");
+        assert!(
+            result.task_management_function_timeouts_valid,
+            "timeouts valid"
+        );
+        assert_eq!(
+            result.task_management_functions_long_timeout, 30_000,
+            "long timeout"
+        );
+    }
 }
@@ -1,20 +1,28 @@
 #![allow(dead_code)]
 
-use std::mem::size_of;
+use std::{mem::size_of, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
-use crate::{result_data::ResultData, Command, DataDirection, Scsi};
+use crate::{
+    command::{cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
 
 #[derive(Clone, Debug)]
 pub struct ReportTimestampCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     command_buffer: CommandBuffer,
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommandResult {
+    // 0 means the timestamp was reset at power on, 2 means it was set by SET TIMESTAMP
     pub timestamp_origin: u8,
+    // milliseconds since the epoch
     pub timestamp: u64,
 }
 
@@ -22,6 +30,7 @@ impl<'a> ReportTimestampCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new()
                 .with_operation_code(OPERATION_CODE)
                 .with_service_action(SERVICE_ACTION)
@@ -29,24 +38,42 @@ impl<'a> ReportTimestampCommand<'a> {
         }
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<CommandResult> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self.command_buffer,
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        Ok(self.command_buffer)
+    }
 }
 
 impl Scsi {
-    pub fn report_timestamp(&self) -> ReportTimestampCommand {
+    pub fn report_timestamp(&self) -> ReportTimestampCommand<'_> {
         ReportTimestampCommand::new(self)
     }
 }
 
+crate::command::async_issue!(report_timestamp: ReportTimestampCommand);
+
+fn parse(data: &ReportTimestampParameterData) -> CommandResult {
+    CommandResult {
+        timestamp_origin: data.timestamp_origin(),
+        timestamp: data.timestamp(),
+    }
+}
+
 const OPERATION_CODE: u8 = 0xA3;
 const SERVICE_ACTION: u8 = 0x0F;
 
@@ -75,6 +102,7 @@ struct ReportTimestampParameterData {
 
 struct ThisCommand {
     command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -102,15 +130,15 @@ impl Command for ThisCommand {
         self.command_buffer.allocation_length()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        let data = result.data;
-        Ok(CommandResult {
-            timestamp_origin: data.timestamp_origin(),
-            timestamp: data.timestamp(),
-        })
+        Ok(parse(result.data))
     }
 }
 
@@ -136,4 +164,15 @@ mod tests {
             concat!("Size of: ", stringify!(ReportTimestampParameterData))
         );
     }
+
+    #[test]
+    fn parse_test() {
+        let data = ReportTimestampParameterData::from_bytes([
+            0x00, 0x0A, 0x02, 0x00, 0x01, 0x8B, 0x3C, 0x5E, 0x7A, 0x00, 0x00, 0x00,
+        ]);
+
+        let result = parse(&data);
+        assert_eq!(result.timestamp_origin, 0x02, "timestamp origin");
+        assert_eq!(result.timestamp, 0x018B_3C5E_7A00, "timestamp");
+    }
 }
@@ -0,0 +1,378 @@
+#![allow(dead_code)]
+
+use std::{mem::size_of, time::Duration};
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{
+        bitfield_bound_check, cdb_bytes, get_array, issue_full_length, timeout_setter, Control,
+    },
+    data_wrapper::{AnyType, VecBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct ReportZonesCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    reporting_options: ReportingOptions,
+    command_buffer: CommandBuffer,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ReportingOptions {
+    All,
+    Empty,
+    ImplicitlyOpened,
+    ExplicitlyOpened,
+    Closed,
+    Full,
+    ReadOnly,
+    Offline,
+    Inactive,
+    ResetWritePointerRecommended,
+    NonSequentialWriteResourcesActive,
+    NotWritePointer,
+    Other(u8),
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandResult {
+    // the length of the descriptors for all zones, even if not all were returned
+    pub zone_list_length: u32,
+    pub same: u8,
+    pub maximum_logical_block_address: u64,
+    pub zones: Vec<ZoneDescriptor>,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoneDescriptor {
+    // 0x1 conventional, 0x2 sequential write required, 0x3 sequential write preferred
+    pub zone_type: u8,
+    // 0x0 not write pointer, 0x1 empty, 0x2 implicitly opened, 0x3 explicitly opened,
+    // 0x4 closed, 0xD read only, 0xE full, 0xF offline
+    pub zone_condition: u8,
+    pub non_sequential: bool,
+    pub reset: bool,
+    pub zone_length: u64,
+    pub zone_start_logical_block_address: u64,
+    pub write_pointer_logical_block_address: u64,
+}
+
+impl<'a> ReportZonesCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            reporting_options: ReportingOptions::All,
+            command_buffer: CommandBuffer::new()
+                .with_operation_code(OPERATION_CODE)
+                .with_service_action(SERVICE_ACTION),
+        }
+    }
+
+    pub fn zone_start_logical_block_address(&mut self, value: u64) -> &mut Self {
+        self.command_buffer
+            .set_zone_start_logical_block_address(value);
+        self
+    }
+
+    pub fn allocation_length(&mut self, value: u32) -> &mut Self {
+        self.command_buffer.set_allocation_length(value);
+        self
+    }
+
+    // only the zones fitting in the allocation length are counted in zone_list_length
+    pub fn partial(&mut self, value: bool) -> &mut Self {
+        self.command_buffer.set_partial(value.into());
+        self
+    }
+
+    // reporting_options must be less than 0x40
+    pub fn reporting_options(&mut self, value: ReportingOptions) -> &mut Self {
+        self.reporting_options = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<CommandResult> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(u8::from(self.reporting_options), 6, "reporting options")?;
+
+        Ok(self
+            .command_buffer
+            .with_reporting_options(self.reporting_options.into()))
+    }
+
+    // issues again with the allocation length reported by the zone list length if the first
+    // response was truncated, the allocation length is left at the larger value
+    pub fn issue_full_length(&mut self) -> crate::Result<CommandResult> {
+        let allocation_length = self.command_buffer.allocation_length() as usize;
+        issue_full_length(
+            self,
+            allocation_length,
+            u32::MAX as usize,
+            |command, length| {
+                command.allocation_length(length as u32);
+            },
+            Self::issue,
+            CommandResult::required_length,
+        )
+    }
+}
+
+impl CommandResult {
+    /// The allocation length needed for the whole response, as its header reports it
+    pub fn required_length(&self) -> usize {
+        size_of::<ParameterHeader>() + self.zone_list_length as usize
+    }
+}
+
+impl Scsi {
+    pub fn report_zones(&self) -> ReportZonesCommand<'_> {
+        ReportZonesCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(report_zones: ReportZonesCommand);
+
+impl From<ReportingOptions> for u8 {
+    fn from(value: ReportingOptions) -> Self {
+        match value {
+            ReportingOptions::All => 0x00,
+            ReportingOptions::Empty => 0x01,
+            ReportingOptions::ImplicitlyOpened => 0x02,
+            ReportingOptions::ExplicitlyOpened => 0x03,
+            ReportingOptions::Closed => 0x04,
+            ReportingOptions::Full => 0x05,
+            ReportingOptions::ReadOnly => 0x06,
+            ReportingOptions::Offline => 0x07,
+            ReportingOptions::Inactive => 0x08,
+            ReportingOptions::ResetWritePointerRecommended => 0x10,
+            ReportingOptions::NonSequentialWriteResourcesActive => 0x11,
+            ReportingOptions::NotWritePointer => 0x3F,
+            ReportingOptions::Other(x) => x,
+        }
+    }
+}
+
+fn parse(bytes: &[u8]) -> CommandResult {
+    let (array, left) = get_array(bytes);
+    let header = ParameterHeader::from_bytes(array);
+
+    let length = usize::min(header.zone_list_length() as usize, left.len());
+    let zones = left[..length]
+        .chunks_exact(size_of::<ZoneDescriptorData>())
+        .map(|x| {
+            let (array, _) = get_array(x);
+            let data = ZoneDescriptorData::from_bytes(array);
+
+            ZoneDescriptor {
+                zone_type: data.zone_type(),
+                zone_condition: data.zone_condition(),
+                non_sequential: data.non_sequential() != 0,
+                reset: data.reset() != 0,
+                zone_length: data.zone_length(),
+                zone_start_logical_block_address: data.zone_start_logical_block_address(),
+                write_pointer_logical_block_address: data.write_pointer_logical_block_address(),
+            }
+        })
+        .collect();
+
+    CommandResult {
+        zone_list_length: header.zone_list_length(),
+        same: header.same(),
+        maximum_logical_block_address: header.maximum_logical_block_address(),
+        zones,
+    }
+}
+
+const OPERATION_CODE: u8 = 0x95;
+const SERVICE_ACTION: u8 = 0x00;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    zone_start_logical_block_address: B64,
+    allocation_length: B32,
+    partial: B1,
+    reserved_1: B1,
+    reporting_options: B6,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct ParameterHeader {
+    zone_list_length: B32,
+    reserved_0: B4,
+    same: B4,
+    reserved_1: B24,
+    maximum_logical_block_address: B64,
+    reserved_2: B128,
+    reserved_3: B128,
+    reserved_4: B128,
+}
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct ZoneDescriptorData {
+    reserved_0: B4,
+    zone_type: B4,
+    zone_condition: B4,
+    reserved_1: B2,
+    non_sequential: B1,
+    reset: B1,
+    reserved_2: B48,
+    zone_length: B64,
+    zone_start_logical_block_address: B64,
+    write_pointer_logical_block_address: B64,
+    reserved_3: B128,
+    reserved_4: B128,
+}
+
+struct ThisCommand {
+    command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
+}
+
+impl Command for ThisCommand {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = VecBufferWrapper;
+
+    type ReturnType = crate::Result<CommandResult>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::FromDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        unsafe { VecBufferWrapper::with_len(self.command_buffer.allocation_length() as usize) }
+    }
+
+    fn data_size(&self) -> u32 {
+        self.command_buffer.allocation_length()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        let length = result.data_in_length(result.data().len());
+
+        Ok(parse(&result.data()[..length]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+    const PARAMETER_HEADER_LENGTH: usize = 64;
+    const ZONE_DESCRIPTOR_LENGTH: usize = 64;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+
+        assert_eq!(
+            size_of::<ParameterHeader>(),
+            PARAMETER_HEADER_LENGTH,
+            concat!("Size of: ", stringify!(ParameterHeader))
+        );
+
+        assert_eq!(
+            size_of::<ZoneDescriptorData>(),
+            ZONE_DESCRIPTOR_LENGTH,
+            concat!("Size of: ", stringify!(ZoneDescriptorData))
+        );
+    }
+
+    #[test]
+    fn multiple_zones_test() {
+        let mut bytes = vec![0u8; PARAMETER_HEADER_LENGTH + 2 * ZONE_DESCRIPTOR_LENGTH];
+        // three zones exist, but only two were returned
+        bytes[..4].copy_from_slice(&(3 * ZONE_DESCRIPTOR_LENGTH as u32).to_be_bytes());
+        bytes[4] = 0x01;
+        bytes[8..16].copy_from_slice(&0x0000_0000_0017_FFFFu64.to_be_bytes());
+
+        let zone = &mut bytes[PARAMETER_HEADER_LENGTH..];
+        zone[0] = 0x01; // conventional
+        zone[1] = 0x00; // not write pointer
+        zone[8..16].copy_from_slice(&0x8_0000u64.to_be_bytes());
+        zone[24..32].copy_from_slice(&u64::MAX.to_be_bytes());
+
+        let zone = &mut bytes[PARAMETER_HEADER_LENGTH + ZONE_DESCRIPTOR_LENGTH..];
+        zone[0] = 0x02; // sequential write required
+        zone[1] = 0x21; // implicitly opened, reset recommended
+        zone[8..16].copy_from_slice(&0x8_0000u64.to_be_bytes());
+        zone[16..24].copy_from_slice(&0x8_0000u64.to_be_bytes());
+        zone[24..32].copy_from_slice(&0x8_1000u64.to_be_bytes());
+
+        let result = parse(&bytes);
+        assert_eq!(result.zone_list_length, 192, "zone list length");
+        assert_eq!(result.same, 1, "same");
+        assert_eq!(
+            result.maximum_logical_block_address, 0x17_FFFF,
+            "maximum logical block address"
+        );
+        assert_eq!(result.zones.len(), 2, "returned zones");
+
+        let zone = &result.zones[0];
+        assert_eq!(zone.zone_type, 0x1, "zone type");
+        assert_eq!(zone.zone_condition, 0x0, "zone condition");
+        assert_eq!(zone.zone_length, 0x8_0000, "zone length");
+
+        let zone = &result.zones[1];
+        assert_eq!(zone.zone_type, 0x2, "zone type");
+        assert_eq!(zone.zone_condition, 0x2, "zone condition");
+        assert!(zone.reset, "reset");
+        assert!(!zone.non_sequential, "non sequential");
+        assert_eq!(
+            zone.zone_start_logical_block_address, 0x8_0000,
+            "zone start logical block address"
+        );
+        assert_eq!(
+            zone.write_pointer_logical_block_address, 0x8_1000,
+            "write pointer logical block address"
+        );
+    }
+}
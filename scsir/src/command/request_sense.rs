@@ -1,9 +1,15 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::sense::{SenseData, MAX_SENSE_BUFFER_LENGTH},
+    command::{
+        cdb_bytes,
+        sense::{SenseData, MAX_SENSE_BUFFER_LENGTH},
+        timeout_setter, Control,
+    },
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -11,6 +17,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct RequestSenseCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     command_buffer: CommandBuffer,
 }
 
@@ -18,6 +25,7 @@ impl<'a> RequestSenseCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new()
                 .with_operation_code(OPERATION_CODE)
                 .with_allocation_length(MAX_SENSE_BUFFER_LENGTH as u8),
@@ -29,24 +37,49 @@ impl<'a> RequestSenseCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    // the default allocation length has room for the longest sense data
+    pub fn allocation_length(&mut self, value: u8) -> &mut Self {
+        self.command_buffer.set_allocation_length(value);
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<SenseData> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self.command_buffer,
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        if self.command_buffer.allocation_length() as usize > MAX_SENSE_BUFFER_LENGTH {
+            return Err(crate::Error::ArgumentOutOfBounds(format!(
+                "Allocation length is out of bounds. The maximum possible value is {}, but {} was provided.",
+                MAX_SENSE_BUFFER_LENGTH,
+                self.command_buffer.allocation_length()
+            )));
+        }
+
+        Ok(self.command_buffer)
+    }
 }
 
 impl Scsi {
-    pub fn request_sense(&self) -> RequestSenseCommand {
+    pub fn request_sense(&self) -> RequestSenseCommand<'_> {
         RequestSenseCommand::new(self)
     }
 }
 
+crate::command::async_issue!(request_sense: RequestSenseCommand);
+
 const OPERATION_CODE: u8 = 0x03;
 
 #[bitfield]
@@ -62,6 +95,7 @@ struct CommandBuffer {
 
 struct ThisCommand {
     command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -89,11 +123,18 @@ impl Command for ThisCommand {
         self.command_buffer.allocation_length() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        Ok(SenseData::parse(result.data, result.transfered_data_length))
+        Ok(SenseData::parse(
+            result.data,
+            result.data_in_length(usize::min(self.data_size() as usize, result.data.len())),
+        ))
     }
 }
 
@@ -0,0 +1,189 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+// obsoleted by SPC-3 in favor of persistent reservations, kept for older devices
+#[derive(Clone, Debug)]
+pub struct Reserve6Command<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    command_buffer: CommandBuffer,
+}
+
+#[derive(Clone, Debug)]
+pub struct Release6Command<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    command_buffer: CommandBuffer,
+}
+
+impl<'a> Reserve6Command<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE_RESERVE),
+        }
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<()> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        Ok(self.command_buffer)
+    }
+}
+
+impl<'a> Release6Command<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE_RELEASE),
+        }
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<()> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        Ok(self.command_buffer)
+    }
+}
+
+impl Scsi {
+    pub fn reserve_6(&self) -> Reserve6Command<'_> {
+        Reserve6Command::new(self)
+    }
+
+    pub fn release_6(&self) -> Release6Command<'_> {
+        Release6Command::new(self)
+    }
+}
+
+crate::command::async_issue!(reserve_6: Reserve6Command);
+
+crate::command::async_issue!(release_6: Release6Command);
+
+const OPERATION_CODE_RESERVE: u8 = 0x16;
+const OPERATION_CODE_RELEASE: u8 = 0x17;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    // third party and extent reservations are obsolete
+    obsolete: B32,
+    control: B8,
+}
+
+struct ThisCommand {
+    command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
+}
+
+impl Command for ThisCommand {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = ();
+
+    type DataBufferWrapper = ();
+
+    type ReturnType = crate::Result<()>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::None
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {}
+
+    fn data_size(&self) -> u32 {
+        0
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 6;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+    }
+
+    #[test]
+    fn command_buffer_test() {
+        assert_eq!(
+            CommandBuffer::new()
+                .with_operation_code(OPERATION_CODE_RESERVE)
+                .into_bytes(),
+            [0x16, 0x00, 0x00, 0x00, 0x00, 0x00],
+            "reserve (6) command"
+        );
+
+        assert_eq!(
+            CommandBuffer::new()
+                .with_operation_code(OPERATION_CODE_RELEASE)
+                .with_control(0x04)
+                .into_bytes(),
+            [0x17, 0x00, 0x00, 0x00, 0x00, 0x04],
+            "release (6) command"
+        );
+    }
+}
@@ -1,9 +1,11 @@
 #![allow(dead_code)]
 
+use std::{mem::size_of, time::Duration};
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
+    command::{bitfield_bound_check, cdb_bytes, logical_block_size_check, timeout_setter, Control},
     data_wrapper::{AnyType, FlexibleStruct},
     result_data::ResultData,
     Command, DataDirection, Scsi,
@@ -12,7 +14,10 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct SanitizeCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     sanitize_service_action: ServiceAction,
+    confirmed: bool,
+    logical_block_size: u32,
     command_buffer: CommandBuffer,
     data_buffer: FlexibleStruct<OverwriteParameterListHeader, u8>,
 }
@@ -38,7 +43,10 @@ impl<'a> SanitizeCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             sanitize_service_action: ServiceAction::Overwrite,
+            confirmed: false,
+            logical_block_size: 0,
             command_buffer: CommandBuffer::new()
                 .with_operation_code(OPERATION_CODE)
                 .with_service_action(ServiceAction::Overwrite as u8),
@@ -63,12 +71,25 @@ impl<'a> SanitizeCommand<'a> {
     }
 
     pub fn service_action(&mut self, value: ServiceAction) -> &mut Self {
+        self.sanitize_service_action = value;
         self.command_buffer.set_service_action(value as u8);
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    // sanitize irrecoverably destroys all user data, issue refuses to run without this
+    pub fn confirm_data_destruction(&mut self, value: bool) -> &mut Self {
+        self.confirmed = value;
+        self
+    }
+
+    // required for overwrite, the logical block length of READ CAPACITY, see Scsi::capacity
+    pub fn logical_block_size(&mut self, value: u32) -> &mut Self {
+        self.logical_block_size = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
@@ -76,18 +97,37 @@ impl<'a> SanitizeCommand<'a> {
         OverwriteParameterListBuilder::new(self)
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<()> {
-        if let ServiceAction::Overwrite = self.sanitize_service_action {
-            self.command_buffer
-                .set_parameter_list_length(self.data_buffer.total_size() as u16);
+        if !self.confirmed {
+            return Err(crate::Error::BadArgument(
+                "sanitize destroys all user data, confirm_data_destruction must be set".to_owned(),
+            ));
         }
 
         self.interface.issue(&ThisCommand {
             sanitize_service_action: self.sanitize_service_action,
-            command_buffer: self.command_buffer,
+            command_buffer: self.command_buffer()?,
             data_buffer: self.data_buffer.clone(),
+            timeout: self.timeout,
         })
     }
+
+    // builds the CDB without the confirmation, nothing is sent
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        let parameter_list_length = parameter_list_length(
+            self.sanitize_service_action,
+            &self.data_buffer,
+            self.logical_block_size,
+        )?;
+
+        Ok(self
+            .command_buffer
+            .with_parameter_list_length(parameter_list_length))
+    }
 }
 
 impl<'a> OverwriteParameterListBuilder<'a> {
@@ -118,20 +158,17 @@ impl<'a> OverwriteParameterListBuilder<'a> {
         self
     }
 
-    // initialization_pattern length must be less than 0xFFFF
+    // initialization_pattern must not be empty nor longer than a logical block, done checks it
+    // if logical_block_size is already set and issue checks it again
     pub fn initialization_pattern(&mut self, value: &[u8]) -> &mut Self {
         self.initialization_pattern = value.to_vec();
         self
     }
 
-    pub fn done(&mut self) -> crate::Result<&'a mut SanitizeCommand> {
+    pub fn done(&mut self) -> crate::Result<&'a mut SanitizeCommand<'_>> {
         bitfield_bound_check!(self.test, 2, "test")?;
         bitfield_bound_check!(self.overwrite_count, 5, "overwrite count")?;
-        bitfield_bound_check!(
-            self.initialization_pattern.len(),
-            16,
-            "initialization pattern length"
-        )?;
+        initialization_pattern_check(&self.initialization_pattern, self.parent.logical_block_size)?;
 
         self.parent.data_buffer.set_body(
             self.header_buffer
@@ -149,12 +186,77 @@ impl<'a> OverwriteParameterListBuilder<'a> {
 }
 
 impl Scsi {
-    pub fn sanitize(&self) -> SanitizeCommand {
+    pub fn sanitize(&self) -> SanitizeCommand<'_> {
         SanitizeCommand::new(self)
     }
 }
 
-const OPERATION_CODE: u8 = 0x43;
+crate::command::async_issue!(sanitize: SanitizeCommand);
+
+// a logical_block_size of 0 isn't known yet and skips the logical block check
+fn initialization_pattern_check(
+    initialization_pattern: &[u8],
+    logical_block_size: u32,
+) -> crate::Result<()> {
+    if initialization_pattern.is_empty() {
+        return Err(crate::Error::BadArgument(
+            "initialization pattern should not be empty".to_owned(),
+        ));
+    }
+
+    if logical_block_size != 0 {
+        logical_block_length_check(initialization_pattern.len(), logical_block_size)?;
+    }
+
+    // the whole parameter list has to fit in the 16 bits parameter list length
+    bitfield_bound_check!(
+        initialization_pattern.len() + size_of::<OverwriteParameterListHeader>(),
+        16,
+        "parameter list length"
+    )?;
+
+    Ok(())
+}
+
+fn parameter_list_length(
+    service_action: ServiceAction,
+    data_buffer: &FlexibleStruct<OverwriteParameterListHeader, u8>,
+    logical_block_size: u32,
+) -> crate::Result<u16> {
+    match service_action {
+        ServiceAction::Overwrite if data_buffer.length() == 0 => Err(crate::Error::BadArgument(
+            "overwrite requires a parameter list with an initialization pattern".to_owned(),
+        )),
+        ServiceAction::Overwrite => {
+            logical_block_size_check(logical_block_size)?;
+            logical_block_length_check(data_buffer.length(), logical_block_size)?;
+
+            Ok(data_buffer.total_size() as u16)
+        }
+        _ => Ok(0),
+    }
+}
+
+fn logical_block_length_check(pattern_length: usize, logical_block_size: u32) -> crate::Result<()> {
+    if pattern_length > logical_block_size as usize {
+        return Err(crate::Error::ArgumentOutOfBounds(format!(
+            "initialization pattern is longer than a logical block. The maximum possible value is {}, but {} was provided.",
+            logical_block_size,
+            pattern_length
+        )));
+    }
+
+    Ok(())
+}
+
+fn direction(service_action: ServiceAction) -> DataDirection {
+    match service_action {
+        ServiceAction::Overwrite => DataDirection::ToDevice,
+        _ => DataDirection::None,
+    }
+}
+
+const OPERATION_CODE: u8 = 0x48;
 
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
@@ -183,6 +285,7 @@ struct ThisCommand {
     sanitize_service_action: ServiceAction,
     command_buffer: CommandBuffer,
     data_buffer: FlexibleStruct<OverwriteParameterListHeader, u8>,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -195,7 +298,7 @@ impl Command for ThisCommand {
     type ReturnType = crate::Result<()>;
 
     fn direction(&self) -> DataDirection {
-        DataDirection::ToDevice
+        direction(self.sanitize_service_action)
     }
 
     fn command(&self) -> Self::CommandBuffer {
@@ -213,6 +316,10 @@ impl Command for ThisCommand {
         }
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -243,4 +350,167 @@ mod tests {
             concat!("Size of: ", stringify!(OverwriteParameterListHeader))
         );
     }
+
+    #[test]
+    fn overwrite_test() {
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_immediate(1)
+            .with_service_action(ServiceAction::Overwrite as u8)
+            .with_parameter_list_length(8);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x48, 0x81, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00],
+            "overwrite command"
+        );
+
+        let mut data_buffer = FlexibleStruct::<OverwriteParameterListHeader, u8>::new();
+        assert!(
+            parameter_list_length(ServiceAction::Overwrite, &data_buffer, 512).is_err(),
+            "overwrite without pattern"
+        );
+
+        for n in [0xDE, 0xAD, 0xBE, 0xEF] {
+            data_buffer.push(n);
+        }
+        assert_eq!(
+            parameter_list_length(ServiceAction::Overwrite, &data_buffer, 512).unwrap(),
+            8,
+            "overwrite parameter list length"
+        );
+        assert!(
+            matches!(
+                parameter_list_length(ServiceAction::Overwrite, &data_buffer, 0),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "overwrite without logical block size"
+        );
+        assert!(
+            matches!(
+                parameter_list_length(ServiceAction::Overwrite, &data_buffer, 2),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "initialization pattern longer than a logical block"
+        );
+        assert!(
+            matches!(direction(ServiceAction::Overwrite), DataDirection::ToDevice),
+            "overwrite direction"
+        );
+
+        assert!(
+            initialization_pattern_check(&[], 0).is_err(),
+            "empty initialization pattern"
+        );
+        assert!(
+            initialization_pattern_check(&[0; 0xFFFC], 0).is_err(),
+            "initialization pattern too long"
+        );
+        assert!(
+            initialization_pattern_check(&[0; 512], 512).is_ok(),
+            "one block initialization pattern"
+        );
+        assert!(
+            matches!(
+                initialization_pattern_check(&[0; 520], 512),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "initialization pattern longer than a logical block"
+        );
+        assert!(
+            initialization_pattern_check(&[0; 520], 0).is_ok(),
+            "logical block size not set yet"
+        );
+    }
+
+    #[test]
+    fn cryptographic_erase_test() {
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_allow_unrestricted_sanitize_exit(1)
+            .with_service_action(ServiceAction::CryptographicErase as u8);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x48, 0x23, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            "cryptographic erase command"
+        );
+
+        let mut data_buffer = FlexibleStruct::<OverwriteParameterListHeader, u8>::new();
+        data_buffer.push(0xFF);
+        assert_eq!(
+            parameter_list_length(ServiceAction::CryptographicErase, &data_buffer, 0).unwrap(),
+            0,
+            "cryptographic erase sends no data"
+        );
+        assert!(
+            matches!(
+                direction(ServiceAction::CryptographicErase),
+                DataDirection::None
+            ),
+            "cryptographic erase direction"
+        );
+    }
+
+    #[test]
+    fn initialization_pattern_length_test() {
+        let mock = crate::MockScsi::new();
+        // OVERWRITE with a 512 byte pattern
+        mock.expect(
+            &[0x48, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x04, 0x00],
+            crate::MockResponse::good(),
+        );
+        let scsi = Scsi::mock(mock.clone());
+
+        let mut command = scsi.sanitize();
+        command
+            .confirm_data_destruction(true)
+            .logical_block_size(256);
+        let mut parameter = command.parameter();
+        let result = parameter
+            .overwrite_count(1)
+            .initialization_pattern(&[0xA5; 512])
+            .done();
+        assert!(
+            matches!(result, Err(crate::Error::ArgumentOutOfBounds(_))),
+            "pattern longer than a logical block"
+        );
+
+        // the logical block size is only known once the parameter list is done
+        let mut command = scsi.sanitize();
+        command.confirm_data_destruction(true);
+        let mut parameter = command.parameter();
+        let result = parameter
+            .overwrite_count(1)
+            .initialization_pattern(&[0xA5; 512])
+            .done()
+            .unwrap()
+            .logical_block_size(256)
+            .issue();
+        assert!(
+            matches!(result, Err(crate::Error::ArgumentOutOfBounds(_))),
+            "pattern longer than a logical block set later"
+        );
+
+        let mut command = scsi.sanitize();
+        command
+            .confirm_data_destruction(true)
+            .logical_block_size(512);
+        let mut parameter = command.parameter();
+        parameter
+            .overwrite_count(1)
+            .initialization_pattern(&[0xA5; 512])
+            .done()
+            .unwrap()
+            .issue()
+            .unwrap();
+
+        assert_eq!(
+            mock.issued().len(),
+            1,
+            "only the valid overwrite was issued"
+        );
+        assert_eq!(mock.issued()[0].data.len(), 516, "parameter list");
+        mock.verify();
+    }
 }
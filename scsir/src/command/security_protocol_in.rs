@@ -1,8 +1,11 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
+    command::{cdb_bytes, timeout_setter, Control},
     data_wrapper::{AnyType, VecBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
@@ -11,6 +14,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct SecurityProtocolInCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     command_buffer: CommandBuffer,
 }
 
@@ -18,6 +22,7 @@ impl<'a> SecurityProtocolInCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
         }
     }
@@ -32,34 +37,68 @@ impl<'a> SecurityProtocolInCommand<'a> {
         self
     }
 
+    // allocation_length is counted in 512 bytes increments when set
     pub fn inc_512(&mut self, value: bool) -> &mut Self {
         self.command_buffer.set_inc_512(value.into());
         self
     }
 
+    // the response format belongs to the security protocol, there is no common header to
+    // read the needed allocation length from
     pub fn allocation_length(&mut self, value: u32) -> &mut Self {
         self.command_buffer.set_allocation_length(value);
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<Vec<u8>> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self.command_buffer,
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        transfer_size(
+            self.command_buffer.allocation_length(),
+            self.command_buffer.inc_512() != 0,
+        )?;
+
+        Ok(self.command_buffer)
+    }
+}
+
+// the transfer size in bytes must still fit the 32 bits data length
+pub(super) fn transfer_size(length: u32, inc_512: bool) -> crate::Result<u32> {
+    if !inc_512 {
+        return Ok(length);
+    }
+
+    length.checked_mul(512).ok_or_else(|| {
+        crate::Error::ArgumentOutOfBounds(format!(
+            "Length is out of bounds. The maximum possible value is {}, but {} was provided.",
+            u32::MAX / 512,
+            length
+        ))
+    })
 }
 
 impl Scsi {
-    pub fn security_protocol_in(&self) -> SecurityProtocolInCommand {
+    pub fn security_protocol_in(&self) -> SecurityProtocolInCommand<'_> {
         SecurityProtocolInCommand::new(self)
     }
 }
 
+crate::command::async_issue!(security_protocol_in: SecurityProtocolInCommand);
+
 const OPERATION_CODE: u8 = 0xA2;
 
 #[bitfield]
@@ -78,6 +117,7 @@ struct CommandBuffer {
 
 struct ThisCommand {
     command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -98,28 +138,30 @@ impl Command for ThisCommand {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        let allocation_length = if self.command_buffer.inc_512() == 0 {
-            self.command_buffer.allocation_length() as usize
-        } else {
-            (self.command_buffer.allocation_length() as usize).saturating_mul(512)
-        };
-
-        unsafe { VecBufferWrapper::with_len(allocation_length) }
+        unsafe { VecBufferWrapper::with_len(self.data_size() as usize) }
     }
 
     fn data_size(&self) -> u32 {
-        if self.command_buffer.inc_512() == 0 {
-            self.command_buffer.allocation_length()
-        } else {
-            self.command_buffer.allocation_length().saturating_mul(512)
-        }
+        transfer_size(
+            self.command_buffer.allocation_length(),
+            self.command_buffer.inc_512() != 0,
+        )
+        .unwrap_or(u32::MAX)
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
     }
 
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        Ok(std::mem::take(result.data))
+        let length = result.data_in_length(result.data.len());
+        let mut data: Vec<u8> = std::mem::take(result.data);
+        data.truncate(length);
+
+        Ok(data)
     }
 }
 
@@ -138,4 +180,18 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn transfer_size_test() {
+        assert_eq!(transfer_size(0x1234, false).unwrap(), 0x1234, "bytes");
+        assert_eq!(
+            transfer_size(4, true).unwrap(),
+            2048,
+            "512 bytes increments"
+        );
+        assert!(
+            transfer_size(0x0080_0000, true).is_err(),
+            "transfer size exceeds 32 bits"
+        );
+    }
 }
@@ -1,10 +1,15 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{
+        bitfield_bound_check, cdb_bytes, security_protocol_in::transfer_size, timeout_setter,
+        Control,
+    },
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,6 +17,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct SecurityProtocolOutCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     command_buffer: CommandBuffer,
     data_buffer: Vec<u8>,
 }
@@ -20,6 +26,7 @@ impl<'a> SecurityProtocolOutCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
             data_buffer: vec![],
         }
@@ -35,13 +42,14 @@ impl<'a> SecurityProtocolOutCommand<'a> {
         self
     }
 
+    // the parameter length must be a multiple of 512 when set
     pub fn inc_512(&mut self, value: bool) -> &mut Self {
         self.command_buffer.set_inc_512(value.into());
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
@@ -50,36 +58,52 @@ impl<'a> SecurityProtocolOutCommand<'a> {
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<()> {
-        let transfer_length = if self.command_buffer.inc_512() == 0 {
-            self.data_buffer.len()
-        } else {
-            self.data_buffer.len() / 512
-        };
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
+        })
+    }
 
-        bitfield_bound_check!(transfer_length, 32, "parameter length")?;
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
 
-        if self.command_buffer.inc_512() == 1 && self.data_buffer.len() % 512 != 0 {
-            return Err(crate::Error::BadArgument(
-                "parameter length is not a multiple of 512".to_owned(),
-            ));
-        }
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        let transfer_length =
+            transfer_length(self.data_buffer.len(), self.command_buffer.inc_512() != 0)?;
 
-        self.interface.issue(&ThisCommand {
-            command_buffer: self
-                .command_buffer
-                .with_transfer_length(transfer_length as u32),
-            data_buffer: self.data_buffer.clone().into(),
-        })
+        Ok(self.command_buffer.with_transfer_length(transfer_length))
+    }
+}
+
+fn transfer_length(data_length: usize, inc_512: bool) -> crate::Result<u32> {
+    let transfer_length = if inc_512 {
+        data_length / 512
+    } else {
+        data_length
+    };
+
+    bitfield_bound_check!(transfer_length, 32, "parameter length")?;
+
+    if transfer_size(transfer_length as u32, inc_512)? as usize != data_length {
+        return Err(crate::Error::BadArgument(
+            "parameter length is not a multiple of 512".to_owned(),
+        ));
     }
+
+    Ok(transfer_length as u32)
 }
 
 impl Scsi {
-    pub fn security_protocol_out(&self) -> SecurityProtocolOutCommand {
+    pub fn security_protocol_out(&self) -> SecurityProtocolOutCommand<'_> {
         SecurityProtocolOutCommand::new(self)
     }
 }
 
+crate::command::async_issue!(security_protocol_out: SecurityProtocolOutCommand);
+
 const OPERATION_CODE: u8 = 0xB5;
 
 #[bitfield]
@@ -96,22 +120,27 @@ struct CommandBuffer {
     control: B8,
 }
 
-struct ThisCommand {
+struct ThisCommand<'a> {
     command_buffer: CommandBuffer,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl Command for ThisCommand {
+impl<'a> Command for ThisCommand<'a> {
     type CommandBuffer = CommandBuffer;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
     fn direction(&self) -> DataDirection {
-        DataDirection::ToDevice
+        if self.data_buffer.is_empty() {
+            DataDirection::None
+        } else {
+            DataDirection::ToDevice
+        }
     }
 
     fn command(&self) -> Self::CommandBuffer {
@@ -119,13 +148,17 @@ impl Command for ThisCommand {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -149,4 +182,35 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn transfer_length_test() {
+        assert_eq!(transfer_length(100, false).unwrap(), 100, "bytes");
+        assert_eq!(
+            transfer_length(2048, true).unwrap(),
+            4,
+            "512 bytes increments"
+        );
+        assert!(
+            transfer_length(1000, true).is_err(),
+            "partial 512 bytes increment"
+        );
+        assert!(
+            transfer_length(0x1_0000_0000, false).is_err(),
+            "parameter length exceeds 32 bits"
+        );
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.security_protocol_out();
+        command.security_protocol(0x01).parameter(&[0x5A; 512]);
+
+        let before = allocations();
+        command.issue().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
@@ -1,10 +1,12 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,24 +14,41 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct SendDiagnosticCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     self_test_code: u8,
     command_buffer: CommandBuffer,
     data_buffer: Vec<u8>,
 }
 
+#[derive(Clone, Copy, Debug)]
+pub enum SelfTestCode {
+    BackgroundShort,
+    BackgroundExtended,
+    AbortBackground,
+    ForegroundShort,
+    ForegroundExtended,
+}
+
 impl<'a> SendDiagnosticCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             self_test_code: 0,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
             data_buffer: vec![],
         }
     }
 
-    // self_test_code must be less than 0x08
-    pub fn self_test_code(&mut self, value: u8) -> &mut Self {
-        self.self_test_code = value;
+    // self_test must not be set together with a self test code
+    pub fn self_test_code(&mut self, value: SelfTestCode) -> &mut Self {
+        self.self_test_code = match value {
+            SelfTestCode::BackgroundShort => 0b001,
+            SelfTestCode::BackgroundExtended => 0b010,
+            SelfTestCode::AbortBackground => 0b100,
+            SelfTestCode::ForegroundShort => 0b101,
+            SelfTestCode::ForegroundExtended => 0b110,
+        };
         self
     }
 
@@ -54,8 +73,8 @@ impl<'a> SendDiagnosticCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
@@ -66,23 +85,38 @@ impl<'a> SendDiagnosticCommand<'a> {
         self
     }
 
-    pub fn issue(&mut self) -> crate::Result<()> {
-        bitfield_bound_check!(self.self_test_code, 3, "self test code")?;
-        bitfield_bound_check!(self.data_buffer.len(), 16, "parameter list length")?;
+    timeout_setter!();
 
+    pub fn issue(&mut self) -> crate::Result<()> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self.command_buffer.with_self_test_code(self.self_test_code),
-            data_buffer: self.data_buffer.clone().into(),
+            command_buffer: self.command_buffer()?,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        if self.self_test_code != 0 && self.command_buffer.self_test() != 0 {
+            return Err(crate::Error::BadArgument(
+                "Self test code must be zero when self test is set".to_owned(),
+            ));
+        }
+        bitfield_bound_check!(self.data_buffer.len(), 16, "parameter list length")?;
+
+        Ok(self.command_buffer.with_self_test_code(self.self_test_code))
+    }
 }
 
 impl Scsi {
-    pub fn send_diagnostic(&self) -> SendDiagnosticCommand {
+    pub fn send_diagnostic(&self) -> SendDiagnosticCommand<'_> {
         SendDiagnosticCommand::new(self)
     }
 }
 
+crate::command::async_issue!(send_diagnostic: SendDiagnosticCommand);
+
 const OPERATION_CODE: u8 = 0x1D;
 
 #[bitfield]
@@ -100,22 +134,27 @@ struct CommandBuffer {
     control: B8,
 }
 
-struct ThisCommand {
+struct ThisCommand<'a> {
     command_buffer: CommandBuffer,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl Command for ThisCommand {
+impl<'a> Command for ThisCommand<'a> {
     type CommandBuffer = CommandBuffer;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
     fn direction(&self) -> DataDirection {
-        DataDirection::ToDevice
+        if self.data_buffer.is_empty() {
+            DataDirection::None
+        } else {
+            DataDirection::ToDevice
+        }
     }
 
     fn command(&self) -> Self::CommandBuffer {
@@ -123,13 +162,17 @@ impl Command for ThisCommand {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -153,4 +196,41 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn background_short_test() {
+        let command = ThisCommand {
+            command_buffer: CommandBuffer::new()
+                .with_operation_code(OPERATION_CODE)
+                .with_self_test_code(0b001),
+            data_buffer: SliceBufferWrapper(&[]),
+            timeout: None,
+        };
+
+        assert_eq!(
+            command.command().into_bytes(),
+            [0x1D, 0x20, 0x00, 0x00, 0x00, 0x00],
+            "background short self test"
+        );
+        assert!(
+            matches!(command.direction(), DataDirection::None),
+            "no parameter list"
+        );
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.send_diagnostic();
+        // the Supported Diagnostic Pages page
+        command
+            .page_format(true)
+            .parameter(&[0x00, 0x00, 0x00, 0x00]);
+
+        let before = allocations();
+        command.issue().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
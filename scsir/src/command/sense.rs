@@ -3,9 +3,13 @@ use std::{
     ops::Deref,
 };
 
+use crate::util::be_u16;
+
 pub const MAX_SENSE_BUFFER_LENGTH: usize = 252;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum SenseData {
     None,
     Fixed(FixedSenseData),
@@ -15,16 +19,22 @@ pub enum SenseData {
 
 impl SenseData {
     pub fn parse(raw: &[u8; MAX_SENSE_BUFFER_LENGTH], sense_length: usize) -> Self {
+        if sense_length == 0 {
+            return Self::None;
+        }
+
         let response_code = raw[0] & 0b01111111;
         match response_code {
             0 => Self::None,
             0x70 | 0x71 => {
-                let sense_key = SenseKey::from(raw[2] & 0b00001111);
-                let additional_sense_length = raw[7];
-                if additional_sense_length as usize + 8 != sense_length {
+                // devices may transfer more or less than the reported additional sense length
+                let reported_length = usize::min(raw[7] as usize + 8, sense_length);
+                if reported_length < FIXED_SENSE_DATA_LENGTH {
                     return Self::Raw(Vec::from(&raw[..sense_length]));
                 }
 
+                let sense_key = SenseKey::from(raw[2] & 0b00001111);
+
                 let sense = FixedSenseData {
                     is_valid: (raw[0] & 0b10000000) != 0,
                     response_code: ErrorType::from(response_code),
@@ -38,24 +48,38 @@ impl SenseData {
                     field_replaceable_unit_code: raw[14],
                     is_sense_key_specific_valid: (raw[15] & 0b10000000) != 0,
                     sense_key_specific: SenseKeySpecific::parse(&raw[15..=17], sense_key),
-                    additional_sense_bytes: Vec::from(&raw[18..sense_length]),
+                    additional_sense_bytes: Vec::from(
+                        &raw[FIXED_SENSE_DATA_LENGTH..reported_length],
+                    ),
                 };
 
                 Self::Fixed(sense)
             }
             0x72 | 0x73 => {
-                let mut descriptor_index = 8usize;
-                if descriptor_index + raw[7] as usize != sense_length {
+                let mut descriptor_index = DESCRIPTOR_SENSE_DATA_HEADER_LENGTH;
+                let reported_length = usize::min(raw[7] as usize + descriptor_index, sense_length);
+                if reported_length < descriptor_index {
                     return Self::Raw(Vec::from(&raw[..sense_length]));
                 }
 
                 let sense_key = SenseKey::from(raw[1] & 0b00001111);
 
                 let mut descriptors = vec![];
-                while descriptor_index < sense_length {
-                    let descriptor = Descriptor::parse(&raw[descriptor_index..], sense_key);
+                while descriptor_index + DESCRIPTOR_HEADER_LENGTH <= reported_length {
+                    let descriptor_length =
+                        DESCRIPTOR_HEADER_LENGTH + raw[descriptor_index + 1] as usize;
+                    let descriptor_end = descriptor_index + descriptor_length;
+                    if descriptor_end > reported_length {
+                        descriptors.push(Descriptor::Unknown(Vec::from(
+                            &raw[descriptor_index..reported_length],
+                        )));
+                        break;
+                    }
+
+                    let descriptor =
+                        Descriptor::parse(&raw[descriptor_index..descriptor_end], sense_key);
                     descriptors.push(descriptor);
-                    descriptor_index += DESCRIPTOR_HEADER_LENGTH + raw[1] as usize;
+                    descriptor_index = descriptor_end;
                 }
 
                 let sense = DescriptorSenseData {
@@ -70,9 +94,106 @@ impl SenseData {
             _ => Self::Raw(Vec::from(&raw[..sense_length])),
         }
     }
+
+    pub fn sense_key(&self) -> Option<SenseKey> {
+        match self {
+            Self::Fixed(sense) => Some(sense.sense_key),
+            Self::Descriptor(sense) => Some(sense.sense_key),
+            _ => None,
+        }
+    }
+
+    pub fn additional_sense_code(&self) -> Option<AdditionalSenseCode> {
+        match self {
+            Self::Fixed(sense) => Some(sense.additional_sense_code.clone()),
+            Self::Descriptor(sense) => Some(sense.additional_sense_code.clone()),
+            _ => None,
+        }
+    }
+
+    // None if the device didn't mark the information field valid
+    pub fn information(&self) -> Option<u64> {
+        match self {
+            Self::Fixed(sense) if sense.is_valid => {
+                Some(u32::from_be_bytes(sense.information) as u64)
+            }
+            Self::Descriptor(sense) => sense.descriptors.iter().find_map(|d| match d {
+                Descriptor::Information {
+                    is_valid: true,
+                    information,
+                } => Some(u64::from_be_bytes(*information)),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn command_specific_information(&self) -> Option<u64> {
+        match self {
+            Self::Fixed(sense) => {
+                Some(u32::from_be_bytes(sense.command_specific_information) as u64)
+            }
+            Self::Descriptor(sense) => sense.descriptors.iter().find_map(|d| match d {
+                Descriptor::CommandSpecificInformation {
+                    command_specific_information,
+                } => Some(u64::from_be_bytes(*command_specific_information)),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    // None if the device didn't mark the sense key specific field valid
+    pub fn sense_key_specific(&self) -> Option<SenseKeySpecific> {
+        match self {
+            Self::Fixed(sense) if sense.is_sense_key_specific_valid => {
+                Some(sense.sense_key_specific.clone())
+            }
+            Self::Descriptor(sense) => sense.descriptors.iter().find_map(|d| match d {
+                Descriptor::SenseKeySpecific {
+                    is_sense_key_specific_valid: true,
+                    sense_key_specific,
+                } => Some(sense_key_specific.clone()),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    // 0 means no field replaceable unit was reported
+    pub fn field_replaceable_unit_code(&self) -> Option<u8> {
+        match self {
+            Self::Fixed(sense) => Some(sense.field_replaceable_unit_code),
+            Self::Descriptor(sense) => sense.descriptors.iter().find_map(|d| match d {
+                Descriptor::FieldReplaceableUnit {
+                    field_replaceable_unit_code,
+                } => Some(*field_replaceable_unit_code),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+}
+
+// sense key / ASC / ASCQ, followed by the description of the additional sense code
+impl Display for SenseData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.sense_key(), self.additional_sense_code()) {
+            (Some(sense_key), Some(code)) => write!(
+                f,
+                "{} / ASC 0x{:02X} / ASCQ 0x{:02X}: {}",
+                sense_key,
+                code.additional_sense_code(),
+                code.additional_sense_code_qualifier(),
+                code.description()
+            ),
+            _ => write!(f, "{:02X?}", self),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FixedSenseData {
     pub is_valid: bool,
     pub response_code: ErrorType,
@@ -90,6 +211,7 @@ pub struct FixedSenseData {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DescriptorSenseData {
     pub response_code: ErrorType,
     pub sense_key: SenseKey,
@@ -98,6 +220,8 @@ pub struct DescriptorSenseData {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ErrorType {
     Current,
     Deferred,
@@ -116,6 +240,8 @@ impl From<u8> for ErrorType {
 
 #[repr(u8)]
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum SenseKey {
     NoSense,
     RecoveredError,
@@ -159,7 +285,33 @@ impl From<u8> for SenseKey {
     }
 }
 
+impl Display for SenseKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::NoSense => "NO SENSE",
+            Self::RecoveredError => "RECOVERED ERROR",
+            Self::NotReady => "NOT READY",
+            Self::MediumError => "MEDIUM ERROR",
+            Self::HardwareError => "HARDWARE ERROR",
+            Self::IllegalRequest => "ILLEGAL REQUEST",
+            Self::UnitAttention => "UNIT ATTENTION",
+            Self::DataProtect => "DATA PROTECT",
+            Self::BlankCheck => "BLANK CHECK",
+            Self::VendorSpecific => "VENDOR SPECIFIC",
+            Self::CopyAborted => "COPY ABORTED",
+            Self::AbortedCommand => "ABORTED COMMAND",
+            Self::Reserved => "RESERVED",
+            Self::VolumeOverflow => "VOLUME OVERFLOW",
+            Self::Miscompare => "MISCOMPARE",
+            Self::Completed => "COMPLETED",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Descriptor {
     Information {
         is_valid: bool,
@@ -244,9 +396,9 @@ impl Descriptor {
                 }
 
                 Self::AnotherProgressIndication {
-                    sense_key: SenseKey::from(raw[2]),
+                    sense_key: SenseKey::from(raw[2] & 0b00001111),
                     additional_sense_code: AdditionalSenseCode::from((raw[3], raw[4])),
-                    progress_indication: u16::from_be_bytes(raw[6..=7].try_into().unwrap()),
+                    progress_indication: raw.get(6..).and_then(be_u16).unwrap_or(0),
                 }
             }
             _ => Self::Unknown(Vec::from(raw)),
@@ -255,6 +407,8 @@ impl Descriptor {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum SenseKeySpecific {
     IllegalRequest {
         is_command_data: bool,
@@ -287,21 +441,21 @@ impl SenseKeySpecific {
                 is_command_data: (raw[0] & 0b01000000) != 0,
                 is_bit_pointer_valid: (raw[0] & 0b00001000) != 0,
                 bit_pointer: (raw[0] & 0b00000111),
-                field_pointer: u16::from_be_bytes(raw[1..=2].try_into().unwrap()),
+                field_pointer: be_u16(&raw[1..]).unwrap_or(0),
             },
             SenseKey::HardwareError | SenseKey::MediumError | SenseKey::RecoveredError => {
                 Self::HardwareError {
-                    actual_retry_count: u16::from_be_bytes(raw[1..=2].try_into().unwrap()),
+                    actual_retry_count: be_u16(&raw[1..]).unwrap_or(0),
                 }
             }
             SenseKey::NoSense | SenseKey::NotReady => Self::NoSense {
-                progress_indication: u16::from_be_bytes(raw[1..=2].try_into().unwrap()),
+                progress_indication: be_u16(&raw[1..]).unwrap_or(0),
             },
             SenseKey::CopyAborted => Self::CopyAborted {
                 is_segment_descriptor: (raw[0] & 0b00100000) != 0,
                 is_bit_pointer_valid: (raw[0] & 0b00001000) != 0,
                 bit_pointer: (raw[0] & 0b00000111),
-                field_pointer: u16::from_be_bytes(raw[1..=2].try_into().unwrap()),
+                field_pointer: be_u16(&raw[1..]).unwrap_or(0),
             },
             SenseKey::UnitAttention => Self::UnitAttention {
                 is_overflow: (raw[0] & 0b00000001) != 0,
@@ -313,8 +467,19 @@ impl SenseKeySpecific {
 
 #[repr(transparent)]
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AdditionalSenseCode(u16);
 
+impl AdditionalSenseCode {
+    pub fn additional_sense_code(&self) -> u8 {
+        self.0.to_be_bytes()[0]
+    }
+
+    pub fn additional_sense_code_qualifier(&self) -> u8 {
+        self.0.to_be_bytes()[1]
+    }
+}
+
 impl Deref for AdditionalSenseCode {
     type Target = u16;
 
@@ -325,785 +490,794 @@ impl Deref for AdditionalSenseCode {
 
 impl Display for AdditionalSenseCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let description = match self.0 {
-            0x0000 => String::from("NO ADDITIONAL SENSE INFORMATION"),
-            0x0001 => String::from("FILEMARK DETECTED"),
-            0x0002 => String::from("END-OF-PARTITION/MEDIUM DETECTED"),
-            0x0003 => String::from("SETMARK DETECTED"),
-            0x0004 => String::from("BEGINNING-OF-PARTITION/MEDIUM DETECTED"),
-            0x0005 => String::from("END-OF-DATA DETECTED"),
-            0x0006 => String::from("I/O PROCESS TERMINATED"),
-            0x0007 => String::from("PROGRAMMABLE EARLY WARNING DETECTED"),
-            0x0011 => String::from("AUDIO PLAY OPERATION IN PROGRESS"),
-            0x0012 => String::from("AUDIO PLAY OPERATION PAUSED"),
-            0x0013 => String::from("AUDIO PLAY OPERATION SUCCESSFULLY COMPLETED"),
-            0x0014 => String::from("AUDIO PLAY OPERATION STOPPED DUE TO ERROR"),
-            0x0015 => String::from("NO CURRENT AUDIO STATUS TO RETURN"),
-            0x0016 => String::from("OPERATION IN PROGRESS"),
-            0x0017 => String::from("CLEANING REQUESTED"),
-            0x0018 => String::from("ERASE OPERATION IN PROGRESS"),
-            0x0019 => String::from("LOCATE OPERATION IN PROGRESS"),
-            0x001A => String::from("REWIND OPERATION IN PROGRESS"),
-            0x001B => String::from("SET CAPACITY OPERATION IN PROGRESS"),
-            0x001C => String::from("VERIFY OPERATION IN PROGRESS"),
-            0x001D => String::from("ATA PASS THROUGH INFORMATION AVAILABLE"),
-            0x001E => String::from("CONFLICTING SA CREATION REQUEST"),
-            0x001F => String::from("LOGICAL UNIT TRANSITIONING TO ANOTHER POWER CONDITION"),
-            0x0020 => String::from("EXTENDED COPY INFORMATION AVAILABLE"),
-            0x0021 => String::from("ATOMIC COMMAND ABORTED DUE TO ACA"),
-            0x0022 => String::from("DEFERRED MICROCODE IS PENDING"),
-            0x0100 => String::from("NO INDEX/SECTOR SIGNAL"),
-            0x0200 => String::from("NO SEEK COMPLETE"),
-            0x0300 => String::from("PERIPHERAL DEVICE WRITE FAULT"),
-            0x0301 => String::from("NO WRITE CURRENT"),
-            0x0302 => String::from("EXCESSIVE WRITE ERRORS"),
-            0x0400 => String::from("LOGICAL UNIT NOT READY, CAUSE NOT REPORTABLE"),
-            0x0401 => String::from("LOGICAL UNIT IS IN PROCESS OF BECOMING READY"),
-            0x0402 => String::from("LOGICAL UNIT NOT READY, INITIALIZING COMMAND REQUIRED"),
-            0x0403 => String::from("LOGICAL UNIT NOT READY, MANUAL INTERVENTION REQUIRED"),
-            0x0404 => String::from("LOGICAL UNIT NOT READY, FORMAT IN PROGRESS"),
-            0x0405 => String::from("LOGICAL UNIT NOT READY, REBUILD IN PROGRESS"),
-            0x0406 => String::from("LOGICAL UNIT NOT READY, RECALCULATION IN PROGRESS"),
-            0x0407 => String::from("LOGICAL UNIT NOT READY, OPERATION IN PROGRESS"),
-            0x0408 => String::from("LOGICAL UNIT NOT READY, LONG WRITE IN PROGRESS"),
-            0x0409 => String::from("LOGICAL UNIT NOT READY, SELF-TEST IN PROGRESS"),
-            0x040A => {
-                String::from("LOGICAL UNIT NOT ACCESSIBLE, ASYMMETRIC ACCESS STATE TRANSITION")
-            }
-            0x040B => String::from("LOGICAL UNIT NOT ACCESSIBLE, TARGET PORT IN STANDBY STATE"),
-            0x040C => String::from("LOGICAL UNIT NOT ACCESSIBLE, TARGET PORT IN UNAVAILABLE STATE"),
-            0x040D => String::from("LOGICAL UNIT NOT READY, STRUCTURE CHECK REQUIRED"),
-            0x040E => String::from("LOGICAL UNIT NOT READY, SECURITY SESSION IN PROGRESS"),
-            0x0410 => String::from("LOGICAL UNIT NOT READY, AUXILIARY MEMORY NOT ACCESSIBLE"),
-            0x0411 => String::from("LOGICAL UNIT NOT READY, NOTIFY (ENABLE SPINUP) REQUIRED"),
-            0x0412 => String::from("LOGICAL UNIT NOT READY, OFFLINE"),
-            0x0413 => String::from("LOGICAL UNIT NOT READY, SA CREATION IN PROGRESS"),
-            0x0414 => String::from("LOGICAL UNIT NOT READY, SPACE ALLOCATION IN PROGRESS"),
-            0x0415 => String::from("LOGICAL UNIT NOT READY, ROBOTICS DISABLED"),
-            0x0416 => String::from("LOGICAL UNIT NOT READY, CONFIGURATION REQUIRED"),
-            0x0417 => String::from("LOGICAL UNIT NOT READY, CALIBRATION REQUIRED"),
-            0x0418 => String::from("LOGICAL UNIT NOT READY, A DOOR IS OPEN"),
-            0x0419 => String::from("LOGICAL UNIT NOT READY, OPERATING IN SEQUENTIAL MODE"),
-            0x041A => String::from("LOGICAL UNIT NOT READY, START STOP UNIT COMMAND IN PROGRESS"),
-            0x041B => String::from("LOGICAL UNIT NOT READY, SANITIZE IN PROGRESS"),
-            0x041C => String::from("LOGICAL UNIT NOT READY, ADDITIONAL POWER USE NOT YET GRANTED"),
-            0x041D => String::from("LOGICAL UNIT NOT READY, CONFIGURATION IN PROGRESS"),
-            0x041E => String::from("LOGICAL UNIT NOT READY, MICROCODE ACTIVATION REQUIRED"),
-            0x041F => String::from("LOGICAL UNIT NOT READY, MICROCODE DOWNLOAD REQUIRED"),
-            0x0420 => String::from("LOGICAL UNIT NOT READY, LOGICAL UNIT RESET REQUIRED"),
-            0x0421 => String::from("LOGICAL UNIT NOT READY, HARD RESET REQUIRED"),
-            0x0422 => String::from("LOGICAL UNIT NOT READY, POWER CYCLE REQUIRED"),
-            0x0423 => String::from("LOGICAL UNIT NOT READY, AFFILIATION REQUIRED"),
-            0x0424 => String::from("DEPOPULATION IN PROGRESS"),
-            0x0425 => String::from("DEPOPULATION RESTORATION IN PROGRESS"),
-            0x0500 => String::from("LOGICAL UNIT DOES NOT RESPOND TO SELECTION"),
-            0x0600 => String::from("NO REFERENCE POSITION FOUND"),
-            0x0700 => String::from("MULTIPLE PERIPHERAL DEVICES SELECTED"),
-            0x0800 => String::from("LOGICAL UNIT COMMUNICATION FAILURE"),
-            0x0801 => String::from("LOGICAL UNIT COMMUNICATION TIME-OUT"),
-            0x0802 => String::from("LOGICAL UNIT COMMUNICATION PARITY ERROR"),
-            0x0803 => String::from("LOGICAL UNIT COMMUNICATION CRC ERROR (ULTRA-DMA/32)"),
-            0x0804 => String::from("UNREACHABLE COPY TARGET"),
-            0x0900 => String::from("TRACK FOLLOWING ERROR"),
-            0x0901 => String::from("TRACKING SERVO FAILURE"),
-            0x0902 => String::from("FOCUS SERVO FAILURE"),
-            0x0903 => String::from("SPINDLE SERVO FAILURE"),
-            0x0904 => String::from("HEAD SELECT FAULT"),
-            0x0905 => String::from("VIBRATION INDUCED TRACKING ERROR"),
-            0x0A00 => String::from("ERROR LOG OVERFLOW"),
-            0x0B00 => String::from("WARNING"),
-            0x0B01 => String::from("WARNING - SPECIFIED TEMPERATURE EXCEEDED"),
-            0x0B02 => String::from("WARNING - ENCLOSURE DEGRADED"),
-            0x0B03 => String::from("WARNING - BACKGROUND SELF-TEST FAILED"),
-            0x0B04 => String::from("WARNING - BACKGROUND PRE-SCAN DETECTED MEDIUM ERROR"),
-            0x0B05 => String::from("WARNING - BACKGROUND MEDIUM SCAN DETECTED MEDIUM ERROR"),
-            0x0B06 => String::from("WARNING - NON-VOLATILE CACHE NOW VOLATILE"),
-            0x0B07 => String::from("WARNING - DEGRADED POWER TO NON-VOLATILE CACHE"),
-            0x0B08 => String::from("WARNING - POWER LOSS EXPECTED"),
-            0x0B09 => String::from("WARNING - DEVICE STATISTICS NOTIFICATION ACTIVE"),
-            0x0B0A => String::from("WARNING - HIGH CRITICAL TEMPERATURE LIMIT EXCEEDED"),
-            0x0B0B => String::from("WARNING - LOW CRITICAL TEMPERATURE LIMIT EXCEEDED"),
-            0x0B0C => String::from("WARNING - HIGH OPERATING TEMPERATURE LIMIT EXCEEDED"),
-            0x0B0D => String::from("WARNING - LOW OPERATING TEMPERATURE LIMIT EXCEEDED"),
-            0x0B0E => String::from("WARNING - HIGH CRITICAL HUMIDITY LIMIT EXCEEDED"),
-            0x0B0F => String::from("WARNING - LOW CRITICAL HUMIDITY LIMIT EXCEEDED"),
-            0x0B10 => String::from("WARNING - HIGH OPERATING HUMIDITY LIMIT EXCEEDED"),
-            0x0B11 => String::from("WARNING - LOW OPERATING HUMIDITY LIMIT EXCEEDED"),
-            0x0B12 => String::from("WARNING - MICROCODE SECURITY AT RISK"),
-            0x0B13 => String::from("WARNING - MICROCODE DIGITAL SIGNATURE VALIDATION FAILURE"),
-            0x0B14 => String::from("WARNING - PHYSICAL ELEMENT STATUS CHANGE"),
-            0x0C00 => String::from("WRITE ERROR"),
-            0x0C01 => String::from("WRITE ERROR - RECOVERED WITH AUTO REALLOCATION"),
-            0x0C02 => String::from("WRITE ERROR - AUTO REALLOCATION FAILED"),
-            0x0C03 => String::from("WRITE ERROR - RECOMMEND REASSIGNMENT"),
-            0x0C04 => String::from("COMPRESSION CHECK MISCOMPARE ERROR"),
-            0x0C05 => String::from("DATA EXPANSION OCCURRED DURING COMPRESSION"),
-            0x0C06 => String::from("BLOCK NOT COMPRESSIBLE"),
-            0x0C07 => String::from("WRITE ERROR - RECOVERY NEEDED"),
-            0x0C08 => String::from("WRITE ERROR - RECOVERY FAILED"),
-            0x0C09 => String::from("WRITE ERROR - LOSS OF STREAMING"),
-            0x0C0A => String::from("WRITE ERROR - PADDING BLOCKS ADDED"),
-            0x0C0B => String::from("AUXILIARY MEMORY WRITE ERROR"),
-            0x0C0C => String::from("WRITE ERROR - UNEXPECTED UNSOLICITED DATA"),
-            0x0C0D => String::from("WRITE ERROR - NOT ENOUGH UNSOLICITED DATA"),
-            0x0C0E => String::from("MULTIPLE WRITE ERRORS"),
-            0x0C0F => String::from("DEFECTS IN ERROR WINDOW"),
-            0x0C10 => String::from("INCOMPLETE MULTIPLE ATOMIC WRITE OPERATIONS"),
-            0x0C11 => String::from("WRITE ERROR - RECOVERY SCAN NEEDED"),
-            0x0C12 => String::from("WRITE ERROR - INSUFFICIENT ZONE RESOURCES"),
-            0x0D00 => String::from("ERROR DETECTED BY THIRD PARTY TEMPORARY INITIATOR"),
-            0x0D01 => String::from("THIRD PARTY DEVICE FAILURE"),
-            0x0D02 => String::from("COPY TARGET DEVICE NOT REACHABLE"),
-            0x0D03 => String::from("INCORRECT COPY TARGET DEVICE TYPE"),
-            0x0D04 => String::from("COPY TARGET DEVICE DATA UNDERRUN"),
-            0x0D05 => String::from("COPY TARGET DEVICE DATA OVERRUN"),
-            0x0E00 => String::from("INVALID INFORMATION UNIT"),
-            0x0E01 => String::from("INFORMATION UNIT TOO SHORT"),
-            0x0E02 => String::from("INFORMATION UNIT TOO LONG"),
-            0x0E03 => String::from("INVALID FIELD IN COMMAND INFORMATION UNIT"),
-            0x1000 => String::from("ID CRC OR ECC ERROR"),
-            0x1001 => String::from("LOGICAL BLOCK GUARD CHECK FAILED"),
-            0x1002 => String::from("LOGICAL BLOCK APPLICATION TAG CHECK FAILED"),
-            0x1003 => String::from("LOGICAL BLOCK REFERENCE TAG CHECK FAILED"),
-            0x1004 => String::from("LOGICAL BLOCK PROTECTION ERROR ON RECOVER BUFFERED DATA"),
-            0x1005 => String::from("LOGICAL BLOCK PROTECTION METHOD ERROR"),
-            0x1100 => String::from("UNRECOVERED READ ERROR"),
-            0x1101 => String::from("READ RETRIES EXHAUSTED"),
-            0x1102 => String::from("ERROR TOO LONG TO CORRECT"),
-            0x1103 => String::from("MULTIPLE READ ERRORS"),
-            0x1104 => String::from("UNRECOVERED READ ERROR - AUTO REALLOCATE FAILED"),
-            0x1105 => String::from("L-EC UNCORRECTABLE ERROR"),
-            0x1106 => String::from("CIRC UNRECOVERED ERROR"),
-            0x1107 => String::from("DATA RE-SYNCHRONIZATION ERROR"),
-            0x1108 => String::from("INCOMPLETE BLOCK READ"),
-            0x1109 => String::from("NO GAP FOUND"),
-            0x110A => String::from("MISCORRECTED ERROR"),
-            0x110B => String::from("UNRECOVERED READ ERROR - RECOMMEND REASSIGNMENT"),
-            0x110C => String::from("UNRECOVERED READ ERROR - RECOMMEND REWRITE THE DATA"),
-            0x110D => String::from("DE-COMPRESSION CRC ERROR"),
-            0x110E => String::from("CANNOT DECOMPRESS USING DECLARED ALGORITHM"),
-            0x110F => String::from("ERROR READING UPC/EAN NUMBER"),
-            0x1110 => String::from("ERROR READING ISRC NUMBER"),
-            0x1111 => String::from("READ ERROR - LOSS OF STREAMING"),
-            0x1112 => String::from("AUXILIARY MEMORY READ ERROR"),
-            0x1113 => String::from("READ ERROR - FAILED RETRANSMISSION REQUEST"),
-            0x1114 => String::from("READ ERROR - LBA MARKED BAD BY APPLICATION CLIENT"),
-            0x1115 => String::from("WRITE AFTER SANITIZE REQUIRED"),
-            0x1200 => String::from("ADDRESS MARK NOT FOUND FOR ID FIELD"),
-            0x1300 => String::from("ADDRESS MARK NOT FOUND FOR DATA FIELD"),
-            0x1400 => String::from("RECORDED ENTITY NOT FOUND"),
-            0x1401 => String::from("RECORD NOT FOUND"),
-            0x1402 => String::from("FILEMARK OR SETMARK NOT FOUND"),
-            0x1403 => String::from("END-OF-DATA NOT FOUND"),
-            0x1404 => String::from("BLOCK SEQUENCE ERROR"),
-            0x1405 => String::from("RECORD NOT FOUND - RECOMMEND REASSIGNMENT"),
-            0x1406 => String::from("RECORD NOT FOUND - DATA AUTO-REALLOCATED"),
-            0x1407 => String::from("LOCATE OPERATION FAILURE"),
-            0x1500 => String::from("RANDOM POSITIONING ERROR"),
-            0x1501 => String::from("MECHANICAL POSITIONING ERROR"),
-            0x1502 => String::from("POSITIONING ERROR DETECTED BY READ OF MEDIUM"),
-            0x1600 => String::from("DATA SYNCHRONIZATION MARK ERROR"),
-            0x1601 => String::from("DATA SYNC ERROR - DATA REWRITTEN"),
-            0x1602 => String::from("DATA SYNC ERROR - RECOMMEND REWRITE"),
-            0x1603 => String::from("DATA SYNC ERROR - DATA AUTO-REALLOCATED"),
-            0x1604 => String::from("DATA SYNC ERROR - RECOMMEND REASSIGNMENT"),
-            0x1700 => String::from("RECOVERED DATA WITH NO ERROR CORRECTION APPLIED"),
-            0x1701 => String::from("RECOVERED DATA WITH RETRIES"),
-            0x1702 => String::from("RECOVERED DATA WITH POSITIVE HEAD OFFSET"),
-            0x1703 => String::from("RECOVERED DATA WITH NEGATIVE HEAD OFFSET"),
-            0x1704 => String::from("RECOVERED DATA WITH RETRIES AND/OR CIRC APPLIED"),
-            0x1705 => String::from("RECOVERED DATA USING PREVIOUS SECTOR ID"),
-            0x1706 => String::from("RECOVERED DATA WITHOUT ECC - DATA AUTO-REALLOCATED"),
-            0x1707 => String::from("RECOVERED DATA WITHOUT ECC - RECOMMEND REASSIGNMENT"),
-            0x1708 => String::from("RECOVERED DATA WITHOUT ECC - RECOMMEND REWRITE"),
-            0x1709 => String::from("RECOVERED DATA WITHOUT ECC - DATA REWRITTEN"),
-            0x1800 => String::from("RECOVERED DATA WITH ERROR CORRECTION APPLIED"),
-            0x1801 => String::from("RECOVERED DATA WITH ERROR CORR. & RETRIES APPLIED"),
-            0x1802 => String::from("RECOVERED DATA - DATA AUTO-REALLOCATED"),
-            0x1803 => String::from("RECOVERED DATA WITH CIRC"),
-            0x1804 => String::from("RECOVERED DATA WITH L-EC"),
-            0x1805 => String::from("RECOVERED DATA - RECOMMEND REASSIGNMENT"),
-            0x1806 => String::from("RECOVERED DATA - RECOMMEND REWRITE"),
-            0x1807 => String::from("RECOVERED DATA WITH ECC - DATA REWRITTEN"),
-            0x1808 => String::from("RECOVERED DATA WITH LINKING"),
-            0x1900 => String::from("DEFECT LIST ERROR"),
-            0x1901 => String::from("DEFECT LIST NOT AVAILABLE"),
-            0x1902 => String::from("DEFECT LIST ERROR IN PRIMARY LIST"),
-            0x1903 => String::from("DEFECT LIST ERROR IN GROWN LIST"),
-            0x1A00 => String::from("PARAMETER LIST LENGTH ERROR"),
-            0x1B00 => String::from("SYNCHRONOUS DATA TRANSFER ERROR"),
-            0x1C00 => String::from("DEFECT LIST NOT FOUND"),
-            0x1C01 => String::from("PRIMARY DEFECT LIST NOT FOUND"),
-            0x1C02 => String::from("GROWN DEFECT LIST NOT FOUND"),
-            0x1D00 => String::from("MISCOMPARE DURING VERIFY OPERATION"),
-            0x1D01 => String::from("MISCOMPARE VERIFY OF UNMAPPED LBA"),
-            0x1E00 => String::from("RECOVERED ID WITH ECC CORRECTION"),
-            0x1F00 => String::from("PARTIAL DEFECT LIST TRANSFER"),
-            0x2000 => String::from("INVALID COMMAND OPERATION CODE"),
-            0x2001 => String::from("ACCESS DENIED - INITIATOR PENDING-ENROLLED"),
-            0x2002 => String::from("ACCESS DENIED - NO ACCESS RIGHTS"),
-            0x2003 => String::from("ACCESS DENIED - INVALID MGMT ID KEY"),
-            0x2004 => String::from("ILLEGAL COMMAND WHILE IN WRITE CAPABLE STATE"),
-            0x2005 => String::from("Obsolete"),
-            0x2006 => String::from("ILLEGAL COMMAND WHILE IN EXPLICIT ADDRESS MODE"),
-            0x2007 => String::from("ILLEGAL COMMAND WHILE IN IMPLICIT ADDRESS MODE"),
-            0x2008 => String::from("ACCESS DENIED - ENROLLMENT CONFLICT"),
-            0x2009 => String::from("ACCESS DENIED - INVALID LU IDENTIFIER"),
-            0x200A => String::from("ACCESS DENIED - INVALID PROXY TOKEN"),
-            0x200B => String::from("ACCESS DENIED - ACL LUN CONFLICT"),
-            0x200C => String::from("ILLEGAL COMMAND WHEN NOT IN APPEND-ONLY MODE"),
-            0x200D => String::from("NOT AN ADMINISTRATIVE LOGICAL UNIT"),
-            0x200E => String::from("NOT A SUBSIDIARY LOGICAL UNIT"),
-            0x200F => String::from("NOT A CONGLOMERATE LOGICAL UNIT"),
-            0x2100 => String::from("LOGICAL BLOCK ADDRESS OUT OF RANGE"),
-            0x2101 => String::from("INVALID ELEMENT ADDRESS"),
-            0x2102 => String::from("INVALID ADDRESS FOR WRITE"),
-            0x2103 => String::from("INVALID WRITE CROSSING LAYER JUMP"),
-            0x2104 => String::from("UNALIGNED WRITE COMMAND"),
-            0x2105 => String::from("WRITE BOUNDARY VIOLATION"),
-            0x2106 => String::from("ATTEMPT TO READ INVALID DATA"),
-            0x2107 => String::from("READ BOUNDARY VIOLATION"),
-            0x2108 => String::from("MISALIGNED WRITE COMMAND"),
-            0x2109 => String::from("ATTEMPT TO ACCESS GAP ZONE"),
-            0x2200 => String::from("ILLEGAL FUNCTION (USE 20 00, 24 00, OR 26 00)"),
-            0x2300 => String::from("INVALID TOKEN OPERATION, CAUSE NOT REPORTABLE"),
-            0x2301 => String::from("INVALID TOKEN OPERATION, UNSUPPORTED TOKEN TYPE"),
-            0x2302 => String::from("INVALID TOKEN OPERATION, REMOTE TOKEN USAGE NOT SUPPORTED"),
-            0x2303 => {
-                String::from("INVALID TOKEN OPERATION, REMOTE ROD TOKEN CREATION NOT SUPPORTED")
-            }
-            0x2304 => String::from("INVALID TOKEN OPERATION, TOKEN UNKNOWN"),
-            0x2305 => String::from("INVALID TOKEN OPERATION, TOKEN CORRUPT"),
-            0x2306 => String::from("INVALID TOKEN OPERATION, TOKEN REVOKED"),
-            0x2307 => String::from("INVALID TOKEN OPERATION, TOKEN EXPIRED"),
-            0x2308 => String::from("INVALID TOKEN OPERATION, TOKEN CANCELLED"),
-            0x2309 => String::from("INVALID TOKEN OPERATION, TOKEN DELETED"),
-            0x230A => String::from("INVALID TOKEN OPERATION, INVALID TOKEN LENGTH"),
-            0x2400 => String::from("INVALID FIELD IN CDB"),
-            0x2401 => String::from("CDB DECRYPTION ERROR"),
-            0x2402 => String::from("Obsolete"),
-            0x2403 => String::from("Obsolete"),
-            0x2404 => String::from("SECURITY AUDIT VALUE FROZEN"),
-            0x2405 => String::from("SECURITY WORKING KEY FROZEN"),
-            0x2406 => String::from("NONCE NOT UNIQUE"),
-            0x2407 => String::from("NONCE TIMESTAMP OUT OF RANGE"),
-            0x2408 => String::from("INVALID XCDB"),
-            0x2409 => String::from("INVALID FAST FORMAT"),
-            0x2500 => String::from("LOGICAL UNIT NOT SUPPORTED"),
-            0x2600 => String::from("INVALID FIELD IN PARAMETER LIST"),
-            0x2601 => String::from("PARAMETER NOT SUPPORTED"),
-            0x2602 => String::from("PARAMETER VALUE INVALID"),
-            0x2603 => String::from("THRESHOLD PARAMETERS NOT SUPPORTED"),
-            0x2604 => String::from("INVALID RELEASE OF PERSISTENT RESERVATION"),
-            0x2605 => String::from("DATA DECRYPTION ERROR"),
-            0x2606 => String::from("TOO MANY TARGET DESCRIPTORS"),
-            0x2607 => String::from("UNSUPPORTED TARGET DESCRIPTOR TYPE CODE"),
-            0x2608 => String::from("TOO MANY SEGMENT DESCRIPTORS"),
-            0x2609 => String::from("UNSUPPORTED SEGMENT DESCRIPTOR TYPE CODE"),
-            0x260A => String::from("UNEXPECTED INEXACT SEGMENT"),
-            0x260B => String::from("INLINE DATA LENGTH EXCEEDED"),
-            0x260C => String::from("INVALID OPERATION FOR COPY SOURCE OR DESTINATION"),
-            0x260D => String::from("COPY SEGMENT GRANULARITY VIOLATION"),
-            0x260E => String::from("INVALID PARAMETER WHILE PORT IS ENABLED"),
-            0x260F => String::from("INVALID DATA-OUT BUFFER INTEGRITY CHECK VALUE"),
-            0x2610 => String::from("DATA DECRYPTION KEY FAIL LIMIT REACHED"),
-            0x2611 => String::from("INCOMPLETE KEY-ASSOCIATED DATA SET"),
-            0x2612 => String::from("VENDOR SPECIFIC KEY REFERENCE NOT FOUND"),
-            0x2613 => String::from("APPLICATION TAG MODE PAGE IS INVALID"),
-            0x2614 => String::from("TAPE STREAM MIRRORING PREVENTED"),
-            0x2615 => String::from("COPY SOURCE OR COPY DESTINATION NOT AUTHORIZED"),
-            0x2616 => String::from("FAST COPY NOT POSSIBLE"),
-            0x2700 => String::from("WRITE PROTECTED"),
-            0x2701 => String::from("HARDWARE WRITE PROTECTED"),
-            0x2702 => String::from("LOGICAL UNIT SOFTWARE WRITE PROTECTED"),
-            0x2703 => String::from("ASSOCIATED WRITE PROTECT"),
-            0x2704 => String::from("PERSISTENT WRITE PROTECT"),
-            0x2705 => String::from("PERMANENT WRITE PROTECT"),
-            0x2706 => String::from("CONDITIONAL WRITE PROTECT"),
-            0x2707 => String::from("SPACE ALLOCATION FAILED WRITE PROTECT"),
-            0x2708 => String::from("ZONE IS READ ONLY"),
-            0x2800 => String::from("NOT READY TO READY CHANGE, MEDIUM MAY HAVE CHANGED"),
-            0x2801 => String::from("IMPORT OR EXPORT ELEMENT ACCESSED"),
-            0x2802 => String::from("FORMAT-LAYER MAY HAVE CHANGED"),
-            0x2803 => String::from("IMPORT/EXPORT ELEMENT ACCESSED, MEDIUM CHANGED"),
-            0x2900 => String::from("POWER ON, RESET, OR BUS DEVICE RESET OCCURRED"),
-            0x2901 => String::from("POWER ON OCCURRED"),
-            0x2902 => String::from("SCSI BUS RESET OCCURRED"),
-            0x2903 => String::from("BUS DEVICE RESET FUNCTION OCCURRED"),
-            0x2904 => String::from("DEVICE INTERNAL RESET"),
-            0x2905 => String::from("TRANSCEIVER MODE CHANGED TO SINGLE-ENDED"),
-            0x2906 => String::from("TRANSCEIVER MODE CHANGED TO LVD"),
-            0x2907 => String::from("I_T NEXUS LOSS OCCURRED"),
-            0x2A00 => String::from("PARAMETERS CHANGED"),
-            0x2A01 => String::from("MODE PARAMETERS CHANGED"),
-            0x2A02 => String::from("LOG PARAMETERS CHANGED"),
-            0x2A03 => String::from("RESERVATIONS PREEMPTED"),
-            0x2A04 => String::from("RESERVATIONS RELEASED"),
-            0x2A05 => String::from("REGISTRATIONS PREEMPTED"),
-            0x2A06 => String::from("ASYMMETRIC ACCESS STATE CHANGED"),
-            0x2A07 => String::from("IMPLICIT ASYMMETRIC ACCESS STATE TRANSITION FAILED"),
-            0x2A08 => String::from("PRIORITY CHANGED"),
-            0x2A09 => String::from("CAPACITY DATA HAS CHANGED"),
-            0x2A0A => String::from("ERROR HISTORY I_T NEXUS CLEARED"),
-            0x2A0B => String::from("ERROR HISTORY SNAPSHOT RELEASED"),
-            0x2A0C => String::from("ERROR RECOVERY ATTRIBUTES HAVE CHANGED"),
-            0x2A0D => String::from("DATA ENCRYPTION CAPABILITIES CHANGED"),
-            0x2A10 => String::from("TIMESTAMP CHANGED"),
-            0x2A11 => String::from("DATA ENCRYPTION PARAMETERS CHANGED BY ANOTHER I_T NEXUS"),
-            0x2A12 => String::from("DATA ENCRYPTION PARAMETERS CHANGED BY VENDOR SPECIFIC EVENT"),
-            0x2A13 => String::from("DATA ENCRYPTION KEY INSTANCE COUNTER HAS CHANGED"),
-            0x2A14 => String::from("SA CREATION CAPABILITIES DATA HAS CHANGED"),
-            0x2A15 => String::from("MEDIUM REMOVAL PREVENTION PREEMPTED"),
-            0x2A16 => String::from("ZONE RESET WRITE POINTER RECOMMENDED"),
-            0x2B00 => String::from("COPY CANNOT EXECUTE SINCE HOST CANNOT DISCONNECT"),
-            0x2C00 => String::from("COMMAND SEQUENCE ERROR"),
-            0x2C01 => String::from("TOO MANY WINDOWS SPECIFIED"),
-            0x2C02 => String::from("INVALID COMBINATION OF WINDOWS SPECIFIED"),
-            0x2C03 => String::from("CURRENT PROGRAM AREA IS NOT EMPTY"),
-            0x2C04 => String::from("CURRENT PROGRAM AREA IS EMPTY"),
-            0x2C05 => String::from("ILLEGAL POWER CONDITION REQUEST"),
-            0x2C06 => String::from("PERSISTENT PREVENT CONFLICT"),
-            0x2C07 => String::from("PREVIOUS BUSY STATUS"),
-            0x2C08 => String::from("PREVIOUS TASK SET FULL STATUS"),
-            0x2C09 => String::from("PREVIOUS RESERVATION CONFLICT STATUS"),
-            0x2C0A => String::from("PARTITION OR COLLECTION CONTAINS USER OBJECTS"),
-            0x2C0B => String::from("NOT RESERVED"),
-            0x2C0C => String::from("ORWRITE GENERATION DOES NOT MATCH"),
-            0x2C0D => String::from("RESET WRITE POINTER NOT ALLOWED"),
-            0x2C0E => String::from("ZONE IS OFFLINE"),
-            0x2C0F => String::from("STREAM NOT OPEN"),
-            0x2C10 => String::from("UNWRITTEN DATA IN ZONE"),
-            0x2C11 => String::from("DESCRIPTOR FORMAT SENSE DATA REQUIRED"),
-            0x2C12 => String::from("ZONE IS INACTIVE"),
-            0x2C13 => String::from("WELL KNOWN LOGICAL UNIT ACCESS REQUIRED"),
-            0x2D00 => String::from("OVERWRITE ERROR ON UPDATE IN PLACE"),
-            0x2E00 => String::from("INSUFFICIENT TIME FOR OPERATION"),
-            0x2E01 => String::from("COMMAND TIMEOUT BEFORE PROCESSING"),
-            0x2E02 => String::from("COMMAND TIMEOUT DURING PROCESSING"),
-            0x2E03 => String::from("COMMAND TIMEOUT DURING PROCESSING DUE TO ERROR RECOVERY"),
-            0x2F00 => String::from("COMMANDS CLEARED BY ANOTHER INITIATOR"),
-            0x2F01 => String::from("COMMANDS CLEARED BY POWER LOSS NOTIFICATION"),
-            0x2F02 => String::from("COMMANDS CLEARED BY DEVICE SERVER"),
-            0x2F03 => String::from("SOME COMMANDS CLEARED BY QUEUING LAYER EVENT"),
-            0x3000 => String::from("INCOMPATIBLE MEDIUM INSTALLED"),
-            0x3001 => String::from("CANNOT READ MEDIUM - UNKNOWN FORMAT"),
-            0x3002 => String::from("CANNOT READ MEDIUM - INCOMPATIBLE FORMAT"),
-            0x3003 => String::from("CLEANING CARTRIDGE INSTALLED"),
-            0x3004 => String::from("CANNOT WRITE MEDIUM - UNKNOWN FORMAT"),
-            0x3005 => String::from("CANNOT WRITE MEDIUM - INCOMPATIBLE FORMAT"),
-            0x3006 => String::from("CANNOT FORMAT MEDIUM - INCOMPATIBLE MEDIUM"),
-            0x3007 => String::from("CLEANING FAILURE"),
-            0x3008 => String::from("CANNOT WRITE - APPLICATION CODE MISMATCH"),
-            0x3009 => String::from("CURRENT SESSION NOT FIXATED FOR APPEND"),
-            0x300A => String::from("CLEANING REQUEST REJECTED"),
-            0x300C => String::from("WORM MEDIUM - OVERWRITE ATTEMPTED"),
-            0x300D => String::from("WORM MEDIUM - INTEGRITY CHECK"),
-            0x3010 => String::from("MEDIUM NOT FORMATTED"),
-            0x3011 => String::from("INCOMPATIBLE VOLUME TYPE"),
-            0x3012 => String::from("INCOMPATIBLE VOLUME QUALIFIER"),
-            0x3013 => String::from("CLEANING VOLUME EXPIRED"),
-            0x3100 => String::from("MEDIUM FORMAT CORRUPTED"),
-            0x3101 => String::from("FORMAT COMMAND FAILED"),
-            0x3102 => String::from("ZONED FORMATTING FAILED DUE TO SPARE LINKING"),
-            0x3103 => String::from("SANITIZE COMMAND FAILED"),
-            0x3104 => String::from("DEPOPULATION FAILED"),
-            0x3105 => String::from("DEPOPULATION RESTORATION FAILED"),
-            0x3200 => String::from("NO DEFECT SPARE LOCATION AVAILABLE"),
-            0x3201 => String::from("DEFECT LIST UPDATE FAILURE"),
-            0x3300 => String::from("TAPE LENGTH ERROR"),
-            0x3400 => String::from("ENCLOSURE FAILURE"),
-            0x3500 => String::from("ENCLOSURE SERVICES FAILURE"),
-            0x3501 => String::from("UNSUPPORTED ENCLOSURE FUNCTION"),
-            0x3502 => String::from("ENCLOSURE SERVICES UNAVAILABLE"),
-            0x3503 => String::from("ENCLOSURE SERVICES TRANSFER FAILURE"),
-            0x3504 => String::from("ENCLOSURE SERVICES TRANSFER REFUSED"),
-            0x3505 => String::from("ENCLOSURE SERVICES CHECKSUM ERROR"),
-            0x3600 => String::from("RIBBON, INK, OR TONER FAILURE"),
-            0x3700 => String::from("ROUNDED PARAMETER"),
-            0x3800 => String::from("EVENT STATUS NOTIFICATION"),
-            0x3802 => String::from("ESN - POWER MANAGEMENT CLASS EVENT"),
-            0x3804 => String::from("ESN - MEDIA CLASS EVENT"),
-            0x3806 => String::from("ESN - DEVICE BUSY CLASS EVENT"),
-            0x3807 => String::from("THIN PROVISIONING SOFT THRESHOLD REACHED"),
-            0x3808 => String::from("DEPOPULATION INTERRUPTED"),
-            0x3900 => String::from("SAVING PARAMETERS NOT SUPPORTED"),
-            0x3A00 => String::from("MEDIUM NOT PRESENT"),
-            0x3A01 => String::from("MEDIUM NOT PRESENT - TRAY CLOSED"),
-            0x3A02 => String::from("MEDIUM NOT PRESENT - TRAY OPEN"),
-            0x3A03 => String::from("MEDIUM NOT PRESENT - LOADABLE"),
-            0x3A04 => String::from("MEDIUM NOT PRESENT - MEDIUM AUXILIARY MEMORY ACCESSIBLE"),
-            0x3B00 => String::from("SEQUENTIAL POSITIONING ERROR"),
-            0x3B01 => String::from("TAPE POSITION ERROR AT BEGINNING-OF-MEDIUM"),
-            0x3B02 => String::from("TAPE POSITION ERROR AT END-OF-MEDIUM"),
-            0x3B03 => String::from("TAPE OR ELECTRONIC VERTICAL FORMS UNIT NOT READY"),
-            0x3B04 => String::from("SLEW FAILURE"),
-            0x3B05 => String::from("PAPER JAM"),
-            0x3B06 => String::from("FAILED TO SENSE TOP-OF-FORM"),
-            0x3B07 => String::from("FAILED TO SENSE BOTTOM-OF-FORM"),
-            0x3B08 => String::from("REPOSITION ERROR"),
-            0x3B09 => String::from("READ PAST END OF MEDIUM"),
-            0x3B0A => String::from("READ PAST BEGINNING OF MEDIUM"),
-            0x3B0B => String::from("POSITION PAST END OF MEDIUM"),
-            0x3B0C => String::from("POSITION PAST BEGINNING OF MEDIUM"),
-            0x3B0D => String::from("MEDIUM DESTINATION ELEMENT FULL"),
-            0x3B0E => String::from("MEDIUM SOURCE ELEMENT EMPTY"),
-            0x3B0F => String::from("END OF MEDIUM REACHED"),
-            0x3B11 => String::from("MEDIUM MAGAZINE NOT ACCESSIBLE"),
-            0x3B12 => String::from("MEDIUM MAGAZINE REMOVED"),
-            0x3B13 => String::from("MEDIUM MAGAZINE INSERTED"),
-            0x3B14 => String::from("MEDIUM MAGAZINE LOCKED"),
-            0x3B15 => String::from("MEDIUM MAGAZINE UNLOCKED"),
-            0x3B16 => String::from("MECHANICAL POSITIONING OR CHANGER ERROR"),
-            0x3B17 => String::from("READ PAST END OF USER OBJECT"),
-            0x3B18 => String::from("ELEMENT DISABLED"),
-            0x3B19 => String::from("ELEMENT ENABLED"),
-            0x3B1A => String::from("DATA TRANSFER DEVICE REMOVED"),
-            0x3B1B => String::from("DATA TRANSFER DEVICE INSERTED"),
-            0x3B1C => String::from("TOO MANY LOGICAL OBJECTS ON PARTITION TO SUPPORT OPERATION"),
-            0x3B20 => String::from("ELEMENT STATIC INFORMATION CHANGED"),
-            0x3D00 => String::from("INVALID BITS IN IDENTIFY MESSAGE"),
-            0x3E00 => String::from("LOGICAL UNIT HAS NOT SELF-CONFIGURED YET"),
-            0x3E01 => String::from("LOGICAL UNIT FAILURE"),
-            0x3E02 => String::from("TIMEOUT ON LOGICAL UNIT"),
-            0x3E03 => String::from("LOGICAL UNIT FAILED SELF-TEST"),
-            0x3E04 => String::from("LOGICAL UNIT UNABLE TO UPDATE SELF-TEST LOG"),
-            0x3F00 => String::from("TARGET OPERATING CONDITIONS HAVE CHANGED"),
-            0x3F01 => String::from("MICROCODE HAS BEEN CHANGED"),
-            0x3F02 => String::from("CHANGED OPERATING DEFINITION"),
-            0x3F03 => String::from("INQUIRY DATA HAS CHANGED"),
-            0x3F04 => String::from("COMPONENT DEVICE ATTACHED"),
-            0x3F05 => String::from("DEVICE IDENTIFIER CHANGED"),
-            0x3F06 => String::from("REDUNDANCY GROUP CREATED OR MODIFIED"),
-            0x3F07 => String::from("REDUNDANCY GROUP DELETED"),
-            0x3F08 => String::from("SPARE CREATED OR MODIFIED"),
-            0x3F09 => String::from("SPARE DELETED"),
-            0x3F0A => String::from("VOLUME SET CREATED OR MODIFIED"),
-            0x3F0B => String::from("VOLUME SET DELETED"),
-            0x3F0C => String::from("VOLUME SET DEASSIGNED"),
-            0x3F0D => String::from("VOLUME SET REASSIGNED"),
-            0x3F0E => String::from("REPORTED LUNS DATA HAS CHANGED"),
-            0x3F0F => String::from("ECHO BUFFER OVERWRITTEN"),
-            0x3F10 => String::from("MEDIUM LOADABLE"),
-            0x3F11 => String::from("MEDIUM AUXILIARY MEMORY ACCESSIBLE"),
-            0x3F12 => String::from("iSCSI IP ADDRESS ADDED"),
-            0x3F13 => String::from("iSCSI IP ADDRESS REMOVED"),
-            0x3F14 => String::from("iSCSI IP ADDRESS CHANGED"),
-            0x3F15 => String::from("INSPECT REFERRALS SENSE DESCRIPTORS"),
-            0x3F16 => String::from("MICROCODE HAS BEEN CHANGED WITHOUT RESET"),
-            0x3F17 => String::from("ZONE TRANSITION TO FULL"),
-            0x3F18 => String::from("BIND COMPLETED"),
-            0x3F19 => String::from("BIND REDIRECTED"),
-            0x3F1A => String::from("SUBSIDIARY BINDING CHANGED"),
-            0x4000 => String::from("RAM FAILURE (SHOULD USE 40 NN)"),
-            0x4100 => String::from("DATA PATH FAILURE (SHOULD USE 40 NN)"),
-            0x4200 => String::from("POWER-ON OR SELF-TEST FAILURE (SHOULD USE 40 NN)"),
-            0x4300 => String::from("MESSAGE ERROR"),
-            0x4400 => String::from("INTERNAL TARGET FAILURE"),
-            0x4401 => String::from("PERSISTENT RESERVATION INFORMATION LOST"),
-            0x4471 => String::from("ATA DEVICE FAILED SET FEATURES"),
-            0x4500 => String::from("SELECT OR RESELECT FAILURE"),
-            0x4600 => String::from("UNSUCCESSFUL SOFT RESET"),
-            0x4700 => String::from("SCSI PARITY ERROR"),
-            0x4701 => String::from("DATA PHASE CRC ERROR DETECTED"),
-            0x4702 => String::from("SCSI PARITY ERROR DETECTED DURING ST DATA PHASE"),
-            0x4703 => String::from("INFORMATION UNIT iuCRC ERROR DETECTED"),
-            0x4704 => String::from("ASYNCHRONOUS INFORMATION PROTECTION ERROR DETECTED"),
-            0x4705 => String::from("PROTOCOL SERVICE CRC ERROR"),
-            0x4706 => String::from("PHY TEST FUNCTION IN PROGRESS"),
-            0x477F => String::from("SOME COMMANDS CLEARED BY ISCSI PROTOCOL EVENT"),
-            0x4800 => String::from("INITIATOR DETECTED ERROR MESSAGE RECEIVED"),
-            0x4900 => String::from("INVALID MESSAGE ERROR"),
-            0x4A00 => String::from("COMMAND PHASE ERROR"),
-            0x4B00 => String::from("DATA PHASE ERROR"),
-            0x4B01 => String::from("INVALID TARGET PORT TRANSFER TAG RECEIVED"),
-            0x4B02 => String::from("TOO MUCH WRITE DATA"),
-            0x4B03 => String::from("ACK/NAK TIMEOUT"),
-            0x4B04 => String::from("NAK RECEIVED"),
-            0x4B05 => String::from("DATA OFFSET ERROR"),
-            0x4B06 => String::from("INITIATOR RESPONSE TIMEOUT"),
-            0x4B07 => String::from("CONNECTION LOST"),
-            0x4B08 => String::from("DATA-IN BUFFER OVERFLOW - DATA BUFFER SIZE"),
-            0x4B09 => String::from("DATA-IN BUFFER OVERFLOW - DATA BUFFER DESCRIPTOR AREA"),
-            0x4B0A => String::from("DATA-IN BUFFER ERROR"),
-            0x4B0B => String::from("DATA-OUT BUFFER OVERFLOW - DATA BUFFER SIZE"),
-            0x4B0C => String::from("DATA-OUT BUFFER OVERFLOW - DATA BUFFER DESCRIPTOR AREA"),
-            0x4B0D => String::from("DATA-OUT BUFFER ERROR"),
-            0x4B0E => String::from("PCIE FABRIC ERROR"),
-            0x4B0F => String::from("PCIE COMPLETION TIMEOUT"),
-            0x4B10 => String::from("PCIE COMPLETER ABORT"),
-            0x4B11 => String::from("PCIE POISONED TLP RECEIVED"),
-            0x4B12 => String::from("PCIE ECRC CHECK FAILED"),
-            0x4B13 => String::from("PCIE UNSUPPORTED REQUEST"),
-            0x4B14 => String::from("PCIE ACS VIOLATION"),
-            0x4B15 => String::from("PCIE TLP PREFIX BLOCKED"),
-            0x4C00 => String::from("LOGICAL UNIT FAILED SELF-CONFIGURATION"),
-            0x4E00 => String::from("OVERLAPPED COMMANDS ATTEMPTED"),
-            0x5000 => String::from("WRITE APPEND ERROR"),
-            0x5001 => String::from("WRITE APPEND POSITION ERROR"),
-            0x5002 => String::from("POSITION ERROR RELATED TO TIMING"),
-            0x5100 => String::from("ERASE FAILURE"),
-            0x5101 => String::from("ERASE FAILURE - INCOMPLETE ERASE OPERATION DETECTED"),
-            0x5200 => String::from("CARTRIDGE FAULT"),
-            0x5300 => String::from("MEDIA LOAD OR EJECT FAILED"),
-            0x5301 => String::from("UNLOAD TAPE FAILURE"),
-            0x5302 => String::from("MEDIUM REMOVAL PREVENTED"),
-            0x5303 => String::from("MEDIUM REMOVAL PREVENTED BY DATA TRANSFER ELEMENT"),
-            0x5304 => String::from("MEDIUM THREAD OR UNTHREAD FAILURE"),
-            0x5305 => String::from("VOLUME IDENTIFIER INVALID"),
-            0x5306 => String::from("VOLUME IDENTIFIER MISSING"),
-            0x5307 => String::from("DUPLICATE VOLUME IDENTIFIER"),
-            0x5308 => String::from("ELEMENT STATUS UNKNOWN"),
-            0x5309 => String::from("DATA TRANSFER DEVICE ERROR - LOAD FAILED"),
-            0x530A => String::from("DATA TRANSFER DEVICE ERROR - UNLOAD FAILED"),
-            0x530B => String::from("DATA TRANSFER DEVICE ERROR - UNLOAD MISSING"),
-            0x530C => String::from("DATA TRANSFER DEVICE ERROR - EJECT FAILED"),
-            0x530D => String::from("DATA TRANSFER DEVICE ERROR - LIBRARY COMMUNICATION FAILED"),
-            0x5400 => String::from("SCSI TO HOST SYSTEM INTERFACE FAILURE"),
-            0x5500 => String::from("SYSTEM RESOURCE FAILURE"),
-            0x5501 => String::from("SYSTEM BUFFER FULL"),
-            0x5502 => String::from("INSUFFICIENT RESERVATION RESOURCES"),
-            0x5503 => String::from("INSUFFICIENT RESOURCES"),
-            0x5504 => String::from("INSUFFICIENT REGISTRATION RESOURCES"),
-            0x5505 => String::from("INSUFFICIENT ACCESS CONTROL RESOURCES"),
-            0x5506 => String::from("AUXILIARY MEMORY OUT OF SPACE"),
-            0x5507 => String::from("QUOTA ERROR"),
-            0x5508 => String::from("MAXIMUM NUMBER OF SUPPLEMENTAL DECRYPTION KEYS EXCEEDED"),
-            0x5509 => String::from("MEDIUM AUXILIARY MEMORY NOT ACCESSIBLE"),
-            0x550A => String::from("DATA CURRENTLY UNAVAILABLE"),
-            0x550B => String::from("INSUFFICIENT POWER FOR OPERATION"),
-            0x550C => String::from("INSUFFICIENT RESOURCES TO CREATE ROD"),
-            0x550D => String::from("INSUFFICIENT RESOURCES TO CREATE ROD TOKEN"),
-            0x550E => String::from("INSUFFICIENT ZONE RESOURCES"),
-            0x550F => String::from("INSUFFICIENT ZONE RESOURCES TO COMPLETE WRITE"),
-            0x5510 => String::from("MAXIMUM NUMBER OF STREAMS OPEN"),
-            0x5511 => String::from("INSUFFICIENT RESOURCES TO BIND"),
-            0x5700 => String::from("UNABLE TO RECOVER TABLE-OF-CONTENTS"),
-            0x5800 => String::from("GENERATION DOES NOT EXIST"),
-            0x5900 => String::from("UPDATED BLOCK READ"),
-            0x5A00 => String::from("OPERATOR REQUEST OR STATE CHANGE INPUT"),
-            0x5A01 => String::from("OPERATOR MEDIUM REMOVAL REQUEST"),
-            0x5A02 => String::from("OPERATOR SELECTED WRITE PROTECT"),
-            0x5A03 => String::from("OPERATOR SELECTED WRITE PERMIT"),
-            0x5B00 => String::from("LOG EXCEPTION"),
-            0x5B01 => String::from("THRESHOLD CONDITION MET"),
-            0x5B02 => String::from("LOG COUNTER AT MAXIMUM"),
-            0x5B03 => String::from("LOG LIST CODES EXHAUSTED"),
-            0x5C00 => String::from("RPL STATUS CHANGE"),
-            0x5C01 => String::from("SPINDLES SYNCHRONIZED"),
-            0x5C02 => String::from("SPINDLES NOT SYNCHRONIZED"),
-            0x5D00 => String::from("FAILURE PREDICTION THRESHOLD EXCEEDED"),
-            0x5D01 => String::from("MEDIA FAILURE PREDICTION THRESHOLD EXCEEDED"),
-            0x5D02 => String::from("LOGICAL UNIT FAILURE PREDICTION THRESHOLD EXCEEDED"),
-            0x5D03 => String::from("SPARE AREA EXHAUSTION PREDICTION THRESHOLD EXCEEDED"),
-            0x5D10 => String::from("HARDWARE IMPENDING FAILURE GENERAL HARD DRIVE FAILURE"),
-            0x5D11 => String::from("HARDWARE IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH"),
-            0x5D12 => String::from("HARDWARE IMPENDING FAILURE DATA ERROR RATE TOO HIGH"),
-            0x5D13 => String::from("HARDWARE IMPENDING FAILURE SEEK ERROR RATE TOO HIGH"),
-            0x5D14 => String::from("HARDWARE IMPENDING FAILURE TOO MANY BLOCK REASSIGNS"),
-            0x5D15 => String::from("HARDWARE IMPENDING FAILURE ACCESS TIMES TOO HIGH"),
-            0x5D16 => String::from("HARDWARE IMPENDING FAILURE START UNIT TIMES TOO HIGH"),
-            0x5D17 => String::from("HARDWARE IMPENDING FAILURE CHANNEL PARAMETRICS"),
-            0x5D18 => String::from("HARDWARE IMPENDING FAILURE CONTROLLER DETECTED"),
-            0x5D19 => String::from("HARDWARE IMPENDING FAILURE THROUGHPUT PERFORMANCE"),
-            0x5D1A => String::from("HARDWARE IMPENDING FAILURE SEEK TIME PERFORMANCE"),
-            0x5D1B => String::from("HARDWARE IMPENDING FAILURE SPIN-UP RETRY COUNT"),
-            0x5D1C => String::from("HARDWARE IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT"),
-            0x5D1D => String::from("HARDWARE IMPENDING FAILURE POWER LOSS PROTECTION CIRCUIT"),
-            0x5D20 => String::from("CONTROLLER IMPENDING FAILURE GENERAL HARD DRIVE FAILURE"),
-            0x5D21 => String::from("CONTROLLER IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH"),
-            0x5D22 => String::from("CONTROLLER IMPENDING FAILURE DATA ERROR RATE TOO HIGH"),
-            0x5D23 => String::from("CONTROLLER IMPENDING FAILURE SEEK ERROR RATE TOO HIGH"),
-            0x5D24 => String::from("CONTROLLER IMPENDING FAILURE TOO MANY BLOCK REASSIGNS"),
-            0x5D25 => String::from("CONTROLLER IMPENDING FAILURE ACCESS TIMES TOO HIGH"),
-            0x5D26 => String::from("CONTROLLER IMPENDING FAILURE START UNIT TIMES TOO HIGH"),
-            0x5D27 => String::from("CONTROLLER IMPENDING FAILURE CHANNEL PARAMETRICS"),
-            0x5D28 => String::from("CONTROLLER IMPENDING FAILURE CONTROLLER DETECTED"),
-            0x5D29 => String::from("CONTROLLER IMPENDING FAILURE THROUGHPUT PERFORMANCE"),
-            0x5D2A => String::from("CONTROLLER IMPENDING FAILURE SEEK TIME PERFORMANCE"),
-            0x5D2B => String::from("CONTROLLER IMPENDING FAILURE SPIN-UP RETRY COUNT"),
-            0x5D2C => String::from("CONTROLLER IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT"),
-            0x5D30 => String::from("DATA CHANNEL IMPENDING FAILURE GENERAL HARD DRIVE FAILURE"),
-            0x5D31 => String::from("DATA CHANNEL IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH"),
-            0x5D32 => String::from("DATA CHANNEL IMPENDING FAILURE DATA ERROR RATE TOO HIGH"),
-            0x5D33 => String::from("DATA CHANNEL IMPENDING FAILURE SEEK ERROR RATE TOO HIGH"),
-            0x5D34 => String::from("DATA CHANNEL IMPENDING FAILURE TOO MANY BLOCK REASSIGNS"),
-            0x5D35 => String::from("DATA CHANNEL IMPENDING FAILURE ACCESS TIMES TOO HIGH"),
-            0x5D36 => String::from("DATA CHANNEL IMPENDING FAILURE START UNIT TIMES TOO HIGH"),
-            0x5D37 => String::from("DATA CHANNEL IMPENDING FAILURE CHANNEL PARAMETRICS"),
-            0x5D38 => String::from("DATA CHANNEL IMPENDING FAILURE CONTROLLER DETECTED"),
-            0x5D39 => String::from("DATA CHANNEL IMPENDING FAILURE THROUGHPUT PERFORMANCE"),
-            0x5D3A => String::from("DATA CHANNEL IMPENDING FAILURE SEEK TIME PERFORMANCE"),
-            0x5D3B => String::from("DATA CHANNEL IMPENDING FAILURE SPIN-UP RETRY COUNT"),
-            0x5D3C => String::from("DATA CHANNEL IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT"),
-            0x5D40 => String::from("SERVO IMPENDING FAILURE GENERAL HARD DRIVE FAILURE"),
-            0x5D41 => String::from("SERVO IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH"),
-            0x5D42 => String::from("SERVO IMPENDING FAILURE DATA ERROR RATE TOO HIGH"),
-            0x5D43 => String::from("SERVO IMPENDING FAILURE SEEK ERROR RATE TOO HIGH"),
-            0x5D44 => String::from("SERVO IMPENDING FAILURE TOO MANY BLOCK REASSIGNS"),
-            0x5D45 => String::from("SERVO IMPENDING FAILURE ACCESS TIMES TOO HIGH"),
-            0x5D46 => String::from("SERVO IMPENDING FAILURE START UNIT TIMES TOO HIGH"),
-            0x5D47 => String::from("SERVO IMPENDING FAILURE CHANNEL PARAMETRICS"),
-            0x5D48 => String::from("SERVO IMPENDING FAILURE CONTROLLER DETECTED"),
-            0x5D49 => String::from("SERVO IMPENDING FAILURE THROUGHPUT PERFORMANCE"),
-            0x5D4A => String::from("SERVO IMPENDING FAILURE SEEK TIME PERFORMANCE"),
-            0x5D4B => String::from("SERVO IMPENDING FAILURE SPIN-UP RETRY COUNT"),
-            0x5D4C => String::from("SERVO IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT"),
-            0x5D50 => String::from("SPINDLE IMPENDING FAILURE GENERAL HARD DRIVE FAILURE"),
-            0x5D51 => String::from("SPINDLE IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH"),
-            0x5D52 => String::from("SPINDLE IMPENDING FAILURE DATA ERROR RATE TOO HIGH"),
-            0x5D53 => String::from("SPINDLE IMPENDING FAILURE SEEK ERROR RATE TOO HIGH"),
-            0x5D54 => String::from("SPINDLE IMPENDING FAILURE TOO MANY BLOCK REASSIGNS"),
-            0x5D55 => String::from("SPINDLE IMPENDING FAILURE ACCESS TIMES TOO HIGH"),
-            0x5D56 => String::from("SPINDLE IMPENDING FAILURE START UNIT TIMES TOO HIGH"),
-            0x5D57 => String::from("SPINDLE IMPENDING FAILURE CHANNEL PARAMETRICS"),
-            0x5D58 => String::from("SPINDLE IMPENDING FAILURE CONTROLLER DETECTED"),
-            0x5D59 => String::from("SPINDLE IMPENDING FAILURE THROUGHPUT PERFORMANCE"),
-            0x5D5A => String::from("SPINDLE IMPENDING FAILURE SEEK TIME PERFORMANCE"),
-            0x5D5B => String::from("SPINDLE IMPENDING FAILURE SPIN-UP RETRY COUNT"),
-            0x5D5C => String::from("SPINDLE IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT"),
-            0x5D60 => String::from("FIRMWARE IMPENDING FAILURE GENERAL HARD DRIVE FAILURE"),
-            0x5D61 => String::from("FIRMWARE IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH"),
-            0x5D62 => String::from("FIRMWARE IMPENDING FAILURE DATA ERROR RATE TOO HIGH"),
-            0x5D63 => String::from("FIRMWARE IMPENDING FAILURE SEEK ERROR RATE TOO HIGH"),
-            0x5D64 => String::from("FIRMWARE IMPENDING FAILURE TOO MANY BLOCK REASSIGNS"),
-            0x5D65 => String::from("FIRMWARE IMPENDING FAILURE ACCESS TIMES TOO HIGH"),
-            0x5D66 => String::from("FIRMWARE IMPENDING FAILURE START UNIT TIMES TOO HIGH"),
-            0x5D67 => String::from("FIRMWARE IMPENDING FAILURE CHANNEL PARAMETRICS"),
-            0x5D68 => String::from("FIRMWARE IMPENDING FAILURE CONTROLLER DETECTED"),
-            0x5D69 => String::from("FIRMWARE IMPENDING FAILURE THROUGHPUT PERFORMANCE"),
-            0x5D6A => String::from("FIRMWARE IMPENDING FAILURE SEEK TIME PERFORMANCE"),
-            0x5D6B => String::from("FIRMWARE IMPENDING FAILURE SPIN-UP RETRY COUNT"),
-            0x5D6C => String::from("FIRMWARE IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT"),
-            0x5D73 => String::from("MEDIA IMPENDING FAILURE ENDURANCE LIMIT MET"),
-            0x5DFF => String::from("FAILURE PREDICTION THRESHOLD EXCEEDED (FALSE)"),
-            0x5E00 => String::from("LOW POWER CONDITION ON"),
-            0x5E01 => String::from("IDLE CONDITION ACTIVATED BY TIMER"),
-            0x5E02 => String::from("STANDBY CONDITION ACTIVATED BY TIMER"),
-            0x5E03 => String::from("IDLE CONDITION ACTIVATED BY COMMAND"),
-            0x5E04 => String::from("STANDBY CONDITION ACTIVATED BY COMMAND"),
-            0x5E05 => String::from("IDLE_B CONDITION ACTIVATED BY TIMER"),
-            0x5E06 => String::from("IDLE_B CONDITION ACTIVATED BY COMMAND"),
-            0x5E07 => String::from("IDLE_C CONDITION ACTIVATED BY TIMER"),
-            0x5E08 => String::from("IDLE_C CONDITION ACTIVATED BY COMMAND"),
-            0x5E09 => String::from("STANDBY_Y CONDITION ACTIVATED BY TIMER"),
-            0x5E0A => String::from("STANDBY_Y CONDITION ACTIVATED BY COMMAND"),
-            0x5E41 => String::from("POWER STATE CHANGE TO ACTIVE"),
-            0x5E42 => String::from("POWER STATE CHANGE TO IDLE"),
-            0x5E43 => String::from("POWER STATE CHANGE TO STANDBY"),
-            0x5E45 => String::from("POWER STATE CHANGE TO SLEEP"),
-            0x5E47 => String::from("POWER STATE CHANGE TO DEVICE CONTROL"),
-            0x6000 => String::from("LAMP FAILURE"),
-            0x6100 => String::from("VIDEO ACQUISITION ERROR"),
-            0x6101 => String::from("UNABLE TO ACQUIRE VIDEO"),
-            0x6102 => String::from("OUT OF FOCUS"),
-            0x6200 => String::from("SCAN HEAD POSITIONING ERROR"),
-            0x6300 => String::from("END OF USER AREA ENCOUNTERED ON THIS TRACK"),
-            0x6301 => String::from("PACKET DOES NOT FIT IN AVAILABLE SPACE"),
-            0x6400 => String::from("ILLEGAL MODE FOR THIS TRACK"),
-            0x6401 => String::from("INVALID PACKET SIZE"),
-            0x6500 => String::from("VOLTAGE FAULT"),
-            0x6600 => String::from("AUTOMATIC DOCUMENT FEEDER COVER UP"),
-            0x6601 => String::from("AUTOMATIC DOCUMENT FEEDER LIFT UP"),
-            0x6602 => String::from("DOCUMENT JAM IN AUTOMATIC DOCUMENT FEEDER"),
-            0x6603 => String::from("DOCUMENT MISS FEED AUTOMATIC IN DOCUMENT FEEDER"),
-            0x6700 => String::from("CONFIGURATION FAILURE"),
-            0x6701 => String::from("CONFIGURATION OF INCAPABLE LOGICAL UNITS FAILED"),
-            0x6702 => String::from("ADD LOGICAL UNIT FAILED"),
-            0x6703 => String::from("MODIFICATION OF LOGICAL UNIT FAILED"),
-            0x6704 => String::from("EXCHANGE OF LOGICAL UNIT FAILED"),
-            0x6705 => String::from("REMOVE OF LOGICAL UNIT FAILED"),
-            0x6706 => String::from("ATTACHMENT OF LOGICAL UNIT FAILED"),
-            0x6707 => String::from("CREATION OF LOGICAL UNIT FAILED"),
-            0x6708 => String::from("ASSIGN FAILURE OCCURRED"),
-            0x6709 => String::from("MULTIPLY ASSIGNED LOGICAL UNIT"),
-            0x670A => String::from("SET TARGET PORT GROUPS COMMAND FAILED"),
-            0x670B => String::from("ATA DEVICE FEATURE NOT ENABLED"),
-            0x670C => String::from("COMMAND REJECTED"),
-            0x670D => String::from("EXPLICIT BIND NOT ALLOWED"),
-            0x6800 => String::from("LOGICAL UNIT NOT CONFIGURED"),
-            0x6801 => String::from("SUBSIDIARY LOGICAL UNIT NOT CONFIGURED"),
-            0x6900 => String::from("DATA LOSS ON LOGICAL UNIT"),
-            0x6901 => String::from("MULTIPLE LOGICAL UNIT FAILURES"),
-            0x6902 => String::from("PARITY/DATA MISMATCH"),
-            0x6A00 => String::from("INFORMATIONAL, REFER TO LOG"),
-            0x6B00 => String::from("STATE CHANGE HAS OCCURRED"),
-            0x6B01 => String::from("REDUNDANCY LEVEL GOT BETTER"),
-            0x6B02 => String::from("REDUNDANCY LEVEL GOT WORSE"),
-            0x6C00 => String::from("REBUILD FAILURE OCCURRED"),
-            0x6D00 => String::from("RECALCULATE FAILURE OCCURRED"),
-            0x6E00 => String::from("COMMAND TO LOGICAL UNIT FAILED"),
-            0x6F00 => String::from("COPY PROTECTION KEY EXCHANGE FAILURE - AUTHENTICATION FAILURE"),
-            0x6F01 => String::from("COPY PROTECTION KEY EXCHANGE FAILURE - KEY NOT PRESENT"),
-            0x6F02 => String::from("COPY PROTECTION KEY EXCHANGE FAILURE - KEY NOT ESTABLISHED"),
-            0x6F03 => String::from("READ OF SCRAMBLED SECTOR WITHOUT AUTHENTICATION"),
-            0x6F04 => String::from("MEDIA REGION CODE IS MISMATCHED TO LOGICAL UNIT REGION"),
-            0x6F05 => String::from("DRIVE REGION MUST BE PERMANENT/REGION RESET COUNT ERROR"),
-            0x6F06 => String::from("INSUFFICIENT BLOCK COUNT FOR BINDING NONCE RECORDING"),
-            0x6F07 => String::from("CONFLICT IN BINDING NONCE RECORDING"),
-            0x6F08 => String::from("INSUFFICIENT PERMISSION"),
-            0x6F09 => String::from("INVALID DRIVE-HOST PAIRING SERVER"),
-            0x6F0A => String::from("DRIVE-HOST PAIRING SUSPENDED"),
-            0x7100 => String::from("DECOMPRESSION EXCEPTION LONG ALGORITHM ID"),
-            0x7200 => String::from("SESSION FIXATION ERROR"),
-            0x7201 => String::from("SESSION FIXATION ERROR WRITING LEAD-IN"),
-            0x7202 => String::from("SESSION FIXATION ERROR WRITING LEAD-OUT"),
-            0x7203 => String::from("SESSION FIXATION ERROR - INCOMPLETE TRACK IN SESSION"),
-            0x7204 => String::from("EMPTY OR PARTIALLY WRITTEN RESERVED TRACK"),
-            0x7205 => String::from("NO MORE TRACK RESERVATIONS ALLOWED"),
-            0x7206 => String::from("RMZ EXTENSION IS NOT ALLOWED"),
-            0x7207 => String::from("NO MORE TEST ZONE EXTENSIONS ARE ALLOWED"),
-            0x7300 => String::from("CD CONTROL ERROR"),
-            0x7301 => String::from("POWER CALIBRATION AREA ALMOST FULL"),
-            0x7302 => String::from("POWER CALIBRATION AREA IS FULL"),
-            0x7303 => String::from("POWER CALIBRATION AREA ERROR"),
-            0x7304 => String::from("PROGRAM MEMORY AREA UPDATE FAILURE"),
-            0x7305 => String::from("PROGRAM MEMORY AREA IS FULL"),
-            0x7306 => String::from("RMA/PMA IS ALMOST FULL"),
-            0x7310 => String::from("CURRENT POWER CALIBRATION AREA ALMOST FULL"),
-            0x7311 => String::from("CURRENT POWER CALIBRATION AREA IS FULL"),
-            0x7317 => String::from("RDZ IS FULL"),
-            0x7400 => String::from("SECURITY ERROR"),
-            0x7401 => String::from("UNABLE TO DECRYPT DATA"),
-            0x7402 => String::from("UNENCRYPTED DATA ENCOUNTERED WHILE DECRYPTING"),
-            0x7403 => String::from("INCORRECT DATA ENCRYPTION KEY"),
-            0x7404 => String::from("CRYPTOGRAPHIC INTEGRITY VALIDATION FAILED"),
-            0x7405 => String::from("ERROR DECRYPTING DATA"),
-            0x7406 => String::from("UNKNOWN SIGNATURE VERIFICATION KEY"),
-            0x7407 => String::from("ENCRYPTION PARAMETERS NOT USEABLE"),
-            0x7408 => String::from("DIGITAL SIGNATURE VALIDATION FAILURE"),
-            0x7409 => String::from("ENCRYPTION MODE MISMATCH ON READ"),
-            0x740A => String::from("ENCRYPTED BLOCK NOT RAW READ ENABLED"),
-            0x740B => String::from("INCORRECT ENCRYPTION PARAMETERS"),
-            0x740C => String::from("UNABLE TO DECRYPT PARAMETER LIST"),
-            0x740D => String::from("ENCRYPTION ALGORITHM DISABLED"),
-            0x7410 => String::from("SA CREATION PARAMETER VALUE INVALID"),
-            0x7411 => String::from("SA CREATION PARAMETER VALUE REJECTED"),
-            0x7412 => String::from("INVALID SA USAGE"),
-            0x7421 => String::from("DATA ENCRYPTION CONFIGURATION PREVENTED"),
-            0x7430 => String::from("SA CREATION PARAMETER NOT SUPPORTED"),
-            0x7440 => String::from("AUTHENTICATION FAILED"),
-            0x7461 => String::from("EXTERNAL DATA ENCRYPTION KEY MANAGER ACCESS ERROR"),
-            0x7462 => String::from("EXTERNAL DATA ENCRYPTION KEY MANAGER ERROR"),
-            0x7463 => String::from("EXTERNAL DATA ENCRYPTION KEY NOT FOUND"),
-            0x7464 => String::from("EXTERNAL DATA ENCRYPTION REQUEST NOT AUTHORIZED"),
-            0x746E => String::from("EXTERNAL DATA ENCRYPTION CONTROL TIMEOUT"),
-            0x746F => String::from("EXTERNAL DATA ENCRYPTION CONTROL ERROR"),
-            0x7471 => String::from("LOGICAL UNIT ACCESS NOT AUTHORIZED"),
-            0x7479 => String::from("SECURITY CONFLICT IN TRANSLATED DEVICE"),
-            other => {
-                let [asc, ascq] = other.to_be_bytes();
-                match asc {
-                    0x40 => format!("DIAGNOSTIC FAILURE ON COMPONENT 0x{:X}", ascq),
-                    0x4D => format!("TAGGED OVERLAPPED COMMANDS ON TASK TAG 0x{:X}", ascq),
-                    0x70 => format!("DECOMPRESSION EXCEPTION SHORT ALGORITHM ID OF 0x{:X}", ascq),
-                    _ => format!("Unknown Code 0x{:X}", other),
-                }
-            }
-        };
-        write!(f, "({:X}) {}", self.0, &description)
+        write!(f, "({:X}) {}", self.0, self.description())
     }
 }
 
+impl AdditionalSenseCode {
+    pub fn description(&self) -> String {
+        let [asc, ascq] = self.0.to_be_bytes();
+        if let Some(description) = asc_ascq_description(asc, ascq) {
+            return description.to_owned();
+        }
+
+        match asc {
+            0x40 => format!("DIAGNOSTIC FAILURE ON COMPONENT 0x{:X}", ascq),
+            0x4D => format!("TAGGED OVERLAPPED COMMANDS ON TASK TAG 0x{:X}", ascq),
+            0x70 => format!("DECOMPRESSION EXCEPTION SHORT ALGORITHM ID OF 0x{:X}", ascq),
+            _ => format!("Unknown Code 0x{:X}", self.0),
+        }
+    }
+}
+
+/// The description of an ASC and ASCQ pair in the T10 table, None for the vendor specific
+/// and unassigned codes, and for the ranges whose ASCQ is a parameter, like ASC 0x40.
+pub fn asc_ascq_description(asc: u8, ascq: u8) -> Option<&'static str> {
+    let description = match u16::from_be_bytes([asc, ascq]) {
+        0x0000 => "NO ADDITIONAL SENSE INFORMATION",
+        0x0001 => "FILEMARK DETECTED",
+        0x0002 => "END-OF-PARTITION/MEDIUM DETECTED",
+        0x0003 => "SETMARK DETECTED",
+        0x0004 => "BEGINNING-OF-PARTITION/MEDIUM DETECTED",
+        0x0005 => "END-OF-DATA DETECTED",
+        0x0006 => "I/O PROCESS TERMINATED",
+        0x0007 => "PROGRAMMABLE EARLY WARNING DETECTED",
+        0x0011 => "AUDIO PLAY OPERATION IN PROGRESS",
+        0x0012 => "AUDIO PLAY OPERATION PAUSED",
+        0x0013 => "AUDIO PLAY OPERATION SUCCESSFULLY COMPLETED",
+        0x0014 => "AUDIO PLAY OPERATION STOPPED DUE TO ERROR",
+        0x0015 => "NO CURRENT AUDIO STATUS TO RETURN",
+        0x0016 => "OPERATION IN PROGRESS",
+        0x0017 => "CLEANING REQUESTED",
+        0x0018 => "ERASE OPERATION IN PROGRESS",
+        0x0019 => "LOCATE OPERATION IN PROGRESS",
+        0x001A => "REWIND OPERATION IN PROGRESS",
+        0x001B => "SET CAPACITY OPERATION IN PROGRESS",
+        0x001C => "VERIFY OPERATION IN PROGRESS",
+        0x001D => "ATA PASS THROUGH INFORMATION AVAILABLE",
+        0x001E => "CONFLICTING SA CREATION REQUEST",
+        0x001F => "LOGICAL UNIT TRANSITIONING TO ANOTHER POWER CONDITION",
+        0x0020 => "EXTENDED COPY INFORMATION AVAILABLE",
+        0x0021 => "ATOMIC COMMAND ABORTED DUE TO ACA",
+        0x0022 => "DEFERRED MICROCODE IS PENDING",
+        0x0100 => "NO INDEX/SECTOR SIGNAL",
+        0x0200 => "NO SEEK COMPLETE",
+        0x0300 => "PERIPHERAL DEVICE WRITE FAULT",
+        0x0301 => "NO WRITE CURRENT",
+        0x0302 => "EXCESSIVE WRITE ERRORS",
+        0x0400 => "LOGICAL UNIT NOT READY, CAUSE NOT REPORTABLE",
+        0x0401 => "LOGICAL UNIT IS IN PROCESS OF BECOMING READY",
+        0x0402 => "LOGICAL UNIT NOT READY, INITIALIZING COMMAND REQUIRED",
+        0x0403 => "LOGICAL UNIT NOT READY, MANUAL INTERVENTION REQUIRED",
+        0x0404 => "LOGICAL UNIT NOT READY, FORMAT IN PROGRESS",
+        0x0405 => "LOGICAL UNIT NOT READY, REBUILD IN PROGRESS",
+        0x0406 => "LOGICAL UNIT NOT READY, RECALCULATION IN PROGRESS",
+        0x0407 => "LOGICAL UNIT NOT READY, OPERATION IN PROGRESS",
+        0x0408 => "LOGICAL UNIT NOT READY, LONG WRITE IN PROGRESS",
+        0x0409 => "LOGICAL UNIT NOT READY, SELF-TEST IN PROGRESS",
+        0x040B => "LOGICAL UNIT NOT ACCESSIBLE, TARGET PORT IN STANDBY STATE",
+        0x040C => "LOGICAL UNIT NOT ACCESSIBLE, TARGET PORT IN UNAVAILABLE STATE",
+        0x040D => "LOGICAL UNIT NOT READY, STRUCTURE CHECK REQUIRED",
+        0x040E => "LOGICAL UNIT NOT READY, SECURITY SESSION IN PROGRESS",
+        0x0410 => "LOGICAL UNIT NOT READY, AUXILIARY MEMORY NOT ACCESSIBLE",
+        0x0411 => "LOGICAL UNIT NOT READY, NOTIFY (ENABLE SPINUP) REQUIRED",
+        0x0412 => "LOGICAL UNIT NOT READY, OFFLINE",
+        0x0413 => "LOGICAL UNIT NOT READY, SA CREATION IN PROGRESS",
+        0x0414 => "LOGICAL UNIT NOT READY, SPACE ALLOCATION IN PROGRESS",
+        0x0415 => "LOGICAL UNIT NOT READY, ROBOTICS DISABLED",
+        0x0416 => "LOGICAL UNIT NOT READY, CONFIGURATION REQUIRED",
+        0x0417 => "LOGICAL UNIT NOT READY, CALIBRATION REQUIRED",
+        0x0418 => "LOGICAL UNIT NOT READY, A DOOR IS OPEN",
+        0x0419 => "LOGICAL UNIT NOT READY, OPERATING IN SEQUENTIAL MODE",
+        0x041A => "LOGICAL UNIT NOT READY, START STOP UNIT COMMAND IN PROGRESS",
+        0x041B => "LOGICAL UNIT NOT READY, SANITIZE IN PROGRESS",
+        0x041C => "LOGICAL UNIT NOT READY, ADDITIONAL POWER USE NOT YET GRANTED",
+        0x041D => "LOGICAL UNIT NOT READY, CONFIGURATION IN PROGRESS",
+        0x041E => "LOGICAL UNIT NOT READY, MICROCODE ACTIVATION REQUIRED",
+        0x041F => "LOGICAL UNIT NOT READY, MICROCODE DOWNLOAD REQUIRED",
+        0x0420 => "LOGICAL UNIT NOT READY, LOGICAL UNIT RESET REQUIRED",
+        0x0421 => "LOGICAL UNIT NOT READY, HARD RESET REQUIRED",
+        0x0422 => "LOGICAL UNIT NOT READY, POWER CYCLE REQUIRED",
+        0x0423 => "LOGICAL UNIT NOT READY, AFFILIATION REQUIRED",
+        0x0424 => "DEPOPULATION IN PROGRESS",
+        0x0425 => "DEPOPULATION RESTORATION IN PROGRESS",
+        0x0500 => "LOGICAL UNIT DOES NOT RESPOND TO SELECTION",
+        0x0600 => "NO REFERENCE POSITION FOUND",
+        0x0700 => "MULTIPLE PERIPHERAL DEVICES SELECTED",
+        0x0800 => "LOGICAL UNIT COMMUNICATION FAILURE",
+        0x0801 => "LOGICAL UNIT COMMUNICATION TIME-OUT",
+        0x0802 => "LOGICAL UNIT COMMUNICATION PARITY ERROR",
+        0x0803 => "LOGICAL UNIT COMMUNICATION CRC ERROR (ULTRA-DMA/32)",
+        0x0804 => "UNREACHABLE COPY TARGET",
+        0x0900 => "TRACK FOLLOWING ERROR",
+        0x0901 => "TRACKING SERVO FAILURE",
+        0x0902 => "FOCUS SERVO FAILURE",
+        0x0903 => "SPINDLE SERVO FAILURE",
+        0x0904 => "HEAD SELECT FAULT",
+        0x0905 => "VIBRATION INDUCED TRACKING ERROR",
+        0x0A00 => "ERROR LOG OVERFLOW",
+        0x0B00 => "WARNING",
+        0x0B01 => "WARNING - SPECIFIED TEMPERATURE EXCEEDED",
+        0x0B02 => "WARNING - ENCLOSURE DEGRADED",
+        0x0B03 => "WARNING - BACKGROUND SELF-TEST FAILED",
+        0x0B04 => "WARNING - BACKGROUND PRE-SCAN DETECTED MEDIUM ERROR",
+        0x0B05 => "WARNING - BACKGROUND MEDIUM SCAN DETECTED MEDIUM ERROR",
+        0x0B06 => "WARNING - NON-VOLATILE CACHE NOW VOLATILE",
+        0x0B07 => "WARNING - DEGRADED POWER TO NON-VOLATILE CACHE",
+        0x0B08 => "WARNING - POWER LOSS EXPECTED",
+        0x0B09 => "WARNING - DEVICE STATISTICS NOTIFICATION ACTIVE",
+        0x0B0A => "WARNING - HIGH CRITICAL TEMPERATURE LIMIT EXCEEDED",
+        0x0B0B => "WARNING - LOW CRITICAL TEMPERATURE LIMIT EXCEEDED",
+        0x0B0C => "WARNING - HIGH OPERATING TEMPERATURE LIMIT EXCEEDED",
+        0x0B0D => "WARNING - LOW OPERATING TEMPERATURE LIMIT EXCEEDED",
+        0x0B0E => "WARNING - HIGH CRITICAL HUMIDITY LIMIT EXCEEDED",
+        0x0B0F => "WARNING - LOW CRITICAL HUMIDITY LIMIT EXCEEDED",
+        0x0B10 => "WARNING - HIGH OPERATING HUMIDITY LIMIT EXCEEDED",
+        0x0B11 => "WARNING - LOW OPERATING HUMIDITY LIMIT EXCEEDED",
+        0x0B12 => "WARNING - MICROCODE SECURITY AT RISK",
+        0x0B13 => "WARNING - MICROCODE DIGITAL SIGNATURE VALIDATION FAILURE",
+        0x0B14 => "WARNING - PHYSICAL ELEMENT STATUS CHANGE",
+        0x0C00 => "WRITE ERROR",
+        0x0C01 => "WRITE ERROR - RECOVERED WITH AUTO REALLOCATION",
+        0x0C02 => "WRITE ERROR - AUTO REALLOCATION FAILED",
+        0x0C03 => "WRITE ERROR - RECOMMEND REASSIGNMENT",
+        0x0C04 => "COMPRESSION CHECK MISCOMPARE ERROR",
+        0x0C05 => "DATA EXPANSION OCCURRED DURING COMPRESSION",
+        0x0C06 => "BLOCK NOT COMPRESSIBLE",
+        0x0C07 => "WRITE ERROR - RECOVERY NEEDED",
+        0x0C08 => "WRITE ERROR - RECOVERY FAILED",
+        0x0C09 => "WRITE ERROR - LOSS OF STREAMING",
+        0x0C0A => "WRITE ERROR - PADDING BLOCKS ADDED",
+        0x0C0B => "AUXILIARY MEMORY WRITE ERROR",
+        0x0C0C => "WRITE ERROR - UNEXPECTED UNSOLICITED DATA",
+        0x0C0D => "WRITE ERROR - NOT ENOUGH UNSOLICITED DATA",
+        0x0C0E => "MULTIPLE WRITE ERRORS",
+        0x0C0F => "DEFECTS IN ERROR WINDOW",
+        0x0C10 => "INCOMPLETE MULTIPLE ATOMIC WRITE OPERATIONS",
+        0x0C11 => "WRITE ERROR - RECOVERY SCAN NEEDED",
+        0x0C12 => "WRITE ERROR - INSUFFICIENT ZONE RESOURCES",
+        0x0D00 => "ERROR DETECTED BY THIRD PARTY TEMPORARY INITIATOR",
+        0x0D01 => "THIRD PARTY DEVICE FAILURE",
+        0x0D02 => "COPY TARGET DEVICE NOT REACHABLE",
+        0x0D03 => "INCORRECT COPY TARGET DEVICE TYPE",
+        0x0D04 => "COPY TARGET DEVICE DATA UNDERRUN",
+        0x0D05 => "COPY TARGET DEVICE DATA OVERRUN",
+        0x0E00 => "INVALID INFORMATION UNIT",
+        0x0E01 => "INFORMATION UNIT TOO SHORT",
+        0x0E02 => "INFORMATION UNIT TOO LONG",
+        0x0E03 => "INVALID FIELD IN COMMAND INFORMATION UNIT",
+        0x1000 => "ID CRC OR ECC ERROR",
+        0x1001 => "LOGICAL BLOCK GUARD CHECK FAILED",
+        0x1002 => "LOGICAL BLOCK APPLICATION TAG CHECK FAILED",
+        0x1003 => "LOGICAL BLOCK REFERENCE TAG CHECK FAILED",
+        0x1004 => "LOGICAL BLOCK PROTECTION ERROR ON RECOVER BUFFERED DATA",
+        0x1005 => "LOGICAL BLOCK PROTECTION METHOD ERROR",
+        0x1100 => "UNRECOVERED READ ERROR",
+        0x1101 => "READ RETRIES EXHAUSTED",
+        0x1102 => "ERROR TOO LONG TO CORRECT",
+        0x1103 => "MULTIPLE READ ERRORS",
+        0x1104 => "UNRECOVERED READ ERROR - AUTO REALLOCATE FAILED",
+        0x1105 => "L-EC UNCORRECTABLE ERROR",
+        0x1106 => "CIRC UNRECOVERED ERROR",
+        0x1107 => "DATA RE-SYNCHRONIZATION ERROR",
+        0x1108 => "INCOMPLETE BLOCK READ",
+        0x1109 => "NO GAP FOUND",
+        0x110A => "MISCORRECTED ERROR",
+        0x110B => "UNRECOVERED READ ERROR - RECOMMEND REASSIGNMENT",
+        0x110C => "UNRECOVERED READ ERROR - RECOMMEND REWRITE THE DATA",
+        0x110D => "DE-COMPRESSION CRC ERROR",
+        0x110E => "CANNOT DECOMPRESS USING DECLARED ALGORITHM",
+        0x110F => "ERROR READING UPC/EAN NUMBER",
+        0x1110 => "ERROR READING ISRC NUMBER",
+        0x1111 => "READ ERROR - LOSS OF STREAMING",
+        0x1112 => "AUXILIARY MEMORY READ ERROR",
+        0x1113 => "READ ERROR - FAILED RETRANSMISSION REQUEST",
+        0x1114 => "READ ERROR - LBA MARKED BAD BY APPLICATION CLIENT",
+        0x1115 => "WRITE AFTER SANITIZE REQUIRED",
+        0x1200 => "ADDRESS MARK NOT FOUND FOR ID FIELD",
+        0x1300 => "ADDRESS MARK NOT FOUND FOR DATA FIELD",
+        0x1400 => "RECORDED ENTITY NOT FOUND",
+        0x1401 => "RECORD NOT FOUND",
+        0x1402 => "FILEMARK OR SETMARK NOT FOUND",
+        0x1403 => "END-OF-DATA NOT FOUND",
+        0x1404 => "BLOCK SEQUENCE ERROR",
+        0x1405 => "RECORD NOT FOUND - RECOMMEND REASSIGNMENT",
+        0x1406 => "RECORD NOT FOUND - DATA AUTO-REALLOCATED",
+        0x1407 => "LOCATE OPERATION FAILURE",
+        0x1500 => "RANDOM POSITIONING ERROR",
+        0x1501 => "MECHANICAL POSITIONING ERROR",
+        0x1502 => "POSITIONING ERROR DETECTED BY READ OF MEDIUM",
+        0x1600 => "DATA SYNCHRONIZATION MARK ERROR",
+        0x1601 => "DATA SYNC ERROR - DATA REWRITTEN",
+        0x1602 => "DATA SYNC ERROR - RECOMMEND REWRITE",
+        0x1603 => "DATA SYNC ERROR - DATA AUTO-REALLOCATED",
+        0x1604 => "DATA SYNC ERROR - RECOMMEND REASSIGNMENT",
+        0x1700 => "RECOVERED DATA WITH NO ERROR CORRECTION APPLIED",
+        0x1701 => "RECOVERED DATA WITH RETRIES",
+        0x1702 => "RECOVERED DATA WITH POSITIVE HEAD OFFSET",
+        0x1703 => "RECOVERED DATA WITH NEGATIVE HEAD OFFSET",
+        0x1704 => "RECOVERED DATA WITH RETRIES AND/OR CIRC APPLIED",
+        0x1705 => "RECOVERED DATA USING PREVIOUS SECTOR ID",
+        0x1706 => "RECOVERED DATA WITHOUT ECC - DATA AUTO-REALLOCATED",
+        0x1707 => "RECOVERED DATA WITHOUT ECC - RECOMMEND REASSIGNMENT",
+        0x1708 => "RECOVERED DATA WITHOUT ECC - RECOMMEND REWRITE",
+        0x1709 => "RECOVERED DATA WITHOUT ECC - DATA REWRITTEN",
+        0x1800 => "RECOVERED DATA WITH ERROR CORRECTION APPLIED",
+        0x1801 => "RECOVERED DATA WITH ERROR CORR. & RETRIES APPLIED",
+        0x1802 => "RECOVERED DATA - DATA AUTO-REALLOCATED",
+        0x1803 => "RECOVERED DATA WITH CIRC",
+        0x1804 => "RECOVERED DATA WITH L-EC",
+        0x1805 => "RECOVERED DATA - RECOMMEND REASSIGNMENT",
+        0x1806 => "RECOVERED DATA - RECOMMEND REWRITE",
+        0x1807 => "RECOVERED DATA WITH ECC - DATA REWRITTEN",
+        0x1808 => "RECOVERED DATA WITH LINKING",
+        0x1900 => "DEFECT LIST ERROR",
+        0x1901 => "DEFECT LIST NOT AVAILABLE",
+        0x1902 => "DEFECT LIST ERROR IN PRIMARY LIST",
+        0x1903 => "DEFECT LIST ERROR IN GROWN LIST",
+        0x1A00 => "PARAMETER LIST LENGTH ERROR",
+        0x1B00 => "SYNCHRONOUS DATA TRANSFER ERROR",
+        0x1C00 => "DEFECT LIST NOT FOUND",
+        0x1C01 => "PRIMARY DEFECT LIST NOT FOUND",
+        0x1C02 => "GROWN DEFECT LIST NOT FOUND",
+        0x1D00 => "MISCOMPARE DURING VERIFY OPERATION",
+        0x1D01 => "MISCOMPARE VERIFY OF UNMAPPED LBA",
+        0x1E00 => "RECOVERED ID WITH ECC CORRECTION",
+        0x1F00 => "PARTIAL DEFECT LIST TRANSFER",
+        0x2000 => "INVALID COMMAND OPERATION CODE",
+        0x2001 => "ACCESS DENIED - INITIATOR PENDING-ENROLLED",
+        0x2002 => "ACCESS DENIED - NO ACCESS RIGHTS",
+        0x2003 => "ACCESS DENIED - INVALID MGMT ID KEY",
+        0x2004 => "ILLEGAL COMMAND WHILE IN WRITE CAPABLE STATE",
+        0x2005 => "Obsolete",
+        0x2006 => "ILLEGAL COMMAND WHILE IN EXPLICIT ADDRESS MODE",
+        0x2007 => "ILLEGAL COMMAND WHILE IN IMPLICIT ADDRESS MODE",
+        0x2008 => "ACCESS DENIED - ENROLLMENT CONFLICT",
+        0x2009 => "ACCESS DENIED - INVALID LU IDENTIFIER",
+        0x200A => "ACCESS DENIED - INVALID PROXY TOKEN",
+        0x200B => "ACCESS DENIED - ACL LUN CONFLICT",
+        0x200C => "ILLEGAL COMMAND WHEN NOT IN APPEND-ONLY MODE",
+        0x200D => "NOT AN ADMINISTRATIVE LOGICAL UNIT",
+        0x200E => "NOT A SUBSIDIARY LOGICAL UNIT",
+        0x200F => "NOT A CONGLOMERATE LOGICAL UNIT",
+        0x2100 => "LOGICAL BLOCK ADDRESS OUT OF RANGE",
+        0x2101 => "INVALID ELEMENT ADDRESS",
+        0x2102 => "INVALID ADDRESS FOR WRITE",
+        0x2103 => "INVALID WRITE CROSSING LAYER JUMP",
+        0x2104 => "UNALIGNED WRITE COMMAND",
+        0x2105 => "WRITE BOUNDARY VIOLATION",
+        0x2106 => "ATTEMPT TO READ INVALID DATA",
+        0x2107 => "READ BOUNDARY VIOLATION",
+        0x2108 => "MISALIGNED WRITE COMMAND",
+        0x2109 => "ATTEMPT TO ACCESS GAP ZONE",
+        0x2200 => "ILLEGAL FUNCTION (USE 20 00, 24 00, OR 26 00)",
+        0x2300 => "INVALID TOKEN OPERATION, CAUSE NOT REPORTABLE",
+        0x2301 => "INVALID TOKEN OPERATION, UNSUPPORTED TOKEN TYPE",
+        0x2302 => "INVALID TOKEN OPERATION, REMOTE TOKEN USAGE NOT SUPPORTED",
+        0x2304 => "INVALID TOKEN OPERATION, TOKEN UNKNOWN",
+        0x2305 => "INVALID TOKEN OPERATION, TOKEN CORRUPT",
+        0x2306 => "INVALID TOKEN OPERATION, TOKEN REVOKED",
+        0x2307 => "INVALID TOKEN OPERATION, TOKEN EXPIRED",
+        0x2308 => "INVALID TOKEN OPERATION, TOKEN CANCELLED",
+        0x2309 => "INVALID TOKEN OPERATION, TOKEN DELETED",
+        0x230A => "INVALID TOKEN OPERATION, INVALID TOKEN LENGTH",
+        0x2400 => "INVALID FIELD IN CDB",
+        0x2401 => "CDB DECRYPTION ERROR",
+        0x2402 => "Obsolete",
+        0x2403 => "Obsolete",
+        0x2404 => "SECURITY AUDIT VALUE FROZEN",
+        0x2405 => "SECURITY WORKING KEY FROZEN",
+        0x2406 => "NONCE NOT UNIQUE",
+        0x2407 => "NONCE TIMESTAMP OUT OF RANGE",
+        0x2408 => "INVALID XCDB",
+        0x2409 => "INVALID FAST FORMAT",
+        0x2500 => "LOGICAL UNIT NOT SUPPORTED",
+        0x2600 => "INVALID FIELD IN PARAMETER LIST",
+        0x2601 => "PARAMETER NOT SUPPORTED",
+        0x2602 => "PARAMETER VALUE INVALID",
+        0x2603 => "THRESHOLD PARAMETERS NOT SUPPORTED",
+        0x2604 => "INVALID RELEASE OF PERSISTENT RESERVATION",
+        0x2605 => "DATA DECRYPTION ERROR",
+        0x2606 => "TOO MANY TARGET DESCRIPTORS",
+        0x2607 => "UNSUPPORTED TARGET DESCRIPTOR TYPE CODE",
+        0x2608 => "TOO MANY SEGMENT DESCRIPTORS",
+        0x2609 => "UNSUPPORTED SEGMENT DESCRIPTOR TYPE CODE",
+        0x260A => "UNEXPECTED INEXACT SEGMENT",
+        0x260B => "INLINE DATA LENGTH EXCEEDED",
+        0x260C => "INVALID OPERATION FOR COPY SOURCE OR DESTINATION",
+        0x260D => "COPY SEGMENT GRANULARITY VIOLATION",
+        0x260E => "INVALID PARAMETER WHILE PORT IS ENABLED",
+        0x260F => "INVALID DATA-OUT BUFFER INTEGRITY CHECK VALUE",
+        0x2610 => "DATA DECRYPTION KEY FAIL LIMIT REACHED",
+        0x2611 => "INCOMPLETE KEY-ASSOCIATED DATA SET",
+        0x2612 => "VENDOR SPECIFIC KEY REFERENCE NOT FOUND",
+        0x2613 => "APPLICATION TAG MODE PAGE IS INVALID",
+        0x2614 => "TAPE STREAM MIRRORING PREVENTED",
+        0x2615 => "COPY SOURCE OR COPY DESTINATION NOT AUTHORIZED",
+        0x2616 => "FAST COPY NOT POSSIBLE",
+        0x2700 => "WRITE PROTECTED",
+        0x2701 => "HARDWARE WRITE PROTECTED",
+        0x2702 => "LOGICAL UNIT SOFTWARE WRITE PROTECTED",
+        0x2703 => "ASSOCIATED WRITE PROTECT",
+        0x2704 => "PERSISTENT WRITE PROTECT",
+        0x2705 => "PERMANENT WRITE PROTECT",
+        0x2706 => "CONDITIONAL WRITE PROTECT",
+        0x2707 => "SPACE ALLOCATION FAILED WRITE PROTECT",
+        0x2708 => "ZONE IS READ ONLY",
+        0x2800 => "NOT READY TO READY CHANGE, MEDIUM MAY HAVE CHANGED",
+        0x2801 => "IMPORT OR EXPORT ELEMENT ACCESSED",
+        0x2802 => "FORMAT-LAYER MAY HAVE CHANGED",
+        0x2803 => "IMPORT/EXPORT ELEMENT ACCESSED, MEDIUM CHANGED",
+        0x2900 => "POWER ON, RESET, OR BUS DEVICE RESET OCCURRED",
+        0x2901 => "POWER ON OCCURRED",
+        0x2902 => "SCSI BUS RESET OCCURRED",
+        0x2903 => "BUS DEVICE RESET FUNCTION OCCURRED",
+        0x2904 => "DEVICE INTERNAL RESET",
+        0x2905 => "TRANSCEIVER MODE CHANGED TO SINGLE-ENDED",
+        0x2906 => "TRANSCEIVER MODE CHANGED TO LVD",
+        0x2907 => "I_T NEXUS LOSS OCCURRED",
+        0x2A00 => "PARAMETERS CHANGED",
+        0x2A01 => "MODE PARAMETERS CHANGED",
+        0x2A02 => "LOG PARAMETERS CHANGED",
+        0x2A03 => "RESERVATIONS PREEMPTED",
+        0x2A04 => "RESERVATIONS RELEASED",
+        0x2A05 => "REGISTRATIONS PREEMPTED",
+        0x2A06 => "ASYMMETRIC ACCESS STATE CHANGED",
+        0x2A07 => "IMPLICIT ASYMMETRIC ACCESS STATE TRANSITION FAILED",
+        0x2A08 => "PRIORITY CHANGED",
+        0x2A09 => "CAPACITY DATA HAS CHANGED",
+        0x2A0A => "ERROR HISTORY I_T NEXUS CLEARED",
+        0x2A0B => "ERROR HISTORY SNAPSHOT RELEASED",
+        0x2A0C => "ERROR RECOVERY ATTRIBUTES HAVE CHANGED",
+        0x2A0D => "DATA ENCRYPTION CAPABILITIES CHANGED",
+        0x2A10 => "TIMESTAMP CHANGED",
+        0x2A11 => "DATA ENCRYPTION PARAMETERS CHANGED BY ANOTHER I_T NEXUS",
+        0x2A12 => "DATA ENCRYPTION PARAMETERS CHANGED BY VENDOR SPECIFIC EVENT",
+        0x2A13 => "DATA ENCRYPTION KEY INSTANCE COUNTER HAS CHANGED",
+        0x2A14 => "SA CREATION CAPABILITIES DATA HAS CHANGED",
+        0x2A15 => "MEDIUM REMOVAL PREVENTION PREEMPTED",
+        0x2A16 => "ZONE RESET WRITE POINTER RECOMMENDED",
+        0x2B00 => "COPY CANNOT EXECUTE SINCE HOST CANNOT DISCONNECT",
+        0x2C00 => "COMMAND SEQUENCE ERROR",
+        0x2C01 => "TOO MANY WINDOWS SPECIFIED",
+        0x2C02 => "INVALID COMBINATION OF WINDOWS SPECIFIED",
+        0x2C03 => "CURRENT PROGRAM AREA IS NOT EMPTY",
+        0x2C04 => "CURRENT PROGRAM AREA IS EMPTY",
+        0x2C05 => "ILLEGAL POWER CONDITION REQUEST",
+        0x2C06 => "PERSISTENT PREVENT CONFLICT",
+        0x2C07 => "PREVIOUS BUSY STATUS",
+        0x2C08 => "PREVIOUS TASK SET FULL STATUS",
+        0x2C09 => "PREVIOUS RESERVATION CONFLICT STATUS",
+        0x2C0A => "PARTITION OR COLLECTION CONTAINS USER OBJECTS",
+        0x2C0B => "NOT RESERVED",
+        0x2C0C => "ORWRITE GENERATION DOES NOT MATCH",
+        0x2C0D => "RESET WRITE POINTER NOT ALLOWED",
+        0x2C0E => "ZONE IS OFFLINE",
+        0x2C0F => "STREAM NOT OPEN",
+        0x2C10 => "UNWRITTEN DATA IN ZONE",
+        0x2C11 => "DESCRIPTOR FORMAT SENSE DATA REQUIRED",
+        0x2C12 => "ZONE IS INACTIVE",
+        0x2C13 => "WELL KNOWN LOGICAL UNIT ACCESS REQUIRED",
+        0x2D00 => "OVERWRITE ERROR ON UPDATE IN PLACE",
+        0x2E00 => "INSUFFICIENT TIME FOR OPERATION",
+        0x2E01 => "COMMAND TIMEOUT BEFORE PROCESSING",
+        0x2E02 => "COMMAND TIMEOUT DURING PROCESSING",
+        0x2E03 => "COMMAND TIMEOUT DURING PROCESSING DUE TO ERROR RECOVERY",
+        0x2F00 => "COMMANDS CLEARED BY ANOTHER INITIATOR",
+        0x2F01 => "COMMANDS CLEARED BY POWER LOSS NOTIFICATION",
+        0x2F02 => "COMMANDS CLEARED BY DEVICE SERVER",
+        0x2F03 => "SOME COMMANDS CLEARED BY QUEUING LAYER EVENT",
+        0x3000 => "INCOMPATIBLE MEDIUM INSTALLED",
+        0x3001 => "CANNOT READ MEDIUM - UNKNOWN FORMAT",
+        0x3002 => "CANNOT READ MEDIUM - INCOMPATIBLE FORMAT",
+        0x3003 => "CLEANING CARTRIDGE INSTALLED",
+        0x3004 => "CANNOT WRITE MEDIUM - UNKNOWN FORMAT",
+        0x3005 => "CANNOT WRITE MEDIUM - INCOMPATIBLE FORMAT",
+        0x3006 => "CANNOT FORMAT MEDIUM - INCOMPATIBLE MEDIUM",
+        0x3007 => "CLEANING FAILURE",
+        0x3008 => "CANNOT WRITE - APPLICATION CODE MISMATCH",
+        0x3009 => "CURRENT SESSION NOT FIXATED FOR APPEND",
+        0x300A => "CLEANING REQUEST REJECTED",
+        0x300C => "WORM MEDIUM - OVERWRITE ATTEMPTED",
+        0x300D => "WORM MEDIUM - INTEGRITY CHECK",
+        0x3010 => "MEDIUM NOT FORMATTED",
+        0x3011 => "INCOMPATIBLE VOLUME TYPE",
+        0x3012 => "INCOMPATIBLE VOLUME QUALIFIER",
+        0x3013 => "CLEANING VOLUME EXPIRED",
+        0x3100 => "MEDIUM FORMAT CORRUPTED",
+        0x3101 => "FORMAT COMMAND FAILED",
+        0x3102 => "ZONED FORMATTING FAILED DUE TO SPARE LINKING",
+        0x3103 => "SANITIZE COMMAND FAILED",
+        0x3104 => "DEPOPULATION FAILED",
+        0x3105 => "DEPOPULATION RESTORATION FAILED",
+        0x3200 => "NO DEFECT SPARE LOCATION AVAILABLE",
+        0x3201 => "DEFECT LIST UPDATE FAILURE",
+        0x3300 => "TAPE LENGTH ERROR",
+        0x3400 => "ENCLOSURE FAILURE",
+        0x3500 => "ENCLOSURE SERVICES FAILURE",
+        0x3501 => "UNSUPPORTED ENCLOSURE FUNCTION",
+        0x3502 => "ENCLOSURE SERVICES UNAVAILABLE",
+        0x3503 => "ENCLOSURE SERVICES TRANSFER FAILURE",
+        0x3504 => "ENCLOSURE SERVICES TRANSFER REFUSED",
+        0x3505 => "ENCLOSURE SERVICES CHECKSUM ERROR",
+        0x3600 => "RIBBON, INK, OR TONER FAILURE",
+        0x3700 => "ROUNDED PARAMETER",
+        0x3800 => "EVENT STATUS NOTIFICATION",
+        0x3802 => "ESN - POWER MANAGEMENT CLASS EVENT",
+        0x3804 => "ESN - MEDIA CLASS EVENT",
+        0x3806 => "ESN - DEVICE BUSY CLASS EVENT",
+        0x3807 => "THIN PROVISIONING SOFT THRESHOLD REACHED",
+        0x3808 => "DEPOPULATION INTERRUPTED",
+        0x3900 => "SAVING PARAMETERS NOT SUPPORTED",
+        0x3A00 => "MEDIUM NOT PRESENT",
+        0x3A01 => "MEDIUM NOT PRESENT - TRAY CLOSED",
+        0x3A02 => "MEDIUM NOT PRESENT - TRAY OPEN",
+        0x3A03 => "MEDIUM NOT PRESENT - LOADABLE",
+        0x3A04 => "MEDIUM NOT PRESENT - MEDIUM AUXILIARY MEMORY ACCESSIBLE",
+        0x3B00 => "SEQUENTIAL POSITIONING ERROR",
+        0x3B01 => "TAPE POSITION ERROR AT BEGINNING-OF-MEDIUM",
+        0x3B02 => "TAPE POSITION ERROR AT END-OF-MEDIUM",
+        0x3B03 => "TAPE OR ELECTRONIC VERTICAL FORMS UNIT NOT READY",
+        0x3B04 => "SLEW FAILURE",
+        0x3B05 => "PAPER JAM",
+        0x3B06 => "FAILED TO SENSE TOP-OF-FORM",
+        0x3B07 => "FAILED TO SENSE BOTTOM-OF-FORM",
+        0x3B08 => "REPOSITION ERROR",
+        0x3B09 => "READ PAST END OF MEDIUM",
+        0x3B0A => "READ PAST BEGINNING OF MEDIUM",
+        0x3B0B => "POSITION PAST END OF MEDIUM",
+        0x3B0C => "POSITION PAST BEGINNING OF MEDIUM",
+        0x3B0D => "MEDIUM DESTINATION ELEMENT FULL",
+        0x3B0E => "MEDIUM SOURCE ELEMENT EMPTY",
+        0x3B0F => "END OF MEDIUM REACHED",
+        0x3B11 => "MEDIUM MAGAZINE NOT ACCESSIBLE",
+        0x3B12 => "MEDIUM MAGAZINE REMOVED",
+        0x3B13 => "MEDIUM MAGAZINE INSERTED",
+        0x3B14 => "MEDIUM MAGAZINE LOCKED",
+        0x3B15 => "MEDIUM MAGAZINE UNLOCKED",
+        0x3B16 => "MECHANICAL POSITIONING OR CHANGER ERROR",
+        0x3B17 => "READ PAST END OF USER OBJECT",
+        0x3B18 => "ELEMENT DISABLED",
+        0x3B19 => "ELEMENT ENABLED",
+        0x3B1A => "DATA TRANSFER DEVICE REMOVED",
+        0x3B1B => "DATA TRANSFER DEVICE INSERTED",
+        0x3B1C => "TOO MANY LOGICAL OBJECTS ON PARTITION TO SUPPORT OPERATION",
+        0x3B20 => "ELEMENT STATIC INFORMATION CHANGED",
+        0x3D00 => "INVALID BITS IN IDENTIFY MESSAGE",
+        0x3E00 => "LOGICAL UNIT HAS NOT SELF-CONFIGURED YET",
+        0x3E01 => "LOGICAL UNIT FAILURE",
+        0x3E02 => "TIMEOUT ON LOGICAL UNIT",
+        0x3E03 => "LOGICAL UNIT FAILED SELF-TEST",
+        0x3E04 => "LOGICAL UNIT UNABLE TO UPDATE SELF-TEST LOG",
+        0x3F00 => "TARGET OPERATING CONDITIONS HAVE CHANGED",
+        0x3F01 => "MICROCODE HAS BEEN CHANGED",
+        0x3F02 => "CHANGED OPERATING DEFINITION",
+        0x3F03 => "INQUIRY DATA HAS CHANGED",
+        0x3F04 => "COMPONENT DEVICE ATTACHED",
+        0x3F05 => "DEVICE IDENTIFIER CHANGED",
+        0x3F06 => "REDUNDANCY GROUP CREATED OR MODIFIED",
+        0x3F07 => "REDUNDANCY GROUP DELETED",
+        0x3F08 => "SPARE CREATED OR MODIFIED",
+        0x3F09 => "SPARE DELETED",
+        0x3F0A => "VOLUME SET CREATED OR MODIFIED",
+        0x3F0B => "VOLUME SET DELETED",
+        0x3F0C => "VOLUME SET DEASSIGNED",
+        0x3F0D => "VOLUME SET REASSIGNED",
+        0x3F0E => "REPORTED LUNS DATA HAS CHANGED",
+        0x3F0F => "ECHO BUFFER OVERWRITTEN",
+        0x3F10 => "MEDIUM LOADABLE",
+        0x3F11 => "MEDIUM AUXILIARY MEMORY ACCESSIBLE",
+        0x3F12 => "iSCSI IP ADDRESS ADDED",
+        0x3F13 => "iSCSI IP ADDRESS REMOVED",
+        0x3F14 => "iSCSI IP ADDRESS CHANGED",
+        0x3F15 => "INSPECT REFERRALS SENSE DESCRIPTORS",
+        0x3F16 => "MICROCODE HAS BEEN CHANGED WITHOUT RESET",
+        0x3F17 => "ZONE TRANSITION TO FULL",
+        0x3F18 => "BIND COMPLETED",
+        0x3F19 => "BIND REDIRECTED",
+        0x3F1A => "SUBSIDIARY BINDING CHANGED",
+        0x4000 => "RAM FAILURE (SHOULD USE 40 NN)",
+        0x4100 => "DATA PATH FAILURE (SHOULD USE 40 NN)",
+        0x4200 => "POWER-ON OR SELF-TEST FAILURE (SHOULD USE 40 NN)",
+        0x4300 => "MESSAGE ERROR",
+        0x4400 => "INTERNAL TARGET FAILURE",
+        0x4401 => "PERSISTENT RESERVATION INFORMATION LOST",
+        0x4471 => "ATA DEVICE FAILED SET FEATURES",
+        0x4500 => "SELECT OR RESELECT FAILURE",
+        0x4600 => "UNSUCCESSFUL SOFT RESET",
+        0x4700 => "SCSI PARITY ERROR",
+        0x4701 => "DATA PHASE CRC ERROR DETECTED",
+        0x4702 => "SCSI PARITY ERROR DETECTED DURING ST DATA PHASE",
+        0x4703 => "INFORMATION UNIT iuCRC ERROR DETECTED",
+        0x4704 => "ASYNCHRONOUS INFORMATION PROTECTION ERROR DETECTED",
+        0x4705 => "PROTOCOL SERVICE CRC ERROR",
+        0x4706 => "PHY TEST FUNCTION IN PROGRESS",
+        0x477F => "SOME COMMANDS CLEARED BY ISCSI PROTOCOL EVENT",
+        0x4800 => "INITIATOR DETECTED ERROR MESSAGE RECEIVED",
+        0x4900 => "INVALID MESSAGE ERROR",
+        0x4A00 => "COMMAND PHASE ERROR",
+        0x4B00 => "DATA PHASE ERROR",
+        0x4B01 => "INVALID TARGET PORT TRANSFER TAG RECEIVED",
+        0x4B02 => "TOO MUCH WRITE DATA",
+        0x4B03 => "ACK/NAK TIMEOUT",
+        0x4B04 => "NAK RECEIVED",
+        0x4B05 => "DATA OFFSET ERROR",
+        0x4B06 => "INITIATOR RESPONSE TIMEOUT",
+        0x4B07 => "CONNECTION LOST",
+        0x4B08 => "DATA-IN BUFFER OVERFLOW - DATA BUFFER SIZE",
+        0x4B09 => "DATA-IN BUFFER OVERFLOW - DATA BUFFER DESCRIPTOR AREA",
+        0x4B0A => "DATA-IN BUFFER ERROR",
+        0x4B0B => "DATA-OUT BUFFER OVERFLOW - DATA BUFFER SIZE",
+        0x4B0C => "DATA-OUT BUFFER OVERFLOW - DATA BUFFER DESCRIPTOR AREA",
+        0x4B0D => "DATA-OUT BUFFER ERROR",
+        0x4B0E => "PCIE FABRIC ERROR",
+        0x4B0F => "PCIE COMPLETION TIMEOUT",
+        0x4B10 => "PCIE COMPLETER ABORT",
+        0x4B11 => "PCIE POISONED TLP RECEIVED",
+        0x4B12 => "PCIE ECRC CHECK FAILED",
+        0x4B13 => "PCIE UNSUPPORTED REQUEST",
+        0x4B14 => "PCIE ACS VIOLATION",
+        0x4B15 => "PCIE TLP PREFIX BLOCKED",
+        0x4C00 => "LOGICAL UNIT FAILED SELF-CONFIGURATION",
+        0x4E00 => "OVERLAPPED COMMANDS ATTEMPTED",
+        0x5000 => "WRITE APPEND ERROR",
+        0x5001 => "WRITE APPEND POSITION ERROR",
+        0x5002 => "POSITION ERROR RELATED TO TIMING",
+        0x5100 => "ERASE FAILURE",
+        0x5101 => "ERASE FAILURE - INCOMPLETE ERASE OPERATION DETECTED",
+        0x5200 => "CARTRIDGE FAULT",
+        0x5300 => "MEDIA LOAD OR EJECT FAILED",
+        0x5301 => "UNLOAD TAPE FAILURE",
+        0x5302 => "MEDIUM REMOVAL PREVENTED",
+        0x5303 => "MEDIUM REMOVAL PREVENTED BY DATA TRANSFER ELEMENT",
+        0x5304 => "MEDIUM THREAD OR UNTHREAD FAILURE",
+        0x5305 => "VOLUME IDENTIFIER INVALID",
+        0x5306 => "VOLUME IDENTIFIER MISSING",
+        0x5307 => "DUPLICATE VOLUME IDENTIFIER",
+        0x5308 => "ELEMENT STATUS UNKNOWN",
+        0x5309 => "DATA TRANSFER DEVICE ERROR - LOAD FAILED",
+        0x530A => "DATA TRANSFER DEVICE ERROR - UNLOAD FAILED",
+        0x530B => "DATA TRANSFER DEVICE ERROR - UNLOAD MISSING",
+        0x530C => "DATA TRANSFER DEVICE ERROR - EJECT FAILED",
+        0x530D => "DATA TRANSFER DEVICE ERROR - LIBRARY COMMUNICATION FAILED",
+        0x5400 => "SCSI TO HOST SYSTEM INTERFACE FAILURE",
+        0x5500 => "SYSTEM RESOURCE FAILURE",
+        0x5501 => "SYSTEM BUFFER FULL",
+        0x5502 => "INSUFFICIENT RESERVATION RESOURCES",
+        0x5503 => "INSUFFICIENT RESOURCES",
+        0x5504 => "INSUFFICIENT REGISTRATION RESOURCES",
+        0x5505 => "INSUFFICIENT ACCESS CONTROL RESOURCES",
+        0x5506 => "AUXILIARY MEMORY OUT OF SPACE",
+        0x5507 => "QUOTA ERROR",
+        0x5508 => "MAXIMUM NUMBER OF SUPPLEMENTAL DECRYPTION KEYS EXCEEDED",
+        0x5509 => "MEDIUM AUXILIARY MEMORY NOT ACCESSIBLE",
+        0x550A => "DATA CURRENTLY UNAVAILABLE",
+        0x550B => "INSUFFICIENT POWER FOR OPERATION",
+        0x550C => "INSUFFICIENT RESOURCES TO CREATE ROD",
+        0x550D => "INSUFFICIENT RESOURCES TO CREATE ROD TOKEN",
+        0x550E => "INSUFFICIENT ZONE RESOURCES",
+        0x550F => "INSUFFICIENT ZONE RESOURCES TO COMPLETE WRITE",
+        0x5510 => "MAXIMUM NUMBER OF STREAMS OPEN",
+        0x5511 => "INSUFFICIENT RESOURCES TO BIND",
+        0x5700 => "UNABLE TO RECOVER TABLE-OF-CONTENTS",
+        0x5800 => "GENERATION DOES NOT EXIST",
+        0x5900 => "UPDATED BLOCK READ",
+        0x5A00 => "OPERATOR REQUEST OR STATE CHANGE INPUT",
+        0x5A01 => "OPERATOR MEDIUM REMOVAL REQUEST",
+        0x5A02 => "OPERATOR SELECTED WRITE PROTECT",
+        0x5A03 => "OPERATOR SELECTED WRITE PERMIT",
+        0x5B00 => "LOG EXCEPTION",
+        0x5B01 => "THRESHOLD CONDITION MET",
+        0x5B02 => "LOG COUNTER AT MAXIMUM",
+        0x5B03 => "LOG LIST CODES EXHAUSTED",
+        0x5C00 => "RPL STATUS CHANGE",
+        0x5C01 => "SPINDLES SYNCHRONIZED",
+        0x5C02 => "SPINDLES NOT SYNCHRONIZED",
+        0x5D00 => "FAILURE PREDICTION THRESHOLD EXCEEDED",
+        0x5D01 => "MEDIA FAILURE PREDICTION THRESHOLD EXCEEDED",
+        0x5D02 => "LOGICAL UNIT FAILURE PREDICTION THRESHOLD EXCEEDED",
+        0x5D03 => "SPARE AREA EXHAUSTION PREDICTION THRESHOLD EXCEEDED",
+        0x5D10 => "HARDWARE IMPENDING FAILURE GENERAL HARD DRIVE FAILURE",
+        0x5D11 => "HARDWARE IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH",
+        0x5D12 => "HARDWARE IMPENDING FAILURE DATA ERROR RATE TOO HIGH",
+        0x5D13 => "HARDWARE IMPENDING FAILURE SEEK ERROR RATE TOO HIGH",
+        0x5D14 => "HARDWARE IMPENDING FAILURE TOO MANY BLOCK REASSIGNS",
+        0x5D15 => "HARDWARE IMPENDING FAILURE ACCESS TIMES TOO HIGH",
+        0x5D16 => "HARDWARE IMPENDING FAILURE START UNIT TIMES TOO HIGH",
+        0x5D17 => "HARDWARE IMPENDING FAILURE CHANNEL PARAMETRICS",
+        0x5D18 => "HARDWARE IMPENDING FAILURE CONTROLLER DETECTED",
+        0x5D19 => "HARDWARE IMPENDING FAILURE THROUGHPUT PERFORMANCE",
+        0x5D1A => "HARDWARE IMPENDING FAILURE SEEK TIME PERFORMANCE",
+        0x5D1B => "HARDWARE IMPENDING FAILURE SPIN-UP RETRY COUNT",
+        0x5D1C => "HARDWARE IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT",
+        0x5D1D => "HARDWARE IMPENDING FAILURE POWER LOSS PROTECTION CIRCUIT",
+        0x5D20 => "CONTROLLER IMPENDING FAILURE GENERAL HARD DRIVE FAILURE",
+        0x5D21 => "CONTROLLER IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH",
+        0x5D22 => "CONTROLLER IMPENDING FAILURE DATA ERROR RATE TOO HIGH",
+        0x5D23 => "CONTROLLER IMPENDING FAILURE SEEK ERROR RATE TOO HIGH",
+        0x5D24 => "CONTROLLER IMPENDING FAILURE TOO MANY BLOCK REASSIGNS",
+        0x5D25 => "CONTROLLER IMPENDING FAILURE ACCESS TIMES TOO HIGH",
+        0x5D26 => "CONTROLLER IMPENDING FAILURE START UNIT TIMES TOO HIGH",
+        0x5D27 => "CONTROLLER IMPENDING FAILURE CHANNEL PARAMETRICS",
+        0x5D28 => "CONTROLLER IMPENDING FAILURE CONTROLLER DETECTED",
+        0x5D29 => "CONTROLLER IMPENDING FAILURE THROUGHPUT PERFORMANCE",
+        0x5D2A => "CONTROLLER IMPENDING FAILURE SEEK TIME PERFORMANCE",
+        0x5D2B => "CONTROLLER IMPENDING FAILURE SPIN-UP RETRY COUNT",
+        0x5D2C => "CONTROLLER IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT",
+        0x5D30 => "DATA CHANNEL IMPENDING FAILURE GENERAL HARD DRIVE FAILURE",
+        0x5D31 => "DATA CHANNEL IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH",
+        0x5D32 => "DATA CHANNEL IMPENDING FAILURE DATA ERROR RATE TOO HIGH",
+        0x5D33 => "DATA CHANNEL IMPENDING FAILURE SEEK ERROR RATE TOO HIGH",
+        0x5D34 => "DATA CHANNEL IMPENDING FAILURE TOO MANY BLOCK REASSIGNS",
+        0x5D35 => "DATA CHANNEL IMPENDING FAILURE ACCESS TIMES TOO HIGH",
+        0x5D36 => "DATA CHANNEL IMPENDING FAILURE START UNIT TIMES TOO HIGH",
+        0x5D37 => "DATA CHANNEL IMPENDING FAILURE CHANNEL PARAMETRICS",
+        0x5D38 => "DATA CHANNEL IMPENDING FAILURE CONTROLLER DETECTED",
+        0x5D39 => "DATA CHANNEL IMPENDING FAILURE THROUGHPUT PERFORMANCE",
+        0x5D3A => "DATA CHANNEL IMPENDING FAILURE SEEK TIME PERFORMANCE",
+        0x5D3B => "DATA CHANNEL IMPENDING FAILURE SPIN-UP RETRY COUNT",
+        0x5D3C => "DATA CHANNEL IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT",
+        0x5D40 => "SERVO IMPENDING FAILURE GENERAL HARD DRIVE FAILURE",
+        0x5D41 => "SERVO IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH",
+        0x5D42 => "SERVO IMPENDING FAILURE DATA ERROR RATE TOO HIGH",
+        0x5D43 => "SERVO IMPENDING FAILURE SEEK ERROR RATE TOO HIGH",
+        0x5D44 => "SERVO IMPENDING FAILURE TOO MANY BLOCK REASSIGNS",
+        0x5D45 => "SERVO IMPENDING FAILURE ACCESS TIMES TOO HIGH",
+        0x5D46 => "SERVO IMPENDING FAILURE START UNIT TIMES TOO HIGH",
+        0x5D47 => "SERVO IMPENDING FAILURE CHANNEL PARAMETRICS",
+        0x5D48 => "SERVO IMPENDING FAILURE CONTROLLER DETECTED",
+        0x5D49 => "SERVO IMPENDING FAILURE THROUGHPUT PERFORMANCE",
+        0x5D4A => "SERVO IMPENDING FAILURE SEEK TIME PERFORMANCE",
+        0x5D4B => "SERVO IMPENDING FAILURE SPIN-UP RETRY COUNT",
+        0x5D4C => "SERVO IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT",
+        0x5D50 => "SPINDLE IMPENDING FAILURE GENERAL HARD DRIVE FAILURE",
+        0x5D51 => "SPINDLE IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH",
+        0x5D52 => "SPINDLE IMPENDING FAILURE DATA ERROR RATE TOO HIGH",
+        0x5D53 => "SPINDLE IMPENDING FAILURE SEEK ERROR RATE TOO HIGH",
+        0x5D54 => "SPINDLE IMPENDING FAILURE TOO MANY BLOCK REASSIGNS",
+        0x5D55 => "SPINDLE IMPENDING FAILURE ACCESS TIMES TOO HIGH",
+        0x5D56 => "SPINDLE IMPENDING FAILURE START UNIT TIMES TOO HIGH",
+        0x5D57 => "SPINDLE IMPENDING FAILURE CHANNEL PARAMETRICS",
+        0x5D58 => "SPINDLE IMPENDING FAILURE CONTROLLER DETECTED",
+        0x5D59 => "SPINDLE IMPENDING FAILURE THROUGHPUT PERFORMANCE",
+        0x5D5A => "SPINDLE IMPENDING FAILURE SEEK TIME PERFORMANCE",
+        0x5D5B => "SPINDLE IMPENDING FAILURE SPIN-UP RETRY COUNT",
+        0x5D5C => "SPINDLE IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT",
+        0x5D60 => "FIRMWARE IMPENDING FAILURE GENERAL HARD DRIVE FAILURE",
+        0x5D61 => "FIRMWARE IMPENDING FAILURE DRIVE ERROR RATE TOO HIGH",
+        0x5D62 => "FIRMWARE IMPENDING FAILURE DATA ERROR RATE TOO HIGH",
+        0x5D63 => "FIRMWARE IMPENDING FAILURE SEEK ERROR RATE TOO HIGH",
+        0x5D64 => "FIRMWARE IMPENDING FAILURE TOO MANY BLOCK REASSIGNS",
+        0x5D65 => "FIRMWARE IMPENDING FAILURE ACCESS TIMES TOO HIGH",
+        0x5D66 => "FIRMWARE IMPENDING FAILURE START UNIT TIMES TOO HIGH",
+        0x5D67 => "FIRMWARE IMPENDING FAILURE CHANNEL PARAMETRICS",
+        0x5D68 => "FIRMWARE IMPENDING FAILURE CONTROLLER DETECTED",
+        0x5D69 => "FIRMWARE IMPENDING FAILURE THROUGHPUT PERFORMANCE",
+        0x5D6A => "FIRMWARE IMPENDING FAILURE SEEK TIME PERFORMANCE",
+        0x5D6B => "FIRMWARE IMPENDING FAILURE SPIN-UP RETRY COUNT",
+        0x5D6C => "FIRMWARE IMPENDING FAILURE DRIVE CALIBRATION RETRY COUNT",
+        0x5D73 => "MEDIA IMPENDING FAILURE ENDURANCE LIMIT MET",
+        0x5DFF => "FAILURE PREDICTION THRESHOLD EXCEEDED (FALSE)",
+        0x5E00 => "LOW POWER CONDITION ON",
+        0x5E01 => "IDLE CONDITION ACTIVATED BY TIMER",
+        0x5E02 => "STANDBY CONDITION ACTIVATED BY TIMER",
+        0x5E03 => "IDLE CONDITION ACTIVATED BY COMMAND",
+        0x5E04 => "STANDBY CONDITION ACTIVATED BY COMMAND",
+        0x5E05 => "IDLE_B CONDITION ACTIVATED BY TIMER",
+        0x5E06 => "IDLE_B CONDITION ACTIVATED BY COMMAND",
+        0x5E07 => "IDLE_C CONDITION ACTIVATED BY TIMER",
+        0x5E08 => "IDLE_C CONDITION ACTIVATED BY COMMAND",
+        0x5E09 => "STANDBY_Y CONDITION ACTIVATED BY TIMER",
+        0x5E0A => "STANDBY_Y CONDITION ACTIVATED BY COMMAND",
+        0x5E41 => "POWER STATE CHANGE TO ACTIVE",
+        0x5E42 => "POWER STATE CHANGE TO IDLE",
+        0x5E43 => "POWER STATE CHANGE TO STANDBY",
+        0x5E45 => "POWER STATE CHANGE TO SLEEP",
+        0x5E47 => "POWER STATE CHANGE TO DEVICE CONTROL",
+        0x6000 => "LAMP FAILURE",
+        0x6100 => "VIDEO ACQUISITION ERROR",
+        0x6101 => "UNABLE TO ACQUIRE VIDEO",
+        0x6102 => "OUT OF FOCUS",
+        0x6200 => "SCAN HEAD POSITIONING ERROR",
+        0x6300 => "END OF USER AREA ENCOUNTERED ON THIS TRACK",
+        0x6301 => "PACKET DOES NOT FIT IN AVAILABLE SPACE",
+        0x6400 => "ILLEGAL MODE FOR THIS TRACK",
+        0x6401 => "INVALID PACKET SIZE",
+        0x6500 => "VOLTAGE FAULT",
+        0x6600 => "AUTOMATIC DOCUMENT FEEDER COVER UP",
+        0x6601 => "AUTOMATIC DOCUMENT FEEDER LIFT UP",
+        0x6602 => "DOCUMENT JAM IN AUTOMATIC DOCUMENT FEEDER",
+        0x6603 => "DOCUMENT MISS FEED AUTOMATIC IN DOCUMENT FEEDER",
+        0x6700 => "CONFIGURATION FAILURE",
+        0x6701 => "CONFIGURATION OF INCAPABLE LOGICAL UNITS FAILED",
+        0x6702 => "ADD LOGICAL UNIT FAILED",
+        0x6703 => "MODIFICATION OF LOGICAL UNIT FAILED",
+        0x6704 => "EXCHANGE OF LOGICAL UNIT FAILED",
+        0x6705 => "REMOVE OF LOGICAL UNIT FAILED",
+        0x6706 => "ATTACHMENT OF LOGICAL UNIT FAILED",
+        0x6707 => "CREATION OF LOGICAL UNIT FAILED",
+        0x6708 => "ASSIGN FAILURE OCCURRED",
+        0x6709 => "MULTIPLY ASSIGNED LOGICAL UNIT",
+        0x670A => "SET TARGET PORT GROUPS COMMAND FAILED",
+        0x670B => "ATA DEVICE FEATURE NOT ENABLED",
+        0x670C => "COMMAND REJECTED",
+        0x670D => "EXPLICIT BIND NOT ALLOWED",
+        0x6800 => "LOGICAL UNIT NOT CONFIGURED",
+        0x6801 => "SUBSIDIARY LOGICAL UNIT NOT CONFIGURED",
+        0x6900 => "DATA LOSS ON LOGICAL UNIT",
+        0x6901 => "MULTIPLE LOGICAL UNIT FAILURES",
+        0x6902 => "PARITY/DATA MISMATCH",
+        0x6A00 => "INFORMATIONAL, REFER TO LOG",
+        0x6B00 => "STATE CHANGE HAS OCCURRED",
+        0x6B01 => "REDUNDANCY LEVEL GOT BETTER",
+        0x6B02 => "REDUNDANCY LEVEL GOT WORSE",
+        0x6C00 => "REBUILD FAILURE OCCURRED",
+        0x6D00 => "RECALCULATE FAILURE OCCURRED",
+        0x6E00 => "COMMAND TO LOGICAL UNIT FAILED",
+        0x6F00 => "COPY PROTECTION KEY EXCHANGE FAILURE - AUTHENTICATION FAILURE",
+        0x6F01 => "COPY PROTECTION KEY EXCHANGE FAILURE - KEY NOT PRESENT",
+        0x6F02 => "COPY PROTECTION KEY EXCHANGE FAILURE - KEY NOT ESTABLISHED",
+        0x6F03 => "READ OF SCRAMBLED SECTOR WITHOUT AUTHENTICATION",
+        0x6F04 => "MEDIA REGION CODE IS MISMATCHED TO LOGICAL UNIT REGION",
+        0x6F05 => "DRIVE REGION MUST BE PERMANENT/REGION RESET COUNT ERROR",
+        0x6F06 => "INSUFFICIENT BLOCK COUNT FOR BINDING NONCE RECORDING",
+        0x6F07 => "CONFLICT IN BINDING NONCE RECORDING",
+        0x6F08 => "INSUFFICIENT PERMISSION",
+        0x6F09 => "INVALID DRIVE-HOST PAIRING SERVER",
+        0x6F0A => "DRIVE-HOST PAIRING SUSPENDED",
+        0x7100 => "DECOMPRESSION EXCEPTION LONG ALGORITHM ID",
+        0x7200 => "SESSION FIXATION ERROR",
+        0x7201 => "SESSION FIXATION ERROR WRITING LEAD-IN",
+        0x7202 => "SESSION FIXATION ERROR WRITING LEAD-OUT",
+        0x7203 => "SESSION FIXATION ERROR - INCOMPLETE TRACK IN SESSION",
+        0x7204 => "EMPTY OR PARTIALLY WRITTEN RESERVED TRACK",
+        0x7205 => "NO MORE TRACK RESERVATIONS ALLOWED",
+        0x7206 => "RMZ EXTENSION IS NOT ALLOWED",
+        0x7207 => "NO MORE TEST ZONE EXTENSIONS ARE ALLOWED",
+        0x7300 => "CD CONTROL ERROR",
+        0x7301 => "POWER CALIBRATION AREA ALMOST FULL",
+        0x7302 => "POWER CALIBRATION AREA IS FULL",
+        0x7303 => "POWER CALIBRATION AREA ERROR",
+        0x7304 => "PROGRAM MEMORY AREA UPDATE FAILURE",
+        0x7305 => "PROGRAM MEMORY AREA IS FULL",
+        0x7306 => "RMA/PMA IS ALMOST FULL",
+        0x7310 => "CURRENT POWER CALIBRATION AREA ALMOST FULL",
+        0x7311 => "CURRENT POWER CALIBRATION AREA IS FULL",
+        0x7317 => "RDZ IS FULL",
+        0x7400 => "SECURITY ERROR",
+        0x7401 => "UNABLE TO DECRYPT DATA",
+        0x7402 => "UNENCRYPTED DATA ENCOUNTERED WHILE DECRYPTING",
+        0x7403 => "INCORRECT DATA ENCRYPTION KEY",
+        0x7404 => "CRYPTOGRAPHIC INTEGRITY VALIDATION FAILED",
+        0x7405 => "ERROR DECRYPTING DATA",
+        0x7406 => "UNKNOWN SIGNATURE VERIFICATION KEY",
+        0x7407 => "ENCRYPTION PARAMETERS NOT USEABLE",
+        0x7408 => "DIGITAL SIGNATURE VALIDATION FAILURE",
+        0x7409 => "ENCRYPTION MODE MISMATCH ON READ",
+        0x740A => "ENCRYPTED BLOCK NOT RAW READ ENABLED",
+        0x740B => "INCORRECT ENCRYPTION PARAMETERS",
+        0x740C => "UNABLE TO DECRYPT PARAMETER LIST",
+        0x740D => "ENCRYPTION ALGORITHM DISABLED",
+        0x7410 => "SA CREATION PARAMETER VALUE INVALID",
+        0x7411 => "SA CREATION PARAMETER VALUE REJECTED",
+        0x7412 => "INVALID SA USAGE",
+        0x7421 => "DATA ENCRYPTION CONFIGURATION PREVENTED",
+        0x7430 => "SA CREATION PARAMETER NOT SUPPORTED",
+        0x7440 => "AUTHENTICATION FAILED",
+        0x7461 => "EXTERNAL DATA ENCRYPTION KEY MANAGER ACCESS ERROR",
+        0x7462 => "EXTERNAL DATA ENCRYPTION KEY MANAGER ERROR",
+        0x7463 => "EXTERNAL DATA ENCRYPTION KEY NOT FOUND",
+        0x7464 => "EXTERNAL DATA ENCRYPTION REQUEST NOT AUTHORIZED",
+        0x746E => "EXTERNAL DATA ENCRYPTION CONTROL TIMEOUT",
+        0x746F => "EXTERNAL DATA ENCRYPTION CONTROL ERROR",
+        0x7471 => "LOGICAL UNIT ACCESS NOT AUTHORIZED",
+        0x7479 => "SECURITY CONFLICT IN TRANSLATED DEVICE",
+        _ => return None,
+    };
+
+    Some(description)
+}
+
 impl Debug for AdditionalSenseCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self)
@@ -1124,4 +1298,218 @@ impl From<AdditionalSenseCode> for (u8, u8) {
     }
 }
 
+const FIXED_SENSE_DATA_LENGTH: usize = 18;
+const DESCRIPTOR_SENSE_DATA_HEADER_LENGTH: usize = 8;
 const DESCRIPTOR_HEADER_LENGTH: usize = 2;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sense_buffer(bytes: &[u8]) -> [u8; MAX_SENSE_BUFFER_LENGTH] {
+        let mut raw = [0; MAX_SENSE_BUFFER_LENGTH];
+        raw[..bytes.len()].copy_from_slice(bytes);
+        raw
+    }
+
+    #[test]
+    fn fixed_format_test() {
+        let raw = sense_buffer(&[
+            0xF0, 0x00, 0x02, 0x00, 0x00, 0x12, 0x34, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x04, 0x01,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+
+        // the whole allocation length may be reported as transferred
+        for length in [18, MAX_SENSE_BUFFER_LENGTH] {
+            let sense = match SenseData::parse(&raw, length) {
+                SenseData::Fixed(sense) => sense,
+                other => panic!("unexpected sense data: {:?}", other),
+            };
+
+            assert!(sense.is_valid, "valid");
+            assert!(
+                matches!(sense.response_code, ErrorType::Current),
+                "response code"
+            );
+            assert!(matches!(sense.sense_key, SenseKey::NotReady), "sense key");
+            assert_eq!(<(u8, u8)>::from(sense.additional_sense_code), (0x04, 0x01));
+            assert_eq!(sense.information, [0x00, 0x00, 0x12, 0x34], "information");
+            assert!(sense.additional_sense_bytes.is_empty(), "additional bytes");
+        }
+
+        assert!(
+            matches!(SenseData::parse(&raw, 8), SenseData::Raw(bytes) if bytes.len() == 8),
+            "short sense data"
+        );
+    }
+
+    #[test]
+    fn descriptor_format_test() {
+        let raw = sense_buffer(&[
+            0x72, 0x03, 0x11, 0x00, 0x00, 0x00, 0x00, 0x10, // header
+            0x00, 0x0A, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+            0x00, // information
+            0x03, 0x02, 0x00, 0x2A, // field replaceable unit
+        ]);
+
+        let sense = match SenseData::parse(&raw, 24) {
+            SenseData::Descriptor(sense) => sense,
+            other => panic!("unexpected sense data: {:?}", other),
+        };
+
+        assert!(
+            matches!(sense.sense_key, SenseKey::MediumError),
+            "sense key"
+        );
+        assert_eq!(<(u8, u8)>::from(sense.additional_sense_code), (0x11, 0x00));
+        assert_eq!(sense.descriptors.len(), 2, "descriptor count");
+        assert!(
+            matches!(
+                sense.descriptors[0],
+                Descriptor::Information {
+                    is_valid: true,
+                    information: [0, 0, 0, 0, 0, 0, 0x10, 0x00]
+                }
+            ),
+            "information descriptor"
+        );
+        assert!(
+            matches!(
+                sense.descriptors[1],
+                Descriptor::FieldReplaceableUnit {
+                    field_replaceable_unit_code: 0x2A
+                }
+            ),
+            "field replaceable unit descriptor"
+        );
+    }
+
+    #[test]
+    fn format_independent_test() {
+        // LOGICAL BLOCK ADDRESS OUT OF RANGE, field pointer to byte 2 of the CDB
+        let fixed = SenseData::parse(
+            &sense_buffer(&[
+                0xF0, 0x00, 0x05, 0x00, 0x00, 0x10, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x21, 0x00,
+                0x07, 0xC0, 0x00, 0x02,
+            ]),
+            18,
+        );
+        let descriptor = SenseData::parse(
+            &sense_buffer(&[
+                0x72, 0x05, 0x21, 0x00, 0x00, 0x00, 0x00, 0x1C, // header
+                0x00, 0x0A, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+                0x00, // information
+                0x02, 0x06, 0x00, 0x00, 0xC0, 0x00, 0x02, 0x00, // sense key specific
+                0x03, 0x02, 0x00, 0x07, // field replaceable unit
+            ]),
+            36,
+        );
+
+        for sense in [fixed, descriptor] {
+            assert!(
+                matches!(sense.sense_key(), Some(SenseKey::IllegalRequest)),
+                "sense key"
+            );
+
+            let code = sense.additional_sense_code().unwrap();
+            assert_eq!(code.additional_sense_code(), 0x21, "asc");
+            assert_eq!(code.additional_sense_code_qualifier(), 0x00, "ascq");
+            assert_eq!(sense.information(), Some(0x1000), "information");
+            assert_eq!(sense.field_replaceable_unit_code(), Some(0x07), "fru");
+            assert!(
+                matches!(
+                    sense.sense_key_specific(),
+                    Some(SenseKeySpecific::IllegalRequest {
+                        is_command_data: true,
+                        field_pointer: 0x0002,
+                        ..
+                    })
+                ),
+                "sense key specific"
+            );
+        }
+
+        assert!(SenseData::None.sense_key().is_none(), "no sense");
+    }
+
+    #[test]
+    fn asc_ascq_description_test() {
+        assert_eq!(
+            asc_ascq_description(0x04, 0x01),
+            Some("LOGICAL UNIT IS IN PROCESS OF BECOMING READY"),
+            "becoming ready"
+        );
+        assert_eq!(
+            asc_ascq_description(0x28, 0x00),
+            Some("NOT READY TO READY CHANGE, MEDIUM MAY HAVE CHANGED"),
+            "not ready to ready change"
+        );
+        assert_eq!(
+            asc_ascq_description(0x3A, 0x00),
+            Some("MEDIUM NOT PRESENT"),
+            "medium not present"
+        );
+        assert_eq!(
+            asc_ascq_description(0x80, 0x00),
+            None,
+            "vendor specific ASC"
+        );
+        assert_eq!(
+            asc_ascq_description(0x04, 0xF0),
+            None,
+            "vendor specific ASCQ"
+        );
+        assert_eq!(
+            asc_ascq_description(0x40, 0x85),
+            None,
+            "parameterized range"
+        );
+        assert_eq!(
+            AdditionalSenseCode::from((0x40, 0x85)).description(),
+            "DIAGNOSTIC FAILURE ON COMPONENT 0x85",
+            "range description"
+        );
+
+        let raw = sense_buffer(&[
+            0x70, 0x00, 0x06, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x28, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+        assert!(
+            SenseData::parse(&raw, 18)
+                .to_string()
+                .ends_with("NOT READY TO READY CHANGE, MEDIUM MAY HAVE CHANGED"),
+            "Display"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        // NOT READY, LOGICAL UNIT IS IN PROCESS OF BECOMING READY
+        let raw = sense_buffer(&[
+            0xF0, 0x00, 0x02, 0x00, 0x00, 0x12, 0x34, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x04, 0x01,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+        let sense = SenseData::parse(&raw, 18);
+
+        let json = serde_json::to_string(&sense).unwrap();
+        assert!(json.starts_with(r#"{"fixed":{"#), "format, {}", json);
+        assert!(
+            json.contains(r#""response_code":"current""#),
+            "error type, {}",
+            json
+        );
+        assert!(
+            json.contains(r#""sense_key":"not_ready""#),
+            "sense key, {}",
+            json
+        );
+
+        let sense: SenseData = serde_json::from_str(&json).unwrap();
+        assert!(
+            matches!(sense.sense_key(), Some(SenseKey::NotReady)),
+            "sense key"
+        );
+        assert_eq!(serde_json::to_string(&sense).unwrap(), json, "round-trip");
+    }
+}
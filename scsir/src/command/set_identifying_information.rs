@@ -1,10 +1,12 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,6 +14,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct SetIdentifyingInformationCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     information_type: u8,
     command_buffer: CommandBuffer,
     data_buffer: Vec<u8>,
@@ -21,6 +24,7 @@ impl<'a> SetIdentifyingInformationCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             information_type: 0,
             command_buffer: CommandBuffer::new()
                 .with_operation_code(OPERATION_CODE)
@@ -35,11 +39,12 @@ impl<'a> SetIdentifyingInformationCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
+    // an empty parameter clears the identifying information
     pub fn parameter(&mut self, value: &[u8]) -> &mut Self {
         self.data_buffer = value.to_owned();
         self.command_buffer
@@ -47,27 +52,56 @@ impl<'a> SetIdentifyingInformationCommand<'a> {
         self
     }
 
-    pub fn issue(&mut self) -> crate::Result<()> {
-        bitfield_bound_check!(self.information_type, 7, "information type")?;
-        bitfield_bound_check!(self.data_buffer.len(), 32, "parameter list length")?;
+    timeout_setter!();
 
+    pub fn issue(&mut self) -> crate::Result<()> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self
-                .command_buffer
-                .with_information_type(self.information_type),
-            data_buffer: self.data_buffer.clone().into(),
+            command_buffer: self.command_buffer()?,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(self.information_type, 7, "information type")?;
+        parameter_check(self.information_type, self.data_buffer.len())?;
+
+        Ok(self
+            .command_buffer
+            .with_information_type(self.information_type))
+    }
 }
 
 impl Scsi {
-    pub fn set_identifying_information(&self) -> SetIdentifyingInformationCommand {
+    pub fn set_identifying_information(&self) -> SetIdentifyingInformationCommand<'_> {
         SetIdentifyingInformationCommand::new(self)
     }
 }
 
+crate::command::async_issue!(set_identifying_information: SetIdentifyingInformationCommand);
+
+fn parameter_check(information_type: u8, parameter_list_length: usize) -> crate::Result<()> {
+    let maximum = match information_type {
+        0x00 => MAX_PERIPHERAL_INFORMATION_LENGTH,
+        _ => MAX_OTHER_INFORMATION_LENGTH,
+    };
+
+    if parameter_list_length > maximum {
+        return Err(crate::Error::ArgumentOutOfBounds(format!(
+            "Parameter list length is out of bounds. The maximum possible value is {}, but {} was provided.",
+            maximum, parameter_list_length
+        )));
+    }
+
+    Ok(())
+}
+
 const OPERATION_CODE: u8 = 0xA4;
 const SERVICE_ACTION: u8 = 0x06;
+const MAX_PERIPHERAL_INFORMATION_LENGTH: usize = 512;
+const MAX_OTHER_INFORMATION_LENGTH: usize = 64;
 
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
@@ -82,22 +116,27 @@ struct CommandBuffer {
     control: B8,
 }
 
-struct ThisCommand {
+struct ThisCommand<'a> {
     command_buffer: CommandBuffer,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl Command for ThisCommand {
+impl<'a> Command for ThisCommand<'a> {
     type CommandBuffer = CommandBuffer;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
     fn direction(&self) -> DataDirection {
-        DataDirection::ToDevice
+        if self.data_buffer.is_empty() {
+            DataDirection::None
+        } else {
+            DataDirection::ToDevice
+        }
     }
 
     fn command(&self) -> Self::CommandBuffer {
@@ -105,13 +144,17 @@ impl Command for ThisCommand {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -135,4 +178,31 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn parameter_check_test() {
+        assert!(parameter_check(0x00, 512).is_ok(), "peripheral label");
+        assert!(
+            parameter_check(0x00, 513).is_err(),
+            "peripheral label too long"
+        );
+        assert!(parameter_check(0x02, 64).is_ok(), "other information");
+        assert!(
+            parameter_check(0x02, 65).is_err(),
+            "other information too long"
+        );
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.set_identifying_information();
+        command.parameter(b"rack 4, slot 12");
+
+        let before = allocations();
+        command.issue().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
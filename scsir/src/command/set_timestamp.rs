@@ -1,10 +1,12 @@
 #![allow(dead_code)]
 
+use std::{mem::size_of, time::Duration};
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,7 +14,9 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct SetTimestampCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     command_buffer: CommandBuffer,
+    timestamp: Option<u64>,
     data_buffer: Vec<u8>,
 }
 
@@ -20,41 +24,101 @@ impl<'a> SetTimestampCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new()
                 .with_operation_code(OPERATION_CODE)
                 .with_service_action(SERVICE_ACTION),
+            timestamp: None,
             data_buffer: vec![],
         }
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    // milliseconds since the epoch, timestamp must be less than 0x1_0000_0000_0000
+    // overrides the raw parameter
+    pub fn timestamp(&mut self, value: u64) -> &mut Self {
+        self.timestamp = Some(value);
         self
     }
 
     pub fn parameter(&mut self, value: &[u8]) -> &mut Self {
+        self.timestamp = None;
         self.data_buffer = value.to_owned();
         self.command_buffer
             .set_parameter_list_length(value.len() as u32);
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<()> {
-        bitfield_bound_check!(self.data_buffer.len(), 32, "parameter list length")?;
+        let (command_buffer, parameter) = self.command()?;
 
         self.interface.issue(&ThisCommand {
-            command_buffer: self.command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            command_buffer,
+            data_buffer: SliceBufferWrapper(parameter.bytes()),
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        self.command().map(|(command_buffer, _)| command_buffer)
+    }
+
+    // the parameter list length comes from the timestamp, so both are built together
+    fn command(&self) -> crate::Result<(CommandBuffer, Parameter<'_>)> {
+        let parameter = match self.timestamp {
+            Some(timestamp) => Parameter::Timestamp(parameter_data(timestamp)?),
+            None => Parameter::Raw(&self.data_buffer),
+        };
+
+        bitfield_bound_check!(parameter.bytes().len(), 32, "parameter list length")?;
+
+        let command_buffer = self
+            .command_buffer
+            .with_parameter_list_length(parameter.bytes().len() as u32);
+
+        Ok((command_buffer, parameter))
+    }
 }
 
 impl Scsi {
-    pub fn set_timestamp(&self) -> SetTimestampCommand {
+    pub fn set_timestamp(&self) -> SetTimestampCommand<'_> {
         SetTimestampCommand::new(self)
     }
 }
 
+crate::command::async_issue!(set_timestamp: SetTimestampCommand);
+
+// the timestamp parameter data is built on the stack, a raw parameter is borrowed
+enum Parameter<'a> {
+    Timestamp([u8; size_of::<SetTimestampParameterData>()]),
+    Raw(&'a [u8]),
+}
+
+impl Parameter<'_> {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Timestamp(bytes) => bytes,
+            Self::Raw(bytes) => bytes,
+        }
+    }
+}
+
+fn parameter_data(timestamp: u64) -> crate::Result<[u8; size_of::<SetTimestampParameterData>()]> {
+    bitfield_bound_check!(timestamp, 48, "timestamp")?;
+
+    Ok(SetTimestampParameterData::new()
+        .with_timestamp(timestamp)
+        .into_bytes())
+}
+
 const OPERATION_CODE: u8 = 0xA4;
 const SERVICE_ACTION: u8 = 0x0F;
 
@@ -70,17 +134,26 @@ struct CommandBuffer {
     control: B8,
 }
 
-struct ThisCommand {
+#[bitfield]
+#[derive(Clone, Copy)]
+struct SetTimestampParameterData {
+    reserved_0: B32,
+    timestamp: B48,
+    reserved_1: B16,
+}
+
+struct ThisCommand<'a> {
     command_buffer: CommandBuffer,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl Command for ThisCommand {
+impl<'a> Command for ThisCommand<'a> {
     type CommandBuffer = CommandBuffer;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
@@ -93,13 +166,17 @@ impl Command for ThisCommand {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -111,9 +188,9 @@ impl Command for ThisCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::mem::size_of;
 
     const COMMAND_LENGTH: usize = 12;
+    const PARAMETER_LENGTH: usize = 12;
 
     #[test]
     fn layout_test() {
@@ -122,5 +199,42 @@ mod tests {
             COMMAND_LENGTH,
             concat!("Size of: ", stringify!(CommandBuffer))
         );
+
+        assert_eq!(
+            size_of::<SetTimestampParameterData>(),
+            PARAMETER_LENGTH,
+            concat!("Size of: ", stringify!(SetTimestampParameterData))
+        );
+    }
+
+    #[test]
+    fn parameter_data_test() {
+        assert_eq!(
+            parameter_data(0x018B_3C5E_7A00).unwrap(),
+            [0x00, 0x00, 0x00, 0x00, 0x01, 0x8B, 0x3C, 0x5E, 0x7A, 0x00, 0x00, 0x00],
+            "timestamp parameter data"
+        );
+
+        assert!(
+            matches!(
+                parameter_data(0x1_0000_0000_0000),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "timestamp out of bounds"
+        );
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.set_timestamp();
+        command.timestamp(0x018B_3C5E_7A00);
+
+        // the parameter data of a timestamp is built on the stack
+        let before = allocations();
+        command.issue().unwrap();
+        assert_eq!(allocations(), before, "allocations");
     }
 }
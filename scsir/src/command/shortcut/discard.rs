@@ -0,0 +1,280 @@
+use crate::Scsi;
+
+use super::inquiry::{
+    block_limits, logical_block_provisioning, BlockLimits, LogicalBlockProvisioning,
+};
+
+/// The command [`Scsi::discard`] deallocated the blocks with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiscardMethod {
+    Unmap,
+    /// WRITE SAME (16) of a zeroed block with the UNMAP bit set
+    WriteSame16,
+}
+
+impl Scsi {
+    /// Deallocates `count` blocks from `logical_block_address` on a thin provisioned device.
+    ///
+    /// Picks UNMAP if the Logical Block Provisioning VPD page sets LBPU and the Block Limits
+    /// VPD page reports how much one UNMAP may cover, otherwise WRITE SAME (16) with the UNMAP
+    /// bit if it sets LBPWS. WRITE SAME writes a zeroed block, so the blocks read back as zeros
+    /// whether or not the device deallocates them. ANCHOR is never set, the blocks are
+    /// deallocated, not anchored.
+    ///
+    /// Returns None without sending anything if the device supports neither, or doesn't have
+    /// the VPD pages. A range beyond the limits of the Block Limits page is rejected, a count
+    /// of 0 too, since WRITE SAME would take it as the rest of the medium.
+    pub fn discard(
+        &self,
+        logical_block_address: u64,
+        count: u32,
+        logical_block_size: u32,
+    ) -> crate::Result<Option<DiscardMethod>> {
+        if count == 0 {
+            return Err(crate::Error::BadArgument(
+                "discarding 0 blocks is not allowed".to_owned(),
+            ));
+        }
+        if logical_block_address.checked_add(count as u64).is_none() {
+            return Err(crate::Error::ArgumentOutOfBounds(format!(
+                "discarding {} blocks from {} goes past the largest logical block address",
+                count, logical_block_address
+            )));
+        }
+
+        // devices without VPD pages reject the EVPD bit with INVALID FIELD IN CDB
+        let provisioning = match logical_block_provisioning(&mut self.inquiry()) {
+            Ok(provisioning) => provisioning,
+            Err(crate::Error::IllegalRequest(_)) => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        let limits = match block_limits(&mut self.inquiry()) {
+            Ok(limits) => Some(limits),
+            Err(crate::Error::IllegalRequest(_)) => None,
+            Err(error) => return Err(error),
+        };
+
+        let Some(method) = discard_method(&provisioning, limits.as_ref()) else {
+            return Ok(None);
+        };
+        range_check(method, count, limits.as_ref())?;
+
+        match method {
+            DiscardMethod::Unmap => {
+                let mut command = self.unmap();
+                command
+                    .parameter()
+                    .add_block_descriptor(logical_block_address, count)
+                    .done()?
+                    .issue()?;
+            }
+            DiscardMethod::WriteSame16 => {
+                self.write_same()
+                    .unmap(true)
+                    .logical_block_address(logical_block_address)
+                    .number_of_blocks(count)
+                    .logical_block_size(logical_block_size)
+                    .parameter(&vec![0; logical_block_size as usize])
+                    .issue_16()?;
+            }
+        }
+
+        Ok(Some(method))
+    }
+}
+
+fn discard_method(
+    provisioning: &LogicalBlockProvisioning,
+    limits: Option<&BlockLimits>,
+) -> Option<DiscardMethod> {
+    // a device that sets LBPU must report both maximums, 0 means UNMAP isn't usable after all
+    let unmap_limits = limits.is_some_and(|limits| {
+        limits.maximum_unmap_lba_count.unwrap_or(0) != 0
+            && limits.maximum_unmap_block_descriptor_count.unwrap_or(0) != 0
+    });
+
+    if provisioning.logical_block_provisioning_unmap && unmap_limits {
+        Some(DiscardMethod::Unmap)
+    } else if provisioning.logical_block_provisioning_write_same {
+        Some(DiscardMethod::WriteSame16)
+    } else {
+        None
+    }
+}
+
+fn range_check(
+    method: DiscardMethod,
+    count: u32,
+    limits: Option<&BlockLimits>,
+) -> crate::Result<()> {
+    // 0 reports no limit for WRITE SAME, UNMAP has been checked to report one
+    let maximum = match method {
+        DiscardMethod::Unmap => limits.and_then(|limits| limits.maximum_unmap_lba_count),
+        DiscardMethod::WriteSame16 => limits
+            .and_then(|limits| limits.maximum_write_same_length)
+            .filter(|&maximum| maximum != 0)
+            .map(|maximum| u32::try_from(maximum).unwrap_or(u32::MAX)),
+    };
+
+    match maximum {
+        Some(maximum) if count > maximum => Err(crate::Error::ArgumentOutOfBounds(format!(
+            "Discarded block count is out of bounds. The maximum possible value is {}, but {} was provided.",
+            maximum, count
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockResponse, MockScsi};
+
+    const PROVISIONING_INQUIRY: [u8; 6] = [0x12, 0x01, 0xB2, 0x00, 0x40, 0x00];
+    const LIMITS_INQUIRY: [u8; 6] = [0x12, 0x01, 0xB0, 0x00, 0x40, 0x00];
+
+    // flags is the byte with LBPU, LBPWS, LBPWS10, LBPRZ, ANC_SUP and DP
+    fn provisioning_page(flags: u8) -> Vec<u8> {
+        vec![0x00, 0xB2, 0x00, 0x04, 0x00, flags, 0x02, 0x00]
+    }
+
+    fn limits_page(maximum_unmap_lba_count: u32, maximum_write_same_length: u64) -> Vec<u8> {
+        let mut page = vec![0; 64];
+        page[1] = 0xB0;
+        page[3] = 0x3C;
+        page[20..24].copy_from_slice(&maximum_unmap_lba_count.to_be_bytes());
+        // one descriptor per UNMAP, or none if UNMAP can't be used
+        let descriptors = u32::from(maximum_unmap_lba_count != 0);
+        page[24..28].copy_from_slice(&descriptors.to_be_bytes());
+        page[36..44].copy_from_slice(&maximum_write_same_length.to_be_bytes());
+        page
+    }
+
+    #[test]
+    fn unmap_preferred_test() {
+        let mock = MockScsi::new();
+        mock.expect(
+            &PROVISIONING_INQUIRY,
+            // LBPU and LBPWS
+            MockResponse::good().data(&provisioning_page(0xC0)),
+        )
+        .expect(
+            &LIMITS_INQUIRY,
+            MockResponse::good().data(&limits_page(0x1_0000, 0)),
+        )
+        .expect(
+            &[0x42, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00],
+            MockResponse::good(),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        assert_eq!(
+            scsi.discard(0x1000, 0x800, 512).unwrap(),
+            Some(DiscardMethod::Unmap),
+            "discard method"
+        );
+
+        let issued = mock.issued();
+        assert_eq!(
+            issued[2].data,
+            [
+                0x00, 0x16, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x10, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00,
+            ],
+            "unmap parameter list"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    fn write_same_preferred_test() {
+        let mock = MockScsi::new();
+        mock.expect(
+            &PROVISIONING_INQUIRY,
+            // LBPU and LBPWS, but the block limits report no UNMAP limits
+            MockResponse::good().data(&provisioning_page(0xC0)),
+        )
+        .expect(
+            &LIMITS_INQUIRY,
+            MockResponse::good().data(&limits_page(0, 0)),
+        )
+        .expect(
+            &[
+                0x93, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x08, 0x00,
+                0x00, 0x00,
+            ],
+            MockResponse::good(),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        assert_eq!(
+            scsi.discard(0x1000, 0x800, 512).unwrap(),
+            Some(DiscardMethod::WriteSame16),
+            "discard method"
+        );
+        assert_eq!(mock.issued()[2].data, [0; 512], "zeroed block");
+        mock.verify();
+    }
+
+    #[test]
+    fn unsupported_test() {
+        let mock = MockScsi::new();
+        mock.expect(
+            &PROVISIONING_INQUIRY,
+            // fully provisioned
+            MockResponse::good().data(&provisioning_page(0x00)),
+        )
+        .expect(
+            &LIMITS_INQUIRY,
+            MockResponse::good().data(&limits_page(0x1_0000, 0)),
+        )
+        .expect(
+            &PROVISIONING_INQUIRY,
+            // ILLEGAL REQUEST, INVALID FIELD IN CDB
+            MockResponse::check_condition(&[
+                0x70, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x24, 0x00,
+                0x00, 0x00, 0x00, 0x00,
+            ]),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        assert_eq!(scsi.discard(0, 8, 512).unwrap(), None, "no provisioning");
+        assert_eq!(scsi.discard(0, 8, 512).unwrap(), None, "no VPD pages");
+        mock.verify();
+    }
+
+    #[test]
+    fn range_test() {
+        let mock = MockScsi::new();
+        mock.expect(
+            &PROVISIONING_INQUIRY,
+            // LBPWS only
+            MockResponse::good().data(&provisioning_page(0x40)),
+        )
+        .expect(
+            &LIMITS_INQUIRY,
+            MockResponse::good().data(&limits_page(0, 0x400)),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        assert!(
+            matches!(
+                scsi.discard(0, 0x401, 512),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "longer than the maximum write same length"
+        );
+        assert!(
+            matches!(scsi.discard(0, 0, 512), Err(crate::Error::BadArgument(_))),
+            "no blocks"
+        );
+        assert!(
+            matches!(
+                scsi.discard(u64::MAX, 1, 512),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "past the last logical block address"
+        );
+        mock.verify();
+    }
+}
@@ -5,6 +5,7 @@ use modular_bitfield_msb::prelude::*;
 use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AsciiInformation {
     pub ascii_information: Vec<String>,
     pub vendor_information: Vec<u8>,
@@ -32,17 +33,14 @@ pub fn ascii_information(
 
     let body = result.get_body();
 
-    unsafe {
-        Ok(AsciiInformation {
-            ascii_information: result.elements_as_slice()[..body.ascii_length() as usize]
-                .split(|c| *c == 0)
-                .map(|s| String::from_utf8_lossy(s).to_string())
-                .filter(|s| !s.is_empty())
-                .collect(),
-            vendor_information: result.elements_as_slice()[body.ascii_length() as usize..]
-                .to_owned(),
-        })
-    }
+    Ok(AsciiInformation {
+        ascii_information: result.elements_as_slice()[..body.ascii_length() as usize]
+            .split(|c| *c == 0)
+            .map(|s| String::from_utf8_lossy(s).to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        vendor_information: result.elements_as_slice()[body.ascii_length() as usize..].to_owned(),
+    })
 }
 
 #[bitfield]
@@ -5,18 +5,43 @@ use modular_bitfield_msb::prelude::*;
 use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockDeviceCharacteristics {
-    pub medium_rotation_rate: u16,
+    pub medium_rotation_rate: MediumRotationRate,
     pub product_type: u8,
     pub write_after_block_erase_required: u8,
     pub write_after_cryptographic_erase_required: u8,
-    pub nominal_form_factor: u8,
+    pub nominal_form_factor: NominalFormFactor,
     pub zoned: u8,
     pub background_operation_control_supported: bool,
     pub fuab: bool,
     pub vbuls: bool,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MediumRotationRate {
+    NotReported,
+    /// e.g. a solid state drive
+    NonRotating,
+    RevolutionsPerMinute(u16),
+    Reserved(u16),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum NominalFormFactor {
+    NotReported,
+    FiveAndOneQuarterInch,
+    ThreeAndOneHalfInch,
+    TwoAndOneHalfInch,
+    OnePointEightInch,
+    LessThanOnePointEightInch,
+    Reserved(u8),
+}
+
 pub fn block_device_characteristics(
     this: &mut InquiryCommand,
 ) -> crate::Result<BlockDeviceCharacteristics> {
@@ -24,19 +49,46 @@ pub fn block_device_characteristics(
 
     let result: FlexibleStruct<Page, ()> = this.issue_flex(0)?;
 
-    let body = result.get_body();
+    Ok(parse(result.get_body()))
+}
 
-    Ok(BlockDeviceCharacteristics {
-        medium_rotation_rate: body.medium_rotation_rate(),
+fn parse(body: Page) -> BlockDeviceCharacteristics {
+    BlockDeviceCharacteristics {
+        medium_rotation_rate: MediumRotationRate::from(body.medium_rotation_rate()),
         product_type: body.product_type(),
         write_after_block_erase_required: body.write_after_block_erase_required(),
         write_after_cryptographic_erase_required: body.write_after_cryptographic_erase_required(),
-        nominal_form_factor: body.nominal_form_factor(),
+        nominal_form_factor: NominalFormFactor::from(body.nominal_form_factor()),
         zoned: body.zoned(),
         background_operation_control_supported: body.background_operation_control_supported() != 0,
         fuab: body.fuab() != 0,
         vbuls: body.vbuls() != 0,
-    })
+    }
+}
+
+impl From<u16> for MediumRotationRate {
+    fn from(value: u16) -> Self {
+        match value {
+            0x0000 => Self::NotReported,
+            0x0001 => Self::NonRotating,
+            0x0401..=0xFFFE => Self::RevolutionsPerMinute(value),
+            other => Self::Reserved(other),
+        }
+    }
+}
+
+impl From<u8> for NominalFormFactor {
+    fn from(value: u8) -> Self {
+        match value {
+            0x0 => Self::NotReported,
+            0x1 => Self::FiveAndOneQuarterInch,
+            0x2 => Self::ThreeAndOneHalfInch,
+            0x3 => Self::TwoAndOneHalfInch,
+            0x4 => Self::OnePointEightInch,
+            0x5 => Self::LessThanOnePointEightInch,
+            other => Self::Reserved(other),
+        }
+    }
 }
 
 const PAGE_CODE: u8 = 0xB1;
@@ -80,4 +132,30 @@ mod tests {
             concat!("Size of: ", stringify!(Page))
         );
     }
+
+    #[test]
+    fn rotating_disk_test() {
+        let mut bytes = [0u8; PAGE_LENGTH];
+        bytes[..9].copy_from_slice(&[0x00, 0xB1, 0x00, 0x3C, 0x1C, 0x20, 0x00, 0x02, 0x01]);
+
+        let characteristics = parse(Page::from_bytes(bytes));
+        assert_eq!(
+            characteristics.medium_rotation_rate,
+            MediumRotationRate::RevolutionsPerMinute(7200),
+            "medium rotation rate"
+        );
+        assert_eq!(
+            characteristics.nominal_form_factor,
+            NominalFormFactor::ThreeAndOneHalfInch,
+            "nominal form factor"
+        );
+        assert!(characteristics.vbuls, "vbuls");
+        assert!(!characteristics.fuab, "fuab");
+
+        assert_eq!(
+            MediumRotationRate::from(0x0001),
+            MediumRotationRate::NonRotating,
+            "solid state medium"
+        );
+    }
 }
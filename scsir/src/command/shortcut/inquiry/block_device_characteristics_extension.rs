@@ -5,6 +5,7 @@ use modular_bitfield_msb::prelude::*;
 use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockDeviceCharacteristicsExtension {
     pub utilization_type: u8,
     pub utilization_units: u8,
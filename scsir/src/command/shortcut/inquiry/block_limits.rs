@@ -5,23 +5,25 @@ use modular_bitfield_msb::prelude::*;
 use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockLimits {
     pub write_same_non_zero: bool,
     pub maximum_compare_and_write_length: u8,
     pub optimal_transfer_length_granularity: u16,
     pub maximum_transfer_length: u32,
     pub optimal_transfer_length: u32,
-    pub maximum_prefetch_length: u32,
-    pub maximum_unmap_lba_count: u32,
-    pub maximum_unmap_block_descriptor_count: u32,
-    pub optimal_unmap_granularity: u32,
+    // Fields below are None if the device returned a short page
+    pub maximum_prefetch_length: Option<u32>,
+    pub maximum_unmap_lba_count: Option<u32>,
+    pub maximum_unmap_block_descriptor_count: Option<u32>,
+    pub optimal_unmap_granularity: Option<u32>,
     pub unmap_granularity_alignment: Option<u32>,
-    pub maximum_write_same_length: u64,
-    pub maximum_atomic_transfer_length: u32,
-    pub atomic_alignment: u32,
-    pub atomic_transfer_length_granularity: u32,
-    pub maximum_atomic_transfer_length_with_atomic_boundary: u32,
-    pub maximum_atomic_boundary_size: u32,
+    pub maximum_write_same_length: Option<u64>,
+    pub maximum_atomic_transfer_length: Option<u32>,
+    pub atomic_alignment: Option<u32>,
+    pub atomic_transfer_length_granularity: Option<u32>,
+    pub maximum_atomic_transfer_length_with_atomic_boundary: Option<u32>,
+    pub maximum_atomic_boundary_size: Option<u32>,
 }
 
 pub fn block_limits(this: &mut InquiryCommand) -> crate::Result<BlockLimits> {
@@ -29,32 +31,40 @@ pub fn block_limits(this: &mut InquiryCommand) -> crate::Result<BlockLimits> {
 
     let result: FlexibleStruct<Page, ()> = this.issue_flex(0)?;
 
-    let body = result.get_body();
+    Ok(parse(result.get_body()))
+}
+
+fn parse(body: Page) -> BlockLimits {
+    // page length doesn't include the first 4 bytes
+    let returned_length = body.page_length() as usize + 4;
+    let present = |end: usize| returned_length >= end;
 
-    let unmap_granularity_alignment = match body.unmap_granularity_alignment_valid() != 0 {
-        true => Some(body.unmap_granularity_alignment()),
-        false => None,
-    };
+    let unmap_granularity_alignment = (present(36)
+        && body.unmap_granularity_alignment_valid() != 0)
+        .then_some(body.unmap_granularity_alignment());
 
-    Ok(BlockLimits {
+    BlockLimits {
         write_same_non_zero: body.write_same_non_zero() != 0,
         maximum_compare_and_write_length: body.maximum_compare_and_write_length(),
         optimal_transfer_length_granularity: body.optimal_transfer_length_granularity(),
         maximum_transfer_length: body.maximum_transfer_length(),
         optimal_transfer_length: body.optimal_transfer_length(),
-        maximum_prefetch_length: body.maximum_prefetch_length(),
-        maximum_unmap_lba_count: body.maximum_unmap_lba_count(),
-        maximum_unmap_block_descriptor_count: body.maximum_unmap_block_descriptor_count(),
-        optimal_unmap_granularity: body.optimal_unmap_granularity(),
+        maximum_prefetch_length: present(20).then_some(body.maximum_prefetch_length()),
+        maximum_unmap_lba_count: present(24).then_some(body.maximum_unmap_lba_count()),
+        maximum_unmap_block_descriptor_count: present(28)
+            .then_some(body.maximum_unmap_block_descriptor_count()),
+        optimal_unmap_granularity: present(32).then_some(body.optimal_unmap_granularity()),
         unmap_granularity_alignment,
-        maximum_write_same_length: body.maximum_write_same_length(),
-        maximum_atomic_transfer_length: body.maximum_atomic_transfer_length(),
-        atomic_alignment: body.atomic_alignment(),
-        atomic_transfer_length_granularity: body.atomic_transfer_length_granularity(),
-        maximum_atomic_transfer_length_with_atomic_boundary: body
-            .maximum_atomic_transfer_length_with_atomic_boundary(),
-        maximum_atomic_boundary_size: body.maximum_atomic_boundary_size(),
-    })
+        maximum_write_same_length: present(44).then_some(body.maximum_write_same_length()),
+        maximum_atomic_transfer_length: present(48)
+            .then_some(body.maximum_atomic_transfer_length()),
+        atomic_alignment: present(52).then_some(body.atomic_alignment()),
+        atomic_transfer_length_granularity: present(56)
+            .then_some(body.atomic_transfer_length_granularity()),
+        maximum_atomic_transfer_length_with_atomic_boundary: present(60)
+            .then_some(body.maximum_atomic_transfer_length_with_atomic_boundary()),
+        maximum_atomic_boundary_size: present(64).then_some(body.maximum_atomic_boundary_size()),
+    }
 }
 
 const PAGE_CODE: u8 = 0xB0;
@@ -101,4 +111,39 @@ mod tests {
             concat!("Size of: ", stringify!(Page))
         );
     }
+
+    #[test]
+    fn short_page_test() {
+        let mut bytes = [0u8; PAGE_LENGTH];
+        bytes[..16].copy_from_slice(&[
+            0x00, 0xB0, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00,
+            0x01, 0x00,
+        ]);
+
+        let limits = parse(Page::from_bytes(bytes));
+        assert_eq!(limits.optimal_transfer_length_granularity, 8, "granularity");
+        assert_eq!(
+            limits.maximum_transfer_length, 0xFFFF,
+            "maximum transfer length"
+        );
+        assert_eq!(
+            limits.optimal_transfer_length, 0x100,
+            "optimal transfer length"
+        );
+        assert_eq!(limits.maximum_prefetch_length, None, "past the short page");
+        assert_eq!(limits.maximum_unmap_lba_count, None, "past the short page");
+        assert_eq!(
+            limits.maximum_write_same_length, None,
+            "past the short page"
+        );
+
+        bytes[3] = 0x3C;
+        bytes[20..24].copy_from_slice(&[0x00, 0x40, 0x00, 0x00]);
+        bytes[32..36].copy_from_slice(&[0x80, 0x00, 0x00, 0x08]);
+
+        let limits = parse(Page::from_bytes(bytes));
+        assert_eq!(limits.maximum_unmap_lba_count, Some(0x40_0000), "full page");
+        assert_eq!(limits.unmap_granularity_alignment, Some(8), "alignment");
+        assert_eq!(limits.maximum_atomic_boundary_size, Some(0), "full page");
+    }
 }
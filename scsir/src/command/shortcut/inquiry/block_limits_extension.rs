@@ -5,6 +5,7 @@ use modular_bitfield_msb::prelude::*;
 use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockLimitsExtension {
     pub maximum_number_of_streams: u16,
     pub optimal_stream_write_size: u16,
@@ -30,7 +31,7 @@ pub fn block_limits_extension(this: &mut InquiryCommand) -> crate::Result<BlockL
         maximum_number_of_streams: body.maximum_number_of_streams(),
         optimal_stream_write_size: body.optimal_stream_write_size(),
         stream_granularity_size: body.stream_granularity_size(),
-        additional_data: unsafe { result.elements_as_slice().to_vec() },
+        additional_data: result.elements_as_slice().to_vec(),
     })
 }
 
@@ -8,19 +8,23 @@ use crate::{
 };
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceIdentification {
     pub descriptors: Vec<IdentificationDescriptor>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IdentificationDescriptor {
     pub protocol_identifier: ProtocolIdentifier,
     pub association: Association,
-    pub identifier_type: u8,
+    pub identifier_type: IdentifierType,
     pub identifier: Identifier,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ProtocolIdentifier {
     None,
     FibreChannel,
@@ -33,13 +37,56 @@ pub enum ProtocolIdentifier {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum IdentifierType {
+    VendorSpecific,
+    T10VendorIdentification,
+    Eui64,
+    Naa,
+    RelativeTargetPort,
+    TargetPortGroup,
+    LogicalUnitGroup,
+    Md5LogicalUnit,
+    ScsiNameString,
+    ProtocolSpecificPortIdentifier,
+    Uuid,
+    Other(u8),
+}
+
+impl IdentifierType {
+    // a stable name for logs and serialized output, reserved values are all "other"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::VendorSpecific => "vendor_specific",
+            Self::T10VendorIdentification => "t10_vendor_identification",
+            Self::Eui64 => "eui64",
+            Self::Naa => "naa",
+            Self::RelativeTargetPort => "relative_target_port",
+            Self::TargetPortGroup => "target_port_group",
+            Self::LogicalUnitGroup => "logical_unit_group",
+            Self::Md5LogicalUnit => "md5_logical_unit",
+            Self::ScsiNameString => "scsi_name_string",
+            Self::ProtocolSpecificPortIdentifier => "protocol_specific_port_identifier",
+            Self::Uuid => "uuid",
+            Self::Other(_) => "other",
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Identifier {
     Binary(Vec<u8>),
     Ascii(String),
+    Utf8(String),
     Unknown(Vec<u8>),
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Association {
     AddressedPhysicalOrLogicalDevice,
     PortThatReceivedTheRequest,
@@ -47,6 +94,19 @@ pub enum Association {
     Other(u8),
 }
 
+impl Association {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AddressedPhysicalOrLogicalDevice => "addressed_physical_or_logical_device",
+            Self::PortThatReceivedTheRequest => "port_that_received_the_request",
+            Self::ScsiTargetDeviceThatContainsTheAddressedLogicalUnit => {
+                "scsi_target_device_that_contains_the_addressed_logical_unit"
+            }
+            Self::Other(_) => "other",
+        }
+    }
+}
+
 pub fn device_identification(this: &mut InquiryCommand) -> crate::Result<DeviceIdentification> {
     this.page_code(Some(PAGE_CODE));
 
@@ -58,7 +118,10 @@ pub fn device_identification(this: &mut InquiryCommand) -> crate::Result<DeviceI
         this.issue_flex(remaining as usize)?
     };
 
-    let mut bytes = unsafe { result.elements_as_slice() };
+    Ok(parse(result.elements_as_slice()))
+}
+
+fn parse(mut bytes: &[u8]) -> DeviceIdentification {
     let mut descriptors = vec![];
 
     while !bytes.is_empty() {
@@ -67,10 +130,30 @@ pub fn device_identification(this: &mut InquiryCommand) -> crate::Result<DeviceI
         descriptors.push(descriptor);
     }
 
-    Ok(DeviceIdentification { descriptors })
+    DeviceIdentification { descriptors }
 }
 
 impl IdentificationDescriptor {
+    /// 8 bytes NAA or EUI-64 identifier
+    pub fn as_u64(&self) -> Option<u64> {
+        match (&self.identifier_type, &self.identifier) {
+            (IdentifierType::Naa | IdentifierType::Eui64, Identifier::Binary(bytes)) => {
+                Some(u64::from_be_bytes(bytes.as_slice().try_into().ok()?))
+            }
+            _ => None,
+        }
+    }
+
+    /// 16 bytes NAA (IEEE Registered Extended) or EUI-64 based identifier
+    pub fn as_u128(&self) -> Option<u128> {
+        match (&self.identifier_type, &self.identifier) {
+            (IdentifierType::Naa | IdentifierType::Eui64, Identifier::Binary(bytes)) => {
+                Some(u128::from_be_bytes(bytes.as_slice().try_into().ok()?))
+            }
+            _ => None,
+        }
+    }
+
     fn from_bytes(bytes: &[u8]) -> (Self, &[u8]) {
         let (array, bytes) = get_array(bytes);
         let descriptor_header = DescriptorHeader::from_bytes(array);
@@ -103,9 +186,30 @@ impl IdentificationDescriptor {
             bytes.len(),
         ));
 
+        let identifier_type = match descriptor_header.identifier_type() {
+            0x0 => IdentifierType::VendorSpecific,
+            0x1 => IdentifierType::T10VendorIdentification,
+            0x2 => IdentifierType::Eui64,
+            0x3 => IdentifierType::Naa,
+            0x4 => IdentifierType::RelativeTargetPort,
+            0x5 => IdentifierType::TargetPortGroup,
+            0x6 => IdentifierType::LogicalUnitGroup,
+            0x7 => IdentifierType::Md5LogicalUnit,
+            0x8 => IdentifierType::ScsiNameString,
+            0x9 => IdentifierType::ProtocolSpecificPortIdentifier,
+            0xA => IdentifierType::Uuid,
+            other => IdentifierType::Other(other),
+        };
+
         let identifier = match descriptor_header.code_set() {
             0x1 => Identifier::Binary(identifier_bytes.to_owned()),
             0x2 => Identifier::Ascii(String::from_utf8_lossy(identifier_bytes).to_string()),
+            // SCSI name strings are null terminated and padded to a multiple of 4 bytes
+            0x3 => Identifier::Utf8(
+                String::from_utf8_lossy(identifier_bytes)
+                    .trim_end_matches('\0')
+                    .to_string(),
+            ),
             _ => Identifier::Unknown(identifier_bytes.to_owned()),
         };
 
@@ -113,7 +217,7 @@ impl IdentificationDescriptor {
             IdentificationDescriptor {
                 protocol_identifier,
                 association,
-                identifier_type: descriptor_header.identifier_type(),
+                identifier_type,
                 identifier,
             },
             bytes,
@@ -167,4 +271,52 @@ mod tests {
             concat!("Size of: ", stringify!(DescriptorHeader))
         );
     }
+
+    #[test]
+    fn parse_test() {
+        let bytes = [
+            // NAA, binary, addressed logical unit
+            0x01, 0x03, 0x00, 0x08, 0x50, 0x00, 0xC5, 0x00, 0x12, 0x34, 0x56, 0x78,
+            // SCSI name string, UTF-8, target port
+            0x63, 0x98, 0x00, 0x0C, b'n', b'a', b'a', b'.', b'5', b'0', b'0', b'0', b'C', b'5',
+            0x00, 0x00,
+        ];
+
+        let page = parse(&bytes);
+        assert_eq!(page.descriptors.len(), 2, "descriptor count");
+
+        let naa = &page.descriptors[0];
+        assert!(
+            matches!(naa.identifier_type, IdentifierType::Naa),
+            "NAA type"
+        );
+        assert_eq!(naa.as_u64(), Some(0x5000_C500_1234_5678), "NAA value");
+        assert_eq!(naa.as_u128(), None, "NAA is only 8 bytes");
+
+        let name = &page.descriptors[1];
+        assert!(
+            matches!(name.identifier_type, IdentifierType::ScsiNameString),
+            "SCSI name string type"
+        );
+        assert!(
+            matches!(
+                name.protocol_identifier,
+                ProtocolIdentifier::SasSerialScsiProtocol
+            ),
+            "SAS protocol"
+        );
+        assert!(
+            matches!(name.association, Association::PortThatReceivedTheRequest),
+            "target port association"
+        );
+        assert_eq!(
+            (name.identifier_type.name(), name.association.name()),
+            ("scsi_name_string", "port_that_received_the_request"),
+            "stable names"
+        );
+        assert!(
+            matches!(&name.identifier, Identifier::Utf8(s) if s == "naa.5000C5"),
+            "SCSI name string"
+        );
+    }
 }
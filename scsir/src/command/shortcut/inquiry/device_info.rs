@@ -0,0 +1,99 @@
+use crate::Scsi;
+
+use super::{standard_inquiry, unit_serial_number, PeripheralDeviceType};
+
+/// What the device is, see [`Scsi::device_info`]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceInfo {
+    pub vendor: String,
+    pub product: String,
+    pub revision: String,
+    /// None if the device has no Unit Serial Number VPD page, or reports an empty one
+    pub serial: Option<String>,
+    pub device_type: PeripheralDeviceType,
+}
+
+impl Scsi {
+    /// Issues a standard INQUIRY and reads the Unit Serial Number VPD page.
+    pub fn device_info(&self) -> crate::Result<DeviceInfo> {
+        let standard = standard_inquiry(&mut self.inquiry())?;
+
+        // devices without VPD pages reject the EVPD bit with INVALID FIELD IN CDB
+        let serial = match unit_serial_number(&mut self.inquiry()) {
+            Ok(serial) => Some(serial.product_serial_number),
+            Err(crate::Error::IllegalRequest(_)) => None,
+            Err(error) => return Err(error),
+        };
+
+        Ok(DeviceInfo {
+            vendor: standard.t10_vendor_identification,
+            product: standard.product_identification,
+            revision: standard.product_revision_level,
+            serial: serial.filter(|serial| !serial.is_empty()),
+            device_type: standard.peripheral_device_type,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockResponse, MockScsi};
+
+    const STANDARD_INQUIRY: [u8; 6] = [0x12, 0x00, 0x00, 0x00, 0x60, 0x00];
+    // the header, then the whole page
+    const SERIAL_NUMBER_HEADER_INQUIRY: [u8; 6] = [0x12, 0x01, 0x80, 0x00, 0x04, 0x00];
+    const SERIAL_NUMBER_INQUIRY: [u8; 6] = [0x12, 0x01, 0x80, 0x00, 0x0E, 0x00];
+
+    // a SATA disk behind a SAT layer
+    const STANDARD_INQUIRY_DATA: &[u8] =
+        b"\x00\x00\x06\x02\x1F\x00\x00\x00ATA     Samsung SSD 860 2B6Q";
+    const UNIT_SERIAL_NUMBER: &[u8] = b"\x00\x80\x00\x0A  S3Z1NB0K";
+
+    #[test]
+    fn device_info_test() {
+        let mock = MockScsi::new();
+        mock.expect(
+            &STANDARD_INQUIRY,
+            MockResponse::good().data(STANDARD_INQUIRY_DATA),
+        )
+        .expect(
+            &SERIAL_NUMBER_HEADER_INQUIRY,
+            MockResponse::good().data(UNIT_SERIAL_NUMBER),
+        )
+        .expect(
+            &SERIAL_NUMBER_INQUIRY,
+            MockResponse::good().data(UNIT_SERIAL_NUMBER),
+        )
+        .expect(
+            &STANDARD_INQUIRY,
+            MockResponse::good().data(STANDARD_INQUIRY_DATA),
+        )
+        // ILLEGAL REQUEST, INVALID FIELD IN CDB
+        .expect(
+            &SERIAL_NUMBER_HEADER_INQUIRY,
+            MockResponse::check_condition(&[
+                0x70, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x24, 0x00,
+                0x00, 0x00, 0x00, 0x00,
+            ]),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let info = scsi.device_info().unwrap();
+        assert_eq!(info.vendor, "ATA", "vendor");
+        assert_eq!(info.product, "Samsung SSD 860", "product");
+        assert_eq!(info.revision, "2B6Q", "revision");
+        assert_eq!(info.serial.as_deref(), Some("S3Z1NB0K"), "serial");
+        assert_eq!(
+            info.device_type,
+            PeripheralDeviceType::DirectAccessBlock,
+            "device type"
+        );
+
+        let info = scsi.device_info().unwrap();
+        assert_eq!(info.product, "Samsung SSD 860", "product without VPD");
+        assert_eq!(info.serial, None, "no serial number page");
+        mock.verify();
+    }
+}
@@ -7,6 +7,7 @@ use modular_bitfield_msb::prelude::*;
 use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LogicalBlockProvisioning {
     pub threshold_exponent: u8,
     pub logical_block_provisioning_unmap: bool,
@@ -14,12 +15,24 @@ pub struct LogicalBlockProvisioning {
     pub logical_block_provisioning_write_same_10: bool,
     pub logical_block_provisioning_read_zeros: u8,
     pub anchor_supported: bool,
+    pub descriptor_present: bool,
     pub minimum_percentage: u8,
-    pub provisioning_type: u8,
+    pub provisioning_type: ProvisioningType,
     pub threshold_percentage: u8,
     pub descriptors: Vec<u8>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ProvisioningType {
+    /// fully provisioned, or the type is not reported
+    NotReported,
+    ResourceProvisioned,
+    ThinProvisioned,
+    Reserved(u8),
+}
+
 pub fn logical_block_provisioning(
     this: &mut InquiryCommand,
 ) -> crate::Result<LogicalBlockProvisioning> {
@@ -27,17 +40,23 @@ pub fn logical_block_provisioning(
 
     let result: FlexibleStruct<PageHeader, u8> = this.issue_flex(64 - size_of::<PageHeader>())?;
 
-    let body = result.get_body();
+    let elements = result.elements_as_slice();
+
+    Ok(parse(result.get_body(), elements))
+}
 
+fn parse(body: PageHeader, elements: &[u8]) -> LogicalBlockProvisioning {
     let descriptor_present = body.descriptor_present() != 0;
 
+    // page length counts the 4 bytes from threshold exponent to threshold percentage
     let descriptors = if descriptor_present {
-        unsafe { Vec::from(&result.elements_as_slice()[..=body.page_length() as usize - 4]) }
+        let length = (body.page_length() as usize).saturating_sub(4);
+        Vec::from(&elements[..usize::min(length, elements.len())])
     } else {
         vec![]
     };
 
-    Ok(LogicalBlockProvisioning {
+    LogicalBlockProvisioning {
         threshold_exponent: body.threshold_exponent(),
         logical_block_provisioning_unmap: body.logical_block_provisioning_unmap() != 0,
         logical_block_provisioning_write_same: body.logical_block_provisioning_write_same() != 0,
@@ -45,11 +64,23 @@ pub fn logical_block_provisioning(
             != 0,
         logical_block_provisioning_read_zeros: body.logical_block_provisioning_read_zeros(),
         anchor_supported: body.anchor_supported() != 0,
+        descriptor_present,
         minimum_percentage: body.minimum_percentage(),
-        provisioning_type: body.provisioning_type(),
+        provisioning_type: ProvisioningType::from(body.provisioning_type()),
         threshold_percentage: body.threshold_percentage(),
         descriptors,
-    })
+    }
+}
+
+impl From<u8> for ProvisioningType {
+    fn from(value: u8) -> Self {
+        match value {
+            0b000 => Self::NotReported,
+            0b001 => Self::ResourceProvisioned,
+            0b010 => Self::ThinProvisioned,
+            other => Self::Reserved(other),
+        }
+    }
 }
 
 const PAGE_CODE: u8 = 0xB2;
@@ -88,4 +119,25 @@ mod tests {
             concat!("Size of: ", stringify!(PageHeader))
         );
     }
+
+    #[test]
+    fn parse_test() {
+        let header = PageHeader::from_bytes([0x00, 0xB2, 0x00, 0x08, 0x0C, 0xE5, 0x02, 0x00]);
+        let descriptors = [0x01, 0x03, 0x00, 0x00, 0xFF, 0xFF];
+
+        let page = parse(header, &descriptors);
+        assert_eq!(page.threshold_exponent, 0x0C, "threshold exponent");
+        assert!(page.logical_block_provisioning_unmap, "lbpu");
+        assert!(page.logical_block_provisioning_write_same, "lbpws");
+        assert!(page.logical_block_provisioning_write_same_10, "lbpws10");
+        assert_eq!(page.logical_block_provisioning_read_zeros, 0b001, "lbprz");
+        assert!(!page.anchor_supported, "anc_sup");
+        assert!(page.descriptor_present, "dp");
+        assert_eq!(
+            page.provisioning_type,
+            ProvisioningType::ThinProvisioned,
+            "provisioning type"
+        );
+        assert_eq!(page.descriptors, [0x01, 0x03, 0x00, 0x00], "descriptors");
+    }
 }
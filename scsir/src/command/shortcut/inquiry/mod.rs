@@ -4,6 +4,7 @@ mod block_device_characteristics_extension;
 mod block_limits;
 mod block_limits_extension;
 mod device_identification;
+mod device_info;
 mod extended_inquiry_data;
 mod logical_block_provisioning;
 mod mode_page_policy;
@@ -13,21 +14,31 @@ mod scsi_ports;
 mod standard_inquiry;
 mod supported_vital_product_data_pages;
 mod unit_serial_number;
+mod vital_product_data;
 mod zoned_block_device_characteristics;
 
 pub use ascii_information::ascii_information;
-pub use block_device_characteristics::block_device_characteristics;
+pub use block_device_characteristics::{
+    block_device_characteristics, BlockDeviceCharacteristics, MediumRotationRate, NominalFormFactor,
+};
 pub use block_device_characteristics_extension::block_device_characteristics_extension;
-pub use block_limits::block_limits;
+pub use block_limits::{block_limits, BlockLimits};
 pub use block_limits_extension::block_limits_extension;
-pub use device_identification::device_identification;
+pub use device_identification::{
+    device_identification, Association, DeviceIdentification, IdentificationDescriptor, Identifier,
+    IdentifierType, ProtocolIdentifier,
+};
+pub use device_info::DeviceInfo;
 pub use extended_inquiry_data::extended_inquiry_data;
-pub use logical_block_provisioning::logical_block_provisioning;
+pub use logical_block_provisioning::{
+    logical_block_provisioning, LogicalBlockProvisioning, ProvisioningType,
+};
 pub use mode_page_policy::mode_page_policy;
 pub use power_condition::power_condition;
 pub use power_consumption::power_consumption;
 pub use scsi_ports::scsi_ports;
-pub use standard_inquiry::standard_inquiry;
+pub use standard_inquiry::{identification_string, standard_inquiry, PeripheralDeviceType};
 pub use supported_vital_product_data_pages::supported_vital_product_data_pages;
 pub use unit_serial_number::unit_serial_number;
+pub use vital_product_data::{vital_product_data, VitalProductData};
 pub use zoned_block_device_characteristics::zoned_block_device_characteristics;
@@ -4,14 +4,19 @@ use std::mem::size_of;
 
 use modular_bitfield_msb::prelude::*;
 
-use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
+use crate::{
+    command::inquiry::InquiryCommand,
+    data_wrapper::{impl_pod, FlexibleStruct},
+};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ModePagePolicy {
     pub descriptors: Vec<ModePagePolicyDescriptor>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ModePagePolicyDescriptor {
     pub policy_page_code: u8,
     pub policy_subpage_code: u8,
@@ -30,20 +35,18 @@ pub fn mode_page_policy(this: &mut InquiryCommand) -> crate::Result<ModePagePoli
         this.issue_flex(remaining)?
     };
 
-    unsafe {
-        Ok(ModePagePolicy {
-            descriptors: result
-                .elements_as_slice()
-                .iter()
-                .map(|e| ModePagePolicyDescriptor {
-                    policy_page_code: e.policy_page_code(),
-                    policy_subpage_code: e.policy_subpage_code(),
-                    multiple_logical_units_share: e.multiple_logical_units_share() != 0,
-                    mode_page_policy: e.mode_page_policy(),
-                })
-                .collect(),
-        })
-    }
+    Ok(ModePagePolicy {
+        descriptors: result
+            .elements_as_slice()
+            .iter()
+            .map(|e| ModePagePolicyDescriptor {
+                policy_page_code: e.policy_page_code(),
+                policy_subpage_code: e.policy_subpage_code(),
+                multiple_logical_units_share: e.multiple_logical_units_share() != 0,
+                mode_page_policy: e.mode_page_policy(),
+            })
+            .collect(),
+    })
 }
 
 const PAGE_CODE: u8 = 0x87;
@@ -69,6 +72,8 @@ struct Descriptor {
     reserved_2: B8,
 }
 
+impl_pod!(Descriptor);
+
 #[cfg(test)]
 mod tests {
     use super::*;
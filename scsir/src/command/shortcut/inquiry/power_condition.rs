@@ -7,6 +7,7 @@ use modular_bitfield_msb::prelude::*;
 use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PowerCondition {
     pub stopped_condition_recovery_time: Duration,
     pub standby_z_condition_recovery_time: Option<Duration>,
@@ -4,14 +4,19 @@ use std::mem::size_of;
 
 use modular_bitfield_msb::prelude::*;
 
-use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
+use crate::{
+    command::inquiry::InquiryCommand,
+    data_wrapper::{impl_pod, FlexibleStruct},
+};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceIdentification {
     pub descriptors: Vec<PowerConsumptionDescriptor>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PowerConsumptionDescriptor {
     pub power_consumption_identifier: u8,
     pub power_consumption_in_microwatts: u64,
@@ -29,7 +34,7 @@ pub fn power_consumption(this: &mut InquiryCommand) -> crate::Result<DeviceIdent
 
     let mut descriptors = vec![];
 
-    for item in unsafe { result.elements_as_slice() } {
+    for item in result.elements_as_slice() {
         let multiplier = match item.power_consumption_units() {
             0b0101 => 1,
             0b0100 => 1_000,
@@ -71,6 +76,8 @@ struct Descriptor {
     power_consumption_value: B16,
 }
 
+impl_pod!(Descriptor);
+
 #[cfg(test)]
 mod tests {
     use super::*;
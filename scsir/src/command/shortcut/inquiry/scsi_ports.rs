@@ -10,11 +10,13 @@ use crate::{
 };
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScsiPorts {
     pub scsi_port_designation_descriptors: Vec<ScsiPortDesignationDescriptor>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScsiPortDesignationDescriptor {
     pub relative_port_identifier: u16,
     pub initiator_port_transportid: Vec<u8>,
@@ -22,6 +24,7 @@ pub struct ScsiPortDesignationDescriptor {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TargetPortDescriptor {
     pub protocol_identifier: ProtocolIdentifier,
     pub designator_type: u8,
@@ -29,6 +32,8 @@ pub struct TargetPortDescriptor {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ProtocolIdentifier {
     None,
     FibreChannel,
@@ -41,6 +46,8 @@ pub enum ProtocolIdentifier {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Designator {
     Binary(Vec<u8>),
     Ascii(String),
@@ -58,7 +65,7 @@ pub fn scsi_ports(this: &mut InquiryCommand) -> crate::Result<ScsiPorts> {
         this.issue_flex(remaining as usize)?
     };
 
-    let mut bytes = unsafe { result.elements_as_slice() };
+    let mut bytes = result.elements_as_slice();
 
     let mut descriptors = vec![];
     while !bytes.is_empty() {
@@ -169,6 +176,8 @@ pub struct ScsiPortDesignationDescriptorHeader {
     initiator_port_transportid_length: B16,
 }
 
+crate::command::impl_bitfield_serde!(ScsiPortDesignationDescriptorHeader);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 struct TargetPortDescriptorHeader {
@@ -7,9 +7,10 @@ use modular_bitfield_msb::prelude::*;
 use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StandardInquiryData {
     pub peripheral_qualifier: u8,
-    pub peripheral_device_type: u8,
+    pub peripheral_device_type: PeripheralDeviceType,
     pub removable_media: bool,
     pub version: u8,
     pub normal_aca_supported: bool,
@@ -32,26 +33,122 @@ pub struct StandardInquiryData {
     pub copyright: String,
 }
 
+/// The PERIPHERAL DEVICE TYPE of INQUIRY data, the command set the logical unit implements
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PeripheralDeviceType {
+    /// SBC, e.g. a disk
+    DirectAccessBlock,
+    /// SSC, e.g. a tape drive
+    SequentialAccess,
+    Processor,
+    WriteOnce,
+    CdDvd,
+    OpticalMemory,
+    MediumChanger,
+    StorageArrayController,
+    EnclosureServices,
+    SimplifiedDirectAccess,
+    ObjectStorage,
+    /// host managed zoned block device
+    Zbc,
+    Unknown(u8),
+}
+
+impl PeripheralDeviceType {
+    // a stable name for logs and serialized output
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::DirectAccessBlock => "direct_access_block",
+            Self::SequentialAccess => "sequential_access",
+            Self::Processor => "processor",
+            Self::WriteOnce => "write_once",
+            Self::CdDvd => "cd_dvd",
+            Self::OpticalMemory => "optical_memory",
+            Self::MediumChanger => "medium_changer",
+            Self::StorageArrayController => "storage_array_controller",
+            Self::EnclosureServices => "enclosure_services",
+            Self::SimplifiedDirectAccess => "simplified_direct_access",
+            Self::ObjectStorage => "object_storage",
+            Self::Zbc => "zbc",
+            Self::Unknown(_) => "unknown",
+        }
+    }
+}
+
+impl From<u8> for PeripheralDeviceType {
+    fn from(value: u8) -> Self {
+        match value {
+            0x00 => Self::DirectAccessBlock,
+            0x01 => Self::SequentialAccess,
+            0x03 => Self::Processor,
+            0x04 => Self::WriteOnce,
+            0x05 => Self::CdDvd,
+            0x07 => Self::OpticalMemory,
+            0x08 => Self::MediumChanger,
+            0x0C => Self::StorageArrayController,
+            0x0D => Self::EnclosureServices,
+            0x0E => Self::SimplifiedDirectAccess,
+            0x11 => Self::ObjectStorage,
+            0x14 => Self::Zbc,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+impl From<PeripheralDeviceType> for u8 {
+    fn from(value: PeripheralDeviceType) -> Self {
+        match value {
+            PeripheralDeviceType::DirectAccessBlock => 0x00,
+            PeripheralDeviceType::SequentialAccess => 0x01,
+            PeripheralDeviceType::Processor => 0x03,
+            PeripheralDeviceType::WriteOnce => 0x04,
+            PeripheralDeviceType::CdDvd => 0x05,
+            PeripheralDeviceType::OpticalMemory => 0x07,
+            PeripheralDeviceType::MediumChanger => 0x08,
+            PeripheralDeviceType::StorageArrayController => 0x0C,
+            PeripheralDeviceType::EnclosureServices => 0x0D,
+            PeripheralDeviceType::SimplifiedDirectAccess => 0x0E,
+            PeripheralDeviceType::ObjectStorage => 0x11,
+            PeripheralDeviceType::Zbc => 0x14,
+            PeripheralDeviceType::Unknown(other) => other,
+        }
+    }
+}
+
 pub fn standard_inquiry(this: &mut InquiryCommand) -> crate::Result<StandardInquiryData> {
     this.page_code(None);
 
-    let result: FlexibleStruct<PageHeader, u8> = this.issue_flex(0)?;
-    let remaining =
-        (result.get_body().additional_length() + 5).saturating_sub(size_of::<PageHeader>() as u8);
-    let result = if remaining == 0 {
-        result
+    let (result, transfered): (FlexibleStruct<PageHeader, u8>, _) =
+        this.issue_flex_transfered(0)?;
+    // an ADDITIONAL LENGTH above 250 doesn't fit in a u8 once the first 5 bytes are added
+    let remaining = (result.get_body().additional_length() as usize + 5)
+        .saturating_sub(size_of::<PageHeader>());
+    let (result, transfered) = if remaining == 0 {
+        (result, transfered)
     } else {
-        this.issue_flex(remaining as usize)?
+        this.issue_flex_transfered(remaining)?
     };
 
-    let body = result.get_body();
+    let copyright = result.elements_as_slice();
 
+    Ok(parse(result.get_body(), copyright, transfered))
+}
+
+// Unused bytes of a short response stay zeroed, so fields that weren't returned come out empty.
+// transfered counts the header too
+fn parse(body: PageHeader, copyright: &[u8], transfered: usize) -> StandardInquiryData {
+    let copyright = &copyright[..usize::min(
+        transfered.saturating_sub(size_of::<PageHeader>()),
+        copyright.len(),
+    )];
     let vendor_unique = body.vendor_unique().to_be_bytes();
     let vendor_unique: [u8; 12] = vendor_unique[0..12].try_into().unwrap();
 
-    Ok(StandardInquiryData {
+    StandardInquiryData {
         peripheral_qualifier: body.peripheral_qualifier(),
-        peripheral_device_type: body.peripheral_device_type(),
+        peripheral_device_type: PeripheralDeviceType::from(body.peripheral_device_type()),
         removable_media: body.removable_media() != 0,
         version: body.version(),
         normal_aca_supported: body.normal_aca_supported() != 0,
@@ -65,18 +162,11 @@ pub fn standard_inquiry(this: &mut InquiryCommand) -> crate::Result<StandardInqu
         enclosure_services: body.enclosure_services() != 0,
         multi_port: body.multi_port() != 0,
         command_queuing: body.command_queuing() != 0,
-        t10_vendor_identification: String::from_utf8_lossy(
+        t10_vendor_identification: identification_string(
             &body.t10_vendor_identification().to_be_bytes(),
-        )
-        .to_string(),
-        product_identification: String::from_utf8_lossy(
-            &body.product_identification().to_be_bytes(),
-        )
-        .to_string(),
-        product_revision_level: String::from_utf8_lossy(
-            &body.product_revision_level().to_be_bytes(),
-        )
-        .to_string(),
+        ),
+        product_identification: identification_string(&body.product_identification().to_be_bytes()),
+        product_revision_level: identification_string(&body.product_revision_level().to_be_bytes()),
         drive_serial_number: body.drive_serial_number(),
         vendor_unique,
         version_descriptors: [
@@ -89,8 +179,31 @@ pub fn standard_inquiry(this: &mut InquiryCommand) -> crate::Result<StandardInqu
             body.version_descriptor_7(),
             body.version_descriptor_8(),
         ],
-        copyright: unsafe { String::from_utf8_lossy(result.elements_as_slice()).to_string() },
-    })
+        copyright: String::from_utf8_lossy(copyright).to_string(),
+    }
+}
+
+/// Converts a fixed width ASCII field of INQUIRY data, like the T10 VENDOR IDENTIFICATION,
+/// into a `String`.
+///
+/// The fields are left-aligned and padded with spaces, some devices pad with NULs instead, so
+/// both are trimmed from either end. Any other byte that isn't printable ASCII, including a
+/// NUL in the middle of the field, becomes U+FFFD.
+pub fn identification_string(bytes: &[u8]) -> String {
+    let is_padding = |byte: &u8| *byte == b' ' || *byte == b'\0';
+    let start = bytes.iter().position(|byte| !is_padding(byte));
+    let end = bytes.iter().rposition(|byte| !is_padding(byte));
+
+    match (start, end) {
+        (Some(start), Some(end)) => bytes[start..=end]
+            .iter()
+            .map(|&byte| match byte {
+                0x20..=0x7E => byte as char,
+                _ => char::REPLACEMENT_CHARACTER,
+            })
+            .collect(),
+        _ => String::new(),
+    }
 }
 
 #[bitfield]
@@ -144,6 +257,7 @@ mod tests {
     use std::mem::size_of;
 
     const PAGE_HEADER_LENGTH: usize = 96;
+    const MINIMUM_STANDARD_INQUIRY_DATA_LENGTH: usize = 36;
 
     #[test]
     fn layout_test() {
@@ -153,4 +267,117 @@ mod tests {
             concat!("Size of: ", stringify!(PageHeader))
         );
     }
+
+    #[test]
+    fn short_response_test() {
+        let mut bytes = [0u8; PAGE_HEADER_LENGTH];
+        bytes[..MINIMUM_STANDARD_INQUIRY_DATA_LENGTH]
+            .copy_from_slice(b"\x00\x80\x06\x02\x1F\x00\x00\x00ATA     SAMSUNG SSD\0\0\0\0\0RVT0");
+
+        let data = parse(
+            PageHeader::from_bytes(bytes),
+            &[],
+            MINIMUM_STANDARD_INQUIRY_DATA_LENGTH,
+        );
+
+        assert_eq!(
+            data.peripheral_device_type,
+            PeripheralDeviceType::DirectAccessBlock,
+            "peripheral device type"
+        );
+        assert!(data.removable_media, "removable media");
+        assert_eq!(data.version, 0x06, "version");
+        assert_eq!(
+            data.t10_vendor_identification, "ATA",
+            "vendor identification"
+        );
+        assert_eq!(
+            data.product_identification, "SAMSUNG SSD",
+            "product identification"
+        );
+        assert_eq!(
+            data.product_revision_level, "RVT0",
+            "product revision level"
+        );
+        assert_eq!(
+            data.drive_serial_number, 0,
+            "fields past the returned length stay zeroed"
+        );
+    }
+
+    #[test]
+    fn long_additional_length_test() {
+        let mut header = [0u8; PAGE_HEADER_LENGTH];
+        header[..MINIMUM_STANDARD_INQUIRY_DATA_LENGTH]
+            .copy_from_slice(b"\x00\x00\x06\x02\xFB\x00\x00\x00ATA     SAMSUNG SSD\0\0\0\0\0RVT0");
+        // 256 bytes of INQUIRY data, but the device stops 10 bytes into the copyright notice
+        let mut data = header.to_vec();
+        data.extend_from_slice(b"(C) 2026 X");
+
+        let mock = crate::MockScsi::new();
+        mock.expect(
+            &[0x12, 0x00, 0x00, 0x00, 0x60, 0x00],
+            crate::MockResponse::good().data(&header),
+        )
+        .expect(
+            &[0x12, 0x00, 0x00, 0x01, 0x00, 0x00],
+            crate::MockResponse::good().data(&data),
+        );
+
+        let scsi = crate::Scsi::mock(mock.clone());
+        let data = standard_inquiry(&mut scsi.inquiry()).unwrap();
+        assert_eq!(
+            data.product_identification, "SAMSUNG SSD",
+            "product identification"
+        );
+        assert_eq!(data.copyright, "(C) 2026 X", "transferred copyright");
+        mock.verify();
+    }
+
+    #[test]
+    fn peripheral_device_type_test() {
+        for (code, device_type) in [
+            (0x00, PeripheralDeviceType::DirectAccessBlock),
+            (0x01, PeripheralDeviceType::SequentialAccess),
+            (0x05, PeripheralDeviceType::CdDvd),
+            (0x08, PeripheralDeviceType::MediumChanger),
+            (0x0D, PeripheralDeviceType::EnclosureServices),
+            (0x14, PeripheralDeviceType::Zbc),
+            (0x1F, PeripheralDeviceType::Unknown(0x1F)),
+        ] {
+            assert_eq!(
+                PeripheralDeviceType::from(code),
+                device_type,
+                "code {:#04x}",
+                code
+            );
+            assert_eq!(
+                u8::from(device_type),
+                code,
+                "{} round trip",
+                device_type.name()
+            );
+        }
+    }
+
+    #[test]
+    fn identification_string_test() {
+        assert_eq!(identification_string(b"ATA     "), "ATA", "trailing spaces");
+        assert_eq!(
+            identification_string(b"  WDC\0\0\0"),
+            "WDC",
+            "spaces and NULs"
+        );
+        assert_eq!(
+            identification_string(b"SAN\0DISK   "),
+            "SAN\u{FFFD}DISK",
+            "embedded NUL"
+        );
+        assert_eq!(
+            identification_string(b"HGST\xC3\xA9 "),
+            "HGST\u{FFFD}\u{FFFD}",
+            "non-ASCII bytes"
+        );
+        assert_eq!(identification_string(b"    \0\0  "), "", "only padding");
+    }
 }
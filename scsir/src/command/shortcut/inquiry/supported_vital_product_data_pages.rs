@@ -5,6 +5,7 @@ use modular_bitfield_msb::prelude::*;
 use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SupportedVitalProductDataPages {
     pub supported_pages: Vec<u8>,
 }
@@ -23,7 +24,7 @@ pub fn supported_vital_product_data_pages(
     };
 
     Ok(SupportedVitalProductDataPages {
-        supported_pages: unsafe { Vec::from(result.elements_as_slice()) },
+        supported_pages: Vec::from(result.elements_as_slice()),
     })
 }
 
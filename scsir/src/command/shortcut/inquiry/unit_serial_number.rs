@@ -5,6 +5,7 @@ use modular_bitfield_msb::prelude::*;
 use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnitSerialNumber {
     pub product_serial_number: String,
 }
@@ -20,11 +21,21 @@ pub fn unit_serial_number(this: &mut InquiryCommand) -> crate::Result<UnitSerial
         this.issue_flex(remaining as usize)?
     };
 
-    Ok(UnitSerialNumber {
-        product_serial_number: unsafe {
-            String::from_utf8_lossy(result.elements_as_slice()).to_string()
-        },
-    })
+    let serial_number = result.elements_as_slice();
+
+    Ok(parse(result.get_body(), serial_number))
+}
+
+fn parse(body: PageHeader, serial_number: &[u8]) -> UnitSerialNumber {
+    let serial_number =
+        &serial_number[..usize::min(body.page_length() as usize, serial_number.len())];
+
+    // some devices pad the serial number with spaces or NULs on either side
+    UnitSerialNumber {
+        product_serial_number: String::from_utf8_lossy(serial_number)
+            .trim_matches(|c: char| c.is_whitespace() || c == '\0')
+            .to_string(),
+    }
 }
 
 const PAGE_CODE: u8 = 0x80;
@@ -54,4 +65,17 @@ mod tests {
             concat!("Size of: ", stringify!(PageHeader))
         );
     }
+
+    #[test]
+    fn parse_test() {
+        let header = PageHeader::from_bytes([0x00, 0x80, 0x00, 0x0C]);
+        assert_eq!(header.page_code(), PAGE_CODE, "page code");
+        assert_eq!(header.page_length(), 0x0C, "page length");
+
+        let serial = parse(header, b"  S3Z1NB0K\0\0");
+        assert_eq!(serial.product_serial_number, "S3Z1NB0K", "padded serial");
+
+        let serial = parse(PageHeader::from_bytes([0x00, 0x80, 0x00, 0x00]), &[]);
+        assert_eq!(serial.product_serial_number, "", "empty page");
+    }
 }
@@ -0,0 +1,169 @@
+#![allow(dead_code)]
+
+use std::mem::size_of;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{inquiry::InquiryCommand, truncated},
+    data_wrapper::FlexibleStruct,
+    Scsi,
+};
+
+use super::PeripheralDeviceType;
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VitalProductData {
+    pub peripheral_qualifier: u8,
+    pub peripheral_device_type: PeripheralDeviceType,
+    pub page_code: u8,
+    /// page length reported by the device, may be larger than the returned page
+    pub page_length: u16,
+    /// the part of the page the device transferred
+    pub page: Vec<u8>,
+}
+
+impl VitalProductData {
+    /// Whether the device transferred less than the page length, e.g. because the allocation
+    /// length was too short or the transfer ended early.
+    pub fn is_truncated(&self) -> bool {
+        truncated(self.page_length as usize, self.page.len())
+    }
+}
+
+impl Scsi {
+    /// Reads the whole VPD page `page_code`, see [`vital_product_data`].
+    pub fn inquiry_vpd(&self, page_code: u8) -> crate::Result<VitalProductData> {
+        vital_product_data(&mut self.inquiry(), page_code)
+    }
+}
+
+pub fn vital_product_data(
+    this: &mut InquiryCommand,
+    page_code: u8,
+) -> crate::Result<VitalProductData> {
+    this.page_code(Some(page_code));
+
+    let (result, transfered): (FlexibleStruct<PageHeader, u8>, _) =
+        this.issue_flex_transfered(0)?;
+    // the whole page must fit in the 16 bits allocation length
+    let remaining = usize::min(
+        result.get_body().page_length() as usize,
+        u16::MAX as usize - size_of::<PageHeader>(),
+    );
+    let (result, transfered) = if remaining == 0 {
+        (result, transfered)
+    } else {
+        this.issue_flex_transfered(remaining)?
+    };
+
+    let page = result.elements_as_slice();
+
+    Ok(parse(result.get_body(), page, transfered))
+}
+
+// transfered counts the header too
+fn parse(body: PageHeader, page: &[u8], transfered: usize) -> VitalProductData {
+    let page_length = body.page_length();
+    let page = &page[..usize::min(
+        transfered.saturating_sub(size_of::<PageHeader>()),
+        page.len(),
+    )];
+
+    VitalProductData {
+        peripheral_qualifier: body.peripheral_qualifier(),
+        peripheral_device_type: PeripheralDeviceType::from(body.peripheral_device_type()),
+        page_code: body.page_code(),
+        page_length,
+        page: page[..usize::min(page_length as usize, page.len())].to_vec(),
+    }
+}
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct PageHeader {
+    peripheral_qualifier: B3,
+    peripheral_device_type: B5,
+    page_code: B8,
+    page_length: B16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const PAGE_HEADER_LENGTH: usize = 4;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<PageHeader>(),
+            PAGE_HEADER_LENGTH,
+            concat!("Size of: ", stringify!(PageHeader))
+        );
+    }
+
+    #[test]
+    fn truncation_test() {
+        let header = PageHeader::from_bytes([0x00, 0x83, 0x00, 0x08]);
+
+        let data = parse(header, &[1, 2, 3, 4], 8);
+        assert_eq!(data.page_code, 0x83, "page code");
+        assert_eq!(data.page, [1, 2, 3, 4], "truncated page");
+        assert!(data.is_truncated(), "truncated page");
+
+        let data = parse(header, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10], 14);
+        assert_eq!(data.page, [1, 2, 3, 4, 5, 6, 7, 8], "clamped page");
+        assert!(!data.is_truncated(), "complete page");
+
+        // the allocation fits the page, but the device stopped after 6 bytes of it
+        let data = parse(header, &[1, 2, 3, 4, 5, 6, 0, 0], 10);
+        assert_eq!(data.page, [1, 2, 3, 4, 5, 6], "short transfer");
+        assert!(data.is_truncated(), "short transfer");
+    }
+
+    #[test]
+    fn short_transfer_test() {
+        let mock = crate::MockScsi::new();
+        mock.expect(
+            &[0x12, 0x01, 0x83, 0x00, 0x04, 0x00],
+            crate::MockResponse::good().data(&[0x00, 0x83, 0x00, 0x08]),
+        )
+        .expect(
+            &[0x12, 0x01, 0x83, 0x00, 0x0C, 0x00],
+            crate::MockResponse::good().data(&[0x00, 0x83, 0x00, 0x08, 1, 2, 3, 4, 5, 6]),
+        );
+
+        let scsi = crate::Scsi::mock(mock.clone());
+        let data = vital_product_data(&mut scsi.inquiry(), 0x83).unwrap();
+        assert_eq!(
+            data.page,
+            [1, 2, 3, 4, 5, 6],
+            "transferred part of the page"
+        );
+        assert!(data.is_truncated(), "short transfer");
+        mock.verify();
+    }
+
+    #[test]
+    fn inquiry_vpd_test() {
+        let mock = crate::MockScsi::new();
+        mock.expect(
+            &[0x12, 0x01, 0xB1, 0x00, 0x04, 0x00],
+            crate::MockResponse::good().data(&[0x00, 0xB1, 0x00, 0x04]),
+        )
+        .expect(
+            &[0x12, 0x01, 0xB1, 0x00, 0x08, 0x00],
+            crate::MockResponse::good().data(&[0x00, 0xB1, 0x00, 0x04, 0x00, 0x01, 0x00, 0x00]),
+        );
+
+        let scsi = crate::Scsi::mock(mock.clone());
+        let data = scsi.inquiry_vpd(0xB1).unwrap();
+        assert_eq!(data.page_code, 0xB1, "page code");
+        assert_eq!(data.page, [0x00, 0x01, 0x00, 0x00], "page");
+        assert!(!data.is_truncated(), "complete page");
+        mock.verify();
+    }
+}
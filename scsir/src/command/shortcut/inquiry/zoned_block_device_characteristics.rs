@@ -5,6 +5,7 @@ use modular_bitfield_msb::prelude::*;
 use crate::{command::inquiry::InquiryCommand, data_wrapper::FlexibleStruct};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZonedBlockDeviceCharacteristics {
     pub unrestricted_read_in_sequential_write_required_zone: bool,
     pub optimal_number_of_open_sequential_write_preferred_zones: u32,
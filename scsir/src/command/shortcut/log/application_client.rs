@@ -6,14 +6,18 @@ pub const APPLICATION_CLIENT_PAGE_CODE: u8 = 0x0F;
 pub const APPLICATION_CLIENT_SUBPAGE_CODE: u8 = 0x00;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ApplicationClientParameter {
     GeneralUsageApplicationClient(Box<GeneralUsageApplicationClient>),
     Other(GeneralParameter),
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GeneralUsageApplicationClient {
     pub header: ParameterHeader,
+    #[cfg_attr(feature = "serde", serde(with = "crate::util::byte_array"))]
     pub general_usage_parameter_bytes: [u8; 252],
 }
 
@@ -6,12 +6,15 @@ pub const BACKGROUND_OPERATION_PAGE_CODE: u8 = 0x15;
 pub const BACKGROUND_OPERATION_SUBPAGE_CODE: u8 = 0x02;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum BackgroundOperationParameter {
     BackgroundOperation(BackgroundOperation),
     Other(GeneralParameter),
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BackgroundOperation {
     pub header: ParameterHeader,
     pub background_operation_status: u8,
@@ -10,6 +10,8 @@ pub const BACKGROUND_SCAN_PAGE_CODE: u8 = 0x15;
 pub const BACKGROUND_SCAN_SUBPAGE_CODE: u8 = 0x00;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum BackgroundScanParameter {
     BackgroundScanStatus(BackgroundScanStatus),
     BackgroundScan(BackgroundScan),
@@ -28,6 +30,8 @@ pub struct BackgroundScanStatus {
     pub number_of_background_medium_scans_performed: B16,
 }
 
+crate::command::impl_bitfield_serde!(BackgroundScanStatus);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct BackgroundScan {
@@ -41,6 +45,8 @@ pub struct BackgroundScan {
     pub logical_block_address: B64,
 }
 
+crate::command::impl_bitfield_serde!(BackgroundScan);
+
 impl LogParameter for BackgroundScanParameter {
     fn new() -> Self {
         Self::Other(GeneralParameter::new())
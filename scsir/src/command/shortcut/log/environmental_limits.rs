@@ -7,6 +7,8 @@ use super::{GeneralParameter, LogParameter, ParameterHeader};
 pub const ENVIRONMENTAL_LIMITS_PAGE_CODE: u8 = 0x0D;
 pub const ENVIRONMENTAL_LIMITS_SUBPAGE_CODE: u8 = 0x02;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum EnvironmentalLimitsParameter {
     TemperatureLimits(TemperatureLimits),
     RelativeHumidityLimits(RelativeHumidityLimits),
@@ -27,6 +29,8 @@ pub struct TemperatureLimits {
     pub low_operating_temperature_limit_trigger: B8,
 }
 
+crate::command::impl_bitfield_serde!(TemperatureLimits);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct RelativeHumidityLimits {
@@ -41,6 +45,8 @@ pub struct RelativeHumidityLimits {
     pub low_operating_relative_humidity_limit_trigger: B8,
 }
 
+crate::command::impl_bitfield_serde!(RelativeHumidityLimits);
+
 impl LogParameter for EnvironmentalLimitsParameter {
     fn new() -> Self {
         Self::Other(GeneralParameter::new())
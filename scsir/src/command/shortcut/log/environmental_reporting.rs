@@ -9,6 +9,8 @@ use super::{GeneralParameter, LogParameter, ParameterHeader};
 pub const ENVIRONMENTAL_REPORTING_PAGE_CODE: u8 = 0x0D;
 pub const ENVIRONMENTAL_REPORTING_SUBPAGE_CODE: u8 = 0x01;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum EnvironmentalReportingParameter {
     TemperatureReport(TemperatureReport),
     RelativeHumidityReport(RelativeHumidityReport),
@@ -28,6 +30,8 @@ pub struct TemperatureReport {
     reserved_1: B16,
 }
 
+crate::command::impl_bitfield_serde!(TemperatureReport);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct RelativeHumidityReport {
@@ -41,6 +45,8 @@ pub struct RelativeHumidityReport {
     reserved_1: B16,
 }
 
+crate::command::impl_bitfield_serde!(RelativeHumidityReport);
+
 impl LogParameter for EnvironmentalReportingParameter {
     fn new() -> Self {
         Self::Other(GeneralParameter::new())
@@ -3,6 +3,7 @@ use crate::command::get_array;
 use super::{LogParameter, ParameterHeader};
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GeneralParameter {
     pub header: ParameterHeader,
     pub value: Vec<u8>,
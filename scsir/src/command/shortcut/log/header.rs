@@ -14,6 +14,8 @@ pub struct PageHeader {
     pub page_length: B16,
 }
 
+crate::command::impl_bitfield_serde!(PageHeader);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug, BitfieldSpecifier)]
 pub struct ParameterHeader {
@@ -22,6 +24,8 @@ pub struct ParameterHeader {
     pub parameter_length: B8,
 }
 
+crate::command::impl_bitfield_serde!(ParameterHeader);
+
 impl PageHeader {
     pub fn from_slice(bytes: &[u8]) -> Self {
         let (array, _) = get_array(bytes);
@@ -7,6 +7,8 @@ use super::{GeneralParameter, LogParameter, ParameterHeader};
 pub const INFORMATIONAL_EXCEPTIONS_PAGE_CODE: u8 = 0x2F;
 pub const INFORMATIONAL_EXCEPTIONS_SUBPAGE_CODE: u8 = 0x00;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum InformationalExceptionsParameter {
     InformationalExceptionsGeneral(InformationalExceptionsGeneral),
     Other(GeneralParameter),
@@ -24,6 +26,8 @@ pub struct InformationalExceptionsGeneral {
     pub vendor_specific: B24,
 }
 
+crate::command::impl_bitfield_serde!(InformationalExceptionsGeneral);
+
 impl LogParameter for InformationalExceptionsParameter {
     fn new() -> Self {
         Self::Other(GeneralParameter::new())
@@ -72,4 +76,47 @@ mod tests {
             concat!("Size of: ", stringify!(InformationalExceptionsGeneral))
         );
     }
+
+    #[test]
+    fn decode_test() {
+        use crate::command::shortcut::log::PageWrapper;
+
+        let bytes = [
+            0x2F, 0x00, 0x00, 0x0C, // page header
+            0x00, 0x00, 0x03, 0x08, // parameter header
+            0x5D, 0x10, // FAILURE PREDICTION THRESHOLD EXCEEDED, HARDWARE IMPENDING FAILURE
+            0x2A, 0x3C, 0x32, 0x00, 0x00, 0x00,
+        ];
+
+        let page = PageWrapper::<InformationalExceptionsParameter>::from_bytes(&bytes);
+        assert_eq!(
+            page.header.page_code(),
+            INFORMATIONAL_EXCEPTIONS_PAGE_CODE,
+            "page code"
+        );
+        assert_eq!(page.parameters.len(), 1, "parameter count");
+
+        let InformationalExceptionsParameter::InformationalExceptionsGeneral(general) =
+            &page.parameters[0]
+        else {
+            panic!("informational exceptions general parameter expected");
+        };
+        assert_eq!(
+            general.informational_exception_additional_sense_code(),
+            0x5D,
+            "ASC"
+        );
+        assert_eq!(
+            general.informational_exception_additional_sense_code_qualifier(),
+            0x10,
+            "ASCQ"
+        );
+        assert_eq!(general.most_recent_temperature_reading(), 42, "temperature");
+        assert_eq!(
+            general.vendor_hda_temperature_trip_point(),
+            60,
+            "trip point"
+        );
+        assert_eq!(general.maximum_temperature(), 50, "maximum temperature");
+    }
 }
@@ -0,0 +1,46 @@
+use std::mem::size_of;
+
+use crate::command::log_sense::LogSenseCommand;
+
+use super::{
+    LogParameter, PageHeader, PageWrapper, SupportedLogPagesParameter,
+    SUPPORTED_LOG_PAGES_PAGE_CODE, SUPPORTED_LOG_PAGES_SUBPAGE_CODE,
+};
+
+pub fn log_page<Parameter: LogParameter>(
+    this: &mut LogSenseCommand,
+    page_code: u8,
+    subpage_code: u8,
+) -> crate::Result<PageWrapper<Parameter>> {
+    this.page_code(page_code).subpage_code(subpage_code);
+
+    let bytes = this
+        .allocation_length(size_of::<PageHeader>() as u16)
+        .issue()?;
+    let header = PageHeader::from_slice(&bytes);
+
+    // the whole page must fit in the 16 bits allocation length
+    let bytes = this
+        .allocation_length(
+            header
+                .page_length()
+                .saturating_add(size_of::<PageHeader>() as u16),
+        )
+        .issue()?;
+
+    Ok(PageWrapper::from_bytes(&bytes))
+}
+
+pub fn supported_log_pages(this: &mut LogSenseCommand) -> crate::Result<Vec<u8>> {
+    let page: PageWrapper<SupportedLogPagesParameter> = log_page(
+        this,
+        SUPPORTED_LOG_PAGES_PAGE_CODE,
+        SUPPORTED_LOG_PAGES_SUBPAGE_CODE,
+    )?;
+
+    Ok(page
+        .parameters
+        .iter()
+        .map(|p| p.page_code() & 0b0011_1111)
+        .collect())
+}
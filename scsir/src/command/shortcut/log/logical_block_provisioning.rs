@@ -9,6 +9,8 @@ use super::{GeneralParameter, LogParameter, ParameterHeader};
 pub const LOGICAL_BLOCK_PROVISIONING_PAGE_CODE: u8 = 0x0C;
 pub const LOGICAL_BLOCK_PROVISIONING_SUBPAGE_CODE: u8 = 0x00;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum LogicalBlockProvisioningParameter {
     LogicalBlockProvisioning(LogicalBlockProvisioning),
     Other(GeneralParameter),
@@ -24,6 +26,8 @@ pub struct LogicalBlockProvisioning {
     reserved_1: B24,
 }
 
+crate::command::impl_bitfield_serde!(LogicalBlockProvisioning);
+
 impl LogParameter for LogicalBlockProvisioningParameter {
     fn new() -> Self {
         Self::Other(GeneralParameter::new())
@@ -9,6 +9,7 @@ mod factory_log;
 mod general;
 mod header;
 mod informational_exceptions;
+mod log_page;
 mod logical_block_provisioning;
 mod non_medium_error;
 mod page_wrapper;
@@ -20,7 +21,9 @@ mod solid_state_media;
 mod start_stop_cycle_counter;
 mod supported_log_pages;
 mod supported_log_pages_and_subpages;
-mod temperature;
+// public for the deprecated name of TemperatureParameter, which clashes with the power
+// condition transitions page in the glob re-exports below
+pub mod temperature;
 mod utilization;
 
 pub use application_client::*;
@@ -34,6 +37,7 @@ pub use factory_log::*;
 pub use general::*;
 pub use header::*;
 pub use informational_exceptions::*;
+pub use log_page::*;
 pub use logical_block_provisioning::*;
 pub use non_medium_error::*;
 pub use page_wrapper::*;
@@ -47,3 +51,6 @@ pub use supported_log_pages::*;
 pub use supported_log_pages_and_subpages::*;
 pub use temperature::*;
 pub use utilization::*;
+
+// the page of the name, not the deprecated alias in temperature
+pub use power_condition_transitions::PowerConditionTransitionsParameter;
@@ -5,14 +5,18 @@ use super::{GeneralParameter, LogParameter, ParameterHeader};
 pub const NON_MEDIUM_ERROR_PAGE_CODE: u8 = 0x06;
 pub const NON_MEDIUM_ERROR_SUBPAGE_CODE: u8 = 0x00;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum NonMediumErrorParameter {
     NonMediumError(Box<NonMediumError>),
     Other(GeneralParameter),
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NonMediumError {
     pub header: ParameterHeader,
+    #[cfg_attr(feature = "serde", serde(with = "crate::util::byte_array"))]
     pub non_medium_error_count: [u8; 252],
 }
 
@@ -3,6 +3,7 @@ use crate::command::get_array;
 use super::header::PageHeader;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PageWrapper<Parameter: LogParameter> {
     pub header: PageHeader,
     pub parameters: Vec<Parameter>,
@@ -46,3 +47,35 @@ impl<Parameter: LogParameter> PageWrapper<Parameter> {
         bytes
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::shortcut::log::GeneralParameter;
+
+    #[test]
+    fn parse_test() {
+        let bytes = [
+            0x02, 0x00, 0x00, 0x13, // page header
+            0x00, 0x00, 0x03, 0x02, 0x12, 0x34, // two bytes
+            0x00, 0x01, 0x03, 0x00, // empty
+            0x80, 0x00, 0x03, 0x05, 0x01, 0x02, 0x03, 0x04, 0x05, // five bytes
+            0xFF, 0xFF, // past the page length
+        ];
+
+        let page = PageWrapper::<GeneralParameter>::from_bytes(&bytes);
+        assert_eq!(page.header.page_code(), 0x02, "page code");
+        assert_eq!(page.parameters.len(), 3, "parameter count");
+
+        let codes: Vec<_> = page
+            .parameters
+            .iter()
+            .map(|p| p.header.parameter_code())
+            .collect();
+        assert_eq!(codes, [0x0000, 0x0001, 0x8000], "parameter codes");
+        assert_eq!(page.parameters[0].value, [0x12, 0x34], "first value");
+        assert!(page.parameters[1].value.is_empty(), "second value");
+        assert_eq!(page.parameters[2].value, [1, 2, 3, 4, 5], "third value");
+        assert_eq!(page.to_bytes(), bytes[..23], "round trip");
+    }
+}
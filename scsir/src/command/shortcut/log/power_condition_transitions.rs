@@ -7,6 +7,8 @@ use super::{GeneralParameter, LogParameter, ParameterHeader};
 pub const POWER_CONDITION_TRANSITIONS_PAGE_CODE: u8 = 0x1A;
 pub const POWER_CONDITION_TRANSITIONS_SUBPAGE_CODE: u8 = 0x00;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum PowerConditionTransitionsParameter {
     PowerConditionTransitions(PowerConditionTransitions),
     Other(GeneralParameter),
@@ -19,6 +21,8 @@ pub struct PowerConditionTransitions {
     pub parameter_value: B32,
 }
 
+crate::command::impl_bitfield_serde!(PowerConditionTransitions);
+
 impl LogParameter for PowerConditionTransitionsParameter {
     fn new() -> Self {
         Self::Other(GeneralParameter::new())
@@ -10,6 +10,7 @@ pub const PROTOCOL_SPECIFIC_PORT_PAGE_CODE: u8 = 0x18;
 pub const PROTOCOL_SPECIFIC_PORT_SUBPAGE_CODE: u8 = 0x00;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProtocolSpecificPortParameter {
     pub header: ParameterHeader,
     pub body: ProtocolSpecificPortBody,
@@ -17,6 +18,7 @@ pub struct ProtocolSpecificPortParameter {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LogDescriptor {
     pub header: SasPhyLogDescriptorHeader,
     pub event_descriptors: Vec<PhyEventDescriptor>,
@@ -32,6 +34,8 @@ pub struct ProtocolSpecificPortBody {
     pub number_of_phys: B8,
 }
 
+crate::command::impl_bitfield_serde!(ProtocolSpecificPortBody);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct SasPhyLogDescriptorHeader {
@@ -67,6 +71,8 @@ pub struct SasPhyLogDescriptorHeader {
     pub number_of_phy_event_descriptors: B8,
 }
 
+crate::command::impl_bitfield_serde!(SasPhyLogDescriptorHeader);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct PhyEventDescriptor {
@@ -76,6 +82,8 @@ pub struct PhyEventDescriptor {
     pub peak_value_detector_threshold: B32,
 }
 
+crate::command::impl_bitfield_serde!(PhyEventDescriptor);
+
 impl LogParameter for ProtocolSpecificPortParameter {
     fn new() -> Self {
         Self {
@@ -26,6 +26,8 @@ pub struct SelfTestResultsParameter {
     pub vendor_specific: B8,
 }
 
+crate::command::impl_bitfield_serde!(SelfTestResultsParameter);
+
 impl LogParameter for SelfTestResultsParameter {
     fn new() -> Self {
         Self::new()
@@ -57,4 +59,66 @@ mod tests {
             concat!("Size of: ", stringify!(SelfTestResultsParameter))
         );
     }
+
+    #[test]
+    fn decode_test() {
+        use crate::command::shortcut::log::PageWrapper;
+
+        // the page always holds 20 entries, the most recent first
+        let mut bytes = vec![0x10, 0x00, 0x01, 0x90];
+        for index in 0..20u8 {
+            let failed = index == 0;
+            bytes.extend_from_slice(&[0x00, index + 1, 0x03, 0x10]);
+            bytes.extend_from_slice(&[
+                0x20 | if failed { 0x07 } else { 0x00 },
+                20 - index,
+                0x12,
+                0x34,
+            ]);
+            let address = if failed { 0x1234_5678 } else { u64::MAX };
+            bytes.extend_from_slice(&address.to_be_bytes());
+            match failed {
+                // MEDIUM ERROR, UNRECOVERED READ ERROR
+                true => bytes.extend_from_slice(&[0x03, 0x11, 0x00, 0x00]),
+                false => bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]),
+            }
+        }
+
+        let page = PageWrapper::<SelfTestResultsParameter>::from_bytes(&bytes);
+        assert_eq!(
+            page.header.page_code(),
+            SELF_TEST_RESULTS_PAGE_CODE,
+            "page code"
+        );
+        assert_eq!(page.parameters.len(), 20, "entry count");
+
+        let latest = &page.parameters[0];
+        // the nested header getter of modular_bitfield_msb swaps the bytes, read it directly
+        assert_eq!(
+            ParameterHeader::from_slice(&latest.bytes).parameter_code(),
+            1,
+            "parameter code"
+        );
+        assert_eq!(latest.self_test_code(), 1, "background short self-test");
+        assert_eq!(latest.self_test_results(), 7, "failed in the read element");
+        assert_eq!(latest.self_test_number(), 20, "self-test number");
+        assert_eq!(
+            latest.accumulated_power_on_hours(),
+            0x1234,
+            "power on hours"
+        );
+        assert_eq!(latest.address_of_first_failure(), 0x1234_5678, "LBA");
+        assert_eq!(latest.sense_key(), 0x03, "sense key");
+        assert_eq!(latest.additional_sense_code(), 0x11, "ASC");
+
+        let oldest = &page.parameters[19];
+        assert_eq!(
+            ParameterHeader::from_slice(&oldest.bytes).parameter_code(),
+            20,
+            "parameter code"
+        );
+        assert_eq!(oldest.self_test_results(), 0, "completed without error");
+        assert_eq!(oldest.address_of_first_failure(), u64::MAX, "no failure");
+        assert_eq!(page.to_bytes(), bytes, "round trip");
+    }
 }
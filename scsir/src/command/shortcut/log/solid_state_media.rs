@@ -9,6 +9,8 @@ use super::{GeneralParameter, LogParameter, ParameterHeader};
 pub const SOLID_STATE_MEDIA_PAGE_CODE: u8 = 0x11;
 pub const SOLID_STATE_MEDIA_SUBPAGE_CODE: u8 = 0x00;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum SolidStateMediaParameter {
     SolidStateMedia(SolidStateMedia),
     Other(GeneralParameter),
@@ -22,6 +24,8 @@ pub struct SolidStateMedia {
     pub percentage_used_endurance_indicator: B8,
 }
 
+crate::command::impl_bitfield_serde!(SolidStateMedia);
+
 impl LogParameter for SolidStateMediaParameter {
     fn new() -> Self {
         Self::Other(GeneralParameter::new())
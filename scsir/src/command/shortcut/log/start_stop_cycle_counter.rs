@@ -5,6 +5,8 @@ use super::{GeneralParameter, LogParameter, ParameterHeader};
 pub const START_STOP_CYCLE_COUNTER_PAGE_CODE: u8 = 0x0E;
 pub const START_STOP_CYCLE_COUNTER_SUBPAGE_CODE: u8 = 0x00;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum StartStopCycleCounterParameter {
     DateOfManufacture(DateOfManufacture),
     AccountingDate(AccountingDate),
@@ -16,6 +18,7 @@ pub enum StartStopCycleCounterParameter {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DateOfManufacture {
     pub header: ParameterHeader,
     pub year_of_manufacture: String,
@@ -23,6 +26,7 @@ pub struct DateOfManufacture {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccountingDate {
     pub header: ParameterHeader,
     pub accounting_date_year: String,
@@ -30,24 +34,28 @@ pub struct AccountingDate {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpecifiedCycleCountOverDeviceLifetime {
     pub header: ParameterHeader,
     pub specified_cycle_count_over_device_lifetime: u32,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccumulatedStartStopCycles {
     pub header: ParameterHeader,
     pub accumulated_start_stop_cycles: u32,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpecifiedLoadUnloadCountOverDeviceLifetime {
     pub header: ParameterHeader,
     pub specified_load_unload_count_over_device_lifetime: u32,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccumulatedLoadUnloadCycles {
     pub header: ParameterHeader,
     pub accumulated_load_unload_cycles: u32,
@@ -13,6 +13,8 @@ pub struct SupportedLogPagesParameter {
     pub page_code: B8,
 }
 
+crate::command::impl_bitfield_serde!(SupportedLogPagesParameter);
+
 impl LogParameter for SupportedLogPagesParameter {
     fn new() -> Self {
         Self::new()
@@ -17,6 +17,8 @@ pub struct SupportedLogPagesAndSubpagesParameter {
     pub subpage_code: B8,
 }
 
+crate::command::impl_bitfield_serde!(SupportedLogPagesAndSubpagesParameter);
+
 impl LogParameter for SupportedLogPagesAndSubpagesParameter {
     fn new() -> Self {
         Self::new()
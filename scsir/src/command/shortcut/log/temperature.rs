@@ -7,12 +7,19 @@ use super::{GeneralParameter, LogParameter, ParameterHeader};
 pub const TEMPERATURE_PAGE_CODE: u8 = 0x0D;
 pub const TEMPERATURE_SUBPAGE_CODE: u8 = 0x00;
 
-pub enum PowerConditionTransitionsParameter {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TemperatureParameter {
     Temperature(Temperature),
     ReferenceTemperature(ReferenceTemperature),
     Other(GeneralParameter),
 }
 
+/// The old name of [`TemperatureParameter`]. `log::PowerConditionTransitionsParameter` is the
+/// one of the power condition transitions page.
+#[deprecated(note = "renamed to TemperatureParameter")]
+pub type PowerConditionTransitionsParameter = TemperatureParameter;
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct Temperature {
@@ -21,6 +28,8 @@ pub struct Temperature {
     pub temperature: B8,
 }
 
+crate::command::impl_bitfield_serde!(Temperature);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct ReferenceTemperature {
@@ -29,7 +38,9 @@ pub struct ReferenceTemperature {
     pub reference_temperature: B8,
 }
 
-impl LogParameter for PowerConditionTransitionsParameter {
+crate::command::impl_bitfield_serde!(ReferenceTemperature);
+
+impl LogParameter for TemperatureParameter {
     fn new() -> Self {
         Self::Other(GeneralParameter::new())
     }
@@ -40,20 +51,19 @@ impl LogParameter for PowerConditionTransitionsParameter {
         let result = match header.parameter_code() {
             0x0000 => {
                 let (array, left) = get_array(bytes);
-                let parameter =
-                    PowerConditionTransitionsParameter::Temperature(Temperature::from_bytes(array));
+                let parameter = TemperatureParameter::Temperature(Temperature::from_bytes(array));
                 (parameter, left)
             }
             0x0001 => {
                 let (array, left) = get_array(bytes);
-                let parameter = PowerConditionTransitionsParameter::ReferenceTemperature(
+                let parameter = TemperatureParameter::ReferenceTemperature(
                     ReferenceTemperature::from_bytes(array),
                 );
                 (parameter, left)
             }
             _ => {
                 let (parameter, left) = GeneralParameter::from_bytes(bytes);
-                (PowerConditionTransitionsParameter::Other(parameter), left)
+                (TemperatureParameter::Other(parameter), left)
             }
         };
 
@@ -62,9 +72,9 @@ impl LogParameter for PowerConditionTransitionsParameter {
 
     fn to_bytes(&self) -> Vec<u8> {
         match self {
-            PowerConditionTransitionsParameter::Temperature(p) => p.bytes.to_vec(),
-            PowerConditionTransitionsParameter::ReferenceTemperature(p) => p.bytes.to_vec(),
-            PowerConditionTransitionsParameter::Other(p) => p.to_bytes(),
+            TemperatureParameter::Temperature(p) => p.bytes.to_vec(),
+            TemperatureParameter::ReferenceTemperature(p) => p.bytes.to_vec(),
+            TemperatureParameter::Other(p) => p.to_bytes(),
         }
     }
 }
@@ -91,4 +101,58 @@ mod tests {
             concat!("Size of: ", stringify!(ReferenceTemperature))
         );
     }
+
+    #[test]
+    fn decode_test() {
+        use crate::command::shortcut::log::PageWrapper;
+
+        let bytes = [
+            0x0D, 0x00, 0x00, 0x0C, // page header
+            0x00, 0x00, 0x03, 0x02, 0x00, 0x28, // 40 C
+            0x00, 0x01, 0x03, 0x02, 0x00, 0x41, // 65 C
+        ];
+
+        let page = PageWrapper::<TemperatureParameter>::from_bytes(&bytes);
+        assert_eq!(page.header.page_code(), TEMPERATURE_PAGE_CODE, "page code");
+        assert_eq!(page.parameters.len(), 2, "parameter count");
+
+        let TemperatureParameter::Temperature(current) = &page.parameters[0] else {
+            panic!("temperature expected");
+        };
+        assert_eq!(current.temperature(), 40, "temperature");
+
+        let TemperatureParameter::ReferenceTemperature(reference) = &page.parameters[1] else {
+            panic!("reference temperature expected");
+        };
+        assert_eq!(
+            reference.reference_temperature(),
+            65,
+            "reference temperature"
+        );
+        assert_eq!(page.to_bytes(), bytes, "round trip");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_name_test() {
+        use crate::command::shortcut::log::{self, PageWrapper};
+
+        let bytes = [0x0D, 0x00, 0x00, 0x06, 0x00, 0x00, 0x03, 0x02, 0x00, 0x28];
+        let page = PageWrapper::<PowerConditionTransitionsParameter>::from_bytes(&bytes);
+        assert!(
+            matches!(page.parameters[0], TemperatureParameter::Temperature(_)),
+            "old name of the temperature parameters"
+        );
+
+        let page = PageWrapper::<log::PowerConditionTransitionsParameter>::from_bytes(&[
+            0x1A, 0x00, 0x00, 0x08, 0x00, 0x01, 0x03, 0x04, 0x00, 0x00, 0x00, 0x07,
+        ]);
+        assert!(
+            matches!(
+                page.parameters[0],
+                log::PowerConditionTransitionsParameter::PowerConditionTransitions(_)
+            ),
+            "the power condition transitions page keeps the name in log"
+        );
+    }
 }
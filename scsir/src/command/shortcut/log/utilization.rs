@@ -7,6 +7,8 @@ use super::{GeneralParameter, LogParameter, ParameterHeader};
 pub const UTILIZATION_PAGE_CODE: u8 = 0x0E;
 pub const UTILIZATION_SUBPAGE_CODE: u8 = 0x01;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum UtilizationParameter {
     WorkloadUtilization(WorkloadUtilization),
     UtilizationRateBasedOnDateAndTime(UtilizationRateBasedOnDateAndTime),
@@ -20,6 +22,8 @@ pub struct WorkloadUtilization {
     pub workload_utilization: B16,
 }
 
+crate::command::impl_bitfield_serde!(WorkloadUtilization);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct UtilizationRateBasedOnDateAndTime {
@@ -28,6 +32,8 @@ pub struct UtilizationRateBasedOnDateAndTime {
     pub reserved: B8,
 }
 
+crate::command::impl_bitfield_serde!(UtilizationRateBasedOnDateAndTime);
+
 impl LogParameter for UtilizationParameter {
     fn new() -> Self {
         Self::Other(GeneralParameter::new())
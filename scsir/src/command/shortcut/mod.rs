@@ -1,3 +1,6 @@
+#[cfg(feature = "block")]
+pub mod discard;
 pub mod inquiry;
 pub mod log;
 pub mod mode;
+pub mod persistent_reservation;
@@ -10,6 +10,7 @@ pub const APPLICATION_TAG_PAGE_CODE: u8 = 0x0A;
 pub const APPLICATION_TAG_SUBPAGE_CODE: u8 = 0x02;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ApplicationTagPage {
     header: ApplicationTagPageHeader,
     descriptors: Vec<ApplicationTagPageDescriptor>,
@@ -26,6 +27,8 @@ pub struct ApplicationTagPageHeader {
     reserved: B96,
 }
 
+crate::command::impl_bitfield_serde!(ApplicationTagPageHeader);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct ApplicationTagPageDescriptor {
@@ -37,6 +40,8 @@ pub struct ApplicationTagPageDescriptor {
     pub logical_block_count: B64,
 }
 
+crate::command::impl_bitfield_serde!(ApplicationTagPageDescriptor);
+
 impl ModePage for ApplicationTagPage {
     fn new() -> Self {
         Self {
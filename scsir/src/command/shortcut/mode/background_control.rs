@@ -30,6 +30,8 @@ pub struct BackgroundControlPage {
     reserved_2: B16,
 }
 
+crate::command::impl_bitfield_serde!(BackgroundControlPage);
+
 impl ModePage for BackgroundControlPage {
     fn new() -> Self {
         Self::new()
@@ -21,6 +21,8 @@ pub struct BackgroundOperationControlPage {
     reserved: B6,
 }
 
+crate::command::impl_bitfield_serde!(BackgroundOperationControlPage);
+
 impl ModePage for BackgroundOperationControlPage {
     fn new() -> Self {
         Self::new()
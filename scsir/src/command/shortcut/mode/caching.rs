@@ -42,6 +42,8 @@ pub struct CachingPage {
     obsolete: B24,
 }
 
+crate::command::impl_bitfield_serde!(CachingPage);
+
 impl ModePage for CachingPage {
     fn new() -> Self {
         Self::new()
@@ -73,4 +75,61 @@ mod tests {
             concat!("Size of: ", stringify!(CachingPage))
         );
     }
+
+    #[test]
+    fn decode_test() {
+        // write cache on, read cache on, prefetch up to 0xFFFF blocks, read-ahead off
+        let bytes = [
+            0x88, 0x12, 0x04, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x20, 0x14,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xAA,
+        ];
+
+        let (page, left) = <CachingPage as ModePage>::from_bytes(&bytes);
+        assert_eq!(left, [0xAA], "bytes after the page");
+        assert_eq!(page.parameters_saveable(), 1, "PS");
+        assert_eq!(page.page_code(), CACHING_PAGE_CODE, "page code");
+        assert_eq!(page.page_length() as usize, PAGE_LENGTH - 2, "page length");
+        assert_eq!(page.write_cache_enable(), 1, "WCE");
+        assert_eq!(page.read_cache_disable(), 0, "RCD");
+        assert_eq!(
+            page.disable_prefetch_transfer_length(),
+            0xFFFF,
+            "disable prefetch"
+        );
+        assert_eq!(page.minimum_prefetch(), 0, "minimum prefetch");
+        assert_eq!(page.maximum_prefetch(), 0xFFFF, "maximum prefetch");
+        assert_eq!(page.maximum_prefetch_ceiling(), 0xFFFF, "ceiling");
+        assert_eq!(page.disable_read_ahead(), 1, "DRA");
+        assert_eq!(page.number_of_cache_segments(), 0x14, "cache segments");
+
+        let mut page = page.with_write_cache_enable(0);
+        page.set_parameters_saveable(0);
+        let mut expected = bytes[..PAGE_LENGTH].to_vec();
+        expected[0] = 0x08;
+        expected[2] = 0x00;
+        assert_eq!(page.to_bytes(), expected, "MODE SELECT round-trip");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let page = CachingPage::new()
+            .with_page_code(CACHING_PAGE_CODE)
+            .with_page_length((PAGE_LENGTH - 2) as u8)
+            .with_write_cache_enable(1);
+
+        // bitfields serialize as the bytes of the page
+        let json = serde_json::to_string(&page).unwrap();
+        assert_eq!(
+            json, "[8,18,4,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]",
+            "serialized"
+        );
+
+        let page: CachingPage = serde_json::from_str(&json).unwrap();
+        assert_eq!(page.write_cache_enable(), 1, "WCE");
+        assert!(
+            serde_json::from_str::<CachingPage>("[8,18,4]").is_err(),
+            "short page"
+        );
+    }
 }
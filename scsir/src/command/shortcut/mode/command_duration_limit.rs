@@ -29,6 +29,8 @@ pub struct CommandDurationLimitPage {
     pub descriptor_7: CommandDurationLimitDescriptor,
 }
 
+crate::command::impl_bitfield_serde!(CommandDurationLimitPage);
+
 #[bitfield]
 #[derive(BitfieldSpecifier, Clone, Copy, Debug)]
 pub struct CommandDurationLimitDescriptor {
@@ -37,6 +39,8 @@ pub struct CommandDurationLimitDescriptor {
     pub command_duration_limit: B16,
 }
 
+crate::command::impl_bitfield_serde!(CommandDurationLimitDescriptor);
+
 impl ModePage for CommandDurationLimitPage {
     fn new() -> Self {
         Self::new()
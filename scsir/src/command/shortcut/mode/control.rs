@@ -50,6 +50,8 @@ pub struct ControlPage {
     pub extended_self_test_completion_time: B16,
 }
 
+crate::command::impl_bitfield_serde!(ControlPage);
+
 impl ModePage for ControlPage {
     fn new() -> Self {
         Self::new()
@@ -81,4 +83,34 @@ mod tests {
             concat!("Size of: ", stringify!(ControlPage))
         );
     }
+
+    #[test]
+    fn decode_test() {
+        // GLTSD, restricted reordering with QERR 01, TAS, 1800 s extended self-test
+        let bytes = [
+            0x0A, 0x0A, 0x02, 0x12, 0x00, 0x40, 0x00, 0x00, 0xFF, 0xFF, 0x07, 0x08,
+        ];
+
+        let (page, left) = <ControlPage as ModePage>::from_bytes(&bytes);
+        assert!(left.is_empty(), "whole page consumed");
+        assert_eq!(page.page_code(), CONTROL_PAGE_CODE, "page code");
+        assert_eq!(page.page_length() as usize, PAGE_LENGTH - 2, "page length");
+        assert_eq!(page.task_set_type(), 0, "TST");
+        assert_eq!(page.global_logging_target_save_disable(), 1, "GLTSD");
+        assert_eq!(page.descriptor_format_sense_data(), 0, "D_SENSE");
+        assert_eq!(page.queue_algorithm_modifier(), 1, "QAM");
+        assert_eq!(page.queue_error_management(), 1, "QERR");
+        assert_eq!(page.task_aborted_status(), 1, "TAS");
+        assert_eq!(page.busy_timeout_period(), 0xFFFF, "busy timeout");
+        assert_eq!(
+            page.extended_self_test_completion_time(),
+            1800,
+            "extended self-test"
+        );
+
+        let page = page.with_descriptor_format_sense_data(1);
+        let mut expected = bytes.to_vec();
+        expected[2] |= 0x04;
+        assert_eq!(page.to_bytes(), expected, "MODE SELECT round-trip");
+    }
 }
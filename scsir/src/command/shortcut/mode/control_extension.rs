@@ -28,6 +28,8 @@ pub struct ControlExtensionPage {
     reserved_3: B72,
 }
 
+crate::command::impl_bitfield_serde!(ControlExtensionPage);
+
 impl ModePage for ControlExtensionPage {
     fn new() -> Self {
         Self::new()
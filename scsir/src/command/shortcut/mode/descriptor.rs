@@ -12,6 +12,8 @@ pub struct ShortDescriptor {
     pub logical_block_length: B24,
 }
 
+crate::command::impl_bitfield_serde!(ShortDescriptor);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct LongDescriptor {
@@ -20,13 +22,19 @@ pub struct LongDescriptor {
     pub logical_block_length: B32,
 }
 
+crate::command::impl_bitfield_serde!(LongDescriptor);
+
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum DescriptorType {
     Short,
     Long,
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum DescriptorStorage {
     Short(ShortDescriptor),
     Long(LongDescriptor),
@@ -31,6 +31,8 @@ pub struct DisconnectReconnectFcPage {
     pub first_burst_size: B16,
 }
 
+crate::command::impl_bitfield_serde!(DisconnectReconnectFcPage);
+
 impl ModePage for DisconnectReconnectFcPage {
     fn new() -> Self {
         Self::new()
@@ -26,6 +26,8 @@ pub struct DisconnectReconnectSasPage {
     pub first_burst_size: B16,
 }
 
+crate::command::impl_bitfield_serde!(DisconnectReconnectSasPage);
+
 impl ModePage for DisconnectReconnectSasPage {
     fn new() -> Self {
         Self::new()
@@ -12,6 +12,7 @@ pub const ENHANCED_PHY_CONTROL_PAGE_CODE: u8 = 0x19;
 pub const ENHANCED_PHY_CONTROL_SUBPAGE_CODE: u8 = 0x03;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnhancedPhyControlPage {
     header: EnhancedPhyControlPageHeader,
     descriptors: Vec<EnhancedPhyControlPageDescriptor>,
@@ -32,6 +33,8 @@ pub struct EnhancedPhyControlPageHeader {
     pub number_of_phys: B8,
 }
 
+crate::command::impl_bitfield_serde!(EnhancedPhyControlPageHeader);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct EnhancedPhyControlPageDescriptor {
@@ -49,6 +52,8 @@ pub struct EnhancedPhyControlPageDescriptor {
     pub hardware_muxing_supported: B1,
 }
 
+crate::command::impl_bitfield_serde!(EnhancedPhyControlPageDescriptor);
+
 impl ModePage for EnhancedPhyControlPage {
     fn new() -> Self {
         Self {
@@ -3,6 +3,7 @@
 use super::{ModePage, PageHeaderStorage};
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GeneralPage {
     header: PageHeaderStorage,
     body: Vec<u8>,
@@ -18,6 +18,8 @@ pub struct ShortHeader {
     pub block_descriptor_length: B8,
 }
 
+crate::command::impl_bitfield_serde!(ShortHeader);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct LongHeader {
@@ -33,13 +35,19 @@ pub struct LongHeader {
     pub block_descriptor_length: B16,
 }
 
+crate::command::impl_bitfield_serde!(LongHeader);
+
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum HeaderType {
     Short,
     Long,
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum HeaderStorage {
     Short(ShortHeader),
     Long(LongHeader),
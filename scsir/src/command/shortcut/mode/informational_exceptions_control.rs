@@ -30,6 +30,8 @@ pub struct InformationalExceptionsControlPage {
     pub report_count: B32,
 }
 
+crate::command::impl_bitfield_serde!(InformationalExceptionsControlPage);
+
 impl ModePage for InformationalExceptionsControlPage {
     fn new() -> Self {
         Self::new()
@@ -10,6 +10,7 @@ pub const IO_ADVICE_HINTS_GROUPING_PAGE_CODE: u8 = 0x0A;
 pub const IO_ADVICE_HINTS_GROUPING_SUBPAGE_CODE: u8 = 0x05;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IoAdviceHintsGroupingPage {
     header: IoAdviceHintsGroupingPageHeader,
     descriptors: Vec<IoAdviceHintsGroupingPageDescriptor>,
@@ -26,6 +27,8 @@ pub struct IoAdviceHintsGroupingPageHeader {
     reserved: B96,
 }
 
+crate::command::impl_bitfield_serde!(IoAdviceHintsGroupingPageHeader);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct IoAdviceHintsGroupingPageDescriptor {
@@ -37,6 +40,8 @@ pub struct IoAdviceHintsGroupingPageDescriptor {
     pub logical_block_markup_descriptor: B96,
 }
 
+crate::command::impl_bitfield_serde!(IoAdviceHintsGroupingPageDescriptor);
+
 impl ModePage for IoAdviceHintsGroupingPage {
     fn new() -> Self {
         Self {
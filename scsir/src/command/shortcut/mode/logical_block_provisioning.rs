@@ -12,6 +12,7 @@ pub const LOGICAL_BLOCK_PROVISIONING_PAGE_CODE: u8 = 0x1C;
 pub const LOGICAL_BLOCK_PROVISIONING_SUBPAGE_CODE: u8 = 0x02;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LogicalBlockProvisioningPage {
     header: LogicalBlockProvisioningPageHeader,
     descriptors: Vec<LogicalBlockProvisioningPageDescriptor>,
@@ -30,6 +31,8 @@ pub struct LogicalBlockProvisioningPageHeader {
     reserved_1: B88,
 }
 
+crate::command::impl_bitfield_serde!(LogicalBlockProvisioningPageHeader);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct LogicalBlockProvisioningPageDescriptor {
@@ -42,6 +45,8 @@ pub struct LogicalBlockProvisioningPageDescriptor {
     pub threshold_count: B32,
 }
 
+crate::command::impl_bitfield_serde!(LogicalBlockProvisioningPageDescriptor);
+
 impl ModePage for LogicalBlockProvisioningPage {
     fn new() -> Self {
         Self {
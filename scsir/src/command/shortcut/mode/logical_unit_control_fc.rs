@@ -23,6 +23,8 @@ pub struct LogicalUnitControlFcPage {
     reserved_2: B32,
 }
 
+crate::command::impl_bitfield_serde!(LogicalUnitControlFcPage);
+
 impl ModePage for LogicalUnitControlFcPage {
     fn new() -> Self {
         Self::new()
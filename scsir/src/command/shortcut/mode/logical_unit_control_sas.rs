@@ -22,6 +22,8 @@ pub struct LogicalUnitControlSasPage {
     reserved_1: B40,
 }
 
+crate::command::impl_bitfield_serde!(LogicalUnitControlSasPage);
+
 impl ModePage for LogicalUnitControlSasPage {
     fn new() -> Self {
         Self::new()
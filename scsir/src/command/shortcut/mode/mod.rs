@@ -18,6 +18,7 @@ mod logical_unit_control_fc;
 mod logical_unit_control_sas;
 mod page_header;
 mod page_wrapper;
+mod parameters;
 mod phy_control_and_discover;
 mod power_condition;
 mod power_consumption;
@@ -47,6 +48,7 @@ pub use logical_unit_control_fc::*;
 pub use logical_unit_control_sas::*;
 pub use page_header::*;
 pub use page_wrapper::*;
+pub use parameters::*;
 pub use phy_control_and_discover::*;
 pub use power_condition::*;
 pub use power_consumption::*;
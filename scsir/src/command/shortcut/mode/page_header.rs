@@ -13,6 +13,8 @@ pub struct CommomPageHeader {
     pub page_length: B8,
 }
 
+crate::command::impl_bitfield_serde!(CommomPageHeader);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct CommomSubpageHeader {
@@ -23,7 +25,11 @@ pub struct CommomSubpageHeader {
     pub page_length: B16,
 }
 
+crate::command::impl_bitfield_serde!(CommomSubpageHeader);
+
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum PageHeaderStorage {
     Page(CommomPageHeader),
     Subpage(CommomSubpageHeader),
@@ -1,6 +1,7 @@
-use super::{DescriptorStorage, DescriptorType, HeaderStorage, HeaderType};
+use super::{DescriptorStorage, DescriptorType, HeaderStorage, HeaderType, LongHeader};
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PageWrapper<Page: ModePage> {
     pub header: HeaderStorage,
     pub descriptors: Vec<DescriptorStorage>,
@@ -59,4 +60,75 @@ impl<Page: ModePage> PageWrapper<Page> {
 
         bytes
     }
+
+    /// The parameter list of MODE SELECT (10) writing the page back, issue it with page format.
+    ///
+    /// The header is rebuilt as a long header whatever MODE SENSE returned, with the mode data
+    /// length and the device-specific parameter zeroed as both are reserved for MODE SELECT.
+    /// The block descriptors are kept, and the PS bit of the page is cleared.
+    pub fn to_select_bytes(&self) -> Vec<u8> {
+        let descriptors: Vec<u8> = self
+            .descriptors
+            .iter()
+            .flat_map(DescriptorStorage::to_bytes)
+            .collect();
+        let long_lba = self
+            .descriptors
+            .iter()
+            .any(|item| matches!(item, DescriptorStorage::Long(_)));
+
+        let header = LongHeader::new()
+            .with_medium_type(self.header.medium_type())
+            .with_long_lba(long_lba.into())
+            .with_block_descriptor_length(descriptors.len() as u16);
+
+        let mut bytes = HeaderStorage::Long(header).to_bytes();
+        bytes.extend_from_slice(&descriptors);
+
+        let page = self.page.to_bytes();
+        let page_start = bytes.len();
+        bytes.extend_from_slice(&page);
+        if let Some(first) = bytes.get_mut(page_start) {
+            // PARAMETERS SAVEABLE is reserved for MODE SELECT
+            *first &= 0b0111_1111;
+        }
+
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::shortcut::mode::CachingPage;
+
+    #[test]
+    fn select_bytes_test() {
+        let bytes = [
+            0x23, 0x00, 0x90, 0x08, // MODE SENSE (6) header, write protected and DPOFUA
+            0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x02, 0x00, // block descriptor
+            0x88, 0x12, 0x04, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x14,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // caching, saveable, write cache on
+        ];
+
+        let mut wrapper = PageWrapper::<CachingPage>::from_bytes(
+            HeaderType::Short,
+            DescriptorType::Short,
+            &bytes,
+        );
+        assert_eq!(wrapper.page.write_cache_enable(), 1, "write cache enable");
+        wrapper.page.set_write_cache_enable(0);
+
+        let list = wrapper.to_select_bytes();
+        assert_eq!(
+            list[..8],
+            [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08],
+            "long header"
+        );
+        assert_eq!(list[8..16], bytes[4..12], "block descriptor");
+        assert_eq!(list[16], 0x08, "page code without PS");
+        assert_eq!(list[18], 0x00, "write cache disabled");
+        assert_eq!(list[17], bytes[13], "page length");
+        assert_eq!(list[19..], bytes[15..], "rest of the page");
+    }
 }
@@ -0,0 +1,220 @@
+use std::mem::size_of;
+
+use crate::command::mode_sense::ModeSenseCommand;
+
+use super::{
+    DescriptorStorage, DescriptorType, HeaderStorage, HeaderType, LongHeader, ShortHeader,
+};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModeParameters {
+    pub header: HeaderStorage,
+    pub descriptors: Vec<DescriptorStorage>,
+    /// raw mode pages, decode them with [`super::ModePage::from_bytes`]
+    pub pages: Vec<u8>,
+}
+
+impl ModeParameters {
+    pub fn from_bytes(header_type: HeaderType, bytes: &[u8]) -> Self {
+        let (header, _) = HeaderStorage::from_bytes(header_type, bytes);
+        let length = usize::min(header.required_allocation_length() as usize, bytes.len());
+        let bytes = &bytes[usize::min(header_length(header_type), length)..length];
+
+        let descriptor_type = if header.long_lba() {
+            DescriptorType::Long
+        } else {
+            DescriptorType::Short
+        };
+
+        let mut descriptors = vec![];
+        let (mut descriptor_bytes, bytes) = bytes.split_at(usize::min(
+            header.block_descriptor_length() as usize,
+            bytes.len(),
+        ));
+
+        while !descriptor_bytes.is_empty() {
+            let descriptor;
+            (descriptor, descriptor_bytes) =
+                DescriptorStorage::from_bytes(descriptor_type, descriptor_bytes);
+            descriptors.push(descriptor);
+        }
+
+        Self {
+            header,
+            descriptors,
+            pages: bytes.to_vec(),
+        }
+    }
+
+    /// parameter list for MODE SELECT with the same header type
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header.to_bytes();
+
+        for item in &self.descriptors {
+            bytes.extend_from_slice(&item.to_bytes());
+        }
+
+        bytes.extend_from_slice(&self.pages);
+        bytes
+    }
+}
+
+pub fn mode_parameters_6(this: &mut ModeSenseCommand) -> crate::Result<ModeParameters> {
+    let bytes = this
+        .allocation_length(size_of::<ShortHeader>() as u16)
+        .issue_6()?;
+    let (header, _) = HeaderStorage::from_bytes(HeaderType::Short, &bytes);
+
+    let bytes = this
+        .allocation_length(u16::min(
+            header.required_allocation_length(),
+            u8::MAX as u16,
+        ))
+        .issue_6()?;
+
+    Ok(ModeParameters::from_bytes(HeaderType::Short, &bytes))
+}
+
+pub fn mode_parameters_10(this: &mut ModeSenseCommand) -> crate::Result<ModeParameters> {
+    let bytes = this
+        .allocation_length(size_of::<LongHeader>() as u16)
+        .issue_10()?;
+    let (header, _) = HeaderStorage::from_bytes(HeaderType::Long, &bytes);
+
+    let bytes = this
+        .allocation_length(header.required_allocation_length())
+        .issue_10()?;
+
+    Ok(ModeParameters::from_bytes(HeaderType::Long, &bytes))
+}
+
+fn header_length(header_type: HeaderType) -> usize {
+    match header_type {
+        HeaderType::Short => size_of::<ShortHeader>(),
+        HeaderType::Long => size_of::<LongHeader>(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command::shortcut::mode::{CachingPage, ModePage, CACHING_PAGE_CODE},
+        MockResponse, MockScsi, Scsi,
+    };
+
+    #[test]
+    fn short_header_test() {
+        let bytes = [
+            0x17, 0x00, 0x80, 0x08, // header, write protected
+            0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x02, 0x00, // block descriptor
+            0x08, 0x0A, 0x04, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, // caching
+            0x00, 0x00, // past the mode data length
+        ];
+
+        let parameters = ModeParameters::from_bytes(HeaderType::Short, &bytes);
+        assert!(parameters.header.write_protect(), "write protect");
+        assert_eq!(parameters.descriptors.len(), 1, "block descriptors");
+        assert_eq!(
+            parameters.descriptors[0].number_of_blocks(),
+            0x1000,
+            "number of blocks"
+        );
+        assert_eq!(
+            parameters.descriptors[0].logical_block_length(),
+            512,
+            "logical block length"
+        );
+        assert_eq!(parameters.pages, bytes[12..24], "mode pages");
+    }
+
+    #[test]
+    fn long_lba_test() {
+        let mut bytes = vec![
+            0x00, 0x28, 0x00, 0x00, 0x01, 0x00, 0x00, 0x20, // header, long lba
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, // first block descriptor
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x02, // second block descriptor
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00,
+        ];
+        let page = [0x1C, 0x00];
+        bytes.extend_from_slice(&page);
+
+        let parameters = ModeParameters::from_bytes(HeaderType::Long, &bytes);
+        assert!(parameters.header.long_lba(), "long lba");
+        assert_eq!(parameters.descriptors.len(), 2, "block descriptors");
+        assert!(
+            matches!(parameters.descriptors[0], DescriptorStorage::Long(_)),
+            "long block descriptor"
+        );
+        assert_eq!(
+            parameters.descriptors[0].number_of_blocks(),
+            0x1_0000_0000,
+            "number of blocks"
+        );
+        assert_eq!(
+            parameters.descriptors[1].logical_block_length(),
+            512,
+            "logical block length"
+        );
+        assert_eq!(parameters.pages, page, "mode pages");
+
+        // without long lba the same descriptor bytes are four short descriptors
+        bytes[4] = 0;
+        let parameters = ModeParameters::from_bytes(HeaderType::Long, &bytes);
+        assert_eq!(parameters.descriptors.len(), 4, "short block descriptors");
+    }
+
+    #[test]
+    fn caching_round_trip_test() {
+        let mut bytes = vec![0x17, 0x00, 0x00, 0x00];
+        bytes.extend_from_slice(&[
+            0x88, 0x12, 0x04, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x14,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ]);
+
+        let mock = MockScsi::new();
+        // MODE SENSE (6) of the header, then of the whole caching page
+        mock.expect(
+            &[0x1A, 0x00, 0x08, 0x00, 0x04, 0x00],
+            MockResponse::good().data(&bytes),
+        )
+        .expect(
+            &[0x1A, 0x00, 0x08, 0x00, 0x18, 0x00],
+            MockResponse::good().data(&bytes),
+        )
+        // MODE SELECT (6) with PF
+        .expect(&[0x15, 0x10, 0x00, 0x00, 0x18, 0x00], MockResponse::good());
+
+        let scsi = Scsi::mock(mock.clone());
+        let parameters = mode_parameters_6(scsi.mode_sense().page_code(CACHING_PAGE_CODE)).unwrap();
+        let (mut page, left) = <CachingPage as ModePage>::from_bytes(&parameters.pages);
+        assert!(left.is_empty(), "single page");
+        assert_eq!(page.write_cache_enable(), 1, "write cache enable");
+
+        // PS is reserved for MODE SELECT
+        page.set_parameters_saveable(0);
+        page.set_write_cache_enable(0);
+        let parameters = ModeParameters {
+            pages: ModePage::to_bytes(&page),
+            ..parameters
+        };
+        scsi.mode_select()
+            .page_format(true)
+            .clear_mode_data_length(true)
+            .parameter(&parameters.to_bytes())
+            .issue_6()
+            .unwrap();
+
+        let list = &mock.issued()[2].data;
+        assert_eq!(list.len(), bytes.len(), "parameter list length");
+        assert_eq!(list[0], 0x00, "cleared mode data length");
+        assert_eq!(list[1..4], bytes[1..4], "unchanged header");
+        assert_eq!(list[4], 0x08, "page code without PS");
+        assert_eq!(list[5], bytes[5], "page length");
+        assert_eq!(list[6], 0x00, "write cache disabled");
+        assert_eq!(list[7..], bytes[7..], "unchanged page");
+        mock.verify();
+    }
+}
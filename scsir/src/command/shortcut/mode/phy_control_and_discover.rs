@@ -12,6 +12,7 @@ pub const PHY_CONTROL_AND_DISCOVER_PAGE_CODE: u8 = 0x19;
 pub const PHY_CONTROL_AND_DISCOVER_SUBPAGE_CODE: u8 = 0x01;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PhyControlAndDiscoverPage {
     header: PhyControlAndDiscoverPageHeader,
     descriptors: Vec<PhyControlAndDiscoverPageDescriptor>,
@@ -32,6 +33,8 @@ pub struct PhyControlAndDiscoverPageHeader {
     number_of_phys: B8,
 }
 
+crate::command::impl_bitfield_serde!(PhyControlAndDiscoverPageHeader);
+
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
 pub struct PhyControlAndDiscoverPageDescriptor {
@@ -66,6 +69,8 @@ pub struct PhyControlAndDiscoverPageDescriptor {
     reserved_10: B32,
 }
 
+crate::command::impl_bitfield_serde!(PhyControlAndDiscoverPageDescriptor);
+
 impl ModePage for PhyControlAndDiscoverPage {
     fn new() -> Self {
         Self {
@@ -36,6 +36,8 @@ pub struct PowerConditionPage {
     reserved_3: B2,
 }
 
+crate::command::impl_bitfield_serde!(PowerConditionPage);
+
 impl ModePage for PowerConditionPage {
     fn new() -> Self {
         Self::new()
@@ -24,6 +24,8 @@ pub struct PowerConsumptionPage {
     reserved_2: B64,
 }
 
+crate::command::impl_bitfield_serde!(PowerConsumptionPage);
+
 impl ModePage for PowerConsumptionPage {
     fn new() -> Self {
         Self::new()
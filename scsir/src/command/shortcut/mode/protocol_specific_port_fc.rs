@@ -30,6 +30,8 @@ pub struct ProtocolSpecificPortFcpage {
     pub sequence_initiative_resource_recovery_timeout_value: B8,
 }
 
+crate::command::impl_bitfield_serde!(ProtocolSpecificPortFcpage);
+
 impl ModePage for ProtocolSpecificPortFcpage {
     fn new() -> Self {
         Self::new()
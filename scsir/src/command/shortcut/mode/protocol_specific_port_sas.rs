@@ -29,6 +29,8 @@ pub struct ProtocolSpecificPortSasPage {
     reserved_2: B40,
 }
 
+crate::command::impl_bitfield_serde!(ProtocolSpecificPortSasPage);
+
 impl ModePage for ProtocolSpecificPortSasPage {
     fn new() -> Self {
         Self::new()
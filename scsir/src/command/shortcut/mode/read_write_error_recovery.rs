@@ -32,6 +32,8 @@ pub struct ReadWriteErrorRecoveryPage {
     pub recovery_time_limit: B16,
 }
 
+crate::command::impl_bitfield_serde!(ReadWriteErrorRecoveryPage);
+
 impl ModePage for ReadWriteErrorRecoveryPage {
     fn new() -> Self {
         Self::new()
@@ -63,4 +65,26 @@ mod tests {
             concat!("Size of: ", stringify!(ReadWriteErrorRecoveryPage))
         );
     }
+
+    #[test]
+    fn decode_test() {
+        // AWRE, ARRE, 20 read and write retries, 0 ms recovery time limit
+        let bytes = [
+            0x01, 0x0A, 0xC0, 0x14, 0x00, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00,
+        ];
+
+        let (page, _) = <ReadWriteErrorRecoveryPage as ModePage>::from_bytes(&bytes);
+        assert_eq!(
+            page.page_code(),
+            READ_WRITE_ERROR_RECOVERY_PAGE_CODE,
+            "page code"
+        );
+        assert_eq!(page.automatic_write_reallocation_enabled(), 1, "AWRE");
+        assert_eq!(page.automatic_read_reallocation_enabled(), 1, "ARRE");
+        assert_eq!(page.post_error(), 0, "PER");
+        assert_eq!(page.read_retry_count(), 20, "read retries");
+        assert_eq!(page.write_retry_count(), 20, "write retries");
+        assert_eq!(page.recovery_time_limit(), 0, "recovery time limit");
+        assert_eq!(page.to_bytes(), bytes, "MODE SELECT round-trip");
+    }
 }
@@ -26,6 +26,8 @@ pub struct SharedPortControlPage {
     reserved_3: B48,
 }
 
+crate::command::impl_bitfield_serde!(SharedPortControlPage);
+
 impl ModePage for SharedPortControlPage {
     fn new() -> Self {
         Self::new()
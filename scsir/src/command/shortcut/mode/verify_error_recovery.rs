@@ -27,6 +27,8 @@ pub struct VerifyErrorRecoveryPage {
     pub verify_recovery_time_limit: B16,
 }
 
+crate::command::impl_bitfield_serde!(VerifyErrorRecoveryPage);
+
 impl ModePage for VerifyErrorRecoveryPage {
     fn new() -> Self {
         Self::new()
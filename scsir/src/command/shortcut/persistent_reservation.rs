@@ -0,0 +1,360 @@
+use crate::{
+    command::{persistent_reserve_in, persistent_reserve_out},
+    Scsi,
+};
+
+/// The PERSISTENT RESERVE IN and OUT service actions a cluster node needs, see
+/// [`Scsi::reservation`]. Every reservation has the logical unit as its scope.
+#[derive(Clone, Copy, Debug)]
+pub struct PersistentReservation<'a> {
+    interface: &'a Scsi,
+}
+
+/// The persistent reservation type, see SPC-6 6.17.3.4
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReservationType {
+    WriteExclusive,
+    ExclusiveAccess,
+    WriteExclusiveRegistrantsOnly,
+    ExclusiveAccessRegistrantsOnly,
+    WriteExclusiveAllRegistrants,
+    ExclusiveAccessAllRegistrants,
+    Other(u8),
+}
+
+/// The persistent reservation of the logical unit, see [`PersistentReservation::read_reservation`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReservationHolder {
+    /// the key of the holder, 0 for the all registrants types
+    pub reservation_key: u64,
+    pub reservation_type: ReservationType,
+}
+
+impl PersistentReservation<'_> {
+    /// Registers `key` for this I_T nexus, replacing `current_key` if it is registered already.
+    ///
+    /// A first registration, without a current key, uses REGISTER AND IGNORE EXISTING KEY. With
+    /// a current key it uses REGISTER, which fails with RESERVATION CONFLICT if that key isn't
+    /// registered anymore, so a node another one preempted stays fenced off.
+    pub fn register(&self, current_key: Option<u64>, key: u64) -> crate::Result<()> {
+        match current_key {
+            Some(current_key) => self.issue(
+                persistent_reserve_out::ServiceAction::Register,
+                None,
+                current_key,
+                key,
+            ),
+            None => self.issue(
+                persistent_reserve_out::ServiceAction::RegisterAndIgnoreExistingKey,
+                None,
+                0,
+                key,
+            ),
+        }
+    }
+
+    /// Removes the registration of this I_T nexus, releasing a reservation it holds.
+    pub fn unregister(&self) -> crate::Result<()> {
+        self.issue(
+            persistent_reserve_out::ServiceAction::RegisterAndIgnoreExistingKey,
+            None,
+            0,
+            0,
+        )
+    }
+
+    /// `key` must be the registered key of this I_T nexus
+    pub fn reserve(&self, key: u64, reservation_type: ReservationType) -> crate::Result<()> {
+        self.issue(
+            persistent_reserve_out::ServiceAction::Reserve,
+            Some(reservation_type),
+            key,
+            0,
+        )
+    }
+
+    /// `reservation_type` must be the type the reservation was made with
+    pub fn release(&self, key: u64, reservation_type: ReservationType) -> crate::Result<()> {
+        self.issue(
+            persistent_reserve_out::ServiceAction::Release,
+            Some(reservation_type),
+            key,
+            0,
+        )
+    }
+
+    /// Removes the registrations of `victim_key` and takes over their reservation with
+    /// `reservation_type`.
+    pub fn preempt(
+        &self,
+        key: u64,
+        victim_key: u64,
+        reservation_type: ReservationType,
+    ) -> crate::Result<()> {
+        self.issue(
+            persistent_reserve_out::ServiceAction::Preempt,
+            Some(reservation_type),
+            key,
+            victim_key,
+        )
+    }
+
+    /// The keys of every registered I_T nexus.
+    pub fn read_keys(&self) -> crate::Result<Vec<u64>> {
+        // if the keys don't fit, they are read again with the length the device asks for
+        let result = self
+            .interface
+            .persistent_reserve_in()
+            .service_action(persistent_reserve_in::ServiceAction::ReadKeys)
+            .allocation_length(READ_KEYS_ALLOCATION_LENGTH)
+            .issue_full_length()?;
+        let persistent_reserve_in::CommandResult::ReadKeys(data) = result else {
+            unreachable!("READ KEYS returns the read keys data");
+        };
+
+        Ok(data.reservation_keys)
+    }
+
+    /// The persistent reservation of the logical unit, None if there is none.
+    pub fn read_reservation(&self) -> crate::Result<Option<ReservationHolder>> {
+        let result = self
+            .interface
+            .persistent_reserve_in()
+            .service_action(persistent_reserve_in::ServiceAction::ReadReservation)
+            .allocation_length(READ_RESERVATION_ALLOCATION_LENGTH)
+            .issue()?;
+        let persistent_reserve_in::CommandResult::ReadReservation(data) = result else {
+            unreachable!("READ RESERVATION returns the read reservation data");
+        };
+
+        Ok(data.reservation.map(|reservation| ReservationHolder {
+            reservation_key: reservation.reservation_key,
+            reservation_type: ReservationType::from(reservation.reservation_type),
+        }))
+    }
+
+    fn issue(
+        &self,
+        service_action: persistent_reserve_out::ServiceAction,
+        reservation_type: Option<ReservationType>,
+        reservation_key: u64,
+        service_action_reservation_key: u64,
+    ) -> crate::Result<()> {
+        let mut command = self.interface.persistent_reserve_out();
+        command
+            .service_action(service_action)
+            .reservation_type(reservation_type.map_or(0, u8::from));
+        let mut parameter = command.parameter();
+        let mut data = parameter.basic_parameter();
+        data.reservation_key(reservation_key)
+            .service_action_reservation_key(service_action_reservation_key);
+
+        data.done().done().issue()
+    }
+}
+
+impl Scsi {
+    pub fn reservation(&self) -> PersistentReservation<'_> {
+        PersistentReservation { interface: self }
+    }
+}
+
+impl From<u8> for ReservationType {
+    fn from(value: u8) -> Self {
+        match value {
+            0x01 => Self::WriteExclusive,
+            0x03 => Self::ExclusiveAccess,
+            0x05 => Self::WriteExclusiveRegistrantsOnly,
+            0x06 => Self::ExclusiveAccessRegistrantsOnly,
+            0x07 => Self::WriteExclusiveAllRegistrants,
+            0x08 => Self::ExclusiveAccessAllRegistrants,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl From<ReservationType> for u8 {
+    fn from(value: ReservationType) -> Self {
+        match value {
+            ReservationType::WriteExclusive => 0x01,
+            ReservationType::ExclusiveAccess => 0x03,
+            ReservationType::WriteExclusiveRegistrantsOnly => 0x05,
+            ReservationType::ExclusiveAccessRegistrantsOnly => 0x06,
+            ReservationType::WriteExclusiveAllRegistrants => 0x07,
+            ReservationType::ExclusiveAccessAllRegistrants => 0x08,
+            ReservationType::Other(x) => x,
+        }
+    }
+}
+
+// the header and room for 32 keys
+const READ_KEYS_ALLOCATION_LENGTH: u16 = 8 + 32 * 8;
+// the header and one reservation descriptor
+const READ_RESERVATION_ALLOCATION_LENGTH: u16 = 8 + 16;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockResponse, MockScsi};
+
+    const KEY: u64 = 0x0123_4567_89AB_CDEF;
+
+    // the basic parameter list with a reservation key and service action reservation key
+    fn parameter_list(reservation_key: u64, service_action_reservation_key: u64) -> Vec<u8> {
+        let mut parameter_list = vec![0; 24];
+        parameter_list[..8].copy_from_slice(&reservation_key.to_be_bytes());
+        parameter_list[8..16].copy_from_slice(&service_action_reservation_key.to_be_bytes());
+        parameter_list
+    }
+
+    #[test]
+    fn register_reserve_test() {
+        let mock = MockScsi::new();
+        // REGISTER AND IGNORE EXISTING KEY
+        mock.expect(
+            &[0x5F, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00],
+            MockResponse::good(),
+        )
+        // RESERVE, WRITE EXCLUSIVE
+        .expect(
+            &[0x5F, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00],
+            MockResponse::good(),
+        )
+        // READ RESERVATION
+        .expect(
+            &[0x5E, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00],
+            MockResponse::good().data(&[
+                0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x10, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB,
+                0xCD, 0xEF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+            ]),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let reservation = scsi.reservation();
+        reservation.register(None, KEY).unwrap();
+        reservation
+            .reserve(KEY, ReservationType::WriteExclusive)
+            .unwrap();
+        assert_eq!(
+            reservation.read_reservation().unwrap(),
+            Some(ReservationHolder {
+                reservation_key: KEY,
+                reservation_type: ReservationType::WriteExclusive,
+            }),
+            "reservation"
+        );
+
+        let issued = mock.issued();
+        assert_eq!(
+            issued[0].data,
+            parameter_list(0, KEY),
+            "register with the new key as the service action reservation key"
+        );
+        assert_eq!(
+            issued[1].data,
+            parameter_list(KEY, 0),
+            "reserve with the registered key"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    fn fenced_test() {
+        const NEW_KEY: u64 = 0x2A;
+
+        let mock = MockScsi::new();
+        // REGISTER, replacing the registered key
+        mock.expect(
+            &[0x5F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00],
+            MockResponse::good(),
+        )
+        // another node preempted NEW_KEY, REGISTER with it is a RESERVATION CONFLICT
+        .expect(
+            &[0x5F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00],
+            MockResponse::good().status(0x18),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let reservation = scsi.reservation();
+        reservation.register(Some(KEY), NEW_KEY).unwrap();
+        let error = reservation.register(Some(NEW_KEY), KEY).unwrap_err();
+        assert!(
+            error.to_string().contains("ReservationConflict"),
+            "fenced off: {}",
+            error
+        );
+
+        let issued = mock.issued();
+        assert_eq!(
+            issued[0].data,
+            parameter_list(KEY, NEW_KEY),
+            "register with the current key as the reservation key"
+        );
+        assert_eq!(
+            issued[1].data,
+            parameter_list(NEW_KEY, KEY),
+            "register with the preempted key"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    fn preempt_test() {
+        let mock = MockScsi::new();
+        // PREEMPT, EXCLUSIVE ACCESS REGISTRANTS ONLY
+        mock.expect(
+            &[0x5F, 0x04, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00],
+            MockResponse::good(),
+        )
+        // READ RESERVATION without a reservation
+        .expect(
+            &[0x5E, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00],
+            MockResponse::good().data(&[0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00]),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let reservation = scsi.reservation();
+        reservation
+            .preempt(KEY, 0x2A, ReservationType::ExclusiveAccessRegistrantsOnly)
+            .unwrap();
+        assert_eq!(
+            reservation.read_reservation().unwrap(),
+            None,
+            "no reservation"
+        );
+
+        assert_eq!(
+            mock.issued()[0].data,
+            parameter_list(KEY, 0x2A),
+            "preempt the victim key"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    fn read_keys_test() {
+        let mut keys = vec![0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x01, 0x08];
+        for key in 1..=33_u64 {
+            keys.extend_from_slice(&key.to_be_bytes());
+        }
+
+        let mock = MockScsi::new();
+        // 33 keys don't fit in the first allocation length
+        mock.expect(
+            &[0x5E, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x08, 0x00],
+            MockResponse::good().data(&keys),
+        )
+        .expect(
+            &[0x5E, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x10, 0x00],
+            MockResponse::good().data(&keys),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        assert_eq!(
+            scsi.reservation().read_keys().unwrap(),
+            (1..=33).collect::<Vec<u64>>(),
+            "registered keys"
+        );
+        mock.verify();
+    }
+}
@@ -1,21 +1,43 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
-use crate::{command::bitfield_bound_check, result_data::ResultData, Command, DataDirection, Scsi};
+use crate::{
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
 
 #[derive(Clone, Debug)]
 pub struct StartStopUnitCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     power_condition_modifer: u8,
     power_condition: u8,
     command_buffer: CommandBuffer,
 }
 
+#[derive(Clone, Copy, Debug)]
+pub enum PowerCondition {
+    // process start and load_eject
+    StartValid,
+    Active,
+    Idle,
+    Standby,
+    // obsolete since SBC-3
+    Sleep,
+    LogicalUnitControl,
+    ForceIdle0,
+    ForceStandby0,
+}
+
 impl<'a> StartStopUnitCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             power_condition_modifer: 0,
             power_condition: 0,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
@@ -33,9 +55,17 @@ impl<'a> StartStopUnitCommand<'a> {
         self
     }
 
-    // power_condition must be less than 0x10
-    pub fn power_condition(&mut self, value: u8) -> &mut Self {
-        self.power_condition = value;
+    pub fn power_condition(&mut self, power_condition: PowerCondition) -> &mut Self {
+        self.power_condition = match power_condition {
+            PowerCondition::StartValid => 0x0,
+            PowerCondition::Active => 0x1,
+            PowerCondition::Idle => 0x2,
+            PowerCondition::Standby => 0x3,
+            PowerCondition::Sleep => 0x5,
+            PowerCondition::LogicalUnitControl => 0x7,
+            PowerCondition::ForceIdle0 => 0xA,
+            PowerCondition::ForceStandby0 => 0xB,
+        };
         self
     }
 
@@ -44,6 +74,7 @@ impl<'a> StartStopUnitCommand<'a> {
         self
     }
 
+    // with start set loads the medium, otherwise ejects it
     pub fn load_eject(&mut self, value: bool) -> &mut Self {
         self.command_buffer.set_load_eject(value.into());
         self
@@ -54,30 +85,40 @@ impl<'a> StartStopUnitCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
-    pub fn issue(&mut self) -> crate::Result<()> {
-        bitfield_bound_check!(self.power_condition_modifer, 4, "power condition modifer")?;
-        bitfield_bound_check!(self.power_condition, 4, "power condition")?;
+    timeout_setter!();
 
+    pub fn issue(&mut self) -> crate::Result<()> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self
-                .command_buffer
-                .with_power_condition_modifer(self.power_condition_modifer)
-                .with_power_condition(self.power_condition),
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(self.power_condition_modifer, 4, "power condition modifer")?;
+
+        Ok(self
+            .command_buffer
+            .with_power_condition_modifer(self.power_condition_modifer)
+            .with_power_condition(self.power_condition))
+    }
 }
 
 impl Scsi {
-    pub fn start_stop_unit(&self) -> StartStopUnitCommand {
+    pub fn start_stop_unit(&self) -> StartStopUnitCommand<'_> {
         StartStopUnitCommand::new(self)
     }
 }
 
+crate::command::async_issue!(start_stop_unit: StartStopUnitCommand);
+
 const OPERATION_CODE: u8 = 0x1B;
 
 #[bitfield]
@@ -99,6 +140,7 @@ struct CommandBuffer {
 
 struct ThisCommand {
     command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -124,6 +166,10 @@ impl Command for ThisCommand {
         0
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -147,4 +193,29 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn eject_test() {
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_immediate(1)
+            .with_load_eject(1)
+            .with_start(0);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x1B, 0x01, 0x00, 0x00, 0x02, 0x00],
+            "eject command"
+        );
+
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_power_condition(0x3);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x1B, 0x00, 0x00, 0x00, 0x30, 0x00],
+            "standby command"
+        );
+    }
 }
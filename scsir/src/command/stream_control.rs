@@ -1,14 +1,19 @@
 #![allow(dead_code)]
 
-use std::mem::size_of;
+use std::{mem::size_of, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
-use crate::{command::bitfield_bound_check, result_data::ResultData, Command, DataDirection, Scsi};
+use crate::{
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
 
 #[derive(Clone, Debug)]
 pub struct StreamControlCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     stream_control: u8,
     command_buffer: CommandBuffer,
     data_buffer: DataBuffer,
@@ -24,6 +29,7 @@ impl<'a> StreamControlCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             stream_control: 0,
             command_buffer: CommandBuffer::new()
                 .with_operation_code(OPERATION_CODE)
@@ -43,8 +49,8 @@ impl<'a> StreamControlCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
@@ -52,14 +58,23 @@ impl<'a> StreamControlCommand<'a> {
         ParameterBuilder::new(self)
     }
 
-    pub fn issue(&mut self) -> crate::Result<()> {
-        bitfield_bound_check!(self.stream_control, 2, "stream control")?;
+    timeout_setter!();
 
+    pub fn issue(&mut self) -> crate::Result<()> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self.command_buffer.with_stream_control(self.stream_control),
+            command_buffer: self.command_buffer()?,
             data_buffer: self.data_buffer,
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(self.stream_control, 2, "stream control")?;
+
+        Ok(self.command_buffer.with_stream_control(self.stream_control))
+    }
 }
 
 impl<'a> ParameterBuilder<'a> {
@@ -75,18 +90,20 @@ impl<'a> ParameterBuilder<'a> {
         self
     }
 
-    pub fn done(&mut self) -> crate::Result<&'a mut StreamControlCommand> {
+    pub fn done(&mut self) -> crate::Result<&'a mut StreamControlCommand<'_>> {
         self.parent.data_buffer = self.data_buffer;
         Ok(self.parent)
     }
 }
 
 impl Scsi {
-    pub fn stream_control(&self) -> StreamControlCommand {
+    pub fn stream_control(&self) -> StreamControlCommand<'_> {
         StreamControlCommand::new(self)
     }
 }
 
+crate::command::async_issue!(stream_control: StreamControlCommand);
+
 const OPERATION_CODE: u8 = 0x9E;
 const SERVICE_ACTION: u8 = 0x14;
 
@@ -115,6 +132,7 @@ struct DataBuffer {
 struct ThisCommand {
     command_buffer: CommandBuffer,
     data_buffer: DataBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -142,6 +160,10 @@ impl Command for ThisCommand {
         size_of::<DataBuffer>() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
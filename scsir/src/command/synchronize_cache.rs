@@ -1,13 +1,21 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
-use crate::{command::bitfield_bound_check, result_data::ResultData, Command, DataDirection, Scsi};
+use crate::{
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
 
 #[derive(Clone, Debug)]
 pub struct SynchronizeCacheCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     immediate: bool,
+    sync_nv: bool,
     group_number: u8,
     logical_block_address: u64,
     number_of_blocks: u32,
@@ -18,7 +26,9 @@ impl<'a> SynchronizeCacheCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             immediate: false,
+            sync_nv: false,
             group_number: 0,
             logical_block_address: 0,
             number_of_blocks: 0,
@@ -31,6 +41,12 @@ impl<'a> SynchronizeCacheCommand<'a> {
         self
     }
 
+    // obsoleted by SBC-3, only synchronizes the non-volatile cache on older devices
+    pub fn sync_nv(&mut self, value: bool) -> &mut Self {
+        self.sync_nv = value;
+        self
+    }
+
     // group_number must be less than 0x20
     pub fn group_number(&mut self, value: u8) -> &mut Self {
         self.group_number = value;
@@ -42,13 +58,14 @@ impl<'a> SynchronizeCacheCommand<'a> {
         self
     }
 
+    // 0 means all logical blocks from logical_block_address to the end of the medium
     pub fn number_of_blocks(&mut self, value: u32) -> &mut Self {
         self.number_of_blocks = value;
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
@@ -72,41 +89,65 @@ impl<'a> SynchronizeCacheCommand<'a> {
         Ok(())
     }
 
+    timeout_setter!();
+
     pub fn issue_10(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_10()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
         self.error_check(32, 16)?;
 
-        let command_buffer = CommandBuffer10::new()
+        Ok(CommandBuffer10::new()
             .with_operation_code(OPERATION_CODE_10)
+            .with_sync_nv(self.sync_nv.into())
             .with_immediate(self.immediate.into())
             .with_logical_block_address(self.logical_block_address as u32)
             .with_group_number(self.group_number)
             .with_number_of_blocks(self.number_of_blocks as u16)
-            .with_control(self.control);
-
-        self.interface.issue(&ThisCommand { command_buffer })
+            .with_control(self.control))
     }
 
     pub fn issue_16(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_16()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
+
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
         self.error_check(64, 32)?;
 
-        let command_buffer = CommandBuffer16::new()
+        Ok(CommandBuffer16::new()
             .with_operation_code(OPERATION_CODE_16)
+            .with_sync_nv(self.sync_nv.into())
             .with_immediate(self.immediate.into())
             .with_logical_block_address(self.logical_block_address)
             .with_number_of_blocks(self.number_of_blocks)
             .with_group_number(self.group_number)
-            .with_control(self.control);
-
-        self.interface.issue(&ThisCommand { command_buffer })
+            .with_control(self.control))
     }
 }
 
 impl Scsi {
-    pub fn synchronize_cache(&self) -> SynchronizeCacheCommand {
+    pub fn synchronize_cache(&self) -> SynchronizeCacheCommand<'_> {
         SynchronizeCacheCommand::new(self)
     }
 }
 
+crate::command::async_issue!(synchronize_cache: SynchronizeCacheCommand);
+
 const OPERATION_CODE_10: u8 = 0x35;
 const OPERATION_CODE_16: u8 = 0x91;
 
@@ -115,7 +156,7 @@ const OPERATION_CODE_16: u8 = 0x91;
 struct CommandBuffer10 {
     operation_code: B8,
     reserved_0: B5,
-    obsolete_0: B1,
+    sync_nv: B1,
     immediate: B1,
     obsolete_1: B1,
     logical_block_address: B32,
@@ -130,7 +171,7 @@ struct CommandBuffer10 {
 struct CommandBuffer16 {
     operation_code: B8,
     reserved_0: B5,
-    obsolete_0: B1,
+    sync_nv: B1,
     immediate: B1,
     obsolete_1: B1,
     logical_block_address: B64,
@@ -142,6 +183,7 @@ struct CommandBuffer16 {
 
 struct ThisCommand<C> {
     command_buffer: C,
+    timeout: Option<Duration>,
 }
 
 impl<C: Copy> Command for ThisCommand<C> {
@@ -167,6 +209,10 @@ impl<C: Copy> Command for ThisCommand<C> {
         0
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -197,4 +243,23 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer16))
         );
     }
+
+    #[test]
+    fn command_buffer_16_test() {
+        // flush from the logical block address to the end of the medium
+        let command_buffer = CommandBuffer16::new()
+            .with_operation_code(OPERATION_CODE_16)
+            .with_immediate(1)
+            .with_logical_block_address(0x0000_0002_0000_0000)
+            .with_number_of_blocks(0);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [
+                0x91, 0x02, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00
+            ],
+            "synchronize cache (16) command"
+        );
+    }
 }
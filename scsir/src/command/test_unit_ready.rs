@@ -1,12 +1,19 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
-use crate::{result_data::ResultData, Command, DataDirection, Scsi};
+use crate::{
+    command::{cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
 
 #[derive(Clone, Debug)]
 pub struct TestUnitReadyCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     command_buffer: CommandBuffer,
 }
 
@@ -14,28 +21,40 @@ impl<'a> TestUnitReadyCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
         }
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<()> {
         self.interface.issue(&ThisCommand {
-            command_buffer: self.command_buffer,
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
         })
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        Ok(self.command_buffer)
+    }
 }
 
 impl Scsi {
-    pub fn test_unit_ready(&self) -> TestUnitReadyCommand {
+    pub fn test_unit_ready(&self) -> TestUnitReadyCommand<'_> {
         TestUnitReadyCommand::new(self)
     }
 }
 
+crate::command::async_issue!(test_unit_ready: TestUnitReadyCommand);
+
 const OPERATION_CODE: u8 = 0x00;
 
 #[bitfield]
@@ -48,6 +67,7 @@ struct CommandBuffer {
 
 struct ThisCommand {
     command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -73,6 +93,10 @@ impl Command for ThisCommand {
         0
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -96,4 +120,29 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn not_ready_test() {
+        use crate::{MockResponse, MockScsi, Scsi};
+
+        let mock = MockScsi::new();
+        // NOT READY, MEDIUM NOT PRESENT
+        mock.expect(
+            &[0x00; 6],
+            MockResponse::check_condition(&[
+                0x70, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x3A, 0x00,
+                0x00, 0x00, 0x00, 0x00,
+            ]),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        assert!(
+            matches!(
+                scsi.test_unit_ready().issue(),
+                Err(crate::Error::NotReady(_))
+            ),
+            "medium not present"
+        );
+        mock.verify();
+    }
 }
@@ -1,12 +1,12 @@
 #![allow(dead_code)]
 
-use std::mem;
+use std::{mem, time::Duration};
 
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, FlexibleStruct},
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    data_wrapper::{impl_pod, AnyType, FlexibleStruct},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -14,6 +14,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct UnmapCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     group_number: u8,
     command_buffer: CommandBuffer,
     data_buffer: FlexibleStruct<UnmapParameterHeader, UnmapBlockDescriptor>,
@@ -22,6 +23,7 @@ pub struct UnmapCommand<'a> {
 #[derive(Debug)]
 pub struct ParameterBuilder<'a> {
     parent: &'a mut UnmapCommand<'a>,
+    maximum_unmap_block_descriptor_count: Option<u32>,
     data_buffer: FlexibleStruct<UnmapParameterHeader, UnmapBlockDescriptor>,
 }
 
@@ -29,6 +31,7 @@ impl<'a> UnmapCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             group_number: 0,
             command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
             data_buffer: FlexibleStruct::new(),
@@ -46,34 +49,50 @@ impl<'a> UnmapCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, control: u8) -> &mut Self {
-        self.command_buffer.set_control(control);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
-    pub fn parameter(&'a mut self) -> ParameterBuilder {
+    pub fn parameter(&'a mut self) -> ParameterBuilder<'a> {
         ParameterBuilder::new(self)
     }
 
-    pub fn issue(&mut self) -> crate::Result<()> {
-        bitfield_bound_check!(self.group_number, 5, "group number")?;
+    timeout_setter!();
 
+    pub fn issue(&mut self) -> crate::Result<()> {
         let temp = ThisCommand {
-            command_buffer: self.command_buffer,
+            command_buffer: self.command_buffer()?,
             data_buffer: self.data_buffer.clone(),
+            timeout: self.timeout,
         };
         self.interface.issue(&temp)
     }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        bitfield_bound_check!(self.group_number, 5, "group number")?;
+
+        Ok(self.command_buffer)
+    }
 }
 
 impl<'a> ParameterBuilder<'a> {
     fn new(parent: &'a mut UnmapCommand<'a>) -> Self {
         Self {
             parent,
+            maximum_unmap_block_descriptor_count: None,
             data_buffer: FlexibleStruct::new(),
         }
     }
 
+    // the limit reported in the block limits vital product data page
+    pub fn maximum_unmap_block_descriptor_count(&mut self, value: u32) -> &mut Self {
+        self.maximum_unmap_block_descriptor_count = Some(value);
+        self
+    }
+
     // You shouldn't add more than 4095 descriptors, which is (2^16 - 1 - 8) / 16
     pub fn add_block_descriptor(
         &mut self,
@@ -88,30 +107,62 @@ impl<'a> ParameterBuilder<'a> {
         self
     }
 
-    pub fn done(&mut self) -> crate::Result<&'a mut UnmapCommand> {
-        let total_size = self.data_buffer.total_size();
-        bitfield_bound_check!(total_size, 16, "parameter list length")?;
+    // each range is a logical block address and a number of logical blocks
+    pub fn add_block_descriptors(&mut self, ranges: &[(u64, u32)]) -> &mut Self {
+        for &(unmap_logical_block_address, number_of_logical_blocks) in ranges {
+            self.add_block_descriptor(unmap_logical_block_address, number_of_logical_blocks);
+        }
+        self
+    }
+
+    pub fn done(&mut self) -> crate::Result<&'a mut UnmapCommand<'_>> {
+        let total_size = fill_header(
+            &mut self.data_buffer,
+            self.maximum_unmap_block_descriptor_count,
+        )?;
 
         self.parent
             .command_buffer
-            .set_parameter_list_length(total_size as u16);
-        let body = unsafe { self.data_buffer.body_as_mut() };
-        body.set_unmap_data_length((total_size - mem::size_of::<u16>()) as u16);
-        body.set_unmap_block_descriptor_data_length(
-            (total_size - mem::size_of::<UnmapParameterHeader>()) as u16,
-        );
-
+            .set_parameter_list_length(total_size);
         self.parent.data_buffer = std::mem::take(&mut self.data_buffer);
         Ok(self.parent)
     }
 }
 
+fn fill_header(
+    data_buffer: &mut FlexibleStruct<UnmapParameterHeader, UnmapBlockDescriptor>,
+    maximum_unmap_block_descriptor_count: Option<u32>,
+) -> crate::Result<u16> {
+    if let Some(maximum) = maximum_unmap_block_descriptor_count {
+        if data_buffer.length() > maximum as usize {
+            return Err(crate::Error::ArgumentOutOfBounds(format!(
+                "Unmap block descriptor count is out of bounds. The maximum possible value is {}, but {} was provided.",
+                maximum,
+                data_buffer.length()
+            )));
+        }
+    }
+
+    let total_size = data_buffer.total_size();
+    bitfield_bound_check!(total_size, 16, "parameter list length")?;
+
+    let body = data_buffer.body_as_mut();
+    body.set_unmap_data_length((total_size - mem::size_of::<u16>()) as u16);
+    body.set_unmap_block_descriptor_data_length(
+        (total_size - mem::size_of::<UnmapParameterHeader>()) as u16,
+    );
+
+    Ok(total_size as u16)
+}
+
 impl Scsi {
-    pub fn unmap(&self) -> UnmapCommand {
+    pub fn unmap(&self) -> UnmapCommand<'_> {
         UnmapCommand::new(self)
     }
 }
 
+crate::command::async_issue!(unmap: UnmapCommand);
+
 const OPERATION_CODE: u8 = 0x42;
 
 #[bitfield]
@@ -145,9 +196,12 @@ struct UnmapBlockDescriptor {
     reserved: B32,
 }
 
+impl_pod!(UnmapParameterHeader, UnmapBlockDescriptor);
+
 struct ThisCommand {
     command_buffer: CommandBuffer,
     data_buffer: FlexibleStruct<UnmapParameterHeader, UnmapBlockDescriptor>,
+    timeout: Option<Duration>,
 }
 
 impl Command for ThisCommand {
@@ -175,6 +229,10 @@ impl Command for ThisCommand {
         self.data_buffer.total_size() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()
@@ -247,4 +305,38 @@ mod tests {
             concat!("UnmapBlockDescriptor 2 comparation")
         );
     }
+
+    #[test]
+    fn two_ranges_test() {
+        let mut data_buffer = FlexibleStruct::<UnmapParameterHeader, UnmapBlockDescriptor>::new();
+        data_buffer.push(
+            UnmapBlockDescriptor::new()
+                .with_unmap_logical_block_address(0x1000)
+                .with_number_of_logical_blocks(0x80),
+        );
+        data_buffer.push(
+            UnmapBlockDescriptor::new()
+                .with_unmap_logical_block_address(0x0001_0000_0000)
+                .with_number_of_logical_blocks(0x10),
+        );
+
+        assert!(
+            fill_header(&mut data_buffer, Some(1)).is_err(),
+            "too many descriptors"
+        );
+
+        let total_size = fill_header(&mut data_buffer, Some(2)).unwrap();
+        assert_eq!(total_size as usize, SG_UNMAP_PARAMETER_2_LEN, "total size");
+        assert_eq!(
+            data_buffer.as_bytes(),
+            [
+                0x00, 0x26, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, // header
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00,
+                0x00, 0x00, // first range
+                0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00,
+                0x00, 0x00, // second range
+            ],
+            "unmap parameter list"
+        );
+    }
 }
@@ -1,10 +1,15 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{
+        bitfield_bound_check, cdb_bytes, logical_block_size_check, timeout_setter, Control,
+        TransferFlags, VARIABLE_LENGTH_OPERATION_CODE,
+    },
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,10 +17,10 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct VerifyCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     control: u8,
-    group_number: u8,
+    transfer_flags: TransferFlags,
     verify_protect: u8,
-    disable_page_out: bool,
     byte_check: u8,
     logical_block_address: u64,
     manually_set_verification_length: bool,
@@ -31,10 +36,10 @@ impl<'a> VerifyCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             control: 0,
-            group_number: 0,
+            transfer_flags: TransferFlags::new(),
             verify_protect: 0,
-            disable_page_out: false,
             byte_check: 0,
             logical_block_address: 0,
             manually_set_verification_length: false,
@@ -42,19 +47,25 @@ impl<'a> VerifyCommand<'a> {
             expected_initial_logical_block_reference_tag: 0,
             expected_logical_block_application_tag: 0,
             logical_block_application_tag_mask: 0,
-            logical_block_size: 512,
+            logical_block_size: 0,
             data_buffer: vec![],
         }
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
     // group_number must be less than 0x20
     pub fn group_number(&mut self, value: u8) -> &mut Self {
-        self.group_number = value;
+        self.transfer_flags.group_number(value);
+        self
+    }
+
+    // replaces DPO, FUA and the group number at once
+    pub fn transfer_flags(&mut self, value: TransferFlags) -> &mut Self {
+        self.transfer_flags = value;
         self
     }
 
@@ -65,7 +76,7 @@ impl<'a> VerifyCommand<'a> {
     }
 
     pub fn disable_page_out(&mut self, value: bool) -> &mut Self {
-        self.disable_page_out = value;
+        self.transfer_flags.disable_page_out(value);
         self
     }
 
@@ -101,6 +112,7 @@ impl<'a> VerifyCommand<'a> {
         self
     }
 
+    // required, the logical block length of READ CAPACITY, see Scsi::capacity
     pub fn logical_block_size(&mut self, value: u32) -> &mut Self {
         self.logical_block_size = value;
         self
@@ -118,15 +130,22 @@ impl<'a> VerifyCommand<'a> {
         verification_length_bits: u32,
         expect_tag: bool,
     ) -> crate::Result<()> {
+        logical_block_size_check(self.logical_block_size)?;
         bitfield_bound_check!(self.verify_protect, 3, "verify protect")?;
         bitfield_bound_check!(self.byte_check, 2, "byte check")?;
-        bitfield_bound_check!(self.group_number, 5, "group number")?;
+        self.transfer_flags.check(5, false)?;
         bitfield_bound_check!(
             self.logical_block_address,
             logical_block_address_bits,
             "logical block address"
         )?;
 
+        byte_check_data(
+            self.byte_check,
+            self.data_buffer.len(),
+            self.logical_block_size,
+        )?;
+
         if self.data_buffer.len() % self.logical_block_size as usize != 0 {
             return Err(crate::Error::BadArgument(format!(
                 "parameter length should be a multiple of logical block size, which is {}.",
@@ -164,7 +183,22 @@ impl<'a> VerifyCommand<'a> {
         Ok(())
     }
 
+    timeout_setter!();
+
     pub fn issue_10(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_10()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            byte_check: self.byte_check,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
         self.error_check(32, 16, false)?;
 
         let verification_length = if self.manually_set_verification_length {
@@ -173,23 +207,31 @@ impl<'a> VerifyCommand<'a> {
             (self.data_buffer.len() / self.logical_block_size as usize) as u32
         };
 
-        let command_buffer = CommandBuffer10::new()
+        Ok(CommandBuffer10::new()
             .with_operation_code(OPERATION_CODE_10)
             .with_verify_protect(self.verify_protect)
-            .with_disable_page_out(self.disable_page_out.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
             .with_byte_check(self.byte_check)
             .with_logical_block_address(self.logical_block_address as u32)
-            .with_group_number(self.group_number)
+            .with_group_number(self.transfer_flags.group_number)
             .with_verification_length(verification_length as u16)
-            .with_control(self.control);
+            .with_control(self.control))
+    }
+
+    pub fn issue_12(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_12()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            byte_check: self.byte_check,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_12(&mut self) -> crate::Result<()> {
+    cdb_bytes!(cdb_bytes_12: issue_12 => command_buffer_12);
+
+    fn command_buffer_12(&self) -> crate::Result<CommandBuffer12> {
         self.error_check(32, 32, false)?;
 
         let verification_length = if self.manually_set_verification_length {
@@ -198,23 +240,31 @@ impl<'a> VerifyCommand<'a> {
             (self.data_buffer.len() / self.logical_block_size as usize) as u32
         };
 
-        let command_buffer = CommandBuffer12::new()
+        Ok(CommandBuffer12::new()
             .with_operation_code(OPERATION_CODE_12)
             .with_verify_protect(self.verify_protect)
-            .with_disable_page_out(self.disable_page_out.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
             .with_byte_check(self.byte_check)
             .with_logical_block_address(self.logical_block_address as u32)
             .with_verification_length(verification_length)
-            .with_group_number(self.group_number)
-            .with_control(self.control);
+            .with_group_number(self.transfer_flags.group_number)
+            .with_control(self.control))
+    }
+
+    pub fn issue_16(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_16()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            byte_check: self.byte_check,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_16(&mut self) -> crate::Result<()> {
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
+
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
         self.error_check(64, 32, false)?;
 
         let verification_length = if self.manually_set_verification_length {
@@ -223,23 +273,31 @@ impl<'a> VerifyCommand<'a> {
             (self.data_buffer.len() / self.logical_block_size as usize) as u32
         };
 
-        let command_buffer = CommandBuffer16::new()
+        Ok(CommandBuffer16::new()
             .with_operation_code(OPERATION_CODE_16)
             .with_verify_protect(self.verify_protect)
-            .with_disable_page_out(self.disable_page_out.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
             .with_byte_check(self.byte_check)
             .with_logical_block_address(self.logical_block_address)
             .with_verification_length(verification_length)
-            .with_group_number(self.group_number)
-            .with_control(self.control);
+            .with_group_number(self.transfer_flags.group_number)
+            .with_control(self.control))
+    }
+
+    pub fn issue_32(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_32()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            byte_check: self.byte_check,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_32(&mut self) -> crate::Result<()> {
+    cdb_bytes!(cdb_bytes_32: issue_32 => command_buffer_32);
+
+    fn command_buffer_32(&self) -> crate::Result<CommandBuffer32> {
         self.error_check(64, 32, true)?;
 
         let verification_length = if self.manually_set_verification_length {
@@ -248,14 +306,13 @@ impl<'a> VerifyCommand<'a> {
             (self.data_buffer.len() / self.logical_block_size as usize) as u32
         };
 
-        let command_buffer = CommandBuffer32::new()
+        Ok(CommandBuffer32::new()
             .with_operation_code(OPERATION_CODE_32)
             .with_control(self.control)
-            .with_group_number(self.group_number)
-            .with_additional_cdb_length(0x18)
+            .with_group_number(self.transfer_flags.group_number)
             .with_service_action(SERVICE_ACTION_32)
             .with_verify_protect(self.verify_protect)
-            .with_disable_page_out(self.disable_page_out.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
             .with_byte_check(self.byte_check)
             .with_logical_block_address(self.logical_block_address)
             .with_expected_initial_logical_block_reference_tag(
@@ -265,25 +322,49 @@ impl<'a> VerifyCommand<'a> {
                 self.expected_logical_block_application_tag,
             )
             .with_logical_block_application_tag_mask(self.logical_block_application_tag_mask)
-            .with_verification_length(verification_length);
+            .with_verification_length(verification_length))
+    }
+}
 
-        self.interface.issue(&ThisCommand {
-            command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
-        })
+// byte check 0 verifies the medium only, 1 compares every logical block, and 3 compares every
+// logical block against a single one
+fn byte_check_data(
+    byte_check: u8,
+    data_length: usize,
+    logical_block_size: u32,
+) -> crate::Result<()> {
+    match byte_check {
+        0 if data_length != 0 => Err(crate::Error::BadArgument(
+            "parameter is not allowed without byte check.".to_owned(),
+        )),
+        0b01 | 0b11 if data_length == 0 => Err(crate::Error::BadArgument(
+            "parameter is required with byte check.".to_owned(),
+        )),
+        0b10 => Err(crate::Error::BadArgument(
+            "byte check 0b10 is reserved.".to_owned(),
+        )),
+        0b11 if data_length != logical_block_size as usize => {
+            Err(crate::Error::BadArgument(format!(
+                "parameter length should be exactly one logical block, which is {}, but {} was provided.",
+                logical_block_size, data_length
+            )))
+        }
+        _ => Ok(()),
     }
 }
 
 impl Scsi {
-    pub fn verify(&self) -> VerifyCommand {
+    pub fn verify(&self) -> VerifyCommand<'_> {
         VerifyCommand::new(self)
     }
 }
 
+crate::command::async_issue!(verify: VerifyCommand);
+
 const OPERATION_CODE_10: u8 = 0x2F;
 const OPERATION_CODE_12: u8 = 0xAF;
 const OPERATION_CODE_16: u8 = 0x8F;
-const OPERATION_CODE_32: u8 = 0x7F;
+const OPERATION_CODE_32: u8 = VARIABLE_LENGTH_OPERATION_CODE;
 const SERVICE_ACTION_32: u16 = 0x000A;
 
 #[bitfield]
@@ -357,22 +438,28 @@ struct CommandBuffer32 {
     verification_length: B32,
 }
 
-struct ThisCommand<C> {
+struct ThisCommand<'a, C> {
     command_buffer: C,
-    data_buffer: VecBufferWrapper,
+    byte_check: u8,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl<C: Copy> Command for ThisCommand<C> {
+impl<'a, C: Copy> Command for ThisCommand<'a, C> {
     type CommandBuffer = C;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
+    // only a byte check sends the data to compare against
     fn direction(&self) -> DataDirection {
-        DataDirection::ToDevice
+        match self.byte_check {
+            0 => DataDirection::None,
+            _ => DataDirection::ToDevice,
+        }
     }
 
     fn command(&self) -> Self::CommandBuffer {
@@ -380,13 +467,17 @@ impl<C: Copy> Command for ThisCommand<C> {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -431,4 +522,111 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer32))
         );
     }
+
+    #[test]
+    fn byte_check_test() {
+        assert!(byte_check_data(0, 0, 512).is_ok(), "medium verify");
+        assert!(
+            matches!(
+                byte_check_data(0, 512, 512),
+                Err(crate::Error::BadArgument(_))
+            ),
+            "medium verify with data"
+        );
+        assert!(
+            byte_check_data(1, 2048, 512).is_ok(),
+            "byte by byte compare"
+        );
+        assert!(
+            matches!(
+                byte_check_data(2, 512, 512),
+                Err(crate::Error::BadArgument(_))
+            ),
+            "reserved byte check"
+        );
+        assert!(byte_check_data(3, 512, 512).is_ok(), "single block compare");
+        assert!(
+            matches!(
+                byte_check_data(3, 1024, 512),
+                Err(crate::Error::BadArgument(_))
+            ),
+            "single block compare with two blocks"
+        );
+
+        assert!(
+            matches!(
+                byte_check_data(1, 0, 512),
+                Err(crate::Error::BadArgument(_))
+            ),
+            "byte by byte compare without data"
+        );
+        assert!(
+            matches!(
+                byte_check_data(3, 0, 512),
+                Err(crate::Error::BadArgument(_))
+            ),
+            "single block compare without data"
+        );
+
+        let command = ThisCommand {
+            command_buffer: CommandBuffer16::new().with_verification_length(8),
+            byte_check: 0,
+            data_buffer: SliceBufferWrapper(&[]),
+            timeout: None,
+        };
+        assert!(
+            matches!(command.direction(), DataDirection::None),
+            "medium verify direction"
+        );
+
+        let command = ThisCommand {
+            command_buffer: CommandBuffer16::new().with_byte_check(1),
+            byte_check: 1,
+            data_buffer: SliceBufferWrapper(&[0; 512]),
+            timeout: None,
+        };
+        assert!(
+            matches!(command.direction(), DataDirection::ToDevice),
+            "byte by byte compare direction"
+        );
+    }
+
+    #[test]
+    fn byte_check_issue_test() {
+        let mock = crate::MockScsi::new();
+        let scsi = Scsi::mock(mock.clone());
+
+        for byte_check in [1, 3] {
+            assert!(
+                matches!(
+                    scsi.verify()
+                        .byte_check(byte_check)
+                        .verification_length(1)
+                        .logical_block_size(512)
+                        .issue_16(),
+                    Err(crate::Error::BadArgument(_))
+                ),
+                "byte check {} without data",
+                byte_check
+            );
+        }
+        assert!(mock.issued().is_empty(), "nothing reached the device");
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.verify();
+        command
+            .byte_check(0b01)
+            .logical_block_size(512)
+            .parameter(&[0xA5; 1024]);
+
+        // the parameter is handed to the driver without a copy
+        let before = allocations();
+        command.issue_16().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
@@ -1,10 +1,16 @@
 #![allow(dead_code)]
 
+use std::{borrow::Cow, time::Duration};
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{
+        bitfield_bound_check, cdb_bytes, logical_block_size_check, needs_16_byte_cdb,
+        protection_information::{protection_check, transferred_block_size, ProtectionType},
+        timeout_setter, Control, TransferFlags, VARIABLE_LENGTH_OPERATION_CODE,
+    },
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,11 +18,10 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct WriteCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     control: u8,
-    group_number: u8,
+    transfer_flags: TransferFlags,
     write_protect: u8,
-    disable_page_out: bool,
-    force_unit_access: bool,
     logical_block_address: u64,
     expected_initial_logical_block_reference_tag: u32,
     expected_logical_block_application_tag: u16,
@@ -24,19 +29,27 @@ pub struct WriteCommand<'a> {
     dld_0: bool,
     dld_1: bool,
     dld_2: bool,
+    transfer_length: Option<u32>,
     logical_block_size: u32,
-    data_buffer: Vec<u8>,
+    protection: Option<ProtectionType>,
+    data_buffer: Cow<'a, [u8]>,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandResult {
+    /// bytes not transferred, non-zero means the write was partial
+    pub residual: usize,
 }
 
 impl<'a> WriteCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             control: 0,
-            group_number: 0,
+            transfer_flags: TransferFlags::new(),
             write_protect: 0,
-            disable_page_out: false,
-            force_unit_access: false,
             logical_block_address: 0,
             expected_initial_logical_block_reference_tag: 0,
             expected_logical_block_application_tag: 0,
@@ -44,30 +57,38 @@ impl<'a> WriteCommand<'a> {
             dld_0: false,
             dld_1: false,
             dld_2: false,
-            logical_block_size: 512,
-            data_buffer: vec![],
+            transfer_length: None,
+            logical_block_size: 0,
+            protection: None,
+            data_buffer: Cow::Borrowed(&[]),
         }
     }
 
     // group_number must be less than 0x40 for write(16) or less than 0x20 for others
     pub fn group_number(&mut self, value: u8) -> &mut Self {
-        self.group_number = value;
+        self.transfer_flags.group_number(value);
+        self
+    }
+
+    // replaces DPO, FUA and the group number at once
+    pub fn transfer_flags(&mut self, value: TransferFlags) -> &mut Self {
+        self.transfer_flags = value;
         self
     }
 
-    // write_protect must be less than 0x08
+    // write_protect must be less than 0x08, and 0 without a protection type
     pub fn write_protect(&mut self, value: u8) -> &mut Self {
         self.write_protect = value;
         self
     }
 
     pub fn disable_page_out(&mut self, value: bool) -> &mut Self {
-        self.disable_page_out = value;
+        self.transfer_flags.disable_page_out(value);
         self
     }
 
     pub fn force_unit_access(&mut self, value: bool) -> &mut Self {
-        self.force_unit_access = value;
+        self.transfer_flags.force_unit_access(value);
         self
     }
 
@@ -106,19 +127,49 @@ impl<'a> WriteCommand<'a> {
         self
     }
 
+    // transfer length is derived from the parameter length if not set
+    pub fn transfer_length(&mut self, value: u32) -> &mut Self {
+        self.transfer_length = Some(value);
+        self
+    }
+
+    // required, the logical block length of READ CAPACITY, see Scsi::capacity
     pub fn logical_block_size(&mut self, value: u32) -> &mut Self {
         self.logical_block_size = value;
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
+    // the parameter is written to the medium as is, double check the logical block address
+    // and the logical block size before issuing
     pub fn parameter(&mut self, value: &[u8]) -> &mut Self {
-        self.data_buffer.clear();
-        self.data_buffer.extend_from_slice(value);
+        match &mut self.data_buffer {
+            Cow::Owned(buffer) => {
+                buffer.clear();
+                buffer.extend_from_slice(value);
+            }
+            Cow::Borrowed(_) => self.data_buffer = Cow::Owned(value.to_vec()),
+        }
+        self
+    }
+
+    // like parameter, but the driver reads the slice itself instead of a copy. The slice is
+    // borrowed until the command is dropped. With ScsiBuilder::direct the slice must also be
+    // aligned for DMA, a buffer of a BufferPool always is
+    pub fn borrowed_parameter(&mut self, value: &'a [u8]) -> &mut Self {
+        self.data_buffer = Cow::Borrowed(value);
+        self
+    }
+
+    // with a protection type, every block of the parameter is followed by 8 bytes of
+    // protection information, see protection_information::interleave. write_protect must not
+    // be 0
+    pub fn protection(&mut self, value: Option<ProtectionType>) -> &mut Self {
+        self.protection = value;
         self
     }
 
@@ -129,8 +180,8 @@ impl<'a> WriteCommand<'a> {
         transfer_length_bits: u32,
         allow_dld: bool,
         expect_tag: bool,
-    ) -> crate::Result<()> {
-        bitfield_bound_check!(self.group_number, group_number_bits, "group number")?;
+    ) -> crate::Result<u32> {
+        self.transfer_flags.check(group_number_bits, true)?;
         bitfield_bound_check!(self.write_protect, 3, "verify protect")?;
         bitfield_bound_check!(
             self.logical_block_address,
@@ -138,23 +189,13 @@ impl<'a> WriteCommand<'a> {
             "logical block address"
         )?;
 
-        if self.data_buffer.len() % self.logical_block_size as usize != 0 {
-            return Err(crate::Error::BadArgument(format!(
-                "parameter length should be a multiple of logical block size, which is {}.",
-                self.logical_block_size
-            )));
-        }
-
-        if (self.data_buffer.len() / self.logical_block_size as usize)
-            .wrapping_shr(transfer_length_bits)
-            != 0
-        {
-            return Err(crate::Error::ArgumentOutOfBounds(format!(
-                "parameter length is out of bounds. The maximum possible value is {}, but {} was provided.",
-                1u128.wrapping_shl(transfer_length_bits) * self.logical_block_size as u128,
-                self.data_buffer.len()
-            )));
-        }
+        let transfer_length = transfer_length_check(
+            self.data_buffer.len(),
+            transferred_block_size(self.logical_block_size, self.protection),
+            self.transfer_length,
+            transfer_length_bits,
+        )?;
+        protection_check(self.protection, self.write_protect, expect_tag)?;
 
         if !allow_dld && (self.dld_0 || self.dld_1 || self.dld_2) {
             return Err(crate::Error::BadArgument(
@@ -172,87 +213,115 @@ impl<'a> WriteCommand<'a> {
             ));
         }
 
-        Ok(())
+        Ok(transfer_length)
+    }
+
+    timeout_setter!();
+
+    pub fn issue_10(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_10()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
+        })
     }
 
-    pub fn issue_10(&mut self) -> crate::Result<()> {
-        self.error_check(5, 32, 16, false, false)?;
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
+        let transfer_length = self.error_check(5, 32, 16, false, false)?;
 
-        let command_buffer = CommandBuffer10::new()
+        Ok(CommandBuffer10::new()
             .with_operation_code(OPERATION_CODE_10)
             .with_write_protect(self.write_protect)
-            .with_disable_page_out(self.disable_page_out.into())
-            .with_force_unit_access(self.force_unit_access.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
+            .with_force_unit_access(self.transfer_flags.force_unit_access.into())
             .with_logical_block_address(self.logical_block_address as u32)
-            .with_group_number(self.group_number)
-            .with_transfer_length(
-                (self.data_buffer.len() / self.logical_block_size as usize) as u16,
-            )
-            .with_control(self.control);
+            .with_group_number(self.transfer_flags.group_number)
+            .with_transfer_length(transfer_length as u16)
+            .with_control(self.control))
+    }
+
+    pub fn issue_12(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_12()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_12(&mut self) -> crate::Result<()> {
-        self.error_check(5, 32, 32, false, false)?;
+    cdb_bytes!(cdb_bytes_12: issue_12 => command_buffer_12);
 
-        let command_buffer = CommandBuffer12::new()
+    fn command_buffer_12(&self) -> crate::Result<CommandBuffer12> {
+        let transfer_length = self.error_check(5, 32, 32, false, false)?;
+
+        Ok(CommandBuffer12::new()
             .with_operation_code(OPERATION_CODE_12)
             .with_write_protect(self.write_protect)
-            .with_disable_page_out(self.disable_page_out.into())
-            .with_force_unit_access(self.force_unit_access.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
+            .with_force_unit_access(self.transfer_flags.force_unit_access.into())
             .with_logical_block_address(self.logical_block_address as u32)
-            .with_transfer_length(
-                (self.data_buffer.len() / self.logical_block_size as usize) as u32,
-            )
-            .with_group_number(self.group_number)
-            .with_control(self.control);
+            .with_transfer_length(transfer_length)
+            .with_group_number(self.transfer_flags.group_number)
+            .with_control(self.control))
+    }
+
+    pub fn issue_16(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_16()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_16(&mut self) -> crate::Result<()> {
-        self.error_check(6, 64, 32, true, false)?;
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
 
-        let command_buffer = CommandBuffer16::new()
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
+        let transfer_length = self.error_check(6, 64, 32, true, false)?;
+
+        Ok(CommandBuffer16::new()
             .with_operation_code(OPERATION_CODE_16)
             .with_write_protect(self.write_protect)
-            .with_disable_page_out(self.disable_page_out.into())
-            .with_force_unit_access(self.force_unit_access.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
+            .with_force_unit_access(self.transfer_flags.force_unit_access.into())
             .with_logical_block_address(self.logical_block_address)
-            .with_transfer_length(
-                (self.data_buffer.len() / self.logical_block_size as usize) as u32,
-            )
+            .with_transfer_length(transfer_length)
             .with_dld_0(self.dld_0.into())
             .with_dld_1(self.dld_1.into())
             .with_dld_2(self.dld_2.into())
-            .with_group_number(self.group_number)
-            .with_control(self.control);
+            .with_group_number(self.transfer_flags.group_number)
+            .with_control(self.control))
+    }
+
+    pub fn issue_32(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_32()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_32(&mut self) -> crate::Result<()> {
-        self.error_check(5, 64, 32, false, true)?;
+    cdb_bytes!(cdb_bytes_32: issue_32 => command_buffer_32);
 
-        let command_buffer = CommandBuffer32::new()
+    fn command_buffer_32(&self) -> crate::Result<CommandBuffer32> {
+        let transfer_length = self.error_check(5, 64, 32, false, true)?;
+
+        Ok(CommandBuffer32::new()
             .with_operation_code(OPERATION_CODE_32)
             .with_control(self.control)
-            .with_group_number(self.group_number)
-            .with_additional_cdb_length(0x18)
+            .with_group_number(self.transfer_flags.group_number)
             .with_service_action(SERVICE_ACTION_32)
             .with_write_protect(self.write_protect)
-            .with_disable_page_out(self.disable_page_out.into())
-            .with_force_unit_access(self.force_unit_access.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
+            .with_force_unit_access(self.transfer_flags.force_unit_access.into())
             .with_logical_block_address(self.logical_block_address)
             .with_expected_initial_logical_block_reference_tag(
                 self.expected_initial_logical_block_reference_tag,
@@ -261,27 +330,83 @@ impl<'a> WriteCommand<'a> {
                 self.expected_logical_block_application_tag,
             )
             .with_logical_block_application_tag_mask(self.logical_block_application_tag_mask)
-            .with_transfer_length(
-                (self.data_buffer.len() / self.logical_block_size as usize) as u32,
-            );
+            .with_transfer_length(transfer_length))
+    }
+}
 
-        self.interface.issue(&ThisCommand {
-            command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
-        })
+pub(crate) fn transfer_length_check(
+    data_length: usize,
+    logical_block_size: u32,
+    transfer_length: Option<u32>,
+    transfer_length_bits: u32,
+) -> crate::Result<u32> {
+    logical_block_size_check(logical_block_size)?;
+
+    if data_length % logical_block_size as usize != 0 {
+        return Err(crate::Error::BadArgument(format!(
+            "parameter length should be a multiple of logical block size, which is {}.",
+            logical_block_size
+        )));
+    }
+
+    // both sg and SPTD take the data length as 32 bits
+    bitfield_bound_check!(data_length, 32, "parameter length")?;
+
+    let block_count = data_length / logical_block_size as usize;
+    if block_count.wrapping_shr(transfer_length_bits) != 0 {
+        return Err(crate::Error::ArgumentOutOfBounds(format!(
+            "parameter length is out of bounds. The maximum possible value is {}, but {} was provided.",
+            1u128.wrapping_shl(transfer_length_bits) * logical_block_size as u128,
+            data_length
+        )));
+    }
+
+    match transfer_length {
+        Some(transfer_length) if transfer_length as usize != block_count => {
+            Err(crate::Error::ArgumentOutOfBounds(format!(
+                "parameter length does not match transfer length. Expected {} bytes, but {} was provided.",
+                transfer_length as u64 * logical_block_size as u64,
+                data_length
+            )))
+        }
+        _ => Ok(block_count as u32),
     }
 }
 
 impl Scsi {
-    pub fn write(&self) -> WriteCommand {
+    pub fn write(&self) -> WriteCommand<'_> {
         WriteCommand::new(self)
     }
+
+    /// Writes `data`, a whole number of blocks, with WRITE (10), or WRITE (16) if the range or
+    /// the device needs it, see [`Scsi::supported_commands`].
+    pub fn write_blocks(
+        &self,
+        logical_block_address: u64,
+        data: &[u8],
+        logical_block_size: u32,
+    ) -> crate::Result<CommandResult> {
+        let block_count = transfer_length_check(data.len(), logical_block_size, None, 32)?;
+
+        let mut command = self.write();
+        command
+            .logical_block_address(logical_block_address)
+            .logical_block_size(logical_block_size)
+            .borrowed_parameter(data);
+
+        match needs_16_byte_cdb(self, OPERATION_CODE_10, logical_block_address, block_count)? {
+            true => command.issue_16(),
+            false => command.issue_10(),
+        }
+    }
 }
 
+crate::command::async_issue!(write: WriteCommand);
+
 const OPERATION_CODE_10: u8 = 0x2A;
 const OPERATION_CODE_12: u8 = 0xAA;
 const OPERATION_CODE_16: u8 = 0x8A;
-const OPERATION_CODE_32: u8 = 0x7F;
+const OPERATION_CODE_32: u8 = VARIABLE_LENGTH_OPERATION_CODE;
 const SERVICE_ACTION_32: u16 = 0x000B;
 
 #[bitfield]
@@ -358,19 +483,20 @@ struct CommandBuffer32 {
     transfer_length: B32,
 }
 
-struct ThisCommand<C> {
+struct ThisCommand<'a, C> {
     command_buffer: C,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl<C: Copy> Command for ThisCommand<C> {
+impl<'a, C: Copy> Command for ThisCommand<'a, C> {
     type CommandBuffer = C;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
-    type ReturnType = crate::Result<()>;
+    type ReturnType = crate::Result<CommandResult>;
 
     fn direction(&self) -> DataDirection {
         DataDirection::ToDevice
@@ -381,18 +507,24 @@ impl<C: Copy> Command for ThisCommand<C> {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
 
-        Ok(())
+        Ok(CommandResult {
+            residual: result.residual,
+        })
     }
 }
 
@@ -432,4 +564,271 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer32))
         );
     }
+
+    #[test]
+    fn transfer_length_check_test() {
+        assert_eq!(
+            transfer_length_check(4096, 512, None, 32).unwrap(),
+            8,
+            "derived transfer length"
+        );
+        assert_eq!(
+            transfer_length_check(4096, 512, Some(8), 32).unwrap(),
+            8,
+            "matching transfer length"
+        );
+        assert!(
+            matches!(
+                transfer_length_check(4096, 512, Some(16), 32),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "mismatched transfer length"
+        );
+        assert!(
+            matches!(
+                transfer_length_check(4000, 512, None, 32),
+                Err(crate::Error::BadArgument(_))
+            ),
+            "partial logical block"
+        );
+        assert!(
+            matches!(
+                transfer_length_check(0x1_0000 * 512, 512, None, 16),
+                Err(crate::Error::ArgumentOutOfBounds(_))
+            ),
+            "write (10) transfer length"
+        );
+    }
+
+    #[test]
+    fn command_buffer_12_test() {
+        let command_buffer = CommandBuffer12::new()
+            .with_operation_code(OPERATION_CODE_12)
+            .with_write_protect(0b010)
+            .with_disable_page_out(1)
+            .with_force_unit_access(1)
+            .with_logical_block_address(0x0102_0304)
+            .with_transfer_length(0x0000_0800)
+            .with_group_number(0x03);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0xAA, 0x58, 0x01, 0x02, 0x03, 0x04, 0x00, 0x00, 0x08, 0x00, 0x03, 0x00],
+            "write (12) command"
+        );
+    }
+
+    #[test]
+    fn residual_test() {
+        use crate::{MockResponse, MockScsi};
+
+        let mock = MockScsi::new();
+        // the adapter moved the first block only
+        mock.expect(
+            &[0x2A, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x02, 0x00],
+            MockResponse::good().residual(512),
+        )
+        .expect(
+            &[0x2A, 0x00, 0x00, 0x00, 0x00, 0x12, 0x00, 0x00, 0x02, 0x00],
+            MockResponse::good(),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let data = [0x5A; 1024];
+        let result = scsi
+            .write()
+            .logical_block_address(0x10)
+            .transfer_length(2)
+            .logical_block_size(512)
+            .parameter(&data)
+            .issue_10()
+            .unwrap();
+        assert_eq!(result.residual, 512, "short write");
+
+        let result = scsi
+            .write()
+            .logical_block_address(0x12)
+            .transfer_length(2)
+            .logical_block_size(512)
+            .parameter(&data)
+            .issue_10()
+            .unwrap();
+        assert_eq!(result.residual, 0, "complete write");
+        assert_eq!(mock.issued()[0].data, data, "data out");
+        mock.verify();
+    }
+
+    #[test]
+    fn write_blocks_test() {
+        use crate::{MockResponse, MockScsi};
+
+        // the first LBA past 2 TiB of 512 byte blocks
+        const LBA_2TIB: u64 = 1 << 32;
+
+        // the smallest sufficient CDB
+        let mock = MockScsi::new();
+        mock.expect(
+            &[0x2A, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x01, 0x00],
+            MockResponse::good(),
+        )
+        .expect(
+            &[
+                0x8A, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+                0x00, 0x00,
+            ],
+            MockResponse::good(),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let result = scsi.write_blocks(LBA_2TIB - 1, &[0; 512], 512).unwrap();
+        assert_eq!(result.residual, 0, "residual");
+        scsi.write_blocks(LBA_2TIB, &[0; 512], 512).unwrap();
+        assert!(
+            scsi.write_blocks(0, &[0; 100], 512).is_err(),
+            "partial block"
+        );
+        assert!(
+            scsi.write_blocks(u64::MAX, &[0; 1024], 512).is_err(),
+            "past the last LBA"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    fn borrowed_parameter_test() {
+        use std::{
+            path::Path,
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc,
+            },
+        };
+
+        use crate::{
+            counting_allocator::allocations,
+            os::{Backend, Request, Response},
+        };
+
+        // records the data out pointer of the last write. The mock copies the data out and
+        // allocates for every command, so it can show neither
+        #[derive(Debug)]
+        struct PointerBackend(Arc<AtomicUsize>);
+
+        impl Backend for PointerBackend {
+            fn issue(&self, request: Request) -> Response {
+                self.0.store(request.data as usize, Ordering::Relaxed);
+
+                Response {
+                    ioctl_result: 0,
+                    os_error: 0,
+                    transfered_data_length: request.data_length as usize,
+                    residual: 0,
+                    sense_length: 0,
+                    status: 0x00,
+                    duration: None,
+                    #[cfg(target_os = "linux")]
+                    host_status: 0,
+                    #[cfg(target_os = "linux")]
+                    driver_status: crate::os::linux::DriverStatus::OK,
+                }
+            }
+        }
+
+        let pointer = Arc::new(AtomicUsize::new(0));
+        let scsi = Scsi::with_backend(
+            Path::new("/dev/null"),
+            Box::new(PointerBackend(pointer.clone())),
+        );
+        let data = vec![0xA5; 1 << 20];
+
+        let before = allocations();
+        scsi.write()
+            .logical_block_size(512)
+            .parameter(&data)
+            .issue_16()
+            .unwrap();
+        assert!(allocations() > before, "copied parameter");
+        assert_ne!(
+            pointer.load(Ordering::Relaxed),
+            data.as_ptr() as usize,
+            "copy handed to the driver"
+        );
+
+        let before = allocations();
+        scsi.write()
+            .logical_block_size(512)
+            .borrowed_parameter(&data)
+            .issue_16()
+            .unwrap();
+        assert_eq!(allocations(), before, "no copy of a borrowed parameter");
+        assert_eq!(
+            pointer.load(Ordering::Relaxed),
+            data.as_ptr() as usize,
+            "caller's slice handed to the driver"
+        );
+
+        let before = allocations();
+        scsi.write_blocks(0, &data, 512).unwrap();
+        assert_eq!(allocations(), before, "no copy in write_blocks");
+    }
+
+    #[test]
+    fn protection_test() {
+        use crate::command::protection_information::{
+            guard, interleave, ProtectionInformation, ProtectionType,
+        };
+        use crate::{MockResponse, MockScsi};
+
+        let data = [0xA5; 1024];
+        let protection: Vec<_> = (0..2)
+            .map(|index| ProtectionInformation {
+                guard: guard(&data[..512]),
+                application_tag: 0,
+                reference_tag: ProtectionType::Type1.reference_tag(0x10, 0, index).unwrap(),
+            })
+            .collect();
+        let parameter = interleave(&data, 512, &protection).unwrap();
+
+        let mock = MockScsi::new();
+        // WRPROTECT 1, two blocks of 520 bytes from LBA 0x10
+        mock.expect(
+            &[
+                0x8A, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x02,
+                0x00, 0x00,
+            ],
+            MockResponse::good(),
+        );
+        let scsi = Scsi::mock(mock.clone());
+
+        let mut command = scsi.write();
+        command
+            .logical_block_address(0x10)
+            .logical_block_size(512)
+            .parameter(&parameter);
+        assert!(
+            matches!(command.issue_16(), Err(crate::Error::BadArgument(_))),
+            "protection information without protection"
+        );
+
+        command.write_protect(1);
+        assert!(
+            matches!(command.issue_16(), Err(crate::Error::BadArgument(_))),
+            "write protect needs a protection type"
+        );
+
+        command.protection(Some(ProtectionType::Type1));
+        command.issue_16().unwrap();
+        assert_eq!(
+            mock.issued()[0].data,
+            parameter,
+            "blocks with protection information"
+        );
+
+        command.write_protect(0);
+        assert!(
+            matches!(command.issue_16(), Err(crate::Error::BadArgument(_))),
+            "protection needs write protect"
+        );
+        mock.verify();
+    }
 }
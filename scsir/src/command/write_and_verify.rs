@@ -1,10 +1,15 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{
+        bitfield_bound_check, cdb_bytes, timeout_setter, write::transfer_length_check, Control,
+        TransferFlags, VARIABLE_LENGTH_OPERATION_CODE,
+    },
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,15 +17,16 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct WriteAndVerifyCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     control: u8,
-    group_number: u8,
+    transfer_flags: TransferFlags,
     write_protect: u8,
-    disable_page_out: bool,
     byte_check: u8,
     logical_block_address: u64,
     expected_initial_logical_block_reference_tag: u32,
     expected_logical_block_application_tag: u16,
     logical_block_application_tag_mask: u16,
+    transfer_length: Option<u32>,
     logical_block_size: u32,
     data_buffer: Vec<u8>,
 }
@@ -29,28 +35,35 @@ impl<'a> WriteAndVerifyCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             control: 0,
-            group_number: 0,
+            transfer_flags: TransferFlags::new(),
             write_protect: 0,
-            disable_page_out: false,
             byte_check: 0,
             logical_block_address: 0,
             expected_initial_logical_block_reference_tag: 0,
             expected_logical_block_application_tag: 0,
             logical_block_application_tag_mask: 0,
-            logical_block_size: 512,
+            transfer_length: None,
+            logical_block_size: 0,
             data_buffer: vec![],
         }
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
     // group_number must be less than 0x20
     pub fn group_number(&mut self, value: u8) -> &mut Self {
-        self.group_number = value;
+        self.transfer_flags.group_number(value);
+        self
+    }
+
+    // replaces DPO, FUA and the group number at once
+    pub fn transfer_flags(&mut self, value: TransferFlags) -> &mut Self {
+        self.transfer_flags = value;
         self
     }
 
@@ -61,7 +74,7 @@ impl<'a> WriteAndVerifyCommand<'a> {
     }
 
     pub fn disable_page_out(&mut self, value: bool) -> &mut Self {
-        self.disable_page_out = value;
+        self.transfer_flags.disable_page_out(value);
         self
     }
 
@@ -91,6 +104,13 @@ impl<'a> WriteAndVerifyCommand<'a> {
         self
     }
 
+    // transfer length is derived from the parameter length if not set
+    pub fn transfer_length(&mut self, value: u32) -> &mut Self {
+        self.transfer_length = Some(value);
+        self
+    }
+
+    // required, the logical block length of READ CAPACITY, see Scsi::capacity
     pub fn logical_block_size(&mut self, value: u32) -> &mut Self {
         self.logical_block_size = value;
         self
@@ -107,8 +127,8 @@ impl<'a> WriteAndVerifyCommand<'a> {
         logical_block_address_bits: u32,
         transfer_length_bits: u32,
         expect_tag: bool,
-    ) -> crate::Result<()> {
-        bitfield_bound_check!(self.group_number, 5, "group number")?;
+    ) -> crate::Result<u32> {
+        self.transfer_flags.check(5, false)?;
         bitfield_bound_check!(self.write_protect, 3, "verify protect")?;
         bitfield_bound_check!(self.byte_check, 2, "byte check")?;
         bitfield_bound_check!(
@@ -117,23 +137,12 @@ impl<'a> WriteAndVerifyCommand<'a> {
             "logical block address"
         )?;
 
-        if self.data_buffer.len() % self.logical_block_size as usize != 0 {
-            return Err(crate::Error::BadArgument(format!(
-                "parameter length should be a multiple of logical block size, which is {}.",
-                self.logical_block_size
-            )));
-        }
-
-        if (self.data_buffer.len() / self.logical_block_size as usize)
-            .wrapping_shr(transfer_length_bits)
-            != 0
-        {
-            return Err(crate::Error::ArgumentOutOfBounds(format!(
-                "parameter length is out of bounds. The maximum possible value is {}, but {} was provided.",
-                1u128.wrapping_shl(transfer_length_bits) * self.logical_block_size as u128,
-                self.data_buffer.len()
-            )));
-        }
+        let transfer_length = transfer_length_check(
+            self.data_buffer.len(),
+            self.logical_block_size,
+            self.transfer_length,
+            transfer_length_bits,
+        )?;
 
         if !expect_tag
             && (self.expected_initial_logical_block_reference_tag != 0
@@ -145,83 +154,111 @@ impl<'a> WriteAndVerifyCommand<'a> {
             ));
         }
 
-        Ok(())
+        Ok(transfer_length)
     }
 
+    timeout_setter!();
+
     pub fn issue_10(&mut self) -> crate::Result<()> {
-        self.error_check(32, 16, false)?;
+        let command_buffer = self.command_buffer_10()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
+        })
+    }
 
-        let command_buffer = CommandBuffer10::new()
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
+        let transfer_length = self.error_check(32, 16, false)?;
+
+        Ok(CommandBuffer10::new()
             .with_operation_code(OPERATION_CODE_10)
             .with_write_protect(self.write_protect)
-            .with_disable_page_out(self.disable_page_out.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
             .with_byte_check(self.byte_check)
             .with_logical_block_address(self.logical_block_address as u32)
-            .with_group_number(self.group_number)
-            .with_transfer_length(
-                (self.data_buffer.len() / self.logical_block_size as usize) as u16,
-            )
-            .with_control(self.control);
+            .with_group_number(self.transfer_flags.group_number)
+            .with_transfer_length(transfer_length as u16)
+            .with_control(self.control))
+    }
+
+    pub fn issue_12(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_12()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_12(&mut self) -> crate::Result<()> {
-        self.error_check(32, 32, false)?;
+    cdb_bytes!(cdb_bytes_12: issue_12 => command_buffer_12);
+
+    fn command_buffer_12(&self) -> crate::Result<CommandBuffer12> {
+        let transfer_length = self.error_check(32, 32, false)?;
 
-        let command_buffer = CommandBuffer12::new()
+        Ok(CommandBuffer12::new()
             .with_operation_code(OPERATION_CODE_12)
             .with_write_protect(self.write_protect)
-            .with_disable_page_out(self.disable_page_out.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
             .with_byte_check(self.byte_check)
             .with_logical_block_address(self.logical_block_address as u32)
-            .with_transfer_length(
-                (self.data_buffer.len() / self.logical_block_size as usize) as u32,
-            )
-            .with_group_number(self.group_number)
-            .with_control(self.control);
+            .with_transfer_length(transfer_length)
+            .with_group_number(self.transfer_flags.group_number)
+            .with_control(self.control))
+    }
+
+    pub fn issue_16(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_16()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_16(&mut self) -> crate::Result<()> {
-        self.error_check(64, 32, false)?;
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
 
-        let command_buffer = CommandBuffer16::new()
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
+        let transfer_length = self.error_check(64, 32, false)?;
+
+        Ok(CommandBuffer16::new()
             .with_operation_code(OPERATION_CODE_16)
             .with_write_protect(self.write_protect)
-            .with_disable_page_out(self.disable_page_out.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
             .with_byte_check(self.byte_check)
             .with_logical_block_address(self.logical_block_address)
-            .with_transfer_length(
-                (self.data_buffer.len() / self.logical_block_size as usize) as u32,
-            )
-            .with_group_number(self.group_number)
-            .with_control(self.control);
+            .with_transfer_length(transfer_length)
+            .with_group_number(self.transfer_flags.group_number)
+            .with_control(self.control))
+    }
+
+    pub fn issue_32(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_32()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_32(&mut self) -> crate::Result<()> {
-        self.error_check(64, 32, true)?;
+    cdb_bytes!(cdb_bytes_32: issue_32 => command_buffer_32);
+
+    fn command_buffer_32(&self) -> crate::Result<CommandBuffer32> {
+        let transfer_length = self.error_check(64, 32, true)?;
 
-        let command_buffer = CommandBuffer32::new()
+        Ok(CommandBuffer32::new()
             .with_operation_code(OPERATION_CODE_32)
             .with_control(self.control)
-            .with_group_number(self.group_number)
-            .with_additional_cdb_length(0x18)
+            .with_group_number(self.transfer_flags.group_number)
             .with_service_action(SERVICE_ACTION_32)
             .with_write_protect(self.write_protect)
-            .with_disable_page_out(self.disable_page_out.into())
+            .with_disable_page_out(self.transfer_flags.disable_page_out.into())
             .with_byte_check(self.byte_check)
             .with_logical_block_address(self.logical_block_address)
             .with_expected_initial_logical_block_reference_tag(
@@ -231,27 +268,22 @@ impl<'a> WriteAndVerifyCommand<'a> {
                 self.expected_logical_block_application_tag,
             )
             .with_logical_block_application_tag_mask(self.logical_block_application_tag_mask)
-            .with_transfer_length(
-                (self.data_buffer.len() / self.logical_block_size as usize) as u32,
-            );
-
-        self.interface.issue(&ThisCommand {
-            command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
-        })
+            .with_transfer_length(transfer_length))
     }
 }
 
 impl Scsi {
-    pub fn write_and_verify(&self) -> WriteAndVerifyCommand {
+    pub fn write_and_verify(&self) -> WriteAndVerifyCommand<'_> {
         WriteAndVerifyCommand::new(self)
     }
 }
 
+crate::command::async_issue!(write_and_verify: WriteAndVerifyCommand);
+
 const OPERATION_CODE_10: u8 = 0x2E;
 const OPERATION_CODE_12: u8 = 0xAE;
 const OPERATION_CODE_16: u8 = 0x8E;
-const OPERATION_CODE_32: u8 = 0x7F;
+const OPERATION_CODE_32: u8 = VARIABLE_LENGTH_OPERATION_CODE;
 const SERVICE_ACTION_32: u16 = 0x000C;
 
 #[bitfield]
@@ -325,17 +357,18 @@ struct CommandBuffer32 {
     transfer_length: B32,
 }
 
-struct ThisCommand<C> {
+struct ThisCommand<'a, C> {
     command_buffer: C,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl<C: Copy> Command for ThisCommand<C> {
+impl<'a, C: Copy> Command for ThisCommand<'a, C> {
     type CommandBuffer = C;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
@@ -348,13 +381,17 @@ impl<C: Copy> Command for ThisCommand<C> {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -399,4 +436,38 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer32))
         );
     }
+
+    #[test]
+    fn command_buffer_16_test() {
+        let command_buffer = CommandBuffer16::new()
+            .with_operation_code(OPERATION_CODE_16)
+            .with_write_protect(0b001)
+            .with_disable_page_out(1)
+            .with_byte_check(0b01)
+            .with_logical_block_address(0x0000_0001_0000_0000)
+            .with_transfer_length(8)
+            .with_group_number(0x02);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [
+                0x8E, 0x32, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08,
+                0x02, 0x00
+            ],
+            "write and verify (16) command"
+        );
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.write_and_verify();
+        command.logical_block_size(512).parameter(&[0xA5; 1024]);
+
+        let before = allocations();
+        command.issue_16().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
@@ -1,10 +1,15 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{
+        bitfield_bound_check, cdb_bytes, logical_block_size_check, timeout_setter, Control,
+        VARIABLE_LENGTH_OPERATION_CODE,
+    },
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,6 +17,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct WriteAtomicCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     control: u8,
     atomic_boundary: u16,
     group_number: u8,
@@ -30,6 +36,7 @@ impl<'a> WriteAtomicCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             control: 0,
             atomic_boundary: 0,
             group_number: 0,
@@ -40,13 +47,13 @@ impl<'a> WriteAtomicCommand<'a> {
             expected_initial_logical_block_reference_tag: 0,
             expected_logical_block_application_tag: 0,
             logical_block_application_tag_mask: 0,
-            logical_block_size: 512,
+            logical_block_size: 0,
             data_buffer: vec![],
         }
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
@@ -97,6 +104,7 @@ impl<'a> WriteAtomicCommand<'a> {
         self
     }
 
+    // required, the logical block length of READ CAPACITY, see Scsi::capacity
     pub fn logical_block_size(&mut self, value: u32) -> &mut Self {
         self.logical_block_size = value;
         self
@@ -114,6 +122,7 @@ impl<'a> WriteAtomicCommand<'a> {
         transfer_length_bits: u32,
         expect_tag: bool,
     ) -> crate::Result<()> {
+        logical_block_size_check(self.logical_block_size)?;
         bitfield_bound_check!(self.group_number, 5, "group number")?;
         bitfield_bound_check!(self.write_protect, 3, "verify protect")?;
         bitfield_bound_check!(
@@ -153,10 +162,24 @@ impl<'a> WriteAtomicCommand<'a> {
         Ok(())
     }
 
+    timeout_setter!();
+
     pub fn issue_16(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_16()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
+
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
         self.error_check(64, 16, false)?;
 
-        let command_buffer = CommandBuffer16::new()
+        Ok(CommandBuffer16::new()
             .with_operation_code(OPERATION_CODE_16)
             .with_write_protect(self.write_protect)
             .with_disable_page_out(self.disable_page_out.into())
@@ -167,23 +190,29 @@ impl<'a> WriteAtomicCommand<'a> {
                 (self.data_buffer.len() / self.logical_block_size as usize) as u16,
             )
             .with_group_number(self.group_number)
-            .with_control(self.control);
+            .with_control(self.control))
+    }
+
+    pub fn issue_32(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_32()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_32(&mut self) -> crate::Result<()> {
+    cdb_bytes!(cdb_bytes_32: issue_32 => command_buffer_32);
+
+    fn command_buffer_32(&self) -> crate::Result<CommandBuffer32> {
         self.error_check(64, 32, true)?;
 
-        let command_buffer = CommandBuffer32::new()
+        Ok(CommandBuffer32::new()
             .with_operation_code(OPERATION_CODE_32)
             .with_control(self.control)
             .with_atomic_boundary(self.atomic_boundary)
             .with_group_number(self.group_number)
-            .with_additional_cdb_length(0x18)
             .with_service_action(SERVICE_ACTION_32)
             .with_write_protect(self.write_protect)
             .with_disable_page_out(self.disable_page_out.into())
@@ -198,23 +227,20 @@ impl<'a> WriteAtomicCommand<'a> {
             .with_logical_block_application_tag_mask(self.logical_block_application_tag_mask)
             .with_transfer_length(
                 (self.data_buffer.len() / self.logical_block_size as usize) as u32,
-            );
-
-        self.interface.issue(&ThisCommand {
-            command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
-        })
+            ))
     }
 }
 
 impl Scsi {
-    pub fn write_atomic(&self) -> WriteAtomicCommand {
+    pub fn write_atomic(&self) -> WriteAtomicCommand<'_> {
         WriteAtomicCommand::new(self)
     }
 }
 
+crate::command::async_issue!(write_atomic: WriteAtomicCommand);
+
 const OPERATION_CODE_16: u8 = 0x9C;
-const OPERATION_CODE_32: u8 = 0x7F;
+const OPERATION_CODE_32: u8 = VARIABLE_LENGTH_OPERATION_CODE;
 const SERVICE_ACTION_32: u16 = 0x000F;
 
 #[bitfield]
@@ -258,17 +284,18 @@ struct CommandBuffer32 {
     transfer_length: B32,
 }
 
-struct ThisCommand<C> {
+struct ThisCommand<'a, C> {
     command_buffer: C,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl<C: Copy> Command for ThisCommand<C> {
+impl<'a, C: Copy> Command for ThisCommand<'a, C> {
     type CommandBuffer = C;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
@@ -281,13 +308,17 @@ impl<C: Copy> Command for ThisCommand<C> {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -318,4 +349,17 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer32))
         );
     }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.write_atomic();
+        command.logical_block_size(512).parameter(&[0xA5; 4096]);
+
+        let before = allocations();
+        command.issue_16().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
@@ -0,0 +1,271 @@
+#![allow(dead_code)]
+
+use std::{mem::size_of, time::Duration};
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{
+        bitfield_bound_check, cdb_bytes,
+        read_attribute::{AttributeFormat, AttributeHeader},
+        timeout_setter, Control,
+    },
+    data_wrapper::{AnyType, SliceBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct WriteAttributeCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    element_type: u8,
+    command_buffer: CommandBuffer,
+    attributes: Vec<AttributeValue>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AttributeValue {
+    pub attribute_identifier: u16,
+    pub format: AttributeFormat,
+    // an empty value deletes the attribute
+    pub value: Vec<u8>,
+}
+
+impl<'a> WriteAttributeCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            element_type: 0,
+            command_buffer: CommandBuffer::new().with_operation_code(OPERATION_CODE),
+            attributes: vec![],
+        }
+    }
+
+    pub fn write_through_cache(&mut self, value: bool) -> &mut Self {
+        self.command_buffer.set_write_through_cache(value.into());
+        self
+    }
+
+    // only used by media changers
+    pub fn element_address(&mut self, value: u16) -> &mut Self {
+        self.command_buffer.set_element_address(value);
+        self
+    }
+
+    // element_type must be less than 0x10, only used by media changers
+    pub fn element_type(&mut self, value: u8) -> &mut Self {
+        self.element_type = value;
+        self
+    }
+
+    pub fn logical_volume_number(&mut self, value: u8) -> &mut Self {
+        self.command_buffer.set_logical_volume_number(value);
+        self
+    }
+
+    pub fn partition_number(&mut self, value: u8) -> &mut Self {
+        self.command_buffer.set_partition_number(value);
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    // the value of each attribute must be shorter than 0x10000 bytes
+    pub fn attributes(&mut self, value: &[AttributeValue]) -> &mut Self {
+        self.attributes = value.to_vec();
+        self
+    }
+
+    pub fn add_attribute(
+        &mut self,
+        attribute_identifier: u16,
+        format: AttributeFormat,
+        value: &[u8],
+    ) -> &mut Self {
+        self.attributes.push(AttributeValue {
+            attribute_identifier,
+            format,
+            value: value.to_vec(),
+        });
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<()> {
+        let (command_buffer, data_buffer) = self.command()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            data_buffer: SliceBufferWrapper(&data_buffer),
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        self.command().map(|(command_buffer, _)| command_buffer)
+    }
+
+    // the parameter list length comes from the attributes, so both are built together
+    fn command(&self) -> crate::Result<(CommandBuffer, Vec<u8>)> {
+        bitfield_bound_check!(self.element_type, 4, "element type")?;
+
+        let data_buffer = parameter_list(&self.attributes)?;
+        let command_buffer = self
+            .command_buffer
+            .with_element_type(self.element_type)
+            .with_parameter_list_length(data_buffer.len() as u32);
+
+        Ok((command_buffer, data_buffer))
+    }
+}
+
+fn parameter_list(attributes: &[AttributeValue]) -> crate::Result<Vec<u8>> {
+    let mut data_buffer = vec![0; size_of::<u32>()];
+
+    for attribute in attributes {
+        bitfield_bound_check!(attribute.value.len(), 16, "attribute length")?;
+
+        let header = AttributeHeader::new()
+            .with_attribute_identifier(attribute.attribute_identifier)
+            .with_format(attribute.format.into())
+            .with_attribute_length(attribute.value.len() as u16);
+        data_buffer.extend_from_slice(&header.into_bytes());
+        data_buffer.extend_from_slice(&attribute.value);
+    }
+
+    bitfield_bound_check!(data_buffer.len(), 32, "parameter list length")?;
+
+    // the parameter data length does not include itself
+    let parameter_data_length = (data_buffer.len() - size_of::<u32>()) as u32;
+    data_buffer[..size_of::<u32>()].copy_from_slice(&parameter_data_length.to_be_bytes());
+
+    Ok(data_buffer)
+}
+
+impl Scsi {
+    pub fn write_attribute(&self) -> WriteAttributeCommand<'_> {
+        WriteAttributeCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(write_attribute: WriteAttributeCommand);
+
+const OPERATION_CODE: u8 = 0x8D;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    reserved_0: B7,
+    write_through_cache: B1,
+    element_address: B16,
+    reserved_1: B4,
+    element_type: B4,
+    logical_volume_number: B8,
+    reserved_2: B8,
+    partition_number: B8,
+    reserved_3: B16,
+    parameter_list_length: B32,
+    reserved_4: B8,
+    control: B8,
+}
+
+struct ThisCommand<'a> {
+    command_buffer: CommandBuffer,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
+}
+
+impl<'a> Command for ThisCommand<'a> {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
+
+    type ReturnType = crate::Result<()>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::ToDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        self.data_buffer
+    }
+
+    fn data_size(&self) -> u32 {
+        self.data_buffer.len() as u32
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+    }
+
+    #[test]
+    fn parameter_list_test() {
+        let attributes = [
+            AttributeValue {
+                attribute_identifier: 0x0800,
+                format: AttributeFormat::Ascii,
+                value: b"SCSIR   ".to_vec(),
+            },
+            AttributeValue {
+                attribute_identifier: 0x0806,
+                format: AttributeFormat::Ascii,
+                value: vec![],
+            },
+        ];
+
+        assert_eq!(
+            parameter_list(&attributes).unwrap(),
+            [
+                0x00, 0x00, 0x00, 0x12, // parameter data length
+                0x08, 0x00, 0x01, 0x00, 0x08, b'S', b'C', b'S', b'I', b'R', b' ', b' ', b' ', 0x08,
+                0x06, 0x01, 0x00, 0x00,
+            ],
+            "attribute parameter list"
+        );
+
+        assert_eq!(
+            parameter_list(&[]).unwrap(),
+            [0x00, 0x00, 0x00, 0x00],
+            "empty parameter list"
+        );
+    }
+}
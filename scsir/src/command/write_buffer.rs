@@ -1,17 +1,20 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{bitfield_bound_check, cdb_bytes, timeout_setter, Control},
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
 
 #[derive(Clone, Debug)]
-pub struct ReadBufferCommand<'a> {
+pub struct WriteBufferCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     mode_specific: u8,
     mode: u8,
     buffer_offset: u32,
@@ -19,10 +22,11 @@ pub struct ReadBufferCommand<'a> {
     data_buffer: Vec<u8>,
 }
 
-impl<'a> ReadBufferCommand<'a> {
+impl<'a> WriteBufferCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             mode_specific: 0,
             mode: 0,
             buffer_offset: 0,
@@ -38,6 +42,8 @@ impl<'a> ReadBufferCommand<'a> {
     }
 
     // mode must be less than 0x20
+    // 0x05 downloads microcode and saves it, 0x07 does the same in chunks at buffer_offset
+    // 0x0E downloads microcode in chunks and saves it, activated later by 0x0F
     pub fn mode(&mut self, value: u8) -> &mut Self {
         self.mode = value;
         self
@@ -54,8 +60,8 @@ impl<'a> ReadBufferCommand<'a> {
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.command_buffer.set_control(value);
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
         self
     }
 
@@ -65,33 +71,60 @@ impl<'a> ReadBufferCommand<'a> {
         self
     }
 
+    timeout_setter!();
+
     pub fn issue(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
         bitfield_bound_check!(self.mode_specific, 3, "mode specific")?;
         bitfield_bound_check!(self.mode, 5, "mode")?;
-        bitfield_bound_check!(self.buffer_offset, 24, "buffer offset")?;
-        bitfield_bound_check!(self.data_buffer.len(), 24, "parameter length")?;
+        parameter_check(self.buffer_offset, self.data_buffer.len())?;
 
-        let command_buffer = self
+        Ok(self
             .command_buffer
             .with_mode_specific(self.mode_specific)
             .with_mode(self.mode)
             .with_buffer_offset(self.buffer_offset)
-            .with_parameter_list_length(self.data_buffer.len() as u32);
-
-        self.interface.issue(&ThisCommand {
-            command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
-        })
+            .with_parameter_list_length(self.data_buffer.len() as u32))
     }
 }
 
 impl Scsi {
-    pub fn write_buffer(&self) -> ReadBufferCommand {
-        ReadBufferCommand::new(self)
+    pub fn write_buffer(&self) -> WriteBufferCommand<'_> {
+        WriteBufferCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(write_buffer: WriteBufferCommand);
+
+fn parameter_check(buffer_offset: u32, parameter_length: usize) -> crate::Result<()> {
+    bitfield_bound_check!(buffer_offset, 24, "buffer offset")?;
+    bitfield_bound_check!(parameter_length, 24, "parameter length")?;
+
+    // the chunk must end inside the 24 bits buffer address space
+    let end = buffer_offset as usize + parameter_length;
+    if end > MAX_BUFFER_END {
+        return Err(crate::Error::ArgumentOutOfBounds(format!(
+            "Buffer offset plus parameter length is out of bounds. The maximum possible value is {}, but {} was provided.",
+            MAX_BUFFER_END, end
+        )));
     }
+
+    Ok(())
 }
 
 const OPERATION_CODE: u8 = 0x3B;
+const MAX_BUFFER_END: usize = 0x100_0000;
 
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
@@ -105,22 +138,27 @@ struct CommandBuffer {
     control: B8,
 }
 
-struct ThisCommand {
+struct ThisCommand<'a> {
     command_buffer: CommandBuffer,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl Command for ThisCommand {
+impl<'a> Command for ThisCommand<'a> {
     type CommandBuffer = CommandBuffer;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
     fn direction(&self) -> DataDirection {
-        DataDirection::ToDevice
+        if self.data_buffer.is_empty() {
+            DataDirection::None
+        } else {
+            DataDirection::ToDevice
+        }
     }
 
     fn command(&self) -> Self::CommandBuffer {
@@ -128,13 +166,17 @@ impl Command for ThisCommand {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -158,4 +200,43 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer))
         );
     }
+
+    #[test]
+    fn download_and_save_test() {
+        // second 4 KiB chunk of a firmware image
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_mode(0x07)
+            .with_buffer_offset(0x1000)
+            .with_parameter_list_length(0x1000);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x3B, 0x07, 0x00, 0x00, 0x10, 0x00, 0x00, 0x10, 0x00, 0x00],
+            "download microcode with offsets and save command"
+        );
+
+        assert!(parameter_check(0x1000, 0x1000).is_ok(), "chunk in bounds");
+        assert!(
+            parameter_check(0xFF_F000, 0x1000).is_ok(),
+            "last chunk in bounds"
+        );
+        assert!(
+            parameter_check(0xFF_F000, 0x1001).is_err(),
+            "chunk out of bounds"
+        );
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.write_buffer();
+        command.mode(0x02).parameter(&[0x5A; 4096]);
+
+        let before = allocations();
+        command.issue().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
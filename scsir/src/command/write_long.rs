@@ -1,10 +1,14 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{
+        bitfield_bound_check, cdb_bytes, read_long::long_block_length, timeout_setter, Control,
+    },
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,35 +16,63 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct WriteLongCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
+    correction_disabled: bool,
     wr_uncor: bool,
+    physical_block: bool,
     logical_block_address: u64,
     control: u8,
     data_buffer: Vec<u8>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CommandResult {
+    Written,
+    // nothing was written, retry with the byte transfer length the device asked for
+    IncorrectLength { byte_transfer_length: u16 },
+}
+
 impl<'a> WriteLongCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
+            correction_disabled: false,
             wr_uncor: false,
+            physical_block: false,
             logical_block_address: 0,
             control: 0,
             data_buffer: vec![],
         }
     }
 
+    // mark the block so it is not corrected by ECC on the next read
+    pub fn correction_disabled(&mut self, value: bool) -> &mut Self {
+        self.correction_disabled = value;
+        self
+    }
+
+    // mark the block as unrecoverable, no data is transfered
     pub fn wr_uncor(&mut self, value: bool) -> &mut Self {
         self.wr_uncor = value;
         self
     }
 
+    // write the whole physical block containing the logical block
+    pub fn physical_block(&mut self, value: bool) -> &mut Self {
+        self.physical_block = value;
+        self
+    }
+
     pub fn logical_block_address(&mut self, value: u64) -> &mut Self {
         self.logical_block_address = value;
         self
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
@@ -50,47 +82,69 @@ impl<'a> WriteLongCommand<'a> {
         self
     }
 
-    pub fn issue_10(&mut self) -> crate::Result<()> {
+    timeout_setter!();
+
+    pub fn issue_10(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_10()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
         bitfield_bound_check!(self.logical_block_address, 32, "logical block address")?;
         bitfield_bound_check!(self.data_buffer.len(), 16, "parameter length")?;
 
-        let command_buffer = CommandBuffer10::new()
+        Ok(CommandBuffer10::new()
             .with_operation_code(OPERATION_CODE_10)
+            .with_correction_disabled(self.correction_disabled.into())
             .with_wr_uncor(self.wr_uncor.into())
+            .with_physical_block(self.physical_block.into())
             .with_logical_block_address(self.logical_block_address as u32)
             .with_byte_transfer_length(self.data_buffer.len() as u16)
-            .with_control(self.control);
+            .with_control(self.control))
+    }
+
+    pub fn issue_16(&mut self) -> crate::Result<CommandResult> {
+        let command_buffer = self.command_buffer_16()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_16(&mut self) -> crate::Result<()> {
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
+
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
         bitfield_bound_check!(self.data_buffer.len(), 16, "parameter length")?;
 
-        let command_buffer = CommandBuffer16::new()
+        Ok(CommandBuffer16::new()
             .with_operation_code(OPERATION_CODE_16)
+            .with_correction_disabled(self.correction_disabled.into())
             .with_wr_uncor(self.wr_uncor.into())
+            .with_physical_block(self.physical_block.into())
             .with_service_action(SERVICE_ACTION_16)
             .with_logical_block_address(self.logical_block_address)
             .with_byte_transfer_length(self.data_buffer.len() as u16)
-            .with_control(self.control);
-
-        self.interface.issue(&ThisCommand {
-            command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
-        })
+            .with_control(self.control))
     }
 }
 
 impl Scsi {
-    pub fn write_long(&self) -> WriteLongCommand {
+    pub fn write_long(&self) -> WriteLongCommand<'_> {
         WriteLongCommand::new(self)
     }
 }
 
+crate::command::async_issue!(write_long: WriteLongCommand);
+
 const OPERATION_CODE_10: u8 = 0x3F;
 const OPERATION_CODE_16: u8 = 0x9F;
 const SERVICE_ACTION_16: u8 = 0x11;
@@ -99,11 +153,11 @@ const SERVICE_ACTION_16: u8 = 0x11;
 #[derive(Clone, Copy)]
 struct CommandBuffer10 {
     operation_code: B8,
-    obsolete_0: B1,
+    correction_disabled: B1,
     wr_uncor: B1,
-    obsolete_1: B1,
+    physical_block: B1,
     reserved_0: B4,
-    obsolete_2: B1,
+    obsolete: B1,
     logical_block_address: B32,
     reserved_1: B8,
     byte_transfer_length: B16,
@@ -114,9 +168,9 @@ struct CommandBuffer10 {
 #[derive(Clone, Copy)]
 struct CommandBuffer16 {
     operation_code: B8,
-    obsolete_0: B1,
+    correction_disabled: B1,
     wr_uncor: B1,
-    obsolete_1: B1,
+    physical_block: B1,
     service_action: B5,
     logical_block_address: B64,
     reserved_1: B16,
@@ -125,22 +179,27 @@ struct CommandBuffer16 {
     control: B8,
 }
 
-struct ThisCommand<C> {
+struct ThisCommand<'a, C> {
     command_buffer: C,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl<C: Copy> Command for ThisCommand<C> {
+impl<'a, C: Copy> Command for ThisCommand<'a, C> {
     type CommandBuffer = C;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
-    type ReturnType = crate::Result<()>;
+    type ReturnType = crate::Result<CommandResult>;
 
     fn direction(&self) -> DataDirection {
-        DataDirection::ToDevice
+        if self.data_buffer.is_empty() {
+            DataDirection::None
+        } else {
+            DataDirection::ToDevice
+        }
     }
 
     fn command(&self) -> Self::CommandBuffer {
@@ -148,18 +207,31 @@ impl<C: Copy> Command for ThisCommand<C> {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
+
+        if let Some(byte_transfer_length) =
+            long_block_length(result.sense_buffer, self.data_buffer.len() as u16)
+        {
+            return Ok(CommandResult::IncorrectLength {
+                byte_transfer_length,
+            });
+        }
+
         result.check_common_error()?;
 
-        Ok(())
+        Ok(CommandResult::Written)
     }
 }
 
@@ -185,4 +257,37 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer16))
         );
     }
+
+    #[test]
+    fn command_buffer_16_test() {
+        let command_buffer = CommandBuffer16::new()
+            .with_operation_code(OPERATION_CODE_16)
+            .with_correction_disabled(1)
+            .with_physical_block(1)
+            .with_service_action(SERVICE_ACTION_16)
+            .with_logical_block_address(0x10)
+            .with_byte_transfer_length(520);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [
+                0x9F, 0xB1, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x02, 0x08,
+                0x00, 0x00
+            ],
+            "write long (16) command"
+        );
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.write_long();
+        command.logical_block_address(0x10).parameter(&[0x5A; 520]);
+
+        let before = allocations();
+        command.issue_16().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
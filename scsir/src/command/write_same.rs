@@ -1,10 +1,15 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use modular_bitfield_msb::prelude::*;
 
 use crate::{
-    command::bitfield_bound_check,
-    data_wrapper::{AnyType, VecBufferWrapper},
+    command::{
+        bitfield_bound_check, cdb_bytes, logical_block_size_check, timeout_setter, Control,
+        VARIABLE_LENGTH_OPERATION_CODE,
+    },
+    data_wrapper::{AnyType, SliceBufferWrapper},
     result_data::ResultData,
     Command, DataDirection, Scsi,
 };
@@ -12,6 +17,7 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct WriteSameCommand<'a> {
     interface: &'a Scsi,
+    timeout: Option<Duration>,
     control: u8,
     group_number: u8,
     write_protect: u8,
@@ -23,6 +29,7 @@ pub struct WriteSameCommand<'a> {
     expected_logical_block_application_tag: u16,
     logical_block_application_tag_mask: u16,
     number_of_blocks: u32,
+    logical_block_size: u32,
     data_buffer: Vec<u8>,
 }
 
@@ -30,6 +37,7 @@ impl<'a> WriteSameCommand<'a> {
     fn new(interface: &'a Scsi) -> Self {
         Self {
             interface,
+            timeout: None,
             control: 0,
             group_number: 0,
             write_protect: 0,
@@ -41,12 +49,13 @@ impl<'a> WriteSameCommand<'a> {
             expected_logical_block_application_tag: 0,
             logical_block_application_tag_mask: 0,
             number_of_blocks: 0,
+            logical_block_size: 0,
             data_buffer: vec![],
         }
     }
 
-    pub fn control(&mut self, value: u8) -> &mut Self {
-        self.control = value;
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
         self
     }
 
@@ -102,6 +111,13 @@ impl<'a> WriteSameCommand<'a> {
         self
     }
 
+    // required, the logical block length of READ CAPACITY, see Scsi::capacity
+    pub fn logical_block_size(&mut self, value: u32) -> &mut Self {
+        self.logical_block_size = value;
+        self
+    }
+
+    // parameter must be exactly one logical block, or empty with no_data_out_buffer
     pub fn parameter(&mut self, value: &[u8]) -> &mut Self {
         self.data_buffer.clear();
         self.data_buffer.extend_from_slice(value);
@@ -134,6 +150,14 @@ impl<'a> WriteSameCommand<'a> {
             ));
         }
 
+        payload_check(
+            self.no_data_out_buffer,
+            self.unmap,
+            self.anchor,
+            self.data_buffer.len(),
+            self.logical_block_size,
+        )?;
+
         if !expect_tag
             && (self.expected_initial_logical_block_reference_tag != 0
                 || self.expected_logical_block_application_tag != 0
@@ -147,10 +171,24 @@ impl<'a> WriteSameCommand<'a> {
         Ok(())
     }
 
+    timeout_setter!();
+
     pub fn issue_10(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_10()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
         self.error_check(32, 16, false, false)?;
 
-        let command_buffer = CommandBuffer10::new()
+        Ok(CommandBuffer10::new()
             .with_operation_code(OPERATION_CODE_10)
             .with_write_protect(self.write_protect)
             .with_anchor(self.anchor.into())
@@ -158,18 +196,25 @@ impl<'a> WriteSameCommand<'a> {
             .with_logical_block_address(self.logical_block_address as u32)
             .with_group_number(self.group_number)
             .with_number_of_blocks(self.number_of_blocks as u16)
-            .with_control(self.control);
+            .with_control(self.control))
+    }
+
+    pub fn issue_16(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_16()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_16(&mut self) -> crate::Result<()> {
+    cdb_bytes!(cdb_bytes_16: issue_16 => command_buffer_16);
+
+    fn command_buffer_16(&self) -> crate::Result<CommandBuffer16> {
         self.error_check(64, 32, true, false)?;
 
-        let command_buffer = CommandBuffer16::new()
+        Ok(CommandBuffer16::new()
             .with_operation_code(OPERATION_CODE_16)
             .with_write_protect(self.write_protect)
             .with_anchor(self.anchor.into())
@@ -178,22 +223,28 @@ impl<'a> WriteSameCommand<'a> {
             .with_logical_block_address(self.logical_block_address)
             .with_number_of_blocks(self.number_of_blocks)
             .with_group_number(self.group_number)
-            .with_control(self.control);
+            .with_control(self.control))
+    }
+
+    pub fn issue_32(&mut self) -> crate::Result<()> {
+        let command_buffer = self.command_buffer_32()?;
 
         self.interface.issue(&ThisCommand {
             command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
+            data_buffer: SliceBufferWrapper(&self.data_buffer),
+            timeout: self.timeout,
         })
     }
 
-    pub fn issue_32(&mut self) -> crate::Result<()> {
+    cdb_bytes!(cdb_bytes_32: issue_32 => command_buffer_32);
+
+    fn command_buffer_32(&self) -> crate::Result<CommandBuffer32> {
         self.error_check(64, 32, true, true)?;
 
-        let command_buffer = CommandBuffer32::new()
+        Ok(CommandBuffer32::new()
             .with_operation_code(OPERATION_CODE_32)
             .with_control(self.control)
             .with_group_number(self.group_number)
-            .with_additional_cdb_length(0x18)
             .with_service_action(SERVICE_ACTION_32)
             .with_write_protect(self.write_protect)
             .with_anchor(self.anchor.into())
@@ -207,24 +258,55 @@ impl<'a> WriteSameCommand<'a> {
                 self.expected_logical_block_application_tag,
             )
             .with_logical_block_application_tag_mask(self.logical_block_application_tag_mask)
-            .with_number_of_blocks(self.number_of_blocks);
+            .with_number_of_blocks(self.number_of_blocks))
+    }
+}
 
-        self.interface.issue(&ThisCommand {
-            command_buffer,
-            data_buffer: self.data_buffer.clone().into(),
-        })
+// with no_data_out_buffer the device writes zeros, or unmaps the blocks if unmap is also set
+fn payload_check(
+    no_data_out_buffer: bool,
+    unmap: bool,
+    anchor: bool,
+    data_length: usize,
+    logical_block_size: u32,
+) -> crate::Result<()> {
+    if anchor && !unmap {
+        return Err(crate::Error::BadArgument(
+            "anchor requires unmap".to_owned(),
+        ));
+    }
+
+    if no_data_out_buffer {
+        if data_length != 0 {
+            return Err(crate::Error::BadArgument(
+                "parameter is not allowed with no data out buffer".to_owned(),
+            ));
+        }
+    } else {
+        logical_block_size_check(logical_block_size)?;
+
+        if data_length != logical_block_size as usize {
+            return Err(crate::Error::BadArgument(format!(
+                "parameter length should be exactly one logical block, which is {}, but {} was provided.",
+                logical_block_size, data_length
+            )));
+        }
     }
+
+    Ok(())
 }
 
 impl Scsi {
-    pub fn write_same(&self) -> WriteSameCommand {
+    pub fn write_same(&self) -> WriteSameCommand<'_> {
         WriteSameCommand::new(self)
     }
 }
 
+crate::command::async_issue!(write_same: WriteSameCommand);
+
 const OPERATION_CODE_10: u8 = 0x41;
 const OPERATION_CODE_16: u8 = 0x93;
-const OPERATION_CODE_32: u8 = 0x7F;
+const OPERATION_CODE_32: u8 = VARIABLE_LENGTH_OPERATION_CODE;
 const SERVICE_ACTION_32: u16 = 0x000D;
 
 #[bitfield]
@@ -281,22 +363,27 @@ struct CommandBuffer32 {
     number_of_blocks: B32,
 }
 
-struct ThisCommand<C> {
+struct ThisCommand<'a, C> {
     command_buffer: C,
-    data_buffer: VecBufferWrapper,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
 }
 
-impl<C: Copy> Command for ThisCommand<C> {
+impl<'a, C: Copy> Command for ThisCommand<'a, C> {
     type CommandBuffer = C;
 
     type DataBuffer = AnyType;
 
-    type DataBufferWrapper = VecBufferWrapper;
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
 
     type ReturnType = crate::Result<()>;
 
     fn direction(&self) -> DataDirection {
-        DataDirection::ToDevice
+        if self.data_buffer.is_empty() {
+            DataDirection::None
+        } else {
+            DataDirection::ToDevice
+        }
     }
 
     fn command(&self) -> Self::CommandBuffer {
@@ -304,13 +391,17 @@ impl<C: Copy> Command for ThisCommand<C> {
     }
 
     fn data(&self) -> Self::DataBufferWrapper {
-        self.data_buffer.clone()
+        self.data_buffer
     }
 
     fn data_size(&self) -> u32 {
         self.data_buffer.len() as u32
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
         result.check_ioctl_error()?;
         result.check_common_error()?;
@@ -348,4 +439,109 @@ mod tests {
             concat!("Size of: ", stringify!(CommandBuffer32))
         );
     }
+
+    #[test]
+    fn no_data_out_buffer_test() {
+        assert!(
+            payload_check(true, true, false, 0, 512).is_ok(),
+            "unmap without data"
+        );
+        assert!(
+            payload_check(true, false, false, 0, 512).is_ok(),
+            "write zeros without data"
+        );
+        assert!(
+            matches!(
+                payload_check(true, true, false, 512, 512),
+                Err(crate::Error::BadArgument(_))
+            ),
+            "data with no data out buffer"
+        );
+
+        let command = ThisCommand {
+            command_buffer: CommandBuffer16::new().with_no_data_out_buffer(1),
+            data_buffer: SliceBufferWrapper(&[]),
+            timeout: None,
+        };
+        assert!(
+            matches!(command.direction(), DataDirection::None),
+            "no data out buffer direction"
+        );
+        assert_eq!(command.data_size(), 0, "no data out buffer size");
+    }
+
+    #[test]
+    fn payload_test() {
+        assert!(
+            payload_check(false, false, false, 4096, 4096).is_ok(),
+            "single logical block"
+        );
+        assert!(
+            matches!(
+                payload_check(false, false, false, 1024, 512),
+                Err(crate::Error::BadArgument(_))
+            ),
+            "two logical blocks"
+        );
+        assert!(
+            matches!(
+                payload_check(false, false, false, 0, 512),
+                Err(crate::Error::BadArgument(_))
+            ),
+            "missing payload"
+        );
+        assert!(
+            matches!(
+                payload_check(false, false, true, 512, 512),
+                Err(crate::Error::BadArgument(_))
+            ),
+            "anchor without unmap"
+        );
+
+        let command = ThisCommand {
+            command_buffer: CommandBuffer16::new().with_unmap(1),
+            data_buffer: SliceBufferWrapper(&[0; 512]),
+            timeout: None,
+        };
+        assert!(
+            matches!(command.direction(), DataDirection::ToDevice),
+            "payload direction"
+        );
+        assert_eq!(command.data_size(), 512, "payload size");
+    }
+
+    #[test]
+    fn command_buffer_10_test() {
+        let command_buffer = CommandBuffer10::new()
+            .with_operation_code(OPERATION_CODE_10)
+            .with_write_protect(0b001)
+            .with_anchor(1)
+            .with_unmap(1)
+            .with_logical_block_address(0x0000_1000)
+            .with_group_number(0x01)
+            .with_number_of_blocks(u16::MAX);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x41, 0x38, 0x00, 0x00, 0x10, 0x00, 0x01, 0xFF, 0xFF, 0x00],
+            "write same (10) command"
+        );
+    }
+
+    #[test]
+    fn issue_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        let scsi = good_scsi();
+        let mut command = scsi.write_same();
+        command
+            .number_of_blocks(8)
+            .logical_block_size(512)
+            .parameter(&[0; 512]);
+
+        // the block is handed to the driver without a copy
+        let before = allocations();
+        command.issue_16().unwrap();
+        assert_eq!(allocations(), before, "allocations");
+    }
 }
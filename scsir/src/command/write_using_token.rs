@@ -0,0 +1,335 @@
+#![allow(dead_code)]
+
+use std::{mem::size_of, time::Duration};
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{
+        bitfield_bound_check, cdb_bytes, populate_token::block_device_range_descriptors,
+        timeout_setter, Control,
+    },
+    data_wrapper::{AnyType, SliceBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct WriteUsingTokenCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    list_identifier: u32,
+    group_number: u8,
+    control: u8,
+    immediate: bool,
+    delete_token: bool,
+    offset_into_rod: u64,
+    rod_token: Vec<u8>,
+    block_ranges: Vec<(u64, u32)>,
+}
+
+impl<'a> WriteUsingTokenCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            list_identifier: 0,
+            group_number: 0,
+            control: 0,
+            immediate: false,
+            delete_token: false,
+            offset_into_rod: 0,
+            rod_token: vec![],
+            block_ranges: vec![],
+        }
+    }
+
+    pub fn list_identifier(&mut self, value: u32) -> &mut Self {
+        self.list_identifier = value;
+        self
+    }
+
+    // group_number must be less than 0x20
+    pub fn group_number(&mut self, value: u8) -> &mut Self {
+        self.group_number = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
+        self
+    }
+
+    pub fn immediate(&mut self, value: bool) -> &mut Self {
+        self.immediate = value;
+        self
+    }
+
+    // the token can't be used again once the write completes
+    pub fn delete_token(&mut self, value: bool) -> &mut Self {
+        self.delete_token = value;
+        self
+    }
+
+    // in logical blocks of the destination
+    pub fn offset_into_rod(&mut self, value: u64) -> &mut Self {
+        self.offset_into_rod = value;
+        self
+    }
+
+    // the token returned by RECEIVE ROD TOKEN INFORMATION, must be 512 bytes long
+    pub fn rod_token(&mut self, value: &[u8]) -> &mut Self {
+        self.rod_token = value.to_owned();
+        self
+    }
+
+    pub fn add_block_range(
+        &mut self,
+        logical_block_address: u64,
+        number_of_logical_blocks: u32,
+    ) -> &mut Self {
+        self.block_ranges
+            .push((logical_block_address, number_of_logical_blocks));
+        self
+    }
+
+    // each range is a logical block address and a number of logical blocks
+    pub fn add_block_ranges(&mut self, ranges: &[(u64, u32)]) -> &mut Self {
+        self.block_ranges.extend_from_slice(ranges);
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<()> {
+        let (command_buffer, data_buffer) = self.command()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            data_buffer: SliceBufferWrapper(&data_buffer),
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        self.command().map(|(command_buffer, _)| command_buffer)
+    }
+
+    // the parameter list length comes from the parameter list, so both are built together
+    fn command(&self) -> crate::Result<(CommandBuffer, Vec<u8>)> {
+        bitfield_bound_check!(self.group_number, 5, "group number")?;
+
+        let data_buffer = parameter_list(
+            self.immediate,
+            self.delete_token,
+            self.offset_into_rod,
+            &self.rod_token,
+            &self.block_ranges,
+        )?;
+
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_service_action(SERVICE_ACTION)
+            .with_list_identifier(self.list_identifier)
+            .with_parameter_list_length(data_buffer.len() as u32)
+            .with_group_number(self.group_number)
+            .with_control(self.control);
+
+        Ok((command_buffer, data_buffer))
+    }
+}
+
+impl Scsi {
+    pub fn write_using_token(&self) -> WriteUsingTokenCommand<'_> {
+        WriteUsingTokenCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(write_using_token: WriteUsingTokenCommand);
+
+fn parameter_list(
+    immediate: bool,
+    delete_token: bool,
+    offset_into_rod: u64,
+    rod_token: &[u8],
+    block_ranges: &[(u64, u32)],
+) -> crate::Result<Vec<u8>> {
+    if rod_token.len() != ROD_TOKEN_LENGTH {
+        return Err(crate::Error::BadArgument(format!(
+            "ROD token should be {} bytes long, but {} was provided.",
+            ROD_TOKEN_LENGTH,
+            rod_token.len()
+        )));
+    }
+
+    let descriptors = block_device_range_descriptors(block_ranges)?;
+    let total_size = size_of::<ParameterListHeader>()
+        + ROD_TOKEN_LENGTH
+        + size_of::<ParameterListTrailer>()
+        + descriptors.len();
+    bitfield_bound_check!(total_size, 16, "parameter list length")?;
+
+    let header = ParameterListHeader::new()
+        .with_write_using_token_data_length((total_size - size_of::<u16>()) as u16)
+        .with_delete_token(delete_token.into())
+        .with_immediate(immediate.into())
+        .with_offset_into_rod(offset_into_rod);
+    let trailer = ParameterListTrailer::new()
+        .with_block_device_range_descriptor_length(descriptors.len() as u16);
+
+    let mut bytes = Vec::with_capacity(total_size);
+    bytes.extend_from_slice(&header.into_bytes());
+    bytes.extend_from_slice(rod_token);
+    bytes.extend_from_slice(&trailer.into_bytes());
+    bytes.extend_from_slice(&descriptors);
+
+    Ok(bytes)
+}
+
+const OPERATION_CODE: u8 = 0x83;
+const SERVICE_ACTION: u8 = 0x11;
+const ROD_TOKEN_LENGTH: usize = 512;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    reserved_1: B32,
+    list_identifier: B32,
+    parameter_list_length: B32,
+    reserved_2: B3,
+    group_number: B5,
+    control: B8,
+}
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct ParameterListHeader {
+    write_using_token_data_length: B16,
+    reserved_0: B6,
+    delete_token: B1,
+    immediate: B1,
+    reserved_1: B40,
+    offset_into_rod: B64,
+}
+
+// follows the ROD token
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct ParameterListTrailer {
+    reserved: B48,
+    block_device_range_descriptor_length: B16,
+}
+
+struct ThisCommand<'a> {
+    command_buffer: CommandBuffer,
+    data_buffer: SliceBufferWrapper<'a>,
+    timeout: Option<Duration>,
+}
+
+impl<'a> Command for ThisCommand<'a> {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = SliceBufferWrapper<'a>;
+
+    type ReturnType = crate::Result<()>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::ToDevice
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {
+        self.data_buffer
+    }
+
+    fn data_size(&self) -> u32 {
+        self.data_buffer.len() as u32
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+    const PARAMETER_HEADER_LENGTH: usize = 16;
+    const PARAMETER_TRAILER_LENGTH: usize = 8;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+
+        assert_eq!(
+            size_of::<ParameterListHeader>(),
+            PARAMETER_HEADER_LENGTH,
+            concat!("Size of: ", stringify!(ParameterListHeader))
+        );
+
+        assert_eq!(
+            size_of::<ParameterListTrailer>(),
+            PARAMETER_TRAILER_LENGTH,
+            concat!("Size of: ", stringify!(ParameterListTrailer))
+        );
+    }
+
+    #[test]
+    fn parameter_list_test() {
+        let token = [0xA5; ROD_TOKEN_LENGTH];
+        let bytes = parameter_list(false, true, 0x10, &token, &[(0x4000, 0x20)]).unwrap();
+
+        assert_eq!(bytes.len(), 552, "parameter list length");
+        assert_eq!(
+            bytes[..PARAMETER_HEADER_LENGTH],
+            [
+                0x02, 0x26, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x10
+            ],
+            "parameter list header"
+        );
+        assert_eq!(
+            bytes[PARAMETER_HEADER_LENGTH..PARAMETER_HEADER_LENGTH + ROD_TOKEN_LENGTH],
+            token,
+            "rod token"
+        );
+        assert_eq!(
+            bytes[PARAMETER_HEADER_LENGTH + ROD_TOKEN_LENGTH..],
+            [
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, // trailer
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00,
+                0x00, 0x00,
+            ],
+            "block device range descriptors"
+        );
+
+        assert!(
+            parameter_list(false, false, 0, &token[..256], &[(0, 1)]).is_err(),
+            "short rod token"
+        );
+    }
+}
@@ -0,0 +1,263 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{
+        bitfield_bound_check, cdb_bytes, timeout_setter, write::transfer_length_check, Control,
+    },
+    data_wrapper::{AnyType, VecBufferWrapper},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct XdWriteReadCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    write_protect: u8,
+    disable_page_out: bool,
+    force_unit_access: bool,
+    disable_write: bool,
+    xor_protection_information: bool,
+    logical_block_address: u64,
+    group_number: u8,
+    transfer_length: Option<u32>,
+    logical_block_size: u32,
+    control: u8,
+    data_buffer: Vec<u8>,
+}
+
+impl<'a> XdWriteReadCommand<'a> {
+    fn new(interface: &'a Scsi) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            write_protect: 0,
+            disable_page_out: false,
+            force_unit_access: false,
+            disable_write: false,
+            xor_protection_information: false,
+            logical_block_address: 0,
+            group_number: 0,
+            transfer_length: None,
+            logical_block_size: 0,
+            control: 0,
+            data_buffer: vec![],
+        }
+    }
+
+    // write_protect must be less than 0x08
+    pub fn write_protect(&mut self, value: u8) -> &mut Self {
+        self.write_protect = value;
+        self
+    }
+
+    pub fn disable_page_out(&mut self, value: bool) -> &mut Self {
+        self.disable_page_out = value;
+        self
+    }
+
+    pub fn force_unit_access(&mut self, value: bool) -> &mut Self {
+        self.force_unit_access = value;
+        self
+    }
+
+    // only return the XOR of the data without writing it to the medium
+    pub fn disable_write(&mut self, value: bool) -> &mut Self {
+        self.disable_write = value;
+        self
+    }
+
+    pub fn xor_protection_information(&mut self, value: bool) -> &mut Self {
+        self.xor_protection_information = value;
+        self
+    }
+
+    pub fn logical_block_address(&mut self, value: u64) -> &mut Self {
+        self.logical_block_address = value;
+        self
+    }
+
+    // group_number must be less than 0x20
+    pub fn group_number(&mut self, value: u8) -> &mut Self {
+        self.group_number = value;
+        self
+    }
+
+    // transfer length is derived from the parameter length if not set
+    pub fn transfer_length(&mut self, value: u32) -> &mut Self {
+        self.transfer_length = Some(value);
+        self
+    }
+
+    // required, the logical block length of READ CAPACITY, see Scsi::capacity
+    pub fn logical_block_size(&mut self, value: u32) -> &mut Self {
+        self.logical_block_size = value;
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.control = value.into().bits();
+        self
+    }
+
+    // the data written to the medium, the XOR of it with the old data is read back
+    // into a buffer of the same length
+    pub fn parameter(&mut self, value: &[u8]) -> &mut Self {
+        self.data_buffer = value.to_owned();
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue_10(&mut self) -> crate::Result<Vec<u8>> {
+        let command_buffer = self.command_buffer_10()?;
+
+        self.interface.issue(&ThisCommand {
+            command_buffer,
+            data_buffer: self.data_buffer.clone().into(),
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes_10: issue_10 => command_buffer_10);
+
+    fn command_buffer_10(&self) -> crate::Result<CommandBuffer10> {
+        bitfield_bound_check!(self.write_protect, 3, "write protect")?;
+        bitfield_bound_check!(self.group_number, 5, "group number")?;
+        bitfield_bound_check!(self.logical_block_address, 32, "logical block address")?;
+        let transfer_length = transfer_length_check(
+            self.data_buffer.len(),
+            self.logical_block_size,
+            self.transfer_length,
+            16,
+        )?;
+
+        Ok(CommandBuffer10::new()
+            .with_operation_code(OPERATION_CODE_10)
+            .with_write_protect(self.write_protect)
+            .with_disable_page_out(self.disable_page_out.into())
+            .with_force_unit_access(self.force_unit_access.into())
+            .with_disable_write(self.disable_write.into())
+            .with_xor_protection_information(self.xor_protection_information.into())
+            .with_logical_block_address(self.logical_block_address as u32)
+            .with_group_number(self.group_number)
+            .with_transfer_length(transfer_length as u16)
+            .with_control(self.control))
+    }
+}
+
+impl Scsi {
+    pub fn xdwriteread(&self) -> XdWriteReadCommand<'_> {
+        XdWriteReadCommand::new(self)
+    }
+}
+
+crate::command::async_issue!(xdwriteread: XdWriteReadCommand);
+
+const OPERATION_CODE_10: u8 = 0x53;
+
+#[bitfield]
+#[derive(Clone, Copy)]
+struct CommandBuffer10 {
+    operation_code: B8,
+    write_protect: B3,
+    disable_page_out: B1,
+    force_unit_access: B1,
+    disable_write: B1,
+    obsolete: B1,
+    xor_protection_information: B1,
+    logical_block_address: B32,
+    reserved: B3,
+    group_number: B5,
+    transfer_length: B16,
+    control: B8,
+}
+
+struct ThisCommand {
+    command_buffer: CommandBuffer10,
+    data_buffer: VecBufferWrapper,
+    timeout: Option<Duration>,
+}
+
+impl Command for ThisCommand {
+    type CommandBuffer = CommandBuffer10;
+
+    type DataBuffer = AnyType;
+
+    type DataBufferWrapper = VecBufferWrapper;
+
+    type ReturnType = crate::Result<Vec<u8>>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::Bidirectional
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    // the XOR data is read into a buffer of the same length right after the data out
+    fn data(&self) -> Self::DataBufferWrapper {
+        let mut data_buffer = self.data_buffer.clone();
+        data_buffer.resize(self.data_buffer.len() * 2, 0);
+        data_buffer
+    }
+
+    fn data_size(&self) -> u32 {
+        self.data_buffer.len() as u32 * 2
+    }
+
+    fn data_in_size(&self) -> u32 {
+        self.data_buffer.len() as u32
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        let data_in = &result.data[self.data_buffer.len()..];
+        let length = result.data_in_length(data_in.len());
+
+        Ok(data_in[..length].to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH_10: usize = 10;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer10>(),
+            COMMAND_LENGTH_10,
+            concat!("Size of: ", stringify!(CommandBuffer10))
+        );
+    }
+
+    #[test]
+    fn command_buffer_10_test() {
+        let command_buffer = CommandBuffer10::new()
+            .with_operation_code(OPERATION_CODE_10)
+            .with_disable_write(1)
+            .with_logical_block_address(0x100)
+            .with_transfer_length(8);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [0x53, 0x04, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x08, 0x00],
+            "xdwriteread (10) command"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use modular_bitfield_msb::prelude::*;
+
+use crate::{
+    command::{cdb_bytes, timeout_setter, Control},
+    result_data::ResultData,
+    Command, DataDirection, Scsi,
+};
+
+#[derive(Clone, Debug)]
+pub struct ZoneManagementCommand<'a> {
+    interface: &'a Scsi,
+    timeout: Option<Duration>,
+    command_buffer: CommandBuffer,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum ServiceAction {
+    CloseZone,
+    FinishZone,
+    OpenZone,
+    ResetWritePointer,
+}
+
+impl<'a> ZoneManagementCommand<'a> {
+    fn new(interface: &'a Scsi, service_action: ServiceAction) -> Self {
+        Self {
+            interface,
+            timeout: None,
+            command_buffer: CommandBuffer::new()
+                .with_operation_code(OPERATION_CODE)
+                .with_service_action(service_action.into()),
+        }
+    }
+
+    // the lowest logical block address of the zone, ignored if all is set
+    pub fn zone_id(&mut self, value: u64) -> &mut Self {
+        self.command_buffer.set_zone_id(value);
+        self
+    }
+
+    // apply to every zone the service action is valid for
+    pub fn all(&mut self, value: bool) -> &mut Self {
+        self.command_buffer.set_all(value.into());
+        self
+    }
+
+    pub fn control(&mut self, value: impl Into<Control>) -> &mut Self {
+        self.command_buffer.set_control(value.into().bits());
+        self
+    }
+
+    timeout_setter!();
+
+    pub fn issue(&mut self) -> crate::Result<()> {
+        self.interface.issue(&ThisCommand {
+            command_buffer: self.command_buffer()?,
+            timeout: self.timeout,
+        })
+    }
+
+    cdb_bytes!(cdb_bytes: issue => command_buffer);
+
+    fn command_buffer(&self) -> crate::Result<CommandBuffer> {
+        Ok(self.command_buffer)
+    }
+}
+
+impl Scsi {
+    pub fn close_zone(&self) -> ZoneManagementCommand<'_> {
+        ZoneManagementCommand::new(self, ServiceAction::CloseZone)
+    }
+
+    pub fn finish_zone(&self) -> ZoneManagementCommand<'_> {
+        ZoneManagementCommand::new(self, ServiceAction::FinishZone)
+    }
+
+    pub fn open_zone(&self) -> ZoneManagementCommand<'_> {
+        ZoneManagementCommand::new(self, ServiceAction::OpenZone)
+    }
+
+    pub fn reset_write_pointer(&self) -> ZoneManagementCommand<'_> {
+        ZoneManagementCommand::new(self, ServiceAction::ResetWritePointer)
+    }
+}
+
+crate::command::async_issue!(close_zone, finish_zone, open_zone, reset_write_pointer: ZoneManagementCommand);
+
+impl From<ServiceAction> for u8 {
+    fn from(value: ServiceAction) -> Self {
+        match value {
+            ServiceAction::CloseZone => 0x01,
+            ServiceAction::FinishZone => 0x02,
+            ServiceAction::OpenZone => 0x03,
+            ServiceAction::ResetWritePointer => 0x04,
+        }
+    }
+}
+
+const OPERATION_CODE: u8 = 0x94;
+
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct CommandBuffer {
+    operation_code: B8,
+    reserved_0: B3,
+    service_action: B5,
+    zone_id: B64,
+    reserved_1: B32,
+    reserved_2: B7,
+    all: B1,
+    control: B8,
+}
+
+struct ThisCommand {
+    command_buffer: CommandBuffer,
+    timeout: Option<Duration>,
+}
+
+impl Command for ThisCommand {
+    type CommandBuffer = CommandBuffer;
+
+    type DataBuffer = ();
+
+    type DataBufferWrapper = ();
+
+    type ReturnType = crate::Result<()>;
+
+    fn direction(&self) -> DataDirection {
+        DataDirection::None
+    }
+
+    fn command(&self) -> Self::CommandBuffer {
+        self.command_buffer
+    }
+
+    fn data(&self) -> Self::DataBufferWrapper {}
+
+    fn data_size(&self) -> u32 {
+        0
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+        result.check_ioctl_error()?;
+        result.check_common_error()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    const COMMAND_LENGTH: usize = 16;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(
+            size_of::<CommandBuffer>(),
+            COMMAND_LENGTH,
+            concat!("Size of: ", stringify!(CommandBuffer))
+        );
+    }
+
+    #[test]
+    fn command_buffer_test() {
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_service_action(ServiceAction::ResetWritePointer.into())
+            .with_all(1);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [
+                0x94, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x01, 0x00
+            ],
+            "reset all write pointers command"
+        );
+
+        let command_buffer = CommandBuffer::new()
+            .with_operation_code(OPERATION_CODE)
+            .with_service_action(ServiceAction::OpenZone.into())
+            .with_zone_id(0x8_0000);
+
+        assert_eq!(
+            command_buffer.into_bytes(),
+            [
+                0x94, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00
+            ],
+            "open zone command"
+        );
+    }
+}
@@ -0,0 +1,64 @@
+// A global allocator for the tests that counts the allocations made by the current thread,
+// so tests running in parallel don't see each other's allocations.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    path::Path,
+};
+
+use crate::{
+    os::{Backend, Request, Response},
+    Scsi,
+};
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+pub(crate) fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+// completes every command with GOOD and the whole transfer. The mock records every command it
+// is sent, so it allocates and can't show that issuing a command allocates nothing
+#[derive(Debug)]
+pub(crate) struct GoodBackend;
+
+impl Backend for GoodBackend {
+    fn issue(&self, request: Request) -> Response {
+        Response {
+            ioctl_result: 0,
+            os_error: 0,
+            transfered_data_length: request.data_length as usize,
+            residual: 0,
+            sense_length: 0,
+            status: 0x00,
+            duration: None,
+            #[cfg(target_os = "linux")]
+            host_status: 0,
+            #[cfg(target_os = "linux")]
+            driver_status: crate::os::linux::DriverStatus::OK,
+        }
+    }
+}
+
+pub(crate) fn good_scsi() -> Scsi {
+    Scsi::with_backend(Path::new("/dev/null"), Box::new(GoodBackend))
+}
@@ -1,7 +1,7 @@
 use std::ffi::c_int;
 
 #[repr(i32)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DataDirection {
     /// e.g. a SCSI Test Unit Ready command
     None = -1,
@@ -15,6 +15,9 @@ pub enum DataDirection {
     /// kernel buffers before the transfer
     ToFromDevice = -4,
     Unknown = -5,
+    /// separate data out and data in buffers transferred by a single command,
+    /// see [`crate::Command::data_in_size`]
+    Bidirectional = -6,
 }
 
 impl From<DataDirection> for c_int {
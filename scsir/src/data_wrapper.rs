@@ -4,13 +4,43 @@ use std::{
     alloc::Layout,
     borrow::{Borrow, BorrowMut},
     fmt::Debug,
+    marker::PhantomData,
     mem::{self, MaybeUninit},
     ops::{Deref, DerefMut},
-    ptr, slice,
+    ptr::{self, NonNull},
+    slice,
 };
 
 pub type AnyType = [u8; 0];
 
+/// Types that can be read out of any zeroed or device written buffer.
+///
+/// # Safety
+///
+/// The type must be `Copy`, have no padding and be valid for every bit pattern. The
+/// `#[bitfield]` structs of this crate qualify since they are byte arrays underneath.
+pub unsafe trait Pod: Copy + sealed::Sealed {}
+
+pub(crate) mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_pod {
+    ( $( $type:ty ),* $(,)? ) => {
+        $(
+            impl crate::data_wrapper::sealed::Sealed for $type {}
+            unsafe impl crate::data_wrapper::Pod for $type {}
+        )*
+    };
+}
+
+pub(crate) use impl_pod;
+
+impl_pod!((), u8, u16, u32, u64);
+
+impl<T: Pod, const N: usize> sealed::Sealed for [T; N] {}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
 pub struct FlexibleStruct<Body, Element> {
     length: usize,
     capacity: usize,
@@ -18,10 +48,18 @@ pub struct FlexibleStruct<Body, Element> {
 }
 
 impl<Body, Element> FlexibleStruct<Body, Element> {
+    // the elements start right after the body, so the body has to keep them aligned
+    const ELEMENTS_ALIGNED: () = assert!(
+        mem::size_of::<Body>() % mem::align_of::<Element>() == 0,
+        "the size of the body is not a multiple of the alignment of the elements"
+    );
+
     pub fn with_body_capacity(body: Body, capacity: usize) -> Self {
-        let size = mem::size_of::<Body>() + mem::size_of::<Element>() * capacity;
-        let layout = Layout::from_size_align(size, mem::align_of::<Body>()).unwrap();
-        let ptr = unsafe { std::alloc::alloc_zeroed(layout) as *mut Raw<Body, Element> };
+        let ptr =
+            unsafe { std::alloc::alloc_zeroed(Self::layout(capacity)) as *mut Raw<Body, Element> };
+        if ptr.is_null() {
+            panic!("Out of memory!");
+        }
 
         unsafe {
             ptr::write_unaligned(ptr::addr_of_mut!((*ptr).body) as *mut Body, body);
@@ -89,20 +127,23 @@ impl<Body, Element> FlexibleStruct<Body, Element> {
         }
     }
 
-    pub unsafe fn body_as_ref(&self) -> &Body {
-        &*ptr::addr_of!((*self.ptr).body)
-    }
-
-    pub unsafe fn body_as_mut(&mut self) -> &mut Body {
-        &mut *ptr::addr_of_mut!((*self.ptr).body)
-    }
-
-    pub unsafe fn elements_as_slice(&self) -> &[Element] {
-        slice::from_raw_parts(ptr::addr_of!((*self.ptr).array).cast(), self.length)
+    /// Drops the elements past `length`, a longer `length` changes nothing.
+    pub fn truncate(&mut self, length: usize) {
+        if std::mem::needs_drop::<Element>() {
+            while self.length > length {
+                self.pop();
+            }
+        } else {
+            self.length = usize::min(self.length, length);
+        }
     }
 
-    pub unsafe fn elements_as_mut_slice(&mut self) -> &mut [Element] {
-        slice::from_raw_parts_mut(ptr::addr_of_mut!((*self.ptr).array).cast(), self.length)
+    /// Drops the elements the device didn't return, which are the ones past the first
+    /// `transfered_data_length` bytes. Only whole elements are kept.
+    pub fn truncate_to_transfered(&mut self, transfered_data_length: usize) {
+        let length = transfered_data_length.saturating_sub(mem::size_of::<Body>())
+            / usize::max(mem::size_of::<Element>(), 1);
+        self.truncate(length);
     }
 
     pub fn total_size(&self) -> usize {
@@ -125,7 +166,7 @@ impl<Body, Element> FlexibleStruct<Body, Element> {
         self.capacity
     }
 
-    pub fn iter_maybe_uninit(&self) -> MaybeUninitIter<Body, Element> {
+    pub fn iter_maybe_uninit(&self) -> MaybeUninitIter<'_, Body, Element> {
         MaybeUninitIter {
             this: self,
             index: 0,
@@ -150,10 +191,10 @@ impl<Body, Element> FlexibleStruct<Body, Element> {
             return;
         }
 
-        let new_size = mem::size_of::<Body>() + mem::size_of::<Element>() * new_capacity;
-        let layout = Layout::from_size_align(new_size, mem::align_of::<Body>()).unwrap();
+        let new_size = Self::layout(new_capacity).size();
         let memory = unsafe {
-            std::alloc::realloc(self.ptr.cast(), layout, new_size) as *mut Raw<Body, Element>
+            std::alloc::realloc(self.ptr.cast(), Self::layout(self.capacity), new_size)
+                as *mut Raw<Body, Element>
         };
 
         if memory.is_null() {
@@ -163,6 +204,51 @@ impl<Body, Element> FlexibleStruct<Body, Element> {
         self.ptr = memory;
         self.capacity = new_capacity;
     }
+
+    // never zero sized, the allocator doesn't accept that
+    fn layout(capacity: usize) -> Layout {
+        let size = mem::size_of::<Body>() + mem::size_of::<Element>() * capacity;
+        let align = usize::max(mem::align_of::<Body>(), mem::align_of::<Element>());
+
+        Layout::from_size_align(usize::max(size, 1), align).unwrap()
+    }
+}
+
+impl<Body: Pod, Element> FlexibleStruct<Body, Element> {
+    pub fn body_as_ref(&self) -> &Body {
+        unsafe { &*ptr::addr_of!((*self.ptr).body) }
+    }
+
+    pub fn body_as_mut(&mut self) -> &mut Body {
+        unsafe { &mut *ptr::addr_of_mut!((*self.ptr).body) }
+    }
+}
+
+impl<Body, Element: Pod> FlexibleStruct<Body, Element> {
+    pub fn elements_as_slice(&self) -> &[Element] {
+        let () = Self::ELEMENTS_ALIGNED;
+
+        unsafe { slice::from_raw_parts(ptr::addr_of!((*self.ptr).array).cast(), self.length) }
+    }
+
+    pub fn elements_as_mut_slice(&mut self) -> &mut [Element] {
+        let () = Self::ELEMENTS_ALIGNED;
+
+        unsafe {
+            slice::from_raw_parts_mut(ptr::addr_of_mut!((*self.ptr).array).cast(), self.length)
+        }
+    }
+}
+
+impl<Body: Pod, Element: Pod> FlexibleStruct<Body, Element> {
+    /// A zeroed body followed by `length` zeroed elements.
+    pub fn zeroed(length: usize) -> Self {
+        // all zeros is a valid Pod, and the allocation is zeroed beyond the body
+        let mut temp = Self::with_body_capacity(unsafe { mem::zeroed() }, length);
+        temp.length = length;
+
+        temp
+    }
 }
 
 impl<Body: Clone, Element> FlexibleStruct<Body, Element> {
@@ -182,7 +268,7 @@ impl<Body: Default, Element> FlexibleStruct<Body, Element> {
 }
 
 impl<Body, Element: Clone> FlexibleStruct<Body, Element> {
-    pub fn iter_clone(&self) -> CloneIter<Body, Element> {
+    pub fn iter_clone(&self) -> CloneIter<'_, Body, Element> {
         CloneIter {
             maybe_uninit_iter: self.iter_maybe_uninit(),
         }
@@ -263,11 +349,7 @@ impl<Body: Default, Element> Default for FlexibleStruct<Body, Element> {
 
 impl<B, E> Drop for FlexibleStruct<B, E> {
     fn drop(&mut self) {
-        let layout = Layout::from_size_align(
-            mem::size_of::<B>() + self.capacity * mem::size_of::<E>(),
-            mem::align_of::<B>(),
-        )
-        .unwrap();
+        let layout = Self::layout(self.capacity);
 
         self.clear();
 
@@ -378,6 +460,70 @@ impl From<Vec<u8>> for VecBufferWrapper {
     }
 }
 
+/// Data out bytes borrowed from the caller, handed to the driver without a copy.
+///
+/// The driver only reads a data out buffer, so the pointer of the shared slice is passed as
+/// is. `AnyType` is zero sized, the mutable reference covers none of the borrowed bytes.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SliceBufferWrapper<'a>(pub &'a [u8]);
+
+impl Borrow<AnyType> for SliceBufferWrapper<'_> {
+    fn borrow(&self) -> &AnyType {
+        unsafe { &*self.0.as_ptr().cast() }
+    }
+}
+
+impl BorrowMut<AnyType> for SliceBufferWrapper<'_> {
+    fn borrow_mut(&mut self) -> &mut AnyType {
+        unsafe { &mut *self.0.as_ptr().cast_mut().cast() }
+    }
+}
+
+impl Deref for SliceBufferWrapper<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+/// Data in bytes borrowed from the caller, the driver fills the slice itself.
+///
+/// The command hands out one copy per attempt and the attempts run one after another, so the
+/// copies never write to the slice at the same time.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct MutSliceBufferWrapper<'a> {
+    ptr: NonNull<u8>,
+    length: usize,
+    marker: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> MutSliceBufferWrapper<'a> {
+    pub fn new(value: &'a mut [u8]) -> Self {
+        Self {
+            length: value.len(),
+            ptr: NonNull::from(value).cast(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+}
+
+impl Borrow<AnyType> for MutSliceBufferWrapper<'_> {
+    fn borrow(&self) -> &AnyType {
+        unsafe { &*self.ptr.as_ptr().cast() }
+    }
+}
+
+impl BorrowMut<AnyType> for MutSliceBufferWrapper<'_> {
+    fn borrow_mut(&mut self) -> &mut AnyType {
+        unsafe { &mut *self.ptr.as_ptr().cast() }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -480,4 +626,47 @@ mod tests {
 
         assert_eq!(body_marker, true, "body marker dropped");
     }
+
+    #[test]
+    fn zeroed_test() {
+        let temp = FlexibleStruct::<[u8; 3], u32>::zeroed(4);
+
+        assert_eq!(temp.length(), 4, "length");
+        assert_eq!(temp.total_size(), 3 + 4 * 4, "total size");
+        assert_eq!(temp.body_as_ref(), &[0; 3], "zeroed body");
+        assert!(temp.as_bytes().iter().all(|&b| b == 0), "zeroed bytes");
+
+        let temp = FlexibleStruct::<u32, u16>::zeroed(3);
+        let elements = temp.elements_as_slice();
+
+        assert_eq!(elements, [0; 3], "zeroed elements");
+        assert_eq!(
+            elements.as_ptr() as usize % mem::align_of::<u16>(),
+            0,
+            "aligned elements"
+        );
+        assert_eq!(
+            temp.body_as_ref() as *const u32 as usize % mem::align_of::<u32>(),
+            0,
+            "aligned body"
+        );
+
+        let mut temp = FlexibleStruct::<(), u8>::zeroed(0);
+        assert!(temp.elements_as_slice().is_empty(), "empty");
+        temp.push(0xFF);
+        assert_eq!(temp.elements_as_mut_slice(), [0xFF], "pushed element");
+    }
+
+    #[test]
+    fn truncate_test() {
+        let mut temp = FlexibleStruct::<[u8; 4], [u8; 8]>::zeroed(4);
+
+        temp.truncate_to_transfered(4 + 2 * 8 + 3);
+        assert_eq!(temp.length(), 2, "whole transferred elements");
+        temp.truncate(3);
+        assert_eq!(temp.length(), 2, "not grown");
+        temp.truncate_to_transfered(2);
+        assert_eq!(temp.length(), 0, "body not transferred completely");
+        assert_eq!(temp.capacity(), 4, "allocation kept");
+    }
 }
@@ -2,11 +2,18 @@ use std::{any::Any, io, path::PathBuf};
 
 use thiserror::Error;
 
+#[cfg(target_os = "linux")]
+use crate::os::linux::{DriverStatus, HostStatus};
+use crate::{
+    command::sense::{SenseData, SenseKey},
+    os::errors,
+};
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Error)]
-pub enum Error<T = Box<dyn Any>> {
-    #[error("{0} is not a block device.")]
+pub enum Error<T = Box<dyn Any + Send + Sync>> {
+    #[error("{0} is not a block, sg or bsg device.")]
     NotBlockDevice(PathBuf),
     #[error("{0} is not an SCSI Generic device, or old SCSI Generic driver.")]
     NotScsiDevice(PathBuf),
@@ -16,8 +23,103 @@ pub enum Error<T = Box<dyn Any>> {
     BadArgument(String),
     #[error("Check condition: {0:?}")]
     CheckCondition(T),
+    /// sense data with a sense key that has no variant of its own
+    #[error("Sense: {0}")]
+    Sense(SenseData),
+    #[error("Sense: {0}")]
+    NotReady(SenseData),
+    #[error("Sense: {0}")]
+    MediumError(SenseData),
+    #[error("Sense: {0}")]
+    HardwareError(SenseData),
+    #[error("Sense: {0}")]
+    IllegalRequest(SenseData),
+    #[error("Sense: {0}")]
+    UnitAttention(SenseData),
+    #[error("Sense: {0}")]
+    DataProtect(SenseData),
+    #[error("Sense: {0}")]
+    AbortedCommand(SenseData),
+    #[error("The command timed out.")]
+    Timeout,
+    /// the command was aborted or bounced back before it completed, e.g. by a bus or device
+    /// reset, a busy bus or a disrupted link, worth retrying
+    #[cfg(target_os = "linux")]
+    #[error(
+        "The command was aborted: host status {host_status:?}, driver status {driver_status:?}"
+    )]
+    Aborted {
+        host_status: HostStatus,
+        driver_status: DriverStatus,
+    },
+    /// the adapter or the link to the device failed the command, retrying won't help
+    #[cfg(target_os = "linux")]
+    #[error("Transport error: host status {host_status:?}, driver status {driver_status:?}")]
+    TransportError {
+        host_status: HostStatus,
+        driver_status: DriverStatus,
+    },
+    /// EACCES, the handle lacks the rights for the command, usually a read-only or non-root open
+    #[error("Permission denied, the device may have to be opened read-write or as root: {0}")]
+    PermissionDenied(io::Error),
+    /// ENODEV, the device went away, e.g. it was unplugged or its driver was unbound
+    #[error("The device is gone: {0}")]
+    NoDevice(io::Error),
+    /// ENOTTY, the handle doesn't take SG_IO at all
+    #[error("This fd is not an sg device, or the driver doesn't support SCSI pass-through: {0}")]
+    NotSgDevice(io::Error),
+    /// EINVAL, the driver rejected the request before it reached the device
+    #[error("The driver rejected the request, check the CDB, transfer length and timeout: {0}")]
+    InvalidRequest(io::Error),
     #[error("{0:?}")]
     Other(String),
     #[error("{0}")]
     IO(#[from] io::Error),
 }
+
+// the common sense keys get a variant each, so callers can match on the kind of failure
+impl From<SenseData> for Error {
+    fn from(sense: SenseData) -> Self {
+        match sense.sense_key() {
+            Some(SenseKey::NotReady) => Self::NotReady(sense),
+            Some(SenseKey::MediumError) => Self::MediumError(sense),
+            Some(SenseKey::HardwareError) => Self::HardwareError(sense),
+            Some(SenseKey::IllegalRequest) => Self::IllegalRequest(sense),
+            Some(SenseKey::UnitAttention) => Self::UnitAttention(sense),
+            Some(SenseKey::DataProtect) => Self::DataProtect(sense),
+            Some(SenseKey::AbortedCommand) => Self::AbortedCommand(sense),
+            _ => Self::Sense(sense),
+        }
+    }
+}
+
+impl Error {
+    /// The error of a failed pass-through ioctl, the errnos that mean something specific for a
+    /// SCSI device get a variant each, a timeout is [`Error::Timeout`].
+    pub(crate) fn from_os_error(os_error: i32) -> Self {
+        let error = io::Error::from_raw_os_error(os_error);
+        if error.kind() == io::ErrorKind::TimedOut {
+            return Self::Timeout;
+        }
+
+        match os_error {
+            errors::PERMISSION_DENIED => Self::PermissionDenied(error),
+            errors::NO_DEVICE => Self::NoDevice(error),
+            errors::NOT_PASS_THROUGH => Self::NotSgDevice(error),
+            errors::INVALID_REQUEST => Self::InvalidRequest(error),
+            _ => Self::IO(error),
+        }
+    }
+
+    /// errno or GetLastError behind the error, if it came from the OS
+    pub fn raw_os_error(&self) -> Option<i32> {
+        match self {
+            Self::PermissionDenied(error)
+            | Self::NoDevice(error)
+            | Self::NotSgDevice(error)
+            | Self::InvalidRequest(error)
+            | Self::IO(error) => error.raw_os_error(),
+            _ => None,
+        }
+    }
+}
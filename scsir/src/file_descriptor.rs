@@ -28,6 +28,14 @@ impl FileDescriptor {
         })
     }
 
+    #[cfg(target_os = "linux")]
+    pub fn from_owned(fd: std::os::fd::OwnedFd, path: &std::path::Path) -> Self {
+        Self {
+            file: File::from(fd),
+            path: String::from(path.to_string_lossy()),
+        }
+    }
+
     #[cfg(target_os = "linux")]
     pub fn is_block(&self) -> crate::Result<bool> {
         use std::os::unix::prelude::FileTypeExt;
@@ -36,6 +44,40 @@ impl FileDescriptor {
         Ok(file_type.is_block_device())
     }
 
+    // bsg nodes, like /dev/bsg/0:0:0:0, are character devices of the bsg class
+    #[cfg(target_os = "linux")]
+    pub fn is_bsg(&self) -> crate::Result<bool> {
+        use nix::libc;
+        use std::os::unix::prelude::{FileTypeExt, MetadataExt};
+
+        let metadata = self.file.metadata()?;
+        if !metadata.file_type().is_char_device() {
+            return Ok(false);
+        }
+
+        let device = metadata.rdev();
+        let class = std::fs::canonicalize(format!(
+            "/sys/dev/char/{}:{}",
+            libc::major(device),
+            libc::minor(device)
+        ));
+
+        Ok(class.is_ok_and(|path| path.components().any(|part| part.as_os_str() == "bsg")))
+    }
+
+    // sg nodes, like /dev/sg0, are character devices of the SCSI generic major
+    #[cfg(target_os = "linux")]
+    pub fn is_sg(&self) -> crate::Result<bool> {
+        use nix::libc;
+        use std::os::unix::prelude::{FileTypeExt, MetadataExt};
+
+        const SCSI_GENERIC_MAJOR: u32 = 21;
+
+        let metadata = self.file.metadata()?;
+        Ok(metadata.file_type().is_char_device()
+            && libc::major(metadata.rdev()) == SCSI_GENERIC_MAJOR)
+    }
+
     #[cfg(target_os = "windows")]
     pub fn is_block(&self) -> crate::Result<bool> {
         use std::io;
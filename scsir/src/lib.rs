@@ -1,16 +1,76 @@
+#[cfg(feature = "async")]
+mod async_scsi;
+mod buffer_pool;
 pub mod command;
+#[cfg(test)]
+mod counting_allocator;
 mod data_direction;
 mod data_wrapper;
 mod error;
 mod file_descriptor;
+#[cfg(any(test, feature = "test-util"))]
+mod mock;
 mod os;
+pub mod prelude;
 mod result_data;
+mod retry_policy;
 mod scsi;
+#[cfg(feature = "trace")]
+mod trace;
+mod util;
 
+#[cfg(feature = "async")]
+pub use async_scsi::{AsyncScsi, Task};
+pub use buffer_pool::{BufferPool, PooledBuffer};
 pub use command::shortcut;
-pub use command::Command;
+pub use command::{CdbBytes, Command};
 pub use data_direction::DataDirection;
 pub use error::{Error, Result};
-pub use result_data::ResultData;
+#[cfg(any(test, feature = "test-util"))]
+pub use mock::{MockCommand, MockResponse, MockScsi};
+pub use result_data::{HasSense, ResultData};
+pub use retry_policy::RetryPolicy;
 
-pub use scsi::Scsi;
+pub use scsi::{Scsi, ScsiBuilder};
+
+/// The block commands are there with the `block` feature and compiled out without it.
+///
+#[cfg_attr(feature = "block", doc = "```no_run")]
+#[cfg_attr(not(feature = "block"), doc = "```compile_fail")]
+/// let scsi = scsir::Scsi::new("/dev/sdX").unwrap();
+/// scsi.read_blocks(0, 1, 512).unwrap();
+/// ```
+///
+/// The same goes for the stream commands and the `stream` feature.
+///
+#[cfg_attr(feature = "stream", doc = "```no_run")]
+#[cfg_attr(not(feature = "stream"), doc = "```compile_fail")]
+/// let scsi = scsir::Scsi::new("/dev/sdX").unwrap();
+/// scsi.get_stream_status().issue().unwrap();
+/// ```
+#[cfg(doctest)]
+pub struct FeatureTest;
+
+/// [`Scsi`] is `Send` and `Sync`, a handle can be shared between threads.
+///
+/// ```no_run
+/// use std::{sync::Arc, thread};
+///
+/// let scsi = Arc::new(scsir::Scsi::new("/dev/sdX").unwrap());
+/// let worker = {
+///     let scsi = Arc::clone(&scsi);
+///     thread::spawn(move || scsi.test_unit_ready().issue())
+/// };
+/// scsi.test_unit_ready().issue().unwrap();
+/// worker.join().unwrap().unwrap();
+/// ```
+///
+/// A command builder borrows the handle, so it can't outlive it on another thread.
+///
+/// ```compile_fail
+/// let scsi = scsir::Scsi::new("/dev/sdX").unwrap();
+/// let mut command = scsi.test_unit_ready();
+/// std::thread::spawn(move || command.issue());
+/// ```
+#[cfg(doctest)]
+pub struct SendSyncTest;
@@ -0,0 +1,463 @@
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+    path::Path,
+    slice,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    os::{Backend, Request, Response},
+    DataDirection, Scsi,
+};
+
+/// A device that answers from canned responses, for testing commands without hardware.
+///
+/// Every expected CDB is registered with [`MockScsi::expect`] and consumed in order. Issuing
+/// a CDB other than the next expected one, or one too many, panics. The mock is shared
+/// between its clones, so keep one to inspect [`MockScsi::issued`] after handing another to
+/// [`Scsi::mock`].
+#[derive(Clone, Default)]
+pub struct MockScsi {
+    state: Arc<Mutex<State>>,
+}
+
+/// The response of the mock to one expected CDB.
+#[derive(Clone, Debug, Default)]
+pub struct MockResponse {
+    data: Vec<u8>,
+    sense: Vec<u8>,
+    status: u8,
+    residual: Option<usize>,
+    duration: Option<Duration>,
+    os_error: i32,
+    #[cfg(target_os = "linux")]
+    host_status: u16,
+    #[cfg(target_os = "linux")]
+    driver_status: u16,
+}
+
+/// A command the mock received.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockCommand {
+    pub command: Vec<u8>,
+    pub direction: DataDirection,
+    /// the bytes sent to the device, empty for commands without data out
+    pub data: Vec<u8>,
+    /// the timeout the command was issued with
+    pub timeout: Duration,
+    /// the sense buffer length handed to the driver, 0 with autosense off
+    pub sense_buffer_length: usize,
+}
+
+#[derive(Default)]
+struct State {
+    expected: VecDeque<(Vec<u8>, MockResponse)>,
+    issued: Vec<MockCommand>,
+}
+
+impl MockScsi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers the next command with `response` once it turns out to be `command`.
+    pub fn expect(&self, command: &[u8], response: MockResponse) -> &Self {
+        self.state
+            .lock()
+            .unwrap()
+            .expected
+            .push_back((command.to_vec(), response));
+        self
+    }
+
+    /// Every command issued so far, the oldest first.
+    pub fn issued(&self) -> Vec<MockCommand> {
+        self.state.lock().unwrap().issued.clone()
+    }
+
+    /// Panics unless every expected command was issued.
+    pub fn verify(&self) {
+        let state = self.state.lock().unwrap();
+        assert!(
+            state.expected.is_empty(),
+            "{} expected commands were not issued, the next one is {:02X?}",
+            state.expected.len(),
+            state.expected.front().map(|(command, _)| command)
+        );
+    }
+}
+
+impl Debug for MockScsi {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("MockScsi")
+            .field("expected", &state.expected.len())
+            .field("issued", &state.issued.len())
+            .finish()
+    }
+}
+
+impl MockResponse {
+    /// GOOD status without data in.
+    pub fn good() -> Self {
+        Self::default()
+    }
+
+    /// CHECK CONDITION with `sense` as the sense data.
+    pub fn check_condition(sense: &[u8]) -> Self {
+        Self {
+            data: vec![],
+            sense: sense.to_vec(),
+            status: 0x02,
+            ..Self::default()
+        }
+    }
+
+    /// A failed ioctl with `errno` as errno or GetLastError, the command never reaches the device.
+    pub fn os_error(errno: i32) -> Self {
+        Self {
+            os_error: errno,
+            ..Self::default()
+        }
+    }
+
+    /// The data in, a read shorter than the allocation length reports the rest as residual.
+    pub fn data(mut self, value: &[u8]) -> Self {
+        self.data = value.to_vec();
+        self
+    }
+
+    /// The residual the adapter reports, instead of what the data in didn't fill. Writes
+    /// report none without it.
+    pub fn residual(mut self, value: usize) -> Self {
+        self.residual = Some(value);
+        self
+    }
+
+    pub fn status(mut self, value: u8) -> Self {
+        self.status = value;
+        self
+    }
+
+    /// How long the command took as the driver reports it, none without it.
+    pub fn duration(mut self, value: Duration) -> Self {
+        self.duration = Some(value);
+        self
+    }
+
+    /// The SG_IO host status, e.g. 0x03 for DID_TIME_OUT.
+    #[cfg(target_os = "linux")]
+    pub fn host_status(mut self, value: u16) -> Self {
+        self.host_status = value;
+        self
+    }
+
+    /// The SG_IO driver status, the driver byte or'ed with the suggestions.
+    #[cfg(target_os = "linux")]
+    pub fn driver_status(mut self, value: u16) -> Self {
+        self.driver_status = value;
+        self
+    }
+}
+
+impl Backend for MockScsi {
+    fn issue(&self, request: Request) -> Response {
+        let mut state = self.state.lock().unwrap();
+
+        let data_out = match request.direction {
+            DataDirection::ToDevice
+            | DataDirection::ToFromDevice
+            | DataDirection::Bidirectional
+                if request.data_length != 0 =>
+            {
+                unsafe { slice::from_raw_parts(request.data, request.data_length as usize) }
+                    .to_vec()
+            }
+            _ => vec![],
+        };
+        state.issued.push(MockCommand {
+            command: request.command.to_vec(),
+            direction: request.direction,
+            data: data_out,
+            timeout: request.timeout,
+            sense_buffer_length: request.sense_buffer_length(),
+        });
+
+        let (command, response) = state
+            .expected
+            .pop_front()
+            .unwrap_or_else(|| panic!("unexpected command {:02X?}", request.command));
+        assert_eq!(
+            command, request.command,
+            "issued command doesn't match the expected one"
+        );
+
+        if response.os_error != 0 {
+            return Response {
+                ioctl_result: -1,
+                os_error: response.os_error,
+                transfered_data_length: 0,
+                residual: 0,
+                sense_length: 0,
+                status: 0,
+                duration: None,
+                #[cfg(target_os = "linux")]
+                host_status: 0,
+                #[cfg(target_os = "linux")]
+                driver_status: crate::os::linux::DriverStatus::OK,
+            };
+        }
+
+        let (data_in, data_in_length) = match request.direction {
+            DataDirection::FromDevice | DataDirection::ToFromDevice => {
+                (request.data, request.data_length)
+            }
+            DataDirection::Bidirectional => (request.data_in, request.data_in_length),
+            _ => (std::ptr::null_mut(), 0),
+        };
+        let length = usize::min(response.data.len(), data_in_length as usize);
+        if length != 0 {
+            unsafe { data_in.copy_from_nonoverlapping(response.data.as_ptr(), length) };
+        }
+
+        let sense_length = match request.autosense {
+            true => usize::min(response.sense.len(), request.sense_buffer.len()),
+            false => 0,
+        };
+        request.sense_buffer[..sense_length].copy_from_slice(&response.sense[..sense_length]);
+
+        let residual = response
+            .residual
+            .unwrap_or(data_in_length as usize - length);
+        let transfered_data_length = match request.direction {
+            DataDirection::ToDevice => (request.data_length as usize).saturating_sub(residual),
+            _ => (data_in_length as usize).saturating_sub(residual),
+        };
+
+        Response {
+            ioctl_result: 0,
+            os_error: 0,
+            transfered_data_length,
+            residual,
+            sense_length,
+            status: response.status,
+            duration: response.duration,
+            #[cfg(target_os = "linux")]
+            host_status: response.host_status,
+            #[cfg(target_os = "linux")]
+            driver_status: crate::os::linux::DriverStatus::from_bits_retain(response.driver_status),
+        }
+    }
+}
+
+impl Scsi {
+    /// A handle that issues every command to `mock` instead of a device.
+    pub fn mock(mock: MockScsi) -> Scsi {
+        Scsi::with_backend(Path::new("mock"), Box::new(mock))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "block")]
+    fn check_condition_test() {
+        let mock = MockScsi::new();
+        // NOT READY, MEDIUM NOT PRESENT
+        mock.expect(
+            &[0x28, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00],
+            MockResponse::check_condition(&[
+                0x70, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x3A, 0x00,
+                0x00, 0x00, 0x00, 0x00,
+            ]),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        assert!(
+            matches!(scsi.read_blocks(0, 1, 512), Err(crate::Error::NotReady(_))),
+            "sense key of the canned sense data"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    #[cfg(feature = "block")]
+    fn data_out_test() {
+        let mock = MockScsi::new();
+        mock.expect(
+            &[0x2A, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x01, 0x00],
+            MockResponse::good(),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        scsi.write_blocks(0x10, &[0xA5; 512], 512).unwrap();
+
+        let issued = mock.issued();
+        assert_eq!(issued.len(), 1, "issued commands");
+        assert_eq!(
+            issued[0].direction,
+            DataDirection::ToDevice,
+            "data direction"
+        );
+        assert_eq!(issued[0].data, [0xA5; 512], "data out");
+    }
+
+    #[test]
+    fn os_error_test() {
+        use crate::os::errors;
+
+        let mock = MockScsi::new();
+        for errno in [
+            errors::PERMISSION_DENIED,
+            errors::NO_DEVICE,
+            errors::NOT_PASS_THROUGH,
+            errors::INVALID_REQUEST,
+            crate::os::READ_ONLY_ERROR,
+        ] {
+            mock.expect(&[0x00; 6], MockResponse::os_error(errno));
+        }
+
+        let scsi = Scsi::mock(mock.clone());
+        let result = scsi.test_unit_ready().issue();
+        assert!(
+            matches!(result, Err(crate::Error::PermissionDenied(_))),
+            "permission denied"
+        );
+        let result = scsi.test_unit_ready().issue();
+        assert!(
+            matches!(result, Err(crate::Error::NoDevice(_))),
+            "device gone"
+        );
+        let error = scsi.test_unit_ready().issue().unwrap_err();
+        assert!(
+            matches!(error, crate::Error::NotSgDevice(_)),
+            "not an sg device"
+        );
+        assert!(
+            error.to_string().contains("not an sg device"),
+            "hint of {}",
+            error
+        );
+        let result = scsi.test_unit_ready().issue();
+        assert!(
+            matches!(result, Err(crate::Error::InvalidRequest(_))),
+            "bad request"
+        );
+        let error = scsi.test_unit_ready().issue().unwrap_err();
+        assert!(matches!(error, crate::Error::IO(_)), "any other errno");
+        assert_eq!(
+            error.raw_os_error(),
+            Some(crate::os::READ_ONLY_ERROR),
+            "errno of {}",
+            error
+        );
+        mock.verify();
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn transport_status_test() {
+        use crate::os::linux::{DriverStatus, HostStatus};
+
+        let mock = MockScsi::new();
+        let responses = [
+            // DID_TIME_OUT
+            MockResponse::good().host_status(0x03),
+            // DRIVER_TIMEOUT
+            MockResponse::good().driver_status(0x06),
+            // DID_ABORT
+            MockResponse::good().host_status(0x05),
+            // DID_RESET
+            MockResponse::good().host_status(0x08),
+            // DID_BUS_BUSY, DID_SOFT_ERROR, DID_IMM_RETRY, DID_REQUEUE, DID_TRANSPORT_DISRUPTED
+            MockResponse::good().host_status(0x02),
+            MockResponse::good().host_status(0x0b),
+            MockResponse::good().host_status(0x0c),
+            MockResponse::good().host_status(0x0d),
+            MockResponse::good().host_status(0x0e),
+            // DID_NO_CONNECT
+            MockResponse::good().host_status(0x01),
+            // DRIVER_HARD with SUGGEST_ABORT
+            MockResponse::good().driver_status(0x27),
+            // DRIVER_SENSE only says that there is sense data, NOT READY here
+            MockResponse::check_condition(&[
+                0x70, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x3A, 0x00,
+                0x00, 0x00, 0x00, 0x00,
+            ])
+            .driver_status(0x08),
+        ];
+        for response in responses {
+            mock.expect(&[0x00; 6], response);
+        }
+
+        let scsi = Scsi::mock(mock.clone());
+        let mut command = scsi.test_unit_ready();
+        assert!(
+            matches!(command.issue(), Err(crate::Error::Timeout)),
+            "host timed out"
+        );
+        assert!(
+            matches!(command.issue(), Err(crate::Error::Timeout)),
+            "driver timed out"
+        );
+        for host_status in [
+            HostStatus::Abort,
+            HostStatus::Reset,
+            HostStatus::BusBusy,
+            HostStatus::SoftError,
+            HostStatus::ImmediateRetry,
+            HostStatus::Requeue,
+            HostStatus::TransportDisrupted,
+        ] {
+            assert!(
+                matches!(
+                    command.issue(),
+                    Err(crate::Error::Aborted {
+                        host_status: status,
+                        driver_status: DriverStatus::OK,
+                    }) if status == host_status
+                ),
+                "retryable {:?}",
+                host_status
+            );
+        }
+        assert!(
+            matches!(
+                command.issue(),
+                Err(crate::Error::TransportError {
+                    host_status: HostStatus::NoConnect,
+                    ..
+                })
+            ),
+            "no connection"
+        );
+        let hard = DriverStatus::HARD | DriverStatus::ABORT;
+        assert!(
+            matches!(
+                command.issue(),
+                Err(crate::Error::TransportError {
+                    host_status: HostStatus::Ok,
+                    driver_status,
+                }) if driver_status == hard
+            ),
+            "hard driver error"
+        );
+        assert!(
+            matches!(command.issue(), Err(crate::Error::NotReady(_))),
+            "sense data instead of a transport error"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    #[should_panic(expected = "expected commands were not issued")]
+    fn verify_test() {
+        let mock = MockScsi::new();
+        mock.expect(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00], MockResponse::good());
+        mock.verify();
+    }
+}
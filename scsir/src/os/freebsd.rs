@@ -0,0 +1,307 @@
+use std::{
+    ffi::{c_char, c_int, c_uint, c_void, CString},
+    mem::size_of,
+    os::unix::prelude::OsStrExt,
+    path::Path,
+};
+
+use nix::libc;
+
+use crate::{
+    command::sense::MAX_SENSE_BUFFER_LENGTH,
+    os::{self, Backend, OpenFlags, Request, Response},
+    DataDirection,
+};
+
+/// The CAM pass(4) driver through libcam, which looks the device up through /dev/xpt0.
+///
+/// The CCB layout below follows cam_ccb.h of FreeBSD 13 and later on little endian targets.
+#[derive(Debug)]
+pub struct CamBackend {
+    device: *mut CamDevice,
+}
+
+// libcam only reads the device after opening it, every CCB is allocated per command
+unsafe impl Send for CamBackend {}
+unsafe impl Sync for CamBackend {}
+
+impl CamBackend {
+    pub fn open(path: &Path, flags: OpenFlags) -> crate::Result<Self> {
+        let path_string = CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| crate::Error::BadArgument(String::from("path contains a nul byte")))?;
+
+        let access_mode = match flags.read_only {
+            true => libc::O_RDONLY,
+            false => libc::O_RDWR,
+        };
+        let device =
+            unsafe { cam_open_device(path_string.as_ptr(), access_mode | flags.custom_flags()) };
+        if device.is_null() {
+            return Err(crate::Error::NotScsiDevice(path.to_owned()));
+        }
+
+        Ok(Self { device })
+    }
+}
+
+impl Drop for CamBackend {
+    fn drop(&mut self) {
+        unsafe { cam_close_device(self.device) };
+    }
+}
+
+impl Backend for CamBackend {
+    fn issue(&self, request: Request) -> Response {
+        let mut response = Response {
+            ioctl_result: -1,
+            os_error: 0,
+            transfered_data_length: 0,
+            residual: request.data_length as usize,
+            sense_length: 0,
+            status: 0,
+            duration: None,
+        };
+
+        // CAM_DIR_BOTH shares a single data buffer between data out and data in
+        if request.direction == DataDirection::Bidirectional {
+            response.os_error = libc::EOPNOTSUPP;
+            return response;
+        }
+
+        let ccb = unsafe { cam_getccb(self.device) };
+        if ccb.is_null() {
+            response.os_error = libc::ENOMEM;
+            return response;
+        }
+
+        // cam_getccb only initializes the header, like CCB_CLEAR_ALL_EXCEPT_HDR
+        unsafe { clear_all_except_hdr(ccb) };
+        let csio = unsafe { &mut *ccb };
+        let mut flags = cam_flags(request.direction) | CAM_DEV_QFRZDIS;
+        if !request.autosense {
+            flags |= CAM_DIS_AUTOSENSE;
+        }
+        if request.command.len() > IOCDBLEN {
+            flags |= CAM_CDB_POINTER;
+            csio.cdb_io.cdb_ptr = request.command.as_ptr() as *mut u8;
+        } else {
+            unsafe {
+                csio.cdb_io.cdb_bytes[..request.command.len()].copy_from_slice(request.command)
+            };
+        }
+
+        csio.ccb_h.func_code = XPT_SCSI_IO;
+        csio.ccb_h.flags = flags;
+        csio.ccb_h.retry_count = 0;
+        csio.ccb_h.timeout = request.timeout_millis();
+        csio.data_ptr = request.data;
+        csio.dxfer_len = request.data_length;
+        csio.sense_len = request.sense_buffer_length() as u8;
+        csio.cdb_len = request.command.len() as u8;
+        csio.tag_action = MSG_SIMPLE_Q_TAG;
+
+        if unsafe { cam_send_ccb(self.device, ccb) } < 0 {
+            response.os_error = os::os_error(-1);
+            unsafe { cam_freeccb(ccb) };
+            return response;
+        }
+
+        let status = csio.ccb_h.status;
+        match status & CAM_STATUS_MASK {
+            CAM_REQ_CMP | CAM_SCSI_STATUS_ERROR => {
+                response.ioctl_result = 0;
+                response.status = csio.scsi_status;
+                response.residual = csio.resid as usize;
+                response.transfered_data_length =
+                    (request.data_length as usize).saturating_sub(response.residual);
+
+                if status & CAM_AUTOSNS_VALID != 0 {
+                    let sense_length = csio.sense_len.saturating_sub(csio.sense_resid) as usize;
+                    request.sense_buffer[..sense_length]
+                        .copy_from_slice(&csio.sense_data[..sense_length]);
+                    response.sense_length = sense_length;
+                }
+            }
+            CAM_CMD_TIMEOUT => response.os_error = libc::ETIMEDOUT,
+            _ => response.os_error = libc::EIO,
+        }
+
+        unsafe { cam_freeccb(ccb) };
+        response
+    }
+}
+
+fn cam_flags(direction: DataDirection) -> u32 {
+    match direction {
+        DataDirection::None | DataDirection::Unknown | DataDirection::Bidirectional => CAM_DIR_NONE,
+        DataDirection::ToDevice => CAM_DIR_OUT,
+        DataDirection::FromDevice => CAM_DIR_IN,
+        DataDirection::ToFromDevice => CAM_DIR_BOTH,
+    }
+}
+
+const XPT_SCSI_IO: u32 = 0x01;
+
+const CAM_CDB_POINTER: u32 = 0x0000_0001;
+const CAM_DIS_AUTOSENSE: u32 = 0x0000_0020;
+const CAM_DIR_BOTH: u32 = 0x0000_0000;
+const CAM_DIR_IN: u32 = 0x0000_0040;
+const CAM_DIR_OUT: u32 = 0x0000_0080;
+const CAM_DIR_NONE: u32 = 0x0000_00C0;
+const CAM_DEV_QFRZDIS: u32 = 0x0000_0400;
+
+const CAM_STATUS_MASK: u32 = 0x3F;
+const CAM_REQ_CMP: u32 = 0x01;
+const CAM_CMD_TIMEOUT: u32 = 0x0B;
+const CAM_SCSI_STATUS_ERROR: u32 = 0x0C;
+const CAM_AUTOSNS_VALID: u32 = 0x80;
+
+const MSG_SIMPLE_Q_TAG: u8 = 0x20;
+const IOCDBLEN: usize = 16;
+
+#[repr(C)]
+struct CamDevice {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct CamPinfo {
+    priority: u32,
+    generation: u32,
+    index: c_int,
+}
+
+#[repr(C)]
+struct CamqEntry {
+    next: *mut c_void,
+    previous: *mut c_void,
+}
+
+#[repr(C)]
+struct Timeval {
+    seconds: libc::time_t,
+    microseconds: libc::suseconds_t,
+}
+
+#[repr(C)]
+struct Bintime {
+    seconds: libc::time_t,
+    fraction: u64,
+}
+
+#[repr(C)]
+struct CcbQosArea {
+    etime: Timeval,
+    sim_data: usize,
+    periph_data: usize,
+}
+
+#[repr(C)]
+struct CcbHdr {
+    pinfo: CamPinfo,
+    xpt_links: CamqEntry,
+    sim_links: CamqEntry,
+    periph_links: CamqEntry,
+    retry_count: u16,
+    alloc_flags: u16,
+    cbfcnp: *mut c_void,
+    func_code: u32,
+    status: u32,
+    path: *mut c_void,
+    path_id: u32,
+    target_id: u32,
+    target_lun: u64,
+    flags: u32,
+    xflags: u32,
+    periph_priv: [usize; 2],
+    sim_priv: [usize; 2],
+    qos: CcbQosArea,
+    timeout: u32,
+    softtimeout: Bintime,
+}
+
+// zeroes the ccb past the header, leaving what cam_getccb filled in
+unsafe fn clear_all_except_hdr(ccb: *mut CcbScsiio) {
+    let header_length = size_of::<CcbHdr>();
+    (ccb as *mut u8)
+        .add(header_length)
+        .write_bytes(0, size_of::<CcbScsiio>() - header_length);
+}
+
+#[repr(C)]
+union CdbIo {
+    cdb_ptr: *mut u8,
+    cdb_bytes: [u8; IOCDBLEN],
+}
+
+#[repr(C)]
+struct CcbScsiio {
+    ccb_h: CcbHdr,
+    next_ccb: *mut c_void,
+    req_map: *mut u8,
+    data_ptr: *mut u8,
+    dxfer_len: u32,
+    sense_data: [u8; MAX_SENSE_BUFFER_LENGTH],
+    sense_len: u8,
+    cdb_len: u8,
+    sglist_cnt: u16,
+    scsi_status: u8,
+    sense_resid: u8,
+    resid: u32,
+    cdb_io: CdbIo,
+    msg_ptr: *mut u8,
+    msg_len: u16,
+    tag_action: u8,
+    priority: u8,
+    tag_id: c_uint,
+    init_id: c_uint,
+}
+
+#[link(name = "cam")]
+extern "C" {
+    fn cam_open_device(path: *const c_char, flags: c_int) -> *mut CamDevice;
+    fn cam_close_device(device: *mut CamDevice);
+    fn cam_getccb(device: *mut CamDevice) -> *mut CcbScsiio;
+    fn cam_freeccb(ccb: *mut CcbScsiio);
+    fn cam_send_ccb(device: *mut CamDevice, ccb: *mut CcbScsiio) -> c_int;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_all_except_hdr_test() {
+        let mut ccb = std::mem::MaybeUninit::<CcbScsiio>::uninit();
+        let raw = ccb.as_mut_ptr() as *mut u8;
+        unsafe {
+            raw.write_bytes(0xFF, size_of::<CcbScsiio>());
+            clear_all_except_hdr(ccb.as_mut_ptr());
+        }
+
+        let bytes = unsafe { std::slice::from_raw_parts(raw, size_of::<CcbScsiio>()) };
+        let (header, rest) = bytes.split_at(size_of::<CcbHdr>());
+        assert!(header.iter().all(|&b| b == 0xFF), "header kept");
+        assert!(rest.iter().all(|&b| b == 0x00), "request zeroed");
+    }
+
+    #[test]
+    fn cam_flags_test() {
+        assert_eq!(cam_flags(DataDirection::None), CAM_DIR_NONE, "no data");
+        assert_eq!(cam_flags(DataDirection::ToDevice), CAM_DIR_OUT, "data out");
+        assert_eq!(cam_flags(DataDirection::FromDevice), CAM_DIR_IN, "data in");
+        assert_eq!(
+            cam_flags(DataDirection::ToFromDevice),
+            CAM_DIR_BOTH,
+            "bidirectional"
+        );
+    }
+
+    #[test]
+    fn open_test() {
+        assert!(
+            CamBackend::open(Path::new("/dev/null"), OpenFlags::default()).is_err(),
+            "not a CAM device"
+        );
+    }
+}
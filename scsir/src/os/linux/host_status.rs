@@ -1,6 +1,6 @@
 use std::ffi::c_ushort;
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum HostStatus {
     /// NO error
     Ok,
@@ -30,6 +30,9 @@ pub enum HostStatus {
     ImmediateRetry,
     /// Requeue command (no immediate retry) also without decrementing the retry count
     Requeue,
+    /// Transport error disrupted execution, the transport class retries or fails the command
+    /// once the link is back
+    TransportDisrupted,
     /// Something unknown
     Unknown,
 }
@@ -51,6 +54,7 @@ impl From<c_ushort> for HostStatus {
             0x0b => HostStatus::SoftError,
             0x0c => HostStatus::ImmediateRetry,
             0x0d => HostStatus::Requeue,
+            0x0e => HostStatus::TransportDisrupted,
             _ => HostStatus::Unknown,
         }
     }
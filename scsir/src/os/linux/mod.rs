@@ -3,11 +3,17 @@ mod auxiliary_info;
 mod driver_status;
 mod host_status;
 mod result_data_ext;
+mod sg_backend;
 mod sg_io_header;
+mod sg_io_v4;
+#[cfg(feature = "io-uring")]
+mod uring;
 
 pub use access_flag::AccessFlags;
 pub use auxiliary_info::AuxiliaryInfo;
 pub use driver_status::DriverStatus;
 pub use host_status::HostStatus;
 pub use result_data_ext::ResultDataExt;
+pub use sg_backend::SgBackend;
 pub use sg_io_header::SgIoHeader;
+pub use sg_io_v4::SgIoV4;
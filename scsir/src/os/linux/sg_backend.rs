@@ -0,0 +1,339 @@
+#[cfg(feature = "io-uring")]
+use std::sync::Mutex;
+use std::{io, path::Path, time::Duration};
+
+use nix::libc;
+
+use crate::{
+    file_descriptor::FileDescriptor,
+    os::{self, Backend, OpenFlags, Request, Response},
+    DataDirection,
+};
+
+use super::{AccessFlags, AuxiliaryInfo, DriverStatus, SgIoHeader, SgIoV4};
+
+const SG_IO: u32 = 0x2285;
+
+/// The SCSI generic driver, through the SG_IO ioctl of a block or sg device
+#[derive(Debug)]
+pub struct SgBackend {
+    file_descriptor: FileDescriptor,
+    // the sg_io_v4 header for every command, not only the bidirectional ones
+    sg_io_v4: bool,
+    // an sg character device, which queues the commands written to it
+    #[cfg(feature = "io-uring")]
+    sg: bool,
+    // one batch at a time, the reads of a batch take whichever reply of the fd is ready
+    #[cfg(feature = "io-uring")]
+    batch: Mutex<()>,
+}
+
+impl SgBackend {
+    pub fn open(path: &Path, flags: OpenFlags) -> crate::Result<Self> {
+        let file_descriptor = FileDescriptor::open(path, flags.options())?;
+
+        let bsg = file_descriptor.is_bsg()?;
+        let sg = file_descriptor.is_sg()?;
+        if !bsg && !sg && !file_descriptor.is_block()? {
+            return Err(crate::Error::NotBlockDevice(path.to_owned()));
+        }
+
+        if !Self::is_scsi_device(&file_descriptor)? {
+            return Err(crate::Error::NotScsiDevice(path.to_owned()));
+        }
+
+        Ok(Self {
+            file_descriptor,
+            sg_io_v4: bsg || flags.sg_io_v4,
+            #[cfg(feature = "io-uring")]
+            sg,
+            #[cfg(feature = "io-uring")]
+            batch: Mutex::new(()),
+        })
+    }
+
+    // sg character devices are accepted too, only the SG_IO version is checked
+    pub fn from_file_descriptor(
+        file_descriptor: FileDescriptor,
+        path: &Path,
+    ) -> crate::Result<Self> {
+        match Self::is_scsi_device(&file_descriptor) {
+            Ok(true) => Ok(Self {
+                sg_io_v4: file_descriptor.is_bsg()?,
+                #[cfg(feature = "io-uring")]
+                sg: file_descriptor.is_sg()?,
+                #[cfg(feature = "io-uring")]
+                batch: Mutex::new(()),
+                file_descriptor,
+            }),
+            Ok(false) => Err(crate::Error::NotScsiDevice(path.to_owned())),
+            Err(crate::Error::IO(error)) if error.raw_os_error() == Some(libc::ENOTTY) => {
+                Err(crate::Error::NotScsiDevice(path.to_owned()))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    fn issue_v4(&self, mut request: Request) -> Response {
+        let mut sg_header = sg_io_v4_header(&mut request);
+
+        let ioctl_result =
+            unsafe { libc::ioctl(self.file_descriptor.raw(), SG_IO as _, &mut sg_header) };
+        let os_error = os::os_error(ioctl_result);
+
+        sg_io_v4_response(&request, &sg_header, ioctl_result, os_error)
+    }
+
+    fn is_scsi_device(file: &FileDescriptor) -> crate::Result<bool> {
+        const SG_GET_VERSION_NUM: u32 = 0x2282;
+
+        let mut version = 0_i32;
+        let result = unsafe {
+            libc::ioctl(
+                file.raw(),
+                SG_GET_VERSION_NUM.try_into().unwrap(),
+                &mut version,
+            )
+        };
+
+        if result != 0 {
+            Err(io::Error::last_os_error())?;
+        }
+
+        if version < 30000 {
+            Ok(false)
+        } else {
+            Ok(true)
+        }
+    }
+}
+
+impl Backend for SgBackend {
+    fn issue(&self, mut request: Request) -> Response {
+        if uses_sg_io_v4(self.sg_io_v4, request.direction) {
+            return self.issue_v4(request);
+        }
+
+        let mut sg_header = sg_io_header(&mut request, 0);
+        let ioctl_result = unsafe {
+            libc::ioctl(
+                self.file_descriptor.raw(),
+                SG_IO.try_into().unwrap(),
+                &mut sg_header,
+            )
+        };
+        let os_error = os::os_error(ioctl_result);
+
+        sg_io_header_response(&sg_header, ioctl_result, os_error)
+    }
+
+    // only sg devices queue the commands written to them, and only with the v3 header
+    #[cfg(feature = "io-uring")]
+    fn issue_batch(&self, mut requests: Vec<Request>) -> Vec<Response> {
+        let queued = self.sg
+            && requests.len() > 1
+            && requests
+                .iter()
+                .all(|request| !uses_sg_io_v4(self.sg_io_v4, request.direction));
+
+        if queued {
+            // a batch that panicked left nothing behind that the next one depends on
+            let _batch = self.batch.lock().unwrap_or_else(|error| error.into_inner());
+            if let Ok(responses) =
+                super::uring::issue_batch(self.file_descriptor.raw(), &mut requests)
+            {
+                return responses;
+            }
+        }
+
+        // without io_uring, e.g. an old kernel or a seccomp filter, one by one
+        requests
+            .into_iter()
+            .map(|request| self.issue(request))
+            .collect()
+    }
+}
+
+pub(super) fn sg_io_header<'a>(
+    request: &'a mut Request,
+    pack_id: i32,
+) -> SgIoHeader<'a, u8, u8, u8> {
+    let timeout = request.timeout_millis();
+    SgIoHeader {
+        interface_id: b'S' as i32,
+        data_direction: request.direction.into(),
+        command_length: request.command.len() as u8,
+        max_sense_buffer_length: request.sense_buffer_length() as u8,
+        iovec_count: 0,
+        data_length: request.data_length,
+        data: unsafe { request.data.as_mut() },
+        command: request.command.first(),
+        sense_buffer: request.sense_buffer.first_mut(),
+        timeout,
+        flags: AccessFlags::DEFAULT,
+        pack_id,
+        user_pointer: 0,
+        status: 0,
+        masked_status: 0,
+        message_status: 0,
+        sense_buffer_written: 0,
+        host_status: 0,
+        driver_status: DriverStatus::OK,
+        residual_count: 0,
+        duration: 0,
+        info: AuxiliaryInfo::OK,
+    }
+}
+
+pub(super) fn sg_io_header_response(
+    sg_header: &SgIoHeader<u8, u8, u8>,
+    ioctl_result: i32,
+    os_error: i32,
+) -> Response {
+    let residual = usize::try_from(sg_header.residual_count).unwrap_or(0);
+
+    Response {
+        ioctl_result,
+        os_error,
+        transfered_data_length: (sg_header.data_length as usize).saturating_sub(residual),
+        residual,
+        sense_length: sg_header.sense_buffer_written as usize,
+        status: sg_header.status,
+        duration: Some(Duration::from_millis(sg_header.duration.into())),
+        host_status: sg_header.host_status,
+        driver_status: DriverStatus::from_bits_retain(sg_header.driver_status.bits()),
+    }
+}
+
+// the v3 header has a single data buffer, separate buffers need the v4 header, which only bsg
+// devices of kernels with bidirectional support accept
+fn uses_sg_io_v4(sg_io_v4: bool, direction: DataDirection) -> bool {
+    sg_io_v4 || direction == DataDirection::Bidirectional
+}
+
+fn sg_io_v4_header(request: &mut Request) -> SgIoV4 {
+    let mut sg_header = SgIoV4 {
+        guard: b'Q' as i32,
+        request_length: request.command.len() as u32,
+        request: request.command.as_ptr() as u64,
+        max_response_length: request.sense_buffer_length() as u32,
+        response: request.sense_buffer.as_mut_ptr() as u64,
+        timeout: request.timeout_millis(),
+        ..Default::default()
+    };
+
+    // v4 has a buffer per direction instead of a direction
+    match request.direction {
+        DataDirection::None => {}
+        DataDirection::ToDevice => {
+            sg_header.data_out_length = request.data_length;
+            sg_header.data_out = request.data as u64;
+        }
+        DataDirection::FromDevice | DataDirection::ToFromDevice | DataDirection::Unknown => {
+            sg_header.data_in_length = request.data_length;
+            sg_header.data_in = request.data as u64;
+        }
+        DataDirection::Bidirectional => {
+            sg_header.data_out_length = request.data_length;
+            sg_header.data_out = request.data as u64;
+            sg_header.data_in_length = request.data_in_length;
+            sg_header.data_in = request.data_in as u64;
+        }
+    }
+
+    sg_header
+}
+
+fn sg_io_v4_response(
+    request: &Request,
+    sg_header: &SgIoV4,
+    ioctl_result: i32,
+    os_error: i32,
+) -> Response {
+    // the data in is what the command returns, the data out only matters without one
+    let (length, residual) = match sg_header.data_in_length {
+        0 => (sg_header.data_out_length, sg_header.data_out_residual),
+        _ => (sg_header.data_in_length, sg_header.data_in_residual),
+    };
+    let residual = usize::try_from(residual).unwrap_or(0);
+
+    Response {
+        ioctl_result,
+        os_error,
+        transfered_data_length: (length as usize).saturating_sub(residual),
+        residual,
+        sense_length: usize::min(
+            sg_header.response_length as usize,
+            request.sense_buffer_length(),
+        ),
+        status: sg_header.device_status as u8,
+        duration: Some(Duration::from_millis(sg_header.duration.into())),
+        host_status: sg_header.transport_status as u16,
+        driver_status: DriverStatus::from_bits_retain(sg_header.driver_status as u16),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::sense::MAX_SENSE_BUFFER_LENGTH;
+    use std::{ptr, time::Duration};
+
+    #[test]
+    fn uses_sg_io_v4_test() {
+        assert!(
+            uses_sg_io_v4(false, DataDirection::Bidirectional),
+            "bidirectional forces v4"
+        );
+        assert!(
+            !uses_sg_io_v4(false, DataDirection::FromDevice),
+            "v3 by default"
+        );
+        assert!(uses_sg_io_v4(true, DataDirection::FromDevice), "v4 toggle");
+        assert!(uses_sg_io_v4(true, DataDirection::None), "v4 toggle");
+    }
+
+    #[test]
+    fn sg_io_v4_test() {
+        let command = [0x88; 16];
+        let mut data = [0u8; 4096];
+        let mut sense_buffer = [0; MAX_SENSE_BUFFER_LENGTH];
+        let mut request = Request {
+            command: &command,
+            direction: DataDirection::FromDevice,
+            data: data.as_mut_ptr(),
+            data_length: data.len() as u32,
+            data_in: ptr::null_mut(),
+            data_in_length: 0,
+            sense_buffer: &mut sense_buffer,
+            autosense: true,
+            timeout: Duration::from_secs(1),
+        };
+
+        let mut sg_header = sg_io_v4_header(&mut request);
+        assert_eq!(sg_header.request_length, 16, "command length");
+        assert_eq!(sg_header.data_in_length, 4096, "data in length");
+        assert_eq!(sg_header.data_out_length, 0, "no data out");
+        assert_eq!(sg_header.timeout, 1000, "timeout");
+
+        // a short read of 3 blocks with a CHECK CONDITION
+        sg_header.data_in_residual = 512;
+        sg_header.response_length = 18;
+        sg_header.device_status = 0x02;
+        let response = sg_io_v4_response(&request, &sg_header, 0, 0);
+        assert_eq!(response.transfered_data_length, 3584, "transferred");
+        assert_eq!(response.residual, 512, "residual");
+        assert_eq!(response.sense_length, 18, "sense length");
+        assert_eq!(response.status, 0x02, "status");
+
+        request.direction = DataDirection::ToDevice;
+        let mut sg_header = sg_io_v4_header(&mut request);
+        assert_eq!(sg_header.data_out_length, 4096, "data out length");
+        assert_eq!(sg_header.data_in_length, 0, "no data in");
+
+        sg_header.data_out_residual = 4096;
+        let response = sg_io_v4_response(&request, &sg_header, 0, 0);
+        assert_eq!(response.transfered_data_length, 0, "nothing written");
+        assert_eq!(response.residual, 4096, "write residual");
+    }
+}
@@ -0,0 +1,111 @@
+use nix::libc::{c_int, c_uint};
+
+/// struct sg_io_v4 of linux/bsg.h, pointers are passed as u64 on every architecture
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct SgIoV4 {
+    /// \[i\] 'Q' to differentiate from v3
+    pub guard: c_int,
+    /// \[i\] 0 -> SCSI
+    pub protocol: c_uint,
+    /// \[i\] 0 -> SCSI command, 1 -> SCSI task management function
+    pub subprotocol: c_uint,
+    /// \[i\] length of the command in bytes
+    pub request_length: c_uint,
+    /// \[i\], \[*i\] points to the command
+    pub request: u64,
+    /// \[i\] task tag, only if flagged
+    pub request_tag: u64,
+    /// \[i\] task attribute
+    pub request_attribute: c_uint,
+    /// \[i\] task priority
+    pub request_priority: c_uint,
+    /// \[i\] spare, for padding
+    pub request_extra: c_uint,
+    /// \[i\] max length to write to response
+    pub max_response_length: c_uint,
+    /// \[i\], \[*o\] points to the sense buffer
+    pub response: u64,
+    /// \[i\] 0 -> flat data out transfer, otherwise the number of iovecs
+    pub data_out_iovec_count: c_uint,
+    /// \[i\] bytes to be transferred to the device
+    pub data_out_length: c_uint,
+    /// \[i\] 0 -> flat data in transfer, otherwise the number of iovecs
+    pub data_in_iovec_count: c_uint,
+    /// \[i\] bytes to be transferred from the device
+    pub data_in_length: c_uint,
+    /// \[i\], \[*i\] points to the data out buffer
+    pub data_out: u64,
+    /// \[i\], \[*o\] points to the data in buffer
+    pub data_in: u64,
+    /// \[i\] unit: millisec
+    pub timeout: c_uint,
+    /// \[i\] bit mask
+    pub flags: c_uint,
+    /// \[i->o\] unused internally
+    pub user_pointer: u64,
+    /// \[i\]
+    pub spare_in: c_uint,
+    /// \[o\] 0 -> ok
+    pub driver_status: c_uint,
+    /// \[o\] 0 -> ok
+    pub transport_status: c_uint,
+    /// \[o\] scsi status
+    pub device_status: c_uint,
+    /// \[o\] status auxiliary information
+    pub retry_delay: c_uint,
+    /// \[o\] additional information
+    pub info: c_uint,
+    /// \[o\] time taken by cmd (unit: millisec)
+    pub duration: c_uint,
+    /// \[o\] byte count actually written to response
+    pub response_length: c_uint,
+    /// \[o\] data_in_length - actual data in transferred
+    pub data_in_residual: c_int,
+    /// \[o\] data_out_length - actual data out transferred
+    pub data_out_residual: c_int,
+    /// \[o\] transport generated task tag
+    pub generated_tag: u64,
+    /// \[o\]
+    pub spare_out: c_uint,
+    pub padding: c_uint,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{mem::MaybeUninit, ptr::addr_of};
+
+    #[test]
+    fn layout_test() {
+        const UNINIT: MaybeUninit<SgIoV4> = MaybeUninit::uninit();
+        let ptr = UNINIT.as_ptr();
+
+        assert_eq!(
+            std::mem::size_of::<SgIoV4>(),
+            160,
+            concat!("Size of: ", stringify!(SgIoV4))
+        );
+
+        let offsets = unsafe {
+            [
+                addr_of!((*ptr).request) as usize,
+                addr_of!((*ptr).response) as usize,
+                addr_of!((*ptr).data_out) as usize,
+                addr_of!((*ptr).data_in) as usize,
+                addr_of!((*ptr).timeout) as usize,
+                addr_of!((*ptr).user_pointer) as usize,
+                addr_of!((*ptr).device_status) as usize,
+                addr_of!((*ptr).data_in_residual) as usize,
+                addr_of!((*ptr).generated_tag) as usize,
+            ]
+            .map(|offset| offset - ptr as usize)
+        };
+
+        assert_eq!(
+            offsets,
+            [16, 48, 72, 80, 88, 96, 116, 136, 144],
+            concat!("Offsets of: ", stringify!(SgIoV4))
+        );
+    }
+}
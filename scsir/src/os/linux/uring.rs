@@ -0,0 +1,373 @@
+//! Keeps several commands in flight on an sg device.
+//!
+//! An sg device queues every v3 header written to it and hands the finished ones back through
+//! read(), in the order they finish. Both go through one io_uring, so a batch takes a few
+//! system calls instead of a blocking SG_IO per command. The replies are matched back to their
+//! request by the pack id.
+
+use std::{
+    io,
+    mem::{size_of, MaybeUninit},
+    os::unix::io::RawFd,
+    ptr::addr_of,
+    sync::atomic::{AtomicI32, Ordering},
+};
+
+use io_uring::{opcode, types, IoUring};
+use nix::libc;
+
+use crate::{
+    os::{Request, Response},
+    DataDirection,
+};
+
+use super::{
+    sg_backend::{sg_io_header, sg_io_header_response},
+    AccessFlags, AuxiliaryInfo, DriverStatus, SgIoHeader,
+};
+
+// the default SG_MAX_QUEUE of the sg driver, writes beyond it fail with EDOM
+const SG_MAX_QUEUE: usize = 16;
+
+// shared by every batch, so a reply an earlier batch left behind, e.g. after a failed read, has
+// a pack id of no later batch. It is still taken from the device by the read that gets it
+static NEXT_PACK_ID: AtomicI32 = AtomicI32::new(1);
+
+// the lowest bit of the user data tells the reads from the writes, the rest is the index
+const READ: u64 = 1;
+
+type Header<'a> = SgIoHeader<'a, u8, u8, u8>;
+
+/// Issues `requests` on the sg device `fd`, up to [`SG_MAX_QUEUE`] at a time, and returns one
+/// response per request in the same order.
+///
+/// A read takes whichever reply is ready, so the caller must not run two batches on one fd at
+/// the same time, one would take the replies of the other. SgBackend holds a lock for it.
+///
+/// Fails only if the io_uring can't be set up, before anything has been sent.
+pub(super) fn issue_batch(fd: RawFd, requests: &mut [Request]) -> io::Result<Vec<Response>> {
+    // a write and a read per request
+    let mut ring = IoUring::new(2 * SG_MAX_QUEUE as u32)?;
+
+    let mut responses = Vec::with_capacity(requests.len());
+    for chunk in requests.chunks_mut(SG_MAX_QUEUE) {
+        responses.extend(issue_chunk(&mut ring, fd, chunk));
+    }
+
+    Ok(responses)
+}
+
+fn issue_chunk(ring: &mut IoUring, fd: RawFd, requests: &mut [Request]) -> Vec<Response> {
+    let first_pack_id = NEXT_PACK_ID.fetch_add(requests.len() as i32, Ordering::Relaxed);
+    let mut headers: Vec<Header> = requests
+        .iter_mut()
+        .enumerate()
+        .map(|(index, request)| sg_io_header(request, first_pack_id.wrapping_add(index as i32)))
+        .collect();
+    let mut replies: Vec<MaybeUninit<Header>> = (0..headers.len()).map(|_| reply()).collect();
+    let mut responses: Vec<Option<Response>> = (0..headers.len()).map(|_| None).collect();
+
+    let mut in_flight = 0;
+    let mut read_error = libc::EIO;
+    for (index, header) in headers.iter().enumerate() {
+        let write = opcode::Write::new(
+            types::Fd(fd),
+            (header as *const Header).cast(),
+            size_of::<Header>() as u32,
+        )
+        .build()
+        .user_data((index as u64) << 1);
+        push(ring, &write);
+        in_flight += 1;
+    }
+
+    while in_flight != 0 {
+        submit(ring);
+
+        let completions: Vec<_> = ring
+            .completion()
+            .map(|entry| (entry.user_data(), entry.result()))
+            .collect();
+        for (user_data, result) in completions {
+            in_flight -= 1;
+            let index = (user_data >> 1) as usize;
+
+            if user_data & READ == 0 {
+                // a failed write only fails its own request, there is no reply to wait for
+                if result < 0 {
+                    responses[index] = Some(error_response(&headers[index], -result));
+                } else {
+                    push(ring, &read(fd, &mut replies[index], index));
+                    in_flight += 1;
+                }
+                continue;
+            }
+
+            let reply = replies[index].as_ptr();
+            // reply() initialized the whole header, so this is defined even if the read failed
+            let pack_id = unsafe { addr_of!((*reply).pack_id).read() };
+            match read_completion(result, pack_id, first_pack_id, &responses) {
+                ReadCompletion::Reply(request) => {
+                    unsafe { take_reply(&mut headers[request], reply) };
+                    responses[request] = Some(sg_io_header_response(&headers[request], 0, 0));
+                }
+                ReadCompletion::Failed(os_error) => read_error = os_error,
+                ReadCompletion::Again | ReadCompletion::Stray => {
+                    push(ring, &read(fd, &mut replies[index], index));
+                    in_flight += 1;
+                }
+            }
+        }
+    }
+
+    headers
+        .iter()
+        .zip(responses)
+        .map(|(header, response)| response.unwrap_or_else(|| error_response(header, read_error)))
+        .collect()
+}
+
+// what the completion of a read means for the chunk
+#[derive(Debug, PartialEq, Eq)]
+enum ReadCompletion {
+    // the reply of the request at this index of the chunk
+    Reply(usize),
+    // the reply the read failed on is gone, the request it was for ends with this errno once
+    // the other reads got theirs
+    Failed(i32),
+    // interrupted before a reply was taken, the read is queued again
+    Again,
+    // a reply of no request of the chunk that is still waiting, e.g. one an earlier batch left
+    // behind. It is dropped, and the read queued again for a reply of the chunk
+    Stray,
+}
+
+// `result` of a read whose header came back with `pack_id`, `responses` of the chunk that
+// starts at `first_pack_id`
+fn read_completion<T>(
+    result: i32,
+    pack_id: i32,
+    first_pack_id: i32,
+    responses: &[Option<T>],
+) -> ReadCompletion {
+    match result {
+        e if e == -libc::EINTR || e == -libc::EAGAIN => ReadCompletion::Again,
+        e if e < 0 => ReadCompletion::Failed(-e),
+        _ => {
+            let request = pack_id.wrapping_sub(first_pack_id) as u32 as usize;
+            match responses.get(request) {
+                Some(None) => ReadCompletion::Reply(request),
+                _ => ReadCompletion::Stray,
+            }
+        }
+    }
+}
+
+// sg_read() checks the interface id and the direction of the header it reads into
+fn reply() -> MaybeUninit<Header<'static>> {
+    MaybeUninit::new(SgIoHeader {
+        interface_id: b'S' as i32,
+        data_direction: DataDirection::None.into(),
+        command_length: 0,
+        max_sense_buffer_length: 0,
+        iovec_count: 0,
+        data_length: 0,
+        data: None,
+        command: None,
+        sense_buffer: None,
+        timeout: 0,
+        flags: AccessFlags::DEFAULT,
+        pack_id: -1,
+        user_pointer: 0,
+        status: 0,
+        masked_status: 0,
+        message_status: 0,
+        sense_buffer_written: 0,
+        host_status: 0,
+        driver_status: DriverStatus::OK,
+        residual_count: 0,
+        duration: 0,
+        info: AuxiliaryInfo::OK,
+    })
+}
+
+fn read(fd: RawFd, reply: &mut MaybeUninit<Header>, index: usize) -> io_uring::squeue::Entry {
+    opcode::Read::new(
+        types::Fd(fd),
+        reply.as_mut_ptr().cast(),
+        size_of::<Header>() as u32,
+    )
+    .build()
+    .user_data((index as u64) << 1 | READ)
+}
+
+// copies the output fields only, the pointers the device echoes back are the ones of `header`
+unsafe fn take_reply(header: &mut Header, reply: *const Header) {
+    header.status = addr_of!((*reply).status).read();
+    header.masked_status = addr_of!((*reply).masked_status).read();
+    header.message_status = addr_of!((*reply).message_status).read();
+    header.sense_buffer_written = addr_of!((*reply).sense_buffer_written).read();
+    header.host_status = addr_of!((*reply).host_status).read();
+    header.driver_status = addr_of!((*reply).driver_status).read();
+    header.residual_count = addr_of!((*reply).residual_count).read();
+    header.duration = addr_of!((*reply).duration).read();
+    header.info = addr_of!((*reply).info).read();
+}
+
+fn error_response(header: &Header, os_error: i32) -> Response {
+    sg_io_header_response(header, -1, os_error)
+}
+
+fn push(ring: &mut IoUring, entry: &io_uring::squeue::Entry) {
+    // at most a write and a read per request of the chunk are queued at once
+    unsafe { ring.submission().push(entry) }
+        .expect("the submission queue has room for every request of the chunk");
+}
+
+fn submit(ring: &IoUring) {
+    loop {
+        match ring.submit_and_wait(1) {
+            Ok(_) => return,
+            Err(e)
+                if matches!(
+                    e.raw_os_error(),
+                    Some(libc::EINTR | libc::EAGAIN | libc::EBUSY)
+                ) => {}
+            // the ring and the entries were set up by this module, anything else is a bug
+            Err(e) => panic!("io_uring_enter failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{os::unix::io::AsRawFd, os::unix::net::UnixDatagram, thread, time::Duration};
+
+    use super::*;
+
+    // stands in for the sg device: takes the headers and replies in the reverse order
+    fn device(socket: UnixDatagram, count: usize) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            let mut headers = vec![];
+            for _ in 0..count {
+                let mut header = reply();
+                let buffer = unsafe {
+                    std::slice::from_raw_parts_mut(
+                        header.as_mut_ptr().cast::<u8>(),
+                        size_of::<Header>(),
+                    )
+                };
+                assert_eq!(socket.recv(buffer).unwrap(), size_of::<Header>(), "header");
+                headers.push(header);
+            }
+
+            for header in headers.iter_mut().rev() {
+                let header = header.as_mut_ptr();
+                unsafe {
+                    let pack_id = (*header).pack_id;
+                    if let Some(data) = (*header).data.as_deref_mut() {
+                        std::ptr::write_bytes(data, pack_id as u8, (*header).data_length as usize);
+                    }
+                    (*header).residual_count = 1;
+                    (*header).duration = 2;
+                    let buffer =
+                        std::slice::from_raw_parts(header.cast::<u8>(), size_of::<Header>());
+                    socket.send(buffer).unwrap();
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn read_completion_test() {
+        let first_pack_id = i32::MAX - 1;
+        // the pack ids of the chunk wrap around
+        let responses = [None, Some(()), None];
+
+        assert_eq!(
+            read_completion(
+                size_of::<Header>() as i32,
+                i32::MIN,
+                first_pack_id,
+                &responses
+            ),
+            ReadCompletion::Reply(2),
+            "pack id to index"
+        );
+        assert_eq!(
+            read_completion(0, first_pack_id + 1, first_pack_id, &responses),
+            ReadCompletion::Stray,
+            "second reply for a pack id"
+        );
+        assert_eq!(
+            read_completion(0, first_pack_id - 1, first_pack_id, &responses),
+            ReadCompletion::Stray,
+            "reply of an earlier batch"
+        );
+        assert_eq!(
+            read_completion(-libc::EINTR, -1, first_pack_id, &responses),
+            ReadCompletion::Again,
+            "interrupted"
+        );
+        assert_eq!(
+            read_completion(-libc::EAGAIN, -1, first_pack_id, &responses),
+            ReadCompletion::Again,
+            "no reply yet"
+        );
+        assert_eq!(
+            read_completion(-libc::EIO, first_pack_id, first_pack_id, &responses),
+            ReadCompletion::Failed(libc::EIO),
+            "failed read, whatever the header says"
+        );
+    }
+
+    #[test]
+    fn issue_batch_test() {
+        if IoUring::new(1).is_err() {
+            // io_uring is unavailable or filtered out
+            return;
+        }
+
+        let (batch, sg) = UnixDatagram::pair().unwrap();
+        let device = device(sg, 3);
+
+        let commands = [[0x28_u8; 10], [0x88; 10], [0xA8; 10]];
+        let mut data = [[0_u8; 4]; 3];
+        let mut sense = [[0_u8; 18]; 3];
+        let mut requests: Vec<Request> = commands
+            .iter()
+            .zip(data.iter_mut())
+            .zip(sense.iter_mut())
+            .map(|((command, data), sense)| Request {
+                command,
+                direction: DataDirection::FromDevice,
+                data: data.as_mut_ptr(),
+                data_length: data.len() as u32,
+                data_in: std::ptr::null_mut(),
+                data_in_length: 0,
+                sense_buffer: sense,
+                autosense: true,
+                timeout: Duration::from_secs(1),
+            })
+            .collect();
+
+        let responses = issue_batch(batch.as_raw_fd(), &mut requests).unwrap();
+        drop(requests);
+        device.join().unwrap();
+
+        assert_eq!(responses.len(), 3, "one response per request");
+        for response in &responses {
+            assert_eq!(response.ioctl_result, 0, "success");
+            assert_eq!(response.residual, 1, "residual");
+            assert_eq!(response.transfered_data_length, 3, "transfered data length");
+            assert_eq!(
+                response.duration,
+                Some(Duration::from_millis(2)),
+                "duration"
+            );
+        }
+        // every request got the data the device wrote for its own pack id
+        assert_eq!(data[1][0], data[0][0].wrapping_add(1), "second request");
+        assert_eq!(data[2][0], data[0][0].wrapping_add(2), "third request");
+    }
+}
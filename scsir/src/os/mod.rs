@@ -1,5 +1,307 @@
+use std::{fmt::Debug, fs::OpenOptions, io, path::Path, time::Duration};
+
+use crate::DataDirection;
+
+#[cfg(target_os = "freebsd")]
+pub mod freebsd;
+
 #[cfg(target_os = "linux")]
 pub mod linux;
 
 #[cfg(target_os = "windows")]
 pub mod windows;
+
+/// Hands a single CDB to the device, one implementation per platform.
+///
+/// The [`crate::Command`] plumbing, sense parsing and retries live in [`crate::Scsi`], so a
+/// backend only translates a request into the native pass-through structure and back.
+pub(crate) trait Backend: Debug + Send + Sync {
+    fn issue(&self, request: Request) -> Response;
+
+    /// Issues every request and returns one response per request, in the same order.
+    ///
+    /// A backend that can keep several commands in flight overrides this, the default issues
+    /// them one after another. A failed request only fails its own response, so every
+    /// response carries its own [`Response::os_error`].
+    fn issue_batch(&self, requests: Vec<Request>) -> Vec<Response> {
+        requests
+            .into_iter()
+            .map(|request| self.issue(request))
+            .collect()
+    }
+}
+
+pub(crate) struct Request<'a> {
+    pub command: &'a [u8],
+    pub direction: DataDirection,
+    /// null if data_length is 0, otherwise valid for data_length bytes
+    pub data: *mut u8,
+    pub data_length: u32,
+    /// only used by DataDirection::Bidirectional, in which case data is the data out buffer
+    pub data_in: *mut u8,
+    pub data_in_length: u32,
+    /// the sense buffer length set on the Scsi handle, at most MAX_SENSE_BUFFER_LENGTH bytes
+    pub sense_buffer: &'a mut [u8],
+    /// false if the driver must not fetch sense data after a CHECK CONDITION
+    pub autosense: bool,
+    pub timeout: Duration,
+}
+
+impl Request<'_> {
+    /// The sense buffer length to hand to the driver, 0 turns autosense off
+    pub fn sense_buffer_length(&self) -> usize {
+        match self.autosense {
+            true => self.sense_buffer.len(),
+            false => 0,
+        }
+    }
+
+    /// The timeout in the milliseconds of SG_IO and CAM, clamped to 1 ms..=u32::MAX ms.
+    ///
+    /// A timeout of 0 would pick the default timeout of the driver instead of an immediate one.
+    pub fn timeout_millis(&self) -> u32 {
+        timeout_millis(self.timeout)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Response {
+    /// 0 on success, otherwise the error is in errno or GetLastError
+    pub ioctl_result: i32,
+    /// errno or GetLastError of a failed ioctl, 0 on success
+    pub os_error: i32,
+    pub transfered_data_length: usize,
+    pub residual: usize,
+    pub sense_length: usize,
+    pub status: u8,
+    /// how long the command took as reported by the driver, None or zero if it doesn't report
+    /// it precisely enough
+    pub duration: Option<Duration>,
+    #[cfg(target_os = "linux")]
+    pub host_status: u16,
+    #[cfg(target_os = "linux")]
+    pub driver_status: linux::DriverStatus,
+}
+
+impl Response {
+    // a request that never reached the device
+    pub(crate) fn failed(os_error: i32) -> Self {
+        Self {
+            ioctl_result: -1,
+            os_error,
+            transfered_data_length: 0,
+            residual: 0,
+            sense_length: 0,
+            status: 0,
+            duration: None,
+            #[cfg(target_os = "linux")]
+            host_status: 0,
+            #[cfg(target_os = "linux")]
+            driver_status: linux::DriverStatus::OK,
+        }
+    }
+}
+
+fn timeout_millis(timeout: Duration) -> u32 {
+    u32::try_from(timeout.as_millis())
+        .unwrap_or(u32::MAX)
+        .max(1)
+}
+
+// read right after the ioctl, before anything else can overwrite it
+pub(crate) fn os_error(ioctl_result: i32) -> i32 {
+    match ioctl_result {
+        0 => 0,
+        _ => io::Error::last_os_error().raw_os_error().unwrap_or(0),
+    }
+}
+
+/// How a backend opens the device, see [`crate::ScsiBuilder`]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct OpenFlags {
+    pub read_only: bool,
+    pub non_blocking: bool,
+    pub direct: bool,
+    /// issue every command with the sg_io_v4 header, bsg nodes always use it
+    #[cfg(target_os = "linux")]
+    pub sg_io_v4: bool,
+}
+
+impl OpenFlags {
+    pub fn options(&self) -> OpenOptions {
+        let mut options = OpenOptions::new();
+        options.read(true).write(!self.read_only);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.custom_flags(self.custom_flags());
+        }
+
+        #[cfg(target_os = "windows")]
+        if self.direct {
+            use std::os::windows::fs::OpenOptionsExt;
+            // FILE_FLAG_NO_BUFFERING, there is no non-blocking open on Windows
+            options.custom_flags(0x2000_0000);
+        }
+
+        options
+    }
+
+    // the flags besides the access mode
+    #[cfg(unix)]
+    pub fn custom_flags(&self) -> i32 {
+        use nix::libc;
+
+        let mut flags = 0;
+        if self.non_blocking {
+            flags |= libc::O_NONBLOCK;
+        }
+        if self.direct {
+            flags |= libc::O_DIRECT;
+        }
+
+        flags
+    }
+}
+
+// errno or GetLastError of a command that sends data through a read-only handle
+#[cfg(unix)]
+pub(crate) const READ_ONLY_ERROR: i32 = nix::libc::EROFS;
+
+#[cfg(target_os = "windows")]
+pub(crate) const READ_ONLY_ERROR: i32 = 19; // ERROR_WRITE_PROTECT
+
+// errno or GetLastError of a request the backend returned no response for
+#[cfg(unix)]
+pub(crate) const NO_RESPONSE_ERROR: i32 = nix::libc::EIO;
+
+#[cfg(target_os = "windows")]
+pub(crate) const NO_RESPONSE_ERROR: i32 = 1117; // ERROR_IO_DEVICE
+
+// errno or GetLastError of a failed pass-through ioctl that gets an error variant of its own
+#[cfg(unix)]
+pub(crate) mod errors {
+    use nix::libc;
+
+    pub const PERMISSION_DENIED: i32 = libc::EACCES;
+    pub const NO_DEVICE: i32 = libc::ENODEV;
+    pub const NOT_PASS_THROUGH: i32 = libc::ENOTTY;
+    pub const INVALID_REQUEST: i32 = libc::EINVAL;
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) mod errors {
+    pub const PERMISSION_DENIED: i32 = 5; // ERROR_ACCESS_DENIED
+    pub const NO_DEVICE: i32 = 1167; // ERROR_DEVICE_NOT_CONNECTED
+    pub const NOT_PASS_THROUGH: i32 = 1; // ERROR_INVALID_FUNCTION
+    pub const INVALID_REQUEST: i32 = 87; // ERROR_INVALID_PARAMETER
+}
+
+/// Fails every request that sends data to the device, and every command without data that
+/// isn't known to leave the medium as it is, without handing it to the driver.
+///
+/// The driver only checks the access mode for some commands, a read-only handle would
+/// otherwise still be able to WRITE through SG_IO. Commands without data can be just as
+/// destructive, e.g. SANITIZE, FORMAT UNIT without a parameter list or RESET WRITE POINTER,
+/// so those are only let through if they are in [`NON_DATA_ALLOWLIST`].
+#[derive(Debug)]
+pub(crate) struct ReadOnlyBackend(pub Box<dyn Backend>);
+
+// the commands without data that neither change the medium nor stop the device
+const NON_DATA_ALLOWLIST: [u8; 9] = [
+    0x00, // TEST UNIT READY
+    0x1E, // PREVENT ALLOW MEDIUM REMOVAL
+    0x2F, // VERIFY (10)
+    0x34, // PRE-FETCH (10)
+    0x35, // SYNCHRONIZE CACHE (10)
+    0x8F, // VERIFY (16)
+    0x90, // PRE-FETCH (16)
+    0x91, // SYNCHRONIZE CACHE (16)
+    0xAF, // VERIFY (12)
+];
+
+impl ReadOnlyBackend {
+    fn rejects(request: &Request) -> bool {
+        match request.direction {
+            DataDirection::FromDevice => false,
+            DataDirection::None | DataDirection::Unknown => !request
+                .command
+                .first()
+                .is_some_and(|opcode| NON_DATA_ALLOWLIST.contains(opcode)),
+            DataDirection::ToDevice
+            | DataDirection::ToFromDevice
+            | DataDirection::Bidirectional => true,
+        }
+    }
+
+    fn rejected() -> Response {
+        Response::failed(READ_ONLY_ERROR)
+    }
+}
+
+impl Backend for ReadOnlyBackend {
+    fn issue(&self, request: Request) -> Response {
+        match Self::rejects(&request) {
+            true => Self::rejected(),
+            false => self.0.issue(request),
+        }
+    }
+
+    fn issue_batch(&self, requests: Vec<Request>) -> Vec<Response> {
+        if !requests.iter().any(Self::rejects) {
+            return self.0.issue_batch(requests);
+        }
+
+        requests
+            .into_iter()
+            .map(|request| self.issue(request))
+            .collect()
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn open(path: &Path, flags: OpenFlags) -> crate::Result<Box<dyn Backend>> {
+    Ok(Box::new(linux::SgBackend::open(path, flags)?))
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn open(path: &Path, flags: OpenFlags) -> crate::Result<Box<dyn Backend>> {
+    Ok(Box::new(windows::SptiBackend::open(path, flags)?))
+}
+
+#[cfg(target_os = "freebsd")]
+pub(crate) fn open(path: &Path, flags: OpenFlags) -> crate::Result<Box<dyn Backend>> {
+    Ok(Box::new(freebsd::CamBackend::open(path, flags)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeout_millis_test() {
+        assert_eq!(timeout_millis(Duration::from_secs(60)), 60_000, "seconds");
+        assert_eq!(
+            timeout_millis(Duration::from_micros(1_999)),
+            1,
+            "rounded down to milliseconds"
+        );
+        assert_eq!(
+            timeout_millis(Duration::from_micros(10)),
+            1,
+            "shorter than a millisecond"
+        );
+        assert_eq!(
+            timeout_millis(Duration::from_millis(u32::MAX as u64)),
+            u32::MAX,
+            "largest timeout"
+        );
+        assert_eq!(
+            timeout_millis(Duration::from_millis(u32::MAX as u64 + 1)),
+            u32::MAX,
+            "clamped"
+        );
+        assert_eq!(timeout_millis(Duration::MAX), u32::MAX, "clamped maximum");
+    }
+}
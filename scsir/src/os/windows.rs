@@ -1,6 +1,25 @@
-use windows::Win32::Storage::IscsiDisc::SCSI_PASS_THROUGH_DIRECT;
+use std::{
+    io,
+    mem::{size_of, size_of_val},
+    path::Path,
+};
 
-use crate::command::sense::MAX_SENSE_BUFFER_LENGTH;
+use windows::Win32::{
+    Foundation::{ERROR_NOT_SUPPORTED, HANDLE},
+    Storage::IscsiDisc::{
+        IOCTL_SCSI_GET_ADDRESS, IOCTL_SCSI_PASS_THROUGH_DIRECT, SCSI_ADDRESS,
+        SCSI_IOCTL_DATA_BIDIRECTIONAL, SCSI_IOCTL_DATA_IN, SCSI_IOCTL_DATA_OUT,
+        SCSI_IOCTL_DATA_UNSPECIFIED, SCSI_PASS_THROUGH_DIRECT,
+    },
+    System::IO::DeviceIoControl,
+};
+
+use crate::{
+    command::sense::MAX_SENSE_BUFFER_LENGTH,
+    file_descriptor::FileDescriptor,
+    os::{self, Backend, OpenFlags, Request, Response},
+    DataDirection,
+};
 
 #[repr(C)]
 pub struct ScsiPassThroughDirectWrapper {
@@ -13,3 +32,165 @@ impl Default for ScsiPassThroughDirectWrapper {
         unsafe { std::mem::zeroed() }
     }
 }
+
+/// The SCSI Pass-Through Interface, through IOCTL_SCSI_PASS_THROUGH_DIRECT.
+///
+/// Opening a disk for pass-through requires administrator privileges.
+#[derive(Debug)]
+pub struct SptiBackend {
+    file_descriptor: FileDescriptor,
+}
+
+impl SptiBackend {
+    pub fn open(path: &Path, flags: OpenFlags) -> crate::Result<Self> {
+        let file_descriptor = FileDescriptor::open(path, flags.options())?;
+
+        if !file_descriptor.is_block()? {
+            return Err(crate::Error::NotBlockDevice(path.to_owned()));
+        }
+
+        if !Self::is_scsi_device(&file_descriptor)? {
+            return Err(crate::Error::NotScsiDevice(path.to_owned()));
+        }
+
+        Ok(Self { file_descriptor })
+    }
+
+    fn is_scsi_device(file: &FileDescriptor) -> crate::Result<bool> {
+        let mut scsi_address = SCSI_ADDRESS::default();
+        let mut bytes_returned = 0;
+        let success = unsafe {
+            DeviceIoControl(
+                HANDLE(file.raw() as isize),
+                IOCTL_SCSI_GET_ADDRESS,
+                None,
+                0,
+                Some(&mut scsi_address as *mut _ as _),
+                size_of::<SCSI_ADDRESS>() as u32,
+                Some(&mut bytes_returned),
+                None,
+            )
+        };
+
+        if success == false {
+            Err(io::Error::last_os_error())?;
+        }
+
+        if bytes_returned == 0 {
+            Ok(false)
+        } else {
+            Ok(true)
+        }
+    }
+}
+
+impl Backend for SptiBackend {
+    fn issue(&self, request: Request) -> Response {
+        const MAX_COMMAND_LENGTH: usize = 16;
+
+        if request.command.len() > MAX_COMMAND_LENGTH {
+            panic!(
+                "Current command length is {}, max command length is {}",
+                request.command.len(),
+                MAX_COMMAND_LENGTH
+            );
+        }
+
+        // SCSI_PASS_THROUGH_DIRECT has a single data buffer
+        if request.direction == DataDirection::Bidirectional {
+            return Response {
+                ioctl_result: -1,
+                os_error: ERROR_NOT_SUPPORTED.0 as i32,
+                transfered_data_length: 0,
+                residual: request.data_in_length as usize,
+                sense_length: 0,
+                status: 0,
+                duration: None,
+            };
+        }
+
+        let mut header = ScsiPassThroughDirectWrapper::default();
+        let address_of_header = std::ptr::addr_of!(header) as usize;
+        let spt = &mut header.scsi_pass_through;
+        spt.Length = size_of_val(spt) as u16;
+        spt.CdbLength = request.command.len() as u8;
+        spt.SenseInfoLength = request.sense_buffer_length() as u8;
+        spt.DataIn = data_in(request.direction);
+        spt.DataTransferLength = request.data_length;
+
+        spt.TimeOutValue = match request
+            .timeout
+            .as_secs()
+            .clamp(u32::MIN as u64, u32::MAX as u64)
+        {
+            0 => 1,
+            n => n as u32,
+        };
+
+        spt.DataBuffer = request.data as _;
+        spt.Cdb[..request.command.len()].copy_from_slice(request.command);
+        spt.SenseInfoOffset =
+            (std::ptr::addr_of!(header.sense) as usize - address_of_header) as u32;
+
+        let mut bytes_returned = 0;
+
+        let success = unsafe {
+            DeviceIoControl(
+                HANDLE(self.file_descriptor.raw() as isize),
+                IOCTL_SCSI_PASS_THROUGH_DIRECT,
+                Some(&header as *const _ as _),
+                size_of_val(&header) as u32,
+                Some(&mut header as *mut _ as _),
+                size_of_val(&header) as u32,
+                Some(&mut bytes_returned),
+                None,
+            )
+        };
+
+        let sense_length = header.scsi_pass_through.SenseInfoLength as usize;
+        let sense_buffer_length = request.sense_buffer.len();
+        request
+            .sense_buffer
+            .copy_from_slice(&header.sense[..sense_buffer_length]);
+
+        let transfered_data_length = header.scsi_pass_through.DataTransferLength as usize;
+
+        let ioctl_result = match success.as_bool() {
+            true => 0,
+            false => -1,
+        };
+
+        Response {
+            ioctl_result,
+            os_error: os::os_error(ioctl_result),
+            transfered_data_length,
+            residual: (request.data_length as usize).saturating_sub(transfered_data_length),
+            sense_length,
+            status: header.scsi_pass_through.ScsiStatus,
+            duration: None,
+        }
+    }
+}
+
+fn data_in(direction: DataDirection) -> u8 {
+    (match direction {
+        DataDirection::None => SCSI_IOCTL_DATA_UNSPECIFIED,
+        DataDirection::ToDevice => SCSI_IOCTL_DATA_OUT,
+        DataDirection::FromDevice => SCSI_IOCTL_DATA_IN,
+        DataDirection::ToFromDevice => SCSI_IOCTL_DATA_BIDIRECTIONAL,
+        DataDirection::Unknown | DataDirection::Bidirectional => SCSI_IOCTL_DATA_UNSPECIFIED,
+    }) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_in_test() {
+        assert_eq!(data_in(DataDirection::ToDevice), 0, "data out");
+        assert_eq!(data_in(DataDirection::FromDevice), 1, "data in");
+        assert_eq!(data_in(DataDirection::None), 2, "unspecified");
+        assert_eq!(data_in(DataDirection::ToFromDevice), 3, "bidirectional");
+    }
+}
@@ -0,0 +1,76 @@
+//! The commonly used items, for `use scsir::prelude::*`.
+//!
+//! [`crate::Result`] is left out so it doesn't shadow the one of the standard prelude.
+
+pub use crate::{
+    command::{
+        sense::{SenseData, SenseKey},
+        CdbBytes, Control,
+    },
+    BufferPool, Command, DataDirection, Error, HasSense, ResultData, RetryPolicy, Scsi,
+    ScsiBuilder,
+};
+
+#[cfg(feature = "async")]
+pub use crate::AsyncScsi;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn prelude_test() {
+        use crate::prelude::*;
+
+        // a custom TEST UNIT READY written against the prelude alone
+        struct TestUnitReady;
+
+        impl Command for TestUnitReady {
+            type CommandBuffer = [u8; 6];
+
+            type DataBuffer = ();
+
+            type DataBufferWrapper = ();
+
+            type ReturnType = Result<(), Error>;
+
+            fn direction(&self) -> DataDirection {
+                DataDirection::None
+            }
+
+            fn command(&self) -> Self::CommandBuffer {
+                [0; 6]
+            }
+
+            fn data(&self) -> Self::DataBufferWrapper {}
+
+            fn data_size(&self) -> u32 {
+                0
+            }
+
+            fn process_result(
+                &self,
+                result: ResultData<Self::DataBufferWrapper>,
+            ) -> Self::ReturnType {
+                result.check_ioctl_error()?;
+                result.check_common_error()
+            }
+        }
+
+        fn names(
+            _: ScsiBuilder,
+            _: RetryPolicy,
+            _: BufferPool,
+            _: Control,
+            _: SenseData,
+            _: SenseKey,
+            _: &dyn HasSense,
+        ) {
+        }
+        let _ = names;
+
+        let mock = crate::MockScsi::new();
+        mock.expect(&[0; 6], crate::MockResponse::good());
+        let scsi: Scsi = Scsi::mock(mock);
+        assert_eq!(TestUnitReady.cdb_bytes(), [0; 6], "CDB");
+        assert!(scsi.issue(&TestUnitReady).is_ok(), "issue");
+    }
+}
@@ -1,6 +1,9 @@
-use std::io;
+use std::{ops::Deref, time::Duration};
 
-use crate::{command::sense::SenseData, error};
+use crate::{
+    command::sense::{SenseData, SenseKey},
+    error,
+};
 
 #[cfg(target_os = "linux")]
 use crate::os::linux::{DriverStatus, HostStatus};
@@ -8,17 +11,29 @@ use crate::os::linux::{DriverStatus, HostStatus};
 #[derive(Debug)]
 pub struct ResultData<'a, D> {
     pub(crate) ioctl_result: i32,
+    pub(crate) os_error: i32,
     pub(crate) transfered_data_length: usize,
+    pub(crate) residual: usize,
     pub(crate) data: &'a mut D,
     pub(crate) transfered_sense_length: usize,
     pub(crate) sense_buffer: &'a SenseData,
+    pub(crate) raw_sense: &'a [u8],
     pub(crate) status: Status,
+    pub(crate) retries: u32,
+    pub(crate) duration: Duration,
     #[cfg(target_os = "linux")]
     pub(crate) host_status: HostStatus,
     #[cfg(target_os = "linux")]
     pub(crate) driver_status: DriverStatus,
 }
 
+/// Implemented by results that can carry sense data, so logging or retry code can look at
+/// the sense of any command the same way.
+pub trait HasSense {
+    /// None if the device returned no parsable sense data
+    fn check_sense(&self) -> Option<&SenseData>;
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum Status {
     Good,
@@ -33,7 +48,24 @@ pub enum Status {
 }
 
 impl<D> ResultData<'_, D> {
+    /// Returns the error of the sense key, like [`crate::Error::MediumError`], if the device
+    /// reported parsable sense data, or [`crate::Error::Sense`] for the less common sense keys
+    ///
+    /// A RECOVERED ERROR is no error, the command completed. Its sense is left for the result,
+    /// see [`ResultData::is_recovered_error`].
     pub fn check_common_error(&self) -> crate::Result<()> {
+        let recovered = self.is_recovered_error();
+
+        if self.transfered_sense_length != 0
+            && !recovered
+            && matches!(
+                self.sense_buffer,
+                SenseData::Fixed(_) | SenseData::Descriptor(_)
+            )
+        {
+            return Err(crate::Error::from(self.sense_buffer.clone()));
+        }
+
         let mut result = String::new();
 
         #[cfg(target_os = "linux")]
@@ -42,16 +74,19 @@ impl<D> ResultData<'_, D> {
                 result.push_str(&format!("host status: {:?}. ", self.host_status));
             }
 
-            if !self.driver_status.is_empty() {
+            // DRIVER_SENSE only says that there is sense data, checked above, and the high nibble
+            // holds the suggestions
+            let driver_byte = self.driver_status.bits() & 0x0F;
+            if driver_byte != DriverStatus::OK.bits() && driver_byte != DriverStatus::SENSE.bits() {
                 result.push_str(&format!("driver status: {:?}. ", self.driver_status));
             }
         }
 
-        if !matches!(self.status, Status::Good) {
+        if !matches!(self.status, Status::Good) && !recovered {
             result.push_str(&format!("Status: {:?}. ", self.status));
         }
 
-        if self.transfered_sense_length != 0 {
+        if self.transfered_sense_length != 0 && !recovered {
             result.push_str(&format!("Sense data: {:02X?}", self.sense_buffer));
         }
 
@@ -62,10 +97,91 @@ impl<D> ResultData<'_, D> {
         Ok(())
     }
 
+    /// The sense data is carried in [`crate::Error::CheckCondition`] and can be downcast to [`SenseData`]
+    pub fn check_condition(&self) -> crate::Result<()> {
+        if matches!(self.status, Status::CheckCondition)
+            && !matches!(self.sense_buffer, SenseData::None)
+        {
+            return Err(crate::Error::CheckCondition(Box::new(
+                self.sense_buffer.clone(),
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// The command completed, but the device had to recover from an error, see SPC-6 4.4.8
+    pub fn is_recovered_error(&self) -> bool {
+        matches!(
+            self.sense_buffer.sense_key(),
+            Some(SenseKey::RecoveredError)
+        )
+    }
+
+    /// A command that ran out of time is reported as [`crate::Error::Timeout`], the errnos with
+    /// a variant of their own, like [`crate::Error::NotSgDevice`], are mapped to it.
+    ///
+    /// On Linux the host and driver status of SG_IO are decoded too: a command that was aborted,
+    /// reset or bounced back for a retry is [`crate::Error::Aborted`], a failure of the adapter
+    /// or the link is [`crate::Error::TransportError`].
     pub fn check_ioctl_error(&self) -> crate::Result<()> {
-        match self.ioctl_result {
-            0 => Ok(()),
-            _ => Err(error::Error::IO(io::Error::last_os_error())),
+        if self.ioctl_result != 0 {
+            return Err(error::Error::from_os_error(self.os_error));
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(error) = self.transport_error() {
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn transport_error(&self) -> Option<crate::Error> {
+        // the low nibble of the driver status is the driver byte, the rest are suggestions
+        let driver_byte = self.driver_status.bits() & 0x0F;
+        if matches!(self.host_status, HostStatus::TimeOut)
+            || driver_byte == DriverStatus::TIMEOUT.bits()
+        {
+            return Some(error::Error::Timeout);
+        }
+
+        let (host_status, driver_status) = (self.host_status, self.driver_status);
+        match host_status {
+            HostStatus::Ok | HostStatus::Passthrough => {}
+            // the command never ran to completion, or the low level driver asked for a retry
+            HostStatus::Abort
+            | HostStatus::Reset
+            | HostStatus::BusBusy
+            | HostStatus::SoftError
+            | HostStatus::ImmediateRetry
+            | HostStatus::Requeue
+            | HostStatus::TransportDisrupted => {
+                return Some(error::Error::Aborted {
+                    host_status,
+                    driver_status,
+                })
+            }
+            _ => {
+                return Some(error::Error::TransportError {
+                    host_status,
+                    driver_status,
+                })
+            }
+        }
+
+        match driver_byte {
+            byte if byte == DriverStatus::ERROR.bits()
+                || byte == DriverStatus::INVALID.bits()
+                || byte == DriverStatus::HARD.bits() =>
+            {
+                Some(error::Error::TransportError {
+                    host_status,
+                    driver_status,
+                })
+            }
+            _ => None,
         }
     }
 
@@ -73,10 +189,30 @@ impl<D> ResultData<'_, D> {
         self.ioctl_result
     }
 
+    /// errno or GetLastError of a failed ioctl, 0 if the ioctl succeeded
+    pub fn os_error(&self) -> i32 {
+        self.os_error
+    }
+
     pub fn transfered_data_length(&self) -> usize {
         self.transfered_data_length
     }
 
+    /// bytes requested but not transferred, as reported by the adapter
+    pub fn residual(&self) -> usize {
+        self.residual
+    }
+
+    // the residual of a data in buffer of `length` bytes, a bogus residual can't exceed it
+    pub(crate) fn data_in_residual(&self, length: usize) -> usize {
+        usize::min(self.residual, length)
+    }
+
+    // how many bytes of a data in buffer of `length` bytes the device filled
+    pub(crate) fn data_in_length(&self, length: usize) -> usize {
+        length - self.data_in_residual(length)
+    }
+
     pub fn data(&self) -> &D {
         self.data
     }
@@ -88,6 +224,94 @@ impl<D> ResultData<'_, D> {
     pub fn sense_buffer(&self) -> &SenseData {
         self.sense_buffer
     }
+
+    /// the sense bytes written by the device, which may be vendor specific
+    pub fn raw_sense(&self) -> &[u8] {
+        self.raw_sense
+    }
+
+    pub fn sense(&self) -> Option<SenseData> {
+        match self.sense_buffer {
+            SenseData::None => None,
+            sense => Some(sense.clone()),
+        }
+    }
+
+    /// how many times the command was reissued by the [`crate::RetryPolicy`] before this result
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// How long the last attempt took, as reported by the driver if it measures it to the
+    /// millisecond or more, otherwise as measured around the ioctl.
+    ///
+    /// The results of [`crate::Scsi::issue_many`] without a driver reported duration get the
+    /// time of the whole batch.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl<D> ResultData<'_, D>
+where
+    D: Deref,
+    D::Target: AsRef<[u8]>,
+{
+    /// The data in bytes the device transferred, for parsing responses no command decodes.
+    ///
+    /// Only meaningful for [`crate::DataDirection::FromDevice`], the data buffer of a
+    /// bidirectional command starts with the data out.
+    pub fn raw_data(&self) -> &[u8] {
+        let data = (**self.data).as_ref();
+        &data[..self.data_in_length(data.len())]
+    }
+}
+
+impl<D> HasSense for ResultData<'_, D> {
+    fn check_sense(&self) -> Option<&SenseData> {
+        match self.sense_buffer {
+            SenseData::None => None,
+            sense => Some(sense),
+        }
+    }
+}
+
+// commands that return nothing on success only carry sense in their error
+impl HasSense for () {
+    fn check_sense(&self) -> Option<&SenseData> {
+        None
+    }
+}
+
+impl HasSense for crate::Error {
+    fn check_sense(&self) -> Option<&SenseData> {
+        match self {
+            Self::Sense(sense)
+            | Self::NotReady(sense)
+            | Self::MediumError(sense)
+            | Self::HardwareError(sense)
+            | Self::IllegalRequest(sense)
+            | Self::UnitAttention(sense)
+            | Self::DataProtect(sense)
+            | Self::AbortedCommand(sense) => Some(sense),
+            Self::CheckCondition(sense) => sense.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl<T: HasSense> HasSense for crate::Result<T> {
+    fn check_sense(&self) -> Option<&SenseData> {
+        match self {
+            Ok(result) => result.check_sense(),
+            Err(error) => error.check_sense(),
+        }
+    }
+}
+
+// the adapter may report more sense bytes written than the buffer can hold
+pub(crate) fn written_sense(sense_buffer: &[u8], written: usize) -> &[u8] {
+    &sense_buffer[..usize::min(written, sense_buffer.len())]
 }
 
 impl From<u8> for Status {
@@ -105,3 +329,273 @@ impl From<u8> for Status {
         }
     }
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::command::sense::{SenseKey, MAX_SENSE_BUFFER_LENGTH};
+
+    #[test]
+    fn check_condition_test() {
+        let mut raw = [0u8; MAX_SENSE_BUFFER_LENGTH];
+        // NOT READY, LOGICAL UNIT IS IN PROCESS OF BECOMING READY
+        raw[..18].copy_from_slice(&[
+            0x70, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x04, 0x01,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+        let sense_buffer = SenseData::parse(&raw, 18);
+
+        let result = ResultData {
+            ioctl_result: 0,
+            os_error: 0,
+            transfered_data_length: 0,
+            residual: 0,
+            data: &mut (),
+            transfered_sense_length: 18,
+            sense_buffer: &sense_buffer,
+            raw_sense: written_sense(&raw, 18),
+            status: Status::CheckCondition,
+            retries: 0,
+            duration: Duration::ZERO,
+            host_status: HostStatus::Ok,
+            driver_status: DriverStatus::SENSE,
+        };
+
+        let error = result.check_condition().unwrap_err();
+        let sense = match &error {
+            crate::Error::CheckCondition(sense) => sense.downcast_ref::<SenseData>(),
+            _ => None,
+        };
+
+        assert!(
+            matches!(
+                sense,
+                Some(SenseData::Fixed(sense)) if matches!(sense.sense_key, SenseKey::NotReady)
+                    && *sense.additional_sense_code == 0x0401
+            ),
+            "not ready sense data"
+        );
+    }
+
+    #[test]
+    fn timeout_test() {
+        let sense_buffer = SenseData::None;
+
+        let mut result = ResultData {
+            ioctl_result: 0,
+            os_error: 0,
+            transfered_data_length: 0,
+            residual: 0,
+            data: &mut (),
+            transfered_sense_length: 0,
+            sense_buffer: &sense_buffer,
+            raw_sense: &[],
+            status: Status::Good,
+            retries: 0,
+            duration: Duration::ZERO,
+            host_status: HostStatus::TimeOut,
+            driver_status: DriverStatus::OK,
+        };
+
+        assert!(
+            matches!(result.check_ioctl_error(), Err(crate::Error::Timeout)),
+            "host timed out"
+        );
+
+        result.host_status = HostStatus::Ok;
+        result.driver_status = DriverStatus::TIMEOUT | DriverStatus::ABORT;
+        assert!(
+            matches!(result.check_ioctl_error(), Err(crate::Error::Timeout)),
+            "driver timed out"
+        );
+
+        result.driver_status = DriverStatus::SENSE;
+        assert!(result.check_ioctl_error().is_ok(), "no timeout");
+    }
+
+    #[test]
+    fn sense_error_test() {
+        let mut raw = [0u8; MAX_SENSE_BUFFER_LENGTH];
+        // NOT READY, LOGICAL UNIT IS IN PROCESS OF BECOMING READY
+        raw[..18].copy_from_slice(&[
+            0x70, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x04, 0x01,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+        let sense_buffer = SenseData::parse(&raw, 18);
+
+        let result = ResultData {
+            ioctl_result: 0,
+            os_error: 0,
+            transfered_data_length: 0,
+            residual: 0,
+            data: &mut (),
+            transfered_sense_length: 18,
+            sense_buffer: &sense_buffer,
+            raw_sense: written_sense(&raw, 18),
+            status: Status::CheckCondition,
+            retries: 0,
+            duration: Duration::ZERO,
+            host_status: HostStatus::Ok,
+            driver_status: DriverStatus::SENSE,
+        };
+
+        let error = result.check_common_error().unwrap_err();
+        let sense = match &error {
+            crate::Error::NotReady(sense) => sense,
+            other => panic!("unexpected error: {:?}", other),
+        };
+
+        assert!(
+            matches!(sense.sense_key(), Some(SenseKey::NotReady)),
+            "sense key"
+        );
+        let code = sense.additional_sense_code().unwrap();
+        assert_eq!(code.additional_sense_code(), 0x04, "asc");
+        assert_eq!(code.additional_sense_code_qualifier(), 0x01, "ascq");
+        assert_eq!(
+            error.to_string(),
+            "Sense: NOT READY / ASC 0x04 / ASCQ 0x01: LOGICAL UNIT IS IN PROCESS OF BECOMING READY",
+            "display"
+        );
+    }
+
+    #[test]
+    fn raw_sense_test() {
+        let raw = [0x70u8; MAX_SENSE_BUFFER_LENGTH];
+
+        assert_eq!(written_sense(&raw, 18).len(), 18, "written length");
+        assert_eq!(
+            written_sense(&raw, MAX_SENSE_BUFFER_LENGTH + 4).len(),
+            MAX_SENSE_BUFFER_LENGTH,
+            "clamped to the buffer"
+        );
+        assert!(written_sense(&raw, 0).is_empty(), "nothing written");
+    }
+
+    #[test]
+    fn sense_key_error_test() {
+        let sense_result = |sense_key: u8| {
+            let mut raw = [0u8; MAX_SENSE_BUFFER_LENGTH];
+            raw[..18].copy_from_slice(&[
+                0x70, 0x00, sense_key, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00,
+            ]);
+            let sense_buffer = SenseData::parse(&raw, 18);
+
+            ResultData {
+                ioctl_result: 0,
+                os_error: 0,
+                transfered_data_length: 0,
+                residual: 0,
+                data: &mut (),
+                transfered_sense_length: 18,
+                sense_buffer: &sense_buffer,
+                raw_sense: written_sense(&raw, 18),
+                status: Status::CheckCondition,
+                retries: 0,
+                duration: Duration::ZERO,
+                host_status: HostStatus::Ok,
+                driver_status: DriverStatus::SENSE,
+            }
+            .check_common_error()
+            .unwrap_err()
+        };
+
+        assert!(
+            matches!(sense_result(0x2), crate::Error::NotReady(_)),
+            "not ready"
+        );
+        assert!(
+            matches!(sense_result(0x3), crate::Error::MediumError(_)),
+            "medium error"
+        );
+        assert!(
+            matches!(sense_result(0x4), crate::Error::HardwareError(_)),
+            "hardware error"
+        );
+        assert!(
+            matches!(sense_result(0x5), crate::Error::IllegalRequest(_)),
+            "illegal request"
+        );
+        assert!(
+            matches!(sense_result(0x6), crate::Error::UnitAttention(_)),
+            "unit attention"
+        );
+        assert!(
+            matches!(sense_result(0x7), crate::Error::DataProtect(_)),
+            "data protect"
+        );
+        assert!(
+            matches!(sense_result(0xB), crate::Error::AbortedCommand(_)),
+            "aborted command"
+        );
+        assert!(
+            matches!(sense_result(0x8), crate::Error::Sense(_)),
+            "blank check has no variant"
+        );
+        assert!(
+            matches!(
+                sense_result(0x3)
+                    .check_sense()
+                    .and_then(SenseData::sense_key),
+                Some(SenseKey::MediumError)
+            ),
+            "full sense data kept"
+        );
+    }
+
+    #[test]
+    fn raw_data_test() {
+        use crate::{
+            data_wrapper::VecBufferWrapper, Command, DataDirection, MockResponse, MockScsi, Scsi,
+        };
+
+        // INQUIRY with a 36 byte allocation length
+        struct RawInquiry;
+
+        impl Command for RawInquiry {
+            type CommandBuffer = [u8; 6];
+
+            type DataBuffer = crate::data_wrapper::AnyType;
+
+            type DataBufferWrapper = VecBufferWrapper;
+
+            type ReturnType = Vec<u8>;
+
+            fn direction(&self) -> DataDirection {
+                DataDirection::FromDevice
+            }
+
+            fn command(&self) -> Self::CommandBuffer {
+                [0x12, 0x00, 0x00, 0x00, 0x24, 0x00]
+            }
+
+            fn data(&self) -> Self::DataBufferWrapper {
+                vec![0xEE; 36].into()
+            }
+
+            fn data_size(&self) -> u32 {
+                36
+            }
+
+            fn process_result(
+                &self,
+                result: ResultData<Self::DataBufferWrapper>,
+            ) -> Self::ReturnType {
+                result.raw_data().to_vec()
+            }
+        }
+
+        let mock = MockScsi::new();
+        mock.expect(
+            &[0x12, 0x00, 0x00, 0x00, 0x24, 0x00],
+            MockResponse::good().data(&[0x00, 0x00, 0x06, 0x12, 0x1F]),
+        );
+
+        assert_eq!(
+            Scsi::mock(mock).issue(&RawInquiry),
+            [0x00, 0x00, 0x06, 0x12, 0x1F],
+            "trimmed to the transferred length"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use crate::{
+    command::sense::{SenseData, SenseKey},
+    result_data::Status,
+};
+
+/// When [`crate::Scsi::issue`] reissues a command by itself.
+///
+/// Only BUSY, TASK SET FULL and CHECK CONDITION with a UNIT ATTENTION sense key are retried.
+/// Anything else, such as a MEDIUM ERROR or an ILLEGAL REQUEST, is returned on the first
+/// attempt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    backoff: Duration,
+}
+
+impl RetryPolicy {
+    // max_attempts counts the first attempt, so 1 never retries
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: u32::max(max_attempts, 1),
+            backoff: Duration::ZERO,
+        }
+    }
+
+    // the delay before the first retry, doubled for every retry after it
+    pub fn backoff(mut self, value: Duration) -> Self {
+        self.backoff = value;
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub(crate) fn should_retry(
+        &self,
+        retries: u32,
+        ioctl_result: i32,
+        status: Status,
+        sense: &SenseData,
+    ) -> bool {
+        if ioctl_result != 0 || retries.saturating_add(1) >= self.max_attempts {
+            return false;
+        }
+
+        match status {
+            Status::Busy | Status::TaskSetFull => true,
+            Status::CheckCondition => matches!(sense.sense_key(), Some(SenseKey::UnitAttention)),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn delay(&self, retries: u32) -> Duration {
+        self.backoff
+            .checked_mul(1u32.checked_shl(retries).unwrap_or(u32::MAX))
+            .unwrap_or(Duration::MAX)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::sense::MAX_SENSE_BUFFER_LENGTH;
+
+    fn fixed_sense(sense_key: u8) -> SenseData {
+        let mut raw = [0u8; MAX_SENSE_BUFFER_LENGTH];
+        raw[..18].copy_from_slice(&[
+            0x70, 0x00, sense_key, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x29,
+            0x00, 0x00, 0x00, 0x00, 0x00,
+        ]);
+        SenseData::parse(&raw, 18)
+    }
+
+    #[test]
+    fn busy_twice_test() {
+        let policy = RetryPolicy::new(3);
+        let responses = [Status::Busy, Status::Busy, Status::Good];
+
+        let mut retries = 0;
+        while policy.should_retry(retries, 0, responses[retries as usize], &SenseData::None) {
+            retries += 1;
+        }
+
+        assert_eq!(retries, 2, "succeeded after two retries");
+        assert!(
+            !policy.should_retry(2, 0, Status::Busy, &SenseData::None),
+            "out of attempts"
+        );
+    }
+
+    #[test]
+    fn check_condition_test() {
+        let policy = RetryPolicy::new(5);
+
+        // POWER ON, RESET, OR BUS DEVICE RESET OCCURRED
+        assert!(
+            policy.should_retry(0, 0, Status::CheckCondition, &fixed_sense(0x06)),
+            "unit attention"
+        );
+        assert!(
+            !policy.should_retry(0, 0, Status::CheckCondition, &fixed_sense(0x03)),
+            "medium error"
+        );
+        assert!(
+            !policy.should_retry(0, 0, Status::CheckCondition, &fixed_sense(0x05)),
+            "illegal request"
+        );
+        assert!(
+            !policy.should_retry(0, -1, Status::Busy, &SenseData::None),
+            "ioctl error"
+        );
+        assert!(
+            !RetryPolicy::default().should_retry(0, 0, Status::Busy, &SenseData::None),
+            "no retries by default"
+        );
+    }
+
+    #[test]
+    fn delay_test() {
+        let policy = RetryPolicy::new(4).backoff(Duration::from_millis(10));
+
+        assert_eq!(policy.delay(0), Duration::from_millis(10), "first retry");
+        assert_eq!(policy.delay(2), Duration::from_millis(40), "third retry");
+        assert_eq!(
+            policy.delay(40),
+            Duration::from_millis(10) * u32::MAX,
+            "saturated"
+        );
+    }
+}
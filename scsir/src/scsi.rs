@@ -2,308 +2,1383 @@
 
 use std::{
     borrow::BorrowMut,
-    fs::OpenOptions,
-    io,
     mem::size_of_val,
     path::{Path, PathBuf},
-    time::Duration,
+    ptr, slice,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
 };
 
-use crate::{file_descriptor::FileDescriptor, Command};
+use crate::{
+    command::{
+        command_bytes, fill_additional_cdb_length,
+        report_supported_operation_codes::SupportedCommands,
+        sense::{SenseData, MAX_SENSE_BUFFER_LENGTH},
+    },
+    os::{self, Backend, OpenFlags, ReadOnlyBackend, Request, Response},
+    result_data::{written_sense, ResultData, Status},
+    BufferPool, Command, DataDirection, RetryPolicy,
+};
 
+/// A handle to one device, every command builder borrows it.
+///
+/// `Scsi` is `Send` and `Sync` on every platform, so one handle can be shared between threads
+/// through an [`Arc`] or scoped threads. SG_IO, SPTI and CAM each take a whole command in one
+/// blocking call with its own header, data and sense buffer, so commands issued from several
+/// threads at once don't interfere, the driver queues them to the device.
 #[derive(Debug)]
 pub struct Scsi {
     path: PathBuf,
-    file_descriptor: FileDescriptor,
-    timeout: Duration,
+    backend: Box<dyn Backend>,
+    default_timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    buffer_pool: Option<BufferPool>,
+    autosense: bool,
+    sense_buffer_length: usize,
+    supported_commands: Mutex<Option<Arc<SupportedCommands>>>,
 }
 
+// a backend or field that isn't thread safe fails the build here, not in a caller's thread pool
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Scsi>();
+    assert_send_sync::<ScsiBuilder>();
+};
+
 impl Scsi {
+    /// Opens a device with the pass-through backend of the platform.
+    ///
+    /// Linux uses SG_IO on a block device, an sg character device such as /dev/sg0 or a bsg
+    /// node, which usually needs root or membership of the disk group. Windows uses SPTI and needs administrator privileges. FreeBSD uses
+    /// CAM through libcam and needs read and write access to the pass(4) device.
+    ///
+    /// Use [`ScsiBuilder`] to open it read-only or with other flags.
     pub fn new<P: AsRef<Path> + ?Sized>(path: &P) -> crate::Result<Scsi> {
-        let mut options = OpenOptions::new();
-        options.read(true).write(true);
-        let file_descriptor = FileDescriptor::open(&path, options)?;
+        ScsiBuilder::new().open(path)
+    }
 
-        if !file_descriptor.is_block()? {
-            return Err(crate::Error::NotBlockDevice(path.as_ref().to_owned()));
-        }
+    /// Takes ownership of a device that is already open, e.g. by a more privileged parent.
+    ///
+    /// Block devices, /dev/sg* character devices and bsg nodes are accepted.
+    #[cfg(target_os = "linux")]
+    pub fn from_fd(fd: std::os::fd::OwnedFd) -> crate::Result<Scsi> {
+        use crate::{file_descriptor::FileDescriptor, os::linux::SgBackend};
+        use std::os::fd::AsRawFd;
 
-        if !Self::is_scsi_device(&file_descriptor)? {
-            return Err(crate::Error::NotScsiDevice(path.as_ref().to_owned()));
-        }
+        let path = PathBuf::from(format!("/proc/self/fd/{}", fd.as_raw_fd()));
+        let file_descriptor = FileDescriptor::from_owned(fd, &path);
+        let backend = SgBackend::from_file_descriptor(file_descriptor, &path)?;
 
-        Ok(Scsi {
-            path: path.as_ref().to_owned(),
-            file_descriptor,
-            timeout: Duration::from_millis(SG_DEFAULT_TIMEOUT),
-        })
+        Ok(Self::with_backend(&path, Box::new(backend)))
     }
 
+    /// Like [`Scsi::from_fd`], but the caller keeps ownership of the descriptor.
+    ///
+    /// The descriptor is duplicated, so dropping the Scsi never closes the caller's copy.
     #[cfg(target_os = "linux")]
+    pub fn from_borrowed_fd(fd: std::os::fd::BorrowedFd) -> crate::Result<Scsi> {
+        Self::from_fd(fd.try_clone_to_owned()?)
+    }
+
+    pub(crate) fn with_backend(path: &Path, backend: Box<dyn Backend>) -> Scsi {
+        Scsi {
+            path: path.to_owned(),
+            backend,
+            default_timeout: None,
+            retry_policy: RetryPolicy::default(),
+            buffer_pool: None,
+            autosense: true,
+            sense_buffer_length: MAX_SENSE_BUFFER_LENGTH,
+            supported_commands: Mutex::new(None),
+        }
+    }
+
+    /// Issues `command` with the timeout of [`Command::timeout`], or the default timeout of the
+    /// handle if it returns None.
+    ///
+    /// With the `trace` feature the command runs in a `scsi_command` span of the `tracing`
+    /// crate, with an event for every retry and one for its completion.
     pub fn issue<T: Command>(&self, command: &T) -> T::ReturnType {
-        use nix::libc;
+        let timeout = resolve_timeout(command.timeout(), self.default_timeout);
+        self.issue_inner(command, timeout)
+    }
 
-        use crate::{
-            command::sense::{SenseData, MAX_SENSE_BUFFER_LENGTH},
-            os::linux::{AccessFlags, AuxiliaryInfo, DriverStatus, SgIoHeader},
-            result_data::{ResultData, Status},
-        };
+    /// Like [`Scsi::issue`], but also returns the sense data of the last attempt whatever the
+    /// status was, e.g. the RECOVERED ERROR of a command that still completed.
+    pub fn issue_with_sense<T: Command>(&self, command: &T) -> (T::ReturnType, Option<SenseData>) {
+        let timeout = resolve_timeout(command.timeout(), self.default_timeout);
+        let (result, sense) = self.issue_from(command, Attempt::first(command), timeout);
 
-        const SG_IO: u32 = 0x2285;
+        match sense {
+            SenseData::None => (result, None),
+            sense => (result, Some(sense)),
+        }
+    }
 
-        let command_buffer = command.command();
-        let mut data_buffer = command.data();
-        let raw_pointer_to_data_buffer = &mut data_buffer as *mut _;
-        let mut sense_buffer = [0u8; MAX_SENSE_BUFFER_LENGTH];
+    /// Issues every command and returns their results in the same order.
+    ///
+    /// Every result is handled on its own, a failing command doesn't stop the ones after it.
+    /// The backend may keep several of them in flight at once, see [`Scsi::submit_batch`], so
+    /// don't rely on the order in which the device sees them.
+    /// Retries, if the retry policy asks for any, are issued one command at a time after the
+    /// batch completed.
+    pub fn issue_many<T: Command>(&self, commands: &[T]) -> Vec<T::ReturnType> {
+        let timeouts: Vec<_> = commands
+            .iter()
+            .map(|command| resolve_timeout(command.timeout(), self.default_timeout))
+            .collect();
+        let mut attempts: Vec<_> = commands.iter().map(Attempt::first).collect();
 
-        let size_of_command_buffer = size_of_val(&command_buffer) as u8;
-        let size_of_data_buffer = command.data_size();
-        let size_of_sense_buffer = size_of_val(&sense_buffer) as u8;
+        let requests = attempts
+            .iter_mut()
+            .zip(commands)
+            .zip(&timeouts)
+            .map(|((attempt, command), timeout)| {
+                attempt.request(command, *timeout, self.autosense, self.sense_buffer_length)
+            })
+            .collect();
+        let start = Instant::now();
+        let mut responses = self.backend.issue_batch(requests);
+        let elapsed = start.elapsed();
+        // a response the backend lost fails its own command, not the whole batch
+        responses.resize_with(commands.len(), || Response::failed(os::NO_RESPONSE_ERROR));
 
-        let pointer_of_command_buffer = Some(&command_buffer);
+        attempts
+            .into_iter()
+            .zip(commands)
+            .zip(timeouts)
+            .zip(responses)
+            .map(|(((mut attempt, command), timeout), response)| {
+                match self.complete(command, &mut attempt, response, elapsed) {
+                    Some((result, _)) => result,
+                    None => self.issue_from(command, attempt.retry(command), timeout).0,
+                }
+            })
+            .collect()
+    }
 
-        let pointer_of_data_buffer = if size_of_data_buffer == 0 {
-            None
-        } else {
-            Some(data_buffer.borrow_mut())
-        };
+    /// Like [`Scsi::issue_many`], but meant for batches that should be in flight together.
+    ///
+    /// On an sg character device, e.g. /dev/sg0, up to 16 commands at a time are written to the
+    /// device through io_uring and reaped as they finish. Other devices, a batch of one, or a kernel without io_uring issue the commands
+    /// one after another.
+    #[cfg(feature = "io-uring")]
+    pub fn submit_batch<T: Command>(&self, commands: &[T]) -> Vec<T::ReturnType> {
+        self.issue_many(commands)
+    }
 
-        let pointer_of_sense_buffer = Some(&mut sense_buffer);
-
-        let mut sg_header = SgIoHeader {
-            interface_id: b'S' as i32,
-            data_direction: command.direction().into(),
-            command_length: size_of_command_buffer,
-            max_sense_buffer_length: size_of_sense_buffer,
-            iovec_count: 0,
-            data_length: size_of_data_buffer,
-            data: pointer_of_data_buffer,
-            command: pointer_of_command_buffer,
-            sense_buffer: pointer_of_sense_buffer,
-            timeout: self
-                .timeout
-                .as_millis()
-                .clamp(u32::MIN as u128, u32::MAX as u128) as u32,
-            flags: AccessFlags::DEFAULT,
-            pack_id: 0,
-            user_pointer: 0,
-            status: 0,
-            masked_status: 0,
-            message_status: 0,
-            sense_buffer_written: 0,
-            host_status: 0,
-            driver_status: DriverStatus::OK,
-            residual_count: 0,
-            duration: 0,
-            info: AuxiliaryInfo::OK,
-        };
+    fn issue_inner<T: Command>(&self, command: &T, timeout: Duration) -> T::ReturnType {
+        self.issue_from(command, Attempt::first(command), timeout).0
+    }
 
-        let ioctl_result = unsafe {
-            libc::ioctl(
-                self.file_descriptor.raw(),
-                SG_IO.try_into().unwrap(),
-                &mut sg_header,
-            )
+    // the result and the sense data of the last attempt
+    fn issue_from<T: Command>(
+        &self,
+        command: &T,
+        mut attempt: Attempt<T>,
+        timeout: Duration,
+    ) -> (T::ReturnType, SenseData) {
+        loop {
+            let request =
+                attempt.request(command, timeout, self.autosense, self.sense_buffer_length);
+            let start = Instant::now();
+            let response = self.backend.issue(request);
+            let elapsed = start.elapsed();
+            if let Some(result) = self.complete(command, &mut attempt, response, elapsed) {
+                return result;
+            }
+
+            attempt = attempt.retry(command);
+        }
+    }
+
+    // None if the retry policy asked for another attempt, after waiting for its delay
+    fn complete<T: Command>(
+        &self,
+        command: &T,
+        attempt: &mut Attempt<T>,
+        response: Response,
+        elapsed: Duration,
+    ) -> Option<(T::ReturnType, SenseData)> {
+        // nothing was requested, whatever the adapter left in the buffer isn't sense data
+        let sense_length = match self.autosense {
+            true => usize::min(response.sense_length, self.sense_buffer_length),
+            false => 0,
         };
+        let raw_sense = written_sense(&attempt.sense_buffer, sense_length);
+        let sense_data = SenseData::parse(&attempt.sense_buffer, raw_sense.len());
 
-        let sense_buffer_written = sg_header.sense_buffer_written as usize;
-        let sense_data = sg_header
-            .sense_buffer
-            .map(|b| SenseData::parse(b, sense_buffer_written))
-            .unwrap_or(SenseData::None);
+        #[cfg(feature = "trace")]
+        let _span = attempt.span.enter();
+
+        let status = Status::from(response.status);
+        if self.retry_policy.should_retry(
+            attempt.retries,
+            response.ioctl_result,
+            status,
+            &sense_data,
+        ) {
+            #[cfg(feature = "trace")]
+            tracing::debug!(
+                retries = attempt.retries,
+                ioctl_result = response.ioctl_result,
+                status = response.status,
+                sense_key = ?sense_data.sense_key(),
+                "retrying"
+            );
+            thread::sleep(self.retry_policy.delay(attempt.retries));
+            return None;
+        }
 
         let result_data = ResultData {
-            ioctl_result,
-            transfered_data_length: sg_header.data_length as usize
-                - sg_header.residual_count as usize,
-            data: unsafe { &mut *raw_pointer_to_data_buffer },
-            transfered_sense_length: sense_buffer_written,
+            ioctl_result: response.ioctl_result,
+            os_error: response.os_error,
+            transfered_data_length: response.transfered_data_length,
+            residual: response.residual,
+            data: &mut attempt.data_buffer,
+            transfered_sense_length: sense_length,
             sense_buffer: &sense_data,
-            status: Status::from(sg_header.status),
-            host_status: sg_header.host_status.into(),
-            driver_status: sg_header.driver_status,
+            raw_sense,
+            status,
+            retries: attempt.retries,
+            // the driver measures in milliseconds, a faster command reads as 0
+            duration: response
+                .duration
+                .filter(|duration| !duration.is_zero())
+                .unwrap_or(elapsed),
+            #[cfg(target_os = "linux")]
+            host_status: response.host_status.into(),
+            #[cfg(target_os = "linux")]
+            driver_status: response.driver_status,
         };
 
-        command.process_result(result_data)
+        #[cfg(feature = "trace")]
+        tracing::debug!(
+            retries = attempt.retries,
+            ioctl_result = response.ioctl_result,
+            status = response.status,
+            sense_key = ?sense_data.sense_key(),
+            duration = ?result_data.duration,
+            "completed"
+        );
+
+        let result = command.process_result(result_data);
+        Some((result, sense_data))
     }
 
-    #[cfg(target_os = "windows")]
-    pub fn issue<T: Command>(&self, command: &T) -> T::ReturnType {
-        use std::slice;
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
 
-        use windows::Win32::{
-            Foundation::HANDLE,
-            Storage::IscsiDisc::{
-                IOCTL_SCSI_PASS_THROUGH_DIRECT, SCSI_IOCTL_DATA_BIDIRECTIONAL, SCSI_IOCTL_DATA_IN,
-                SCSI_IOCTL_DATA_OUT, SCSI_IOCTL_DATA_UNSPECIFIED,
-            },
-            System::IO::DeviceIoControl,
+    /// Sets the timeout of every command that doesn't set its own.
+    ///
+    /// A timeout set on the command builder, or returned by [`Command::timeout`], takes
+    /// precedence over this one, which in turn replaces [`Scsi::DEFAULT_TIMEOUT`].
+    ///
+    /// The driver takes whole milliseconds, so shorter timeouts are rounded up to 1 ms and ones
+    /// longer than u32::MAX milliseconds, about 49 days, are clamped. Windows rounds down to
+    /// whole seconds instead, with a minimum of one second.
+    pub fn set_default_timeout(&mut self, timeout: Duration) {
+        self.default_timeout = Some(timeout);
+    }
+
+    pub fn default_timeout(&self) -> Duration {
+        resolve_timeout(None, self.default_timeout)
+    }
+
+    /// Retries commands that fail with BUSY, TASK SET FULL or a UNIT ATTENTION.
+    ///
+    /// Commands are issued only once by default.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Lets the pooled issue methods, like [`crate::command::read::ReadCommand::issue_16_pooled`],
+    /// reuse their data buffers instead of allocating one per command.
+    pub fn set_buffer_pool(&mut self, pool: BufferPool) {
+        self.buffer_pool = Some(pool);
+    }
+
+    pub fn buffer_pool(&self) -> Option<&BufferPool> {
+        self.buffer_pool.as_ref()
+    }
+
+    /// Whether the driver fetches the sense data of a CHECK CONDITION, on by default.
+    ///
+    /// With autosense off a failed command reports the CHECK CONDITION status without sense
+    /// data, which can then be read with an explicit REQUEST SENSE.
+    pub fn set_autosense(&mut self, autosense: bool) {
+        self.autosense = autosense;
+    }
+
+    pub fn autosense(&self) -> bool {
+        self.autosense
+    }
+
+    /// Sets how many bytes of sense data the driver may return, at most 252.
+    ///
+    /// Fixed format sense fits in 18 bytes, descriptor format sense with several descriptors
+    /// needs more. Sense data past the length is cut off and not parsed.
+    pub fn set_sense_buffer_len(&mut self, length: usize) {
+        self.sense_buffer_length = usize::min(length, MAX_SENSE_BUFFER_LENGTH);
+    }
+
+    pub fn sense_buffer_len(&self) -> usize {
+        self.sense_buffer_length
+    }
+
+    pub(crate) fn supported_commands_cache(&self) -> &Mutex<Option<Arc<SupportedCommands>>> {
+        &self.supported_commands
+    }
+
+    #[deprecated(note = "use set_default_timeout instead")]
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.set_default_timeout(timeout);
+    }
+
+    #[deprecated(note = "use default_timeout instead")]
+    pub fn timeout(&self) -> Duration {
+        self.default_timeout()
+    }
+}
+
+/// Opens a [`Scsi`] with the flags and settings that [`Scsi::new`] leaves at their defaults.
+///
+/// ```no_run
+/// let scsi = scsir::ScsiBuilder::new()
+///     .read_only(true)
+///     .non_blocking(true)
+///     .open("/dev/sg0")?;
+/// # Ok::<(), scsir::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ScsiBuilder {
+    flags: OpenFlags,
+    default_timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+}
+
+impl ScsiBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the device without write access, commands that send data fail with EROFS
+    /// (ERROR_WRITE_PROTECT on Windows) instead of reaching the device.
+    ///
+    /// So do the commands without data besides TEST UNIT READY, PREVENT ALLOW MEDIUM REMOVAL,
+    /// VERIFY, PRE-FETCH and SYNCHRONIZE CACHE, since SANITIZE, FORMAT UNIT, START STOP UNIT or
+    /// the zone commands change the device without sending anything.
+    pub fn read_only(&mut self, value: bool) -> &mut Self {
+        self.flags.read_only = value;
+        self
+    }
+
+    // O_NONBLOCK, ignored on Windows
+    pub fn non_blocking(&mut self, value: bool) -> &mut Self {
+        self.flags.non_blocking = value;
+        self
+    }
+
+    // O_DIRECT, or FILE_FLAG_NO_BUFFERING on Windows
+    pub fn direct(&mut self, value: bool) -> &mut Self {
+        self.flags.direct = value;
+        self
+    }
+
+    // issues every command with the v4 header of bsg instead of the v3 one, which only bsg
+    // nodes accept. bsg nodes and bidirectional commands use v4 either way
+    #[cfg(target_os = "linux")]
+    pub fn sg_io_v4(&mut self, value: bool) -> &mut Self {
+        self.flags.sg_io_v4 = value;
+        self
+    }
+
+    // see Scsi::set_default_timeout
+    pub fn default_timeout(&mut self, value: Duration) -> &mut Self {
+        self.default_timeout = Some(value);
+        self
+    }
+
+    pub fn retry_policy(&mut self, value: RetryPolicy) -> &mut Self {
+        self.retry_policy = value;
+        self
+    }
+
+    pub fn open<P: AsRef<Path> + ?Sized>(&self, path: &P) -> crate::Result<Scsi> {
+        let path = path.as_ref();
+        Ok(self.build(path, os::open(path, self.flags)?))
+    }
+
+    pub(crate) fn build(&self, path: &Path, mut backend: Box<dyn Backend>) -> Scsi {
+        if self.flags.read_only {
+            backend = Box::new(ReadOnlyBackend(backend));
+        }
+
+        let mut scsi = Scsi::with_backend(path, backend);
+        scsi.default_timeout = self.default_timeout;
+        scsi.retry_policy = self.retry_policy;
+        scsi
+    }
+}
+
+impl Scsi {
+    /// The timeout used when neither the command nor the handle sets one
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(SG_DEFAULT_TIMEOUT);
+}
+
+// the command's own timeout, then the default of the handle, then the crate default
+fn resolve_timeout(command: Option<Duration>, handle: Option<Duration>) -> Duration {
+    command.or(handle).unwrap_or(Scsi::DEFAULT_TIMEOUT)
+}
+
+const SG_DEFAULT_TIMEOUT: u64 = 60_000;
+
+// the buffers of one attempt, a retry starts over with fresh ones
+struct Attempt<T: Command> {
+    command_buffer: T::CommandBuffer,
+    data_buffer: T::DataBufferWrapper,
+    data_length: u32,
+    sense_buffer: [u8; MAX_SENSE_BUFFER_LENGTH],
+    retries: u32,
+    // shared by every attempt of the command
+    #[cfg(feature = "trace")]
+    span: tracing::Span,
+}
+
+impl<T: Command> Attempt<T> {
+    fn first(command: &T) -> Self {
+        Self::new(
+            command,
+            0,
+            #[cfg(feature = "trace")]
+            None,
+        )
+    }
+
+    // the first attempt opens the span of the command
+    fn new(
+        command: &T,
+        retries: u32,
+        #[cfg(feature = "trace")] span: Option<tracing::Span>,
+    ) -> Self {
+        let mut command_buffer = command.command();
+        fill_additional_cdb_length(unsafe {
+            slice::from_raw_parts_mut(
+                &mut command_buffer as *mut _ as *mut u8,
+                size_of_val(&command_buffer),
+            )
+        });
+        #[cfg(feature = "trace")]
+        let span = span.unwrap_or_else(|| {
+            crate::trace::command_span(command_bytes(&command_buffer), command.direction())
+        });
+
+        Self {
+            command_buffer,
+            data_buffer: command.data(),
+            data_length: command.data_size(),
+            sense_buffer: [0; MAX_SENSE_BUFFER_LENGTH],
+            retries,
+            #[cfg(feature = "trace")]
+            span,
+        }
+    }
+
+    fn retry(self, command: &T) -> Self {
+        Self::new(
+            command,
+            self.retries + 1,
+            #[cfg(feature = "trace")]
+            Some(self.span),
+        )
+    }
+
+    fn request(
+        &mut self,
+        command: &T,
+        timeout: Duration,
+        autosense: bool,
+        sense_buffer_length: usize,
+    ) -> Request<'_> {
+        let data = match self.data_length {
+            0 => ptr::null_mut(),
+            _ => self.data_buffer.borrow_mut() as *mut T::DataBuffer as *mut u8,
         };
 
-        use crate::{
-            command::sense::SenseData,
-            os::windows::ScsiPassThroughDirectWrapper,
-            result_data::{ResultData, Status},
+        let data_in_length = match command.direction() {
+            DataDirection::Bidirectional => u32::min(command.data_in_size(), self.data_length),
+            _ => 0,
         };
+        let data_out_length = self.data_length - data_in_length;
 
-        const MAX_COMMAND_LENGTH: u8 = 16;
+        Request {
+            command: command_bytes(&self.command_buffer),
+            direction: command.direction(),
+            data: match data_out_length {
+                0 => ptr::null_mut(),
+                _ => data,
+            },
+            data_length: data_out_length,
+            data_in: match data_in_length {
+                0 => ptr::null_mut(),
+                _ => unsafe { data.add(data_out_length as usize) },
+            },
+            data_in_length,
+            sense_buffer: &mut self.sense_buffer[..sense_buffer_length],
+            autosense,
+            timeout,
+        }
+    }
+}
 
-        let command_buffer = command.command();
-        let mut data_buffer = command.data();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let size_of_command_buffer = size_of_val(&command_buffer) as u8;
-        let size_of_data_buffer = command.data_size();
+    // READ (16) of a single 8 byte block at the LBA
+    struct Read16(u8);
 
-        if size_of_command_buffer > MAX_COMMAND_LENGTH {
-            panic!(
-                "Current command length is {}, max command length is {}",
-                size_of_command_buffer, MAX_COMMAND_LENGTH
-            );
+    impl Command for Read16 {
+        type CommandBuffer = [u8; 16];
+
+        type DataBuffer = [u8; 8];
+
+        type DataBufferWrapper = [u8; 8];
+
+        type ReturnType = crate::Result<[u8; 8]>;
+
+        fn direction(&self) -> DataDirection {
+            DataDirection::FromDevice
         }
-        let command_pointer = &command_buffer as *const _ as *const u8;
-        let command_slice =
-            unsafe { slice::from_raw_parts(command_pointer, size_of_command_buffer as usize) };
-
-        let mut header = ScsiPassThroughDirectWrapper::default();
-        let address_of_header = std::ptr::addr_of!(header) as usize;
-        let mut spt = &mut header.scsi_pass_through;
-        spt.Length = size_of_val(spt) as u16;
-        spt.CdbLength = size_of_command_buffer;
-        spt.SenseInfoLength = size_of_val(&header.sense) as u8;
-        spt.DataIn = match command.direction() {
-            crate::DataDirection::None => SCSI_IOCTL_DATA_UNSPECIFIED,
-            crate::DataDirection::ToDevice => SCSI_IOCTL_DATA_OUT,
-            crate::DataDirection::FromDevice => SCSI_IOCTL_DATA_IN,
-            crate::DataDirection::ToFromDevice => SCSI_IOCTL_DATA_BIDIRECTIONAL,
-            crate::DataDirection::Unknown => SCSI_IOCTL_DATA_UNSPECIFIED,
-        } as u8;
-
-        spt.DataTransferLength = size_of_data_buffer;
-
-        spt.TimeOutValue = match self
-            .timeout
-            .as_secs()
-            .clamp(u32::MIN as u64, u32::MAX as u64)
-        {
-            0 => 1,
-            n => n as u32,
-        };
 
-        spt.DataBuffer = data_buffer.borrow_mut() as *mut _ as _;
+        fn command(&self) -> Self::CommandBuffer {
+            let mut command = [0; 16];
+            command[0] = 0x88;
+            command[9] = self.0;
+            command[13] = 1;
+            command
+        }
 
-        spt.SenseInfoOffset =
-            (std::ptr::addr_of!(header.sense) as usize - address_of_header) as u32;
+        fn data(&self) -> Self::DataBufferWrapper {
+            [0; 8]
+        }
 
-        spt.Cdb[..command_slice.len()].copy_from_slice(command_slice);
+        fn process_result(&self, result: ResultData<Self::DataBufferWrapper>) -> Self::ReturnType {
+            result.check_ioctl_error()?;
+            result.check_common_error()?;
 
-        let mut bytes_returned = 0;
+            Ok(*result.data())
+        }
+    }
 
-        let success = unsafe {
-            DeviceIoControl(
-                HANDLE(self.file_descriptor.raw() as isize),
-                IOCTL_SCSI_PASS_THROUGH_DIRECT,
-                Some(&header as *const _ as _),
-                size_of_val(&header) as u32,
-                Some(&mut header as *mut _ as _),
-                size_of_val(&header) as u32,
-                Some(&mut bytes_returned),
-                None,
-            )
+    #[test]
+    fn send_sync_test() {
+        use crate::command::{
+            inquiry::InquiryCommand, report_luns::ReportLunsCommand,
+            test_unit_ready::TestUnitReadyCommand,
         };
 
-        let ioctl_result = match success.as_bool() {
-            true => 0,
-            false => -1,
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Scsi>();
+        assert_send_sync::<ScsiBuilder>();
+        assert_send_sync::<BufferPool>();
+        assert_send_sync::<crate::PooledBuffer>();
+        assert_send_sync::<crate::Error>();
+        assert_send_sync::<TestUnitReadyCommand>();
+        assert_send_sync::<InquiryCommand>();
+        assert_send_sync::<ReportLunsCommand>();
+        #[cfg(feature = "block")]
+        assert_send_sync::<crate::command::read::ReadCommand>();
+
+        // one handle shared by several threads
+        let scsi = Scsi::mock(crate::MockScsi::new());
+        std::thread::scope(|scope| {
+            for _ in 0..2 {
+                scope.spawn(|| scsi.path().to_owned());
+            }
+        });
+    }
+
+    #[test]
+    fn timeout_precedence_test() {
+        let command = Duration::from_millis(10);
+        let handle = Duration::from_secs(5);
+
+        assert_eq!(
+            resolve_timeout(Some(command), Some(handle)),
+            command,
+            "command timeout"
+        );
+        assert_eq!(
+            resolve_timeout(None, Some(handle)),
+            handle,
+            "handle default timeout"
+        );
+        assert_eq!(
+            resolve_timeout(None, None),
+            Duration::from_secs(60),
+            "crate default timeout"
+        );
+    }
+
+    #[test]
+    fn builder_timeout_test() {
+        let mock = crate::MockScsi::new();
+        mock.expect(&[0x00, 0, 0, 0, 0, 0], crate::MockResponse::good())
+            .expect(&[0x00, 0, 0, 0, 0, 0], crate::MockResponse::good());
+
+        let mut scsi = Scsi::mock(mock.clone());
+        scsi.set_default_timeout(Duration::from_secs(5));
+        scsi.test_unit_ready()
+            .timeout(Duration::from_millis(10))
+            .issue()
+            .unwrap();
+        scsi.test_unit_ready().issue().unwrap();
+
+        let issued = mock.issued();
+        assert_eq!(
+            issued[0].timeout,
+            Duration::from_millis(10),
+            "timeout of the builder"
+        );
+        assert_eq!(
+            issued[1].timeout,
+            Duration::from_secs(5),
+            "default timeout of the handle"
+        );
+        mock.verify();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn from_fd_test() {
+        use std::os::{
+            fd::{AsFd, OwnedFd},
+            unix::net::UnixStream,
         };
 
-        let sense_data = SenseData::parse(
-            &header.sense,
-            header.scsi_pass_through.SenseInfoLength as usize,
+        let (left, right) = UnixStream::pair().unwrap();
+
+        assert!(
+            matches!(
+                Scsi::from_fd(OwnedFd::from(left)),
+                Err(crate::Error::NotScsiDevice(_))
+            ),
+            "owned socket"
         );
 
-        let result_data = ResultData {
-            ioctl_result,
-            transfered_data_length: header.scsi_pass_through.DataTransferLength as usize,
-            data: &mut data_buffer,
-            transfered_sense_length: header.scsi_pass_through.SenseInfoLength as usize,
-            sense_buffer: &sense_data,
-            status: Status::from(header.scsi_pass_through.ScsiStatus),
+        assert!(
+            matches!(
+                Scsi::from_borrowed_fd(right.as_fd()),
+                Err(crate::Error::NotScsiDevice(_))
+            ),
+            "borrowed socket"
+        );
+        // the borrowed descriptor is still open
+        assert!(right.peer_addr().is_ok(), "borrowed descriptor closed");
+    }
+
+    #[test]
+    fn bidirectional_test() {
+        use crate::{MockResponse, MockScsi};
+
+        // reads back the XOR of the data out, like XDWRITEREAD with DISABLE WRITE set
+        let mock = MockScsi::new();
+        mock.expect(
+            &[0x53, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00],
+            MockResponse::good().data(&[0xFF, 0xF0, 0x0F, 0x00]),
+        );
+
+        let scsi = Scsi::mock(mock.clone());
+        let result = scsi
+            .passthrough()
+            .command_buffer(&[0x53, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00])
+            .direction(DataDirection::Bidirectional)
+            .parameter(&[0x00, 0x0F, 0xF0, 0xFF])
+            .allocation_length(4)
+            .issue()
+            .unwrap();
+
+        assert_eq!(result.data, [0xFF, 0xF0, 0x0F, 0x00], "data in");
+        assert_eq!(result.residual, 0, "residual");
+
+        let issued = mock.issued();
+        assert_eq!(
+            issued[0].direction,
+            DataDirection::Bidirectional,
+            "direction"
+        );
+        assert_eq!(issued[0].data, [0x00, 0x0F, 0xF0, 0xFF], "data out");
+        mock.verify();
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn trace_test() {
+        use std::{
+            fmt::{Debug, Write},
+            sync::{
+                atomic::{AtomicU64, Ordering},
+                Mutex,
+            },
+        };
+
+        use tracing::{
+            field::{Field, Visit},
+            span::{Attributes, Id, Record},
+            Event, Metadata,
         };
 
-        command.process_result(result_data)
+        use crate::{MockResponse, MockScsi};
+
+        // the name and fields of every span and event
+        #[derive(Default)]
+        struct Recorder {
+            next_id: AtomicU64,
+            spans: Mutex<Vec<String>>,
+            events: Mutex<Vec<String>>,
+        }
+
+        struct Fields(String);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                write!(self.0, " {}={:?}", field.name(), value).unwrap();
+            }
+        }
+
+        impl tracing::Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut fields = Fields(span.metadata().name().to_owned());
+                span.record(&mut fields);
+                self.spans.lock().unwrap().push(fields.0);
+                Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut fields = Fields(String::new());
+                event.record(&mut fields);
+                self.events.lock().unwrap().push(fields.0);
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        let mock = MockScsi::new();
+        mock.expect(&[0x00; 6], MockResponse::good().status(0x08))
+            .expect(&[0x00; 6], MockResponse::good());
+        let mut scsi = Scsi::mock(mock.clone());
+        scsi.set_retry_policy(RetryPolicy::new(2));
+
+        let recorder = Arc::new(Recorder::default());
+        tracing::subscriber::with_default(recorder.clone(), || {
+            scsi.test_unit_ready().issue().unwrap();
+        });
+
+        assert_eq!(
+            *recorder.spans.lock().unwrap(),
+            ["scsi_command opcode=0 cdb=[00, 00, 00, 00, 00, 00] direction=None"],
+            "one span per command"
+        );
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(events.len(), 2, "events");
+        assert!(
+            events[0].starts_with(" message=retrying retries=0 ioctl_result=0 status=8"),
+            "busy attempt, {}",
+            events[0]
+        );
+        assert!(
+            events[1].starts_with(" message=completed retries=1 ioctl_result=0 status=0"),
+            "retried attempt, {}",
+            events[1]
+        );
+        mock.verify();
     }
 
-    pub fn path(&self) -> &PathBuf {
-        &self.path
+    #[test]
+    fn issue_many_test() {
+        use std::sync::{Arc, Mutex};
+
+        use crate::{os::Response, MockResponse, MockScsi};
+
+        // completes every batch in reverse
+        #[derive(Debug)]
+        struct ReverseBackend(MockScsi, Arc<Mutex<Vec<usize>>>);
+
+        impl Backend for ReverseBackend {
+            fn issue(&self, request: Request) -> Response {
+                self.0.issue(request)
+            }
+
+            fn issue_batch(&self, requests: Vec<Request>) -> Vec<Response> {
+                self.1.lock().unwrap().push(requests.len());
+
+                let mut responses: Vec<_> = requests
+                    .into_iter()
+                    .rev()
+                    .map(|request| self.issue(request))
+                    .collect();
+                responses.reverse();
+                responses
+            }
+        }
+
+        let mock = MockScsi::new();
+        for lba in (0..5).rev() {
+            let response = match lba {
+                // MEDIUM ERROR, UNRECOVERED READ ERROR
+                3 => MockResponse::check_condition(&[
+                    0x70, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x11,
+                    0x00, 0x00, 0x00, 0x00, 0x00,
+                ]),
+                _ => MockResponse::good().data(&[lba; 8]),
+            };
+            mock.expect(&Read16(lba).command(), response);
+        }
+
+        let batches = Arc::new(Mutex::new(vec![]));
+        let backend = ReverseBackend(mock.clone(), batches.clone());
+        let scsi = Scsi::with_backend(Path::new("/dev/null"), Box::new(backend));
+        let commands: Vec<_> = (0..5).map(Read16).collect();
+
+        let results = scsi.issue_many(&commands);
+
+        assert_eq!(results.len(), 5, "one result per command");
+        for (lba, result) in results.iter().enumerate() {
+            match lba {
+                3 => assert!(
+                    matches!(result, Err(crate::Error::MediumError(_))),
+                    "medium error of LBA 3"
+                ),
+                _ => assert_eq!(
+                    result.as_ref().unwrap(),
+                    &[lba as u8; 8],
+                    "data of LBA {}",
+                    lba
+                ),
+            }
+        }
+
+        assert_eq!(*batches.lock().unwrap(), [5], "a single batch");
+        mock.verify();
     }
 
-    pub fn set_timeout(&mut self, timeout: Duration) {
-        self.timeout = timeout;
+    #[test]
+    fn lost_response_test() {
+        use crate::{os::Response, MockResponse, MockScsi};
+
+        // drops the response of the last request of every batch
+        #[derive(Debug)]
+        struct LossyBackend(MockScsi);
+
+        impl Backend for LossyBackend {
+            fn issue(&self, request: Request) -> Response {
+                self.0.issue(request)
+            }
+
+            fn issue_batch(&self, requests: Vec<Request>) -> Vec<Response> {
+                let mut responses = self.0.issue_batch(requests);
+                responses.pop();
+                responses
+            }
+        }
+
+        let mock = MockScsi::new();
+        for lba in 0..3 {
+            mock.expect(&Read16(lba).command(), MockResponse::good().data(&[lba; 8]));
+        }
+
+        let scsi = Scsi::with_backend(Path::new("/dev/null"), Box::new(LossyBackend(mock)));
+        let commands: Vec<_> = (0..3).map(Read16).collect();
+        let results = scsi.issue_many(&commands);
+
+        assert_eq!(results.len(), 3, "one result per command");
+        assert_eq!(results[0].as_ref().unwrap(), &[0; 8], "data of LBA 0");
+        assert_eq!(results[1].as_ref().unwrap(), &[1; 8], "data of LBA 1");
+        assert_eq!(
+            results[2].as_ref().unwrap_err().raw_os_error(),
+            Some(os::NO_RESPONSE_ERROR),
+            "LBA 2 without a response"
+        );
     }
 
-    pub fn timeout(&self) -> Duration {
-        self.timeout
+    #[cfg(feature = "io-uring")]
+    #[test]
+    fn submit_batch_test() {
+        use crate::{MockResponse, MockScsi};
+
+        fn read_16(lba: u8) -> [u8; 16] {
+            let mut cdb = [0; 16];
+            cdb[0] = 0x88;
+            cdb[9] = lba;
+            cdb[13] = 1;
+            cdb
+        }
+
+        let mock = MockScsi::new();
+        for lba in 0..4 {
+            let response = match lba {
+                // MEDIUM ERROR, UNRECOVERED READ ERROR
+                2 => MockResponse::check_condition(&[
+                    0x70, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x11,
+                    0x00, 0x00, 0x00, 0x00, 0x00,
+                ]),
+                _ => MockResponse::good().data(&[lba; 8]),
+            };
+            mock.expect(&read_16(lba), response);
+        }
+
+        let scsi = Scsi::mock(mock.clone());
+        let commands: Vec<_> = (0..4).map(Read16).collect();
+        let results = scsi.submit_batch(&commands);
+
+        assert_eq!(results.len(), 4, "one result per command");
+        for (lba, result) in results.iter().enumerate() {
+            match lba {
+                2 => assert!(
+                    matches!(result, Err(crate::Error::MediumError(_))),
+                    "medium error of LBA 2"
+                ),
+                _ => assert_eq!(
+                    result.as_ref().unwrap(),
+                    &[lba as u8; 8],
+                    "data of LBA {}",
+                    lba
+                ),
+            }
+        }
+        mock.verify();
     }
 
-    #[cfg(target_os = "linux")]
-    fn is_scsi_device(file: &FileDescriptor) -> crate::Result<bool> {
-        use nix::libc;
+    #[test]
+    fn independent_results_test() {
+        use crate::{MockResponse, MockScsi};
+
+        // fails the ioctl of odd LBAs, with the LBA as errno
+        let mock = MockScsi::new();
+        for lba in 0..6 {
+            let response = match lba % 2 {
+                0 => MockResponse::good().data(&[lba; 8]),
+                _ => MockResponse::os_error(lba as i32),
+            };
+            mock.expect(&Read16(lba).command(), response);
+        }
 
-        const SG_GET_VERSION_NUM: u32 = 0x2282;
+        let scsi = Scsi::mock(mock.clone());
+        let commands: Vec<_> = (0..6).map(Read16).collect();
 
-        let mut version = 0_i32;
-        let result = unsafe {
-            libc::ioctl(
-                file.raw(),
-                SG_GET_VERSION_NUM.try_into().unwrap(),
-                &mut version,
-            )
-        };
+        let results = scsi.issue_many(&commands);
 
-        if result != 0 {
-            Err(io::Error::last_os_error())?;
+        assert_eq!(results.len(), 6, "one result per command");
+        for (lba, result) in results.iter().enumerate() {
+            match result {
+                Ok(_) => assert_eq!(lba % 2, 0, "LBA {} succeeded", lba),
+                Err(crate::Error::IO(error)) => {
+                    assert_eq!(
+                        error.raw_os_error(),
+                        Some(lba as i32),
+                        "errno of LBA {}",
+                        lba
+                    )
+                }
+                Err(error) => panic!("unexpected error of LBA {}: {:?}", lba, error),
+            }
         }
+        mock.verify();
+    }
+
+    #[test]
+    fn autosense_test() {
+        use crate::{HasSense, MockResponse, MockScsi};
 
-        if version < 30000 {
-            Ok(false)
-        } else {
-            Ok(true)
+        // always a CHECK CONDITION, NOT READY, MEDIUM NOT PRESENT
+        let mock = MockScsi::new();
+        for _ in 0..2 {
+            mock.expect(
+                &[0x00; 6],
+                MockResponse::check_condition(&[
+                    0x70, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x3A,
+                    0x00, 0x00, 0x00, 0x00, 0x00,
+                ]),
+            );
         }
+
+        let mut scsi = Scsi::mock(mock.clone());
+        assert!(scsi.autosense(), "on by default");
+
+        let result = scsi.test_unit_ready().issue();
+        assert!(
+            matches!(result, Err(crate::Error::NotReady(_))),
+            "not ready with autosense"
+        );
+        assert!(
+            result.check_sense().is_some(),
+            "decoded sense with autosense"
+        );
+
+        scsi.set_autosense(false);
+        let result = scsi.test_unit_ready().issue();
+        assert!(result.check_sense().is_none(), "no sense without autosense");
+        assert!(
+            matches!(&result, Err(crate::Error::Other(message)) if message.contains("CheckCondition")),
+            "check condition without autosense"
+        );
+
+        let sense_lengths: Vec<_> = mock
+            .issued()
+            .iter()
+            .map(|command| command.sense_buffer_length)
+            .collect();
+        assert_eq!(
+            sense_lengths,
+            [MAX_SENSE_BUFFER_LENGTH, 0],
+            "sense buffer length handed to the driver"
+        );
+        mock.verify();
     }
 
-    #[cfg(target_os = "windows")]
-    fn is_scsi_device(file: &FileDescriptor) -> crate::Result<bool> {
-        use std::mem::size_of;
+    #[test]
+    fn sense_buffer_length_test() {
+        use crate::{MockResponse, MockScsi};
 
-        use windows::Win32::Foundation::HANDLE;
-        use windows::Win32::Storage::IscsiDisc::{IOCTL_SCSI_GET_ADDRESS, SCSI_ADDRESS};
-        use windows::Win32::System::IO::DeviceIoControl;
+        // TEST UNIT READY, returns the raw sense of the result
+        struct RawSense;
 
-        let mut scsi_address = SCSI_ADDRESS::default();
-        let mut bytes_returned = 0;
-        let success = unsafe {
-            DeviceIoControl(
-                HANDLE(file.raw() as isize),
-                IOCTL_SCSI_GET_ADDRESS,
-                None,
-                0,
-                Some(&mut scsi_address as *mut _ as _),
-                size_of::<SCSI_ADDRESS>() as u32,
-                Some(&mut bytes_returned),
-                None,
-            )
-        };
+        impl Command for RawSense {
+            type CommandBuffer = [u8; 6];
+
+            type DataBuffer = ();
+
+            type DataBufferWrapper = ();
+
+            type ReturnType = Vec<u8>;
 
-        if success == false {
-            Err(io::Error::last_os_error())?;
+            fn direction(&self) -> DataDirection {
+                DataDirection::None
+            }
+
+            fn command(&self) -> Self::CommandBuffer {
+                [0; 6]
+            }
+
+            fn data(&self) -> Self::DataBufferWrapper {}
+
+            fn data_size(&self) -> u32 {
+                0
+            }
+
+            fn process_result(
+                &self,
+                result: ResultData<Self::DataBufferWrapper>,
+            ) -> Self::ReturnType {
+                result.raw_sense().to_vec()
+            }
         }
 
-        if bytes_returned == 0 {
-            Ok(false)
-        } else {
-            Ok(true)
+        // descriptor format, 15 information descriptors of 12 bytes
+        let mut sense = vec![0x72, 0x03, 0x11, 0x00, 0x00, 0x00, 0x00, 15 * 12];
+        for _ in 0..15 {
+            sense.extend_from_slice(&[0x00, 0x0A, 0x80, 0x00, 0, 0, 0, 0, 0, 0, 0x10, 0x00]);
         }
+
+        let mock = MockScsi::new();
+        mock.expect(&[0; 6], MockResponse::check_condition(&sense))
+            .expect(&[0; 6], MockResponse::check_condition(&sense));
+        let mut scsi = Scsi::mock(mock);
+        assert_eq!(
+            scsi.sense_buffer_len(),
+            MAX_SENSE_BUFFER_LENGTH,
+            "largest by default"
+        );
+
+        scsi.set_sense_buffer_len(18);
+        assert_eq!(scsi.issue(&RawSense), sense[..18], "cut off");
+
+        scsi.set_sense_buffer_len(1024);
+        assert_eq!(scsi.sense_buffer_len(), MAX_SENSE_BUFFER_LENGTH, "clamped");
+        assert_eq!(scsi.issue(&RawSense), sense, "fully captured");
     }
-}
 
-const SG_DEFAULT_TIMEOUT: u64 = 60_000;
+    #[test]
+    fn issue_with_sense_test() {
+        use crate::{command::sense::SenseKey, MockResponse, MockScsi};
+
+        // READ (16) returning the data it read
+        struct RecoveredRead16;
+
+        impl Command for RecoveredRead16 {
+            type CommandBuffer = [u8; 16];
+
+            type DataBuffer = [u8; 8];
+
+            type DataBufferWrapper = [u8; 8];
+
+            type ReturnType = crate::Result<[u8; 8]>;
+
+            fn direction(&self) -> DataDirection {
+                DataDirection::FromDevice
+            }
+
+            fn command(&self) -> Self::CommandBuffer {
+                Read16(0).command()
+            }
+
+            fn data(&self) -> Self::DataBufferWrapper {
+                [0; 8]
+            }
+
+            fn process_result(
+                &self,
+                result: ResultData<Self::DataBufferWrapper>,
+            ) -> Self::ReturnType {
+                result.check_ioctl_error()?;
+                result.check_common_error()?;
+
+                Ok(*result.data())
+            }
+        }
+
+        // RECOVERED ERROR, RECOVERED DATA WITH RETRIES
+        let recovered = MockResponse::check_condition(&[
+            0x70, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x17, 0x01,
+            0x00, 0x00, 0x00, 0x00,
+        ])
+        .data(&[0xA5; 8]);
+        // SG_IO sets DRIVER_SENSE whenever it returns sense data
+        #[cfg(target_os = "linux")]
+        let recovered = recovered.driver_status(0x08);
+
+        let mock = MockScsi::new();
+        mock.expect(&Read16(0).command(), recovered)
+            .expect(&Read16(0).command(), MockResponse::good().data(&[0x5A; 8]));
+
+        let scsi = Scsi::mock(mock);
+        let (result, sense) = scsi.issue_with_sense(&RecoveredRead16);
+        assert_eq!(result.unwrap(), [0xA5; 8], "read data");
+        let sense = sense.expect("recovered error sense");
+        assert!(
+            matches!(sense.sense_key(), Some(SenseKey::RecoveredError)),
+            "sense key"
+        );
+        assert_eq!(
+            sense.additional_sense_code().map(|code| *code),
+            Some(0x1701),
+            "additional sense code"
+        );
+
+        let (result, sense) = scsi.issue_with_sense(&RecoveredRead16);
+        assert_eq!(result.unwrap(), [0x5A; 8], "read data without sense");
+        assert!(sense.is_none(), "no sense");
+    }
+
+    #[test]
+    fn sense_buffer_allocation_test() {
+        use crate::counting_allocator::{allocations, good_scsi};
+
+        // GOOD without sense, the common path
+        let mut scsi = good_scsi();
+        let mut command = scsi.test_unit_ready();
+        // the sense buffer lives in the attempt on the stack, nothing is allocated per issue
+        let before = allocations();
+        for _ in 0..16 {
+            command.issue().unwrap();
+        }
+        assert_eq!(allocations() - before, 0, "allocations with autosense");
+
+        scsi.set_autosense(false);
+        let mut command = scsi.test_unit_ready();
+        let before = allocations();
+        command.issue().unwrap();
+        assert_eq!(allocations() - before, 0, "allocations without autosense");
+    }
+
+    #[test]
+    #[cfg(feature = "block")]
+    fn read_only_test() {
+        use crate::{MockResponse, MockScsi};
+
+        let mock = MockScsi::new();
+        for lba in 0..3 {
+            mock.expect(&Read16(lba).command(), MockResponse::good().data(&[lba; 8]));
+        }
+
+        let scsi = ScsiBuilder::new()
+            .read_only(true)
+            .default_timeout(Duration::from_secs(5))
+            .build(Path::new("mock"), Box::new(mock.clone()));
+        assert_eq!(
+            scsi.default_timeout(),
+            Duration::from_secs(5),
+            "default timeout"
+        );
+
+        let result = scsi
+            .write()
+            .logical_block_address(0)
+            .logical_block_size(512)
+            .parameter(&[0; 512])
+            .issue_16();
+        assert!(
+            matches!(
+                &result,
+                Err(crate::Error::IO(error)) if error.raw_os_error() == Some(os::READ_ONLY_ERROR)
+            ),
+            "write rejected: {:?}",
+            result
+        );
+
+        assert!(scsi.issue(&Read16(0)).is_ok(), "read allowed");
+
+        let results = scsi.issue_many(&[Read16(1), Read16(2)]);
+        assert!(results.iter().all(Result::is_ok), "batched reads allowed");
+
+        assert!(
+            mock.issued()
+                .iter()
+                .all(|command| command.command[0] == 0x88),
+            "only the reads reach the backend"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    fn read_only_non_data_test() {
+        use crate::{MockResponse, MockScsi};
+
+        let mock = MockScsi::new();
+        mock.expect(&[0x00; 6], MockResponse::good()).expect(
+            &[0x35, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            MockResponse::good(),
+        );
+        let scsi = ScsiBuilder::new()
+            .read_only(true)
+            .build(Path::new("mock"), Box::new(mock.clone()));
+
+        let issue = |command: &[u8]| {
+            scsi.passthrough()
+                .command_buffer(command)
+                .direction(DataDirection::None)
+                .issue()
+        };
+        let rejected = [
+            ("SANITIZE", vec![0x48, 0x01, 0, 0, 0, 0, 0, 0, 0, 0]),
+            ("FORMAT UNIT", vec![0x04, 0, 0, 0, 0, 0]),
+            ("START STOP UNIT", vec![0x1B, 0, 0, 0, 0x00, 0]),
+            (
+                "FINISH ZONE",
+                [0x94, 0x02].into_iter().chain([0; 14]).collect(),
+            ),
+            (
+                "RESET WRITE POINTER",
+                [0x94, 0x04].into_iter().chain([0; 14]).collect(),
+            ),
+        ];
+        for (name, command) in rejected {
+            let result = issue(&command);
+            assert!(
+                matches!(
+                    &result,
+                    Err(crate::Error::IO(error)) if error.raw_os_error() == Some(os::READ_ONLY_ERROR)
+                ),
+                "{} rejected: {:?}",
+                name,
+                result
+            );
+        }
+
+        assert!(issue(&[0x00; 6]).is_ok(), "TEST UNIT READY allowed");
+        assert!(
+            issue(&[0x35, 0, 0, 0, 0, 0, 0, 0, 0, 0]).is_ok(),
+            "SYNCHRONIZE CACHE allowed"
+        );
+        assert_eq!(
+            mock.issued().len(),
+            2,
+            "only the allowed commands are issued"
+        );
+        mock.verify();
+    }
+
+    #[test]
+    fn duration_test() {
+        use crate::{MockResponse, MockScsi};
+
+        // TEST UNIT READY, returns the duration of the result
+        struct Elapsed;
+
+        impl Command for Elapsed {
+            type CommandBuffer = [u8; 6];
+
+            type DataBuffer = ();
+
+            type DataBufferWrapper = ();
+
+            type ReturnType = Duration;
+
+            fn direction(&self) -> DataDirection {
+                DataDirection::None
+            }
+
+            fn command(&self) -> Self::CommandBuffer {
+                [0; 6]
+            }
+
+            fn data(&self) -> Self::DataBufferWrapper {}
+
+            fn data_size(&self) -> u32 {
+                0
+            }
+
+            fn process_result(
+                &self,
+                result: ResultData<Self::DataBufferWrapper>,
+            ) -> Self::ReturnType {
+                result.duration()
+            }
+        }
+
+        let mock = MockScsi::new();
+        mock.expect(&[0; 6], MockResponse::good())
+            // reported by the driver
+            .expect(
+                &[0; 6],
+                MockResponse::good().duration(Duration::from_millis(5)),
+            )
+            // below the resolution of the driver
+            .expect(&[0; 6], MockResponse::good().duration(Duration::ZERO));
+        let scsi = Scsi::mock(mock.clone());
+        assert!(
+            !scsi.issue(&Elapsed).is_zero(),
+            "measured around the backend"
+        );
+
+        assert_eq!(
+            scsi.issue(&Elapsed),
+            Duration::from_millis(5),
+            "reported by the driver"
+        );
+        let start = Instant::now();
+        let elapsed = scsi.issue(&Elapsed);
+        assert!(
+            !elapsed.is_zero() && elapsed <= start.elapsed(),
+            "measured below the resolution of the driver"
+        );
+        mock.verify();
+    }
+}
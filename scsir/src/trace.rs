@@ -0,0 +1,18 @@
+//! Spans and events of the `tracing` crate for every command issued by [`crate::Scsi`].
+//!
+//! A command runs in a `scsi_command` span with its operation code, CDB and data direction. A
+//! `retrying` event is emitted for every attempt the retry policy repeats, and a `completed`
+//! event for the last one. Everything is at the DEBUG level, with `scsir` as the target.
+
+use tracing::Span;
+
+use crate::DataDirection;
+
+pub(crate) fn command_span(command: &[u8], direction: DataDirection) -> Span {
+    tracing::debug_span!(
+        "scsi_command",
+        opcode = command.first().copied().unwrap_or_default(),
+        cdb = %format_args!("{:02X?}", command),
+        direction = ?direction,
+    )
+}
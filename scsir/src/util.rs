@@ -0,0 +1,111 @@
+//! Big endian fields of CDBs, parameter data and sense data.
+//!
+//! Each helper reads the field from the start of the slice and returns None if the slice is
+//! shorter than the field, e.g. a response truncated by a small allocation length.
+
+#![allow(dead_code)]
+
+pub(crate) fn be_u16(bytes: &[u8]) -> Option<u16> {
+    field(bytes).map(u16::from_be_bytes)
+}
+
+pub(crate) fn be_u24(bytes: &[u8]) -> Option<u32> {
+    be_uint::<3>(bytes).map(|value| value as u32)
+}
+
+pub(crate) fn be_u32(bytes: &[u8]) -> Option<u32> {
+    field(bytes).map(u32::from_be_bytes)
+}
+
+pub(crate) fn be_u48(bytes: &[u8]) -> Option<u64> {
+    be_uint::<6>(bytes)
+}
+
+pub(crate) fn be_u64(bytes: &[u8]) -> Option<u64> {
+    field(bytes).map(u64::from_be_bytes)
+}
+
+fn field<const N: usize>(bytes: &[u8]) -> Option<[u8; N]> {
+    bytes.get(..N)?.try_into().ok()
+}
+
+// fields without a native integer width
+fn be_uint<const N: usize>(bytes: &[u8]) -> Option<u64> {
+    let field: [u8; N] = field(bytes)?;
+
+    Some(
+        field
+            .iter()
+            .fold(0, |value, &byte| (value << 8) | byte as u64),
+    )
+}
+
+/// `#[serde(with)]` for byte arrays longer than the 32 bytes serde supports on its own.
+#[cfg(feature = "serde")]
+pub(crate) mod byte_array {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer, const N: usize>(
+        bytes: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(bytes)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[u8; N], D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        let length = bytes.len();
+        bytes
+            .try_into()
+            .map_err(|_| D::Error::invalid_length(length, &"a byte array"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BYTES: [u8; 9] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0xFF];
+
+    #[test]
+    fn be_u16_test() {
+        assert_eq!(be_u16(&BYTES), Some(0x0123), "value");
+        assert_eq!(be_u16(&BYTES[..2]), Some(0x0123), "exact length");
+        assert_eq!(be_u16(&BYTES[..1]), None, "truncated");
+    }
+
+    #[test]
+    fn be_u24_test() {
+        assert_eq!(be_u24(&BYTES), Some(0x01_2345), "value");
+        assert_eq!(be_u24(&[0xFF; 3]), Some(0xFF_FFFF), "largest value");
+        assert_eq!(be_u24(&BYTES[..2]), None, "truncated");
+    }
+
+    #[test]
+    fn be_u32_test() {
+        assert_eq!(be_u32(&BYTES), Some(0x0123_4567), "value");
+        assert_eq!(be_u32(&BYTES[..4]), Some(0x0123_4567), "exact length");
+        assert_eq!(be_u32(&BYTES[..3]), None, "truncated");
+    }
+
+    #[test]
+    fn be_u48_test() {
+        assert_eq!(be_u48(&BYTES), Some(0x0123_4567_89AB), "value");
+        assert_eq!(be_u48(&[0xFF; 6]), Some(0xFFFF_FFFF_FFFF), "largest value");
+        assert_eq!(be_u48(&BYTES[..5]), None, "truncated");
+    }
+
+    #[test]
+    fn be_u64_test() {
+        assert_eq!(be_u64(&BYTES), Some(0x0123_4567_89AB_CDEF), "value");
+        assert_eq!(
+            be_u64(&BYTES[..8]),
+            Some(0x0123_4567_89AB_CDEF),
+            "exact length"
+        );
+        assert_eq!(be_u64(&BYTES[..7]), None, "truncated");
+        assert_eq!(be_u64(&[]), None, "empty");
+    }
+}